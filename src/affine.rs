@@ -0,0 +1,261 @@
+//! Affine transforms and their decomposition
+
+use std::ops::Mul;
+
+use crate::{ Float, Point2, Point3, Rotor2, Rotor3, Vector2, Vector3 };
+
+/// Parts of a 2d affine transform: translation · rotation · scale · shear,
+/// with shear the matrix [[1, k], [0, 1]]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Decomposition2<T> {
+    pub translation: Vector2<T>,
+    pub rotation: Rotor2<T>,
+    pub scale: Vector2<T>,
+    pub shear: T,
+}
+
+/// Parts of a 3d affine transform: translation · rotation · stretch, where the
+/// symmetric stretch has the scale on its diagonal and the shear (xy, xz, yz) off it
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Decomposition3<T> {
+    pub translation: Vector3<T>,
+    pub rotation: Rotor3<T>,
+    pub scale: Vector3<T>,
+    pub shear: Vector3<T>,
+}
+
+/// 2d Affine transform: linear part by columns, then translation
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Affine2<T>([Vector2<T>; 2], Vector2<T>);
+
+/// 3d Affine transform: linear part by columns, then translation
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Affine3<T>([Vector3<T>; 3], Vector3<T>);
+
+impl<T: Float> Affine2<T> {
+    /// new: [Vector; 2] × Vector -> Affine
+    pub fn new(columns: [Vector2<T>; 2], translation: Vector2<T>) -> Self {
+        Affine2(columns, translation)
+    }
+
+    /// identity: Affine
+    pub fn identity() -> Self {
+        let (zero, one) = (T::zero(), T::one());
+        Affine2([Vector2(one, zero), Vector2(zero, one)], Vector2(zero, zero))
+    }
+
+    /// columns: &Affine -> [Vector; 2]
+    pub fn columns(&self) -> [Vector2<T>; 2] {
+        self.0
+    }
+
+    /// translation: &Affine -> Vector
+    pub fn translation(&self) -> Vector2<T> {
+        self.1
+    }
+
+    /// transform_vector: Affine × Vector -> Vector
+    pub fn transform_vector(self, v: Vector2<T>) -> Vector2<T> {
+        self.0[0] * v.0 + self.0[1] * v.1
+    }
+
+    /// transform_point: Affine × Point -> Point
+    pub fn transform_point(self, p: Point2<T>) -> Point2<T> {
+        let Vector2(x, y) = self.transform_vector(Vector2(p.0, p.1)) + self.1;
+        Point2(x, y)
+    }
+
+    /// compose: Decomposition -> Affine
+    pub fn compose(d: Decomposition2<T>) -> Self {
+        let Decomposition2 { translation, rotation, scale, shear } = d;
+        let (zero, one) = (T::zero(), T::one());
+        let x = rotation.rotate(Vector2(one, zero));
+        let y = rotation.rotate(Vector2(zero, one));
+        Affine2([x * scale.0, x * (scale.0 * shear) + y * scale.1], translation)
+    }
+
+    /// decompose: Affine -> Option<Decomposition>
+    /// by Gram–Schmidt on the columns; a reflection shows as a negative
+    /// y scale, and None is returned for a singular transform
+    pub fn decompose(self) -> Option<Decomposition2<T>> {
+        let [a, b] = self.0;
+        let sx = a.length();
+        let det = a.0 * b.1 - a.1 * b.0;
+        if sx == T::zero() || det == T::zero() {
+            return None;
+        }
+        let x = a / sx;
+        let y = Vector2(-x.1, x.0);
+        let rotation = Rotor2::from_to(Vector2(T::one(), T::zero()), x);
+        Some(Decomposition2 { translation: self.1, rotation, scale: Vector2(sx, y.dot(b)), shear: x.dot(b) / sx })
+    }
+}
+
+/// (*): Affine × Affine -> Affine
+/// applies the right transform first
+impl<T: Float> Mul for Affine2<T> {
+    type Output = Affine2<T>;
+
+    fn mul(self, m: Affine2<T>) -> Self::Output {
+        let Point2(x, y) = self.transform_point(Point2(m.1.0, m.1.1));
+        Affine2([self.transform_vector(m.0[0]), self.transform_vector(m.0[1])], Vector2(x, y))
+    }
+}
+
+impl<T: Float> Affine3<T> {
+    /// new: [Vector; 3] × Vector -> Affine
+    pub fn new(columns: [Vector3<T>; 3], translation: Vector3<T>) -> Self {
+        Affine3(columns, translation)
+    }
+
+    /// identity: Affine
+    pub fn identity() -> Self {
+        let (zero, one) = (T::zero(), T::one());
+        Affine3(
+            [Vector3(one, zero, zero), Vector3(zero, one, zero), Vector3(zero, zero, one)],
+            Vector3(zero, zero, zero),
+        )
+    }
+
+    /// columns: &Affine -> [Vector; 3]
+    pub fn columns(&self) -> [Vector3<T>; 3] {
+        self.0
+    }
+
+    /// translation: &Affine -> Vector
+    pub fn translation(&self) -> Vector3<T> {
+        self.1
+    }
+
+    /// transform_vector: Affine × Vector -> Vector
+    pub fn transform_vector(self, v: Vector3<T>) -> Vector3<T> {
+        self.0[0] * v.0 + self.0[1] * v.1 + self.0[2] * v.2
+    }
+
+    /// transform_point: Affine × Point -> Point
+    pub fn transform_point(self, p: Point3<T>) -> Point3<T> {
+        let Vector3(x, y, z) = self.transform_vector(Vector3(p.0, p.1, p.2)) + self.1;
+        Point3(x, y, z)
+    }
+
+    /// compose: Decomposition -> Affine
+    pub fn compose(d: Decomposition3<T>) -> Self {
+        let Decomposition3 { translation, rotation, scale, shear } = d;
+        let stretch = [
+            Vector3(scale.0, shear.0, shear.1),
+            Vector3(shear.0, scale.1, shear.2),
+            Vector3(shear.1, shear.2, scale.2),
+        ];
+        Affine3(stretch.map(|c| rotation.rotate(c)), translation)
+    }
+
+    /// decompose: Affine -> Option<Decomposition>
+    /// the rotation is the polar factor, the closest rotation to the linear part;
+    /// a reflection shows as negative scales, and None is returned for a singular transform
+    pub fn decompose(self) -> Option<Decomposition3<T>> {
+        let det = |[a, b, c]: [Vector3<T>; 3]| a.dot(b.cross(c));
+        let d = det(self.0);
+        if d == T::zero() || !d.is_finite() {
+            return None;
+        }
+        // polar factor of ±M, whichever has positive determinant
+        let sign = if d < T::zero() { -T::one() } else { T::one() };
+        let m = self.0.map(|c| c * sign);
+        let half = T::from_f64(0.5);
+        let mut q = m;
+        for _ in 0..64 {
+            // Newton step Q ← (Q + Q⁻ᵀ) / 2; the inverse transpose has the
+            // cross products of the columns over the determinant as columns
+            let [a, b, c] = q;
+            let d = det(q);
+            let inv_t = [b.cross(c) / d, c.cross(a) / d, a.cross(b) / d];
+            let next = [(a + inv_t[0]) * half, (b + inv_t[1]) * half, (c + inv_t[2]) * half];
+            let change = (0..3).fold(T::zero(), |s, i| s + (next[i] - q[i]).length());
+            q = next;
+            if change <= T::epsilon() * T::from_f64(4.0) {
+                break;
+            }
+        }
+        // stretch S = Qᵀ M, symmetrized against rounding, carrying the sign back
+        let s = |i: usize, j: usize| q[i].dot(m[j]);
+        let off = |i: usize, j: usize| (s(i, j) + s(j, i)) * half * sign;
+        let scale = Vector3(s(0, 0), s(1, 1), s(2, 2)) * sign;
+        let shear = Vector3(off(0, 1), off(0, 2), off(1, 2));
+        let rotation = Rotor3::from_basis(q[0], q[1], q[2]);
+        Some(Decomposition3 { translation: self.1, rotation, scale, shear })
+    }
+}
+
+/// (*): Affine × Affine -> Affine
+/// applies the right transform first
+impl<T: Float> Mul for Affine3<T> {
+    type Output = Affine3<T>;
+
+    fn mul(self, m: Affine3<T>) -> Self::Output {
+        let Point3(x, y, z) = self.transform_point(Point3(m.1.0, m.1.1, m.1.2));
+        Affine3(m.0.map(|c| self.transform_vector(c)), Vector3(x, y, z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn near2(a: Affine2<f64>, b: Affine2<f64>) -> bool {
+        (0..2).all(|i| (a.0[i] - b.0[i]).length() < 1e-12) && (a.1 - b.1).length() < 1e-12
+    }
+
+    fn near3(a: Affine3<f64>, b: Affine3<f64>) -> bool {
+        (0..3).all(|i| (a.0[i] - b.0[i]).length() < 1e-12) && (a.1 - b.1).length() < 1e-12
+    }
+
+    #[test]
+    fn affine2() {
+        let r = Rotor2::from_to(Vector2(1.0, 0.0), Vector2(0.0, 1.0));
+        let parts = Decomposition2 { translation: Vector2(1.0, 2.0), rotation: r, scale: Vector2(2.0, 3.0), shear: 0.5 };
+        let m = Affine2::compose(parts);
+        assert!(near2(m, Affine2::new([Vector2(0.0, 2.0), Vector2(-3.0, 1.0)], Vector2(1.0, 2.0))));
+        assert!(m.transform_point(Point2(1.0, 0.0)).distance(Point2(1.0, 4.0)) < 1e-12);
+        let d = m.decompose().unwrap();
+        assert_eq!(d.translation, Vector2(1.0, 2.0));
+        assert!((d.rotation.rotate(Vector2(1.0, 0.0)) - Vector2(0.0, 1.0)).length() < 1e-12);
+        assert!((d.scale - Vector2(2.0, 3.0)).length() < 1e-12 && (d.shear - 0.5).abs() < 1e-12);
+        let mirror = Affine2::new([Vector2(1.0, 0.0), Vector2(0.0, -2.0)], Vector2(0.0, 0.0));
+        let d = mirror.decompose().unwrap();
+        assert_eq!(d.scale, Vector2(1.0, -2.0));
+        assert!(near2(Affine2::compose(d), mirror));
+        assert!(near2(m * Affine2::identity(), m));
+        assert_eq!(Affine2::new([Vector2(1.0, 1.0), Vector2(2.0, 2.0)], Vector2(0.0, 0.0)).decompose(), None);
+    }
+
+    #[test]
+    fn affine3() {
+        let zero = Vector3(0.0, 0.0, 0.0);
+        let r = Rotor3::look_rotation(Vector3(1.0, 2.0, 2.0), Vector3(0.0, 0.0, 1.0));
+        let scale = Vector3(2.0, 0.5, 3.0);
+        let m = Affine3::compose(Decomposition3 { translation: Vector3(1.0, -1.0, 4.0), rotation: r, scale, shear: zero });
+        let d = m.decompose().unwrap();
+        assert_eq!(d.translation, Vector3(1.0, -1.0, 4.0));
+        assert!((d.scale - scale).length() < 1e-12 && d.shear.length() < 1e-12);
+        for v in [Vector3(1.0, 0.0, 0.0), Vector3(0.0, 1.0, 0.0), Vector3(0.0, 0.0, 1.0)] {
+            assert!((d.rotation.rotate(v) - r.rotate(v)).length() < 1e-12);
+        }
+
+        let sheared = Affine3::new([Vector3(1.0, 0.2, 0.0), Vector3(0.5, 2.0, 0.1), Vector3(0.0, -0.3, 1.5)], zero);
+        let d = sheared.decompose().unwrap();
+        assert!(d.shear.length() > 0.1);
+        assert!(near3(Affine3::compose(d), sheared));
+
+        let mirrored = Affine3::compose(Decomposition3 { translation: zero, rotation: r, scale: Vector3(-1.0, -2.0, -1.0), shear: zero });
+        let d = mirrored.decompose().unwrap();
+        assert!((d.scale - Vector3(-1.0, -2.0, -1.0)).length() < 1e-12);
+        assert!(near3(Affine3::compose(d), mirrored));
+
+        let spin = Affine3::compose(Decomposition3 { translation: zero, rotation: Rotor3::exp(Vector3(0.0, 0.0, PI / 2.0)), scale, shear: zero });
+        assert!(near3(spin * Affine3::identity(), spin));
+        let p = Point3(1.0, 1.0, 1.0);
+        assert!((spin * sheared).transform_point(p).distance(spin.transform_point(sheared.transform_point(p))) < 1e-12);
+        assert_eq!(Affine3::new([Vector3(1.0, 0.0, 0.0); 3], zero).decompose(), None);
+    }
+}
@@ -0,0 +1,146 @@
+//! Transforms about an anchor point, as editing tools apply them
+
+use crate::{ Float, Point2, Vector2 };
+
+/// Shapes that can be scaled and turned about a point, keeping their kind:
+/// circles stay circles, arcs arcs
+pub trait Anchored2<T: Float>: Sized {
+    /// scale_about: &Shape × Point × K -> Shape
+    /// every point `factor` times as far from `anchor`; a negative factor
+    /// also turns the shape half round it, so outlines keep their orientation
+    fn scale_about(&self, anchor: Point2<T>, factor: T) -> Self;
+
+    /// rotate_about: &Shape × Point × K -> Shape
+    /// turned counter-clockwise by `angle` radians around `anchor`
+    fn rotate_about(&self, anchor: Point2<T>, angle: T) -> Self;
+}
+
+/// Turn and uniform scaling about an anchor, the scale not negative
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Similar2<T> {
+    anchor: Point2<T>,
+    cos: T,
+    sin: T,
+    angle: T,
+    scale: T,
+}
+
+impl<T: Float> Similar2<T> {
+    pub(crate) fn scaling(anchor: Point2<T>, factor: T) -> Self {
+        let (zero, one) = (T::zero(), T::one());
+        if factor < zero {
+            // exactly a half turn, rather than through cos π and sin π
+            Similar2 { anchor, cos: -one, sin: zero, angle: T::pi(), scale: -factor }
+        } else {
+            Similar2 { anchor, cos: one, sin: zero, angle: zero, scale: factor }
+        }
+    }
+
+    pub(crate) fn rotation(anchor: Point2<T>, angle: T) -> Self {
+        Similar2 { anchor, cos: angle.cos(), sin: angle.sin(), angle, scale: T::one() }
+    }
+
+    pub(crate) fn vector(&self, v: Vector2<T>) -> Vector2<T> {
+        Vector2(v.0 * self.cos - v.1 * self.sin, v.0 * self.sin + v.1 * self.cos) * self.scale
+    }
+
+    pub(crate) fn point(&self, p: Point2<T>) -> Point2<T> {
+        self.anchor + self.vector(p.vector_from(self.anchor))
+    }
+
+    pub(crate) fn points(&self, ps: &[Point2<T>]) -> Vec<Point2<T>> {
+        ps.iter().map(|&p| self.point(p)).collect()
+    }
+
+    pub(crate) fn length(&self, r: T) -> T {
+        r * self.scale
+    }
+
+    pub(crate) fn angle(&self, a: T) -> T {
+        a + self.angle
+    }
+}
+
+/// Shapes mapped by a `Similar2`, which nested shapes map their members by
+pub(crate) trait Mapped2<T> {
+    fn mapped(&self, m: &Similar2<T>) -> Self;
+}
+
+/// Implements `Anchored2` for a type from its mapping by a `Similar2`
+macro_rules! impl_anchored {
+    ($name:ident, |$shape:ident, $m:ident| $body:expr) => {
+        impl<T: $crate::Float> $crate::anchor::Mapped2<T> for $name<T> {
+            fn mapped(&self, $m: &$crate::anchor::Similar2<T>) -> Self {
+                let $shape = self;
+                $body
+            }
+        }
+
+        impl<T: $crate::Float> $crate::anchor::Anchored2<T> for $name<T> {
+            fn scale_about(&self, anchor: $crate::Point2<T>, factor: T) -> Self {
+                $crate::anchor::Mapped2::mapped(self, &$crate::anchor::Similar2::scaling(anchor, factor))
+            }
+
+            fn rotate_about(&self, anchor: $crate::Point2<T>, angle: T) -> Self {
+                $crate::anchor::Mapped2::mapped(self, &$crate::anchor::Similar2::rotation(anchor, angle))
+            }
+        }
+    };
+}
+
+pub(crate) use impl_anchored;
+
+impl_anchored!(Point2, |p, m| m.point(*p));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ Arc2, Circle2, Curve2, Geometry2, Path2, PathCommand, Polygon2, Rect2, Segment2 };
+
+    fn near(p: Point2<f64>, q: Point2<f64>) -> bool {
+        p.distance(q) < 1e-12
+    }
+
+    #[test]
+    fn scale_about_centroid() {
+        let p = Polygon2::with_holes(
+            vec![Point2(0.0, 0.0), Point2(4.0, 0.0), Point2(4.0, 2.0), Point2(0.0, 2.0)],
+            vec![vec![Point2(1.0, 0.5), Point2(1.0, 1.5), Point2(2.0, 1.5), Point2(2.0, 0.5)]],
+        );
+        let c = p.centroid();
+        for factor in [3.0, 0.5, -2.0] {
+            let q = p.scale_about(c, factor);
+            assert!(near(q.centroid(), c));
+            assert!((q.area() - p.area() * factor * factor).abs() < 1e-12);
+            assert!(q.signed_area() > 0.0);
+        }
+        let r = Rect2::new(Point2(1.0, 1.0), Point2(3.0, 2.0)).scale_about(Point2(1.0, 1.0), -1.0);
+        assert_eq!(r, Rect2::new(Point2(-1.0, 0.0), Point2(1.0, 1.0)));
+    }
+
+    #[test]
+    fn rotate_about_point() {
+        let pivot = Point2(1.0, 1.0);
+        let quarter = std::f64::consts::FRAC_PI_2;
+        assert!(near(Point2(2.0, 1.0).rotate_about(pivot, quarter), Point2(1.0, 2.0)));
+        let s = Segment2::new(Point2(1.0, 1.0), Point2(3.0, 1.0)).rotate_about(pivot, quarter);
+        assert!(near(s.start(), pivot) && near(s.end(), Point2(1.0, 3.0)));
+        let c = Circle2::new(Point2(3.0, 1.0), 0.5).rotate_about(pivot, -quarter).scale_about(pivot, 2.0);
+        assert!(near(c.center(), Point2(1.0, -3.0)) && c.radius() == 1.0);
+        // arcs keep their shape: the turned arc runs through the turned points
+        let arc = Arc2::new(Point2(2.0, 0.0), 1.0, 0.3, 2.0);
+        let turned = arc.rotate_about(pivot, 1.1).scale_about(Point2(-1.0, 0.0), -1.5);
+        for t in [0.0, 0.4, 1.0] {
+            let expected = arc.point_at(t).rotate_about(pivot, 1.1).scale_about(Point2(-1.0, 0.0), -1.5);
+            assert!(turned.point_at(t).distance(expected) < 1e-12);
+        }
+        let path = Path2::new(vec![
+            PathCommand::MoveTo(Point2(0.0, 0.0)),
+            PathCommand::ArcTo { radius: 1.0, large_arc: false, sweep: true, to: Point2(2.0, 0.0) },
+        ]);
+        let turned = path.scale_about(Point2(0.0, 0.0), 2.0);
+        assert_eq!(turned.commands()[1], PathCommand::ArcTo { radius: 2.0, large_arc: false, sweep: true, to: Point2(4.0, 0.0) });
+        let g = Geometry2::Point(Point2(0.0, 2.0)).rotate_about(pivot, quarter);
+        assert!(matches!(g, Geometry2::Point(p) if near(p, Point2(0.0, 0.0))));
+    }
+}
@@ -0,0 +1,997 @@
+//! Arrangements of segments as doubly connected edge lists
+
+use std::cmp::Ordering;
+use std::collections::{ BTreeMap, HashMap, HashSet };
+
+use crate::{ Budget, Float, Point2, Polygon2, Rect2, Segment2 };
+use crate::stats;
+
+/// Directed half of an arrangement edge; the face lies on its left
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HalfEdge {
+    /// vertex the half-edge leaves
+    pub origin: usize,
+    /// oppositely directed half of the same edge
+    pub twin: usize,
+    /// following half-edge around the face
+    pub next: usize,
+    /// preceding half-edge around the face
+    pub prev: usize,
+    /// face on the left
+    pub face: usize,
+}
+
+/// Face of an arrangement: one outer boundary cycle, unless unbounded,
+/// and one cycle per connected component floating inside it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Face {
+    /// a half-edge of the counter-clockwise outer boundary
+    pub outer: Option<usize>,
+    /// a half-edge of each clockwise hole boundary
+    pub inner: Vec<usize>,
+}
+
+/// Planar subdivision induced by a set of segments:
+/// vertices at end points and crossings, half-edges between them and
+/// the faces they bound, face 0 being the unbounded one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arrangement2<T> {
+    vertices: Vec<Point2<T>>,
+    half_edges: Vec<HalfEdge>,
+    faces: Vec<Face>,
+    /// segments along each edge, and whether they run along its first half
+    sources: Vec<Vec<(usize, bool)>>,
+}
+
+impl<T: Float> Arrangement2<T> {
+    /// new: [Segment] × K -> Arrangement
+    /// points closer than `tolerance` are merged, so are overlapping
+    /// collinear pieces; isolated points keep a vertex but no edge
+    pub fn new(segments: &[Segment2<T>], tolerance: T) -> Self {
+        Self::new_within(segments, tolerance, &mut Budget::unlimited()).expect("unlimited budget")
+    }
+
+    /// new_within: [Segment] × K × &mut Budget -> Option<Arrangement>
+    /// `new`, a unit of the budget per stop of its sweep; None if it runs out
+    pub fn new_within(segments: &[Segment2<T>], tolerance: T, budget: &mut Budget) -> Option<Self> {
+        let mut weld = Weld::new(tolerance);
+        let mut stops: Vec<Vec<usize>> = segments.iter().map(|s| vec![weld.index(s.start()), weld.index(s.end())]).collect();
+        let swept = sweep(segments, Some(tolerance), budget)?;
+        for (i, j, p) in swept.crossings {
+            let v = weld.index(p);
+            stops[i].push(v);
+            stops[j].push(v);
+        }
+        // end points the sweep found next to other segments
+        for (i, p) in swept.touches {
+            let v = weld.index(p);
+            if segments[i].distance(weld.points[v]) <= tolerance {
+                stops[i].push(v);
+            }
+        }
+        let vertices = weld.points;
+        // pieces between consecutive stops, with the segment they lie along
+        // and whether it runs from the lower vertex index to the higher
+        let mut pieces = Vec::new();
+        for (i, (s, stops)) in segments.iter().zip(&mut stops).enumerate() {
+            let d = s.end().vector_from(s.start());
+            let along = |v: &usize| vertices[*v].vector_from(s.start()).dot(d);
+            stops.sort_by(|a, b| along(a).partial_cmp(&along(b)).unwrap_or(Ordering::Equal));
+            stops.dedup();
+            pieces.extend(stops.windows(2).filter(|w| w[0] != w[1]).map(|w| ((w[0].min(w[1]), w[0].max(w[1])), i, w[0] < w[1])));
+        }
+        pieces.sort_by_key(|p| p.0);
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        let mut sources: Vec<Vec<(usize, bool)>> = Vec::new();
+        for (edge, i, forward) in pieces {
+            if edges.last() != Some(&edge) {
+                edges.push(edge);
+                sources.push(Vec::new());
+            }
+            sources.last_mut().expect("edge pushed").push((i, forward));
+        }
+
+        let mut half_edges: Vec<HalfEdge> = edges
+            .iter()
+            .flat_map(|&(u, v)| [u, v])
+            .enumerate()
+            .map(|(h, origin)| HalfEdge { origin, twin: h ^ 1, next: 0, prev: 0, face: 0 })
+            .collect();
+        let mut outgoing = vec![Vec::new(); vertices.len()];
+        for (h, e) in half_edges.iter().enumerate() {
+            outgoing[e.origin].push(h);
+        }
+        let heading = |h: usize| {
+            let (a, b) = (vertices[half_edges[h].origin], vertices[half_edges[h ^ 1].origin]);
+            let d = b.vector_from(a);
+            d.1.atan2(d.0)
+        };
+        for around in &mut outgoing {
+            around.sort_by(|&a, &b| heading(a).partial_cmp(&heading(b)).unwrap_or(Ordering::Equal));
+        }
+        // next of u→v is the edge leaving v just clockwise of v→u
+        for around in &outgoing {
+            for (k, &t) in around.iter().enumerate() {
+                let next = around[(k + around.len() - 1) % around.len()];
+                half_edges[t ^ 1].next = next;
+                half_edges[next].prev = t ^ 1;
+            }
+        }
+
+        let mut arrangement = Arrangement2 { vertices, half_edges, faces: vec![Face { outer: None, inner: Vec::new() }], sources };
+        arrangement.assign_faces();
+        Some(arrangement)
+    }
+
+    /// Bounded faces are the counter-clockwise cycles; every other cycle
+    /// is the outline of a component, hung in the face just below its
+    /// leftmost vertex, or in the face the component below hangs in
+    fn assign_faces(&mut self) {
+        let mut seen = vec![false; self.half_edges.len()];
+        let mut outline = vec![usize::MAX; self.half_edges.len()];
+        let mut holes = Vec::new();
+        for h in 0..self.half_edges.len() {
+            if seen[h] {
+                continue;
+            }
+            let cycle = self.cycle(h);
+            cycle.iter().for_each(|&g| seen[g] = true);
+            if self.ring(&cycle).signed_area() > T::zero() {
+                for &g in &cycle {
+                    self.half_edges[g].face = self.faces.len();
+                }
+                self.faces.push(Face { outer: Some(h), inner: Vec::new() });
+            } else {
+                cycle.iter().for_each(|&g| outline[g] = holes.len());
+                let leftmost = cycle.iter().map(|&g| self.vertices[self.half_edges[g].origin]).reduce(|a, b| if lexicographic(b, a) == Ordering::Less { b } else { a });
+                holes.push((h, cycle, leftmost.expect("cycles are not empty")));
+            }
+        }
+        let mut order: Vec<usize> = (0..holes.len()).collect();
+        order.sort_by(|&a, &b| lexicographic(holes[a].2, holes[b].2));
+        let below = Slabs::new(&self.vertices, &self.half_edges, order.iter().map(|&k| holes[k].2.0).collect());
+        let mut host = vec![0; holes.len()];
+        // the component below lies further left, so its host is known
+        for (rank, &k) in order.iter().enumerate() {
+            host[k] = match below.below(rank, holes[k].2) {
+                Some(g) if outline[g] != usize::MAX => host[outline[g]],
+                Some(g) => self.half_edges[g].face,
+                None => 0,
+            };
+        }
+        for (k, (h, cycle, _)) in holes.into_iter().enumerate() {
+            for &g in &cycle {
+                self.half_edges[g].face = host[k];
+            }
+            self.faces[host[k]].inner.push(h);
+        }
+    }
+
+    fn ring(&self, cycle: &[usize]) -> Polygon2<T> {
+        Polygon2::new(cycle.iter().map(|&h| self.vertices[self.half_edges[h].origin]).collect())
+    }
+
+    /// vertices: &Arrangement -> &[Point]
+    pub fn vertices(&self) -> &[Point2<T>] {
+        &self.vertices
+    }
+
+    /// half_edges: &Arrangement -> &[HalfEdge]
+    /// the halves of edge e are 2e and 2e + 1
+    pub fn half_edges(&self) -> &[HalfEdge] {
+        &self.half_edges
+    }
+
+    /// faces: &Arrangement -> &[Face]
+    pub fn faces(&self) -> &[Face] {
+        &self.faces
+    }
+
+    /// sources: &Arrangement × usize -> [(usize, bool)]
+    /// the input segments half-edge h lies along, each with whether it
+    /// runs the same way
+    pub fn sources(&self, h: usize) -> impl Iterator<Item = (usize, bool)> + '_ {
+        self.sources[h / 2].iter().map(move |&(s, forward)| (s, forward == h.is_multiple_of(2)))
+    }
+
+    /// cycle: &Arrangement × usize -> [usize]
+    /// half-edges met following `next` from `h` until back to it
+    pub fn cycle(&self, h: usize) -> Vec<usize> {
+        let mut cycle = vec![h];
+        let mut g = self.half_edges[h].next;
+        while g != h {
+            cycle.push(g);
+            g = self.half_edges[g].next;
+        }
+        cycle
+    }
+
+    /// boundary: &Arrangement × usize -> Option<Polygon>
+    /// counter-clockwise outer ring of a face, None for the unbounded face
+    pub fn boundary(&self, face: usize) -> Option<Polygon2<T>> {
+        self.faces[face].outer.map(|h| self.ring(&self.cycle(h)))
+    }
+}
+
+/// crossings: [Segment] -> [(usize, usize, Point)]
+/// every pair of segments meeting at a single point, i < j, sorted by pair.
+/// Bentley–Ottmann sweep from left to right: segments only get compared
+/// once they are neighbours in the sweep line, so the cost follows the
+/// number of crossings rather than the number of pairs
+pub fn crossings<T: Float>(segments: &[Segment2<T>]) -> Vec<(usize, usize, Point2<T>)> {
+    sweep(segments, None, &mut Budget::unlimited()).expect("unlimited budget").crossings
+}
+
+/// What a sweep meets
+pub(crate) struct Swept<T> {
+    /// pairs of segments meeting at a single point, and where
+    pub(crate) crossings: Vec<(usize, usize, Point2<T>)>,
+    /// segments passing near an end point, and the end point
+    pub(crate) touches: Vec<(usize, Point2<T>)>,
+}
+
+/// The crossings, and with `reach` the segments passing within it of each
+/// end point, at least; None if the budget runs out. A segment within reach
+/// passes a point at most reach · √(1 + m²) higher or lower, so the ones
+/// steeper than 45° are looked for in a second sweep along y, where they are
+/// the shallow ones, and no band is ever wider than reach · √2; points
+/// beyond a segment's ends are looked for around those ends
+pub(crate) fn sweep<T: Float>(segments: &[Segment2<T>], reach: Option<T>, budget: &mut Budget) -> Option<Swept<T>> {
+    let _timer = stats::time("arrangement");
+    let points: Vec<Point2<T>> = match reach {
+        Some(_) => segments.iter().flat_map(|s| [s.start(), s.end()]).collect(),
+        None => Vec::new(),
+    };
+    let (mut swept, mut tests) = scan(segments, reach.map(|r| (r, &points[..])), budget)?;
+    if let Some(r) = reach {
+        let steep: Vec<usize> = (0..segments.len())
+            .filter(|&i| {
+                let d = segments[i].end().vector_from(segments[i].start());
+                d.1.abs() > d.0.abs()
+            })
+            .collect();
+        if !steep.is_empty() {
+            let flip = |p: Point2<T>| Point2(p.1, p.0);
+            let turned: Vec<Segment2<T>> = steep.iter().map(|&i| Segment2::new(flip(segments[i].start()), flip(segments[i].end()))).collect();
+            let turned_points: Vec<Point2<T>> = points.iter().map(|&p| flip(p)).collect();
+            let (more, more_tests) = scan(&turned, Some((r, &turned_points[..])), budget)?;
+            swept.touches.extend(more.touches.into_iter().map(|(i, p)| (steep[i], flip(p))));
+            tests += more_tests;
+        }
+        // a point beyond, or level with, the end of a segment along its sweep
+        // may be passed by neither sweep; within reach of the segment, it is
+        // within reach · (1 + √2) of that end, so a grid of such cells finds it
+        if r > T::zero() {
+            let cell = r * (T::one() + T::from_f64(2.0).sqrt());
+            let key = |p: Point2<T>| ((p.0 / cell).floor().to_f64() as i64, (p.1 / cell).floor().to_f64() as i64);
+            let mut grid: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+            for (k, &p) in points.iter().enumerate() {
+                grid.entry(key(p)).or_default().push(k);
+            }
+            for (i, s) in segments.iter().enumerate() {
+                if !budget.tick() {
+                    return None;
+                }
+                let d = s.end().vector_from(s.start());
+                let beyond = |p: Point2<T>| {
+                    let (u, a, b) = if d.1.abs() > d.0.abs() { (p.1, s.start().1, s.end().1) } else { (p.0, s.start().0, s.end().0) };
+                    (u <= a && u <= b) || (u >= a && u >= b)
+                };
+                for (x, y) in [key(s.start()), key(s.end())] {
+                    for (dx, dy) in [(-1, -1), (0, -1), (1, -1), (-1, 0), (0, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+                        for &k in grid.get(&(x + dx, y + dy)).into_iter().flatten() {
+                            tests += 1;
+                            let q = points[k];
+                            if k / 2 != i && beyond(q) && s.distance(q) <= r {
+                                swept.touches.push((i, q));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    stats::count("arrangement", "segment tests", tests);
+    stats::count("arrangement", "crossings", swept.crossings.len());
+    Some(swept)
+}
+
+/// One sweep along x: the crossings, the segments no steeper than 45° (or
+/// right on it) passing within reach of each of the given points, and how
+/// many segment pairs were tested
+fn scan<T: Float>(segments: &[Segment2<T>], near: Option<(T, &[Point2<T>])>, budget: &mut Budget) -> Option<(Swept<T>, usize)> {
+    let Some(bounds) = Rect2::from_points(segments.iter().flat_map(|s| [s.start(), s.end()])) else {
+        return Some((Swept { crossings: Vec::new(), touches: Vec::new() }, 0));
+    };
+    let e = bounds.max().vector_from(bounds.min());
+    let tolerance = (e.0 + e.1) * T::epsilon() * T::from_f64(64.0);
+    // end points ordered left to right, bottom to top for vertical segments
+    let ends: Vec<(Point2<T>, Point2<T>)> = segments
+        .iter()
+        .map(|s| if lexicographic(s.start(), s.end()) == Ordering::Greater { (s.end(), s.start()) } else { (s.start(), s.end()) })
+        .collect();
+    let height = |i: usize, p: Point2<T>| {
+        let (a, b) = ends[i];
+        if a.0 == b.0 {
+            return if p.1 < a.1 { a.1 } else if p.1 > b.1 { b.1 } else { p.1 };
+        }
+        a.1 + (b.1 - a.1) * ((p.0 - a.0) / (b.0 - a.0))
+    };
+    let slope = |i: usize| {
+        let (a, b) = ends[i];
+        if a.0 == b.0 { T::infinity() } else { (b.1 - a.1) / (b.0 - a.0) }
+    };
+    let shallow = |i: usize| slope(i).abs() <= T::one();
+    let band = near.map(|(r, _)| {
+        let r = r * T::from_f64(2.0).sqrt();
+        if r > tolerance { r } else { tolerance }
+    });
+
+    let mut events: BTreeMap<Event<T>, Vec<Passing>> = BTreeMap::new();
+    for (i, &(a, b)) in ends.iter().enumerate() {
+        events.entry(Event(a)).or_default().push(Passing::Starts(i));
+        events.entry(Event(b)).or_default().push(Passing::Ends(i));
+    }
+    for &q in near.map_or(&[][..], |(_, points)| points) {
+        events.entry(Event(q)).or_default().push(Passing::Near);
+    }
+    let mut status = Status::new();
+    let (mut found, mut touches) = (Vec::new(), Vec::new());
+    let mut tests = 0;
+    while let Some((Event(p), passing)) = events.pop_first() {
+        if !budget.tick() {
+            return None;
+        }
+        let mut below = status.partition_point(|i| height(i, p) < p.1 - tolerance);
+        let mut above = status.partition_point(|i| height(i, p) <= p.1 + tolerance).max(below);
+        // a steep segment through a crossing may miss its height there by
+        // more than the tolerance; it is still next to the run, so take it in
+        let through = |i: usize| passing.iter().any(|e| matches!(e, Passing::Through(j) if *j == i));
+        while below > 0 && through(status.get(below - 1)) {
+            below -= 1;
+        }
+        while above < status.len() && through(status.get(above)) {
+            above += 1;
+        }
+        let mut here = status.drain(below, above);
+        if let Some(band) = band.filter(|_| passing.iter().any(|e| matches!(e, Passing::Near))) {
+            touches.extend(here.iter().map(|&i| (i, p)));
+            let lower = (0..below).rev().take_while(|&k| height(status.get(k), p) >= p.1 - band);
+            let upper = (below..status.len()).take_while(|&k| height(status.get(k), p) <= p.1 + band);
+            touches.extend(lower.chain(upper).map(|k| status.get(k)).filter(|&i| shallow(i)).map(|i| (i, p)));
+        }
+        let mut ending = Vec::new();
+        for pass in passing {
+            match pass {
+                Passing::Starts(i) | Passing::Through(i) => here.push(i),
+                Passing::Ends(i) => ending.push(i),
+                Passing::Near => {}
+            }
+        }
+        here.sort_unstable();
+        here.dedup();
+        ending.sort_unstable();
+        let mut met = here.clone();
+        met.extend(&ending);
+        met.sort_unstable();
+        met.dedup();
+        for (k, &i) in met.iter().enumerate() {
+            for &j in &met[k + 1..] {
+                tests += 1;
+                if let Some(q) = segments[i].intersection(&segments[j]) {
+                    found.push((i, j, q));
+                }
+            }
+        }
+
+        // what goes on does so in its order just right of p
+        here.retain(|i| ending.binary_search(i).is_err() && lexicographic(ends[*i].1, p) == Ordering::Greater);
+        here.sort_by(|&i, &j| slope(i).partial_cmp(&slope(j)).unwrap_or(Ordering::Equal));
+        let (lo, hi) = (below, below + here.len());
+        status.insert(below, here);
+        let mut schedule = |i: usize, j: usize| {
+            tests += 1;
+            if let Some(q) = Segment2::new(ends[i].0, ends[i].1).intersection(&Segment2::new(ends[j].0, ends[j].1)) {
+                if lexicographic(q, p) == Ordering::Greater && q.vector_from(p).length() > tolerance {
+                    events.entry(Event(q)).or_default().extend([Passing::Through(i), Passing::Through(j)]);
+                }
+            }
+        };
+        if lo == hi {
+            if lo > 0 && lo < status.len() {
+                schedule(status.get(lo - 1), status.get(lo));
+            }
+        } else {
+            if lo > 0 {
+                schedule(status.get(lo - 1), status.get(lo));
+            }
+            if hi < status.len() {
+                schedule(status.get(hi - 1), status.get(hi));
+            }
+        }
+    }
+    found.sort_by_key(|&(i, j, _)| (i, j));
+    found.dedup_by(|a, b| (a.0, a.1) == (b.0, b.1));
+    Some((Swept { crossings: found, touches }, tests))
+}
+
+/// Sweep line status: segment indices bottom to top, in an implicit treap
+/// so that finding, cutting out and putting back a run of them takes
+/// O(log n) rather than shifting everything above
+struct Status {
+    nodes: Vec<Node>,
+    root: usize,
+    free: Vec<usize>,
+    seed: u64,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Node {
+    item: usize,
+    priority: u64,
+    size: usize,
+    left: usize,
+    right: usize,
+}
+
+const NIL: usize = usize::MAX;
+
+impl Status {
+    fn new() -> Self {
+        Status { nodes: Vec::new(), root: NIL, free: Vec::new(), seed: 0x9e37_79b9_7f4a_7c15 }
+    }
+
+    fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    fn size(&self, t: usize) -> usize {
+        if t == NIL { 0 } else { self.nodes[t].size }
+    }
+
+    /// k-th item from the bottom
+    fn get(&self, mut k: usize) -> usize {
+        let mut t = self.root;
+        loop {
+            let left = self.size(self.nodes[t].left);
+            match k.cmp(&left) {
+                Ordering::Less => t = self.nodes[t].left,
+                Ordering::Equal => return self.nodes[t].item,
+                Ordering::Greater => {
+                    k -= left + 1;
+                    t = self.nodes[t].right;
+                }
+            }
+        }
+    }
+
+    /// number of items from the bottom for which `pred` holds, as for a slice
+    fn partition_point(&self, pred: impl Fn(usize) -> bool) -> usize {
+        let (mut t, mut k) = (self.root, 0);
+        while t != NIL {
+            if pred(self.nodes[t].item) {
+                k += self.size(self.nodes[t].left) + 1;
+                t = self.nodes[t].right;
+            } else {
+                t = self.nodes[t].left;
+            }
+        }
+        k
+    }
+
+    /// takes out the items lo..hi
+    fn drain(&mut self, lo: usize, hi: usize) -> Vec<usize> {
+        let (a, rest) = self.split(self.root, lo);
+        let (run, c) = self.split(rest, hi - lo);
+        self.root = self.merge(a, c);
+        let mut items = Vec::with_capacity(hi - lo);
+        let mut stack = Vec::new();
+        let mut t = run;
+        while t != NIL || !stack.is_empty() {
+            while t != NIL {
+                stack.push(t);
+                t = self.nodes[t].left;
+            }
+            let n = stack.pop().expect("stack not empty");
+            items.push(self.nodes[n].item);
+            self.free.push(n);
+            t = self.nodes[n].right;
+        }
+        items
+    }
+
+    /// puts the items in order at position `at`
+    fn insert(&mut self, at: usize, items: Vec<usize>) {
+        let mut run = NIL;
+        for item in items {
+            self.seed ^= self.seed << 13;
+            self.seed ^= self.seed >> 7;
+            self.seed ^= self.seed << 17;
+            let node = Node { item, priority: self.seed, size: 1, left: NIL, right: NIL };
+            let n = match self.free.pop() {
+                Some(n) => {
+                    self.nodes[n] = node;
+                    n
+                }
+                None => {
+                    self.nodes.push(node);
+                    self.nodes.len() - 1
+                }
+            };
+            run = self.merge(run, n);
+        }
+        let (a, b) = self.split(self.root, at);
+        let a = self.merge(a, run);
+        self.root = self.merge(a, b);
+    }
+
+    fn update(&mut self, t: usize) {
+        self.nodes[t].size = 1 + self.size(self.nodes[t].left) + self.size(self.nodes[t].right);
+    }
+
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == NIL || b == NIL {
+            return if a == NIL { b } else { a };
+        }
+        if self.nodes[a].priority > self.nodes[b].priority {
+            let right = self.merge(self.nodes[a].right, b);
+            self.nodes[a].right = right;
+            self.update(a);
+            a
+        } else {
+            let left = self.merge(a, self.nodes[b].left);
+            self.nodes[b].left = left;
+            self.update(b);
+            b
+        }
+    }
+
+    /// the first k items, and the rest
+    fn split(&mut self, t: usize, k: usize) -> (usize, usize) {
+        if t == NIL {
+            return (NIL, NIL);
+        }
+        let left = self.nodes[t].left;
+        if k <= self.size(left) {
+            let (a, b) = self.split(left, k);
+            self.nodes[t].left = b;
+            self.update(t);
+            (a, t)
+        } else {
+            let (a, b) = self.split(self.nodes[t].right, k - self.size(left) - 1);
+            self.nodes[t].right = a;
+            self.update(t);
+            (t, b)
+        }
+    }
+}
+
+/// Points merged within a tolerance, looked up in a hash grid of that
+/// spacing; each point is merged into the first one kept near it
+struct Weld<T> {
+    points: Vec<Point2<T>>,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    tolerance: T,
+}
+
+impl<T: Float> Weld<T> {
+    fn new(tolerance: T) -> Self {
+        Weld { points: Vec::new(), cells: HashMap::new(), tolerance }
+    }
+
+    /// grid cell of a point; without a positive tolerance, the point itself
+    fn cell(&self, p: Point2<T>) -> (i64, i64) {
+        if self.tolerance > T::zero() {
+            ((p.0 / self.tolerance).floor().to_f64() as i64, (p.1 / self.tolerance).floor().to_f64() as i64)
+        } else {
+            // adding zero turns -0 into 0
+            ((p.0 + T::zero()).to_f64().to_bits() as i64, (p.1 + T::zero()).to_f64().to_bits() as i64)
+        }
+    }
+
+    fn index(&mut self, p: Point2<T>) -> usize {
+        let (x, y) = self.cell(p);
+        let reach = if self.tolerance > T::zero() { 1 } else { 0 };
+        let near = (-reach..=reach)
+            .flat_map(|dx| (-reach..=reach).map(move |dy| (x.saturating_add(dx), y.saturating_add(dy))))
+            .filter_map(|c| self.cells.get(&c))
+            .flatten()
+            .copied()
+            .filter(|&i| p.vector_from(self.points[i]).length() <= self.tolerance)
+            .min();
+        near.unwrap_or_else(|| {
+            self.points.push(p);
+            self.cells.entry((x, y)).or_default().push(self.points.len() - 1);
+            self.points.len() - 1
+        })
+    }
+}
+
+/// Non-vertical edges over the x of sorted query points, for shooting rays
+/// down from them: a segment tree whose nodes list the edges spanning all
+/// their queries, bottom to top, so a ray passes O(log n) sorted lists
+struct Slabs<'a, T> {
+    vertices: &'a [Point2<T>],
+    half_edges: &'a [HalfEdge],
+    xs: Vec<T>,
+    nodes: Vec<Vec<usize>>,
+}
+
+impl<'a, T: Float> Slabs<'a, T> {
+    fn new(vertices: &'a [Point2<T>], half_edges: &'a [HalfEdge], xs: Vec<T>) -> Self {
+        let size = xs.len().next_power_of_two();
+        let mut slabs = Slabs { vertices, half_edges, xs, nodes: vec![Vec::new(); 2 * size] };
+        // each edge by its half running left to right, spanning the queries
+        // just left of whose x it passes
+        for h in (0..half_edges.len()).step_by(2) {
+            let (a, b) = slabs.ends(h);
+            let g = match a.0.partial_cmp(&b.0) {
+                Some(Ordering::Less) => h,
+                Some(Ordering::Greater) => h + 1,
+                _ => continue,
+            };
+            let (a, b) = slabs.ends(g);
+            let (mut lo, mut hi) = (slabs.xs.partition_point(|&x| x <= a.0) + size, slabs.xs.partition_point(|&x| x <= b.0) + size);
+            while lo < hi {
+                if lo % 2 == 1 {
+                    slabs.nodes[lo].push(g);
+                    lo += 1;
+                }
+                if hi % 2 == 1 {
+                    hi -= 1;
+                    slabs.nodes[hi].push(g);
+                }
+                lo /= 2;
+                hi /= 2;
+            }
+        }
+        for k in 1..slabs.nodes.len() {
+            let mut leaf = k;
+            while leaf < size {
+                leaf *= 2;
+            }
+            let Some(&x) = slabs.xs.get(leaf - size) else { continue };
+            let mut list = std::mem::take(&mut slabs.nodes[k]);
+            // edges that meet at x are ordered as they leave it leftwards
+            list.sort_by(|&g, &f| {
+                let (hg, hf) = (slabs.height(g, x), slabs.height(f, x));
+                hg.partial_cmp(&hf).unwrap_or(Ordering::Equal).then(slabs.slope(f).partial_cmp(&slabs.slope(g)).unwrap_or(Ordering::Equal))
+            });
+            slabs.nodes[k] = list;
+        }
+        slabs
+    }
+
+    fn ends(&self, h: usize) -> (Point2<T>, Point2<T>) {
+        (self.vertices[self.half_edges[h].origin], self.vertices[self.half_edges[h ^ 1].origin])
+    }
+
+    fn height(&self, h: usize, x: T) -> T {
+        let (a, b) = self.ends(h);
+        a.1 + (b.1 - a.1) * ((x - a.0) / (b.0 - a.0))
+    }
+
+    fn slope(&self, h: usize) -> T {
+        let (a, b) = self.ends(h);
+        (b.1 - a.1) / (b.0 - a.0)
+    }
+
+    /// below: &Slabs × usize × Point -> Option<usize>
+    /// the left-to-right half-edge first met going down from just left of
+    /// the `leaf`-th query point p
+    fn below(&self, leaf: usize, p: Point2<T>) -> Option<usize> {
+        let mut k = leaf + self.nodes.len() / 2;
+        let mut best: Option<(usize, T)> = None;
+        while k > 0 {
+            let list = &self.nodes[k];
+            let n = list.partition_point(|&g| self.height(g, p.0) < p.1);
+            if let Some(&g) = n.checked_sub(1).map(|n| &list[n]) {
+                let h = self.height(g, p.0);
+                // of two meeting at x, the flatter is the higher just left of it
+                if best.is_none_or(|(f, b)| h > b || (h == b && self.slope(g) < self.slope(f))) {
+                    best = Some((g, h));
+                }
+            }
+            k /= 2;
+        }
+        best.map(|(g, _)| g)
+    }
+}
+
+/// Sweep stop, ordered left to right then bottom to top
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Event<T>(Point2<T>);
+
+impl<T: Float> Eq for Event<T> {}
+
+impl<T: Float> PartialOrd for Event<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Float> Ord for Event<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        lexicographic(self.0, other.0)
+    }
+}
+
+/// How a segment meets a sweep stop
+#[derive(Debug, Copy, Clone)]
+enum Passing {
+    Starts(usize),
+    Ends(usize),
+    Through(usize),
+    /// a point to find the segments near
+    Near,
+}
+
+/// snap_round: [Segment] × K -> [Segment]
+/// iterated snap rounding onto the grid of spacing `cell`: pixels holding an
+/// end point or a crossing are hot, and every piece is rerouted through the
+/// centre of each hot pixel it passes until none is passed any more. The
+/// pieces only meet at their grid end points, duplicates and points dropped
+pub fn snap_round<T: Float>(segments: &[Segment2<T>], cell: T) -> Vec<Segment2<T>> {
+    let half = T::from_f64(0.5);
+    let snap = |p: Point2<T>| Point2((p.0 / cell + half).floor() * cell, (p.1 / cell + half).floor() * cell);
+    let pixel = |p: Point2<T>| ((p.0 / cell + half).floor().to_f64() as i64, (p.1 / cell + half).floor().to_f64() as i64);
+    // hot pixel centres by pixel
+    let mut hot: HashMap<(i64, i64), Point2<T>> = HashMap::new();
+    for p in segments.iter().flat_map(|s| [s.start(), s.end()]).chain(crossings(segments).into_iter().map(|(_, _, p)| p)) {
+        hot.insert(pixel(p), snap(p));
+    }
+
+    // the hot pixels a to b passes in order, walking the pixel columns
+    // along its longer axis and the few rows each covers
+    let route = |a: Point2<T>, b: Point2<T>, ends: [Point2<T>; 2]| {
+        let d = b.vector_from(a);
+        let steep = d.1.abs() > d.0.abs();
+        let (a2, b2) = if steep { (Point2(a.1, a.0), Point2(b.1, b.0)) } else { (a, b) };
+        let (a2, b2) = if a2.0 > b2.0 { (b2, a2) } else { (a2, b2) };
+        let at = |x: T| if b2.0 == a2.0 { a2.1 } else { a2.1 + (b2.1 - a2.1) * ((x - a2.0) / (b2.0 - a2.0)) };
+        let (first, last) = (pixel(a2).0 - 1, pixel(b2).0 + 1);
+        let mut through = Vec::new();
+        for i in first..=last {
+            let x0 = (T::from_f64(i as f64) - half) * cell;
+            let x1 = x0 + cell;
+            let (x0, x1) = (if x0 > a2.0 { x0 } else { a2.0 }, if x1 < b2.0 { x1 } else { b2.0 });
+            if x0 > x1 {
+                continue;
+            }
+            let (y0, y1) = (at(x0), at(x1));
+            let (y0, y1) = if y0 < y1 { (y0, y1) } else { (y1, y0) };
+            let (lo, hi) = (pixel(Point2(x0, y0)).1 - 1, pixel(Point2(x1, y1)).1 + 1);
+            for j in lo..=hi {
+                let key = if steep { (j, i) } else { (i, j) };
+                if let Some(&h) = hot.get(&key) {
+                    if !ends.contains(&h) && passes(a, b, h, cell * half) {
+                        through.push(h);
+                    }
+                }
+            }
+        }
+        through.sort_by(|p, q| p.vector_from(a).dot(d).partial_cmp(&q.vector_from(a).dot(d)).unwrap_or(Ordering::Equal).then(lexicographic(*p, *q)));
+        through
+    };
+    let mut pieces = Vec::new();
+    let mut pending: Vec<(Point2<T>, Point2<T>)> = Vec::new();
+    for s in segments {
+        let ends = [snap(s.start()), snap(s.end())];
+        let mut chain = vec![ends[0]];
+        chain.extend(route(s.start(), s.end(), ends));
+        chain.push(ends[1]);
+        pending.extend(chain.windows(2).map(|w| (w[0], w[1])));
+    }
+    // a piece may be sent back through a pixel it came from, so each is
+    // routed once
+    let mut routed = HashSet::new();
+    while let Some((a, b)) = pending.pop() {
+        let key = if lexicographic(a, b) == Ordering::Greater { (pixel(b), pixel(a)) } else { (pixel(a), pixel(b)) };
+        if a == b || !routed.insert(key) {
+            continue;
+        }
+        let through = route(a, b, [a, b]);
+        if through.is_empty() {
+            pieces.push(if lexicographic(a, b) == Ordering::Greater { (b, a) } else { (a, b) });
+        } else {
+            let chain: Vec<_> = std::iter::once(a).chain(through).chain(std::iter::once(b)).collect();
+            pending.extend(chain.windows(2).map(|w| (w[0], w[1])));
+        }
+    }
+    pieces.sort_by(|p, q| lexicographic(p.0, q.0).then(lexicographic(p.1, q.1)));
+    pieces.dedup();
+    pieces.into_iter().map(|(a, b)| Segment2::new(a, b)).collect()
+}
+
+pub(crate) fn lexicographic<T: Float>(a: Point2<T>, b: Point2<T>) -> Ordering {
+    a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal).then(a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+}
+
+/// The segment meets the closed square of half side `r` around `c`
+/// (Liang–Barsky clipping)
+fn passes<T: Float>(a: Point2<T>, b: Point2<T>, c: Point2<T>, r: T) -> bool {
+    let (mut t0, mut t1) = (T::zero(), T::one());
+    for (p, d, lo, hi) in [(a.0, b.0 - a.0, c.0 - r, c.0 + r), (a.1, b.1 - a.1, c.1 - r, c.1 + r)] {
+        if d == T::zero() {
+            if p < lo || p > hi {
+                return false;
+            }
+            continue;
+        }
+        let (s, t) = ((lo - p) / d, (hi - p) / d);
+        let (s, t) = if s < t { (s, t) } else { (t, s) };
+        t0 = if s > t0 { s } else { t0 };
+        t1 = if t < t1 { t } else { t1 };
+    }
+    t0 <= t1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f64, y: f64, size: f64) -> [Segment2<f64>; 4] {
+        let p = [Point2(x, y), Point2(x + size, y), Point2(x + size, y + size), Point2(x, y + size)];
+        std::array::from_fn(|i| Segment2::new(p[i], p[(i + 1) % 4]))
+    }
+
+    #[test]
+    fn square_with_diagonals() {
+        let mut segments = square(0.0, 0.0, 2.0).to_vec();
+        segments.push(Segment2::new(Point2(0.0, 0.0), Point2(2.0, 2.0)));
+        segments.push(Segment2::new(Point2(2.0, 0.0), Point2(0.0, 2.0)));
+        let a = Arrangement2::new(&segments, 1e-9);
+        assert_eq!(a.vertices().len(), 5);
+        assert_eq!(a.half_edges().len(), 16);
+        assert_eq!(a.faces().len(), 5);
+        assert_eq!(a.faces()[0].inner.len(), 1);
+        for f in 1..5 {
+            assert!((a.boundary(f).unwrap().signed_area() - 1.0).abs() < 1e-12);
+        }
+        for (h, e) in a.half_edges().iter().enumerate() {
+            assert_eq!(a.half_edges()[e.next].prev, h);
+            assert_eq!(a.half_edges()[e.next].face, e.face);
+            assert_eq!(a.half_edges()[e.twin].twin, h);
+        }
+    }
+
+    #[test]
+    fn nested_components() {
+        let mut segments = square(0.0, 0.0, 4.0).to_vec();
+        segments.extend(square(1.0, 1.0, 1.0));
+        let a = Arrangement2::new(&segments, 1e-9);
+        assert_eq!(a.faces().len(), 3);
+        let outer = (1..3).find(|&f| a.boundary(f).unwrap().area() > 2.0).unwrap();
+        assert_eq!(a.faces()[outer].inner.len(), 1);
+        assert_eq!(a.faces()[0].inner.len(), 1);
+        assert_eq!(a.half_edges()[a.faces()[outer].inner[0]].face, outer);
+    }
+
+    #[test]
+    fn hosts_and_sources() {
+        let mut segments = square(0.0, 0.0, 8.0).to_vec();
+        segments.extend(square(1.0, 1.0, 2.0));
+        segments.extend(square(4.0, 1.0, 3.0));
+        segments.extend(square(5.0, 2.0, 1.0));
+        segments.push(Segment2::new(Point2(8.0, 0.0), Point2(4.0, 0.0)));
+        let a = Arrangement2::new(&segments, 1e-9);
+        assert_eq!(a.faces().len(), 5);
+        let by_area = |area: f64| (1..5).find(|&f| a.boundary(f).unwrap().area() == area).unwrap();
+        let (big, middle) = (by_area(64.0), by_area(9.0));
+        assert_eq!(a.faces()[big].inner.len(), 2);
+        assert_eq!(a.faces()[middle].inner.len(), 1);
+        assert_eq!(a.half_edges()[a.faces()[middle].inner[0]].face, middle);
+        let at = |p: Point2<f64>| a.vertices().iter().position(|&q| q == p).unwrap();
+        let h = (0..a.half_edges().len())
+            .find(|&h| a.half_edges()[h].origin == at(Point2(4.0, 0.0)) && a.half_edges()[h ^ 1].origin == at(Point2(8.0, 0.0)))
+            .unwrap();
+        assert_eq!(a.sources(h).collect::<Vec<_>>(), [(0, true), (16, false)]);
+        assert_eq!(a.sources(h ^ 1).collect::<Vec<_>>(), [(0, false), (16, true)]);
+    }
+
+    #[test]
+    fn overlaps_and_touches() {
+        let segments = [
+            Segment2::new(Point2(0.0, 0.0), Point2(2.0, 0.0)),
+            Segment2::new(Point2(1.0, 0.0), Point2(3.0, 0.0)),
+            Segment2::new(Point2(1.5, 0.0), Point2(1.5, 1.0)),
+        ];
+        let a = Arrangement2::new(&segments, 1e-9);
+        assert_eq!(a.vertices().len(), 6);
+        assert_eq!(a.half_edges().len(), 10);
+        assert_eq!(a.faces().len(), 1);
+        assert_eq!(a.cycle(0).len(), 10);
+        assert_eq!(crossings(&segments).len(), 2);
+        // an end point next to a steep segment, found by the sweep along y
+        let steep = [Segment2::new(Point2(1.0, -5.0), Point2(1.001, 5.0)), Segment2::new(Point2(-2.0, 0.1), Point2(1.0005, 0.0))];
+        let a = Arrangement2::new(&steep, 1e-6);
+        assert_eq!(a.vertices().len(), 4);
+        assert_eq!(a.half_edges().len(), 6);
+    }
+
+    fn brute_force(segments: &[Segment2<f64>]) -> Vec<(usize, usize)> {
+        let mut found = Vec::new();
+        for (i, s) in segments.iter().enumerate() {
+            for (j, t) in segments.iter().enumerate().skip(i + 1) {
+                if s.intersection(t).is_some() {
+                    found.push((i, j));
+                }
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn sweep_matches_brute_force() {
+        let mut seed = 7u64;
+        let mut random = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) as f64 / (1u64 << 31) as f64 * 10.0
+        };
+        let segments: Vec<_> = (0..60).map(|_| Segment2::new(Point2(random(), random()), Point2(random(), random()))).collect();
+        let pairs: Vec<_> = crossings(&segments).into_iter().map(|(i, j, _)| (i, j)).collect();
+        assert_eq!(pairs, brute_force(&segments));
+        // grid with shared end points, verticals and three lines through one point
+        let mut grid: Vec<_> = (0..4).map(|k| Segment2::new(Point2(0.0, k as f64), Point2(3.0, k as f64))).collect();
+        grid.extend((0..4).map(|k| Segment2::new(Point2(k as f64, 3.0), Point2(k as f64, 0.0))));
+        grid.push(Segment2::new(Point2(0.0, 0.0), Point2(3.0, 3.0)));
+        grid.push(Segment2::new(Point2(3.0, 0.0), Point2(0.0, 3.0)));
+        grid.push(Segment2::new(Point2(1.5, 1.5), Point2(1.5, 1.5)));
+        let pairs: Vec<_> = crossings(&grid).into_iter().map(|(i, j, _)| (i, j)).collect();
+        assert_eq!(pairs, brute_force(&grid));
+        // a crossing on a near vertical segment, whose height there is off by
+        // more than the tolerance
+        let steep: Vec<_> = [
+            [8.97, 5.08, 1.88, 6.27], [0.07, 2.01, 6.31, 9.34], [7.75, 3.11, 3.43, 8.15], [0.7, 6.25, 1.04, 6.93],
+            [3.87, 3.11, 4.37, 1.5], [6.97, 1.34, 3.29, 9.03], [1.68, 9.37, 3.48, 1.01], [4.52, 1.91, 4.53, 9.67],
+        ].iter().map(|c| Segment2::new(Point2(c[0], c[1]), Point2(c[2], c[3]))).collect();
+        let pairs: Vec<_> = crossings(&steep).into_iter().map(|(i, j, _)| (i, j)).collect();
+        assert_eq!(pairs, brute_force(&steep));
+    }
+
+    #[test]
+    fn sweep_finds_touches() {
+        let mut seed = 5u64;
+        let mut random = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) as f64 / (1u64 << 31) as f64 * 10.0
+        };
+        let segments: Vec<_> = (0..80).map(|k| {
+            let a = Point2(random(), random());
+            let b = if k % 3 == 0 { Point2(a.0 + random() / 500.0, random()) } else { Point2(random(), random()) };
+            Segment2::new(a, b)
+        }).collect();
+        let reach = 0.3;
+        let swept = sweep(&segments, Some(reach), &mut Budget::unlimited()).unwrap();
+        for (i, s) in segments.iter().enumerate() {
+            for t in segments.iter().filter(|&t| t != s) {
+                for q in [t.start(), t.end()] {
+                    if s.distance(q) <= reach {
+                        assert!(swept.touches.contains(&(i, q)), "{i} near {q:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn snap_rounding() {
+        let segments = [
+            Segment2::new(Point2(0.1, 0.2), Point2(4.2, 3.9)),
+            Segment2::new(Point2(0.3, 3.8), Point2(3.7, 0.1)),
+            Segment2::new(Point2(-0.2, 1.1), Point2(4.1, 1.4)),
+        ];
+        let snapped = snap_round(&segments, 1.0);
+        let on_grid = |p: Point2<f64>| p.0 == p.0.round() && p.1 == p.1.round();
+        assert!(snapped.iter().all(|s| on_grid(s.start()) && on_grid(s.end()) && s.start() != s.end()));
+        for (_, _, p) in crossings(&snapped) {
+            assert!(on_grid(p));
+        }
+        let a = Arrangement2::new(&snapped, 0.0);
+        assert!(a.vertices().iter().all(|&p| on_grid(p)));
+        assert_eq!(a.half_edges().len(), 2 * snapped.len());
+        // a piece passing by a hot pixel is dragged through its centre
+        let by = [Segment2::new(Point2(0.0, 0.0), Point2(4.0, 0.4)), Segment2::new(Point2(2.1, 0.4), Point2(2.1, 3.0))];
+        assert!(snap_round(&by, 1.0).contains(&Segment2::new(Point2(0.0, 0.0), Point2(2.0, 0.0))));
+    }
+}
@@ -0,0 +1,221 @@
+//! Signed distance field atlases of 2D shapes
+//!
+//! Each shape is sampled at texel centers into a tile of its own, its bounds
+//! plus a padding of texels all around, and the tiles are packed into one
+//! single-channel texture. A texel holds the signed distance to the outline,
+//! positive inside, mapped from [-range, range] texels onto [0, 255] so the
+//! outline sits at 127.5; magnified and thresholded at one half, as in
+//! distance field text rendering, this keeps edges sharp at any scale.
+
+use crate::curve::segment_distance;
+use crate::pack::pack;
+use crate::{ ContainmentGrid2, FillRule, Float, Packing, Path2, Point2, Polygon2, Rect2, Vector2 };
+
+/// Where one shape lies in an atlas
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SdfTile<T> {
+    /// lowest texel column of the tile
+    pub x: usize,
+    /// lowest texel row of the tile
+    pub y: usize,
+    /// columns of texels
+    pub width: usize,
+    /// rows of texels
+    pub height: usize,
+    /// shape coordinates of the tile's lowest corner
+    pub origin: Point2<T>,
+    /// shape units per texel
+    pub texel: T,
+}
+
+impl<T: Float> SdfTile<T> {
+    /// quad: &SdfTile -> Rect
+    /// the part of the shape's plane the tile covers, to draw it over
+    pub fn quad(&self) -> Rect2<T> {
+        let size = |n: usize| T::from_f64(n as f64) * self.texel;
+        Rect2::new(self.origin, self.origin + Vector2(size(self.width), size(self.height)))
+    }
+}
+
+/// Signed distance tiles of shapes packed into one 8-bit texture
+#[derive(Debug, Clone, PartialEq)]
+pub struct SdfAtlas2<T> {
+    width: usize,
+    height: usize,
+    /// texels at which distances saturate
+    range: T,
+    /// row by row from texel (0, 0)
+    texels: Vec<u8>,
+    tiles: Vec<SdfTile<T>>,
+}
+
+impl<T: Float> SdfAtlas2<T> {
+    /// new: [Path] × FillRule × K × usize × K × usize -> Option<SdfAtlas>
+    /// `scale` texels per shape unit, `padding` texels around each shape,
+    /// distances saturating at `range` texels, in a texture `width` texels
+    /// wide and as tall as the tiles need; None if a tile is wider than
+    /// that, or the scale or range is not positive. Curves are flattened
+    /// within a sixteenth of a texel; an empty shape gets an empty tile
+    pub fn new(shapes: &[Path2<T>], rule: FillRule, scale: T, padding: usize, range: T, width: usize) -> Option<Self> {
+        if !(scale > T::zero() && range > T::zero()) {
+            return None;
+        }
+        let texel = T::one() / scale;
+        let pad = T::from_f64(padding as f64) * texel;
+        let ceil = |x: T| {
+            let f = x.floor();
+            (if f < x { f + T::one() } else { f }).to_f64() as usize
+        };
+        let mut tiles: Vec<SdfTile<T>> = shapes.iter().map(|shape| match shape.bounds() {
+            Some(b) => {
+                let e = b.max().vector_from(b.min());
+                SdfTile {
+                    x: 0,
+                    y: 0,
+                    width: ceil(e.0 * scale) + 2 * padding,
+                    height: ceil(e.1 * scale) + 2 * padding,
+                    origin: b.min() - Vector2(pad, pad),
+                    texel,
+                }
+            }
+            None => SdfTile { x: 0, y: 0, width: 0, height: 0, origin: Point2(T::zero(), T::zero()), texel },
+        }).collect();
+        let drawn: Vec<usize> = (0..tiles.len()).filter(|&i| tiles[i].width > 0 && tiles[i].height > 0).collect();
+        if drawn.iter().any(|&i| tiles[i].width > width) {
+            return None;
+        }
+        let size = |n: usize| T::from_f64(n as f64);
+        let items: Vec<Rect2<T>> = drawn.iter().map(|&i| Rect2::new(Point2(T::zero(), T::zero()), Point2(size(tiles[i].width), size(tiles[i].height)))).collect();
+        let tall = drawn.iter().map(|&i| tiles[i].height).sum::<usize>();
+        let placements = pack(&items, Vector2(size(width), size(tall)), Packing::Skyline, false);
+        if placements.len() != drawn.len() {
+            return None;
+        }
+        for p in &placements {
+            let at = p.transform.translation();
+            let tile = &mut tiles[drawn[p.item]];
+            tile.x = at.0.to_f64().round() as usize;
+            tile.y = at.1.to_f64().round() as usize;
+        }
+        let height = drawn.iter().map(|&i| tiles[i].y + tiles[i].height).max().unwrap_or(0);
+        let mut texels = vec![0u8; width * height];
+        for &i in &drawn {
+            let tile = tiles[i];
+            let rings: Vec<Vec<Point2<T>>> = shapes[i].to_polygons(texel / T::from_f64(16.0)).iter().flat_map(|p| p.rings().map(|r| r.to_vec()).collect::<Vec<_>>()).collect();
+            let Some((first, rest)) = rings.split_first() else { continue };
+            let grid = ContainmentGrid2::new(&Polygon2::with_holes(first.clone(), rest.to_vec()));
+            let edges: Vec<(Point2<T>, Point2<T>)> = rings.iter().flat_map(|r| (0..r.len()).map(move |k| (r[k], r[(k + 1) % r.len()]))).collect();
+            let half = T::from_f64(0.5);
+            for row in 0..tile.height {
+                for column in 0..tile.width {
+                    let p = tile.origin + Vector2((size(column) + half) * texel, (size(row) + half) * texel);
+                    let d = edges.iter().map(|&(a, b)| segment_distance(p, a, b)).fold(T::infinity(), |m, d| if d < m { d } else { m }) * scale;
+                    let signed = if rule.is_filled(grid.winding_number(p)) { d } else { -d };
+                    texels[(tile.y + row) * width + tile.x + column] = encode(signed, range);
+                }
+            }
+        }
+        Some(SdfAtlas2 { width, height, range, texels, tiles })
+    }
+
+    /// width: &SdfAtlas -> usize
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// height: &SdfAtlas -> usize
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// texels: &SdfAtlas -> [u8]
+    /// row by row from texel (0, 0), rows `width` bytes apart with no gaps,
+    /// to upload as an R8 texture
+    pub fn texels(&self) -> &[u8] {
+        &self.texels
+    }
+
+    /// tiles: &SdfAtlas -> [SdfTile]
+    /// one per shape, in order
+    pub fn tiles(&self) -> &[SdfTile<T>] {
+        &self.tiles
+    }
+
+    /// uv: &SdfAtlas × usize -> Option<Rect>
+    /// texture coordinates in [0, 1] of a shape's tile, None for an empty one
+    pub fn uv(&self, shape: usize) -> Option<Rect2<T>> {
+        let tile = self.tiles.get(shape)?;
+        if tile.width == 0 || tile.height == 0 {
+            return None;
+        }
+        let (w, h) = (T::from_f64(self.width as f64), T::from_f64(self.height as f64));
+        let at = |x: usize, y: usize| Point2(T::from_f64(x as f64) / w, T::from_f64(y as f64) / h);
+        Some(Rect2::new(at(tile.x, tile.y), at(tile.x + tile.width, tile.y + tile.height)))
+    }
+
+    /// distance: &SdfAtlas × u8 -> K
+    /// the signed distance in texels a texel value stands for, saturated
+    /// values standing for the range
+    pub fn distance(&self, value: u8) -> T {
+        (T::from_f64(value as f64) / T::from_f64(255.0) - T::from_f64(0.5)) * self.range * T::from_f64(2.0)
+    }
+}
+
+/// Signed texel distance onto [0, 255], ±range at the ends
+fn encode<T: Float>(signed: T, range: T) -> u8 {
+    let v = (signed / (range * T::from_f64(2.0)) + T::from_f64(0.5)) * T::from_f64(255.0);
+    let v = if v < T::zero() { T::zero() } else if v > T::from_f64(255.0) { T::from_f64(255.0) } else { v };
+    v.to_f64().round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PathCommand::*;
+
+    fn square(x: f64, y: f64, s: f64) -> Path2<f64> {
+        Path2::new(vec![MoveTo(Point2(x, y)), LineTo(Point2(x + s, y)), LineTo(Point2(x + s, y + s)), LineTo(Point2(x, y + s)), Close])
+    }
+
+    #[test]
+    fn tiles() {
+        let shapes = [square(0.0, 0.0, 2.0), Path2::new(Vec::new()), square(5.0, 5.0, 1.0)];
+        let atlas = SdfAtlas2::new(&shapes, FillRule::NonZero, 4.0, 2, 2.0, 32).unwrap();
+        let t = atlas.tiles();
+        assert_eq!((t[0].width, t[0].height, t[2].width, t[2].height), (12, 12, 8, 8));
+        assert_eq!((t[1].width, atlas.uv(1)), (0, None));
+        assert_eq!((atlas.width(), atlas.height(), atlas.texels().len()), (32, 12, 32 * 12));
+        assert_eq!(t[0].quad(), Rect2::new(Point2(-0.5, -0.5), Point2(2.5, 2.5)));
+        // the tiles do not overlap
+        assert!(t[0].x + t[0].width <= t[2].x || t[2].x + t[2].width <= t[0].x || t[0].y + t[0].height <= t[2].y);
+        let uv = atlas.uv(0).unwrap();
+        assert_eq!(uv.max().1, 1.0);
+        assert!(SdfAtlas2::new(&shapes, FillRule::NonZero, 4.0, 2, 2.0, 8).is_none());
+        assert!(SdfAtlas2::new(&shapes, FillRule::NonZero, 0.0, 2, 2.0, 32).is_none());
+    }
+
+    #[test]
+    fn distances() {
+        // a ring: outer square with the same square inside it wound again
+        let mut ring = square(0.0, 0.0, 4.0);
+        for c in square(1.0, 1.0, 2.0).commands() {
+            ring.push(*c);
+        }
+        let atlas = SdfAtlas2::new(&[ring], FillRule::EvenOdd, 2.0, 1, 3.0, 16).unwrap();
+        let t = atlas.tiles()[0];
+        let at = |column: usize, row: usize| atlas.distance(atlas.texels()[(t.y + row) * atlas.width() + t.x + column]);
+        let near = |a: f64, b: f64| (a - b).abs() <= 3.0 * 2.0 / 255.0;
+        // texel centers at shape coordinates (column / 2 - 0.25, row / 2 - 0.25)
+        assert!(near(at(0, 4), -0.5));
+        assert!(near(at(1, 4), 0.5));
+        assert!(near(at(2, 4), 0.5));
+        assert!(near(at(4, 4), -1.5));
+        assert!(near(at(5, 5), -1.5));
+        assert!(near(at(1, 1), 0.5) && at(3, 3) < 0.0);
+        assert!(near(at(0, 0), -(0.5f64 * 0.5 * 2.0).sqrt()));
+        assert_eq!(atlas.texels()[(t.y + 4) * 16 + t.x + 4], 64);
+        let solid = SdfAtlas2::new(&[square(0.0, 0.0, 20.0)], FillRule::NonZero, 1.0, 0, 3.0, 20).unwrap();
+        assert_eq!(solid.texels()[10 * 20 + 10], 255);
+        assert_eq!(solid.distance(255), 3.0);
+    }
+}
@@ -0,0 +1,120 @@
+//! Coordinate axes
+
+use std::ops::{ Index, IndexMut };
+
+use crate::{ Float, Point2, Point3, Vector2, Vector3 };
+
+/// Axis of the plane
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Axis2 {
+    /// horizontal, first coordinate
+    X,
+    /// vertical, second coordinate
+    Y,
+}
+
+/// Axis of space
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Axis3 {
+    /// first coordinate
+    X,
+    /// second coordinate
+    Y,
+    /// third coordinate
+    Z,
+}
+
+impl Axis2 {
+    /// Both axes in order
+    pub const ALL: [Axis2; 2] = [Axis2::X, Axis2::Y];
+
+    /// index: Axis -> usize
+    /// 0 for x, 1 for y
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// next: Axis -> Axis
+    /// the other axis, for alternating kd-tree planes
+    pub fn next(self) -> Self {
+        Axis2::ALL[(self.index() + 1) % 2]
+    }
+
+    /// unit: Axis -> Vector
+    pub fn unit<T: Float>(self) -> Vector2<T> {
+        let mut v = Vector2(T::zero(), T::zero());
+        v[self] = T::one();
+        v
+    }
+}
+
+impl Axis3 {
+    /// All three axes in order
+    pub const ALL: [Axis3; 3] = [Axis3::X, Axis3::Y, Axis3::Z];
+
+    /// index: Axis -> usize
+    /// 0 for x, 1 for y, 2 for z
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// next: Axis -> Axis
+    /// x to y to z and back to x, for cycling kd-tree planes
+    pub fn next(self) -> Self {
+        Axis3::ALL[(self.index() + 1) % 3]
+    }
+
+    /// unit: Axis -> Vector
+    pub fn unit<T: Float>(self) -> Vector3<T> {
+        let mut v = Vector3(T::zero(), T::zero(), T::zero());
+        v[self] = T::one();
+        v
+    }
+}
+
+macro_rules! impl_axis_index {
+    ($axis:ident, $name:ident; $($variant:ident => $field:tt),+) => {
+        impl<T> Index<$axis> for $name<T> {
+            type Output = T;
+
+            fn index(&self, axis: $axis) -> &T {
+                match axis {
+                    $($axis::$variant => &self.$field,)+
+                }
+            }
+        }
+
+        impl<T> IndexMut<$axis> for $name<T> {
+            fn index_mut(&mut self, axis: $axis) -> &mut T {
+                match axis {
+                    $($axis::$variant => &mut self.$field,)+
+                }
+            }
+        }
+    };
+}
+
+impl_axis_index!(Axis2, Vector2; X => 0, Y => 1);
+impl_axis_index!(Axis2, Point2; X => 0, Y => 1);
+impl_axis_index!(Axis3, Vector3; X => 0, Y => 1, Z => 2);
+impl_axis_index!(Axis3, Point3; X => 0, Y => 1, Z => 2);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexing() {
+        let mut p = Point3(1, 2, 3);
+        assert_eq!(Axis3::ALL.map(|a| p[a]), [1, 2, 3]);
+        p[Axis3::Z] = 7;
+        assert_eq!(p, Point3(1, 2, 7));
+        let mut v = Vector2(4.0, 5.0);
+        v[Axis2::X] += 1.0;
+        assert_eq!((v[Axis2::X], v[Axis2::Y]), (5.0, 5.0));
+        assert_eq!(Axis3::Y.unit::<f64>(), Vector3(0.0, 1.0, 0.0));
+        assert_eq!(Axis2::Y.unit::<f64>(), Vector2(0.0, 1.0));
+        assert_eq!((Axis3::Z.next(), Axis3::X.next(), Axis2::Y.next()), (Axis3::X, Axis3::Y, Axis2::X));
+        assert_eq!(Axis3::Z.index(), 2);
+    }
+}
@@ -0,0 +1,183 @@
+//! Axis-angle and rotation-vector forms of 3d rotations
+//!
+//! A rotation vector ω in so(3) turns by |ω| about ω; `exp` maps it to a
+//! rotor and `log` back, choosing |ω| ≤ π. Gyroscopes report body rates,
+//! so a rate ω held for dt moves an attitude q to q exp(ω dt).
+
+use crate::{ Float, Rotor3, Vector3 };
+
+/// 3d Axis-angle rotation: unit axis, angle turned counter-clockwise
+/// looking down the axis
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AxisAngle3<T>(Vector3<T>, T);
+
+/// 3d Rotation vector: axis scaled by the angle
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RotationVector3<T>(Vector3<T>);
+
+fn basis<T: Float>() -> [Vector3<T>; 3] {
+    let (zero, one) = (T::zero(), T::one());
+    [Vector3(one, zero, zero), Vector3(zero, one, zero), Vector3(zero, zero, one)]
+}
+
+impl<T: Float> AxisAngle3<T> {
+    /// new: Vector × K -> Option<AxisAngle>
+    /// None for a zero axis
+    pub fn new(axis: Vector3<T>, angle: T) -> Option<Self> {
+        let l = axis.length();
+        if l == T::zero() || !l.is_finite() {
+            return None;
+        }
+        Some(AxisAngle3(axis / l, angle))
+    }
+
+    /// axis: &AxisAngle -> Vector
+    pub fn axis(&self) -> Vector3<T> {
+        self.0
+    }
+
+    /// angle: &AxisAngle -> K
+    pub fn angle(&self) -> T {
+        self.1
+    }
+
+    /// to_rotation_vector: &AxisAngle -> RotationVector
+    pub fn to_rotation_vector(&self) -> RotationVector3<T> {
+        RotationVector3(self.0 * self.1)
+    }
+
+    /// to_rotor: &AxisAngle -> Rotor
+    pub fn to_rotor(&self) -> Rotor3<T> {
+        self.to_rotation_vector().exp()
+    }
+
+    /// to_matrix: &AxisAngle -> [Vector; 3]
+    /// rotation matrix by columns
+    pub fn to_matrix(&self) -> [Vector3<T>; 3] {
+        self.to_rotation_vector().to_matrix()
+    }
+
+    /// from_rotor: Rotor -> AxisAngle
+    /// angle in [0, π]; about +x for no rotation
+    pub fn from_rotor(r: Rotor3<T>) -> Self {
+        RotationVector3::log(r).to_axis_angle()
+    }
+
+    /// from_matrix: [Vector; 3] -> AxisAngle
+    /// of a rotation matrix by columns, as `from_rotor`
+    pub fn from_matrix(columns: [Vector3<T>; 3]) -> Self {
+        RotationVector3::from_matrix(columns).to_axis_angle()
+    }
+}
+
+impl<T: Float> RotationVector3<T> {
+    /// new: Vector -> RotationVector
+    pub fn new(v: Vector3<T>) -> Self {
+        RotationVector3(v)
+    }
+
+    /// vector: &RotationVector -> Vector
+    pub fn vector(&self) -> Vector3<T> {
+        self.0
+    }
+
+    /// angle: &RotationVector -> K
+    pub fn angle(&self) -> T {
+        self.0.length()
+    }
+
+    /// to_axis_angle: &RotationVector -> AxisAngle
+    /// about +x for the zero vector
+    pub fn to_axis_angle(&self) -> AxisAngle3<T> {
+        let angle = self.angle();
+        if angle == T::zero() { AxisAngle3(basis()[0], T::zero()) } else { AxisAngle3(self.0 / angle, angle) }
+    }
+
+    /// exp: &RotationVector -> Rotor
+    pub fn exp(&self) -> Rotor3<T> {
+        Rotor3::exp(self.0)
+    }
+
+    /// log: Rotor -> RotationVector
+    /// the shortest one, |ω| ≤ π
+    pub fn log(r: Rotor3<T>) -> Self {
+        RotationVector3(r.log())
+    }
+
+    /// hat: &RotationVector -> [Vector; 3]
+    /// skew matrix [ω]× by columns, [ω]× v = ω × v
+    pub fn hat(&self) -> [Vector3<T>; 3] {
+        basis().map(|e| self.0.cross(e))
+    }
+
+    /// to_matrix: &RotationVector -> [Vector; 3]
+    /// rotation matrix by columns (Rodrigues)
+    pub fn to_matrix(&self) -> [Vector3<T>; 3] {
+        let r = self.exp();
+        basis().map(|e| r.rotate(e))
+    }
+
+    /// from_matrix: [Vector; 3] -> RotationVector
+    /// of a rotation matrix by columns, |ω| ≤ π
+    pub fn from_matrix(columns: [Vector3<T>; 3]) -> Self {
+        RotationVector3::log(Rotor3::from_basis(columns[0], columns[1], columns[2]))
+    }
+}
+
+impl<T: Float> Rotor3<T> {
+    /// integrate: Rotor × Vector × K -> Rotor
+    /// attitude after turning at the body-frame angular rate for dt,
+    /// exact for a rate held over the step
+    pub fn integrate(self, rate: Vector3<T>, dt: T) -> Self {
+        self * Rotor3::exp(rate * dt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn near(v: Vector3<f64>, w: Vector3<f64>) -> bool {
+        (v - w).length() < 1e-12
+    }
+
+    #[test]
+    fn conversions() {
+        let a = AxisAngle3::new(Vector3(0.0, 0.0, 2.0), PI / 2.0).unwrap();
+        assert!(near(a.axis(), Vector3(0.0, 0.0, 1.0)));
+        let m = a.to_matrix();
+        assert!(near(m[0], Vector3(0.0, 1.0, 0.0)) && near(m[1], Vector3(-1.0, 0.0, 0.0)));
+        let w = RotationVector3::new(Vector3(0.4, -1.1, 2.0));
+        let back = RotationVector3::from_matrix(w.to_matrix());
+        assert!(near(back.vector(), w.vector()));
+        assert!(near(RotationVector3::log(w.exp()).vector(), w.vector()));
+        let aa = AxisAngle3::from_rotor(w.exp());
+        assert!(near(aa.to_rotation_vector().vector(), w.vector()));
+        assert!(near(AxisAngle3::from_matrix(aa.to_matrix()).axis(), aa.axis()));
+        // past a half turn the other way round is shorter
+        let long = RotationVector3::new(Vector3(0.0, 1.5 * PI, 0.0));
+        assert!(near(RotationVector3::log(long.exp()).vector(), Vector3(0.0, -0.5 * PI, 0.0)));
+        let v = Vector3(1.0, 2.0, 3.0);
+        let hat = w.hat();
+        assert!(near(hat[0] * v.0 + hat[1] * v.1 + hat[2] * v.2, w.vector().cross(v)));
+        assert_eq!(AxisAngle3::new(Vector3(0.0, 0.0, 0.0), 1.0), None);
+        assert_eq!(RotationVector3::new(Vector3(0.0, 0.0, 0.0)).to_axis_angle().angle(), 0.0);
+    }
+
+    #[test]
+    fn gyro_integration() {
+        // a body spinning about its own x while pitched a quarter turn
+        let start = Rotor3::exp(Vector3(0.0, PI / 2.0, 0.0));
+        let rate = Vector3(0.5, 0.0, 0.0);
+        let mut q = start;
+        for _ in 0..1000 {
+            q = q.integrate(rate, 0.002);
+        }
+        let expected = start * Rotor3::exp(Vector3(1.0, 0.0, 0.0));
+        let v = Vector3(0.3, 0.2, -1.0);
+        assert!((q.rotate(v) - expected.rotate(v)).length() < 1e-9);
+        // body x is world -z throughout
+        assert!(near(q.rotate(Vector3(1.0, 0.0, 0.0)), Vector3(0.0, 0.0, -1.0)));
+    }
+}
@@ -0,0 +1,281 @@
+//! Arc splines: polylines and curves as G1 runs of arcs and lines (biarcs)
+//!
+//! Between two points with tangents, a biarc is a pair of arcs meeting
+//! tangentially; of the family, the one with equal tangent lengths is used.
+//! Spans whose biarc strays past the tolerance are halved and fitted again.
+
+use crate::{ Arc2, Closest2, Curve2, Float, Point2, Polyline2, Segment2, Vector2 };
+
+/// Piece of an arc spline
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ArcSpan2<T> {
+    /// straight run
+    Line(Segment2<T>),
+    /// circular arc
+    Arc(Arc2<T>),
+}
+
+impl<T: Float> ArcSpan2<T> {
+    /// start: &Span -> Point
+    pub fn start(&self) -> Point2<T> {
+        match self {
+            ArcSpan2::Line(s) => s.start(),
+            ArcSpan2::Arc(a) => a.point_at(T::zero()),
+        }
+    }
+
+    /// end: &Span -> Point
+    pub fn end(&self) -> Point2<T> {
+        match self {
+            ArcSpan2::Line(s) => s.end(),
+            ArcSpan2::Arc(a) => a.point_at(T::one()),
+        }
+    }
+}
+
+impl<T: Float> Closest2<T> for ArcSpan2<T> {
+    fn closest_point_on(&self, p: Point2<T>) -> Point2<T> {
+        match self {
+            ArcSpan2::Line(s) => s.closest_point_on(p),
+            ArcSpan2::Arc(a) => a.closest_point_on(p),
+        }
+    }
+}
+
+/// polyline_to_arcs: &Polyline × K -> [Span]
+/// arcs and lines through every kept vertex, within `tolerance` of the
+/// vertices and edge midpoints; tangents at inner vertices bisect the
+/// edges, so the result is G1 where the polyline turns gently, and an edge
+/// whose biarc bulges too far, as at a sharp corner, stays a line
+pub fn polyline_to_arcs<T: Float>(polyline: &Polyline2<T>, tolerance: T) -> Vec<ArcSpan2<T>> {
+    let mut v: Vec<Point2<T>> = polyline.vertices().to_vec();
+    v.dedup();
+    let n = v.len();
+    let mut spans = Vec::new();
+    if n < 2 {
+        return spans;
+    }
+    let unit = |d: Vector2<T>| d / d.length();
+    let mut tangents: Vec<Vector2<T>> = (0..n).map(|i| {
+        let incoming = if i > 0 { Some(unit(v[i].vector_from(v[i - 1]))) } else { None };
+        let outgoing = if i + 1 < n { Some(unit(v[i + 1].vector_from(v[i]))) } else { None };
+        match (incoming, outgoing) {
+            (Some(a), Some(b)) => {
+                let s = a + b;
+                if s.dot(s) > T::epsilon() { unit(s) } else { a }
+            }
+            (Some(a), None) | (None, Some(a)) => a,
+            (None, None) => unreachable!(),
+        }
+    }).collect();
+    // at the ends, the tangent of the circle through the last three vertices:
+    // the inner tangent mirrored in the last edge
+    if n >= 3 {
+        let mirrored = |edge: Vector2<T>, inner: Vector2<T>| {
+            let e = unit(edge);
+            e * (T::from_f64(2.0) * e.dot(inner)) - inner
+        };
+        tangents[0] = mirrored(v[1].vector_from(v[0]), tangents[1]);
+        tangents[n - 1] = mirrored(v[n - 1].vector_from(v[n - 2]), tangents[n - 2]);
+    }
+    let half = T::from_f64(0.5);
+    let mut pending = vec![(0, n - 1)];
+    // spans come off the stack first to last
+    while let Some((i, j)) = pending.pop() {
+        let fitted = biarc(v[i], tangents[i], v[j], tangents[j]);
+        let fits = fitted.as_ref().is_some_and(|pieces| {
+            let samples = (i + 1..j).map(|k| v[k]).chain((i..j).map(|k| v[k] + v[k + 1].vector_from(v[k]) * half));
+            within(pieces, samples, tolerance)
+        });
+        match fitted {
+            Some(pieces) if fits => pieces.into_iter().for_each(|s| push(&mut spans, s)),
+            _ if j - i >= 2 => {
+                let m = (i + j) / 2;
+                pending.push((m, j));
+                pending.push((i, m));
+            }
+            _ => push(&mut spans, ArcSpan2::Line(Segment2::new(v[i], v[j]))),
+        }
+    }
+    spans
+}
+
+/// curve_to_arcs: &Curve × K -> [Span]
+/// arcs and lines following the curve within `tolerance` at sixteen
+/// samples per fitted span, G1 wherever the curve is; each polynomial
+/// piece is fitted on its own
+pub fn curve_to_arcs<T: Float, C: Curve2<T>>(curve: &C, tolerance: T) -> Vec<ArcSpan2<T>> {
+    // the chord stands in where the curve stalls
+    let tangent = |t: T, chord: Vector2<T>| {
+        let d = curve.derivative(t);
+        let d = if d.dot(d) > T::zero() { d } else { chord };
+        d / d.length()
+    };
+    let spans_count = curve.spans().max(1);
+    let mut pending: Vec<(T, T, u32)> = (0..spans_count).rev().map(|k| {
+        let f = |k: usize| T::from_f64(k as f64 / spans_count as f64);
+        (f(k), f(k + 1), 0)
+    }).collect();
+    let mut spans = Vec::new();
+    while let Some((t0, t1, depth)) = pending.pop() {
+        let (p0, p1) = (curve.point_at(t0), curve.point_at(t1));
+        if p0 == p1 {
+            continue;
+        }
+        let chord = p1.vector_from(p0);
+        let fitted = biarc(p0, tangent(t0, chord), p1, tangent(t1, chord));
+        let fits = fitted.as_ref().is_some_and(|pieces| {
+            let samples = (1..16).map(|k| curve.point_at(t0 + (t1 - t0) * T::from_f64(k as f64 / 16.0)));
+            within(pieces, samples, tolerance)
+        });
+        match fitted {
+            Some(pieces) if fits || depth >= 32 => pieces.into_iter().for_each(|s| push(&mut spans, s)),
+            _ if depth < 32 => {
+                let m = (t0 + t1) * T::from_f64(0.5);
+                pending.push((m, t1, depth + 1));
+                pending.push((t0, m, depth + 1));
+            }
+            _ => push(&mut spans, ArcSpan2::Line(Segment2::new(p0, p1))),
+        }
+    }
+    spans
+}
+
+/// Whether every sample is within `tolerance` of the pieces
+fn within<T: Float, I: Iterator<Item = Point2<T>>>(pieces: &[ArcSpan2<T>; 2], mut samples: I, tolerance: T) -> bool {
+    samples.all(|p| pieces.iter().any(|s| s.closest_point_on(p).distance(p) <= tolerance))
+}
+
+/// Appends a span, merging straight runs that continue each other
+fn push<T: Float>(spans: &mut Vec<ArcSpan2<T>>, span: ArcSpan2<T>) {
+    if let (Some(ArcSpan2::Line(last)), ArcSpan2::Line(next)) = (spans.last(), span) {
+        let (a, b) = (last.end().vector_from(last.start()), next.end().vector_from(next.start()));
+        let cross = a.0 * b.1 - a.1 * b.0;
+        if last.end() == next.start() && a.dot(b) > T::zero() && cross.abs() <= a.length() * b.length() * T::epsilon() * T::from_f64(64.0) {
+            let merged = Segment2::new(last.start(), next.end());
+            *spans.last_mut().unwrap() = ArcSpan2::Line(merged);
+            return;
+        }
+    }
+    spans.push(span);
+}
+
+/// Equal-tangent biarc from p0 leaving along t0 to p1 arriving along t1,
+/// both unit; None where the tangents are equal and across the chord,
+/// which only infinite arcs join
+fn biarc<T: Float>(p0: Point2<T>, t0: Vector2<T>, p1: Point2<T>, t1: Vector2<T>) -> Option<[ArcSpan2<T>; 2]> {
+    let v = p1.vector_from(p0);
+    let t = t0 + t1;
+    let (vt, tt, vv) = (v.dot(t), t.dot(t), v.dot(v));
+    let four = T::from_f64(4.0);
+    let gap = four - tt;
+    let d = if gap > T::epsilon() * T::from_f64(64.0) {
+        ((vt * vt + gap * vv).sqrt() - vt) / gap
+    } else if vt > T::zero() {
+        vv / (T::from_f64(2.0) * vt)
+    } else {
+        return None;
+    };
+    let (q0, q1) = (p0 + t0 * d, p1 - t1 * d);
+    let join = q0 + q1.vector_from(q0) * T::from_f64(0.5);
+    Some([arc(p0, t0, join), arc(p1, -t1, join).reversed()])
+}
+
+/// Arc from p leaving along unit t to e, a line if e lies ahead on t
+fn arc<T: Float>(p: Point2<T>, t: Vector2<T>, e: Point2<T>) -> ArcSpan2<T> {
+    let chord = e.vector_from(p);
+    let n = Vector2(-t.1, t.0);
+    let side = n.dot(chord);
+    if side.abs() <= chord.length() * T::epsilon() * T::from_f64(64.0) {
+        return ArcSpan2::Line(Segment2::new(p, e));
+    }
+    // signed radius, positive turning left
+    let r = chord.dot(chord) / (T::from_f64(2.0) * side);
+    let center = p + n * r;
+    let (a, b) = (p.vector_from(center), e.vector_from(center));
+    let tau = T::pi() * T::from_f64(2.0);
+    let mut sweep = (a.0 * b.1 - a.1 * b.0).atan2(a.dot(b));
+    if r > T::zero() && sweep < T::zero() {
+        sweep = sweep + tau;
+    } else if r < T::zero() && sweep > T::zero() {
+        sweep = sweep - tau;
+    }
+    ArcSpan2::Arc(Arc2::new(center, r.abs(), a.1.atan2(a.0), sweep))
+}
+
+impl<T: Float> ArcSpan2<T> {
+    /// The same span run backwards
+    fn reversed(self) -> Self {
+        match self {
+            ArcSpan2::Line(s) => ArcSpan2::Line(Segment2::new(s.end(), s.start())),
+            ArcSpan2::Arc(a) => ArcSpan2::Arc(Arc2::new(a.center(), a.radius(), a.start() + a.sweep(), -a.sweep())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBezier2;
+
+    /// Whether the spans join end to end
+    fn chained(spans: &[ArcSpan2<f64>]) -> bool {
+        spans.windows(2).all(|w| w[0].end().distance(w[1].start()) < 1e-9)
+    }
+
+    fn direction(s: &ArcSpan2<f64>, at_end: bool) -> Vector2<f64> {
+        match s {
+            ArcSpan2::Line(l) => {
+                let d = l.end().vector_from(l.start());
+                d / d.length()
+            }
+            ArcSpan2::Arc(a) => {
+                let d = a.derivative(if at_end { 1.0 } else { 0.0 });
+                d / d.length()
+            }
+        }
+    }
+
+    #[test]
+    fn circle_samples_become_few_arcs() {
+        let points: Vec<Point2<f64>> = (0..=64).map(|k| {
+            let a = k as f64 / 64.0 * std::f64::consts::PI;
+            Point2(3.0 * a.cos(), 3.0 * a.sin())
+        }).collect();
+        let spans = polyline_to_arcs(&Polyline2::new(points.clone()), 1e-3);
+        assert!(spans.len() <= 4);
+        assert!(chained(&spans));
+        assert!(spans.iter().all(|s| matches!(s, ArcSpan2::Arc(a) if (a.radius() - 3.0).abs() < 1e-2)));
+        for p in &points {
+            assert!(spans.iter().any(|s| s.closest_point_on(*p).distance(*p) <= 1e-3));
+        }
+        for w in spans.windows(2) {
+            assert!((direction(&w[0], true) - direction(&w[1], false)).length() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn corners_and_straights() {
+        let l = Polyline2::new(vec![Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(2.0, 0.0), Point2(2.0, 2.0)]);
+        let spans = polyline_to_arcs(&l, 1e-3);
+        assert!(chained(&spans));
+        assert_eq!(spans.first(), Some(&ArcSpan2::Line(Segment2::new(Point2(0.0, 0.0), Point2(2.0, 0.0)))));
+        assert_eq!(spans.last(), Some(&ArcSpan2::Line(Segment2::new(Point2(2.0, 0.0), Point2(2.0, 2.0)))));
+        assert!(polyline_to_arcs(&Polyline2::new(vec![Point2(1.0, 1.0)]), 1e-3).is_empty());
+    }
+
+    #[test]
+    fn bezier() {
+        let c = CubicBezier2::new(Point2(0.0, 0.0), Point2(1.0, 2.0), Point2(3.0, -2.0), Point2(4.0, 0.0));
+        let spans = curve_to_arcs(&c, 1e-4);
+        assert!(chained(&spans));
+        assert!(spans[0].start().distance(Point2(0.0, 0.0)) < 1e-12 && spans[spans.len() - 1].end().distance(Point2(4.0, 0.0)) < 1e-12);
+        for k in 0..=200 {
+            let p = c.point_at(k as f64 / 200.0);
+            assert!(spans.iter().any(|s| s.closest_point_on(p).distance(p) <= 1e-4));
+        }
+        for w in spans.windows(2) {
+            assert!((direction(&w[0], true) - direction(&w[1], false)).length() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,434 @@
+//! Axis-aligned bounding boxes
+
+use std::ops::Sub;
+
+use crate::{ Axis2, Axis3, Float, Interval, Point2, Point3, Vector3 };
+use crate::anchor::impl_anchored;
+
+/// Shapes enclosed by a 2d rectangle
+pub trait Bounded2<T: Float> {
+    /// bounds: &Shape -> Option<Rect>
+    /// None for empty shapes
+    fn bounds(&self) -> Option<Rect2<T>>;
+
+    /// bounding_circle: &Shape -> Option<(Point, K)>
+    /// center and radius of the circle through the corners of `bounds`
+    fn bounding_circle(&self) -> Option<(Point2<T>, T)> {
+        let r = self.bounds()?;
+        let half = T::from_f64(0.5);
+        let c = r.0 + r.1.vector_from(r.0) * half;
+        Some((c, r.1.distance(r.0) * half))
+    }
+}
+
+/// Shapes enclosed by a 3d box
+pub trait Bounded3<T: Float> {
+    /// bounds: &Shape -> Option<Aabb>
+    /// None for empty shapes
+    fn bounds(&self) -> Option<Aabb3<T>>;
+
+    /// bounding_sphere: &Shape -> Option<(Point, K)>
+    /// center and radius of the sphere through the corners of `bounds`
+    fn bounding_sphere(&self) -> Option<(Point3<T>, T)> {
+        let b = self.bounds()?;
+        let half = T::from_f64(0.5);
+        let c = b.0 + b.1.vector_from(b.0) * half;
+        Some((c, b.1.distance(b.0) * half))
+    }
+}
+
+/// 2d axis-aligned rectangle: min corner, max corner
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Rect2<T>(Point2<T>, Point2<T>);
+
+impl<T: PartialOrd + Copy> Rect2<T> {
+    /// new: Point × Point -> Rect
+    /// spanned by two opposite corners in any order
+    pub fn new(a: Point2<T>, b: Point2<T>) -> Self {
+        let (x0, x1) = if a.0 <= b.0 { (a.0, b.0) } else { (b.0, a.0) };
+        let (y0, y1) = if a.1 <= b.1 { (a.1, b.1) } else { (b.1, a.1) };
+        Rect2(Point2(x0, y0), Point2(x1, y1))
+    }
+
+    /// from_points: [Point] -> Option<Rect>
+    /// None for no points
+    pub fn from_points<I: IntoIterator<Item = Point2<T>>>(points: I) -> Option<Self> {
+        let mut it = points.into_iter();
+        let p = it.next()?;
+        Some(it.fold(Rect2(p, p), |r, p| r.expand(p)))
+    }
+
+    /// min: &Rect -> Point
+    pub fn min(&self) -> Point2<T> {
+        self.0
+    }
+
+    /// max: &Rect -> Point
+    pub fn max(&self) -> Point2<T> {
+        self.1
+    }
+
+    /// expand: Rect × Point -> Rect
+    /// smallest rectangle containing both
+    pub fn expand(self, p: Point2<T>) -> Self {
+        self.union(Rect2(p, p))
+    }
+
+    /// union: Rect × Rect -> Rect
+    pub fn union(self, r: Rect2<T>) -> Self {
+        let lo = |a: T, b: T| if b < a { b } else { a };
+        let hi = |a: T, b: T| if b > a { b } else { a };
+        Rect2(
+            Point2(lo(self.0.0, r.0.0), lo(self.0.1, r.0.1)),
+            Point2(hi(self.1.0, r.1.0), hi(self.1.1, r.1.1)),
+        )
+    }
+
+    /// overlaps: &Rect × &Rect -> bool
+    /// whether the intervals overlap on both axes, touching included
+    pub fn overlaps(&self, r: &Rect2<T>) -> bool {
+        Axis2::ALL.into_iter().all(|a| self.interval(a).overlaps(r.interval(a)))
+    }
+
+    /// contains: &Rect × Point -> bool
+    /// boundary included
+    pub fn contains(&self, p: Point2<T>) -> bool {
+        self.0.0 <= p.0 && p.0 <= self.1.0 && self.0.1 <= p.1 && p.1 <= self.1.1
+    }
+
+    /// from_intervals: Interval × Interval -> Rect
+    /// the rectangle spanning x over the first and y over the second
+    pub fn from_intervals(x: Interval<T>, y: Interval<T>) -> Self {
+        Rect2(Point2(x.lo(), y.lo()), Point2(x.hi(), y.hi()))
+    }
+
+    /// interval: &Rect × Axis -> Interval
+    /// the extent along one axis, its projection onto it
+    pub fn interval(&self, axis: Axis2) -> Interval<T> {
+        Interval::spanning(self.0[axis], self.1[axis])
+    }
+
+    /// split: &Rect × Axis × K -> (Rect, Rect)
+    /// the parts below and above the line across `axis` at `at`, clamped
+    /// into the rectangle
+    pub fn split(&self, axis: Axis2, at: T) -> (Self, Self) {
+        let at = if at < self.0[axis] { self.0[axis] } else if at > self.1[axis] { self.1[axis] } else { at };
+        let (mut below, mut above) = (self.1, self.0);
+        below[axis] = at;
+        above[axis] = at;
+        (Rect2(self.0, below), Rect2(above, self.1))
+    }
+}
+
+impl<T: PartialOrd + Copy + Sub<Output = T>> Rect2<T> {
+    /// longest_axis: &Rect -> Axis
+    /// the axis of the widest extent, x on ties
+    pub fn longest_axis(&self) -> Axis2 {
+        if self.1.1 - self.0.1 > self.1.0 - self.0.0 { Axis2::Y } else { Axis2::X }
+    }
+}
+
+/// 3d axis-aligned box: min corner, max corner
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Aabb3<T>(Point3<T>, Point3<T>);
+
+impl<T: PartialOrd + Copy> Aabb3<T> {
+    /// new: Point × Point -> Aabb
+    /// spanned by two opposite corners in any order
+    pub fn new(a: Point3<T>, b: Point3<T>) -> Self {
+        let (x0, x1) = if a.0 <= b.0 { (a.0, b.0) } else { (b.0, a.0) };
+        let (y0, y1) = if a.1 <= b.1 { (a.1, b.1) } else { (b.1, a.1) };
+        let (z0, z1) = if a.2 <= b.2 { (a.2, b.2) } else { (b.2, a.2) };
+        Aabb3(Point3(x0, y0, z0), Point3(x1, y1, z1))
+    }
+
+    /// from_points: [Point] -> Option<Aabb>
+    /// None for no points
+    pub fn from_points<I: IntoIterator<Item = Point3<T>>>(points: I) -> Option<Self> {
+        let mut it = points.into_iter();
+        let p = it.next()?;
+        Some(it.fold(Aabb3(p, p), |b, p| b.expand(p)))
+    }
+
+    /// min: &Aabb -> Point
+    pub fn min(&self) -> Point3<T> {
+        self.0
+    }
+
+    /// max: &Aabb -> Point
+    pub fn max(&self) -> Point3<T> {
+        self.1
+    }
+
+    /// expand: Aabb × Point -> Aabb
+    /// smallest box containing both
+    pub fn expand(self, p: Point3<T>) -> Self {
+        self.union(Aabb3(p, p))
+    }
+
+    /// union: Aabb × Aabb -> Aabb
+    pub fn union(self, b: Aabb3<T>) -> Self {
+        let lo = |a: T, b: T| if b < a { b } else { a };
+        let hi = |a: T, b: T| if b > a { b } else { a };
+        Aabb3(
+            Point3(lo(self.0.0, b.0.0), lo(self.0.1, b.0.1), lo(self.0.2, b.0.2)),
+            Point3(hi(self.1.0, b.1.0), hi(self.1.1, b.1.1), hi(self.1.2, b.1.2)),
+        )
+    }
+
+    /// contains: &Aabb × Point -> bool
+    /// boundary included
+    pub fn contains(&self, p: Point3<T>) -> bool {
+        self.0.0 <= p.0 && p.0 <= self.1.0
+            && self.0.1 <= p.1 && p.1 <= self.1.1
+            && self.0.2 <= p.2 && p.2 <= self.1.2
+    }
+
+    /// from_intervals: Interval × Interval × Interval -> Aabb
+    /// the box spanning the intervals along x, y and z
+    pub fn from_intervals(x: Interval<T>, y: Interval<T>, z: Interval<T>) -> Self {
+        Aabb3(Point3(x.lo(), y.lo(), z.lo()), Point3(x.hi(), y.hi(), z.hi()))
+    }
+
+    /// interval: &Aabb × Axis -> Interval
+    /// the extent along one axis, its projection onto it
+    pub fn interval(&self, axis: Axis3) -> Interval<T> {
+        Interval::spanning(self.0[axis], self.1[axis])
+    }
+
+    /// overlaps: &Aabb × &Aabb -> bool
+    /// whether the intervals overlap on every axis, touching included
+    pub fn overlaps(&self, b: &Aabb3<T>) -> bool {
+        Axis3::ALL.into_iter().all(|a| self.interval(a).overlaps(b.interval(a)))
+    }
+
+    /// split: &Aabb × Axis × K -> (Aabb, Aabb)
+    /// the parts below and above the plane across `axis` at `at`, clamped
+    /// into the box
+    pub fn split(&self, axis: Axis3, at: T) -> (Self, Self) {
+        let at = if at < self.0[axis] { self.0[axis] } else if at > self.1[axis] { self.1[axis] } else { at };
+        let (mut below, mut above) = (self.1, self.0);
+        below[axis] = at;
+        above[axis] = at;
+        (Aabb3(self.0, below), Aabb3(above, self.1))
+    }
+}
+
+impl<T: PartialOrd + Copy + Sub<Output = T>> Aabb3<T> {
+    /// longest_axis: &Aabb -> Axis
+    /// the axis of the widest extent, the first on ties
+    pub fn longest_axis(&self) -> Axis3 {
+        let extent = |a: Axis3| self.1[a] - self.0[a];
+        Axis3::ALL.into_iter().fold(Axis3::X, |m, a| if extent(a) > extent(m) { a } else { m })
+    }
+}
+
+impl<T: Float> Bounded2<T> for Point2<T> {
+    fn bounds(&self) -> Option<Rect2<T>> {
+        Some(Rect2(*self, *self))
+    }
+}
+
+impl<T: Float> Bounded2<T> for Rect2<T> {
+    fn bounds(&self) -> Option<Rect2<T>> {
+        Some(*self)
+    }
+}
+
+// turned, a rectangle becomes the bounds of its turned corners
+impl_anchored!(Rect2, |r, m| {
+    let [a, b, c, d] = [r.0, Point2(r.1.0, r.0.1), r.1, Point2(r.0.0, r.1.1)].map(|p| m.point(p));
+    Rect2::new(a, c).expand(b).expand(d)
+});
+
+impl<T: Float> Bounded3<T> for Point3<T> {
+    fn bounds(&self) -> Option<Aabb3<T>> {
+        Some(Aabb3(*self, *self))
+    }
+}
+
+impl<T: Float> Bounded3<T> for Aabb3<T> {
+    fn bounds(&self) -> Option<Aabb3<T>> {
+        Some(*self)
+    }
+}
+
+/// Ritter's sphere: across the farthest pair found from the first point,
+/// grown over every point left outside; within a few percent of minimal
+pub(crate) fn ritter_sphere<T: Float>(points: &[Point3<T>]) -> Option<(Point3<T>, T)> {
+    let first = *points.first()?;
+    let farthest = |from: Point3<T>| points.iter().copied().fold(from, |m, p| if p.distance(from) > m.distance(from) { p } else { m });
+    let x = farthest(first);
+    let y = farthest(x);
+    let half = T::from_f64(0.5);
+    let (mut c, mut r) = (x + y.vector_from(x) * half, x.distance(y) * half);
+    for &p in points {
+        let d = p.distance(c);
+        if d > r {
+            let grown = (r + d) * half;
+            c = c + p.vector_from(c) * ((grown - r) / d);
+            r = grown;
+        }
+    }
+    Some((c, r))
+}
+
+/// Smallest enclosing sphere by Welzl's move-to-front scheme, unrolled into
+/// loops over up to four support points; expected linear time as the points
+/// are visited in a shuffled order
+pub(crate) fn minimal_sphere<T: Float>(points: &[Point3<T>]) -> Option<(Point3<T>, T)> {
+    let mut ps = points.to_vec();
+    let mut seed = 0x2545F4914F6CDD1Du64;
+    for i in (1..ps.len()).rev() {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ps.swap(i, (seed >> 33) as usize % (i + 1));
+    }
+    let extent = ps.iter().fold(T::zero(), |m, p| [p.0, p.1, p.2].into_iter().fold(m, |m, x| if x.abs() > m { x.abs() } else { m }));
+    let slack = extent * T::epsilon() * T::from_f64(64.0);
+    let outside = |s: (Point3<T>, T), p: Point3<T>| p.distance(s.0) > s.1 + slack;
+    let mut s = (*ps.first()?, T::zero());
+    for i in 1..ps.len() {
+        if !outside(s, ps[i]) {
+            continue;
+        }
+        s = (ps[i], T::zero());
+        for j in 0..i {
+            if !outside(s, ps[j]) {
+                continue;
+            }
+            s = sphere_through(&[ps[i], ps[j]]);
+            for k in 0..j {
+                if !outside(s, ps[k]) {
+                    continue;
+                }
+                s = sphere_through(&[ps[i], ps[j], ps[k]]);
+                for l in 0..k {
+                    if outside(s, ps[l]) {
+                        s = sphere_through(&[ps[i], ps[j], ps[k], ps[l]]);
+                    }
+                }
+            }
+        }
+    }
+    Some(s)
+}
+
+/// Smallest sphere with two to four points on its surface; points too flat
+/// to span one fall back to the smallest sphere through fewer of them that
+/// still holds them all
+fn sphere_through<T: Float>(ps: &[Point3<T>]) -> (Point3<T>, T) {
+    let a = ps[0];
+    let two = T::from_f64(2.0);
+    let rel: Vec<Vector3<T>> = ps[1..].iter().map(|p| p.vector_from(a)).collect();
+    let offset = match rel[..] {
+        [b] => Some(b / two),
+        [b, c] => {
+            let n = b.cross(c);
+            let nn = n.dot(n);
+            (nn > T::zero()).then(|| (n.cross(b) * c.dot(c) + c.cross(n) * b.dot(b)) / (two * nn))
+        }
+        [b, c, d] => {
+            let det = b.dot(c.cross(d));
+            (det != T::zero()).then(|| (c.cross(d) * b.dot(b) + d.cross(b) * c.dot(c) + b.cross(c) * d.dot(d)) / (two * det))
+        }
+        _ => None,
+    };
+    if let Some(o) = offset.filter(|o| o.0.is_finite() && o.1.is_finite() && o.2.is_finite()) {
+        return (a + o, o.length());
+    }
+    // degenerate: the best sphere through a subset
+    let n = ps.len();
+    let mut best: Option<(Point3<T>, T)> = None;
+    for skip in 0..n {
+        let rest: Vec<Point3<T>> = (0..n).filter(|&k| k != skip).map(|k| ps[k]).collect();
+        let s = if rest.len() == 1 { (rest[0], T::zero()) } else { sphere_through(&rest) };
+        let holds = ps.iter().all(|p| p.distance(s.0) <= s.1 * (T::one() + T::epsilon() * T::from_f64(64.0)));
+        if holds && best.is_none_or(|b| s.1 < b.1) {
+            best = Some(s);
+        }
+    }
+    best.unwrap_or((a, T::zero()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rect2() {
+        let r = Rect2::new(Point2(3, 1), Point2(0, 2));
+        assert_eq!((r.min(), r.max()), (Point2(0, 1), Point2(3, 2)));
+        assert!(r.contains(Point2(3, 2)) && !r.contains(Point2(4, 2)));
+        assert_eq!(r.expand(Point2(-1, 5)), Rect2(Point2(-1, 1), Point2(3, 5)));
+        assert_eq!(Rect2::from_points(vec![Point2(1, 1), Point2(-1, 2), Point2(0, -3)]), Some(Rect2(Point2(-1, -3), Point2(1, 2))));
+        assert_eq!(Rect2::<i32>::from_points(vec![]), None);
+    }
+
+    #[test]
+    fn aabb3() {
+        let b = Aabb3::new(Point3(3, 1, 0), Point3(0, 2, -1));
+        assert_eq!((b.min(), b.max()), (Point3(0, 1, -1), Point3(3, 2, 0)));
+        assert!(b.contains(Point3(3, 2, 0)) && !b.contains(Point3(3, 2, 1)));
+        assert_eq!(b.expand(Point3(-1, 5, 0)), Aabb3(Point3(-1, 1, -1), Point3(3, 5, 0)));
+        assert_eq!(Aabb3::<i32>::from_points(vec![]), None);
+    }
+
+    #[test]
+    fn axes() {
+        let r = Rect2::new(Point2(0, 0), Point2(4, 6));
+        assert_eq!(r.longest_axis(), Axis2::Y);
+        assert_eq!(r.split(Axis2::X, 1), (Rect2(Point2(0, 0), Point2(1, 6)), Rect2(Point2(1, 0), Point2(4, 6))));
+        let b = Aabb3::new(Point3(0, 0, 0), Point3(3, 3, 2));
+        assert_eq!(b.longest_axis(), Axis3::X);
+        assert_eq!(Aabb3::new(Point3(0, 0, 0), Point3(1, 1, 2)).longest_axis(), Axis3::Z);
+        let (lo, hi) = b.split(Axis3::Z, 5);
+        assert_eq!((lo, hi), (b, Aabb3(Point3(0, 0, 2), Point3(3, 3, 2))));
+    }
+
+    #[test]
+    fn bounding_volumes() {
+        let r = Rect2::new(Point2(0.0, 0.0), Point2(6.0, 8.0));
+        assert_eq!(r.bounding_circle(), Some((Point2(3.0, 4.0), 5.0)));
+        assert_eq!(Point2(1.0, 2.0).bounding_circle(), Some((Point2(1.0, 2.0), 0.0)));
+        let b = Aabb3::new(Point3(0.0, 0.0, 0.0), Point3(2.0, 2.0, 1.0));
+        assert_eq!(Bounded3::bounds(&b), Some(b));
+        assert_eq!(b.bounding_sphere(), Some((Point3(1.0, 1.0, 0.5), 1.5)));
+    }
+
+    #[test]
+    fn enclosing_spheres() {
+        // corners of a regular tetrahedron, a few points within and repeats
+        let mut ps = vec![Point3(1.0, 1.0, 1.0), Point3(1.0, -1.0, -1.0), Point3(-1.0, 1.0, -1.0), Point3(-1.0, -1.0, 1.0)];
+        ps.extend([Point3(0.1, 0.2, 0.3), Point3(-0.5, 0.0, 0.2), Point3(1.0, 1.0, 1.0)]);
+        let (c, r) = minimal_sphere(&ps).unwrap();
+        assert!(c.distance(Point3(0.0, 0.0, 0.0)) < 1e-12 && (r - 3f64.sqrt()).abs() < 1e-12);
+        // collinear points span only their two ends
+        let line: Vec<_> = (0..10).map(|k| Point3(k as f64, 2.0 * k as f64, 0.0)).collect();
+        let (c, r) = minimal_sphere(&line).unwrap();
+        assert!(c.distance(Point3(4.5, 9.0, 0.0)) < 1e-12 && (r - 4.5 * 5f64.sqrt()).abs() < 1e-12);
+        assert_eq!(minimal_sphere(&[Point3(2.0, 0.0, 0.0); 3]), Some((Point3(2.0, 0.0, 0.0), 0.0)));
+        assert_eq!(minimal_sphere::<f64>(&[]), None);
+        assert_eq!(ritter_sphere::<f64>(&[]), None);
+    }
+
+    #[test]
+    fn ritter_against_minimal() {
+        let mut seed = 3u64;
+        let mut random = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+        };
+        for n in [2, 5, 50, 500] {
+            let ps: Vec<_> = (0..n).map(|_| Point3(random() * 3.0, random(), random() * 2.0)).collect();
+            let (c, r) = minimal_sphere(&ps).unwrap();
+            let (rc, rr) = ritter_sphere(&ps).unwrap();
+            assert!(ps.iter().all(|p| p.distance(c) <= r + 1e-12 && p.distance(rc) <= rr + 1e-12));
+            assert!(r <= rr + 1e-12 && rr < r * 1.2);
+            // minimal: no smaller sphere about a nearby center holds them all
+            for step in [Vector3(1e-3, 0.0, 0.0), Vector3(0.0, -1e-3, 0.0), Vector3(0.0, 0.0, 1e-3)] {
+                let reach = ps.iter().map(|p| p.distance(c + step)).fold(0.0, f64::max);
+                assert!(reach >= r - 1e-12);
+            }
+        }
+    }
+}
@@ -0,0 +1,350 @@
+//! Dynamic bounding volume tree for the broad phase
+
+use crate::{ Aabb3, Float, Vector3 };
+
+/// Handle to an entry of an `AabbTree3`; a removed entry's handle may be
+/// handed out again
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Proxy(usize);
+
+/// Boxes tagged with items, kept in a balanced tree under insertion, removal
+/// and movement. Each entry is stored fattened by a margin, so small moves
+/// leave the tree alone
+#[derive(Debug, Clone)]
+pub struct AabbTree3<T, I> {
+    nodes: Vec<Node<T, I>>,
+    root: Option<usize>,
+    /// nodes ready for reuse
+    free: Vec<usize>,
+    margin: T,
+    len: usize,
+}
+
+/// Tree node; leaves hold an item
+#[derive(Debug, Clone)]
+struct Node<T, I> {
+    bounds: Aabb3<T>,
+    parent: Option<usize>,
+    /// None for leaves and free nodes
+    children: Option<[usize; 2]>,
+    /// 0 for leaves
+    height: usize,
+    item: Option<I>,
+}
+
+impl<T: Float, I> AabbTree3<T, I> {
+    /// new: K -> AabbTree
+    /// with entries fattened by `margin` on every side
+    pub fn new(margin: T) -> Self {
+        AabbTree3 { nodes: Vec::new(), root: None, free: Vec::new(), margin, len: 0 }
+    }
+
+    /// len: &AabbTree -> usize
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// is_empty: &AabbTree -> bool
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// height: &AabbTree -> usize
+    /// edges on the longest path from the root down, 0 when empty
+    pub fn height(&self) -> usize {
+        self.root.map_or(0, |r| self.nodes[r].height)
+    }
+
+    /// get: &AabbTree × Proxy -> Option<&Item>
+    pub fn get(&self, proxy: Proxy) -> Option<&I> {
+        self.nodes.get(proxy.0)?.item.as_ref()
+    }
+
+    /// fat_bounds: &AabbTree × Proxy -> Option<Aabb>
+    /// the box the entry is stored under
+    pub fn fat_bounds(&self, proxy: Proxy) -> Option<Aabb3<T>> {
+        self.get(proxy)?;
+        Some(self.nodes[proxy.0].bounds)
+    }
+
+    /// insert: &mut AabbTree × Aabb × Item -> Proxy
+    pub fn insert(&mut self, bounds: Aabb3<T>, item: I) -> Proxy {
+        let leaf = self.allocate(Node { bounds: self.fatten(bounds), parent: None, children: None, height: 0, item: Some(item) });
+        self.insert_leaf(leaf);
+        self.len += 1;
+        Proxy(leaf)
+    }
+
+    /// remove: &mut AabbTree × Proxy -> Option<Item>
+    /// None for a handle not in the tree
+    pub fn remove(&mut self, proxy: Proxy) -> Option<I> {
+        let item = self.nodes.get_mut(proxy.0)?.item.take()?;
+        self.remove_leaf(proxy.0);
+        self.free.push(proxy.0);
+        self.len -= 1;
+        Some(item)
+    }
+
+    /// update: &mut AabbTree × Proxy × Aabb -> bool
+    /// moves an entry to new bounds; true if it left its fat box and was
+    /// reinserted, false if it stayed or the handle is not in the tree
+    pub fn update(&mut self, proxy: Proxy, bounds: Aabb3<T>) -> bool {
+        if self.get(proxy).is_none() || encloses(&self.nodes[proxy.0].bounds, &bounds) {
+            return false;
+        }
+        self.remove_leaf(proxy.0);
+        self.nodes[proxy.0].bounds = self.fatten(bounds);
+        self.insert_leaf(proxy.0);
+        true
+    }
+
+    /// query: &AabbTree × &Aabb -> [Proxy]
+    /// entries whose fat boxes meet the box
+    pub fn query(&self, bounds: &Aabb3<T>) -> Vec<Proxy> {
+        let mut found = Vec::new();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(k) = stack.pop() {
+            let node = &self.nodes[k];
+            if !node.bounds.overlaps(bounds) {
+                continue;
+            }
+            match node.children {
+                Some(c) => stack.extend(c),
+                None => found.push(Proxy(k)),
+            }
+        }
+        found
+    }
+
+    /// pairs: &AabbTree -> [(Proxy, Proxy)]
+    /// each pair of entries whose fat boxes meet, once, lesser handle first
+    pub fn pairs(&self) -> Vec<(Proxy, Proxy)> {
+        let leaves = (0..self.nodes.len()).filter(|&k| self.nodes[k].item.is_some());
+        leaves
+            .flat_map(|k| self.query(&self.nodes[k].bounds).into_iter().filter(move |p| p.0 > k).map(move |p| (Proxy(k), p)))
+            .collect()
+    }
+
+    fn fatten(&self, b: Aabb3<T>) -> Aabb3<T> {
+        let m = Vector3(self.margin, self.margin, self.margin);
+        Aabb3::new(b.min() - m, b.max() + m)
+    }
+
+    fn allocate(&mut self, node: Node<T, I>) -> usize {
+        match self.free.pop() {
+            Some(k) => {
+                self.nodes[k] = node;
+                k
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// Replaces the child `old` of `parent`, or the root without a parent
+    fn relink(&mut self, parent: Option<usize>, old: usize, new: usize) {
+        match parent {
+            Some(p) => {
+                let c = self.nodes[p].children.as_mut().expect("parent has children");
+                let side = if c[0] == old { 0 } else { 1 };
+                c[side] = new;
+            }
+            None => self.root = Some(new),
+        }
+        self.nodes[new].parent = parent;
+    }
+
+    /// Hangs a leaf next to the sibling that grows the summed surface area
+    /// of the tree the least, then rebalances up to the root
+    fn insert_leaf(&mut self, leaf: usize) {
+        let Some(mut k) = self.root else {
+            self.root = Some(leaf);
+            self.nodes[leaf].parent = None;
+            return;
+        };
+        let b = self.nodes[leaf].bounds;
+        while let Some([c0, c1]) = self.nodes[k].children {
+            let here = area(&self.nodes[k].bounds);
+            let combined = area(&self.nodes[k].bounds.union(b));
+            let two = T::from_f64(2.0);
+            // a new parent here, or the growth pushed down each side
+            let cost = two * combined;
+            let inherited = two * (combined - here);
+            let descend = |c: usize| {
+                let n = &self.nodes[c];
+                let grown = area(&n.bounds.union(b));
+                if n.children.is_none() { grown + inherited } else { grown - area(&n.bounds) + inherited }
+            };
+            let (cost0, cost1) = (descend(c0), descend(c1));
+            if cost < cost0 && cost < cost1 {
+                break;
+            }
+            k = if cost0 < cost1 { c0 } else { c1 };
+        }
+        let sibling = k;
+        let old = self.nodes[sibling].parent;
+        let bounds = self.nodes[sibling].bounds.union(b);
+        let height = self.nodes[sibling].height + 1;
+        let parent = self.allocate(Node { bounds, parent: None, children: Some([sibling, leaf]), height, item: None });
+        self.relink(old, sibling, parent);
+        self.nodes[sibling].parent = Some(parent);
+        self.nodes[leaf].parent = Some(parent);
+        self.refit(Some(parent));
+    }
+
+    /// Takes a leaf out, its sibling taking its parent's place
+    fn remove_leaf(&mut self, leaf: usize) {
+        let Some(parent) = self.nodes[leaf].parent else {
+            self.root = None;
+            return;
+        };
+        let [c0, c1] = self.nodes[parent].children.expect("parent has children");
+        let sibling = if c0 == leaf { c1 } else { c0 };
+        let grand = self.nodes[parent].parent;
+        self.relink(grand, parent, sibling);
+        self.nodes[parent].children = None;
+        self.free.push(parent);
+        self.nodes[leaf].parent = None;
+        self.refit(grand);
+    }
+
+    /// Rebalances, rebounds and reheights from a node up to the root
+    fn refit(&mut self, mut at: Option<usize>) {
+        while let Some(k) = at {
+            let k = self.balance(k);
+            let [c0, c1] = self.nodes[k].children.expect("inner node");
+            self.nodes[k].height = 1 + self.nodes[c0].height.max(self.nodes[c1].height);
+            self.nodes[k].bounds = self.nodes[c0].bounds.union(self.nodes[c1].bounds);
+            at = self.nodes[k].parent;
+        }
+    }
+
+    /// Rotates the taller child of `a` up when the heights of its children
+    /// differ by more than one, returning the node now in its place
+    fn balance(&mut self, a: usize) -> usize {
+        let Some([b, c]) = self.nodes[a].children else { return a };
+        let (hb, hc) = (self.nodes[b].height, self.nodes[c].height);
+        let (up, keep, side) = if hc > hb + 1 {
+            (c, b, 1)
+        } else if hb > hc + 1 {
+            (b, c, 0)
+        } else {
+            return a;
+        };
+        let [f, g] = self.nodes[up].children.expect("taller child is inner");
+        // the taller grandchild stays under `up`, the other moves to `a`
+        let (stay, moved) = if self.nodes[f].height > self.nodes[g].height { (f, g) } else { (g, f) };
+        let parent = self.nodes[a].parent;
+        self.relink(parent, a, up);
+        self.nodes[up].children = Some([a, stay]);
+        self.nodes[a].parent = Some(up);
+        let mut children = [keep, keep];
+        children[side] = moved;
+        self.nodes[a].children = Some(children);
+        self.nodes[moved].parent = Some(a);
+        self.nodes[a].bounds = self.nodes[keep].bounds.union(self.nodes[moved].bounds);
+        self.nodes[a].height = 1 + self.nodes[keep].height.max(self.nodes[moved].height);
+        self.nodes[up].bounds = self.nodes[a].bounds.union(self.nodes[stay].bounds);
+        self.nodes[up].height = 1 + self.nodes[a].height.max(self.nodes[stay].height);
+        up
+    }
+}
+
+/// Half the surface area of a box
+fn area<T: Float>(b: &Aabb3<T>) -> T {
+    let e = b.max().vector_from(b.min());
+    e.0 * e.1 + e.1 * e.2 + e.2 * e.0
+}
+
+/// Whether the box a holds the box b
+fn encloses<T: Float>(a: &Aabb3<T>, b: &Aabb3<T>) -> bool {
+    a.contains(b.min()) && a.contains(b.max())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point3;
+
+    fn cube(c: Point3<f64>, r: f64) -> Aabb3<f64> {
+        Aabb3::new(c - Vector3(r, r, r), c + Vector3(r, r, r))
+    }
+
+    #[test]
+    fn insert_update_remove() {
+        let mut tree = AabbTree3::new(0.1);
+        let a = tree.insert(cube(Point3(0.0, 0.0, 0.0), 1.0), "a");
+        let b = tree.insert(cube(Point3(5.0, 0.0, 0.0), 1.0), "b");
+        assert_eq!((tree.len(), tree.get(b)), (2, Some(&"b")));
+        assert_eq!(tree.fat_bounds(a), Some(cube(Point3(0.0, 0.0, 0.0), 1.1)));
+        assert!(tree.pairs().is_empty());
+        // within the margin: left alone
+        assert!(!tree.update(b, cube(Point3(5.05, 0.0, 0.0), 1.0)));
+        assert!(tree.update(b, cube(Point3(1.5, 0.0, 0.0), 1.0)));
+        assert_eq!(tree.pairs(), vec![(a, b)]);
+        assert_eq!(tree.query(&cube(Point3(2.4, 0.0, 0.0), 0.1)), vec![b]);
+        assert_eq!(tree.remove(a), Some("a"));
+        assert_eq!(tree.remove(a), None);
+        assert!(!tree.update(a, cube(Point3(0.0, 0.0, 0.0), 1.0)));
+        assert_eq!(tree.remove(b), Some("b"));
+        assert!(tree.is_empty() && tree.query(&cube(Point3(0.0, 0.0, 0.0), 10.0)).is_empty());
+    }
+
+    #[test]
+    fn matches_brute_force_while_moving() {
+        let mut seed = 11u64;
+        let mut random = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64 * 20.0 - 10.0
+        };
+        let mut tree = AabbTree3::new(0.2);
+        let mut live: Vec<(Proxy, Point3<f64>)> = Vec::new();
+        for k in 0..400 {
+            let c = Point3(random(), random(), random());
+            live.push((tree.insert(cube(c, 0.5), k), c));
+        }
+        // sorted insertion is the worst case for an unbalanced tree
+        for k in 0..200 {
+            let c = Point3(k as f64 * 0.1, 0.0, 0.0);
+            live.push((tree.insert(cube(c, 0.5), 400 + k), c));
+        }
+        for round in 0..5 {
+            for (i, (p, c)) in live.iter_mut().enumerate() {
+                if (i + round) % 3 == 0 {
+                    *c += Vector3(random(), random(), random()) * 0.05;
+                    tree.update(*p, cube(*c, 0.5));
+                }
+            }
+            for _ in 0..20 {
+                let (p, _) = live.swap_remove(((random() + 10.0) * 10.0) as usize % live.len());
+                assert!(tree.remove(p).is_some());
+            }
+            let n = tree.len() as f64;
+            assert!((tree.height() as f64) <= 2.0 * n.log2() + 1.0);
+            let probe = cube(Point3(random(), random(), random()), 2.0);
+            let mut found = tree.query(&probe);
+            found.sort();
+            let mut brute: Vec<Proxy> = live.iter().map(|e| e.0).filter(|&p| tree.fat_bounds(p).unwrap().overlaps(&probe)).collect();
+            brute.sort();
+            assert_eq!(found, brute);
+            for (p, c) in &live {
+                assert!(encloses(&tree.fat_bounds(*p).unwrap(), &cube(*c, 0.5)));
+            }
+        }
+        let mut pairs = tree.pairs();
+        pairs.sort();
+        let boxes: Vec<(Proxy, Aabb3<f64>)> = live.iter().map(|e| (e.0, tree.fat_bounds(e.0).unwrap())).collect();
+        let mut brute = Vec::new();
+        for (i, a) in boxes.iter().enumerate() {
+            for b in &boxes[i + 1..] {
+                if a.1.overlaps(&b.1) {
+                    brute.push((a.0.min(b.0), a.0.max(b.0)));
+                }
+            }
+        }
+        brute.sort();
+        assert_eq!(pairs, brute);
+    }
+}
@@ -0,0 +1,179 @@
+//! Budgets for long-running operations
+//!
+//! The `_within` variants of expensive operations take a `Budget` and
+//! check it once per unit of work, giving up with None as soon as it runs
+//! out: a step allowance, a deadline, or a `CancelToken` another thread
+//! has cancelled. Interactive callers can so abort work, or cap each
+//! frame's share and retry with a larger budget.
+
+use std::sync::atomic::{ AtomicBool, Ordering };
+use std::sync::Arc;
+use std::time::{ Duration, Instant };
+
+/// Flag to cancel an operation from elsewhere; clones share it
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// new: () -> CancelToken
+    pub fn new() -> Self {
+        CancelToken::default()
+    }
+
+    /// cancel: &CancelToken -> ()
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// is_cancelled: &CancelToken -> bool
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Allowance of an operation; without limits it never runs out
+#[derive(Debug, Clone, Default)]
+pub struct Budget {
+    steps: Option<u64>,
+    deadline: Option<Instant>,
+    token: Option<CancelToken>,
+    spent: u64,
+    exhausted: bool,
+}
+
+impl Budget {
+    /// unlimited: () -> Budget
+    pub fn unlimited() -> Self {
+        Budget::default()
+    }
+
+    /// with_steps: Budget × u64 -> Budget
+    /// at most that many units of work
+    pub fn with_steps(mut self, steps: u64) -> Self {
+        self.steps = Some(steps);
+        self
+    }
+
+    /// with_deadline: Budget × Instant -> Budget
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// with_timeout: Budget × Duration -> Budget
+    /// deadline that long from now
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    /// with_token: Budget × CancelToken -> Budget
+    pub fn with_token(mut self, token: CancelToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// spent: &Budget -> u64
+    /// units of work taken so far
+    pub fn spent(&self) -> u64 {
+        self.spent
+    }
+
+    /// is_exhausted: &Budget -> bool
+    /// whether an operation gave up on it; it stays so
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// tick: &mut Budget -> bool
+    /// takes a unit of work, false if there is none left
+    pub fn tick(&mut self) -> bool {
+        self.exhausted = self.exhausted
+            || self.steps.is_some_and(|s| self.spent >= s)
+            || self.deadline.is_some_and(|d| Instant::now() >= d)
+            || self.token.as_ref().is_some_and(CancelToken::is_cancelled);
+        if !self.exhausted {
+            self.spent += 1;
+        }
+        !self.exhausted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ Point2, Point3, Polygon2, TriMesh };
+
+    #[test]
+    fn limits() {
+        let mut b = Budget::unlimited().with_steps(3);
+        assert!(b.tick() && b.tick() && b.tick());
+        assert!(!b.tick() && b.is_exhausted() && b.spent() == 3);
+        let token = CancelToken::new();
+        let mut b = Budget::unlimited().with_token(token.clone()).with_timeout(Duration::from_secs(3600));
+        assert!(b.tick());
+        std::thread::spawn(move || token.cancel()).join().unwrap();
+        assert!(!b.tick());
+        assert!(!Budget::unlimited().with_deadline(Instant::now()).tick());
+    }
+
+    #[test]
+    fn operations_give_up() {
+        let points: Vec<Point3<f64>> = (0..40).map(|k| Point3((k * 7 % 11) as f64, (k * 5 % 13) as f64, (k * k % 17) as f64)).collect();
+        assert!(crate::tetra::delaunay_within(&points, &mut Budget::unlimited().with_steps(10)).is_none());
+        // a unit per point and per circumsphere test
+        let mut enough = Budget::unlimited();
+        let tets = crate::tetra::delaunay_within(&points, &mut enough).unwrap();
+        assert_eq!(tets.len(), crate::tetra::delaunay(&points).len());
+        assert!(enough.spent() > 40);
+        assert!(crate::tetra::delaunay_within(&points, &mut Budget::unlimited().with_steps(enough.spent())).is_some());
+        assert!(crate::tetra::delaunay_within(&points, &mut Budget::unlimited().with_steps(enough.spent() - 1)).is_none());
+        let flat: Vec<Point2<f64>> = points.iter().map(|p| Point2(p.0, p.1 + 0.01 * p.2)).collect();
+        assert!(crate::delaunay::triangulate_within(&flat, &mut Budget::unlimited().with_steps(40)).is_none());
+        assert_eq!(crate::delaunay::triangulate_within(&flat, &mut Budget::unlimited()), Some(crate::delaunay::triangulate(&flat)));
+
+        let squares: Vec<Polygon2<f64>> = (0..8)
+            .map(|k| {
+                let x = k as f64;
+                Polygon2::new(vec![Point2(x, 0.0), Point2(x + 1.5, 0.0), Point2(x + 1.5, 1.0), Point2(x, 1.0)])
+            })
+            .collect();
+        let cancelled = CancelToken::new();
+        cancelled.cancel();
+        assert!(crate::polygon::union_all_within(&squares, &mut Budget::unlimited().with_token(cancelled)).is_none());
+        let mut spent = Budget::unlimited();
+        let whole = crate::polygon::union_all_within(&squares, &mut spent).unwrap();
+        assert_eq!(whole.len(), 1);
+        assert!((whole.area() - 8.5).abs() < 1e-9);
+        // 7 merges, each spending more inside: the last is cut short too
+        assert!(spent.spent() > 7);
+        assert!(crate::polygon::union_all_within(&squares, &mut Budget::unlimited().with_steps(spent.spent() - 1)).is_none());
+        let bow = Polygon2::new(vec![Point2(0.0, 0.0), Point2(2.0, 2.0), Point2(2.0, 0.0), Point2(0.0, 2.0)]);
+        assert!(bow.make_valid_within(&mut Budget::unlimited().with_steps(1)).is_none());
+        assert!(bow.split_within(&crate::Line2::new(Point2(1.0, 0.0), crate::Vector2(0.0, 1.0)), &mut Budget::unlimited().with_steps(1)).is_none());
+        let mut budget = Budget::unlimited();
+        assert_eq!(bow.fill_within(crate::FillRule::NonZero, &mut budget), Some(bow.fill(crate::FillRule::NonZero)));
+        assert!(budget.spent() > 1);
+        let old = crate::MultiPolygon2::new(vec![squares[0].clone()]);
+        let new = crate::MultiPolygon2::new(vec![squares[1].clone()]);
+        assert!(crate::change::diff_regions_within(&old, &new, &mut Budget::unlimited().with_steps(1)).is_none());
+        let path = |p: &Polygon2<f64>| {
+            let mut commands: Vec<_> = p.vertices().iter().map(|&v| crate::PathCommand::LineTo(v)).collect();
+            commands[0] = crate::PathCommand::MoveTo(p.vertices()[0]);
+            commands.push(crate::PathCommand::Close);
+            crate::Path2::new(commands)
+        };
+        let (a, b) = (path(&squares[0]), path(&squares[1]));
+        assert!(a.boolean_within(&b, crate::BooleanOp::Union, crate::FillRule::NonZero, 1e-3, &mut Budget::unlimited().with_steps(1)).is_none());
+        let edges: Vec<_> = squares.iter().flat_map(|p| p.edges()).collect();
+        assert!(crate::Arrangement2::new_within(&edges, 1e-9, &mut Budget::unlimited().with_steps(4)).is_none());
+
+        // a strip of 16 triangles halved takes several collapses
+        let vertices: Vec<Point3<f64>> = (0..18).map(|k| Point3((k / 2) as f64, (k % 2) as f64, 0.0)).collect();
+        let triangles: Vec<[usize; 3]> = (0..8).flat_map(|i| [[2 * i, 2 * i + 2, 2 * i + 1], [2 * i + 1, 2 * i + 2, 2 * i + 3]]).collect();
+        let strip = TriMesh::new(vertices, triangles);
+        assert!(strip.generate_lods_within(&[0.5], &mut Budget::unlimited().with_steps(2)).is_none());
+        let mut budget = Budget::unlimited();
+        assert_eq!(strip.generate_lods_within(&[0.5], &mut budget), Some(strip.generate_lods(&[0.5])));
+        assert!(budget.spent() > 2);
+    }
+}
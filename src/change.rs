@@ -0,0 +1,105 @@
+//! Change detection between two versions of an areal layer
+
+use crate::polygon::overlay_within;
+use crate::{ Budget, Float, MultiPolygon2, Segment2 };
+
+/// Regions gained, lost and kept from one version of a layer to the next
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionDiff<T> {
+    /// in the new version only
+    pub added: MultiPolygon2<T>,
+    /// in the old version only
+    pub removed: MultiPolygon2<T>,
+    /// in both
+    pub unchanged: MultiPolygon2<T>,
+    /// area of `added`
+    pub added_area: T,
+    /// area of `removed`
+    pub removed_area: T,
+    /// area of `unchanged`
+    pub unchanged_area: T,
+}
+
+impl<T: Float> RegionDiff<T> {
+    /// changed_area: &RegionDiff -> K
+    /// area of the symmetric difference
+    pub fn changed_area(&self) -> T {
+        self.added_area + self.removed_area
+    }
+
+    /// is_unchanged: &RegionDiff -> bool
+    pub fn is_unchanged(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// diff_regions: &MultiPolygon × &MultiPolygon -> RegionDiff
+/// the new version against the old, each a valid multipolygon; the three
+/// parts are valid and tile the union of the two
+pub fn diff_regions<T: Float>(old: &MultiPolygon2<T>, new: &MultiPolygon2<T>) -> RegionDiff<T> {
+    diff_regions_within(old, new, &mut Budget::unlimited()).expect("unlimited budget")
+}
+
+/// diff_regions_within: &MultiPolygon × &MultiPolygon × &mut Budget -> Option<RegionDiff>
+/// `diff_regions`, a unit of the budget per stop of its sweep and per
+/// face; None if it runs out
+pub fn diff_regions_within<T: Float>(old: &MultiPolygon2<T>, new: &MultiPolygon2<T>, budget: &mut Budget) -> Option<RegionDiff<T>> {
+    // both versions in one arrangement, each face wound once around by
+    // whichever covers it
+    let old: Vec<Segment2<T>> = old.oriented().iter().flat_map(|p| p.edges()).collect();
+    let new: Vec<Segment2<T>> = new.oriented().iter().flat_map(|p| p.edges()).collect();
+    let [added, removed, unchanged] = overlay_within(&old, &new, [
+        &|o, n| o <= 0 && n > 0,
+        &|o, n| o > 0 && n <= 0,
+        &|o, n| o > 0 && n > 0,
+    ], budget)?;
+    Some(RegionDiff {
+        added_area: added.area(),
+        removed_area: removed.area(),
+        unchanged_area: unchanged.area(),
+        added,
+        removed,
+        unchanged,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ Point2, Polygon2 };
+
+    fn square(x: f64, y: f64, s: f64) -> Polygon2<f64> {
+        Polygon2::new(vec![Point2(x, y), Point2(x + s, y), Point2(x + s, y + s), Point2(x, y + s)])
+    }
+
+    #[test]
+    fn shifted_footprint() {
+        let old = MultiPolygon2::new(vec![square(0.0, 0.0, 4.0), square(10.0, 0.0, 1.0)]);
+        let new = MultiPolygon2::new(vec![square(2.0, 0.0, 4.0), square(20.0, 0.0, 2.0)]);
+        let diff = diff_regions(&old, &new);
+        assert_eq!(diff.unchanged_area, 8.0);
+        assert_eq!(diff.removed_area, 9.0);
+        assert_eq!(diff.added_area, 12.0);
+        assert_eq!(diff.changed_area(), 21.0);
+        assert_eq!((diff.added.len(), diff.removed.len(), diff.unchanged.len()), (2, 2, 1));
+        assert!(diff.added.iter().chain(&diff.removed).chain(&diff.unchanged).all(|p| p.is_valid()));
+        let back = diff_regions(&new, &old);
+        assert_eq!((back.added_area, back.removed_area), (9.0, 12.0));
+    }
+
+    #[test]
+    fn holes_and_identity() {
+        // a courtyard opened in a block
+        let block = MultiPolygon2::new(vec![square(0.0, 0.0, 6.0)]);
+        let courtyard = MultiPolygon2::new(vec![Polygon2::with_holes(
+            square(0.0, 0.0, 6.0).vertices().to_vec(),
+            vec![square(2.0, 2.0, 2.0).vertices().iter().rev().copied().collect()],
+        )]);
+        let diff = diff_regions(&block, &courtyard);
+        assert_eq!((diff.added_area, diff.removed_area, diff.unchanged_area), (0.0, 4.0, 32.0));
+        assert_eq!(diff.unchanged.polygons()[0].holes().len(), 1);
+        let same = diff_regions(&courtyard, &courtyard);
+        assert!(same.is_unchanged() && same.unchanged_area == 32.0);
+        assert!(diff_regions(&MultiPolygon2::<f64>::new(Vec::new()), &block).removed.is_empty());
+    }
+}
@@ -0,0 +1,482 @@
+//! Circles: intersections, tangent lines, tangent circles and packings
+
+use crate::anchor::impl_anchored;
+use crate::path::quadratic_roots;
+use crate::{ Bounded2, Closest2, FillRule, Float, Line2, Point2, Polygon2, Rect2, Vector2 };
+
+/// Object an Apollonius circle is to touch
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Contact<T> {
+    /// pass through the point
+    Point(Point2<T>),
+    /// touch the line on either side
+    Line(Line2<T>),
+    /// touch the circle from outside or inside
+    Circle(Circle2<T>),
+}
+
+/// 2d Circle: center, radius
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Circle2<T>(Point2<T>, T);
+
+impl<T> Circle2<T> {
+    /// new: Point × K -> Circle
+    pub fn new(center: Point2<T>, radius: T) -> Self {
+        Circle2(center, radius)
+    }
+}
+
+impl<T: Copy> Circle2<T> {
+    /// center: &Circle -> Point
+    pub fn center(&self) -> Point2<T> {
+        self.0
+    }
+
+    /// radius: &Circle -> K
+    pub fn radius(&self) -> T {
+        self.1
+    }
+}
+
+impl<T: Float> Circle2<T> {
+    /// intersect_line: &Circle × &Line -> [Point]
+    /// none, the touching point, or both crossings in the line's direction
+    pub fn intersect_line(&self, l: &Line2<T>) -> Vec<Point2<T>> {
+        let f = l.project(self.0);
+        let h2 = self.1 * self.1 - f.vector_from(self.0).dot(f.vector_from(self.0));
+        let u = l.direction() / l.direction().length();
+        chord(f, u, h2)
+    }
+
+    /// intersect_circle: &Circle × &Circle -> [Point]
+    /// none for disjoint, nested or coincident circles; the two points
+    /// are ordered left then right of the line from this center to the other
+    pub fn intersect_circle(&self, c: &Circle2<T>) -> Vec<Point2<T>> {
+        let v = c.0.vector_from(self.0);
+        let d = v.length();
+        if d == T::zero() {
+            return Vec::new();
+        }
+        let u = v / d;
+        let a = (d * d + self.1 * self.1 - c.1 * c.1) / (d * T::from_f64(2.0));
+        let mut points = chord(self.0 + u * a, perp(u), self.1 * self.1 - a * a);
+        points.reverse();
+        points
+    }
+
+    /// tangents_from: &Circle × Point -> [Line]
+    /// lines through an outside point touching the circle, each
+    /// directed from the point to its touching point
+    pub fn tangents_from(&self, p: Point2<T>) -> Vec<Line2<T>> {
+        let v = p.vector_from(self.0);
+        let d = v.length();
+        if d < self.1 || d == T::zero() {
+            return Vec::new();
+        }
+        let u = v / d;
+        let foot = self.0 + u * (self.1 * self.1 / d);
+        let h2 = self.1 * self.1 - self.1 * self.1 * self.1 * self.1 / (d * d);
+        chord(foot, perp(u), h2).into_iter()
+            .map(|t| if t == p { Line2::new(p, perp(u)) } else { Line2::through(p, t) })
+            .collect()
+    }
+
+    /// external_tangents: &Circle × &Circle -> [Line]
+    /// common tangents with both circles on the same side,
+    /// directed from this circle's touching point towards the other's
+    pub fn external_tangents(&self, c: &Circle2<T>) -> Vec<Line2<T>> {
+        self.common_tangents(c, c.1)
+    }
+
+    /// internal_tangents: &Circle × &Circle -> [Line]
+    /// common tangents passing between the circles,
+    /// directed from this circle's touching point towards the other's
+    pub fn internal_tangents(&self, c: &Circle2<T>) -> Vec<Line2<T>> {
+        self.common_tangents(c, -c.1)
+    }
+
+    /// Tangents whose normal n satisfies n · (c₂ - c₁) = r₁ - r₂ (r₂ signed)
+    fn common_tangents(&self, c: &Circle2<T>, r2: T) -> Vec<Line2<T>> {
+        let v = c.0.vector_from(self.0);
+        let d = v.length();
+        if d == T::zero() {
+            return Vec::new();
+        }
+        let u = v / d;
+        let k = (self.1 - r2) / d;
+        let normals = chord(Point2(k, T::zero()), Vector2(T::zero(), T::one()), T::one() - k * k);
+        normals.into_iter().map(|m| {
+            let n = u * m.0 + perp(u) * m.1;
+            // t₂ - t₁ = d (u - k n), perpendicular to n
+            let along = u - n * k;
+            let along = if along.length() == T::zero() { perp(n) } else { along };
+            Line2::new(self.0 + n * self.1, along)
+        }).collect()
+    }
+
+    /// tangent_to_lines: &Line × &Line × K -> [Circle]
+    /// circles of the radius touching both lines, up to four
+    pub fn tangent_to_lines(a: &Line2<T>, b: &Line2<T>, radius: T) -> Vec<Circle2<T>> {
+        let mut out = Vec::new();
+        for sa in [radius, -radius] {
+            for sb in [radius, -radius] {
+                if let Some(p) = a.offset(sa).intersect(&b.offset(sb)) {
+                    out.push(Circle2(p, radius));
+                }
+            }
+        }
+        out
+    }
+
+    /// tangent_to_line_and_circle: &Line × &Circle × K -> [Circle]
+    /// circles of the radius touching the line and the circle, up to eight
+    pub fn tangent_to_line_and_circle(l: &Line2<T>, c: &Circle2<T>, radius: T) -> Vec<Circle2<T>> {
+        let mut out = Vec::new();
+        for s in [radius, -radius] {
+            let l = l.offset(s);
+            for r in loci(c.1, radius) {
+                out.extend(Circle2(c.0, r).intersect_line(&l).into_iter().map(|p| Circle2(p, radius)));
+            }
+        }
+        out
+    }
+
+    /// tangent_to_circles: &Circle × &Circle × K -> [Circle]
+    /// circles of the radius touching both circles, up to eight
+    pub fn tangent_to_circles(a: &Circle2<T>, b: &Circle2<T>, radius: T) -> Vec<Circle2<T>> {
+        let mut out = Vec::new();
+        for ra in loci(a.1, radius) {
+            for rb in loci(b.1, radius) {
+                out.extend(Circle2(a.0, ra).intersect_circle(&Circle2(b.0, rb)).into_iter().map(|p| Circle2(p, radius)));
+            }
+        }
+        out
+    }
+}
+
+impl<T: Float> Circle2<T> {
+    /// apollonius: [Contact; 3] -> [Circle]
+    /// circles touching all three objects, up to eight
+    pub fn apollonius(contacts: [Contact<T>; 3]) -> Vec<Circle2<T>> {
+        let (zero, one, two) = (T::zero(), T::one(), T::from_f64(2.0));
+        // each contact, for a choice of side, is a(x² + y² - r²) + l · (x, y, r) + f = 0
+        let sides = |c: &Contact<T>| -> Vec<(bool, [T; 3], T)> {
+            match *c {
+                Contact::Point(p) => vec![(true, [-two * p.0, -two * p.1, zero], p.0 * p.0 + p.1 * p.1)],
+                Contact::Line(l) => {
+                    let d = l.direction();
+                    let n = perp(d) / d.length();
+                    let f = -(n.0 * l.point().0 + n.1 * l.point().1);
+                    [one, -one].into_iter().map(|s| (false, [n.0, n.1, -s], f)).collect()
+                }
+                Contact::Circle(Circle2(c, r)) => [one, -one]
+                    .into_iter()
+                    .map(|s| (true, [-two * c.0, -two * c.1, -two * s * r], c.0 * c.0 + c.1 * c.1 - r * r))
+                    .collect()
+            }
+        };
+        let (a, b, c) = (sides(&contacts[0]), sides(&contacts[1]), sides(&contacts[2]));
+        let mut out: Vec<Circle2<T>> = Vec::new();
+        for &p in &a {
+            for &q in &b {
+                for &r in &c {
+                    for z in apollonius_solve([p, q, r]) {
+                        let circle = Circle2(Point2(z[0], z[1]), z[2]);
+                        let scale = z.iter().fold(one, |m, v| if v.abs() > m { v.abs() } else { m });
+                        let tolerance = scale * T::epsilon() * T::from_f64(1024.0);
+                        let seen = out.iter().any(|o| o.0.distance(circle.0) <= tolerance && (o.1 - circle.1).abs() <= tolerance);
+                        if z[2] > zero && !seen {
+                            out.push(circle);
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// pack_in: &Polygon × K × usize -> [Circle]
+    /// greedy packing: up to `count` times, the largest circle fitting in the
+    /// polygon (holes respected) clear of those placed so far, while its
+    /// radius is at least `min_radius`
+    pub fn pack_in(polygon: &Polygon2<T>, min_radius: T, count: usize) -> Vec<Circle2<T>> {
+        let mut placed: Vec<Circle2<T>> = Vec::new();
+        let Some(bounds) = polygon.bounds() else {
+            return placed;
+        };
+        let clearance = |p: Point2<T>, placed: &[Circle2<T>]| {
+            if !polygon.contains(p, FillRule::NonZero) {
+                return -T::infinity();
+            }
+            placed.iter().fold(polygon.closest_point_on(p).distance(p), |m, c| {
+                let d = c.0.distance(p) - c.1;
+                if d < m { d } else { m }
+            })
+        };
+        let (lo, hi) = (bounds.min(), bounds.max());
+        let step = min_radius;
+        let (nx, ny) = (((hi.0 - lo.0) / step).floor(), ((hi.1 - lo.1) / step).floor());
+        let mut candidates = Vec::new();
+        let mut y = T::zero();
+        while y <= ny {
+            let mut x = T::zero();
+            while x <= nx {
+                let p = Point2(lo.0 + (x + T::from_f64(0.5)) * step, lo.1 + (y + T::from_f64(0.5)) * step);
+                if polygon.contains(p, FillRule::NonZero) {
+                    candidates.push((p, clearance(p, &[])));
+                }
+                x = x + T::one();
+            }
+            y = y + T::one();
+        }
+        while placed.len() < count {
+            let Some(&(start, _)) = candidates.iter().reduce(|a, b| if b.1 > a.1 { b } else { a }) else {
+                break;
+            };
+            // pattern search from the best grid point, diagonals included to climb
+            // the ridges where two constraints meet
+            let (mut p, mut best, mut h) = (start, clearance(start, &placed), step * T::from_f64(0.5));
+            for _ in 0..40 {
+                let (o, z) = (h, T::zero());
+                let moves = [Vector2(o, z), Vector2(-o, z), Vector2(z, o), Vector2(z, -o), Vector2(o, o), Vector2(-o, o), Vector2(o, -o), Vector2(-o, -o)];
+                match moves.iter().map(|&m| (p + m, clearance(p + m, &placed))).reduce(|a, b| if b.1 > a.1 { b } else { a }) {
+                    Some((q, c)) if c > best => (p, best) = (q, c),
+                    _ => h = h * T::from_f64(0.5),
+                }
+            }
+            if best < min_radius {
+                break;
+            }
+            let circle = Circle2(p, best);
+            placed.push(circle);
+            for (q, c) in candidates.iter_mut() {
+                let d = q.distance(p) - best;
+                if d < *c {
+                    *c = d;
+                }
+            }
+        }
+        placed
+    }
+}
+
+/// Centres and radii solving three contact equations, at most two
+fn apollonius_solve<T: Float>(rows: [(bool, [T; 3], T); 3]) -> Vec<[T; 3]> {
+    let zero = T::zero();
+    let det2 = |a: [T; 2], b: [T; 2]| a[0] * b[1] - a[1] * b[0];
+    let Some(q) = rows.iter().position(|r| r.0) else {
+        // three lines: linear in (x, y, r)
+        let m: Vec<[T; 3]> = rows.iter().map(|r| r.1).collect();
+        let f: Vec<T> = rows.iter().map(|r| -r.2).collect();
+        let det = |c: [[T; 3]; 3]| c[0][0] * (c[1][1] * c[2][2] - c[1][2] * c[2][1]) - c[0][1] * (c[1][0] * c[2][2] - c[1][2] * c[2][0]) + c[0][2] * (c[1][0] * c[2][1] - c[1][1] * c[2][0]);
+        let d = det([m[0], m[1], m[2]]);
+        if d == zero {
+            return Vec::new();
+        }
+        let z: [T; 3] = std::array::from_fn(|k| {
+            let c: [[T; 3]; 3] = std::array::from_fn(|i| std::array::from_fn(|j| if j == k { f[i] } else { m[i][j] }));
+            det(c) / d
+        });
+        return vec![z];
+    };
+    let (_, lq, fq) = rows[q];
+    // the other two rows made linear by taking the quadratic one off
+    let linear: Vec<([T; 3], T)> = rows
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != q)
+        .map(|(_, &(a, l, f))| if a { (std::array::from_fn(|j| l[j] - lq[j]), f - fq) } else { (l, f) })
+        .collect();
+    let (l0, f0, l1, f1) = (linear[0].0, linear[0].1, linear[1].0, linear[1].1);
+    // keep the unknown whose removal leaves the best conditioned 2×2 system free
+    let (k, i, j) = [(2, 0, 1), (1, 0, 2), (0, 1, 2)]
+        .into_iter()
+        .map(|(k, i, j)| (det2([l0[i], l0[j]], [l1[i], l1[j]]).abs(), (k, i, j)))
+        .fold((zero, (2, 0, 1)), |m, c| if c.0 > m.0 { c } else { m })
+        .1;
+    let d = det2([l0[i], l0[j]], [l1[i], l1[j]]);
+    if d == zero {
+        return Vec::new();
+    }
+    // z = e + g u with u the free unknown
+    let solve = |b0: T, b1: T| ((b0 * l1[j] - b1 * l0[j]) / d, (l0[i] * b1 - l1[i] * b0) / d);
+    let (ei, ej) = solve(-f0, -f1);
+    let (gi, gj) = solve(-l0[k], -l1[k]);
+    let (mut e, mut g) = ([zero; 3], [zero; 3]);
+    (e[i], e[j], g[i], g[j], g[k]) = (ei, ej, gi, gj, T::one());
+    let two = T::from_f64(2.0);
+    let a2 = g[0] * g[0] + g[1] * g[1] - g[2] * g[2];
+    let a1 = two * (e[0] * g[0] + e[1] * g[1] - e[2] * g[2]) + lq[0] * g[0] + lq[1] * g[1] + lq[2] * g[2];
+    let a0 = e[0] * e[0] + e[1] * e[1] - e[2] * e[2] + lq[0] * e[0] + lq[1] * e[1] + lq[2] * e[2] + fq;
+    quadratic_roots(a2, a1, a0).into_iter().map(|u| std::array::from_fn(|m| e[m] + g[m] * u)).collect()
+}
+
+impl<T: Float> Bounded2<T> for Circle2<T> {
+    fn bounds(&self) -> Option<Rect2<T>> {
+        let r = Vector2(self.1, self.1);
+        Some(Rect2::new(self.0 - r, self.0 + r))
+    }
+
+    fn bounding_circle(&self) -> Option<(Point2<T>, T)> {
+        Some((self.0, self.1))
+    }
+}
+
+fn perp<T: Float>(v: Vector2<T>) -> Vector2<T> {
+    Vector2(-v.1, v.0)
+}
+
+/// Points at ±√h2 along u from m: none if h2 < 0, one if h2 = 0
+fn chord<T: Float>(m: Point2<T>, u: Vector2<T>, h2: T) -> Vec<Point2<T>> {
+    if h2 < T::zero() {
+        Vec::new()
+    } else if h2 == T::zero() {
+        vec![m]
+    } else {
+        let h = h2.sqrt();
+        vec![m - u * h, m + u * h]
+    }
+}
+
+/// Radii of the circles on which centers of radius-r circles touching a radius-R circle lie
+fn loci<T: Float>(big: T, r: T) -> Vec<T> {
+    let inner = (big - r).abs();
+    if inner == T::zero() { vec![big + r] } else { vec![big + r, inner] }
+}
+
+impl_anchored!(Circle2, |c, m| Circle2(m.point(c.0), m.length(c.1)));
+
+impl<T: Float> Closest2<T> for Circle2<T> {
+    fn closest_point_on(&self, p: Point2<T>) -> Point2<T> {
+        let v = p.vector_from(self.0);
+        let l = v.length();
+        if l == T::zero() {
+            return self.0 + Vector2(self.1, T::zero());
+        }
+        self.0 + v * (self.1 / l)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn near(p: Point2<f64>, q: Point2<f64>) -> bool {
+        p.distance(q) < 1e-12
+    }
+
+    fn touches(c: &Circle2<f64>, l: &Line2<f64>) -> bool {
+        (l.signed_distance(c.center()).abs() - c.radius()).abs() < 1e-12
+    }
+
+    #[test]
+    fn intersections() {
+        let c = Circle2::new(Point2(0.0, 0.0), 5.0);
+        let l = Line2::new(Point2(-10.0, 3.0), Vector2(2.0, 0.0));
+        assert_eq!(c.intersect_line(&l), vec![Point2(-4.0, 3.0), Point2(4.0, 3.0)]);
+        assert_eq!(c.intersect_line(&l.offset(2.0)), vec![Point2(0.0, 5.0)]);
+        assert!(c.intersect_line(&l.offset(3.0)).is_empty());
+        let d = Circle2::new(Point2(8.0, 0.0), 5.0);
+        assert_eq!(c.intersect_circle(&d), vec![Point2(4.0, 3.0), Point2(4.0, -3.0)]);
+        assert_eq!(c.intersect_circle(&Circle2::new(Point2(10.0, 0.0), 5.0)), vec![Point2(5.0, 0.0)]);
+        assert!(c.intersect_circle(&Circle2::new(Point2(1.0, 0.0), 1.0)).is_empty());
+        assert!(c.intersect_circle(&c).is_empty());
+    }
+
+    #[test]
+    fn tangent_lines() {
+        let c = Circle2::new(Point2(0.0, 0.0), 3.0);
+        let ts = c.tangents_from(Point2(5.0, 0.0));
+        assert_eq!(ts.len(), 2);
+        assert!(ts.iter().all(|t| touches(&c, t) && t.point() == Point2(5.0, 0.0)));
+        assert!(near(ts[0].point() + ts[0].direction(), Point2(1.8, -2.4)));
+        assert_eq!(c.tangents_from(Point2(0.0, 3.0)), vec![Line2::new(Point2(0.0, 3.0), Vector2(-1.0, 0.0))]);
+        assert!(c.tangents_from(Point2(1.0, 1.0)).is_empty());
+
+        let d = Circle2::new(Point2(10.0, 0.0), 1.0);
+        let ext = c.external_tangents(&d);
+        let int = c.internal_tangents(&d);
+        assert_eq!((ext.len(), int.len()), (2, 2));
+        for t in ext.iter().chain(&int) {
+            assert!(touches(&c, t) && touches(&d, t));
+            assert!(t.direction().dot(d.center().vector_from(c.center())) > 0.0);
+        }
+        // both centers on one side of an external tangent, opposite sides of an internal one
+        let side = |t: &Line2<f64>, p| t.signed_distance(p);
+        assert!(ext.iter().all(|t| side(t, c.center()) * side(t, d.center()) > 0.0));
+        assert!(int.iter().all(|t| side(t, c.center()) * side(t, d.center()) < 0.0));
+        assert!(c.internal_tangents(&Circle2::new(Point2(4.0, 0.0), 2.0)).is_empty());
+        assert_eq!(c.internal_tangents(&Circle2::new(Point2(4.0, 0.0), 1.0)).len(), 1);
+    }
+
+    #[test]
+    fn tangent_circles() {
+        let x = Line2::new(Point2(0.0, 0.0), Vector2(1.0, 0.0));
+        let y = Line2::new(Point2(0.0, 0.0), Vector2(0.0, 1.0));
+        let cs = Circle2::tangent_to_lines(&x, &y, 1.0);
+        assert_eq!(cs.len(), 4);
+        assert!(cs.iter().all(|c| touches(c, &x) && touches(c, &y)));
+        assert!(Circle2::tangent_to_lines(&x, &x.offset(1.0), 1.0).is_empty());
+
+        let k = Circle2::new(Point2(0.0, 4.0), 2.0);
+        let cs = Circle2::tangent_to_line_and_circle(&x, &k, 1.0);
+        assert!(!cs.is_empty());
+        for c in &cs {
+            let gap = c.center().distance(k.center());
+            assert!(touches(c, &x) && ((gap - 3.0).abs() < 1e-12 || (gap - 1.0).abs() < 1e-12));
+        }
+
+        let (a, b) = (Circle2::new(Point2(0.0, 0.0), 1.0), Circle2::new(Point2(4.0, 0.0), 1.0));
+        let cs = Circle2::tangent_to_circles(&a, &b, 2.0);
+        assert_eq!(cs.len(), 4);
+        for c in &cs {
+            for o in [a, b] {
+                let gap = c.center().distance(o.center());
+                assert!((gap - 3.0).abs() < 1e-12 || (gap - 1.0).abs() < 1e-12);
+            }
+        }
+        assert_eq!(a.bounding_circle(), Some((Point2(0.0, 0.0), 1.0)));
+
+        // Apollonius: three disjoint circles, three sides of a triangle, three points
+        let touches_circle = |c: &Circle2<f64>, o: &Circle2<f64>| {
+            let gap = c.center().distance(o.center());
+            (gap - (c.radius() + o.radius())).abs() < 1e-9 || (gap - (c.radius() - o.radius()).abs()).abs() < 1e-9
+        };
+        let given = [Circle2::new(Point2(0.0, 0.0), 1.0), Circle2::new(Point2(6.0, 0.0), 2.0), Circle2::new(Point2(2.0, 5.0), 1.5)];
+        let cs = Circle2::apollonius(given.map(Contact::Circle));
+        assert_eq!(cs.len(), 8);
+        assert!(cs.iter().all(|c| given.iter().all(|o| touches_circle(c, o))));
+        let sides = [
+            Line2::new(Point2(0.0, 0.0), Vector2(1.0, 0.0)),
+            Line2::new(Point2(0.0, 0.0), Vector2(0.0, 1.0)),
+            Line2::new(Point2(3.0, 0.0), Vector2(-3.0, 4.0)),
+        ];
+        let cs = Circle2::apollonius(sides.map(Contact::Line));
+        assert_eq!(cs.len(), 4);
+        assert!(cs.iter().all(|c| sides.iter().all(|l| (l.signed_distance(c.center()).abs() - c.radius()).abs() < 1e-9)));
+        assert!(cs.iter().any(|c| near(c.center(), Point2(1.0, 1.0)) && (c.radius() - 1.0).abs() < 1e-12));
+        let cs = Circle2::apollonius([Point2(0.0, 0.0), Point2(2.0, 0.0), Point2(0.0, 2.0)].map(Contact::Point));
+        assert_eq!(cs.len(), 1);
+        assert!(near(cs[0].center(), Point2(1.0, 1.0)));
+        let mixed = Circle2::apollonius([Contact::Point(Point2(0.0, 2.0)), Contact::Line(sides[0]), Contact::Circle(Circle2::new(Point2(5.0, 3.0), 1.0))]);
+        assert!(!mixed.is_empty());
+        assert!(mixed.iter().all(|c| touches(c, &sides[0]) && (c.center().distance(Point2(0.0, 2.0)) - c.radius()).abs() < 1e-9));
+        assert_eq!(b.bounds(), Some(Rect2::new(Point2(3.0, -1.0), Point2(5.0, 1.0))));
+    }
+
+    #[test]
+    fn packing() {
+        let square = Polygon2::new(vec![Point2(0.0, 0.0), Point2(10.0, 0.0), Point2(10.0, 10.0), Point2(0.0, 10.0)]);
+        let cs = Circle2::pack_in(&square, 0.5, 12);
+        // the inscribed circle, then one in each corner
+        assert_eq!(cs.len(), 5);
+        assert!(near(cs[0].center(), Point2(5.0, 5.0)) && (cs[0].radius() - 5.0).abs() < 1e-9);
+        assert!(cs[1..].iter().all(|c| (c.radius() - 5.0 * (3.0 - 8.0f64.sqrt())).abs() < 1e-6));
+        for (i, c) in cs.iter().enumerate() {
+            assert!(c.radius() >= 0.5);
+            assert!(square.closest_point_on(c.center()).distance(c.center()) >= c.radius() - 1e-9);
+            assert!(cs[i + 1..].iter().all(|d| c.center().distance(d.center()) >= c.radius() + d.radius() - 1e-9));
+        }
+        assert!(cs.windows(2).all(|w| w[0].radius() >= w[1].radius() - 1e-9));
+    }
+}
@@ -0,0 +1,133 @@
+//! Cleanup of digitized outlines: closing, orientation, collinear vertices
+
+use crate::curve::segment_distance;
+use crate::{ Float, MultiPolygon2, Point2, Polygon2, Polyline2 };
+
+impl<T: Float> Polyline2<T> {
+    /// close: &Polyline × K -> Option<Polygon>
+    /// the polygon the polyline outlines when its ends are within
+    /// `tolerance`, the last vertex dropped as a copy of the first; None
+    /// if they are farther apart or fewer than three vertices remain
+    pub fn close(&self, tolerance: T) -> Option<Polygon2<T>> {
+        let v = self.vertices();
+        let (&first, &last) = (v.first()?, v.last()?);
+        if v.len() < 2 || first.distance(last) > tolerance {
+            return None;
+        }
+        let ring = v[..v.len() - 1].to_vec();
+        if ring.len() < 3 { None } else { Some(Polygon2::new(ring)) }
+    }
+
+    /// merge_collinear: &Polyline × K -> Polyline
+    /// drops vertices within `tolerance` of the segment joining the last
+    /// kept vertex to the next one, repeated vertices included; the ends
+    /// are kept
+    pub fn merge_collinear(&self, tolerance: T) -> Self {
+        Polyline2::new(merged(self.vertices(), tolerance))
+    }
+}
+
+impl<T: Float> Polygon2<T> {
+    /// oriented: &Polygon -> Polygon
+    /// copy with a counter-clockwise exterior and clockwise holes
+    pub fn oriented(&self) -> Self {
+        let ring = |r: &[Point2<T>], ccw: bool| {
+            let mut r = r.to_vec();
+            if (Polygon2::new(r.clone()).signed_area() > T::zero()) != ccw {
+                r.reverse();
+            }
+            r
+        };
+        Polygon2::with_holes(ring(self.vertices(), true), self.holes().iter().map(|h| ring(h, false)).collect())
+    }
+
+    /// merge_collinear: &Polygon × K -> Polygon
+    /// every ring's vertices dropped as for polylines, going round; a
+    /// ring that would keep fewer than three, lying within `tolerance` of
+    /// a line, is left as it was
+    pub fn merge_collinear(&self, tolerance: T) -> Self {
+        let ring = |r: &[Point2<T>]| merged_ring(r, tolerance);
+        Polygon2::with_holes(ring(self.vertices()), self.holes().iter().map(|h| ring(h)).collect())
+    }
+}
+
+impl<T: Float> MultiPolygon2<T> {
+    /// oriented: &MultiPolygon -> MultiPolygon
+    /// every member as `Polygon2::oriented`
+    pub fn oriented(&self) -> Self {
+        self.iter().map(|p| p.oriented()).collect()
+    }
+
+    /// merge_collinear: &MultiPolygon × K -> MultiPolygon
+    /// every member as `Polygon2::merge_collinear`
+    pub fn merge_collinear(&self, tolerance: T) -> Self {
+        self.iter().map(|p| p.merge_collinear(tolerance)).collect()
+    }
+}
+
+/// Vertices of an open run kept by `merge_collinear`, ends included
+fn merged<T: Float>(v: &[Point2<T>], tolerance: T) -> Vec<Point2<T>> {
+    let Some((&first, rest)) = v.split_first() else {
+        return Vec::new();
+    };
+    let mut kept = vec![first];
+    for (i, &p) in rest.iter().enumerate() {
+        match rest.get(i + 1) {
+            Some(&next) if segment_distance(p, kept[kept.len() - 1], next) <= tolerance => {}
+            _ => kept.push(p),
+        }
+    }
+    kept
+}
+
+/// Vertices of a ring kept by `merge_collinear`, walked from one that
+/// stands out from its neighbours; the ring itself if fewer than three
+fn merged_ring<T: Float>(r: &[Point2<T>], tolerance: T) -> Vec<Point2<T>> {
+    let n = r.len();
+    let corner = (0..n).find(|&i| n >= 3 && segment_distance(r[i], r[(i + n - 1) % n], r[(i + 1) % n]) > tolerance);
+    let Some(start) = corner else {
+        return r.to_vec();
+    };
+    let mut walk: Vec<Point2<T>> = (0..=n).map(|k| r[(start + k) % n]).collect();
+    walk = merged(&walk, tolerance);
+    walk.pop();
+    if walk.len() < 3 { r.to_vec() } else { walk }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn close_and_orient() {
+        let traced = Polyline2::new(vec![Point2(0.0, 0.0), Point2(0.0, 2.0), Point2(2.0, 2.0), Point2(2.0, 0.0), Point2(0.01, -0.01)]);
+        assert_eq!(traced.close(0.001), None);
+        let square = traced.close(0.02).unwrap();
+        assert_eq!(square.vertices().len(), 4);
+        assert!(square.signed_area() < 0.0);
+        let hole = vec![Point2(0.5, 0.5), Point2(1.5, 0.5), Point2(1.0, 1.5)];
+        let m = MultiPolygon2::new(vec![Polygon2::with_holes(square.vertices().to_vec(), vec![hole])]).oriented();
+        let p = &m.polygons()[0];
+        assert!(p.signed_area() > 0.0 && Polygon2::new(p.holes()[0].clone()).signed_area() < 0.0);
+        assert!((p.area() - 3.5).abs() < 1e-12);
+        assert_eq!(Polyline2::new(vec![Point2(0.0, 0.0), Point2(1.0, 1.0), Point2(0.0, 0.0)]).close(0.1), None);
+    }
+
+    #[test]
+    fn collinear() {
+        let l = Polyline2::new(vec![Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(1.0, 0.0), Point2(2.0, 0.001), Point2(3.0, 0.0), Point2(3.0, 1.0)]);
+        assert_eq!(l.merge_collinear(0.01).vertices(), &[Point2(0.0, 0.0), Point2(3.0, 0.0), Point2(3.0, 1.0)]);
+        assert_eq!(l.merge_collinear(0.0).vertices().len(), 5);
+        // a spike back along an edge is not collinear
+        let spike = Polyline2::new(vec![Point2(0.0, 0.0), Point2(2.0, 0.0), Point2(1.0, 0.0)]);
+        assert_eq!(spike.merge_collinear(0.01).vertices().len(), 3);
+        // ring starting mid-edge, with a hole with a midpoint
+        let ring = vec![Point2(1.0, 0.0), Point2(2.0, 0.0), Point2(2.0, 2.0), Point2(0.0, 2.0), Point2(0.0, 0.0)];
+        let hole = vec![Point2(0.5, 0.5), Point2(0.5, 1.0), Point2(0.5, 1.5), Point2(1.5, 1.5), Point2(1.5, 0.5)];
+        let p = Polygon2::with_holes(ring, vec![hole]).merge_collinear(1e-9);
+        assert_eq!(p.vertices(), &[Point2(2.0, 0.0), Point2(2.0, 2.0), Point2(0.0, 2.0), Point2(0.0, 0.0)]);
+        assert_eq!(p.holes()[0].len(), 4);
+        let flat = Polygon2::new(vec![Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(2.0, 0.0)]);
+        assert_eq!(flat.merge_collinear(0.1), flat);
+    }
+}
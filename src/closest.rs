@@ -0,0 +1,73 @@
+//! Closest points on shapes
+
+use crate::{ Point2, Point3 };
+
+/// 2d shapes points can be snapped onto
+pub trait Closest2<T> {
+    /// closest_point_on: &Shape × Point -> Point
+    /// nearest point of the shape (of its outline for regions);
+    /// p itself for empty shapes
+    fn closest_point_on(&self, p: Point2<T>) -> Point2<T>;
+}
+
+/// 3d shapes points can be snapped onto
+pub trait Closest3<T> {
+    /// closest_point_on: &Shape × Point -> Point
+    /// nearest point of the shape (of its surface for solids);
+    /// p itself for empty shapes
+    fn closest_point_on(&self, p: Point3<T>) -> Point3<T>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ Arc2, Circle2, CubicBezier2, Line2, Polygon2, Polyline2, QuadBezier2, Segment2, TriMesh, Vector2 };
+
+    fn near(p: Point2<f64>, q: Point2<f64>) -> bool {
+        p.distance(q) < 1e-9
+    }
+
+    #[test]
+    fn lines_and_circles() {
+        let s = Segment2::new(Point2(0.0, 0.0), Point2(2.0, 0.0));
+        assert_eq!(s.closest_point_on(Point2(1.0, 5.0)), Point2(1.0, 0.0));
+        assert_eq!(s.closest_point_on(Point2(-3.0, 1.0)), Point2(0.0, 0.0));
+        assert_eq!(Line2::new(Point2(0.0, 0.0), Vector2(1.0, 0.0)).closest_point_on(Point2(-3.0, 1.0)), Point2(-3.0, 0.0));
+        let c = Circle2::new(Point2(1.0, 1.0), 2.0);
+        assert!(near(c.closest_point_on(Point2(1.0, 5.0)), Point2(1.0, 3.0)));
+        let quarter = Arc2::new(Point2(0.0, 0.0), 1.0, 0.0, std::f64::consts::FRAC_PI_2);
+        assert!(near(quarter.closest_point_on(Point2(2.0, 2.0)), Point2(0.5f64.sqrt(), 0.5f64.sqrt())));
+        assert!(near(quarter.closest_point_on(Point2(-1.0, -0.1)), Point2(0.0, 1.0)));
+        assert!(near(quarter.closest_point_on(Point2(0.5, -2.0)), Point2(1.0, 0.0)));
+    }
+
+    #[test]
+    fn curves() {
+        // symmetric parabola y = 2t(1 - t)·2 peaking at (1, 1)
+        let q = QuadBezier2::new(Point2(0.0, 0.0), Point2(1.0, 2.0), Point2(2.0, 0.0));
+        assert!(near(q.closest_point_on(Point2(1.0, 3.0)), Point2(1.0, 1.0)));
+        let straight = CubicBezier2::new(Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(2.0, 0.0), Point2(3.0, 0.0));
+        assert!(near(straight.closest_point_on(Point2(2.2, -1.0)), Point2(2.2, 0.0)));
+        assert!(near(straight.closest_point_on(Point2(4.0, 1.0)), Point2(3.0, 0.0)));
+    }
+
+    #[test]
+    fn chains_and_meshes() {
+        let l = Polyline2::new(vec![Point2(0.0, 0.0), Point2(2.0, 0.0), Point2(2.0, 2.0)]);
+        assert_eq!(l.closest_point_on(Point2(3.0, 1.0)), Point2(2.0, 1.0));
+        let square = Polygon2::with_holes(
+            vec![Point2(0.0, 0.0), Point2(4.0, 0.0), Point2(4.0, 4.0), Point2(0.0, 4.0)],
+            vec![vec![Point2(1.0, 1.0), Point2(1.0, 2.0), Point2(2.0, 2.0), Point2(2.0, 1.0)]],
+        );
+        assert_eq!(square.closest_point_on(Point2(3.5, 2.0)), Point2(4.0, 2.0));
+        assert_eq!(square.closest_point_on(Point2(1.2, 1.5)), Point2(1.0, 1.5));
+        let tetra = TriMesh::new(
+            vec![Point3(0.0, 0.0, 0.0), Point3(1.0, 0.0, 0.0), Point3(0.0, 1.0, 0.0), Point3(0.0, 0.0, 1.0)],
+            vec![[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]],
+        );
+        assert!(tetra.closest_point_on(Point3(0.2, 0.2, -1.0)).distance(Point3(0.2, 0.2, 0.0)) < 1e-12);
+        assert_eq!(tetra.closest_point_on(Point3(-1.0, -1.0, -1.0)), Point3(0.0, 0.0, 0.0));
+        let c = tetra.closest_point_on(Point3(1.0, 1.0, 1.0));
+        assert!(Point3(1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0).distance(c) < 1e-12);
+    }
+}
@@ -0,0 +1,222 @@
+//! Point clouds
+
+use std::cmp::Ordering;
+
+use crate::{ Aabb3, Bounded3, Float, Isometry3, Point3 };
+use crate::bounds::{ minimal_sphere, ritter_sphere };
+use crate::kdtree::KdTree;
+
+/// 3d Point cloud: contiguous points, each with an attribute (normal, color, intensity...)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointCloud3<T, A = ()> {
+    points: Vec<Point3<T>>,
+    attributes: Vec<A>,
+}
+
+impl<T> PointCloud3<T> {
+    /// new: [Point] -> Cloud
+    pub fn new(points: Vec<Point3<T>>) -> Self {
+        let attributes = vec![(); points.len()];
+        PointCloud3 { points, attributes }
+    }
+}
+
+impl<T, A> PointCloud3<T, A> {
+    /// with_attributes: [Point] × [A] -> Cloud
+    /// panics unless there is one attribute per point
+    pub fn with_attributes(points: Vec<Point3<T>>, attributes: Vec<A>) -> Self {
+        assert_eq!(points.len(), attributes.len(), "one attribute per point");
+        PointCloud3 { points, attributes }
+    }
+
+    /// points: &Cloud -> &[Point]
+    pub fn points(&self) -> &[Point3<T>] {
+        &self.points
+    }
+
+    /// attributes: &Cloud -> &[A]
+    pub fn attributes(&self) -> &[A] {
+        &self.attributes
+    }
+
+    /// len: &Cloud -> usize
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// is_empty: &Cloud -> bool
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// push: &mut Cloud × Point × A
+    pub fn push(&mut self, p: Point3<T>, attribute: A) {
+        self.points.push(p);
+        self.attributes.push(attribute);
+    }
+
+    /// retain: &mut Cloud × (Point × &A -> bool)
+    /// keeps the points passing the predicate, in order
+    pub fn retain<F: FnMut(Point3<T>, &A) -> bool>(&mut self, mut keep: F) where T: Copy {
+        let mut i = 0;
+        for j in 0..self.points.len() {
+            if keep(self.points[j], &self.attributes[j]) {
+                self.points.swap(i, j);
+                self.attributes.swap(i, j);
+                i += 1;
+            }
+        }
+        self.points.truncate(i);
+        self.attributes.truncate(i);
+    }
+}
+
+impl<T: Float, A> PointCloud3<T, A> {
+    /// transform: &mut Cloud × Isometry
+    /// moves every point in place; attributes are left untouched
+    pub fn transform(&mut self, m: Isometry3<T>) {
+        for p in &mut self.points {
+            *p = m.transform_point(*p);
+        }
+    }
+
+    /// centroid: &Cloud -> Option<Point>
+    /// mean of the points, None if empty
+    pub fn centroid(&self) -> Option<Point3<T>> {
+        let first = *self.points.first()?;
+        let sum = self.points.iter().fold(first.vector_from(first), |s, p| s + p.vector_from(first));
+        Some(first + sum / T::from_f64(self.points.len() as f64))
+    }
+
+    /// minimal_bounding_sphere: &Cloud -> Option<(Point, K)>
+    /// center and radius of the smallest sphere holding every point (Welzl),
+    /// None if empty
+    pub fn minimal_bounding_sphere(&self) -> Option<(Point3<T>, T)> {
+        minimal_sphere(&self.points)
+    }
+
+    /// remove_statistical_outliers: &mut Cloud × usize × K
+    /// drops points whose mean distance to their `k` nearest neighbours exceeds
+    /// the cloud-wide mean of that distance by more than `stddev` standard deviations
+    /// (neighbours from a kd-tree)
+    pub fn remove_statistical_outliers(&mut self, k: usize, stddev: T) {
+        let n = self.points.len();
+        let k = k.min(n.saturating_sub(1));
+        if k == 0 {
+            return;
+        }
+        let tree = KdTree::new(&self.points);
+        let mut found = Vec::with_capacity(k + 1);
+        let mean: Vec<T> = self.points.iter().map(|&p| {
+            // the point itself comes first, at distance 0
+            tree.k_nearest(p, k + 1, &mut found);
+            found[1..].iter().fold(T::zero(), |s, &(d, _)| s + d.sqrt()) / T::from_f64(k as f64)
+        }).collect();
+        let count = T::from_f64(n as f64);
+        let mu = mean.iter().fold(T::zero(), |s, &x| s + x) / count;
+        let sigma = (mean.iter().fold(T::zero(), |s, &x| s + (x - mu) * (x - mu)) / count).sqrt();
+        let limit = mu + sigma * stddev;
+        let mut i = 0;
+        self.retain(|_, _| {
+            i += 1;
+            mean[i - 1] <= limit
+        });
+    }
+}
+
+impl<T: Float, A: Clone> PointCloud3<T, A> {
+    /// voxel_downsample: &Cloud × K -> Cloud
+    /// one point per occupied cube of side `cell`: the centroid of its points,
+    /// carrying the attribute of the point closest to that centroid
+    pub fn voxel_downsample(&self, cell: T) -> Self {
+        let key = |p: Point3<T>| [(p.0 / cell).floor(), (p.1 / cell).floor(), (p.2 / cell).floor()];
+        let mut order: Vec<usize> = (0..self.points.len()).collect();
+        order.sort_by(|&i, &j| {
+            key(self.points[i]).partial_cmp(&key(self.points[j])).unwrap_or(Ordering::Equal)
+        });
+        let mut out = PointCloud3 { points: Vec::new(), attributes: Vec::new() };
+        for run in order.chunk_by(|&i, &j| key(self.points[i]) == key(self.points[j])) {
+            let first = self.points[run[0]];
+            let sum = run.iter().fold(first.vector_from(first), |s, &i| s + self.points[i].vector_from(first));
+            let c = first + sum / T::from_f64(run.len() as f64);
+            let nearest = run.iter().copied().fold(run[0], |best, i| {
+                if self.points[i].distance(c) < self.points[best].distance(c) { i } else { best }
+            });
+            out.push(c, self.attributes[nearest].clone());
+        }
+        out
+    }
+}
+
+impl<T: Float, A> Bounded3<T> for PointCloud3<T, A> {
+    fn bounds(&self) -> Option<Aabb3<T>> {
+        Aabb3::from_points(self.points.iter().copied())
+    }
+
+    /// Ritter's sphere: a pass over the points, a few percent above minimal
+    fn bounding_sphere(&self) -> Option<(Point3<T>, T)> {
+        ritter_sphere(&self.points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ Rotor3, Vector3 };
+
+    #[test]
+    fn container() {
+        let mut c = PointCloud3::with_attributes(vec![Point3(0.0, 0.0, 0.0), Point3(2.0, 0.0, 0.0)], vec![1u8, 2]);
+        c.push(Point3(1.0, 3.0, -1.0), 3);
+        assert_eq!((c.len(), c.is_empty()), (3, false));
+        assert_eq!(c.centroid(), Some(Point3(1.0, 1.0, -1.0 / 3.0)));
+        assert_eq!(c.bounds(), Some(Aabb3::new(Point3(0.0, 0.0, -1.0), Point3(2.0, 3.0, 0.0))));
+        c.retain(|p, &a| p.0 > 0.5 || a == 1);
+        assert_eq!(c.points(), &[Point3(0.0, 0.0, 0.0), Point3(2.0, 0.0, 0.0), Point3(1.0, 3.0, -1.0)]);
+        c.retain(|_, &a| a != 2);
+        assert_eq!(c.attributes(), &[1, 3]);
+        let (m, r) = c.minimal_bounding_sphere().unwrap();
+        assert!(m == Point3(0.5, 1.5, -0.5) && (r - 11f64.sqrt() / 2.0).abs() < 1e-12);
+        assert!((c.bounding_sphere().unwrap().1 - r).abs() < 1e-12);
+        assert_eq!(PointCloud3::<f64>::new(vec![]).centroid(), None);
+    }
+
+    #[test]
+    fn transform_in_place() {
+        let mut c = PointCloud3::new(vec![Point3(1.0, 0.0, 0.0), Point3(0.0, 1.0, 0.0)]);
+        let r = Rotor3::from_to(Vector3(1.0, 0.0, 0.0), Vector3(0.0, 1.0, 0.0));
+        c.transform(Isometry3::new(r, Vector3(0.0, 0.0, 5.0)));
+        assert!(c.points()[0].distance(Point3(0.0, 1.0, 5.0)) < 1e-12);
+        assert!(c.points()[1].distance(Point3(-1.0, 0.0, 5.0)) < 1e-12);
+        assert_eq!(c.attributes().len(), 2);
+    }
+
+    #[test]
+    fn voxel_downsample() {
+        let c = PointCloud3::with_attributes(vec![
+            Point3(0.1, 0.1, 0.1), Point3(2.5, 0.5, 0.5), Point3(0.3, 0.5, 0.2),
+            Point3(0.2, 0.3, 0.9), Point3(-0.5, 0.5, 0.5),
+        ], vec!['a', 'b', 'c', 'd', 'e']);
+        let d = c.voxel_downsample(1.0);
+        assert_eq!(d.len(), 3);
+        assert_eq!(d.attributes(), &['e', 'c', 'b']);
+        assert!(d.points()[1].distance(Point3(0.2, 0.3, 0.4)) < 1e-12);
+        assert_eq!(d.points()[2], Point3(2.5, 0.5, 0.5));
+        assert!(PointCloud3::<f64>::new(vec![]).voxel_downsample(1.0).is_empty());
+    }
+
+    #[test]
+    fn statistical_outliers() {
+        let mut points: Vec<_> = (0..25).map(|i| Point3((i % 5) as f64, (i / 5) as f64, 0.0)).collect();
+        points.push(Point3(2.0, 2.0, 40.0));
+        let attributes: Vec<usize> = (0..points.len()).collect();
+        let mut c = PointCloud3::with_attributes(points, attributes);
+        c.remove_statistical_outliers(4, 1.0);
+        assert_eq!(c.len(), 25);
+        assert!(c.points().iter().all(|p| p.2 == 0.0));
+        assert_eq!(c.attributes()[24], 24);
+        let mut single = PointCloud3::new(vec![Point3(0.0, 0.0, 0.0)]);
+        single.remove_statistical_outliers(8, 1.0);
+        assert_eq!(single.len(), 1);
+    }
+}
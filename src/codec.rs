@@ -0,0 +1,665 @@
+//! Compact binary encoding
+//!
+//! Every encoding opens with a header: the bytes `gem`, the format
+//! version, a tag for the kind of shape and the coordinate compression.
+//! Counts and indices are LEB128 varints, other scalars little-endian f64.
+//! Point coordinates are either f64 too or, under `Compression::Delta`,
+//! rounded to multiples of a step and stored as zigzag varint differences
+//! from the previous point's, per axis, across the whole encoding.
+
+use crate::{ Aabb3, Circle2, Float, Geometry2, GeometryCollection2, Line2, MultiPoint2, MultiPolygon2, MultiPolyline2 };
+use crate::{ Point2, Point3, Polygon2, Polyline2, Polyline3, ProgressiveMesh, Rect2, Segment2, Sphere, TriMesh, Triangle3, Vector2, Vector3, VertexSplit };
+
+const MAGIC: [u8; 3] = *b"gem";
+
+/// Format version written by `to_bytes`; `from_bytes` reads it and older
+pub const VERSION: u8 = 1;
+
+/// How point coordinates are stored
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Compression {
+    /// f64 as given
+    None,
+    /// rounded to multiples of `step`, as varint differences; a step that
+    /// is not positive and finite stores them as given
+    Delta { step: f64 },
+}
+
+/// Shapes with a binary encoding
+pub trait Binary: Sized {
+    /// to_bytes: &Shape × Compression -> [u8]
+    fn to_bytes(&self, compression: Compression) -> Vec<u8>;
+
+    /// from_bytes: [u8] -> Option<Shape>
+    /// None unless the bytes are exactly one encoding of this kind, of
+    /// this version or an older one
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+/// Body of an encoding, after the header
+pub(crate) trait Codec: Sized {
+    /// kind tag in the header
+    const KIND: u8;
+
+    fn encode(&self, w: &mut Writer);
+
+    fn decode(r: &mut Reader) -> Option<Self>;
+}
+
+impl<C: Codec> Binary for C {
+    fn to_bytes(&self, compression: Compression) -> Vec<u8> {
+        let step = match compression {
+            Compression::Delta { step } if step > 0.0 && step.is_finite() => Some(step),
+            _ => None,
+        };
+        let mut w = Writer { bytes: MAGIC.to_vec(), step, last: [0; 3], canonical: false };
+        w.bytes.extend([VERSION, C::KIND]);
+        match step {
+            Some(s) => {
+                w.bytes.push(1);
+                w.scalar(s);
+            }
+            None => w.bytes.push(0),
+        }
+        self.encode(&mut w);
+        w.bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut r = Reader { bytes, at: 0, step: None, last: [0; 3] };
+        if r.take(3)? != MAGIC || r.byte()? > VERSION || r.byte()? != C::KIND {
+            return None;
+        }
+        r.step = match r.byte()? {
+            0 => None,
+            1 => Some(r.scalar().filter(|s| *s > 0.0 && s.is_finite())?),
+            _ => return None,
+        };
+        let shape = C::decode(&mut r)?;
+        (r.at == bytes.len()).then_some(shape)
+    }
+}
+
+pub(crate) struct Writer {
+    bytes: Vec<u8>,
+    step: Option<f64>,
+    /// previous rounded coordinate on each axis
+    last: [i64; 3],
+    /// every scalar rounded to the step too, with one zero and one NaN
+    canonical: bool,
+}
+
+/// Kind tag and body of a shape with every scalar in canonical form,
+/// rounded to multiples of the step if any, for hashing and comparison
+pub(crate) fn canonical_bytes<C: Codec>(shape: &C, step: Option<f64>) -> Vec<u8> {
+    let mut w = Writer { bytes: vec![C::KIND], step, last: [0; 3], canonical: true };
+    shape.encode(&mut w);
+    w.bytes
+}
+
+impl Writer {
+    fn varint(&mut self, mut n: u64) {
+        while n >= 0x80 {
+            self.bytes.push(n as u8 | 0x80);
+            n >>= 7;
+        }
+        self.bytes.push(n as u8);
+    }
+
+    fn count(&mut self, n: usize) {
+        self.varint(n as u64);
+    }
+
+    fn scalar(&mut self, x: f64) {
+        let x = match (self.canonical, self.step) {
+            (false, _) => x,
+            _ if x.is_nan() => f64::NAN,
+            (true, Some(s)) => (x / s).round() * s + 0.0,
+            (true, None) => x + 0.0,
+        };
+        self.bytes.extend(x.to_le_bytes());
+    }
+
+    fn coords(&mut self, xs: &[f64]) {
+        for (k, &x) in xs.iter().enumerate() {
+            match self.step {
+                Some(s) => {
+                    let q = (x / s).round() as i64;
+                    let d = q.wrapping_sub(self.last[k]);
+                    self.varint(((d << 1) ^ (d >> 63)) as u64);
+                    self.last[k] = q;
+                }
+                None => self.scalar(x),
+            }
+        }
+    }
+
+    fn point2<T: Float>(&mut self, p: Point2<T>) {
+        self.coords(&[p.0.to_f64(), p.1.to_f64()]);
+    }
+
+    fn point3<T: Float>(&mut self, p: Point3<T>) {
+        self.coords(&[p.0.to_f64(), p.1.to_f64(), p.2.to_f64()]);
+    }
+
+    fn points2<T: Float>(&mut self, ps: &[Point2<T>]) {
+        self.count(ps.len());
+        ps.iter().for_each(|&p| self.point2(p));
+    }
+
+    fn points3<T: Float>(&mut self, ps: &[Point3<T>]) {
+        self.count(ps.len());
+        ps.iter().for_each(|&p| self.point3(p));
+    }
+
+    fn all<C: Codec>(&mut self, items: &[C]) {
+        self.count(items.len());
+        items.iter().for_each(|c| c.encode(self));
+    }
+}
+
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+    step: Option<f64>,
+    last: [i64; 3],
+}
+
+impl Reader<'_> {
+    fn take(&mut self, n: usize) -> Option<&[u8]> {
+        let end = self.at.checked_add(n).filter(|&e| e <= self.bytes.len())?;
+        let taken = &self.bytes[self.at..end];
+        self.at = end;
+        Some(taken)
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn varint(&mut self) -> Option<u64> {
+        let mut n = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.byte()?;
+            n |= u64::from(b & 0x7f) << shift;
+            if b < 0x80 {
+                return Some(n);
+            }
+        }
+        None
+    }
+
+    /// A count of items each taking at least a byte, so no more than remain
+    fn count(&mut self) -> Option<usize> {
+        let n = usize::try_from(self.varint()?).ok()?;
+        (n <= self.bytes.len() - self.at).then_some(n)
+    }
+
+    /// An index into items read before, bounded only by the platform
+    fn index(&mut self) -> Option<usize> {
+        usize::try_from(self.varint()?).ok()
+    }
+
+    fn scalar(&mut self) -> Option<f64> {
+        self.take(8).map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    fn coords<const N: usize>(&mut self) -> Option<[f64; N]> {
+        let mut xs = [0.0; N];
+        for (k, x) in xs.iter_mut().enumerate() {
+            *x = match self.step {
+                Some(s) => {
+                    let z = self.varint()?;
+                    let q = self.last[k].wrapping_add((z >> 1) as i64 ^ -((z & 1) as i64));
+                    self.last[k] = q;
+                    q as f64 * s
+                }
+                None => self.scalar()?,
+            };
+        }
+        Some(xs)
+    }
+
+    fn point2<T: Float>(&mut self) -> Option<Point2<T>> {
+        let [x, y] = self.coords()?;
+        Some(Point2(T::from_f64(x), T::from_f64(y)))
+    }
+
+    fn point3<T: Float>(&mut self) -> Option<Point3<T>> {
+        let [x, y, z] = self.coords()?;
+        Some(Point3(T::from_f64(x), T::from_f64(y), T::from_f64(z)))
+    }
+
+    fn real<T: Float>(&mut self) -> Option<T> {
+        self.scalar().map(T::from_f64)
+    }
+
+    fn points2<T: Float>(&mut self) -> Option<Vec<Point2<T>>> {
+        (0..self.count()?).map(|_| self.point2()).collect()
+    }
+
+    fn points3<T: Float>(&mut self) -> Option<Vec<Point3<T>>> {
+        (0..self.count()?).map(|_| self.point3()).collect()
+    }
+
+    fn all<C: Codec>(&mut self) -> Option<Vec<C>> {
+        (0..self.count()?).map(|_| C::decode(self)).collect()
+    }
+}
+
+impl<T: Float> Codec for Vector2<T> {
+    const KIND: u8 = 1;
+
+    fn encode(&self, w: &mut Writer) {
+        w.scalar(self.0.to_f64());
+        w.scalar(self.1.to_f64());
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        Some(Vector2(r.real()?, r.real()?))
+    }
+}
+
+impl<T: Float> Codec for Vector3<T> {
+    const KIND: u8 = 2;
+
+    fn encode(&self, w: &mut Writer) {
+        w.scalar(self.0.to_f64());
+        w.scalar(self.1.to_f64());
+        w.scalar(self.2.to_f64());
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        Some(Vector3(r.real()?, r.real()?, r.real()?))
+    }
+}
+
+impl<T: Float> Codec for Point2<T> {
+    const KIND: u8 = 3;
+
+    fn encode(&self, w: &mut Writer) {
+        w.point2(*self);
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        r.point2()
+    }
+}
+
+impl<T: Float> Codec for Point3<T> {
+    const KIND: u8 = 4;
+
+    fn encode(&self, w: &mut Writer) {
+        w.point3(*self);
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        r.point3()
+    }
+}
+
+impl<T: Float> Codec for Segment2<T> {
+    const KIND: u8 = 5;
+
+    fn encode(&self, w: &mut Writer) {
+        w.point2(self.start());
+        w.point2(self.end());
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        Some(Segment2::new(r.point2()?, r.point2()?))
+    }
+}
+
+impl<T: Float> Codec for Line2<T> {
+    const KIND: u8 = 6;
+
+    fn encode(&self, w: &mut Writer) {
+        w.point2(self.point());
+        self.direction().encode(w);
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        Some(Line2::new(r.point2()?, Vector2::decode(r)?))
+    }
+}
+
+impl<T: Float> Codec for Circle2<T> {
+    const KIND: u8 = 7;
+
+    fn encode(&self, w: &mut Writer) {
+        w.point2(self.center());
+        w.scalar(self.radius().to_f64());
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        Some(Circle2::new(r.point2()?, r.real()?))
+    }
+}
+
+impl<T: Float> Codec for Rect2<T> {
+    const KIND: u8 = 8;
+
+    fn encode(&self, w: &mut Writer) {
+        w.point2(self.min());
+        w.point2(self.max());
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        Some(Rect2::new(r.point2()?, r.point2()?))
+    }
+}
+
+impl<T: Float> Codec for Aabb3<T> {
+    const KIND: u8 = 9;
+
+    fn encode(&self, w: &mut Writer) {
+        w.point3(self.min());
+        w.point3(self.max());
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        Some(Aabb3::new(r.point3()?, r.point3()?))
+    }
+}
+
+impl<T: Float> Codec for Sphere<T> {
+    const KIND: u8 = 10;
+
+    fn encode(&self, w: &mut Writer) {
+        w.point3(self.center());
+        w.scalar(self.radius().to_f64());
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        Some(Sphere::new(r.point3()?, r.real()?))
+    }
+}
+
+impl<T: Float> Codec for Triangle3<T> {
+    const KIND: u8 = 11;
+
+    fn encode(&self, w: &mut Writer) {
+        self.vertices().iter().for_each(|&p| w.point3(p));
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        Some(Triangle3::new(r.point3()?, r.point3()?, r.point3()?))
+    }
+}
+
+impl<T: Float> Codec for Polyline2<T> {
+    const KIND: u8 = 12;
+
+    fn encode(&self, w: &mut Writer) {
+        w.points2(self.vertices());
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        Some(Polyline2::new(r.points2()?))
+    }
+}
+
+impl<T: Float> Codec for Polyline3<T> {
+    const KIND: u8 = 13;
+
+    fn encode(&self, w: &mut Writer) {
+        w.points3(self.vertices());
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        Some(Polyline3::new(r.points3()?))
+    }
+}
+
+impl<T: Float> Codec for Polygon2<T> {
+    const KIND: u8 = 14;
+
+    fn encode(&self, w: &mut Writer) {
+        w.points2(self.vertices());
+        w.count(self.holes().len());
+        self.holes().iter().for_each(|h| w.points2(h));
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        let exterior = r.points2()?;
+        let holes = (0..r.count()?).map(|_| r.points2()).collect::<Option<_>>()?;
+        Some(Polygon2::with_holes(exterior, holes))
+    }
+}
+
+impl<T: Float> Codec for MultiPoint2<T> {
+    const KIND: u8 = 15;
+
+    fn encode(&self, w: &mut Writer) {
+        w.points2(self.points());
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        Some(MultiPoint2::new(r.points2()?))
+    }
+}
+
+impl<T: Float> Codec for MultiPolyline2<T> {
+    const KIND: u8 = 16;
+
+    fn encode(&self, w: &mut Writer) {
+        w.all(self.polylines());
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        Some(MultiPolyline2::new(r.all()?))
+    }
+}
+
+impl<T: Float> Codec for MultiPolygon2<T> {
+    const KIND: u8 = 17;
+
+    fn encode(&self, w: &mut Writer) {
+        w.all(self.polygons());
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        Some(MultiPolygon2::new(r.all()?))
+    }
+}
+
+/// Each member tagged with the kind of its variant, collections by this one
+impl<T: Float> Codec for Geometry2<T> {
+    const KIND: u8 = 18;
+
+    fn encode(&self, w: &mut Writer) {
+        match self {
+            Geometry2::Point(p) => {
+                w.bytes.push(Point2::<T>::KIND);
+                p.encode(w);
+            }
+            Geometry2::Polyline(l) => {
+                w.bytes.push(Polyline2::<T>::KIND);
+                l.encode(w);
+            }
+            Geometry2::Polygon(p) => {
+                w.bytes.push(Polygon2::<T>::KIND);
+                p.encode(w);
+            }
+            Geometry2::MultiPoint(m) => {
+                w.bytes.push(MultiPoint2::<T>::KIND);
+                m.encode(w);
+            }
+            Geometry2::MultiPolyline(m) => {
+                w.bytes.push(MultiPolyline2::<T>::KIND);
+                m.encode(w);
+            }
+            Geometry2::MultiPolygon(m) => {
+                w.bytes.push(MultiPolygon2::<T>::KIND);
+                m.encode(w);
+            }
+            Geometry2::Collection(c) => {
+                w.bytes.push(Self::KIND);
+                w.all(c.geometries());
+            }
+        }
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        Some(match r.byte()? {
+            k if k == Point2::<T>::KIND => Geometry2::Point(Point2::decode(r)?),
+            k if k == Polyline2::<T>::KIND => Geometry2::Polyline(Polyline2::decode(r)?),
+            k if k == Polygon2::<T>::KIND => Geometry2::Polygon(Polygon2::decode(r)?),
+            k if k == MultiPoint2::<T>::KIND => Geometry2::MultiPoint(MultiPoint2::decode(r)?),
+            k if k == MultiPolyline2::<T>::KIND => Geometry2::MultiPolyline(MultiPolyline2::decode(r)?),
+            k if k == MultiPolygon2::<T>::KIND => Geometry2::MultiPolygon(MultiPolygon2::decode(r)?),
+            k if k == Self::KIND => Geometry2::Collection(GeometryCollection2::new(r.all()?)),
+            _ => return None,
+        })
+    }
+}
+
+/// Vertices, index triples, then a flag and one entry per triangle for the
+/// uvs and for the normals, both stored as f64
+impl<T: Float> Codec for TriMesh<T> {
+    const KIND: u8 = 19;
+
+    fn encode(&self, w: &mut Writer) {
+        w.points3(self.vertices());
+        w.count(self.triangles().len());
+        self.triangles().iter().flatten().for_each(|&i| w.count(i));
+        match self.uvs() {
+            Some(uvs) => {
+                w.bytes.push(1);
+                uvs.iter().flatten().for_each(|p| {
+                    w.scalar(p.0.to_f64());
+                    w.scalar(p.1.to_f64());
+                });
+            }
+            None => w.bytes.push(0),
+        }
+        match self.normals() {
+            Some(normals) => {
+                w.bytes.push(1);
+                normals.iter().flatten().for_each(|n| n.encode(w));
+            }
+            None => w.bytes.push(0),
+        }
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        let vertices = r.points3()?;
+        let n = r.count()?;
+        let triangles: Vec<[usize; 3]> = (0..n)
+            .map(|_| {
+                let t = [r.index()?, r.index()?, r.index()?];
+                t.iter().all(|&i| i < vertices.len()).then_some(t)
+            })
+            .collect::<Option<_>>()?;
+        let mut mesh = TriMesh::new(vertices, triangles);
+        if r.byte()? == 1 {
+            let uv = |r: &mut Reader| Some(Point2(r.real()?, r.real()?));
+            let uvs = (0..n).map(|_| Some([uv(r)?, uv(r)?, uv(r)?])).collect::<Option<_>>()?;
+            mesh = mesh.with_uvs(uvs);
+        }
+        if r.byte()? == 1 {
+            let normals = (0..n).map(|_| Some([Vector3::decode(r)?, Vector3::decode(r)?, Vector3::decode(r)?])).collect::<Option<_>>()?;
+            mesh = mesh.with_normals(normals);
+        }
+        Some(mesh)
+    }
+}
+
+/// Parent, position, (triangle, corner) pairs, then the faces' indices
+impl<T: Float> Codec for VertexSplit<T> {
+    const KIND: u8 = 20;
+
+    fn encode(&self, w: &mut Writer) {
+        w.count(self.parent);
+        w.point3(self.position);
+        w.count(self.corners.len());
+        self.corners.iter().for_each(|&(t, k)| {
+            w.count(t);
+            w.count(k);
+        });
+        w.count(self.faces.len());
+        self.faces.iter().flatten().for_each(|&i| w.count(i));
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        let parent = r.index()?;
+        let position = r.point3()?;
+        let corners = (0..r.count()?).map(|_| Some((r.index()?, r.index()?))).collect::<Option<_>>()?;
+        let faces = (0..r.count()?).map(|_| Some([r.index()?, r.index()?, r.index()?])).collect::<Option<_>>()?;
+        Some(VertexSplit { parent, position, corners, faces })
+    }
+}
+
+/// Base mesh as a `TriMesh` body, the splits, then the level; every split
+/// is checked against the mesh before it
+impl<T: Float> Codec for ProgressiveMesh<T> {
+    const KIND: u8 = 21;
+
+    fn encode(&self, w: &mut Writer) {
+        self.base().encode(w);
+        w.all(self.splits());
+        w.count(self.level());
+    }
+
+    fn decode(r: &mut Reader) -> Option<Self> {
+        let mut mesh = ProgressiveMesh::new(TriMesh::decode(r)?);
+        for split in r.all::<VertexSplit<T>>()? {
+            mesh.push(split).then_some(())?;
+        }
+        let level = r.index()?;
+        (mesh.refine(level) == level).then_some(mesh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let ring = vec![Point2(0.0, 0.0), Point2(4.0, 0.0), Point2(4.0, 3.0), Point2(0.0, 3.0)];
+        let hole = vec![Point2(1.0, 1.0), Point2(1.0, 2.0), Point2(2.0, 2.0)];
+        let polygon = Polygon2::with_holes(ring.clone(), vec![hole]);
+        assert_eq!(Polygon2::from_bytes(&polygon.to_bytes(Compression::None)), Some(polygon.clone()));
+        let circle = Circle2::new(Point2(1.5, -2.25), 0.5);
+        assert_eq!(Circle2::from_bytes(&circle.to_bytes(Compression::None)), Some(circle));
+        let geometry = Geometry2::Collection(GeometryCollection2::new(vec![
+            Geometry2::Point(Point2(7.0, 7.0)),
+            Geometry2::MultiPolygon(MultiPolygon2::new(vec![polygon])),
+            Geometry2::Polyline(Polyline2::new(ring)),
+        ]));
+        let bytes = geometry.to_bytes(Compression::Delta { step: 0.5 });
+        assert_eq!(Geometry2::from_bytes(&bytes), Some(geometry));
+        let mesh = TriMesh::new(
+            vec![Point3(0.0, 0.0, 0.0), Point3(1.0, 0.0, 0.0), Point3(0.0, 1.0, 0.0), Point3(0.0, 0.0, 1.0)],
+            vec![[0, 2, 1], [0, 1, 3]],
+        )
+        .with_normals(vec![[Vector3(0.0, 0.0, -1.0); 3], [Vector3(0.0, -1.0, 0.0); 3]]);
+        assert_eq!(TriMesh::from_bytes(&mesh.to_bytes(Compression::Delta { step: 1e-3 })), Some(mesh));
+        let single: Point3<f32> = Point3(1.0, 2.0, 3.0);
+        assert_eq!(Point3::<f32>::from_bytes(&single.to_bytes(Compression::None)), Some(single));
+    }
+
+    #[test]
+    fn compression_and_rejection() {
+        // a dense track: deltas of a few steps take a byte or two per axis
+        let track = Polyline2::new((0..1000).map(|i| Point2(1000.0 + i as f64 * 0.01, (i as f64 * 0.01).sin())).collect());
+        let raw = track.to_bytes(Compression::None);
+        let packed = track.to_bytes(Compression::Delta { step: 1e-4 });
+        assert!(packed.len() * 4 < raw.len());
+        let back = Polyline2::from_bytes(&packed).unwrap();
+        assert!(back.vertices().iter().zip(track.vertices()).all(|(a, b)| a.distance(*b) <= 1e-4));
+        // wrong kind, truncated, trailing bytes, newer version, bad index
+        assert!(Polygon2::<f64>::from_bytes(&raw).is_none());
+        assert!(Polyline2::<f64>::from_bytes(&raw[..raw.len() - 1]).is_none());
+        assert!(Polyline2::<f64>::from_bytes(&[raw.as_slice(), &[0]].concat()).is_none());
+        let mut newer = raw.clone();
+        newer[3] = VERSION + 1;
+        assert!(Polyline2::<f64>::from_bytes(&newer).is_none());
+        let mut mesh = TriMesh::new(vec![Point3(0.0, 0.0, 0.0); 3], vec![[0, 1, 2]]).to_bytes(Compression::None);
+        let at = mesh.len() - 4;
+        mesh[at] = 3;
+        assert!(TriMesh::<f64>::from_bytes(&mesh).is_none());
+    }
+}
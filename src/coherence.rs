@@ -0,0 +1,198 @@
+//! Warm starts for collision queries repeated frame after frame
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::contact::{ counter_clockwise, counter_clockwise_edge, edge_separation, is_counter_clockwise, max_separation, touching };
+use crate::solid::overlap_from;
+use crate::{ Float, Manifold2, Polygon2, Support3, Vector3 };
+
+/// GJK queries on pairs of convex shapes, each started from the direction
+/// its pair last ended on: a pair still apart along it is settled by the
+/// first support points
+#[derive(Debug, Clone)]
+pub struct GjkCache3<K, T> {
+    axes: HashMap<(K, K), Vector3<T>>,
+}
+
+/// SAT queries on pairs of convex polygons, each trying first the face that
+/// last separated its pair
+#[derive(Debug, Clone)]
+pub struct SatCache2<K> {
+    /// whether the face is of the second polygon, and its index
+    faces: HashMap<(K, K), (bool, usize)>,
+}
+
+impl<K: Eq + Hash, T: Float> GjkCache3<K, T> {
+    /// new: () -> GjkCache
+    pub fn new() -> Self {
+        GjkCache3 { axes: HashMap::new() }
+    }
+
+    /// len: &GjkCache -> usize
+    /// pairs remembered
+    pub fn len(&self) -> usize {
+        self.axes.len()
+    }
+
+    /// is_empty: &GjkCache -> bool
+    pub fn is_empty(&self) -> bool {
+        self.axes.is_empty()
+    }
+
+    /// intersects: &mut GjkCache × (Key, Key) × &Shape × &Shape -> bool
+    /// touching counts; the pair is keyed as given, in order
+    pub fn intersects<A: Support3<T>, B: Support3<T>>(&mut self, pair: (K, K), a: &A, b: &B) -> bool {
+        let start = self.axes.get(&pair).copied().unwrap_or(Vector3(T::one(), T::zero(), T::zero()));
+        let (hit, axis) = overlap_from(|d| a.support(d), |d| b.support(d), start);
+        if axis.dot(axis) > T::zero() {
+            self.axes.insert(pair, axis);
+        }
+        hit
+    }
+
+    /// forget: &mut GjkCache × &(Key, Key)
+    /// drops a pair, as when the broad phase stops reporting it
+    pub fn forget(&mut self, pair: &(K, K)) {
+        self.axes.remove(pair);
+    }
+
+    /// retain: &mut GjkCache × ((Key, Key) -> bool)
+    /// keeps the pairs for which `keep` holds
+    pub fn retain<F: FnMut(&(K, K)) -> bool>(&mut self, mut keep: F) {
+        self.axes.retain(|pair, _| keep(pair));
+    }
+}
+
+impl<K: Eq + Hash, T: Float> Default for GjkCache3<K, T> {
+    fn default() -> Self {
+        GjkCache3::new()
+    }
+}
+
+impl<K: Eq + Hash> SatCache2<K> {
+    /// new: () -> SatCache
+    pub fn new() -> Self {
+        SatCache2 { faces: HashMap::new() }
+    }
+
+    /// len: &SatCache -> usize
+    /// pairs remembered
+    pub fn len(&self) -> usize {
+        self.faces.len()
+    }
+
+    /// is_empty: &SatCache -> bool
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+
+    /// polygon_polygon: &mut SatCache × (Key, Key) × &Polygon × &Polygon -> Option<Manifold>
+    /// as `contact::polygon_polygon`, but None straight away while the
+    /// remembered face still separates the pair
+    pub fn polygon_polygon<T: Float>(&mut self, pair: (K, K), a: &Polygon2<T>, b: &Polygon2<T>) -> Option<Manifold2<T>> {
+        let (va, vb) = (a.vertices(), b.vertices());
+        if let Some(&(second, i)) = self.faces.get(&pair) {
+            let (face, other) = if second { (vb, va) } else { (va, vb) };
+            if i < face.len() && face.len() >= 3 {
+                let (p, q) = counter_clockwise_edge(face, is_counter_clockwise(face), i);
+                if edge_separation(p, q, other) > T::zero() {
+                    return None;
+                }
+            }
+        }
+        let (pa, pb) = (counter_clockwise(a), counter_clockwise(b));
+        if pa.len() < 3 || pb.len() < 3 {
+            self.faces.remove(&pair);
+            return None;
+        }
+        let (sa, sb) = (max_separation(&pa, &pb), max_separation(&pb, &pa));
+        if sa.1 > T::zero() || sb.1 > T::zero() {
+            self.faces.insert(pair, if sa.1 >= sb.1 { (false, sa.0) } else { (true, sb.0) });
+            return None;
+        }
+        self.faces.remove(&pair);
+        touching(&pa, &pb, sa, sb)
+    }
+
+    /// forget: &mut SatCache × &(Key, Key)
+    /// drops a pair, as when the broad phase stops reporting it
+    pub fn forget(&mut self, pair: &(K, K)) {
+        self.faces.remove(pair);
+    }
+
+    /// retain: &mut SatCache × ((Key, Key) -> bool)
+    /// keeps the pairs for which `keep` holds
+    pub fn retain<F: FnMut(&(K, K)) -> bool>(&mut self, mut keep: F) {
+        self.faces.retain(|pair, _| keep(pair));
+    }
+}
+
+impl<K: Eq + Hash> Default for SatCache2<K> {
+    fn default() -> Self {
+        SatCache2::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use crate::contact::polygon_polygon;
+    use crate::{ Aabb3, Point2, Point3, Sphere };
+
+    /// Counts the support points asked of a shape
+    struct Counted<'a, S>(S, &'a Cell<usize>);
+
+    impl<S: Support3<f64>> Support3<f64> for Counted<'_, S> {
+        fn support(&self, d: Vector3<f64>) -> Point3<f64> {
+            self.1.set(self.1.get() + 1);
+            self.0.support(d)
+        }
+    }
+
+    #[test]
+    fn gjk_warm_starts() {
+        let (calls, cold) = (Cell::new(0), Cell::new(0));
+        let mut cache = GjkCache3::new();
+        let b = Aabb3::new(Point3(-1.0, -1.0, -1.0), Point3(1.0, 1.0, 1.0));
+        // a ball passing over the box, sinking into it on the way
+        for frame in 0..40 {
+            let c = Point3(-4.0 + frame as f64 * 0.2, 2.4 - frame as f64 * 0.04, 0.3);
+            let s = Sphere::new(c, 0.5);
+            let before = (calls.get(), cold.get());
+            let hit = cache.intersects((1, 2), &Counted(s, &calls), &b);
+            assert_eq!(GjkCache3::new().intersects((1, 2), &Counted(s, &cold), &b), hit);
+            let exact = {
+                let q = Point3(c.0.clamp(-1.0, 1.0), c.1.clamp(-1.0, 1.0), c.2.clamp(-1.0, 1.0));
+                q.distance(c) <= 0.5
+            };
+            assert_eq!(hit, exact);
+            assert!(calls.get() - before.0 <= cold.get() - before.1);
+        }
+        // while the last axis still separates, one support point settles it
+        assert!(calls.get() * 3 < cold.get() * 2);
+        assert_eq!(cache.len(), 1);
+        cache.retain(|&(a, _)| a != 1);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn sat_warm_starts() {
+        let square = |x: f64, y: f64| Polygon2::new(vec![Point2(x, y), Point2(x + 1.0, y), Point2(x + 1.0, y + 1.0), Point2(x, y + 1.0)]);
+        let mut cache = SatCache2::new();
+        let a = square(0.0, 0.0);
+        for frame in 0..30 {
+            let mut b = square(3.0 - frame as f64 * 0.1, 0.5);
+            // clockwise every other frame: the remembered face must not depend on winding
+            if frame % 2 == 1 {
+                b = Polygon2::new(b.vertices().iter().rev().copied().collect());
+            }
+            let cached = cache.polygon_polygon(("a", "b"), &a, &b);
+            assert_eq!(cached, polygon_polygon(&a, &b));
+            assert_eq!(cache.len(), usize::from(cached.is_none()));
+        }
+        cache.forget(&("a", "b"));
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,388 @@
+//! Conics, ellipses and fitting them to scattered points
+
+use crate::anchor::impl_anchored;
+use crate::{ Float, Point2, Vector2 };
+
+/// 2d Conic: a x² + b xy + c y² + d x + e y + f = 0, coefficients in that order
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Conic2<T>([T; 6]);
+
+/// 2d Ellipse: center, semi-axes, and the angle of the first one from +x
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Ellipse2<T>(Point2<T>, Vector2<T>, T);
+
+/// Conic fitted to points, with each point's distance to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConicFit2<T> {
+    pub conic: Conic2<T>,
+    /// first-order distances, as `Conic2::distance`
+    pub residuals: Vec<T>,
+}
+
+/// Ellipse fitted to points, with each point's distance to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct EllipseFit2<T> {
+    pub ellipse: Ellipse2<T>,
+    /// first-order distances, as `Conic2::distance`
+    pub residuals: Vec<T>,
+}
+
+impl<T: Float> Conic2<T> {
+    /// new: [K; 6] -> Conic
+    pub fn new(coefficients: [T; 6]) -> Self {
+        Conic2(coefficients)
+    }
+
+    /// coefficients: &Conic -> [K; 6]
+    pub fn coefficients(&self) -> [T; 6] {
+        self.0
+    }
+
+    /// value: &Conic × Point -> K
+    /// zero on the conic
+    pub fn value(&self, p: Point2<T>) -> T {
+        let [a, b, c, d, e, f] = self.0;
+        (a * p.0 + b * p.1 + d) * p.0 + (c * p.1 + e) * p.1 + f
+    }
+
+    /// gradient: &Conic × Point -> Vector
+    pub fn gradient(&self, p: Point2<T>) -> Vector2<T> {
+        let [a, b, c, d, e, _] = self.0;
+        let two = T::from_f64(2.0);
+        Vector2(two * a * p.0 + b * p.1 + d, b * p.0 + two * c * p.1 + e)
+    }
+
+    /// distance: &Conic × Point -> K
+    /// first-order (Sampson) distance |value| / |gradient|, close to the
+    /// true one near the conic; zero on it
+    pub fn distance(&self, p: Point2<T>) -> T {
+        let (v, g) = (self.value(p).abs(), self.gradient(p).length());
+        if v == T::zero() {
+            T::zero()
+        } else if g == T::zero() {
+            T::infinity()
+        } else {
+            v / g
+        }
+    }
+
+    /// is_ellipse: &Conic -> bool
+    /// b² < 4ac, real or not
+    pub fn is_ellipse(&self) -> bool {
+        let [a, b, c, ..] = self.0;
+        b * b < T::from_f64(4.0) * a * c
+    }
+
+    /// ellipse: &Conic -> Option<Ellipse>
+    /// None unless a real, non-degenerate ellipse; the first semi-axis is
+    /// the major one, its angle in (-π/2, π/2]
+    pub fn ellipse(&self) -> Option<Ellipse2<T>> {
+        let [a, b, c, d, e, f] = self.0;
+        let (two, half) = (T::from_f64(2.0), T::from_f64(0.5));
+        let det = T::from_f64(4.0) * a * c - b * b;
+        if det <= T::zero() || !det.is_finite() {
+            return None;
+        }
+        let center = Point2((b * e - two * c * d) / det, (b * d - two * a * e) / det);
+        let f0 = f + (d * center.0 + e * center.1) * half;
+        let theta = b.atan2(a - c) * half;
+        let (s, co) = (theta.sin(), theta.cos());
+        let lu = a * co * co + b * co * s + c * s * s;
+        let lv = a + c - lu;
+        let (ru, rv) = (-f0 / lu, -f0 / lv);
+        if !(ru > T::zero() && rv > T::zero() && ru.is_finite() && rv.is_finite()) {
+            return None;
+        }
+        let (ru, rv) = (ru.sqrt(), rv.sqrt());
+        let ellipse = if ru >= rv {
+            Ellipse2(center, Vector2(ru, rv), theta)
+        } else {
+            let turned = if theta > T::zero() { theta - T::pi() * half } else { theta + T::pi() * half };
+            Ellipse2(center, Vector2(rv, ru), turned)
+        };
+        Some(ellipse)
+    }
+}
+
+impl<T: Float> Ellipse2<T> {
+    /// new: Point × Vector × K -> Ellipse
+    /// semi-axes along the angle and across it
+    pub fn new(center: Point2<T>, radii: Vector2<T>, angle: T) -> Self {
+        Ellipse2(center, radii, angle)
+    }
+
+    /// center: &Ellipse -> Point
+    pub fn center(&self) -> Point2<T> {
+        self.0
+    }
+
+    /// radii: &Ellipse -> Vector
+    pub fn radii(&self) -> Vector2<T> {
+        self.1
+    }
+
+    /// angle: &Ellipse -> K
+    pub fn angle(&self) -> T {
+        self.2
+    }
+
+    /// point_at: &Ellipse × K -> Point
+    /// at parameter angle t from the end of the first semi-axis
+    pub fn point_at(&self, t: T) -> Point2<T> {
+        let (u, v) = (Vector2(self.2.cos(), self.2.sin()), Vector2(-self.2.sin(), self.2.cos()));
+        self.0 + u * (self.1.0 * t.cos()) + v * (self.1.1 * t.sin())
+    }
+
+    /// area: &Ellipse -> K
+    pub fn area(&self) -> T {
+        T::pi() * self.1.0 * self.1.1
+    }
+
+    /// conic: &Ellipse -> Conic
+    /// zero on the ellipse, negative inside, -1 at the center
+    pub fn conic(&self) -> Conic2<T> {
+        let (s, c) = (self.2.sin(), self.2.cos());
+        let (ia, ib) = (T::one() / (self.1.0 * self.1.0), T::one() / (self.1.1 * self.1.1));
+        let two = T::from_f64(2.0);
+        let a = c * c * ia + s * s * ib;
+        let b = two * c * s * (ia - ib);
+        let cc = s * s * ia + c * c * ib;
+        let Point2(h, k) = self.0;
+        Conic2([a, b, cc, -two * a * h - b * k, -b * h - two * cc * k, a * h * h + b * h * k + cc * k * k - T::one()])
+    }
+}
+
+impl_anchored!(Ellipse2, |el, m| Ellipse2(m.point(el.0), Vector2(m.length(el.1.0), m.length(el.1.1)), m.angle(el.2)));
+
+/// fit_conic: [Point] -> Option<ConicFit>
+/// algebraic least squares: the unit coefficient vector, in coordinates
+/// centred and scaled to the points, with the least sum of squared values;
+/// None for fewer than 5 points
+pub fn fit_conic<T: Float>(points: &[Point2<T>]) -> Option<ConicFit2<T>> {
+    let (mean, scale, q) = normalized(points, 5)?;
+    let mut s = [[T::zero(); 6]; 6];
+    for p in &q {
+        let row = [p.0 * p.0, p.0 * p.1, p.1 * p.1, p.0, p.1, T::one()];
+        for i in 0..6 {
+            for j in 0..6 {
+                s[i][j] = s[i][j] + row[i] * row[j];
+            }
+        }
+    }
+    let (values, vectors) = symmetric_eigen(s);
+    let k = (0..6).fold(0, |k, i| if values[i] < values[k] { i } else { k });
+    let conic = denormalized(std::array::from_fn(|i| vectors[i][k]), mean, scale);
+    let residuals = points.iter().map(|&p| conic.distance(p)).collect();
+    Some(ConicFit2 { conic, residuals })
+}
+
+/// fit_ellipse: [Point] -> Option<EllipseFit>
+/// direct least squares (Fitzgibbon, in Halíř and Flusser's stable form):
+/// algebraic fit constrained to b² − 4ac = −1, so always an ellipse;
+/// None for fewer than 5 points or points on a line
+pub fn fit_ellipse<T: Float>(points: &[Point2<T>]) -> Option<EllipseFit2<T>> {
+    let (mean, scale, q) = normalized(points, 5)?;
+    let zero = T::zero();
+    // scatter of the quadratic part [x², xy, y²] and linear part [x, y, 1]
+    let (mut s1, mut s2, mut s3) = ([[zero; 3]; 3], [[zero; 3]; 3], [[zero; 3]; 3]);
+    for p in &q {
+        let (d1, d2) = ([p.0 * p.0, p.0 * p.1, p.1 * p.1], [p.0, p.1, T::one()]);
+        for i in 0..3 {
+            for j in 0..3 {
+                s1[i][j] = s1[i][j] + d1[i] * d1[j];
+                s2[i][j] = s2[i][j] + d1[i] * d2[j];
+                s3[i][j] = s3[i][j] + d2[i] * d2[j];
+            }
+        }
+    }
+    // linear part in terms of the quadratic one: a2 = t · a1
+    let inv = inverse3(s3)?;
+    let t: [[T; 3]; 3] = std::array::from_fn(|i| std::array::from_fn(|j| {
+        -(0..3).fold(zero, |acc, k| acc + inv[i][k] * s2[j][k])
+    }));
+    let m: [[T; 3]; 3] = std::array::from_fn(|i| std::array::from_fn(|j| {
+        s1[i][j] + (0..3).fold(zero, |acc, k| acc + s2[i][k] * t[k][j])
+    }));
+    // m a1 = λ c1 a1 with m symmetric and positive semi-definite: in the
+    // whitened basis of m it becomes a symmetric problem, the ellipse its
+    // one positive eigenvalue; a null vector of m is an exact fit
+    let c1 = [[zero, zero, T::from_f64(2.0)], [zero, -T::one(), zero], [T::from_f64(2.0), zero, zero]];
+    let constraint = |a: [T; 3]| T::from_f64(4.0) * a[0] * a[2] - a[1] * a[1];
+    let (values, vectors) = symmetric_eigen(m);
+    let largest = values.iter().fold(zero, |l, &v| if v > l { v } else { l });
+    let k = (0..3).fold(0, |k, i| if values[i] < values[k] { i } else { k });
+    let exact = std::array::from_fn(|i| vectors[i][k]);
+    let a1 = if values[k] <= largest * T::epsilon() * T::from_f64(64.0) && constraint(exact) > zero {
+        exact
+    } else {
+        if values[k] <= zero || !values[k].is_finite() {
+            return None;
+        }
+        let w: [[T; 3]; 3] = std::array::from_fn(|i| std::array::from_fn(|j| vectors[i][j] / values[j].sqrt()));
+        let kk: [[T; 3]; 3] = std::array::from_fn(|i| std::array::from_fn(|j| {
+            (0..3).fold(zero, |acc, r| acc + (0..3).fold(zero, |acc, s| acc + w[r][i] * c1[r][s] * w[s][j]))
+        }));
+        let (mus, bs) = symmetric_eigen(kk);
+        let top = (0..3).fold(0, |k, i| if mus[i] > mus[k] { i } else { k });
+        if mus[top] <= zero || !mus[top].is_finite() {
+            return None;
+        }
+        std::array::from_fn(|i| (0..3).fold(zero, |acc, j| acc + w[i][j] * bs[j][top]))
+    };
+    let a2: [T; 3] = std::array::from_fn(|i| (0..3).fold(zero, |acc, j| acc + t[i][j] * a1[j]));
+    let conic = denormalized([a1[0], a1[1], a1[2], a2[0], a2[1], a2[2]], mean, scale);
+    let ellipse = conic.ellipse()?;
+    let residuals = points.iter().map(|&p| conic.distance(p)).collect();
+    Some(EllipseFit2 { ellipse, residuals })
+}
+
+/// Points centred on their mean and scaled to unit root mean square
+/// distance, with that mean and scale; None for fewer than `least` points
+/// or all of them equal
+#[allow(clippy::type_complexity)]
+fn normalized<T: Float>(points: &[Point2<T>], least: usize) -> Option<(Point2<T>, T, Vec<Point2<T>>)> {
+    if points.len() < least {
+        return None;
+    }
+    let n = T::from_f64(points.len() as f64);
+    let sum = points.iter().fold(Vector2(T::zero(), T::zero()), |s, p| s + Vector2(p.0, p.1));
+    let mean = Point2(sum.0 / n, sum.1 / n);
+    let spread = points.iter().fold(T::zero(), |s, &p| {
+        let d = p.vector_from(mean);
+        s + d.dot(d)
+    });
+    let scale = (spread / n).sqrt();
+    if scale <= T::zero() || !scale.is_finite() {
+        return None;
+    }
+    let q = points.iter().map(|&p| {
+        let d = p.vector_from(mean) / scale;
+        Point2(d.0, d.1)
+    }).collect();
+    Some((mean, scale, q))
+}
+
+/// Conic in the original coordinates from one in centred, scaled ones
+fn denormalized<T: Float>(k: [T; 6], mean: Point2<T>, scale: T) -> Conic2<T> {
+    let [a, b, c, d, e, f] = k;
+    let two = T::from_f64(2.0);
+    let (a, b, c, d, e) = (a / (scale * scale), b / (scale * scale), c / (scale * scale), d / scale, e / scale);
+    let Point2(h, v) = mean;
+    Conic2([
+        a,
+        b,
+        c,
+        d - two * a * h - b * v,
+        e - two * c * v - b * h,
+        f + a * h * h + b * h * v + c * v * v - d * h - e * v,
+    ])
+}
+
+/// Inverse of a 3 × 3 matrix, None if singular
+pub(crate) fn inverse3<T: Float>(m: [[T; 3]; 3]) -> Option<[[T; 3]; 3]> {
+    let cof = |i: usize, j: usize| {
+        let (r0, r1) = ((i + 1) % 3, (i + 2) % 3);
+        let (c0, c1) = ((j + 1) % 3, (j + 2) % 3);
+        m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+    };
+    let det = m[0][0] * cof(0, 0) + m[0][1] * cof(0, 1) + m[0][2] * cof(0, 2);
+    if det == T::zero() || !det.is_finite() {
+        return None;
+    }
+    Some(std::array::from_fn(|i| std::array::from_fn(|j| cof(j, i) / det)))
+}
+
+/// Eigenvalues and eigenvectors (by columns) of a symmetric matrix, by
+/// cyclic Jacobi rotations
+pub(crate) fn symmetric_eigen<T: Float, const N: usize>(mut a: [[T; N]; N]) -> ([T; N], [[T; N]; N]) {
+    let mut v: [[T; N]; N] = std::array::from_fn(|i| std::array::from_fn(|j| if i == j { T::one() } else { T::zero() }));
+    for _ in 0..64 {
+        let off = (0..N).fold(T::zero(), |s, i| (0..N).filter(|&j| j != i).fold(s, |s, j| s + a[i][j] * a[i][j]));
+        let diagonal = (0..N).fold(T::zero(), |s, i| s + a[i][i] * a[i][i]);
+        if off <= diagonal * T::epsilon() * T::epsilon() {
+            break;
+        }
+        for p in 0..N {
+            for q in p + 1..N {
+                if a[p][q] == T::zero() {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (T::from_f64(2.0) * a[p][q]);
+                let t = T::one() / (theta.abs() + (theta * theta + T::one()).sqrt());
+                let t = if theta < T::zero() { -t } else { t };
+                let c = T::one() / (t * t + T::one()).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = c * kp - s * kq;
+                    row[q] = s * kp + c * kq;
+                }
+                let (rp, rq) = (a[p], a[q]);
+                a[p] = std::array::from_fn(|k| c * rp[k] - s * rq[k]);
+                a[q] = std::array::from_fn(|k| s * rp[k] + c * rq[k]);
+                for row in v.iter_mut() {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = c * kp - s * kq;
+                    row[q] = s * kp + c * kq;
+                }
+            }
+        }
+    }
+    (std::array::from_fn(|i| a[i][i]), v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(e: &Ellipse2<f64>, n: usize, noise: f64) -> Vec<Point2<f64>> {
+        let mut seed = 11u64;
+        let mut random = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+        };
+        (0..n).map(|k| e.point_at(k as f64 / n as f64 * 5.0) + Vector2(random(), random()) * noise).collect()
+    }
+
+    #[test]
+    fn ellipse_round_trip() {
+        let e = Ellipse2::new(Point2(3.0, -2.0), Vector2(5.0, 2.0), 0.4);
+        let conic = e.conic();
+        for t in [0.0, 1.0, 2.5] {
+            assert!(conic.value(e.point_at(t)).abs() < 1e-12);
+        }
+        assert!(conic.value(e.center()) < 0.0 && conic.is_ellipse());
+        let back = conic.ellipse().unwrap();
+        assert!(back.center().distance(e.center()) < 1e-12);
+        assert!((back.radii() - e.radii()).length() < 1e-12 && (back.angle() - 0.4).abs() < 1e-12);
+        // the same ellipse listed by its minor axis
+        let minor = Ellipse2::new(Point2(0.0, 0.0), Vector2(1.0, 3.0), 0.2).conic().ellipse().unwrap();
+        assert!((minor.radii() - Vector2(3.0, 1.0)).length() < 1e-12);
+        assert!((minor.angle() - (0.2 - std::f64::consts::FRAC_PI_2)).abs() < 1e-12);
+        assert!(Conic2::new([1.0, 0.0, -1.0, 0.0, 0.0, -1.0]).ellipse().is_none());
+        assert!(Conic2::new([1.0, 0.0, 1.0, 0.0, 0.0, 1.0]).ellipse().is_none());
+    }
+
+    #[test]
+    fn fits() {
+        let e = Ellipse2::new(Point2(100.0, 40.0), Vector2(8.0, 3.0), -1.0);
+        // exact points on an arc of the ellipse
+        let fit = fit_ellipse(&sample(&e, 12, 0.0)).unwrap();
+        assert!(fit.ellipse.center().distance(e.center()) < 1e-8);
+        assert!((fit.ellipse.radii() - e.radii()).length() < 1e-8);
+        assert!(fit.residuals.iter().all(|&r| r < 1e-8));
+        // noisy ones
+        let fit = fit_ellipse(&sample(&e, 200, 0.05)).unwrap();
+        assert!(fit.ellipse.center().distance(e.center()) < 0.1);
+        assert!((fit.ellipse.radii() - e.radii()).length() < 0.2);
+        assert!((fit.ellipse.angle() - e.angle()).abs() < 0.05);
+        assert!(fit.residuals.iter().all(|&r| r < 0.2));
+        // a general conic: a hyperbola through its points
+        let hyperbola: Vec<Point2<f64>> = (1..8).map(|k| Point2(k as f64, 3.0 / k as f64)).collect();
+        let fit = fit_conic(&hyperbola).unwrap();
+        assert!(!fit.conic.is_ellipse());
+        assert!(fit.residuals.iter().all(|&r| r < 1e-9));
+        assert!(fit_ellipse(&[Point2(0.0, 0.0); 6]).is_none());
+        assert!(fit_conic(&hyperbola[..4]).is_none());
+    }
+}
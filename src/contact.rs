@@ -0,0 +1,237 @@
+//! Contact manifolds between overlapping 2d shapes
+
+use std::borrow::Cow;
+
+use crate::{ Circle2, Closest2, Float, Point2, Polygon2, Vector2 };
+
+/// How two shapes overlap: the direction to push them apart and where
+#[derive(Debug, Clone, PartialEq)]
+pub struct Manifold2<T> {
+    /// unit normal from the first shape toward the second
+    pub normal: Vector2<T>,
+    /// one or two contacts
+    pub points: Vec<ManifoldPoint2<T>>,
+}
+
+/// One contact of a manifold
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ManifoldPoint2<T> {
+    /// midway between the two surfaces
+    pub point: Point2<T>,
+    /// overlap along the normal, zero when touching
+    pub depth: T,
+}
+
+/// circle_circle: &Circle × &Circle -> Option<Manifold>
+/// None when apart; concentric circles are pushed apart along x
+pub fn circle_circle<T: Float>(a: &Circle2<T>, b: &Circle2<T>) -> Option<Manifold2<T>> {
+    let d = b.center().vector_from(a.center());
+    let l = d.length();
+    let depth = a.radius() + b.radius() - l;
+    if depth < T::zero() {
+        return None;
+    }
+    let normal = if l > T::zero() { d / l } else { Vector2(T::one(), T::zero()) };
+    let (pa, pb) = (a.center() + normal * a.radius(), b.center() - normal * b.radius());
+    Some(Manifold2 { normal, points: vec![ManifoldPoint2 { point: midpoint(pa, pb), depth }] })
+}
+
+/// circle_polygon: &Circle × &Polygon -> Option<Manifold>
+/// against the exterior of a convex polygon of either orientation; None when apart
+pub fn circle_polygon<T: Float>(a: &Circle2<T>, b: &Polygon2<T>) -> Option<Manifold2<T>> {
+    let ring = counter_clockwise(b);
+    let (c, r) = (a.center(), a.radius());
+    let (face, separation) = (0..ring.len())
+        .map(|i| (i, outward(&ring, i).dot(c.vector_from(ring[i]))))
+        .fold(None, |best: Option<(usize, T)>, f| if best.is_some_and(|b| b.1 >= f.1) { best } else { Some(f) })?;
+    if separation <= T::zero() {
+        // center inside: out through the nearest face
+        let n = outward(&ring, face);
+        let depth = r - separation;
+        let point = midpoint(c - n * r, c - n * separation);
+        return Some(Manifold2 { normal: -n, points: vec![ManifoldPoint2 { point, depth }] });
+    }
+    let q = b.closest_point_on(c);
+    let l = q.distance(c);
+    if l > r {
+        return None;
+    }
+    let normal = q.vector_from(c) / l;
+    Some(Manifold2 { normal, points: vec![ManifoldPoint2 { point: midpoint(c + normal * r, q), depth: r - l }] })
+}
+
+/// polygon_polygon: &Polygon × &Polygon -> Option<Manifold>
+/// between the exteriors of two convex polygons of either orientation: the
+/// face of least penetration is the reference, and the most opposed face of
+/// the other polygon, clipped to its sides, gives up to two contacts.
+/// None when apart
+pub fn polygon_polygon<T: Float>(a: &Polygon2<T>, b: &Polygon2<T>) -> Option<Manifold2<T>> {
+    let (pa, pb) = (counter_clockwise(a), counter_clockwise(b));
+    if pa.len() < 3 || pb.len() < 3 {
+        return None;
+    }
+    let (sa, sb) = (max_separation(&pa, &pb), max_separation(&pb, &pa));
+    touching(&pa, &pb, sa, sb)
+}
+
+/// Manifold of two counter-clockwise convex rings given the most separating
+/// face of each and its gap; None when either gap is positive
+pub(crate) fn touching<T: Float>(pa: &[Point2<T>], pb: &[Point2<T>], (fa, sa): (usize, T), (fb, sb): (usize, T)) -> Option<Manifold2<T>> {
+    if sa > T::zero() || sb > T::zero() {
+        return None;
+    }
+    // prefer a's face unless b's is clearly better, so the choice is stable
+    let extent = pa.iter().chain(pb).fold(T::zero(), |m, p| {
+        let k = if p.0.abs() > p.1.abs() { p.0.abs() } else { p.1.abs() };
+        if k > m { k } else { m }
+    });
+    let tolerance = extent * T::epsilon() * T::from_f64(64.0);
+    let flip = sb > sa * T::from_f64(0.98) + tolerance;
+    let (reference, incident, face) = if flip { (pb, pa, fb) } else { (pa, pb, fa) };
+    let n = outward(reference, face);
+    let (r1, r2) = (reference[face], reference[(face + 1) % reference.len()]);
+    let k = (0..incident.len())
+        .map(|i| (i, outward(incident, i).dot(n)))
+        .fold(None, |best: Option<(usize, T)>, f| if best.is_some_and(|b| b.1 <= f.1) { best } else { Some(f) })?
+        .0;
+    let mut edge = vec![incident[k], incident[(k + 1) % incident.len()]];
+    let t = r2.vector_from(r1);
+    edge = clip(&edge, t, r1);
+    edge = clip(&edge, -t, r2);
+    let points: Vec<_> = edge
+        .into_iter()
+        .filter_map(|p| {
+            let s = n.dot(p.vector_from(r1));
+            (s <= T::zero()).then_some(ManifoldPoint2 { point: p - n * (s / T::from_f64(2.0)), depth: -s })
+        })
+        .collect();
+    if points.is_empty() {
+        return None;
+    }
+    Some(Manifold2 { normal: if flip { -n } else { n }, points })
+}
+
+/// Exterior of a polygon, counter-clockwise, borrowed when it already is
+pub(crate) fn counter_clockwise<T: Float>(p: &Polygon2<T>) -> Cow<'_, [Point2<T>]> {
+    let ring = p.vertices();
+    if is_counter_clockwise(ring) {
+        Cow::Borrowed(ring)
+    } else {
+        Cow::Owned(ring.iter().rev().copied().collect())
+    }
+}
+
+/// Whether a ring's signed area is not negative
+pub(crate) fn is_counter_clockwise<T: Float>(ring: &[Point2<T>]) -> bool {
+    let n = ring.len();
+    let twice = (0..n).fold(T::zero(), |s, i| {
+        let (p, q) = (ring[i], ring[(i + 1) % n]);
+        s + p.0 * q.1 - q.0 * p.1
+    });
+    twice >= T::zero()
+}
+
+/// Edge i of a ring as it runs counter-clockwise, without copying the ring
+pub(crate) fn counter_clockwise_edge<T: Float>(ring: &[Point2<T>], ccw: bool, i: usize) -> (Point2<T>, Point2<T>) {
+    let n = ring.len();
+    if ccw { (ring[i], ring[(i + 1) % n]) } else { (ring[n - 1 - i], ring[(2 * n - 2 - i) % n]) }
+}
+
+/// Unit outward normal of edge i of a counter-clockwise ring
+fn outward<T: Float>(ring: &[Point2<T>], i: usize) -> Vector2<T> {
+    let d = ring[(i + 1) % ring.len()].vector_from(ring[i]);
+    Vector2(d.1, -d.0) / d.length()
+}
+
+/// Face of a whose outward normal separates b the most, and by how much;
+/// negative when they overlap along every face
+pub(crate) fn max_separation<T: Float>(a: &[Point2<T>], b: &[Point2<T>]) -> (usize, T) {
+    (0..a.len())
+        .map(|i| (i, separation(a, b, i)))
+        .fold((0, -T::infinity()), |best, f| if f.1 > best.1 { f } else { best })
+}
+
+/// Gap between face i of a and the nearest point of b along its outward normal
+pub(crate) fn separation<T: Float>(a: &[Point2<T>], b: &[Point2<T>], i: usize) -> T {
+    edge_separation(a[i], a[(i + 1) % a.len()], b)
+}
+
+/// Gap between the edge p → q of a counter-clockwise ring and the nearest of
+/// the points along its outward normal
+pub(crate) fn edge_separation<T: Float>(p: Point2<T>, q: Point2<T>, points: &[Point2<T>]) -> T {
+    let d = q.vector_from(p);
+    let n = Vector2(d.1, -d.0) / d.length();
+    points.iter().map(|&x| n.dot(x.vector_from(p))).fold(T::infinity(), |m, s| if s < m { s } else { m })
+}
+
+/// Keeps the part of a segment where t · (p - o) ≥ 0
+fn clip<T: Float>(edge: &[Point2<T>], t: Vector2<T>, o: Point2<T>) -> Vec<Point2<T>> {
+    if edge.len() < 2 {
+        return edge.to_vec();
+    }
+    let side = |p: Point2<T>| t.dot(p.vector_from(o));
+    let (p, q) = (edge[0], edge[1]);
+    let (sp, sq) = (side(p), side(q));
+    let mut kept: Vec<Point2<T>> = [(p, sp), (q, sq)].into_iter().filter(|&(_, s)| s >= T::zero()).map(|(p, _)| p).collect();
+    if (sp < T::zero()) != (sq < T::zero()) {
+        kept.push(p + q.vector_from(p) * (sp / (sp - sq)));
+    }
+    kept
+}
+
+/// Point halfway from a to b
+fn midpoint<T: Float>(a: Point2<T>, b: Point2<T>) -> Point2<T> {
+    a + b.vector_from(a) * T::from_f64(0.5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f64, y: f64, w: f64, h: f64) -> Polygon2<f64> {
+        Polygon2::new(vec![Point2(x, y), Point2(x + w, y), Point2(x + w, y + h), Point2(x, y + h)])
+    }
+
+    fn near(a: Point2<f64>, b: Point2<f64>) -> bool {
+        a.distance(b) < 1e-12
+    }
+
+    #[test]
+    fn circles() {
+        let m = circle_circle(&Circle2::new(Point2(0.0, 0.0), 1.0), &Circle2::new(Point2(1.5, 0.0), 1.0)).unwrap();
+        assert_eq!(m.normal, Vector2(1.0, 0.0));
+        assert_eq!(m.points, vec![ManifoldPoint2 { point: Point2(0.75, 0.0), depth: 0.5 }]);
+        assert!(circle_circle(&Circle2::new(Point2(0.0, 0.0), 1.0), &Circle2::new(Point2(0.0, 2.5), 1.0)).is_none());
+        let s = square(0.0, 0.0, 2.0, 2.0);
+        let above = circle_polygon(&Circle2::new(Point2(1.0, 2.5), 1.0), &s).unwrap();
+        assert_eq!(above.normal, Vector2(0.0, -1.0));
+        assert!(near(above.points[0].point, Point2(1.0, 1.75)) && above.points[0].depth == 0.5);
+        let inside = circle_polygon(&Circle2::new(Point2(1.0, 1.8), 0.5), &s).unwrap();
+        assert_eq!(inside.normal, Vector2(0.0, -1.0));
+        assert!((inside.points[0].depth - 0.7).abs() < 1e-12);
+        let corner = circle_polygon(&Circle2::new(Point2(2.5, 2.5), 1.0), &s).unwrap();
+        assert!((corner.normal - Vector2(-1.0, -1.0) / 2f64.sqrt()).length() < 1e-12);
+        assert!(circle_polygon(&Circle2::new(Point2(2.8, 2.8), 1.0), &s).is_none());
+    }
+
+    #[test]
+    fn polygons() {
+        let a = square(0.0, 0.0, 2.0, 2.0);
+        let m = polygon_polygon(&a, &square(1.0, 1.5, 2.0, 2.0)).unwrap();
+        assert_eq!(m.normal, Vector2(0.0, 1.0));
+        assert_eq!(m.points.len(), 2);
+        assert!(m.points.iter().all(|c| c.depth == 0.5));
+        assert!(m.points.iter().any(|c| near(c.point, Point2(1.0, 1.75))) && m.points.iter().any(|c| near(c.point, Point2(2.0, 1.75))));
+        // b's face is the reference: the normal still runs from a to b
+        let flipped = polygon_polygon(&square(1.0, 1.5, 2.0, 2.0), &a).unwrap();
+        assert_eq!(flipped.normal, Vector2(0.0, -1.0));
+        let diamond = Polygon2::new(vec![Point2(1.0, 1.8), Point2(2.0, 2.8), Point2(1.0, 3.8), Point2(0.0, 2.8)]);
+        let tip = polygon_polygon(&a, &diamond).unwrap();
+        assert_eq!(tip.normal, Vector2(0.0, 1.0));
+        assert_eq!(tip.points.len(), 1);
+        assert!(near(tip.points[0].point, Point2(1.0, 1.9)) && (tip.points[0].depth - 0.2).abs() < 1e-12);
+        let cw = Polygon2::new(diamond.vertices().iter().rev().copied().collect());
+        assert_eq!(polygon_polygon(&a, &cw).unwrap().points.len(), 1);
+        assert!(polygon_polygon(&a, &square(2.5, 0.0, 1.0, 1.0)).is_none());
+    }
+}
@@ -0,0 +1,287 @@
+//! Repeated point-in-polygon queries
+//!
+//! A `ContainmentGrid2` buckets the edges of one polygon by horizontal rows,
+//! so a query counts crossings only among the few edges of its row instead
+//! of all of them. Rows are the leaves of a binary tree of bands, and an
+//! edge is listed in the few largest bands inside its span, so a tall edge
+//! costs a logarithmic number of entries rather than one per row; a query
+//! reads the bands from its row up to the root. Build it once to classify
+//! many points against the same large polygon, such as points against
+//! country borders; `classify_points` does so for a whole batch, and
+//! `classify_points_par` in parallel with the `parallel` feature.
+
+use std::cmp::Ordering;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::predicates::orient2d;
+use crate::{ FillRule, Float, MultiPolygon2, Point2, Polygon2 };
+
+/// Where a point lies relative to a region
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Containment {
+    /// in the interior
+    Inside,
+    /// exactly on an edge or vertex
+    Boundary,
+    /// in the exterior
+    Outside,
+}
+
+/// Regions whose points can be classified in batches
+pub trait Classify2<T: Float> {
+    /// containment_grid: &Region -> ContainmentGrid
+    /// rings oriented so that, filled non-zero, holes are holes whatever
+    /// their orientation and overlapping members count once
+    fn containment_grid(&self) -> ContainmentGrid2<T>;
+}
+
+/// Polygon edges bucketed per row band, for fast containment queries
+#[derive(Debug, Clone)]
+pub struct ContainmentGrid2<T> {
+    min_y: T,
+    row_height: T,
+    rows: usize,
+    /// band b holds `edges[starts[b]..starts[b + 1]]`: band 1 spans every
+    /// row, band b splits into 2b and 2b + 1, and row r is band `leaves + r`
+    starts: Vec<usize>,
+    edges: Vec<(Point2<T>, Point2<T>)>,
+}
+
+impl<T: Float> ContainmentGrid2<T> {
+    /// new: &Polygon -> ContainmentGrid
+    /// about one row per edge, each edge listed in the largest bands
+    /// covering the rows it spans; rings kept as they are wound
+    pub fn new(polygon: &Polygon2<T>) -> Self {
+        Self::from_edges(polygon.rings().flat_map(|ring| ring_edges(ring, false)).collect())
+    }
+
+    fn from_edges(all: Vec<(Point2<T>, Point2<T>)>) -> Self {
+        let (mut min_y, mut max_y) = (T::infinity(), -T::infinity());
+        for (a, _) in &all {
+            min_y = if a.1 < min_y { a.1 } else { min_y };
+            max_y = if a.1 > max_y { a.1 } else { max_y };
+        }
+        let rows = all.len().max(1);
+        let bands = 2 * rows.next_power_of_two();
+        let span = max_y - min_y;
+        let row_height = if span > T::zero() { span / T::from_f64(rows as f64) } else { T::one() };
+        let mut grid = ContainmentGrid2 { min_y, row_height, rows, starts: vec![0; bands + 1], edges: Vec::new() };
+        if all.is_empty() {
+            return grid;
+        }
+        let spans: Vec<(usize, usize)> = all.iter().map(|(a, b)| {
+            let (lo, hi) = if a.1 < b.1 { (a.1, b.1) } else { (b.1, a.1) };
+            (grid.row(lo), grid.row(hi))
+        }).collect();
+        for &(r0, r1) in &spans {
+            covering(bands / 2, r0, r1, |b| grid.starts[b + 1] += 1);
+        }
+        for b in 0..bands {
+            grid.starts[b + 1] += grid.starts[b];
+        }
+        let mut next = grid.starts.clone();
+        let zero = Point2(T::zero(), T::zero());
+        grid.edges = vec![(zero, zero); grid.starts[bands]];
+        for (&edge, &(r0, r1)) in all.iter().zip(&spans) {
+            covering(bands / 2, r0, r1, |b| {
+                grid.edges[next[b]] = edge;
+                next[b] += 1;
+            });
+        }
+        grid
+    }
+
+    /// Edges of the bands holding row r, from the row up
+    fn row_edges(&self, r: usize) -> impl Iterator<Item = &(Point2<T>, Point2<T>)> + '_ {
+        let leaves = (self.starts.len() - 1) / 2;
+        std::iter::successors(Some(leaves + r), |&b| (b > 1).then_some(b / 2))
+            .flat_map(move |b| &self.edges[self.starts[b]..self.starts[b + 1]])
+    }
+
+    /// Row of a height, clamped to the grid
+    fn row(&self, y: T) -> usize {
+        let r = ((y - self.min_y) / self.row_height).floor().to_f64();
+        if r <= 0.0 { 0 } else { (r as usize).min(self.rows - 1) }
+    }
+
+    /// rows: &ContainmentGrid -> usize
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// winding_number: &ContainmentGrid × Point -> i32
+    /// as `Polygon2::winding_number`, from the edges of p's row only
+    pub fn winding_number(&self, p: Point2<T>) -> i32 {
+        if self.edges.is_empty() || p.1 < self.min_y {
+            return 0;
+        }
+        self.row_edges(self.row(p.1)).map(|&(a, b)| {
+            let side = (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0);
+            if a.1 <= p.1 && p.1 < b.1 && side > T::zero() {
+                1
+            } else if b.1 <= p.1 && p.1 < a.1 && side < T::zero() {
+                -1
+            } else {
+                0
+            }
+        }).sum()
+    }
+
+    /// contains: &ContainmentGrid × Point × FillRule -> bool
+    /// the same answer as `Polygon2::contains`
+    pub fn contains(&self, p: Point2<T>, rule: FillRule) -> bool {
+        rule.is_filled(self.winding_number(p))
+    }
+
+    /// classify: &ContainmentGrid × Point × FillRule -> Containment
+    /// as `contains`, with points exactly on an edge told apart
+    pub fn classify(&self, p: Point2<T>, rule: FillRule) -> Containment {
+        if self.edges.is_empty() || p.1 < self.min_y {
+            return Containment::Outside;
+        }
+        let on_edge = self.row_edges(self.row(p.1)).any(|&(a, b)| {
+            let within = |u: T, v: T, w: T| (u <= w && w <= v) || (v <= w && w <= u);
+            within(a.0, b.0, p.0) && within(a.1, b.1, p.1) && orient2d(a, b, p) == Ordering::Equal
+        });
+        if on_edge {
+            Containment::Boundary
+        } else if self.contains(p, rule) {
+            Containment::Inside
+        } else {
+            Containment::Outside
+        }
+    }
+}
+
+/// Calls `add` with the largest bands covering rows r0..=r1, given the
+/// first leaf band
+fn covering(leaves: usize, r0: usize, r1: usize, mut add: impl FnMut(usize)) {
+    let (mut lo, mut hi) = (leaves + r0, leaves + r1 + 1);
+    while lo < hi {
+        if lo % 2 == 1 {
+            add(lo);
+            lo += 1;
+        }
+        if hi % 2 == 1 {
+            hi -= 1;
+            add(hi);
+        }
+        lo /= 2;
+        hi /= 2;
+    }
+}
+
+/// Edges of a ring, reversed if `flip`
+fn ring_edges<T: Copy>(ring: &[Point2<T>], flip: bool) -> impl Iterator<Item = (Point2<T>, Point2<T>)> + '_ {
+    (0..ring.len()).map(move |i| {
+        let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+        if flip { (b, a) } else { (a, b) }
+    })
+}
+
+/// Edges of a polygon, its exterior counter-clockwise and holes clockwise
+fn oriented_edges<T: Float>(polygon: &Polygon2<T>) -> impl Iterator<Item = (Point2<T>, Point2<T>)> + '_ {
+    polygon.rings().enumerate().flat_map(|(k, ring)| {
+        let twice_area = ring_edges(ring, false).fold(T::zero(), |s, (a, b)| s + (a.0 * b.1 - b.0 * a.1));
+        // exterior first: flip it if clockwise, holes if counter-clockwise
+        ring_edges(ring, (twice_area < T::zero()) == (k == 0))
+    })
+}
+
+impl<T: Float> Classify2<T> for Polygon2<T> {
+    fn containment_grid(&self) -> ContainmentGrid2<T> {
+        ContainmentGrid2::from_edges(oriented_edges(self).collect())
+    }
+}
+
+impl<T: Float> Classify2<T> for MultiPolygon2<T> {
+    fn containment_grid(&self) -> ContainmentGrid2<T> {
+        ContainmentGrid2::from_edges(self.iter().flat_map(oriented_edges).collect())
+    }
+}
+
+/// classify_points: &Region × [Point] -> [Containment]
+/// every point against one containment grid of the region
+pub fn classify_points<T: Float, R: Classify2<T> + ?Sized>(region: &R, points: &[Point2<T>]) -> Vec<Containment> {
+    let grid = region.containment_grid();
+    points.iter().map(|&p| grid.classify(p, FillRule::NonZero)).collect()
+}
+
+/// classify_points_par: &Region × [Point] -> [Containment]
+/// as `classify_points`, in parallel
+#[cfg(feature = "parallel")]
+pub fn classify_points_par<T: Float + Send + Sync, R: Classify2<T> + ?Sized>(region: &R, points: &[Point2<T>]) -> Vec<Containment> {
+    let grid = region.containment_grid();
+    points.par_iter().with_min_len(1024).map(|&p| grid.classify(p, FillRule::NonZero)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_polygon() {
+        // a wobbly star with a square hole
+        let outer: Vec<Point2<f64>> = (0..200).map(|k| {
+            let a = k as f64 * std::f64::consts::TAU / 200.0;
+            let r = 10.0 + 3.0 * (7.0 * a).sin();
+            Point2(r * a.cos(), r * a.sin())
+        }).collect();
+        let hole = vec![Point2(-2.0, -2.0), Point2(-2.0, 2.0), Point2(2.0, 2.0), Point2(2.0, -2.0)];
+        let polygon = Polygon2::with_holes(outer, vec![hole]);
+        let grid = ContainmentGrid2::new(&polygon);
+        assert_eq!(grid.rows(), 204);
+        let mut seed = 7u64;
+        let mut inside = 0;
+        for _ in 0..2000 {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let x = (seed >> 11) as f64 / (1u64 << 53) as f64 * 30.0 - 15.0;
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let y = (seed >> 11) as f64 / (1u64 << 53) as f64 * 30.0 - 15.0;
+            let p = Point2(x, y);
+            assert_eq!(grid.winding_number(p), polygon.winding_number(p));
+            inside += grid.contains(p, FillRule::NonZero) as usize;
+        }
+        assert!(inside > 500 && inside < 1500);
+        // on vertices and edges the half-open rule is kept
+        for p in polygon.rings().flatten().chain(&[Point2(0.0, 2.0), Point2(-2.0, 0.0)]) {
+            assert_eq!(grid.winding_number(*p), polygon.winding_number(*p));
+        }
+    }
+
+    #[test]
+    fn degenerate() {
+        let empty = ContainmentGrid2::new(&Polygon2::<f64>::new(Vec::new()));
+        assert!(!empty.contains(Point2(0.0, 0.0), FillRule::NonZero));
+        let flat = ContainmentGrid2::new(&Polygon2::new(vec![Point2(0.0, 1.0), Point2(5.0, 1.0), Point2(9.0, 1.0)]));
+        assert!(!flat.contains(Point2(3.0, 1.0), FillRule::EvenOdd));
+        let square = Polygon2::new(vec![Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(1.0, 1.0), Point2(0.0, 1.0)]);
+        let grid = ContainmentGrid2::new(&square);
+        assert!(grid.contains(Point2(0.5, 0.5), FillRule::NonZero));
+        assert!(!grid.contains(Point2(0.5, -0.5), FillRule::NonZero) && !grid.contains(Point2(0.5, f64::NAN), FillRule::NonZero));
+        assert!(!grid.contains(Point2(0.5, 1.5), FillRule::NonZero));
+    }
+
+    #[test]
+    fn batches() {
+        // same-wound hole and an overlapping pair of squares
+        let square = |x: f64, y: f64, s: f64| vec![Point2(x, y), Point2(x + s, y), Point2(x + s, y + s), Point2(x, y + s)];
+        let framed = Polygon2::with_holes(square(0.0, 0.0, 4.0), vec![square(1.0, 1.0, 2.0)]);
+        let region = MultiPolygon2::new(vec![framed, Polygon2::new(square(3.0, 3.0, 2.0)), Polygon2::new(square(4.5, 0.0, 1.0))]);
+        let points = [Point2(0.5, 0.5), Point2(2.0, 2.0), Point2(3.5, 3.5), Point2(4.0, 4.5), Point2(4.5, 0.5), Point2(6.0, 6.0), Point2(1.0, 2.0)];
+        use Containment::*;
+        assert_eq!(classify_points(&region, &points), [Inside, Outside, Inside, Inside, Boundary, Outside, Boundary]);
+        let many: Vec<Point2<f64>> = (0..5000).map(|k| Point2((k % 70) as f64 * 0.1 - 0.5, (k / 70) as f64 * 0.1 - 0.5)).collect();
+        let grid = region.containment_grid();
+        let classes = classify_points(&region, &many);
+        for (p, c) in many.iter().zip(&classes) {
+            assert_eq!(*c, grid.classify(*p, FillRule::NonZero));
+        }
+        assert!(classes.contains(&Boundary));
+        assert_eq!(classify_points(&MultiPolygon2::new(Vec::new()), &points), [Outside; 7]);
+        #[cfg(feature = "parallel")]
+        assert_eq!(classify_points_par(&region, &many), classes);
+    }
+}
@@ -0,0 +1,156 @@
+//! Handedness and up-axis conventions of other coordinate systems
+//!
+//! Geometry here is in plain coordinates where x × y = z. A convention
+//! names which of those axes another system calls right, up and forward:
+//! right is always +x, up is +y or +z, and forward is whichever remaining
+//! direction makes right × forward = up when right-handed, −up when
+//! left-handed. So right-handed y-up looks along −z (OpenGL, glTF),
+//! left-handed y-up along +z (Direct3D, Unity) and right-handed z-up along
+//! +y (Blender).
+
+use crate::{ Affine3, Float, Rotor3, Vector3 };
+
+/// Orientation of a coordinate system
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Handedness {
+    /// right × forward = up
+    Right,
+    /// right × forward = −up
+    Left,
+}
+
+/// Axis a coordinate system points up
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UpAxis {
+    /// up is +y
+    Y,
+    /// up is +z
+    Z,
+}
+
+/// Handedness and up axis of a coordinate system
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Conventions {
+    pub handedness: Handedness,
+    pub up: UpAxis,
+}
+
+impl Conventions {
+    /// new: Handedness × UpAxis -> Conventions
+    pub fn new(handedness: Handedness, up: UpAxis) -> Self {
+        Conventions { handedness, up }
+    }
+
+    /// right: &Conventions -> Vector
+    pub fn right<T: Float>(&self) -> Vector3<T> {
+        Vector3(T::one(), T::zero(), T::zero())
+    }
+
+    /// up: &Conventions -> Vector
+    pub fn up<T: Float>(&self) -> Vector3<T> {
+        let (zero, one) = (T::zero(), T::one());
+        match self.up {
+            UpAxis::Y => Vector3(zero, one, zero),
+            UpAxis::Z => Vector3(zero, zero, one),
+        }
+    }
+
+    /// forward: &Conventions -> Vector
+    pub fn forward<T: Float>(&self) -> Vector3<T> {
+        let (zero, one) = (T::zero(), T::one());
+        match (self.up, self.handedness) {
+            (UpAxis::Y, Handedness::Right) => Vector3(zero, zero, -one),
+            (UpAxis::Y, Handedness::Left) => Vector3(zero, zero, one),
+            (UpAxis::Z, Handedness::Right) => Vector3(zero, one, zero),
+            (UpAxis::Z, Handedness::Left) => Vector3(zero, -one, zero),
+        }
+    }
+
+    /// look_rotation: &Conventions × Vector × Vector -> Rotor
+    /// turns this system's forward onto `forward` and its up onto `up` made
+    /// perpendicular to it, as `Rotor3::look_rotation` does for +z and +y;
+    /// an `up` parallel to `forward` is replaced by the least aligned axis
+    pub fn look_rotation<T: Float>(&self, forward: Vector3<T>, up: Vector3<T>) -> Rotor3<T> {
+        // Rotor3::look_rotation turns z and y onto the targets; undo it for
+        // this system's own forward and up
+        let own = Rotor3::look_rotation(self.forward(), self.up());
+        Rotor3::look_rotation(forward, up) * own.reverse()
+    }
+
+    /// convert_vector: &Conventions × &Conventions × Vector -> Vector
+    /// coordinates in this system of a direction to those in `to`, keeping
+    /// what each calls right, up and forward
+    pub fn convert_vector<T: Float>(&self, to: &Conventions, v: Vector3<T>) -> Vector3<T> {
+        to.right() * v.dot(self.right()) + to.up() * v.dot(self.up()) + to.forward() * v.dot(self.forward())
+    }
+
+    /// converter: &Conventions × &Conventions -> Affine
+    /// the change of coordinates into `to`, a reflection between systems
+    /// of opposite handedness
+    pub fn converter<T: Float>(&self, to: &Conventions) -> Affine3<T> {
+        let (zero, one) = (T::zero(), T::one());
+        let columns = [Vector3(one, zero, zero), Vector3(zero, one, zero), Vector3(zero, zero, one)].map(|e| self.convert_vector(to, e));
+        Affine3::new(columns, Vector3(zero, zero, zero))
+    }
+
+    /// convert_rotor: &Conventions × &Conventions × Rotor -> Rotor
+    /// the same physical rotation in the coordinates of `to`; between
+    /// systems of opposite handedness its sense flips with the reflection
+    pub fn convert_rotor<T: Float>(&self, to: &Conventions, r: Rotor3<T>) -> Rotor3<T> {
+        let (zero, one) = (T::zero(), T::one());
+        let [x, y, z] = [Vector3(one, zero, zero), Vector3(zero, one, zero), Vector3(zero, zero, one)]
+            .map(|e| self.convert_vector(to, r.rotate(to.convert_vector(self, e))));
+        Rotor3::from_basis(x, y, z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn near(v: Vector3<f64>, w: Vector3<f64>) -> bool {
+        (v - w).length() < 1e-12
+    }
+
+    #[test]
+    fn axes_and_conversion() {
+        let gl = Conventions::new(Handedness::Right, UpAxis::Y);
+        let d3d = Conventions::new(Handedness::Left, UpAxis::Y);
+        let blender = Conventions::new(Handedness::Right, UpAxis::Z);
+        let lz = Conventions::new(Handedness::Left, UpAxis::Z);
+        for c in [gl, d3d, blender, lz] {
+            let sign = if c.handedness == Handedness::Right { 1.0 } else { -1.0 };
+            assert!(near(c.right::<f64>().cross(c.forward()), c.up::<f64>() * sign));
+        }
+        // y-up to z-up: (x, y, z) -> (x, -z, y)
+        let v = Vector3(1.0, 2.0, 3.0);
+        assert!(near(gl.convert_vector(&blender, v), Vector3(1.0, -3.0, 2.0)));
+        assert!(near(gl.convert_vector(&d3d, v), Vector3(1.0, 2.0, -3.0)));
+        assert!(near(blender.convert_vector(&gl, gl.convert_vector(&blender, v)), v));
+        let m = d3d.converter::<f64>(&lz);
+        assert!(near(m.transform_vector(v), d3d.convert_vector(&lz, v)));
+    }
+
+    #[test]
+    fn rotations_across_conventions() {
+        let gl = Conventions::new(Handedness::Right, UpAxis::Y);
+        let d3d = Conventions::new(Handedness::Left, UpAxis::Y);
+        let blender = Conventions::new(Handedness::Right, UpAxis::Z);
+        let (y, z) = (Vector3(0.0, 1.0, 0.0), Vector3(0.0, 0.0, 1.0));
+        assert!(near(d3d.look_rotation(z, y).rotate(Vector3(1.0, 2.0, 3.0)), Vector3(1.0, 2.0, 3.0)));
+        assert!(near(gl.look_rotation(-z, y).rotate(Vector3(1.0, 2.0, 3.0)), Vector3(1.0, 2.0, 3.0)));
+        // looking right: forward goes to +x, up stays
+        for c in [gl, d3d, blender] {
+            let r = c.look_rotation(Vector3(1.0, 0.0, 0.0), c.up());
+            assert!(near(r.rotate(c.forward()), Vector3(1.0, 0.0, 0.0)));
+            assert!(near(r.rotate(c.up()), c.up()));
+        }
+        // a turn carried across keeps acting on the carried vectors
+        let r = Rotor3::exp(Vector3(0.3, -0.5, 0.9));
+        let v = Vector3(0.2, 1.0, -0.4);
+        for (a, b) in [(gl, blender), (gl, d3d), (d3d, blender)] {
+            let carried = a.convert_rotor(&b, r);
+            assert!(near(carried.rotate(a.convert_vector(&b, v)), a.convert_vector(&b, r.rotate(v))));
+        }
+    }
+}
@@ -0,0 +1,681 @@
+//! Parametric curves
+
+use crate::anchor::impl_anchored;
+use crate::path::{ segment_bounds, Segment };
+use crate::{ Bounded2, Closest2, Float, Point2, Point3, Polyline2, Rect2, Vector2, Vector3 };
+
+/// Parametric curve over t ∈ [0, 1]
+pub trait Curve2<T> {
+    /// point_at: &Curve × K -> Point
+    fn point_at(&self, t: T) -> Point2<T>;
+
+    /// derivative: &Curve × K -> Vector
+    /// velocity with respect to t
+    fn derivative(&self, t: T) -> Vector2<T>;
+
+    /// second_derivative: &Curve × K -> Vector
+    fn second_derivative(&self, t: T) -> Vector2<T>;
+
+    /// curvature: &Curve × K -> K
+    /// signed, positive while turning counter-clockwise; zero where the
+    /// velocity vanishes
+    fn curvature(&self, t: T) -> T
+    where
+        T: Float,
+    {
+        let (d, dd) = (self.derivative(t), self.second_derivative(t));
+        let speed = d.length();
+        if speed == T::zero() {
+            return T::zero();
+        }
+        (d.0 * dd.1 - d.1 * dd.0) / (speed * speed * speed)
+    }
+
+    /// spans: &Curve -> usize
+    /// number of pieces the parameter range is split into before
+    /// adaptive subdivision, one per polynomial piece
+    fn spans(&self) -> usize {
+        1
+    }
+}
+
+/// Quadratic Bézier: start, control, end
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct QuadBezier2<T>(Point2<T>, Point2<T>, Point2<T>);
+
+/// Cubic Bézier: start, control, control, end
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CubicBezier2<T>(Point2<T>, Point2<T>, Point2<T>, Point2<T>);
+
+/// Circular arc: center, radius, start angle, sweep angle (counter-clockwise if positive)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Arc2<T>(Point2<T>, T, T, T);
+
+/// Uniform Catmull–Rom spline through its points, end points repeated
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatmullRom2<T>(Vec<Point2<T>>);
+
+/// 3d cubic Bézier: start, control, control, end
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CubicBezier3<T>(Point3<T>, Point3<T>, Point3<T>, Point3<T>);
+
+impl<T> QuadBezier2<T> {
+    /// new: Point × Point × Point -> Bézier
+    pub fn new(p0: Point2<T>, p1: Point2<T>, p2: Point2<T>) -> Self {
+        QuadBezier2(p0, p1, p2)
+    }
+}
+
+impl<T> CubicBezier2<T> {
+    /// new: Point × Point × Point × Point -> Bézier
+    pub fn new(p0: Point2<T>, p1: Point2<T>, p2: Point2<T>, p3: Point2<T>) -> Self {
+        CubicBezier2(p0, p1, p2, p3)
+    }
+}
+
+impl<T> Arc2<T> {
+    /// new: Point × K × K × K -> Arc
+    pub fn new(center: Point2<T>, radius: T, start: T, sweep: T) -> Self {
+        Arc2(center, radius, start, sweep)
+    }
+}
+
+impl<T: Copy> Arc2<T> {
+    /// center: &Arc -> Point
+    pub fn center(&self) -> Point2<T> {
+        self.0
+    }
+
+    /// radius: &Arc -> K
+    pub fn radius(&self) -> T {
+        self.1
+    }
+
+    /// start: &Arc -> K
+    /// start angle
+    pub fn start(&self) -> T {
+        self.2
+    }
+
+    /// sweep: &Arc -> K
+    /// counter-clockwise if positive
+    pub fn sweep(&self) -> T {
+        self.3
+    }
+}
+
+impl<T> CatmullRom2<T> {
+    /// new: [Point] -> Spline
+    pub fn new(points: Vec<Point2<T>>) -> Self {
+        CatmullRom2(points)
+    }
+}
+
+impl<T> CubicBezier3<T> {
+    /// new: Point × Point × Point × Point -> Bézier
+    pub fn new(p0: Point3<T>, p1: Point3<T>, p2: Point3<T>, p3: Point3<T>) -> Self {
+        CubicBezier3(p0, p1, p2, p3)
+    }
+}
+
+fn lerp<T: Float>(p: Point2<T>, q: Point2<T>, t: T) -> Point2<T> {
+    p + q.vector_from(p) * t
+}
+
+impl<T: Float> Curve2<T> for QuadBezier2<T> {
+    fn point_at(&self, t: T) -> Point2<T> {
+        let (a, b) = (lerp(self.0, self.1, t), lerp(self.1, self.2, t));
+        lerp(a, b, t)
+    }
+
+    fn derivative(&self, t: T) -> Vector2<T> {
+        (self.1.vector_from(self.0) * (T::one() - t) + self.2.vector_from(self.1) * t) * T::from_f64(2.0)
+    }
+
+    fn second_derivative(&self, _: T) -> Vector2<T> {
+        (self.2.vector_from(self.1) - self.1.vector_from(self.0)) * T::from_f64(2.0)
+    }
+}
+
+impl<T: Float> Curve2<T> for CubicBezier2<T> {
+    fn point_at(&self, t: T) -> Point2<T> {
+        let (a, b, c) = (lerp(self.0, self.1, t), lerp(self.1, self.2, t), lerp(self.2, self.3, t));
+        let (d, e) = (lerp(a, b, t), lerp(b, c, t));
+        lerp(d, e, t)
+    }
+
+    fn derivative(&self, t: T) -> Vector2<T> {
+        let s = T::one() - t;
+        let three = T::from_f64(3.0);
+        (self.1.vector_from(self.0) * (s * s) + self.2.vector_from(self.1) * (s * t * T::from_f64(2.0))
+            + self.3.vector_from(self.2) * (t * t)) * three
+    }
+
+    fn second_derivative(&self, t: T) -> Vector2<T> {
+        let (a, b, c) = (self.1.vector_from(self.0), self.2.vector_from(self.1), self.3.vector_from(self.2));
+        ((b - a) * (T::one() - t) + (c - b) * t) * T::from_f64(6.0)
+    }
+}
+
+impl<T: Float> Curve2<T> for Arc2<T> {
+    fn point_at(&self, t: T) -> Point2<T> {
+        let a = self.2 + self.3 * t;
+        self.0 + Vector2(a.cos(), a.sin()) * self.1
+    }
+
+    fn derivative(&self, t: T) -> Vector2<T> {
+        let a = self.2 + self.3 * t;
+        Vector2(-a.sin(), a.cos()) * (self.1 * self.3)
+    }
+
+    fn second_derivative(&self, t: T) -> Vector2<T> {
+        let a = self.2 + self.3 * t;
+        Vector2(a.cos(), a.sin()) * -(self.1 * self.3 * self.3)
+    }
+}
+
+impl<T: Float> Curve2<T> for CatmullRom2<T> {
+    fn point_at(&self, t: T) -> Point2<T> {
+        let Some(([p0, p1, p2, p3], u)) = self.span(t) else {
+            return self.0[0];
+        };
+        let v = |q: Point2<T>| Vector2(q.0, q.1);
+        let (u2, u3) = (u * u, u * u * u);
+        let c = v(p1) * T::from_f64(2.0)
+            + (v(p2) - v(p0)) * u
+            + (v(p0) * T::from_f64(2.0) - v(p1) * T::from_f64(5.0) + v(p2) * T::from_f64(4.0) - v(p3)) * u2
+            + (v(p1) * T::from_f64(3.0) - v(p0) - v(p2) * T::from_f64(3.0) + v(p3)) * u3;
+        let c = c * T::from_f64(0.5);
+        Point2(c.0, c.1)
+    }
+
+    fn derivative(&self, t: T) -> Vector2<T> {
+        let Some(([p0, p1, p2, p3], u)) = self.span(t) else {
+            return Vector2(T::zero(), T::zero());
+        };
+        let v = |q: Point2<T>| Vector2(q.0, q.1);
+        let d = (v(p2) - v(p0))
+            + (v(p0) * T::from_f64(2.0) - v(p1) * T::from_f64(5.0) + v(p2) * T::from_f64(4.0) - v(p3)) * (T::from_f64(2.0) * u)
+            + (v(p1) * T::from_f64(3.0) - v(p0) - v(p2) * T::from_f64(3.0) + v(p3)) * (T::from_f64(3.0) * u * u);
+        d * (T::from_f64(0.5) * T::from_f64((self.0.len() - 1) as f64))
+    }
+
+    fn second_derivative(&self, t: T) -> Vector2<T> {
+        let Some(([p0, p1, p2, p3], u)) = self.span(t) else {
+            return Vector2(T::zero(), T::zero());
+        };
+        let v = |q: Point2<T>| Vector2(q.0, q.1);
+        let d = (v(p0) * T::from_f64(2.0) - v(p1) * T::from_f64(5.0) + v(p2) * T::from_f64(4.0) - v(p3)) * T::from_f64(2.0)
+            + (v(p1) * T::from_f64(3.0) - v(p0) - v(p2) * T::from_f64(3.0) + v(p3)) * (T::from_f64(6.0) * u);
+        let n = T::from_f64((self.0.len() - 1) as f64);
+        d * (T::from_f64(0.5) * n * n)
+    }
+
+    fn spans(&self) -> usize {
+        self.0.len().saturating_sub(1).max(1)
+    }
+}
+
+impl<T: Float> CatmullRom2<T> {
+    /// Control points of the span holding t, and the parameter within it;
+    /// None with fewer than two points
+    fn span(&self, t: T) -> Option<([Point2<T>; 4], T)> {
+        let n = self.0.len();
+        if n < 2 {
+            return None;
+        }
+        let s = t * T::from_f64((n - 1) as f64);
+        let mut i = 0;
+        while i + 2 < n && T::from_f64((i + 1) as f64) <= s {
+            i += 1;
+        }
+        let p = |k: isize| self.0[(i as isize + k).clamp(0, n as isize - 1) as usize];
+        Some(([p(-1), p(0), p(1), p(2)], s - T::from_f64(i as f64)))
+    }
+}
+
+impl<T: Float> CubicBezier3<T> {
+    /// point_at: &Bézier × K -> Point
+    pub fn point_at(&self, t: T) -> Point3<T> {
+        let lerp = |p: Point3<T>, q: Point3<T>| p + q.vector_from(p) * t;
+        let (a, b, c) = (lerp(self.0, self.1), lerp(self.1, self.2), lerp(self.2, self.3));
+        let (d, e) = (lerp(a, b), lerp(b, c));
+        lerp(d, e)
+    }
+
+    /// derivative: &Bézier × K -> Vector
+    pub fn derivative(&self, t: T) -> Vector3<T> {
+        let s = T::one() - t;
+        (self.1.vector_from(self.0) * (s * s) + self.2.vector_from(self.1) * (s * t * T::from_f64(2.0))
+            + self.3.vector_from(self.2) * (t * t)) * T::from_f64(3.0)
+    }
+
+    /// second_derivative: &Bézier × K -> Vector
+    pub fn second_derivative(&self, t: T) -> Vector3<T> {
+        let (a, b, c) = (self.1.vector_from(self.0), self.2.vector_from(self.1), self.3.vector_from(self.2));
+        ((b - a) * (T::one() - t) + (c - b) * t) * T::from_f64(6.0)
+    }
+
+    /// third_derivative: &Bézier -> Vector
+    pub fn third_derivative(&self) -> Vector3<T> {
+        let (a, b, c) = (self.1.vector_from(self.0), self.2.vector_from(self.1), self.3.vector_from(self.2));
+        (c - b - b + a) * T::from_f64(6.0)
+    }
+
+    /// curvature: &Bézier × K -> K
+    /// |B′ × B″| / |B′|³; zero where the velocity vanishes
+    pub fn curvature(&self, t: T) -> T {
+        let d = self.derivative(t);
+        let speed = d.length();
+        if speed == T::zero() {
+            return T::zero();
+        }
+        d.cross(self.second_derivative(t)).length() / (speed * speed * speed)
+    }
+
+    /// torsion: &Bézier × K -> K
+    /// (B′ × B″) · B‴ / |B′ × B″|², how fast the curve leaves its
+    /// osculating plane; zero where the curvature vanishes
+    pub fn torsion(&self, t: T) -> T {
+        let b = self.derivative(t).cross(self.second_derivative(t));
+        let l = b.dot(b);
+        if l == T::zero() {
+            return T::zero();
+        }
+        b.dot(self.third_derivative()) / l
+    }
+}
+
+impl_anchored!(QuadBezier2, |b, m| QuadBezier2(m.point(b.0), m.point(b.1), m.point(b.2)));
+impl_anchored!(CubicBezier2, |b, m| CubicBezier2(m.point(b.0), m.point(b.1), m.point(b.2), m.point(b.3)));
+impl_anchored!(Arc2, |a, m| Arc2(m.point(a.0), m.length(a.1), m.angle(a.2), a.3));
+impl_anchored!(CatmullRom2, |s, m| CatmullRom2(m.points(&s.0)));
+
+impl<T: Float> Bounded2<T> for QuadBezier2<T> {
+    fn bounds(&self) -> Option<Rect2<T>> {
+        segment_bounds(&[Segment::Quad([self.0, self.1, self.2])])
+    }
+}
+
+impl<T: Float> Bounded2<T> for CubicBezier2<T> {
+    fn bounds(&self) -> Option<Rect2<T>> {
+        segment_bounds(&[Segment::Cubic([self.0, self.1, self.2, self.3])])
+    }
+}
+
+impl<T: Float> Bounded2<T> for Arc2<T> {
+    fn bounds(&self) -> Option<Rect2<T>> {
+        segment_bounds(&[Segment::Arc(self.0, self.1, self.2, self.3)])
+    }
+}
+
+impl<T: Float> Bounded2<T> for CatmullRom2<T> {
+    /// each span converted to its cubic Bézier form
+    fn bounds(&self) -> Option<Rect2<T>> {
+        let n = self.0.len();
+        if n < 2 {
+            return Rect2::from_points(self.0.iter().copied());
+        }
+        let sixth = T::from_f64(1.0 / 6.0);
+        let spans: Vec<_> = (0..n - 1).map(|i| {
+            let p = |k: isize| self.0[(i as isize + k).clamp(0, n as isize - 1) as usize];
+            let (p0, p1, p2, p3) = (p(-1), p(0), p(1), p(2));
+            Segment::Cubic([p1, p1 + p2.vector_from(p0) * sixth, p2 - p3.vector_from(p1) * sixth, p2])
+        }).collect();
+        segment_bounds(&spans)
+    }
+}
+
+impl<T: Float> QuadBezier2<T> {
+    /// offset: &Bézier × K × K -> [Bézier]
+    /// cubic pieces of the degree-elevated curve's offset, see `CubicBezier2::offset`
+    pub fn offset(&self, distance: T, tolerance: T) -> Vec<CubicBezier2<T>> {
+        let third = T::from_f64(2.0 / 3.0);
+        let c1 = self.0 + self.1.vector_from(self.0) * third;
+        let c2 = self.2 + self.1.vector_from(self.2) * third;
+        CubicBezier2(self.0, c1, c2, self.2).offset(distance, tolerance)
+    }
+}
+
+impl<T: Float> CubicBezier2<T> {
+    /// offset: &Bézier × K × K -> [Bézier]
+    /// the curve moved `distance` to the left of travel, as consecutive cubics
+    /// within `tolerance`; loops past cusps of the offset are kept
+    pub fn offset(&self, distance: T, tolerance: T) -> Vec<CubicBezier2<T>> {
+        let mut out = Vec::new();
+        offset_cubic(*self, distance, tolerance, 0, &mut out);
+        out
+    }
+
+    fn split(self, t: T) -> (Self, Self) {
+        let (ab, bc, cd) = (lerp(self.0, self.1, t), lerp(self.1, self.2, t), lerp(self.2, self.3, t));
+        let (abc, bcd) = (lerp(ab, bc, t), lerp(bc, cd, t));
+        let m = lerp(abc, bcd, t);
+        (CubicBezier2(self.0, ab, abc, m), CubicBezier2(m, bcd, cd, self.3))
+    }
+}
+
+impl<T: Float> CatmullRom2<T> {
+    /// offset: &Spline × K × K -> [Bézier]
+    /// offsets of the spans in their cubic Bézier form
+    pub fn offset(&self, distance: T, tolerance: T) -> Vec<CubicBezier2<T>> {
+        let n = self.0.len();
+        let sixth = T::from_f64(1.0 / 6.0);
+        (0..n.saturating_sub(1)).flat_map(|i| {
+            let p = |k: isize| self.0[(i as isize + k).clamp(0, n as isize - 1) as usize];
+            let (p0, p1, p2, p3) = (p(-1), p(0), p(1), p(2));
+            CubicBezier2(p1, p1 + p2.vector_from(p0) * sixth, p2 - p3.vector_from(p1) * sixth, p2)
+                .offset(distance, tolerance)
+        }).collect()
+    }
+}
+
+impl<T: Float> Arc2<T> {
+    /// offset: &Arc × K -> Option<Arc>
+    /// the concentric arc `distance` to the left of travel, exact;
+    /// None once the radius would shrink to zero or below
+    pub fn offset(&self, distance: T) -> Option<Arc2<T>> {
+        let r = if self.3 < T::zero() { self.1 + distance } else { self.1 - distance };
+        if r > T::zero() { Some(Arc2(self.0, r, self.2, self.3)) } else { None }
+    }
+}
+
+/// Left unit normal of a direction, None if it vanishes
+fn normal<T: Float>(v: Vector2<T>) -> Option<Vector2<T>> {
+    let l = v.length();
+    if l == T::zero() { None } else { Some(Vector2(-v.1, v.0) / l) }
+}
+
+/// Cubic through the offset end points whose end tangents follow the offset
+/// curve, O' = B' (1 - dκ); halved until it matches the exact offset at the quarter points
+fn offset_cubic<T: Float>(c: CubicBezier2<T>, d: T, tolerance: T, depth: usize, out: &mut Vec<CubicBezier2<T>>) {
+    // end point and scaled handle, for the curve read from p0 towards p3
+    let end = |p0: Point2<T>, p1: Point2<T>, p2: Point2<T>, p3: Point2<T>, d: T| {
+        let h = p1.vector_from(p0);
+        let n = normal(h).or_else(|| normal(p2.vector_from(p0))).or_else(|| normal(p3.vector_from(p0)));
+        let n = n.unwrap_or(Vector2(T::zero(), T::zero()));
+        // κ = B' × B'' / |B'|³ with B' = 3h, B'' = 6(p2 - p1 - h)
+        let l = h.length();
+        let scale = if l == T::zero() {
+            T::one()
+        } else {
+            let g = p2.vector_from(p1) - h;
+            T::one() - d * (h.0 * g.1 - h.1 * g.0) * T::from_f64(2.0 / 3.0) / (l * l * l)
+        };
+        (p0 + n * d, h * scale)
+    };
+    let (q0, h0) = end(c.0, c.1, c.2, c.3, d);
+    // read backwards, left is right
+    let (q3, h3) = end(c.3, c.2, c.1, c.0, -d);
+    let candidate = CubicBezier2(q0, q0 + h0, q3 + h3, q3);
+    let fits = depth >= MAX_DEPTH || [0.25, 0.5, 0.75].iter().all(|&t| {
+        let t = T::from_f64(t);
+        normal(c.derivative(t)).is_none_or(|n| candidate.point_at(t).distance(c.point_at(t) + n * d) <= tolerance)
+    });
+    if fits {
+        out.push(candidate);
+    } else {
+        let (a, b) = c.split(T::from_f64(0.5));
+        offset_cubic(a, d, tolerance, depth + 1, out);
+        offset_cubic(b, d, tolerance, depth + 1, out);
+    }
+}
+
+/// flatten: &Curve × K -> Polyline
+/// adaptive subdivision until the curve stays within `tolerance` of every chord
+/// (checked at the quarter points of each span)
+pub fn flatten<T: Float, C: Curve2<T> + ?Sized>(curve: &C, tolerance: T) -> Polyline2<T> {
+    Polyline2::new(flatten_params(curve, tolerance).into_iter().map(|(_, p)| p).collect())
+}
+
+/// Vertices of `flatten` with their curve parameters
+pub(crate) fn flatten_params<T: Float, C: Curve2<T> + ?Sized>(curve: &C, tolerance: T) -> Vec<(T, Point2<T>)> {
+    let spans = curve.spans();
+    let mut points = vec![(T::zero(), curve.point_at(T::zero()))];
+    for k in 0..spans {
+        let t0 = T::from_f64(k as f64 / spans as f64);
+        let t1 = T::from_f64((k + 1) as f64 / spans as f64);
+        let (p0, p1) = (curve.point_at(t0), curve.point_at(t1));
+        subdivide(curve, (t0, p0), (t1, p1), tolerance, 0, &mut points);
+    }
+    points
+}
+
+pub(crate) const MAX_DEPTH: usize = 24;
+
+fn subdivide<T: Float, C: Curve2<T> + ?Sized>(
+    curve: &C,
+    (t0, p0): (T, Point2<T>),
+    (t1, p1): (T, Point2<T>),
+    tolerance: T,
+    depth: usize,
+    out: &mut Vec<(T, Point2<T>)>,
+) {
+    let tm = (t0 + t1) * T::from_f64(0.5);
+    let pm = curve.point_at(tm);
+    let flat = depth >= MAX_DEPTH || [0.25, 0.5, 0.75].iter().all(|&f| {
+        let p = if f == 0.5 { pm } else { curve.point_at(t0 + (t1 - t0) * T::from_f64(f)) };
+        segment_distance(p, p0, p1) <= tolerance
+    });
+    if flat {
+        out.push((t1, p1));
+    } else {
+        subdivide(curve, (t0, p0), (tm, pm), tolerance, depth + 1, out);
+        subdivide(curve, (tm, pm), (t1, p1), tolerance, depth + 1, out);
+    }
+}
+
+/// Distance from p to the segment ab
+pub(crate) fn segment_distance<T: Float>(p: Point2<T>, a: Point2<T>, b: Point2<T>) -> T {
+    segment_closest(p, a, b).distance(p)
+}
+
+pub(crate) fn segment_closest<T: Float>(p: Point2<T>, a: Point2<T>, b: Point2<T>) -> Point2<T> {
+    let (ab, ap) = (b.vector_from(a), p.vector_from(a));
+    let l = ab.dot(ab);
+    if l == T::zero() {
+        return a;
+    }
+    let t = ap.dot(ab) / l;
+    let t = if t < T::zero() { T::zero() } else if t > T::one() { T::one() } else { t };
+    a + ab * t
+}
+
+/// Best of a few samples per span, then bisection around it on the sign of
+/// (B(t) - p) · B'(t), the tangent taken from a short chord
+pub(crate) fn closest_on_curve<T: Float, C: Curve2<T> + ?Sized>(curve: &C, p: Point2<T>) -> Point2<T> {
+    let n = curve.spans() * 16;
+    let step = T::one() / T::from_f64(n as f64);
+    let clamp = |t: T| if t < T::zero() { T::zero() } else if t > T::one() { T::one() } else { t };
+    let t = (0..=n)
+        .map(|k| T::from_f64(k as f64) * step)
+        .map(|t| (curve.point_at(t).distance(p), t))
+        .fold((T::infinity(), T::zero()), |best, s| if s.0 < best.0 { s } else { best })
+        .1;
+    let h = step * T::from_f64(1e-4);
+    let slope = |t: T| curve.point_at(t).vector_from(p).dot(curve.point_at(clamp(t + h)).vector_from(curve.point_at(clamp(t - h))));
+    let (mut lo, mut hi) = (clamp(t - step), clamp(t + step));
+    if slope(lo) >= T::zero() || slope(hi) <= T::zero() {
+        let (a, b) = (curve.point_at(lo), curve.point_at(hi));
+        let c = curve.point_at(t);
+        return [a, b].into_iter().fold(c, |m, q| if q.distance(p) < m.distance(p) { q } else { m });
+    }
+    for _ in 0..64 {
+        let mid = (lo + hi) * T::from_f64(0.5);
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if slope(mid) < T::zero() { lo = mid } else { hi = mid }
+    }
+    curve.point_at((lo + hi) * T::from_f64(0.5))
+}
+
+impl<T: Float> Closest2<T> for QuadBezier2<T> {
+    fn closest_point_on(&self, p: Point2<T>) -> Point2<T> {
+        closest_on_curve(self, p)
+    }
+}
+
+impl<T: Float> Closest2<T> for CubicBezier2<T> {
+    fn closest_point_on(&self, p: Point2<T>) -> Point2<T> {
+        closest_on_curve(self, p)
+    }
+}
+
+impl<T: Float> Closest2<T> for CatmullRom2<T> {
+    fn closest_point_on(&self, p: Point2<T>) -> Point2<T> {
+        if self.0.is_empty() {
+            return p;
+        }
+        closest_on_curve(self, p)
+    }
+}
+
+impl<T: Float> Closest2<T> for Arc2<T> {
+    fn closest_point_on(&self, p: Point2<T>) -> Point2<T> {
+        let v = p.vector_from(self.0);
+        let tau = T::pi() * T::from_f64(2.0);
+        // angle from the start, the way the arc turns, in [0, 2π)
+        let turned = (v.1.atan2(v.0) - self.2) * if self.3 < T::zero() { -T::one() } else { T::one() };
+        let turned = turned - (turned / tau).floor() * tau;
+        if turned <= self.3.abs() && v != Vector2(T::zero(), T::zero()) {
+            return self.0 + v * (self.1 / v.length());
+        }
+        let (a, b) = (self.point_at(T::zero()), self.point_at(T::one()));
+        if a.distance(p) <= b.distance(p) { a } else { b }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn max_error<C: Curve2<f64>>(c: &C, line: &Polyline2<f64>) -> f64 {
+        (0..=1000).map(|i| {
+            let p = c.point_at(i as f64 / 1000.0);
+            line.vertices().windows(2)
+                .map(|w| segment_distance(p, w[0], w[1]))
+                .fold(f64::INFINITY, f64::min)
+        }).fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn point_at() {
+        let q = QuadBezier2::new(Point2(0.0, 0.0), Point2(1.0, 2.0), Point2(2.0, 0.0));
+        assert_eq!(q.point_at(0.5), Point2(1.0, 1.0));
+        let c = CubicBezier2::new(Point2(0.0, 0.0), Point2(0.0, 1.0), Point2(1.0, 1.0), Point2(1.0, 0.0));
+        assert_eq!(c.point_at(0.5), Point2(0.5, 0.75));
+        let a = Arc2::new(Point2(1.0, 1.0), 2.0, 0.0, PI);
+        assert!(a.point_at(0.5).distance(Point2(1.0, 3.0)) < 1e-12);
+        let s = CatmullRom2::new(vec![Point2(0.0, 0.0), Point2(1.0, 1.0), Point2(2.0, 0.0)]);
+        assert_eq!(s.point_at(0.5), Point2(1.0, 1.0));
+        assert_eq!(s.point_at(1.0), Point2(2.0, 0.0));
+    }
+
+    #[test]
+    fn curvature() {
+        let a = Arc2::new(Point2(1.0, 1.0), 2.0, 0.3, -PI);
+        assert!((a.curvature(0.4) + 0.5).abs() < 1e-12);
+        // y = x² at x = 0 through a quadratic Bézier, where κ = 2
+        let q = QuadBezier2::new(Point2(-1.0, 1.0), Point2(0.0, -1.0), Point2(1.0, 1.0));
+        assert!((q.curvature(0.5) - 2.0).abs() < 1e-12);
+        assert_eq!(CubicBezier2::new(Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(2.0, 0.0), Point2(3.0, 0.0)).curvature(0.3), 0.0);
+        // derivatives of the spline against differences of its points
+        let s = CatmullRom2::new(vec![Point2(0.0, 0.0), Point2(1.0, 2.0), Point2(3.0, 1.0), Point2(4.0, 3.0)]);
+        let h = 1e-6;
+        for t in [0.1, 0.5, 0.8] {
+            let d = s.point_at(t + h).vector_from(s.point_at(t - h)) / (2.0 * h);
+            assert!((d - s.derivative(t)).length() < 1e-6);
+            let dd = (s.derivative(t + h) - s.derivative(t - h)) / (2.0 * h);
+            assert!((dd - s.second_derivative(t)).length() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn curvature_and_torsion_3d() {
+        // a planar cubic does not twist
+        let flat = CubicBezier3::new(Point3(0.0, 0.0, 0.0), Point3(1.0, 2.0, 0.0), Point3(3.0, 2.0, 0.0), Point3(4.0, 0.0, 0.0));
+        assert_eq!(flat.torsion(0.3), 0.0);
+        let plane = CubicBezier2::new(Point2(0.0, 0.0), Point2(1.0, 2.0), Point2(3.0, 2.0), Point2(4.0, 0.0));
+        assert!((flat.curvature(0.5) - 12.0 / 20.25).abs() < 1e-12);
+        assert!((flat.curvature(0.2) + plane.curvature(0.2)).abs() < 1e-12);
+        // against differences of the unit binormal, whose rate is -τ N along the arc
+        let c = CubicBezier3::new(Point3(0.0, 0.0, 0.0), Point3(1.0, 0.0, 0.0), Point3(1.0, 1.0, 0.0), Point3(1.0, 1.0, 1.0));
+        let binormal = |t: f64| {
+            let b = c.derivative(t).cross(c.second_derivative(t));
+            b / b.length()
+        };
+        let (t, h) = (0.4, 1e-6);
+        let db = (binormal(t + h) - binormal(t - h)) / (2.0 * h) / c.derivative(t).length();
+        assert!((db.length() - c.torsion(t).abs()).abs() < 1e-6);
+        assert!(c.torsion(t) > 0.0);
+        assert!((c.point_at(1.0).distance(Point3(1.0, 1.0, 1.0))) < 1e-12);
+    }
+
+    #[test]
+    fn flatten_within_tolerance() {
+        let c = CubicBezier2::new(Point2(0.0, 0.0), Point2(0.0, 10.0), Point2(10.0, 10.0), Point2(10.0, 0.0));
+        for tol in [1.0, 0.1, 0.001] {
+            let line = flatten(&c, tol);
+            assert!(max_error(&c, &line) <= tol * 1.01);
+            assert_eq!(line.vertices()[0], Point2(0.0, 0.0));
+            assert_eq!(*line.vertices().last().unwrap(), Point2(10.0, 0.0));
+        }
+        assert!(flatten(&c, 0.001).vertices().len() > flatten(&c, 0.1).vertices().len());
+    }
+
+    #[test]
+    fn flatten_arc_and_spline() {
+        let a = Arc2::new(Point2(0.0, 0.0), 5.0, 0.0, 2.0 * PI);
+        let line = flatten(&a, 0.01);
+        assert!(max_error(&a, &line) <= 0.0101);
+        // sagitta bound: chord angle ≤ 2 acos(1 - tol / r)
+        assert!(line.vertices().len() <= 2 * (PI / (1.0 - 0.01 / 5.0f64).acos()) as usize + 2);
+        let s = CatmullRom2::new(vec![Point2(0.0, 0.0), Point2(1.0, 3.0), Point2(2.0, -3.0), Point2(3.0, 0.0)]);
+        assert!(max_error(&s, &flatten(&s, 0.01)) <= 0.0101);
+        let straight = QuadBezier2::new(Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(2.0, 0.0));
+        assert_eq!(flatten(&straight, 0.01).vertices().len(), 2);
+    }
+
+    #[test]
+    fn tight_bounds() {
+        let c = CubicBezier2::new(Point2(0.0, 0.0), Point2(0.0, 4.0), Point2(4.0, 4.0), Point2(4.0, 0.0));
+        assert_eq!(c.bounds(), Some(Rect2::new(Point2(0.0, 0.0), Point2(4.0, 3.0))));
+        let a = Arc2::new(Point2(1.0, 1.0), 2.0, 0.0, PI);
+        let r = a.bounds().unwrap();
+        assert!(r.min().distance(Point2(-1.0, 1.0)) < 1e-12 && r.max().distance(Point2(3.0, 3.0)) < 1e-12);
+        let s = CatmullRom2::new(vec![Point2(0.0, 0.0), Point2(1.0, 3.0), Point2(2.0, -3.0), Point2(3.0, 0.0)]);
+        let r = s.bounds().unwrap();
+        let line = flatten(&s, 1e-6);
+        assert!(line.vertices().iter().all(|&p| r.contains(p)));
+        assert!(line.vertices().iter().any(|p| p.1 > 3.0) && r.max().1 - 3.0 < 0.5);
+    }
+
+    #[test]
+    fn offsets() {
+        // quarter circle of radius 10 about the origin, counter-clockwise
+        let k = 10.0 * 0.5522847498;
+        let c = CubicBezier2::new(Point2(10.0, 0.0), Point2(10.0, k), Point2(k, 10.0), Point2(0.0, 10.0));
+        for (d, tol) in [(2.0, 0.01), (-3.0, 0.001), (9.0, 0.01)] {
+            let pieces = c.offset(d, tol);
+            assert_eq!(pieces[0].0, Point2(10.0 - d, 0.0));
+            for piece in &pieces {
+                for p in flatten(piece, 1e-4).vertices() {
+                    assert!((p.distance(Point2(0.0, 0.0)) - (10.0 - d)).abs() <= tol + 0.01);
+                }
+            }
+            assert!(pieces.last().unwrap().3.distance(Point2(0.0, 10.0 - d)) < 1e-12);
+        }
+        let line = QuadBezier2::new(Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(2.0, 0.0));
+        let pieces = line.offset(1.0, 1e-6);
+        assert_eq!(pieces.len(), 1);
+        assert_eq!((pieces[0].0, pieces[0].3), (Point2(0.0, 1.0), Point2(2.0, 1.0)));
+        let s = CatmullRom2::new(vec![Point2(0.0, 0.0), Point2(1.0, 3.0), Point2(2.0, -3.0), Point2(3.0, 0.0)]);
+        assert!(s.offset(0.5, 0.01).len() >= 3);
+        let a = Arc2::new(Point2(0.0, 0.0), 2.0, 0.0, PI);
+        assert_eq!(a.offset(0.5), Some(Arc2::new(Point2(0.0, 0.0), 1.5, 0.0, PI)));
+        assert_eq!(Arc2::new(Point2(0.0, 0.0), 2.0, 0.0, -PI).offset(0.5).map(|a| a.1), Some(2.5));
+        assert_eq!(a.offset(2.0), None);
+    }
+}
@@ -0,0 +1,250 @@
+//! Debug dumps of intermediate geometry
+//!
+//! Collect labeled points, segments, polygons and meshes into a `Scene`
+//! while an algorithm runs, then write it out as SVG (top view, z dropped)
+//! or PLY (3d, labels kept as comments) for inspection in a viewer.
+
+use std::fmt::{ Display, Write as _ };
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::{ Float, Point2, Point3, Polygon2, TriMesh };
+
+/// Labeled geometry to dump
+#[derive(Debug, Clone, Default)]
+pub struct Scene<T> {
+    items: Vec<(String, Item<T>)>,
+}
+
+#[derive(Debug, Clone)]
+enum Item<T> {
+    Point(Point3<T>),
+    Segment(Point3<T>, Point3<T>),
+    Polygon(Vec<Point3<T>>),
+    Mesh(Vec<Point3<T>>, Vec<[usize; 3]>),
+}
+
+const PALETTE: [&str; 6] = ["#d62728", "#1f77b4", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b"];
+
+impl<T: Float + Display> Scene<T> {
+    /// new: Scene
+    pub fn new() -> Self {
+        Scene { items: Vec::new() }
+    }
+
+    /// point: &mut Scene × label × Point
+    pub fn point(&mut self, label: &str, p: Point2<T>) {
+        self.point3(label, lift(p))
+    }
+
+    /// point3: &mut Scene × label × Point
+    pub fn point3(&mut self, label: &str, p: Point3<T>) {
+        self.items.push((label.to_string(), Item::Point(p)))
+    }
+
+    /// segment: &mut Scene × label × Point × Point
+    pub fn segment(&mut self, label: &str, a: Point2<T>, b: Point2<T>) {
+        self.segment3(label, lift(a), lift(b))
+    }
+
+    /// segment3: &mut Scene × label × Point × Point
+    pub fn segment3(&mut self, label: &str, a: Point3<T>, b: Point3<T>) {
+        self.items.push((label.to_string(), Item::Segment(a, b)))
+    }
+
+    /// polygon: &mut Scene × label × &Polygon
+    /// one outline per ring, holes included
+    pub fn polygon(&mut self, label: &str, polygon: &Polygon2<T>) {
+        for ring in polygon.rings() {
+            self.items.push((label.to_string(), Item::Polygon(ring.iter().map(|&p| lift(p)).collect())))
+        }
+    }
+
+    /// mesh: &mut Scene × label × &Mesh
+    pub fn mesh(&mut self, label: &str, mesh: &TriMesh<T>) {
+        self.items.push((label.to_string(), Item::Mesh(mesh.vertices().to_vec(), mesh.triangles().to_vec())))
+    }
+
+    /// to_svg: &Scene -> String
+    /// view from +z with y up, one color per item; labels show as tooltips
+    pub fn to_svg(&self) -> String {
+        let points: Vec<Point3<T>> = self.items.iter().flat_map(|(_, item)| match item {
+            Item::Point(p) => vec![*p],
+            Item::Segment(a, b) => vec![*a, *b],
+            Item::Polygon(ring) | Item::Mesh(ring, _) => ring.clone(),
+        }).collect();
+        let zero = T::zero();
+        let (mut lo, mut hi) = points.first().map_or((Point3(zero, zero, zero), Point3(zero, zero, zero)), |&p| (p, p));
+        for p in &points {
+            lo = Point3(min(lo.0, p.0), min(lo.1, p.1), zero);
+            hi = Point3(max(hi.0, p.0), max(hi.1, p.1), zero);
+        }
+        let (w, h) = (hi.0 - lo.0, hi.1 - lo.1);
+        let size = max(max(w, h), T::one());
+        // margin and line width relative to the drawing
+        let pad = size * T::from_f64(0.05);
+        let stroke = size * T::from_f64(0.003);
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+            lo.0 - pad, -(hi.1 + pad), w + pad + pad, h + pad + pad,
+        );
+        let _ = writeln!(svg, r#"<g transform="scale(1 -1)" fill="none" stroke-width="{}">"#, stroke);
+        for (i, (label, item)) in self.items.iter().enumerate() {
+            let color = PALETTE[i % PALETTE.len()];
+            let title = format!("<title>{}</title>", escape(label));
+            let _ = match item {
+                Item::Point(p) => writeln!(
+                    svg,
+                    r#"<circle cx="{}" cy="{}" r="{}" fill="{color}">{title}</circle>"#,
+                    p.0, p.1, stroke * T::from_f64(2.0),
+                ),
+                Item::Segment(a, b) => writeln!(
+                    svg,
+                    r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{color}">{title}</line>"#,
+                    a.0, a.1, b.0, b.1,
+                ),
+                Item::Polygon(ring) => writeln!(
+                    svg,
+                    r#"<polygon points="{}" stroke="{color}">{title}</polygon>"#,
+                    coords(ring.iter()),
+                ),
+                Item::Mesh(vertices, triangles) => {
+                    let _ = writeln!(svg, r#"<g stroke="{color}">{title}"#);
+                    for t in triangles {
+                        let _ = writeln!(svg, r#"<polygon points="{}"/>"#, coords(t.iter().map(|&k| &vertices[k])));
+                    }
+                    writeln!(svg, "</g>")
+                }
+            };
+        }
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+
+    /// to_ply: &Scene -> String
+    /// ASCII PLY: every vertex, segments as edges, polygons and meshes as faces
+    pub fn to_ply(&self) -> String {
+        let mut vertices = Vec::new();
+        let mut edges = Vec::new();
+        let mut faces = Vec::new();
+        let mut comments = String::new();
+        for (label, item) in &self.items {
+            let base = vertices.len();
+            match item {
+                Item::Point(p) => vertices.push(*p),
+                Item::Segment(a, b) => {
+                    vertices.extend([*a, *b]);
+                    edges.push([base, base + 1]);
+                }
+                Item::Polygon(ring) => {
+                    vertices.extend(ring.iter().copied());
+                    faces.push((base..base + ring.len()).collect::<Vec<_>>());
+                }
+                Item::Mesh(vs, ts) => {
+                    vertices.extend(vs.iter().copied());
+                    faces.extend(ts.iter().map(|t| t.iter().map(|&k| base + k).collect::<Vec<_>>()));
+                }
+            }
+            let _ = writeln!(comments, "comment {}: vertices {}..{}", label.replace('\n', " "), base, vertices.len());
+        }
+        let mut ply = String::from("ply\nformat ascii 1.0\n");
+        ply.push_str(&comments);
+        let _ = writeln!(ply, "element vertex {}\nproperty float x\nproperty float y\nproperty float z", vertices.len());
+        let _ = writeln!(ply, "element edge {}\nproperty int vertex1\nproperty int vertex2", edges.len());
+        let _ = writeln!(ply, "element face {}\nproperty list uchar int vertex_indices\nend_header", faces.len());
+        for p in &vertices {
+            let _ = writeln!(ply, "{} {} {}", p.0, p.1, p.2);
+        }
+        for [a, b] in &edges {
+            let _ = writeln!(ply, "{a} {b}");
+        }
+        for f in &faces {
+            let indices: Vec<String> = f.iter().map(|k| k.to_string()).collect();
+            let _ = writeln!(ply, "{} {}", f.len(), indices.join(" "));
+        }
+        ply
+    }
+
+    /// write_svg: &Scene × path -> io::Result
+    pub fn write_svg<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_svg())
+    }
+
+    /// write_ply: &Scene × path -> io::Result
+    pub fn write_ply<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_ply())
+    }
+}
+
+fn lift<T: Float>(p: Point2<T>) -> Point3<T> {
+    Point3(p.0, p.1, T::zero())
+}
+
+fn min<T: Float>(a: T, b: T) -> T {
+    if b < a { b } else { a }
+}
+
+fn max<T: Float>(a: T, b: T) -> T {
+    if b > a { b } else { a }
+}
+
+fn coords<'a, T: Display + 'a, I: Iterator<Item = &'a Point3<T>>>(points: I) -> String {
+    points.map(|p| format!("{},{}", p.0, p.1)).collect::<Vec<_>>().join(" ")
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene() -> Scene<f64> {
+        let mut s = Scene::new();
+        s.point("hit <a>", Point2(1.0, 2.0));
+        s.segment("edge", Point2(0.0, 0.0), Point2(4.0, 0.0));
+        s.polygon("ring", &Polygon2::new(vec![Point2(0.0, 0.0), Point2(4.0, 0.0), Point2(0.0, 3.0)]));
+        let mesh = TriMesh::new(
+            vec![Point3(0.0, 0.0, 1.0), Point3(1.0, 0.0, 1.0), Point3(0.0, 1.0, 1.0), Point3(0.0, 0.0, 2.0)],
+            vec![[0, 1, 2], [0, 3, 1]],
+        );
+        s.mesh("tet", &mesh);
+        s
+    }
+
+    #[test]
+    fn svg() {
+        let svg = scene().to_svg();
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        assert!(svg.contains(r#"<circle cx="1" cy="2""#) && svg.contains("<title>hit &lt;a&gt;</title>"));
+        assert!(svg.contains(r#"<line x1="0" y1="0" x2="4" y2="0""#));
+        assert!(svg.contains(r#"points="0,0 4,0 0,3""#));
+        assert_eq!(svg.matches("<polygon").count(), 3);
+        assert!(Scene::<f64>::new().to_svg().contains("viewBox"));
+    }
+
+    #[test]
+    fn ply() {
+        let ply = scene().to_ply();
+        let lines: Vec<&str> = ply.lines().collect();
+        assert!(lines.contains(&"element vertex 10") && lines.contains(&"element edge 1") && lines.contains(&"element face 3"));
+        assert!(lines.contains(&"comment tet: vertices 6..10"));
+        assert_eq!(&lines[lines.len() - 4..], &["1 2", "3 3 4 5", "3 6 7 8", "3 6 9 7"]);
+    }
+
+    #[test]
+    fn write_files() {
+        let dir = std::env::temp_dir();
+        let s = scene();
+        let (svg, ply) = (dir.join("gemrs_debug_scene.svg"), dir.join("gemrs_debug_scene.ply"));
+        s.write_svg(&svg).unwrap();
+        s.write_ply(&ply).unwrap();
+        assert_eq!(fs::read_to_string(&svg).unwrap(), s.to_svg());
+        assert_eq!(fs::read_to_string(&ply).unwrap(), s.to_ply());
+        let _ = (fs::remove_file(svg), fs::remove_file(ply));
+    }
+}
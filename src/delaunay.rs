@@ -0,0 +1,389 @@
+//! Delaunay triangulation of planar point sets
+
+use std::cmp::Ordering;
+
+use crate::predicates::orient2d;
+use crate::{ Budget, Float, Point2, Rect2, Vector2 };
+use crate::stats;
+
+/// triangulate: [Point] -> [[usize; 3]]
+/// counter-clockwise triangles, as indices into `points`, whose circumcircles
+/// hold no other point (Bowyer–Watson insertion, each point located by a
+/// walk from the last new triangle). The hull is closed by a vertex at
+/// infinity rather than an enclosing triangle, so every hull edge is kept;
+/// repeated points are left out, and no triangles if all are on a line
+pub fn triangulate<T: Float>(points: &[Point2<T>]) -> Vec<[usize; 3]> {
+    triangulate_within(points, &mut Budget::unlimited()).expect("unlimited budget")
+}
+
+/// triangulate_within: [Point] × &mut Budget -> Option<[[usize; 3]]>
+/// `triangulate`, a unit of the budget per point inserted and per circle
+/// test; None if it runs out
+pub fn triangulate_within<T: Float>(points: &[Point2<T>], budget: &mut Budget) -> Option<Vec<[usize; 3]>> {
+    let _timer = stats::time("delaunay");
+    let Some(first) = first_triangle(points) else {
+        return Some(Vec::new());
+    };
+    Some(Mesh::new(points.to_vec(), first, budget)?.finite().collect())
+}
+
+/// Corners of a counter-clockwise triangle to start from: the first point,
+/// the next one elsewhere and the next one off their line; None if there
+/// is no such
+pub(crate) fn first_triangle<T: Float>(points: &[Point2<T>]) -> Option<[usize; 3]> {
+    let n = points.len();
+    let second = (1..n).find(|&i| points[i] != points[0])?;
+    let third = (second + 1..n).find(|&i| orient2d(points[0], points[second], points[i]) != Ordering::Equal)?;
+    Some(match orient2d(points[0], points[second], points[third]) {
+        Ordering::Greater => [0, second, third],
+        _ => [0, third, second],
+    })
+}
+
+/// Stand-in corner for the vertex at infinity
+pub(crate) const GHOST: usize = usize::MAX;
+
+/// Delaunay triangulation with the three neighbours of each triangle,
+/// closed by ghost triangles [b, a, GHOST] across each hull edge a → b so
+/// that every edge has a triangle on either side
+#[derive(Debug, Clone)]
+pub(crate) struct Mesh<T> {
+    pub(crate) points: Vec<Point2<T>>,
+    /// counter-clockwise corners, slots of removed triangles reused
+    pub(crate) corners: Vec<[usize; 3]>,
+    /// neighbours[t][k] lies across the edge opposite corner k of t
+    pub(crate) neighbours: Vec<[usize; 3]>,
+    /// circumcenter and squared radius, None for ghosts and degenerate ones
+    circles: Vec<Option<(Point2<T>, T)>>,
+    alive: Vec<bool>,
+    free: Vec<usize>,
+    /// a triangle made last, where walks start
+    last: usize,
+}
+
+impl<T: Float> Mesh<T> {
+    /// Delaunay triangulation of the points grown from the `first_triangle`
+    /// of them; None if the budget runs out
+    pub(crate) fn new(points: Vec<Point2<T>>, first: [usize; 3], budget: &mut Budget) -> Option<Self> {
+        let n = points.len();
+        let [a, b, c] = first;
+        let mut mesh = Mesh {
+            points,
+            corners: vec![[a, b, c], [b, a, GHOST], [c, b, GHOST], [a, c, GHOST]],
+            neighbours: vec![[2, 3, 1], [3, 2, 0], [1, 3, 0], [2, 1, 0]],
+            circles: Vec::new(),
+            alive: vec![true; 4],
+            free: Vec::new(),
+            last: 0,
+        };
+        mesh.circles = (0..4).map(|t| mesh.circle(mesh.corners[t])).collect();
+        let mut tests = 0;
+        let mut scratch = Scratch::default();
+        // along a Hilbert curve, so each walk starts next to its point
+        let mut order: Vec<usize> = (0..n).filter(|i| !first.contains(i)).collect();
+        if let Some(bounds) = Rect2::from_points(mesh.points.iter().copied()) {
+            let (lo, e) = (bounds.min(), bounds.max().vector_from(bounds.min()));
+            let cells = T::from_f64(65535.0);
+            let cell = |x: T, w: T| if w > T::zero() { ((x / w) * cells).to_f64() as u32 } else { 0 };
+            let key = |p: Point2<T>| hilbert(cell(p.0 - lo.0, e.0), cell(p.1 - lo.1, e.1));
+            order.sort_by_cached_key(|&i| key(mesh.points[i]));
+        }
+        for i in order {
+            if !budget.tick() {
+                return None;
+            }
+            mesh.insert(i, &mut scratch, &mut tests, budget)?;
+        }
+        stats::count("delaunay", "points", n);
+        stats::count("delaunay", "circle tests", tests);
+        Some(mesh)
+    }
+
+    /// Live triangles without a ghost corner
+    pub(crate) fn finite(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        (0..self.corners.len()).filter(|&t| self.alive[t] && !self.is_ghost(t)).map(|t| self.corners[t])
+    }
+
+    /// A live triangle at each point, None for repeated ones left out
+    pub(crate) fn incident(&self) -> Vec<Option<usize>> {
+        let mut at = vec![None; self.points.len()];
+        for t in (0..self.corners.len()).filter(|&t| self.alive[t]) {
+            for v in self.corners[t].into_iter().filter(|&v| v != GHOST) {
+                at[v] = Some(t);
+            }
+        }
+        at
+    }
+
+    /// Circumcenter of t, None for ghosts and degenerate triangles
+    pub(crate) fn center(&self, t: usize) -> Option<Point2<T>> {
+        self.circles[t].map(|(o, _)| o)
+    }
+
+    pub(crate) fn is_ghost(&self, t: usize) -> bool {
+        self.corners[t][2] == GHOST
+    }
+
+    fn circle(&self, [a, b, c]: [usize; 3]) -> Option<(Point2<T>, T)> {
+        if c == GHOST {
+            return None;
+        }
+        let [a, b, c] = [a, b, c].map(|i| self.points[i]);
+        circumcenter(a, b, c).map(|o| (o, o.vector_from(a).dot(o.vector_from(a))))
+    }
+
+    /// p strictly inside the circumcircle of t; for a ghost, strictly
+    /// beyond its hull edge or on it between its ends
+    pub(crate) fn conflicts(&self, t: usize, p: Point2<T>) -> bool {
+        if self.is_ghost(t) {
+            let [a, b, _] = self.corners[t];
+            let (a, b) = (self.points[a], self.points[b]);
+            return match orient2d(a, b, p) {
+                Ordering::Greater => true,
+                Ordering::Equal => p.vector_from(a).dot(p.vector_from(b)) < T::zero(),
+                Ordering::Less => false,
+            };
+        }
+        self.circles[t].is_some_and(|(o, r2)| p.vector_from(o).dot(p.vector_from(o)) < r2)
+    }
+
+    /// locate: the finite triangle holding p, edges included, or a ghost
+    /// whose hull edge p lies beyond; walks across the edges p is beyond,
+    /// trying them in turn from a different one each step
+    pub(crate) fn locate(&self, p: Point2<T>) -> usize {
+        self.locate_from(p, self.last)
+    }
+
+    /// `locate`, walking from triangle `start`
+    pub(crate) fn locate_from(&self, p: Point2<T>, start: usize) -> usize {
+        let mut t = if self.is_ghost(start) { self.neighbours[start][2] } else { start };
+        for step in 0..self.corners.len() {
+            let c = self.corners[t];
+            let beyond = (0..3).map(|s| (s + step) % 3).find(|&k| {
+                orient2d(self.points[c[(k + 1) % 3]], self.points[c[(k + 2) % 3]], p) == Ordering::Less
+            });
+            match beyond {
+                Some(k) => t = self.neighbours[t][k],
+                None => return t,
+            }
+            if self.is_ghost(t) {
+                return t;
+            }
+        }
+        // rounding sent the walk round in circles
+        let holds = |t: usize| {
+            let c = self.corners[t];
+            (0..3).all(|k| orient2d(self.points[c[(k + 1) % 3]], self.points[c[(k + 2) % 3]], p) != Ordering::Less)
+        };
+        (0..self.corners.len())
+            .find(|&t| self.alive[t] && if self.is_ghost(t) { self.conflicts(t, p) } else { holds(t) })
+            .unwrap_or(start)
+    }
+
+    /// The triangles in conflict with p connected to `start`, which counts
+    /// whatever its circle, breadth first into `found`; ghosts only with
+    /// `ghosts`. `seen(t)` is true the first time t comes up. How many
+    /// triangles were tested, a unit of the budget each; None if it runs out
+    pub(crate) fn cavity(
+        &self,
+        start: usize,
+        p: Point2<T>,
+        ghosts: bool,
+        mut seen: impl FnMut(usize) -> bool,
+        found: &mut Vec<usize>,
+        budget: &mut Budget,
+    ) -> Option<usize> {
+        let mut tests = 0;
+        found.clear();
+        seen(start);
+        found.push(start);
+        let mut k = 0;
+        while k < found.len() {
+            for u in self.neighbours[found[k]] {
+                if (ghosts || !self.is_ghost(u)) && seen(u) {
+                    if !budget.tick() {
+                        return None;
+                    }
+                    tests += 1;
+                    if self.conflicts(u, p) {
+                        found.push(u);
+                    }
+                }
+            }
+            k += 1;
+        }
+        Some(tests)
+    }
+
+    /// Bowyer–Watson step: p's cavity replaced by a fan of triangles
+    /// from p to its boundary
+    fn insert(&mut self, i: usize, scratch: &mut Scratch, tests: &mut usize, budget: &mut Budget) -> Option<()> {
+        let p = self.points[i];
+        let start = self.locate(p);
+        if self.corners[start].iter().any(|&v| v != GHOST && self.points[v] == p) {
+            return Some(());
+        }
+        // triangles seen this round are marked `round`, those in the cavity
+        // one more
+        scratch.round += 2;
+        let round = scratch.round;
+        scratch.mark.resize(self.corners.len(), 0);
+        let mark = &mut scratch.mark;
+        *tests += self.cavity(start, p, true, |t| std::mem::replace(&mut mark[t], round) < round, &mut scratch.cavity, budget)?;
+        for &t in &scratch.cavity {
+            scratch.mark[t] = round + 1;
+        }
+        // boundary edges u → w with the triangle outside and its corner
+        // facing back
+        scratch.fan.clear();
+        for &t in &scratch.cavity {
+            for k in 0..3 {
+                let out = self.neighbours[t][k];
+                if scratch.mark[out] == round + 1 {
+                    continue;
+                }
+                let (u, w) = (self.corners[t][(k + 1) % 3], self.corners[t][(k + 2) % 3]);
+                let back = (0..3).find(|&j| self.neighbours[out][j] == t).unwrap_or(0);
+                scratch.fan.push((u, w, out, back, 0));
+            }
+        }
+        self.free.extend(scratch.cavity.iter().copied());
+        for t in &scratch.cavity {
+            self.alive[*t] = false;
+        }
+        for f in scratch.fan.iter_mut() {
+            let (u, w, out, back, _) = *f;
+            let corners = if u == GHOST { [w, i, GHOST] } else if w == GHOST { [i, u, GHOST] } else { [u, w, i] };
+            let t = self.add(corners);
+            let facing = corners.iter().position(|&v| v == i).unwrap_or(0);
+            self.neighbours[t][facing] = out;
+            self.neighbours[out][back] = t;
+            f.4 = t;
+        }
+        // consecutive fan triangles share the edge from p to the vertex
+        // between them
+        scratch.fan.sort_unstable_by_key(|f| f.0);
+        for k in 0..scratch.fan.len() {
+            let (u, w, _, _, t) = scratch.fan[k];
+            let Ok(next) = scratch.fan.binary_search_by_key(&w, |f| f.0) else { continue };
+            let (_, x, _, _, s) = scratch.fan[next];
+            let at = |t: usize, v: usize| self.corners[t].iter().position(|&c| c == v).unwrap_or(0);
+            let (a, b) = (at(t, u), at(s, x));
+            self.neighbours[t][a] = s;
+            self.neighbours[s][b] = t;
+        }
+        self.last = scratch.fan.last().map_or(self.last, |f| f.4);
+        Some(())
+    }
+
+    fn add(&mut self, corners: [usize; 3]) -> usize {
+        let circle = self.circle(corners);
+        match self.free.pop() {
+            Some(t) => {
+                self.corners[t] = corners;
+                self.circles[t] = circle;
+                self.alive[t] = true;
+                t
+            }
+            None => {
+                self.corners.push(corners);
+                self.neighbours.push([0; 3]);
+                self.circles.push(circle);
+                self.alive.push(true);
+                self.corners.len() - 1
+            }
+        }
+    }
+}
+
+/// Position of cell (x, y) of a 2¹⁶ × 2¹⁶ grid along a Hilbert curve
+fn hilbert(mut x: u32, mut y: u32) -> u64 {
+    let mut d = 0;
+    let mut s = 1u32 << 15;
+    while s > 0 {
+        let (rx, ry) = ((x & s > 0) as u32, (y & s > 0) as u32);
+        d += u64::from(s) * u64::from(s) * u64::from((3 * rx) ^ ry);
+        // rotate the quadrant so the curve inside runs the same way
+        if ry == 0 {
+            if rx == 1 {
+                x = s - 1 - (x & (s - 1));
+                y = s - 1 - (y & (s - 1));
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s >>= 1;
+    }
+    d
+}
+
+/// Buffers reused from one insertion to the next
+#[derive(Default)]
+struct Scratch {
+    /// round in which each triangle was last seen, plus one if taken
+    mark: Vec<usize>,
+    round: usize,
+    cavity: Vec<usize>,
+    /// boundary edge u → w, the triangle outside and its corner facing
+    /// in, and the fan triangle made on it
+    fan: Vec<(usize, usize, usize, usize, usize)>,
+}
+
+/// Center of the circle through a, b, c; None if collinear
+pub(crate) fn circumcenter<T: Float>(a: Point2<T>, b: Point2<T>, c: Point2<T>) -> Option<Point2<T>> {
+    let (u, v) = (b.vector_from(a), c.vector_from(a));
+    let d = (u.0 * v.1 - u.1 * v.0) * T::from_f64(2.0);
+    if d == T::zero() {
+        return None;
+    }
+    let (uu, vv) = (u.dot(u), v.dot(v));
+    Some(a + Vector2(v.1 * uu - u.1 * vv, u.0 * vv - v.0 * uu) / d)
+}
+
+/// Twice the signed area of abc, positive when counter-clockwise
+pub(crate) fn cross<T: Float>(a: Point2<T>, b: Point2<T>, c: Point2<T>) -> T {
+    let (u, v) = (b.vector_from(a), c.vector_from(a));
+    u.0 * v.1 - u.1 * v.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scattered(n: usize) -> Vec<Point2<f64>> {
+        let mut seed = 987654321u64;
+        let mut next = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            0.05 + 0.9 * ((seed >> 11) as f64 / (1u64 << 53) as f64)
+        };
+        let mut points = vec![Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(1.0, 1.0), Point2(0.0, 1.0)];
+        for _ in 0..n {
+            points.push(Point2(next(), next()));
+        }
+        points
+    }
+
+    #[test]
+    fn empty_circumcircles() {
+        let points = scattered(60);
+        let triangles = triangulate(&points);
+        let area: f64 = triangles.iter().map(|&[a, b, c]| cross(points[a], points[b], points[c]) / 2.0).sum();
+        assert!((area - 1.0).abs() < 1e-12);
+        // Euler: 2n - h - 2 triangles for n points with h on the hull
+        assert_eq!(triangles.len(), 2 * points.len() - 4 - 2);
+        for &[a, b, c] in &triangles {
+            assert!(cross(points[a], points[b], points[c]) > 0.0);
+            let o = circumcenter(points[a], points[b], points[c]).unwrap();
+            let r = o.distance(points[a]);
+            assert!(points.iter().all(|p| p.distance(o) >= r - 1e-12));
+        }
+        // a hull bulging out by next to nothing keeps all its edges, and a
+        // repeated point is left out
+        let mut arc: Vec<_> = (0..=10).map(|i| Point2(i as f64, -1e-9 * (i * (10 - i)) as f64)).collect();
+        arc.extend([Point2(5.0, 5.0), arc[3]]);
+        let triangles = triangulate(&arc);
+        assert_eq!(triangles.len(), 10);
+        assert!(triangles.iter().all(|t| t.contains(&11) && !t.contains(&12)));
+        assert!(triangulate::<f64>(&[]).is_empty());
+        assert!(triangulate(&[Point2(0.0, 0.0), Point2(1.0, 1.0), Point2(2.0, 2.0)]).is_empty());
+    }
+}
@@ -0,0 +1,289 @@
+//! Dual numbers for forward-mode automatic differentiation
+
+use std::cmp::{ Ordering, PartialOrd };
+use std::ops::{ Add, Sub, Neg, Mul, Div };
+
+use crate::{ Float, Vector2, Vector3, Point2, Point3 };
+
+/// Dual number a + bε where ε² = 0
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Dual<T>(T, T);
+
+impl<T> Dual<T> {
+    /// new: K × K -> Dual
+    pub fn new(re: T, eps: T) -> Self {
+        Dual(re, eps)
+    }
+}
+
+impl<T: Copy> Dual<T> {
+    /// re: &Dual -> K
+    pub fn re(&self) -> T {
+        self.0
+    }
+
+    /// eps: &Dual -> K
+    pub fn eps(&self) -> T {
+        self.1
+    }
+}
+
+impl<T: Float> Dual<T> {
+    /// constant: K -> Dual
+    /// a + 0ε
+    pub fn constant(re: T) -> Self {
+        Dual(re, T::zero())
+    }
+
+    /// variable: K -> Dual
+    /// a + 1ε
+    pub fn variable(re: T) -> Self {
+        Dual(re, T::one())
+    }
+}
+
+/// (<): &Dual × &Dual -> bool
+/// ordered by the real part first
+impl<T: PartialOrd> PartialOrd for Dual<T> {
+    fn partial_cmp(&self, d: &Dual<T>) -> Option<Ordering> {
+        match self.0.partial_cmp(&d.0) {
+            Some(Ordering::Equal) => self.1.partial_cmp(&d.1),
+            ord => ord,
+        }
+    }
+}
+
+/// (-): Dual -> Dual
+impl<T: Neg<Output=T>> Neg for Dual<T> {
+    type Output = Dual<T>;
+
+    fn neg(self) -> Self::Output {
+        Dual(-self.0, -self.1)
+    }
+}
+
+/// (+): Dual × Dual -> Dual
+impl<T: Add<Output=T>> Add for Dual<T> {
+    type Output = Dual<T>;
+
+    fn add(self, d: Dual<T>) -> Self::Output {
+        Dual(self.0 + d.0, self.1 + d.1)
+    }
+}
+
+/// (-): Dual × Dual -> Dual
+impl<T: Sub<Output=T>> Sub for Dual<T> {
+    type Output = Dual<T>;
+
+    fn sub(self, d: Dual<T>) -> Self::Output {
+        Dual(self.0 - d.0, self.1 - d.1)
+    }
+}
+
+/// (*): Dual × Dual -> Dual
+/// (a + bε)(c + dε) = ac + (ad + bc)ε
+impl<T: Add<Output=T> + Mul<Output=T> + Copy> Mul for Dual<T> {
+    type Output = Dual<T>;
+
+    fn mul(self, d: Dual<T>) -> Self::Output {
+        Dual(self.0 * d.0, self.0 * d.1 + self.1 * d.0)
+    }
+}
+
+/// (/): Dual × Dual -> Dual
+/// (a + bε) / (c + dε) = a/c + (bc - ad)/c² ε
+impl<T: Sub<Output=T> + Mul<Output=T> + Div<Output=T> + Copy> Div for Dual<T> {
+    type Output = Dual<T>;
+
+    fn div(self, d: Dual<T>) -> Self::Output {
+        Dual(self.0 / d.0, (self.1 * d.0 - self.0 * d.1) / (d.0 * d.0))
+    }
+}
+
+impl<T: Float> Float for Dual<T> {
+    fn zero() -> Self {
+        Dual::constant(T::zero())
+    }
+
+    fn one() -> Self {
+        Dual::constant(T::one())
+    }
+
+    fn infinity() -> Self {
+        Dual::constant(T::infinity())
+    }
+
+    fn pi() -> Self {
+        Dual::constant(T::pi())
+    }
+
+    fn from_f64(x: f64) -> Self {
+        Dual::constant(T::from_f64(x))
+    }
+
+    /// of the real part
+    fn to_f64(self) -> f64 {
+        self.0.to_f64()
+    }
+
+    fn epsilon() -> Self {
+        Dual::constant(T::epsilon())
+    }
+
+    fn abs(self) -> Self {
+        if self.0 < T::zero() { -self } else { self }
+    }
+
+    /// floor is locally constant
+    fn floor(self) -> Self {
+        Dual::constant(self.0.floor())
+    }
+
+    /// sqrt(a + bε) = √a + b/(2√a) ε
+    fn sqrt(self) -> Self {
+        let s = self.0.sqrt();
+        Dual(s, self.1 / (s + s))
+    }
+
+    fn sin(self) -> Self {
+        Dual(self.0.sin(), self.1 * self.0.cos())
+    }
+
+    fn cos(self) -> Self {
+        Dual(self.0.cos(), -self.1 * self.0.sin())
+    }
+
+    /// ∂atan2(y, x) = (x dy - y dx) / (x² + y²)
+    fn atan2(self, x: Self) -> Self {
+        let (y, x) = (self, x);
+        Dual(y.0.atan2(x.0), (x.0 * y.1 - y.0 * x.1) / (x.0 * x.0 + y.0 * y.0))
+    }
+
+    fn next_up(self) -> Self {
+        Dual(self.0.next_up(), self.1)
+    }
+
+    fn next_down(self) -> Self {
+        Dual(self.0.next_down(), self.1)
+    }
+
+    fn is_finite(self) -> bool {
+        self.0.is_finite() && self.1.is_finite()
+    }
+}
+
+/// gradient2: (Point<Dual> -> Dual) × Point -> Vector
+/// ∇f at p
+pub fn gradient2<T, F>(f: F, p: Point2<T>) -> Vector2<T>
+where
+    T: Float,
+    F: Fn(Point2<Dual<T>>) -> Dual<T>,
+{
+    let [dx, dy] = seeds2(p);
+    Vector2(f(dx).1, f(dy).1)
+}
+
+/// gradient3: (Point<Dual> -> Dual) × Point -> Vector
+/// ∇f at p
+pub fn gradient3<T, F>(f: F, p: Point3<T>) -> Vector3<T>
+where
+    T: Float,
+    F: Fn(Point3<Dual<T>>) -> Dual<T>,
+{
+    let [dx, dy, dz] = seeds3(p);
+    Vector3(f(dx).1, f(dy).1, f(dz).1)
+}
+
+/// jacobian2: (Point<Dual> -> Point<Dual>) × Point -> [Vector; 2]
+/// columns ∂f/∂x, ∂f/∂y of the transform f at p
+pub fn jacobian2<T, F>(f: F, p: Point2<T>) -> [Vector2<T>; 2]
+where
+    T: Float,
+    F: Fn(Point2<Dual<T>>) -> Point2<Dual<T>>,
+{
+    seeds2(p).map(|d| {
+        let q = f(d);
+        Vector2(q.0.1, q.1.1)
+    })
+}
+
+/// jacobian3: (Point<Dual> -> Point<Dual>) × Point -> [Vector; 3]
+/// columns ∂f/∂x, ∂f/∂y, ∂f/∂z of the transform f at p
+pub fn jacobian3<T, F>(f: F, p: Point3<T>) -> [Vector3<T>; 3]
+where
+    T: Float,
+    F: Fn(Point3<Dual<T>>) -> Point3<Dual<T>>,
+{
+    seeds3(p).map(|d| {
+        let q = f(d);
+        Vector3(q.0.1, q.1.1, q.2.1)
+    })
+}
+
+fn seeds2<T: Float>(p: Point2<T>) -> [Point2<Dual<T>>; 2] {
+    let (x, y) = (Dual::constant(p.0), Dual::constant(p.1));
+    [
+        Point2(Dual::variable(p.0), y),
+        Point2(x, Dual::variable(p.1)),
+    ]
+}
+
+fn seeds3<T: Float>(p: Point3<T>) -> [Point3<Dual<T>>; 3] {
+    let (x, y, z) = (Dual::constant(p.0), Dual::constant(p.1), Dual::constant(p.2));
+    [
+        Point3(Dual::variable(p.0), y, z),
+        Point3(x, Dual::variable(p.1), z),
+        Point3(x, y, Dual::variable(p.2)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arith_dual() {
+        let a = Dual(3.0, 1.0);
+        let b = Dual(2.0, 0.0);
+        assert_eq!(a + b, Dual(5.0, 1.0));
+        assert_eq!(a - b, Dual(1.0, 1.0));
+        assert_eq!(a * a, Dual(9.0, 6.0));
+        assert_eq!(b / a, Dual(2.0 / 3.0, -2.0 / 9.0));
+        assert_eq!(Dual(4.0, 1.0).sqrt(), Dual(2.0, 0.25));
+    }
+
+    #[test]
+    fn trig_dual() {
+        let x = Dual::variable(0.5f64);
+        assert_eq!(x.sin(), Dual(0.5f64.sin(), 0.5f64.cos()));
+        assert_eq!(x.cos(), Dual(0.5f64.cos(), -0.5f64.sin()));
+        assert_eq!(x.atan2(Dual::constant(1.0)), Dual(0.5f64.atan(), 1.0 / 1.25));
+        assert_eq!(Dual(2.5, 1.0).floor(), Dual(2.0, 0.0));
+    }
+
+    #[test]
+    fn vec_of_dual() {
+        let v = Vector2(Dual::variable(1.0), Dual::constant(2.0));
+        assert_eq!(v * Dual::constant(3.0), Vector2(Dual(3.0, 3.0), Dual(6.0, 0.0)));
+        assert_eq!(v.dot(v), Dual(5.0, 2.0));
+        assert_eq!(v.length(), Dual(5.0f64.sqrt(), 1.0 / 5.0f64.sqrt()));
+    }
+
+    #[test]
+    fn gradient_of_distance() {
+        let c = Point2(Dual::constant(1.0), Dual::constant(1.0));
+        let g = gradient2(|p| p.distance(c), Point2(4.0, 5.0));
+        assert_eq!(g, Vector2(0.6, 0.8));
+        let c = Point3(Dual::constant(0.0), Dual::constant(0.0), Dual::constant(0.0));
+        let g = gradient3(|p| p.distance(c), Point3(0.0, 3.0, 4.0));
+        assert_eq!(g, Vector3(0.0, 0.6, 0.8));
+    }
+
+    #[test]
+    fn jacobian_of_transform() {
+        let j = jacobian2(|p| Point2(p.0 * p.1, p.0 + p.1 * Dual::constant(2.0)), Point2(3.0, 5.0));
+        assert_eq!(j, [Vector2(5.0, 1.0), Vector2(3.0, 2.0)]);
+        let j = jacobian3(|p| p + Vector3(p.1, p.2, p.0), Point3(1.0, 2.0, 3.0));
+        assert_eq!(j, [Vector3(1.0, 0.0, 1.0), Vector3(1.0, 1.0, 0.0), Vector3(0.0, 1.0, 1.0)]);
+    }
+}
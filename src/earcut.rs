@@ -0,0 +1,483 @@
+//! Ear clipping with hole bridging, after mapbox's earcut
+//!
+//! Rings become one doubly linked list: each hole, leftmost first, is cut
+//! into the exterior along a bridge to a visible vertex. Ears are then
+//! clipped from the list; when none is left the list is cleaned of
+//! repeated and collinear vertices, then of local self-intersections, and
+//! at last split along a valid diagonal and each half clipped on its own.
+//! No spatial index is kept, so polygons of thousands of vertices go
+//! quadratic: the path is meant for many small ones.
+
+use crate::stats;
+use crate::{ Float, Point2 };
+
+/// Vertex of a ring list
+#[derive(Debug, Clone, Copy)]
+struct Node<T> {
+    /// index into the concatenated rings
+    i: usize,
+    x: T,
+    y: T,
+    prev: usize,
+    next: usize,
+    /// hole of a single point, kept through filtering
+    steiner: bool,
+}
+
+/// Ring lists over a shared arena
+struct Earcut<T> {
+    nodes: Vec<Node<T>>,
+    triangles: Vec<[usize; 3]>,
+    /// candidate ears tested, for the `stats` report
+    ear_tests: usize,
+}
+
+/// earcut: [Ring] -> [[usize; 3]]
+/// triangles of the exterior `rings[0]` less the holes `rings[1..]`, indexing
+/// into the rings concatenated in order, counter-clockwise whatever the ring
+/// orientations
+pub(crate) fn earcut<T: Float>(rings: &[&[Point2<T>]]) -> Vec<[usize; 3]> {
+    let Some((exterior, holes)) = rings.split_first() else {
+        return Vec::new();
+    };
+    let _timer = stats::time("earcut");
+    let mut e = Earcut { nodes: Vec::new(), triangles: Vec::new(), ear_tests: 0 };
+    let Some(mut outer) = e.ring(exterior, 0, true) else {
+        return Vec::new();
+    };
+    if e.nodes[outer].next == e.nodes[outer].prev {
+        return Vec::new();
+    }
+    let mut queue = Vec::new();
+    let mut offset = exterior.len();
+    for hole in holes {
+        if let Some(list) = e.ring(hole, offset, false) {
+            if e.nodes[list].next == list {
+                e.nodes[list].steiner = true;
+            }
+            queue.push(e.leftmost(list));
+        }
+        offset += hole.len();
+    }
+    queue.sort_by(|&a, &b| {
+        let (a, b) = (e.nodes[a], e.nodes[b]);
+        a.x.partial_cmp(&b.x).unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.y.partial_cmp(&b.y).unwrap_or(std::cmp::Ordering::Equal))
+    });
+    stats::count("earcut", "holes", queue.len());
+    for hole in queue {
+        outer = e.eliminate_hole(hole, outer);
+    }
+    e.clip(outer, 0);
+    stats::count("earcut", "ear tests", e.ear_tests);
+    e.triangles
+}
+
+/// Twice the signed area, positive counter-clockwise
+fn signed_area<T: Float>(ring: &[Point2<T>]) -> T {
+    let n = ring.len();
+    (0..n).fold(T::zero(), |s, k| {
+        let (a, b) = (ring[k], ring[(k + 1) % n]);
+        s + a.0 * b.1 - b.0 * a.1
+    })
+}
+
+/// Twice the signed area of p q r, positive clockwise
+fn area<T: Float>(p: Node<T>, q: Node<T>, r: Node<T>) -> T {
+    (q.y - p.y) * (r.x - q.x) - (q.x - p.x) * (r.y - q.y)
+}
+
+fn equals<T: Float>(p: Node<T>, q: Node<T>) -> bool {
+    p.x == q.x && p.y == q.y
+}
+
+/// -1, 0 or 1
+fn sign<T: Float>(x: T) -> i32 {
+    if x > T::zero() {
+        1
+    } else if x < T::zero() {
+        -1
+    } else {
+        0
+    }
+}
+
+/// Whether q lies in the bounding box of p r, for q collinear with them
+fn on_segment<T: Float>(p: Node<T>, q: Node<T>, r: Node<T>) -> bool {
+    q.x <= max(p.x, r.x) && q.x >= min(p.x, r.x) && q.y <= max(p.y, r.y) && q.y >= min(p.y, r.y)
+}
+
+fn min<T: Float>(a: T, b: T) -> T {
+    if b < a { b } else { a }
+}
+
+fn max<T: Float>(a: T, b: T) -> T {
+    if b > a { b } else { a }
+}
+
+/// Whether segments p1 q1 and p2 q2 meet, touching included
+fn intersects<T: Float>(p1: Node<T>, q1: Node<T>, p2: Node<T>, q2: Node<T>) -> bool {
+    let o1 = sign(area(p1, q1, p2));
+    let o2 = sign(area(p1, q1, q2));
+    let o3 = sign(area(p2, q2, p1));
+    let o4 = sign(area(p2, q2, q1));
+    (o1 != o2 && o3 != o4)
+        || (o1 == 0 && on_segment(p1, p2, q1))
+        || (o2 == 0 && on_segment(p1, q2, q1))
+        || (o3 == 0 && on_segment(p2, p1, q2))
+        || (o4 == 0 && on_segment(p2, q1, q2))
+}
+
+/// Whether p lies in triangle a b c, boundary included
+#[allow(clippy::too_many_arguments)]
+fn in_triangle<T: Float>(ax: T, ay: T, bx: T, by: T, cx: T, cy: T, px: T, py: T) -> bool {
+    (cx - px) * (ay - py) >= (ax - px) * (cy - py)
+        && (ax - px) * (by - py) >= (bx - px) * (ay - py)
+        && (bx - px) * (cy - py) >= (cx - px) * (by - py)
+}
+
+impl<T: Float> Earcut<T> {
+    fn at(&self, k: usize) -> Node<T> {
+        self.nodes[k]
+    }
+
+    fn next(&self, k: usize) -> usize {
+        self.nodes[k].next
+    }
+
+    fn prev(&self, k: usize) -> usize {
+        self.nodes[k].prev
+    }
+
+    /// Appends a node after `last`, or a list of one without it
+    fn insert(&mut self, i: usize, p: Point2<T>, last: Option<usize>) -> usize {
+        let k = self.nodes.len();
+        let mut node = Node { i, x: p.0, y: p.1, prev: k, next: k, steiner: false };
+        if let Some(last) = last {
+            node.prev = last;
+            node.next = self.next(last);
+            let after = self.next(last);
+            self.nodes[after].prev = k;
+            self.nodes[last].next = k;
+        }
+        self.nodes.push(node);
+        k
+    }
+
+    fn remove(&mut self, k: usize) {
+        let (p, n) = (self.prev(k), self.next(k));
+        self.nodes[n].prev = p;
+        self.nodes[p].next = n;
+    }
+
+    /// Links a ring counter-clockwise for the exterior, clockwise for holes
+    fn ring(&mut self, ring: &[Point2<T>], offset: usize, exterior: bool) -> Option<usize> {
+        let mut last = None;
+        if exterior == (signed_area(ring) > T::zero()) {
+            for (k, &p) in ring.iter().enumerate() {
+                last = Some(self.insert(offset + k, p, last));
+            }
+        } else {
+            for (k, &p) in ring.iter().enumerate().rev() {
+                last = Some(self.insert(offset + k, p, last));
+            }
+        }
+        if let Some(l) = last {
+            if l != self.next(l) && equals(self.at(l), self.at(self.next(l))) {
+                let n = self.next(l);
+                self.remove(l);
+                last = Some(n);
+            }
+        }
+        last
+    }
+
+    /// Drops repeated and collinear vertices between `start` and `end`
+    fn filter(&mut self, start: usize, end: Option<usize>) -> usize {
+        let mut end = end.unwrap_or(start);
+        let mut p = start;
+        loop {
+            let mut again = false;
+            let node = self.at(p);
+            let (prev, next) = (self.at(node.prev), self.at(node.next));
+            if !node.steiner && (equals(node, next) || area(prev, node, next) == T::zero()) {
+                self.remove(p);
+                p = node.prev;
+                end = p;
+                if p == self.next(p) {
+                    break;
+                }
+                again = true;
+            } else {
+                p = node.next;
+            }
+            if !again && p == end {
+                break;
+            }
+        }
+        end
+    }
+
+    /// Clips ears off the list, falling back pass by pass
+    fn clip(&mut self, start: usize, pass: u8) {
+        let mut ear = start;
+        let mut stop = ear;
+        while self.prev(ear) != self.next(ear) {
+            let (prev, next) = (self.prev(ear), self.next(ear));
+            self.ear_tests += 1;
+            if self.is_ear(ear) {
+                self.triangles.push([self.at(prev).i, self.at(ear).i, self.at(next).i]);
+                self.remove(ear);
+                // skipping the next vertex leaves fewer slivers
+                ear = self.next(next);
+                stop = ear;
+                continue;
+            }
+            ear = next;
+            if ear == stop {
+                match pass {
+                    0 => {
+                        let f = self.filter(ear, None);
+                        self.clip(f, 1);
+                    }
+                    1 => {
+                        let f = self.filter(ear, None);
+                        let cured = self.cure_local_intersections(f);
+                        self.clip(cured, 2);
+                    }
+                    _ => self.split(ear),
+                }
+                break;
+            }
+        }
+    }
+
+    fn is_ear(&self, ear: usize) -> bool {
+        let (a, b, c) = (self.at(self.prev(ear)), self.at(ear), self.at(self.next(ear)));
+        if area(a, b, c) >= T::zero() {
+            return false;
+        }
+        let (x0, x1) = (min(a.x, min(b.x, c.x)), max(a.x, max(b.x, c.x)));
+        let (y0, y1) = (min(a.y, min(b.y, c.y)), max(a.y, max(b.y, c.y)));
+        let mut k = c.next;
+        while k != self.prev(ear) {
+            let p = self.at(k);
+            if p.x >= x0 && p.x <= x1 && p.y >= y0 && p.y <= y1
+                && !(a.x == p.x && a.y == p.y)
+                && in_triangle(a.x, a.y, b.x, b.y, c.x, c.y, p.x, p.y)
+                && area(self.at(p.prev), p, self.at(p.next)) >= T::zero()
+            {
+                return false;
+            }
+            k = p.next;
+        }
+        true
+    }
+
+    /// Clips the two triangles around each crossing of neighbouring edges
+    fn cure_local_intersections(&mut self, start: usize) -> usize {
+        let (mut start, mut p) = (start, start);
+        loop {
+            let a = self.prev(p);
+            let b = self.next(self.next(p));
+            let (na, nb) = (self.at(a), self.at(b));
+            if !equals(na, nb) && intersects(na, self.at(p), self.at(self.next(p)), nb)
+                && self.locally_inside(a, b) && self.locally_inside(b, a)
+            {
+                self.triangles.push([na.i, self.at(p).i, nb.i]);
+                let n = self.next(p);
+                self.remove(p);
+                self.remove(n);
+                p = b;
+                start = b;
+            }
+            p = self.next(p);
+            if p == start {
+                break;
+            }
+        }
+        self.filter(p, None)
+    }
+
+    /// Splits the list along some valid diagonal and clips both halves
+    fn split(&mut self, start: usize) {
+        let mut a = start;
+        loop {
+            let mut b = self.next(self.next(a));
+            while b != self.prev(a) {
+                if self.at(a).i != self.at(b).i && self.is_valid_diagonal(a, b) {
+                    let c = self.split_polygon(a, b);
+                    let a = self.filter(a, Some(self.next(a)));
+                    let c = self.filter(c, Some(self.next(c)));
+                    self.clip(a, 0);
+                    self.clip(c, 0);
+                    return;
+                }
+                b = self.next(b);
+            }
+            a = self.next(a);
+            if a == start {
+                return;
+            }
+        }
+    }
+
+    fn is_valid_diagonal(&self, a: usize, b: usize) -> bool {
+        let (na, nb) = (self.at(a), self.at(b));
+        let (ap, an, bp, bn) = (self.at(na.prev), self.at(na.next), self.at(nb.prev), self.at(nb.next));
+        an.i != nb.i && ap.i != nb.i && !self.intersects_polygon(a, b)
+            && ((self.locally_inside(a, b) && self.locally_inside(b, a) && self.middle_inside(a, b)
+                && (area(ap, na, bp) != T::zero() || area(na, bp, nb) != T::zero()))
+                || (equals(na, nb) && area(ap, na, an) > T::zero() && area(bp, nb, bn) > T::zero()))
+    }
+
+    /// Whether diagonal a b crosses an edge of the list
+    fn intersects_polygon(&self, a: usize, b: usize) -> bool {
+        let (na, nb) = (self.at(a), self.at(b));
+        let mut p = a;
+        loop {
+            let (np, nn) = (self.at(p), self.at(self.next(p)));
+            if np.i != na.i && nn.i != na.i && np.i != nb.i && nn.i != nb.i && intersects(np, nn, na, nb) {
+                return true;
+            }
+            p = np.next;
+            if p == a {
+                return false;
+            }
+        }
+    }
+
+    /// Whether diagonal a b leaves a into the polygon
+    fn locally_inside(&self, a: usize, b: usize) -> bool {
+        let (na, nb) = (self.at(a), self.at(b));
+        let (ap, an) = (self.at(na.prev), self.at(na.next));
+        if area(ap, na, an) < T::zero() {
+            area(na, nb, an) >= T::zero() && area(na, ap, nb) >= T::zero()
+        } else {
+            area(na, nb, ap) < T::zero() || area(na, an, nb) < T::zero()
+        }
+    }
+
+    /// Whether the midpoint of a b is inside the list
+    fn middle_inside(&self, a: usize, b: usize) -> bool {
+        let (na, nb) = (self.at(a), self.at(b));
+        let half = T::from_f64(0.5);
+        let (px, py) = ((na.x + nb.x) * half, (na.y + nb.y) * half);
+        let (mut p, mut inside) = (a, false);
+        loop {
+            let (np, nn) = (self.at(p), self.at(self.next(p)));
+            if (np.y > py) != (nn.y > py) && nn.y != np.y
+                && px < (nn.x - np.x) * (py - np.y) / (nn.y - np.y) + np.x
+            {
+                inside = !inside;
+            }
+            p = np.next;
+            if p == a {
+                return inside;
+            }
+        }
+    }
+
+    /// Joins the lists of a and b along a b, doubling both; returns the
+    /// copy of b, which starts the other side
+    fn split_polygon(&mut self, a: usize, b: usize) -> usize {
+        let (na, nb) = (self.at(a), self.at(b));
+        let a2 = self.nodes.len();
+        let b2 = a2 + 1;
+        self.nodes.push(Node { prev: a2, next: a2, steiner: false, ..na });
+        self.nodes.push(Node { prev: b2, next: b2, steiner: false, ..nb });
+        let (an, bp) = (na.next, nb.prev);
+        self.nodes[a].next = b;
+        self.nodes[b].prev = a;
+        self.nodes[a2].next = an;
+        self.nodes[an].prev = a2;
+        self.nodes[b2].next = a2;
+        self.nodes[a2].prev = b2;
+        self.nodes[bp].next = b2;
+        self.nodes[b2].prev = bp;
+        b2
+    }
+
+    fn leftmost(&self, start: usize) -> usize {
+        let (mut p, mut left) = (start, start);
+        loop {
+            let (np, nl) = (self.at(p), self.at(left));
+            if np.x < nl.x || (np.x == nl.x && np.y < nl.y) {
+                left = p;
+            }
+            p = np.next;
+            if p == start {
+                return left;
+            }
+        }
+    }
+
+    /// Bridges a hole into the outer list
+    fn eliminate_hole(&mut self, hole: usize, outer: usize) -> usize {
+        let Some(bridge) = self.find_hole_bridge(hole, outer) else {
+            return outer;
+        };
+        let reverse = self.split_polygon(bridge, hole);
+        self.filter(reverse, Some(self.next(reverse)));
+        self.filter(bridge, Some(self.next(bridge)))
+    }
+
+    /// Outer vertex visible from the leftmost point of the hole
+    fn find_hole_bridge(&self, hole: usize, outer: usize) -> Option<usize> {
+        let h = self.at(hole);
+        let (hx, hy) = (h.x, h.y);
+        let mut qx = -T::infinity();
+        let mut m = None;
+        // nearest edge crossed by a ray from the hole to the left
+        let mut p = outer;
+        loop {
+            let (np, nn) = (self.at(p), self.at(self.next(p)));
+            if hy <= np.y && hy >= nn.y && nn.y != np.y {
+                let x = np.x + (hy - np.y) * (nn.x - np.x) / (nn.y - np.y);
+                if x <= hx && x > qx {
+                    qx = x;
+                    m = Some(if np.x < nn.x { p } else { np.next });
+                    if x == hx {
+                        // the hole touches the edge
+                        return m;
+                    }
+                }
+            }
+            p = np.next;
+            if p == outer {
+                break;
+            }
+        }
+        let mut m = m?;
+        // a reflex vertex inside the triangle of hole, crossing and edge end
+        // hides that end; of those take the one at the least angle
+        let stop = m;
+        let (mx, my) = (self.at(m).x, self.at(m).y);
+        let mut tan_min = T::infinity();
+        p = m;
+        loop {
+            let np = self.at(p);
+            let (ax, cx) = if hy < my { (hx, qx) } else { (qx, hx) };
+            if hx >= np.x && np.x >= mx && hx != np.x && in_triangle(ax, hy, mx, my, cx, hy, np.x, np.y) {
+                let tan = (hy - np.y).abs() / (hx - np.x);
+                let nm = self.at(m);
+                if self.locally_inside(p, hole)
+                    && (tan < tan_min || (tan == tan_min && (np.x > nm.x || (np.x == nm.x && self.sector_contains_sector(m, p)))))
+                {
+                    m = p;
+                    tan_min = tan;
+                }
+            }
+            p = np.next;
+            if p == stop {
+                return Some(m);
+            }
+        }
+    }
+
+    /// Whether the sector at p lies within the sector at m
+    fn sector_contains_sector(&self, m: usize, p: usize) -> bool {
+        let (nm, np) = (self.at(m), self.at(p));
+        area(self.at(nm.prev), nm, self.at(np.prev)) < T::zero() && area(self.at(np.next), nm, self.at(nm.next)) < T::zero()
+    }
+}
@@ -0,0 +1,196 @@
+//! Euler angles in any of the twelve axis orders
+//!
+//! The order names the fixed (extrinsic) axes turned about, first to last:
+//! `Xyz` with angles (a, b, c) is Rz(c) Ry(b) Rx(a). Read right to left the
+//! same product is the intrinsic sequence z, y′, x″, so yaw-pitch-roll
+//! about moving axes is `Xyz` with the angles (roll, pitch, yaw).
+//!
+//! Converting back, the middle angle comes from the one matrix entry it
+//! alone decides, and the outer two from how far the first axis's image
+//! and the last axis's preimage are turned about their own axes.
+
+use crate::{ Float, Rotor3, Vector3 };
+
+/// Axis order of Euler angles, first turn first: Tait-Bryan orders use
+/// three different axes, proper Euler orders repeat the first
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EulerOrder {
+    Xyz,
+    Xzy,
+    Yxz,
+    Yzx,
+    Zxy,
+    Zyx,
+    Xyx,
+    Xzx,
+    Yxy,
+    Yzy,
+    Zxz,
+    Zyz,
+}
+
+/// Three turns about fixed axes in the given order
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EulerAngles<T> {
+    pub order: EulerOrder,
+    pub angles: [T; 3],
+}
+
+impl EulerOrder {
+    /// axes: EulerOrder -> [usize; 3]
+    /// indices of the axes turned about, x = 0
+    pub fn axes(self) -> [usize; 3] {
+        match self {
+            EulerOrder::Xyz => [0, 1, 2],
+            EulerOrder::Xzy => [0, 2, 1],
+            EulerOrder::Yxz => [1, 0, 2],
+            EulerOrder::Yzx => [1, 2, 0],
+            EulerOrder::Zxy => [2, 0, 1],
+            EulerOrder::Zyx => [2, 1, 0],
+            EulerOrder::Xyx => [0, 1, 0],
+            EulerOrder::Xzx => [0, 2, 0],
+            EulerOrder::Yxy => [1, 0, 1],
+            EulerOrder::Yzy => [1, 2, 1],
+            EulerOrder::Zxz => [2, 0, 2],
+            EulerOrder::Zyz => [2, 1, 2],
+        }
+    }
+
+    /// is_proper: EulerOrder -> bool
+    /// whether the first axis is turned about again last
+    pub fn is_proper(self) -> bool {
+        let [i, _, k] = self.axes();
+        i == k
+    }
+}
+
+fn axis<T: Float>(i: usize) -> Vector3<T> {
+    let (zero, one) = (T::zero(), T::one());
+    [Vector3(one, zero, zero), Vector3(zero, one, zero), Vector3(zero, zero, one)][i]
+}
+
+fn component<T: Float>(v: Vector3<T>, i: usize) -> T {
+    [v.0, v.1, v.2][i]
+}
+
+/// Turn about the axis taking the part of `from` across it to that of `to`
+fn angle_about<T: Float>(axis: Vector3<T>, from: Vector3<T>, to: Vector3<T>) -> T {
+    let (f, t) = (from - axis * axis.dot(from), to - axis * axis.dot(to));
+    axis.dot(f.cross(t)).atan2(f.dot(t))
+}
+
+impl<T: Float> EulerAngles<T> {
+    /// new: EulerOrder × [K; 3] -> EulerAngles
+    pub fn new(order: EulerOrder, angles: [T; 3]) -> Self {
+        EulerAngles { order, angles }
+    }
+
+    /// to_rotor: &EulerAngles -> Rotor
+    pub fn to_rotor(&self) -> Rotor3<T> {
+        let [i, j, k] = self.order.axes();
+        let [a, b, c] = self.angles;
+        Rotor3::exp(axis::<T>(k) * c) * Rotor3::exp(axis::<T>(j) * b) * Rotor3::exp(axis::<T>(i) * a)
+    }
+
+    /// to_matrix: &EulerAngles -> [Vector; 3]
+    /// rotation matrix by columns
+    pub fn to_matrix(&self) -> [Vector3<T>; 3] {
+        let r = self.to_rotor();
+        [0, 1, 2].map(|i| r.rotate(axis(i)))
+    }
+
+    /// from_rotor: EulerOrder × Rotor -> EulerAngles
+    /// as `from_matrix`
+    pub fn from_rotor(order: EulerOrder, r: Rotor3<T>) -> Self {
+        EulerAngles::from_matrix(order, [0, 1, 2].map(|i| r.rotate(axis(i))))
+    }
+
+    /// from_matrix: EulerOrder × [Vector; 3] -> EulerAngles
+    /// angles of a rotation matrix given by columns. The middle angle is in
+    /// [-π/2, π/2] for Tait-Bryan orders and [0, π] for proper ones, the
+    /// outer ones in [-π, π]; at gimbal lock the first is zero
+    pub fn from_matrix(order: EulerOrder, columns: [Vector3<T>; 3]) -> Self {
+        let [i, j, k] = order.axes();
+        let (ei, ej, ek) = (axis::<T>(i), axis::<T>(j), axis::<T>(k));
+        let first = columns[i];
+        // the first axis turned by b about the middle one: cos b of it along
+        // the first axis and sin b across for proper orders, ±sin b along
+        // the last axis and cos b across for Tait-Bryan ones
+        let (across, b) = if order.is_proper() {
+            let across = (first - ei * component(first, i)).length();
+            (across, across.atan2(component(first, i)))
+        } else {
+            let across = (first - ek * component(first, k)).length();
+            (across, (ej.cross(ei).dot(ek) * component(first, k)).atan2(across))
+        };
+        let turn = |v: Vector3<T>, angle: T| Rotor3::exp(ej * angle).rotate(v);
+        let locked = across <= T::epsilon() * T::from_f64(64.0);
+        let (a, c) = if locked {
+            // only a combination of the outer turns shows: put it all last
+            (T::zero(), angle_about(ek, ej, columns[j]))
+        } else {
+            // row k of the matrix is the last axis taken back through it
+            let row = Vector3(component(columns[0], k), component(columns[1], k), component(columns[2], k));
+            (angle_about(ei, row, turn(ek, -b)), angle_about(ek, turn(ei, b), first))
+        };
+        EulerAngles { order, angles: [a, b, c] }
+    }
+
+    /// is_gimbal_locked: &EulerAngles × K -> bool
+    /// whether the first and last axes line up to within `tolerance` in
+    /// sine, so that turns about them are near interchangeable
+    pub fn is_gimbal_locked(&self, tolerance: T) -> bool {
+        let b = self.angles[1];
+        if self.order.is_proper() { b.sin().abs() <= tolerance } else { b.cos().abs() <= tolerance }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    const ORDERS: [EulerOrder; 12] = [
+        EulerOrder::Xyz, EulerOrder::Xzy, EulerOrder::Yxz, EulerOrder::Yzx, EulerOrder::Zxy, EulerOrder::Zyx,
+        EulerOrder::Xyx, EulerOrder::Xzx, EulerOrder::Yxy, EulerOrder::Yzy, EulerOrder::Zxz, EulerOrder::Zyz,
+    ];
+
+    fn same(a: [Vector3<f64>; 3], b: [Vector3<f64>; 3]) -> bool {
+        a.iter().zip(b).all(|(x, y)| (*x - y).length() < 1e-9)
+    }
+
+    #[test]
+    fn round_trips() {
+        let e = EulerAngles::new(EulerOrder::Xyz, [PI / 2.0, 0.0, 0.0]);
+        assert!(same(e.to_matrix(), [Vector3(1.0, 0.0, 0.0), Vector3(0.0, 0.0, 1.0), Vector3(0.0, -1.0, 0.0)]));
+        // x first, then z about the fixed axis
+        let e = EulerAngles::new(EulerOrder::Xyz, [PI / 2.0, 0.0, PI / 2.0]);
+        assert!((e.to_rotor().rotate(Vector3(0.0, 1.0, 0.0)) - Vector3(0.0, 0.0, 1.0)).length() < 1e-12);
+        for order in ORDERS {
+            for angles in [[0.3, -0.7, 1.1], [-2.5, 1.2, 3.0], [0.0, 0.1, -0.2], [1.0, 2.5, -1.5]] {
+                let m = EulerAngles::new(order, angles).to_matrix();
+                let back = EulerAngles::from_matrix(order, m);
+                assert!(same(back.to_matrix(), m), "{order:?} {angles:?} -> {:?}", back.angles);
+                assert!(!back.is_gimbal_locked(1e-6));
+                let b = back.angles[1];
+                assert!(if order.is_proper() { (0.0..=PI).contains(&b) } else { b.abs() <= PI / 2.0 });
+                let r = EulerAngles::new(order, angles).to_rotor();
+                assert!(same(EulerAngles::from_rotor(order, r).to_matrix(), m));
+            }
+        }
+    }
+
+    #[test]
+    fn gimbal_lock() {
+        for order in ORDERS {
+            let middle = if order.is_proper() { PI } else { -PI / 2.0 };
+            let m = EulerAngles::new(order, [0.4, middle, -1.3]).to_matrix();
+            let back = EulerAngles::from_matrix(order, m);
+            assert!(back.is_gimbal_locked(1e-9), "{order:?}");
+            assert_eq!(back.angles[0], 0.0);
+            assert!(same(back.to_matrix(), m), "{order:?} -> {:?}", back.angles);
+        }
+        assert!(EulerAngles::new(EulerOrder::Zxz, [1.0, 0.0, 2.0]).is_gimbal_locked(1e-12));
+        assert!(!EulerAngles::new(EulerOrder::Zyx, [1.0, 0.0, 2.0]).is_gimbal_locked(1e-12));
+    }
+}
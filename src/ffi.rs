@@ -0,0 +1,220 @@
+//! C bindings
+//!
+//! `#[repr(C)]` mirrors of the core types and `extern "C"` functions over
+//! them, ready for cbindgen. Rotations cross the boundary as rotation
+//! vectors (axis scaled by angle in radians). Functions writing into
+//! caller buffers return the number of elements the full result needs,
+//! writing no more than the given capacity.
+
+use std::slice;
+
+use crate::{ Circle2, Isometry3, Line2, Point2, Point3, Polygon2, Rotor3, Vector2, Vector3 };
+
+/// 2d point or vector
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GemVec2 {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// 3d point or vector
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GemVec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+/// Rigid motion: rotation vector, then translation
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GemIsometry3 {
+    pub rotation: GemVec3,
+    pub translation: GemVec3,
+}
+
+/// Line through a point along a direction
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GemLine2 {
+    pub point: GemVec2,
+    pub direction: GemVec2,
+}
+
+/// Circle: center, radius
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GemCircle2 {
+    pub center: GemVec2,
+    pub radius: f64,
+}
+
+impl From<GemVec2> for Point2<f64> {
+    fn from(v: GemVec2) -> Self {
+        Point2(v.x, v.y)
+    }
+}
+
+impl From<Point2<f64>> for GemVec2 {
+    fn from(p: Point2<f64>) -> Self {
+        GemVec2 { x: p.0, y: p.1 }
+    }
+}
+
+impl From<GemIsometry3> for Isometry3<f64> {
+    fn from(m: GemIsometry3) -> Self {
+        let (w, t) = (m.rotation, m.translation);
+        Isometry3::new(Rotor3::exp(Vector3(w.x, w.y, w.z)), Vector3(t.x, t.y, t.z))
+    }
+}
+
+impl From<Isometry3<f64>> for GemIsometry3 {
+    fn from(m: Isometry3<f64>) -> Self {
+        let (w, t) = (m.rotation().log(), m.translation());
+        GemIsometry3 {
+            rotation: GemVec3 { x: w.0, y: w.1, z: w.2 },
+            translation: GemVec3 { x: t.0, y: t.1, z: t.2 },
+        }
+    }
+}
+
+impl From<GemLine2> for Line2<f64> {
+    fn from(l: GemLine2) -> Self {
+        Line2::new(l.point.into(), Vector2(l.direction.x, l.direction.y))
+    }
+}
+
+impl From<GemCircle2> for Circle2<f64> {
+    fn from(c: GemCircle2) -> Self {
+        Circle2::new(c.center.into(), c.radius)
+    }
+}
+
+/// Copies what fits of `items` into the buffer and returns the full count
+///
+/// # Safety
+/// `out` must be valid for `capacity` writes, or null with zero capacity
+unsafe fn fill<U: Copy>(items: &[U], out: *mut U, capacity: usize) -> usize {
+    for (i, &item) in items.iter().take(capacity).enumerate() {
+        *out.add(i) = item;
+    }
+    items.len()
+}
+
+/// Applies the rigid motion to a point
+#[no_mangle]
+pub extern "C" fn gem_isometry3_transform_point(m: GemIsometry3, p: GemVec3) -> GemVec3 {
+    let q = Isometry3::from(m).transform_point(Point3(p.x, p.y, p.z));
+    GemVec3 { x: q.0, y: q.1, z: q.2 }
+}
+
+/// Composition applying `b` first, then `a`
+#[no_mangle]
+pub extern "C" fn gem_isometry3_compose(a: GemIsometry3, b: GemIsometry3) -> GemIsometry3 {
+    (Isometry3::from(a) * Isometry3::from(b)).into()
+}
+
+/// Inverse motion
+#[no_mangle]
+pub extern "C" fn gem_isometry3_inverse(m: GemIsometry3) -> GemIsometry3 {
+    Isometry3::from(m).inverse().into()
+}
+
+/// Intersection of two lines; false, leaving `out` untouched, if parallel
+///
+/// # Safety
+/// `out` must be valid for a write
+#[no_mangle]
+pub unsafe extern "C" fn gem_line2_intersect(a: GemLine2, b: GemLine2, out: *mut GemVec2) -> bool {
+    match Line2::from(a).intersect(&b.into()) {
+        Some(p) => {
+            *out = p.into();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Intersections of a circle and a line, at most two
+///
+/// # Safety
+/// `out` must be valid for `capacity` writes
+#[no_mangle]
+pub unsafe extern "C" fn gem_circle2_intersect_line(c: GemCircle2, l: GemLine2, out: *mut GemVec2, capacity: usize) -> usize {
+    let points: Vec<GemVec2> = Circle2::from(c).intersect_line(&l.into()).into_iter().map(Into::into).collect();
+    fill(&points, out, capacity)
+}
+
+/// Intersections of two circles, at most two
+///
+/// # Safety
+/// `out` must be valid for `capacity` writes
+#[no_mangle]
+pub unsafe extern "C" fn gem_circle2_intersect_circle(a: GemCircle2, b: GemCircle2, out: *mut GemVec2, capacity: usize) -> usize {
+    let points: Vec<GemVec2> = Circle2::from(a).intersect_circle(&b.into()).into_iter().map(Into::into).collect();
+    fill(&points, out, capacity)
+}
+
+/// Ear-clipping triangulation of a simple polygon ring of `n` vertices;
+/// writes index triples, 3 (n - 2) indices in all
+///
+/// # Safety
+/// `vertices` must be valid for `n` reads and `out` for `capacity` writes
+#[no_mangle]
+pub unsafe extern "C" fn gem_polygon2_triangulate(vertices: *const GemVec2, n: usize, out: *mut u32, capacity: usize) -> usize {
+    if n == 0 {
+        return 0;
+    }
+    let ring = slice::from_raw_parts(vertices, n).iter().map(|&v| v.into()).collect();
+    let indices: Vec<u32> = Polygon2::new(ring).triangulate().into_iter().flatten().map(|i| i as u32).collect();
+    fill(&indices, out, capacity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transforms() {
+        let quarter = GemIsometry3 {
+            rotation: GemVec3 { x: 0.0, y: 0.0, z: std::f64::consts::FRAC_PI_2 },
+            translation: GemVec3 { x: 1.0, y: 0.0, z: 0.0 },
+        };
+        let p = gem_isometry3_transform_point(quarter, GemVec3 { x: 1.0, y: 0.0, z: 0.0 });
+        assert!((p.x - 1.0).abs() < 1e-12 && (p.y - 1.0).abs() < 1e-12 && p.z.abs() < 1e-12);
+        let back = gem_isometry3_compose(gem_isometry3_inverse(quarter), quarter);
+        let q = gem_isometry3_transform_point(back, GemVec3 { x: 3.0, y: -2.0, z: 5.0 });
+        assert!((q.x - 3.0).abs() < 1e-12 && (q.y + 2.0).abs() < 1e-12 && (q.z - 5.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn intersections() {
+        let origin = GemVec2 { x: 0.0, y: 0.0 };
+        let x = GemLine2 { point: origin, direction: GemVec2 { x: 1.0, y: 0.0 } };
+        let y = GemLine2 { point: GemVec2 { x: 2.0, y: 5.0 }, direction: GemVec2 { x: 0.0, y: 1.0 } };
+        let mut p = origin;
+        assert!(unsafe { gem_line2_intersect(x, y, &mut p) } && p == GemVec2 { x: 2.0, y: 0.0 });
+        assert!(!unsafe { gem_line2_intersect(x, x, &mut p) });
+        let c = GemCircle2 { center: origin, radius: 5.0 };
+        let mut out = [origin; 2];
+        assert_eq!(unsafe { gem_circle2_intersect_line(c, x, out.as_mut_ptr(), 2) }, 2);
+        assert_eq!(out, [GemVec2 { x: -5.0, y: 0.0 }, GemVec2 { x: 5.0, y: 0.0 }]);
+        let d = GemCircle2 { center: GemVec2 { x: 8.0, y: 0.0 }, radius: 5.0 };
+        assert_eq!(unsafe { gem_circle2_intersect_circle(c, d, out.as_mut_ptr(), 1) }, 2);
+        assert_eq!(out[0], GemVec2 { x: 4.0, y: 3.0 });
+    }
+
+    #[test]
+    fn triangulation() {
+        let square = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)].map(|(x, y)| GemVec2 { x, y });
+        let mut out = [0u32; 6];
+        assert_eq!(unsafe { gem_polygon2_triangulate(square.as_ptr(), 4, out.as_mut_ptr(), 6) }, 6);
+        let mut used = out.to_vec();
+        used.sort();
+        used.dedup();
+        assert_eq!(used, vec![0, 1, 2, 3]);
+        assert_eq!(unsafe { gem_polygon2_triangulate(square.as_ptr(), 4, std::ptr::null_mut(), 0) }, 6);
+    }
+}
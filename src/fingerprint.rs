@@ -0,0 +1,106 @@
+//! Canonical hashing and deduplication of shapes
+//!
+//! A fingerprint hashes the canonical form of a shape's binary encoding:
+//! one zero, one NaN and, in quantized mode, every scalar rounded to
+//! multiples of a step, so features read from different files compare
+//! equal when they match to that step. The hash is FNV-1a, stable across
+//! runs and platforms, so fingerprints can be stored and compared later.
+
+use std::collections::HashMap;
+
+use crate::codec::{ canonical_bytes, Codec };
+
+/// How closely shapes must agree to hash alike
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum HashMode {
+    /// the very same values
+    Exact,
+    /// values rounded to multiples of `step`: shapes nearer than that may
+    /// still fall on either side of a rounding boundary; a step that is not
+    /// positive and finite hashes exactly
+    Quantized { step: f64 },
+}
+
+impl HashMode {
+    fn step(self) -> Option<f64> {
+        match self {
+            HashMode::Quantized { step } if step > 0.0 && step.is_finite() => Some(step),
+            _ => None,
+        }
+    }
+}
+
+/// Shapes with a canonical form to hash and compare
+pub trait Fingerprint {
+    /// canonical_bytes: &Shape × HashMode -> [u8]
+    /// equal exactly when the shapes agree in that mode
+    fn canonical_bytes(&self, mode: HashMode) -> Vec<u8>;
+
+    /// fingerprint: &Shape × HashMode -> u64
+    fn fingerprint(&self, mode: HashMode) -> u64 {
+        fnv1a(&self.canonical_bytes(mode))
+    }
+}
+
+impl<C: Codec> Fingerprint for C {
+    fn canonical_bytes(&self, mode: HashMode) -> Vec<u8> {
+        canonical_bytes(self, mode.step())
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |h, &b| (h ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+/// dedupe: [Shape] × HashMode -> [usize]
+/// indices of the first shape of each group agreeing in that mode, in order;
+/// canonical forms are compared in full, so colliding hashes never merge
+pub fn dedupe<S: Fingerprint>(shapes: &[S], mode: HashMode) -> Vec<usize> {
+    let mut seen: HashMap<u64, Vec<Vec<u8>>> = HashMap::new();
+    let mut kept = Vec::new();
+    for (i, shape) in shapes.iter().enumerate() {
+        let bytes = shape.canonical_bytes(mode);
+        let bucket = seen.entry(fnv1a(&bytes)).or_default();
+        if !bucket.contains(&bytes) {
+            bucket.push(bytes);
+            kept.push(i);
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ Circle2, Geometry2, Point2, Point3, Polygon2, TriMesh };
+
+    #[test]
+    fn modes() {
+        let a = Circle2::new(Point2(1.0, -0.0), 2.0);
+        let b = Circle2::new(Point2(1.0, 0.0), 2.0);
+        assert_eq!(a.fingerprint(HashMode::Exact), b.fingerprint(HashMode::Exact));
+        let c = Circle2::new(Point2(1.0 + 1e-9, 0.0), 2.0 - 1e-9);
+        assert_ne!(a.fingerprint(HashMode::Exact), c.fingerprint(HashMode::Exact));
+        let coarse = HashMode::Quantized { step: 1e-6 };
+        assert_eq!(a.fingerprint(coarse), c.fingerprint(coarse));
+        assert_eq!(c.fingerprint(HashMode::Quantized { step: -1.0 }), c.fingerprint(HashMode::Exact));
+        // the kind is part of the hash: a point is not a one-vertex polyline
+        let p = Geometry2::Point(Point2(1.0, 0.0));
+        let l = Geometry2::Polyline(crate::Polyline2::new(vec![Point2(1.0, 0.0)]));
+        assert_ne!(p.fingerprint(HashMode::Exact), l.fingerprint(HashMode::Exact));
+        assert_ne!(Point2(1.0, 0.0).fingerprint(HashMode::Exact), p.fingerprint(HashMode::Exact));
+        // stable across runs and builds
+        assert_eq!(fnv1a(b"gem"), 0xd4e26a18faaa85dc);
+    }
+
+    #[test]
+    fn dedupes() {
+        let square = |x: f64| Polygon2::new(vec![Point2(x, 0.0), Point2(x + 1.0, 0.0), Point2(x + 1.0, 1.0), Point2(x, 1.0)]);
+        let shapes = [square(0.0), square(2.0), square(1e-12), square(0.0), square(2.0 + 1e-12)];
+        assert_eq!(dedupe(&shapes, HashMode::Exact), [0, 1, 2, 4]);
+        assert_eq!(dedupe(&shapes, HashMode::Quantized { step: 1e-6 }), [0, 1]);
+        let mesh = |z: f64| TriMesh::new(vec![Point3(0.0, 0.0, z), Point3(1.0, 0.0, z), Point3(0.0, 1.0, z)], vec![[0, 1, 2]]);
+        assert_eq!(dedupe(&[mesh(0.0), mesh(1.0), mesh(-0.0)], HashMode::Exact), [0, 1]);
+        assert!(dedupe::<TriMesh<f64>>(&[], HashMode::Exact).is_empty());
+    }
+}
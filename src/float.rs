@@ -0,0 +1,182 @@
+//! Floating-point scalars
+
+use std::ops::{ Add, Sub, Neg, Mul, Div };
+
+/// Field of floating-point numbers
+pub trait Float:
+    Copy + PartialOrd
+    + Add<Output=Self> + Sub<Output=Self> + Neg<Output=Self>
+    + Mul<Output=Self> + Div<Output=Self>
+{
+    /// 0: K
+    fn zero() -> Self;
+
+    /// 1: K
+    fn one() -> Self;
+
+    /// ∞: K
+    fn infinity() -> Self;
+
+    /// π: K
+    fn pi() -> Self;
+
+    /// from_f64: f64 -> K
+    /// nearest representable value
+    fn from_f64(x: f64) -> Self;
+
+    /// to_f64: K -> f64
+    /// nearest double
+    fn to_f64(self) -> f64;
+
+    /// ε: K
+    /// difference between 1 and the next representable value
+    fn epsilon() -> Self;
+
+    /// abs: K -> K
+    fn abs(self) -> Self;
+
+    /// floor: K -> K
+    fn floor(self) -> Self;
+
+    /// sqrt: K -> K
+    fn sqrt(self) -> Self;
+
+    /// sin: K -> K
+    fn sin(self) -> Self;
+
+    /// cos: K -> K
+    fn cos(self) -> Self;
+
+    /// atan2: K × K -> K
+    /// angle of the point (x, y) with y = self
+    fn atan2(self, x: Self) -> Self;
+
+    /// next_up: K -> K
+    /// least value greater than self
+    fn next_up(self) -> Self;
+
+    /// next_down: K -> K
+    /// greatest value less than self
+    fn next_down(self) -> Self;
+
+    /// is_finite: K -> bool
+    fn is_finite(self) -> bool;
+}
+
+macro_rules! impl_float {
+    ($($t:ident),+) => {$(
+        impl Float for $t {
+            fn zero() -> Self {
+                0.0
+            }
+
+            fn one() -> Self {
+                1.0
+            }
+
+            fn infinity() -> Self {
+                <$t>::INFINITY
+            }
+
+            fn pi() -> Self {
+                std::$t::consts::PI
+            }
+
+            fn from_f64(x: f64) -> Self {
+                x as $t
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn epsilon() -> Self {
+                <$t>::EPSILON
+            }
+
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+
+            fn floor(self) -> Self {
+                <$t>::floor(self)
+            }
+
+            #[cfg(not(feature = "deterministic"))]
+            fn sqrt(self) -> Self {
+                <$t>::sqrt(self)
+            }
+
+            #[cfg(feature = "deterministic")]
+            fn sqrt(self) -> Self {
+                libm::Libm::<$t>::sqrt(self)
+            }
+
+            #[cfg(not(feature = "deterministic"))]
+            fn sin(self) -> Self {
+                <$t>::sin(self)
+            }
+
+            #[cfg(feature = "deterministic")]
+            fn sin(self) -> Self {
+                libm::Libm::<$t>::sin(self)
+            }
+
+            #[cfg(not(feature = "deterministic"))]
+            fn cos(self) -> Self {
+                <$t>::cos(self)
+            }
+
+            #[cfg(feature = "deterministic")]
+            fn cos(self) -> Self {
+                libm::Libm::<$t>::cos(self)
+            }
+
+            #[cfg(not(feature = "deterministic"))]
+            fn atan2(self, x: Self) -> Self {
+                <$t>::atan2(self, x)
+            }
+
+            #[cfg(feature = "deterministic")]
+            fn atan2(self, x: Self) -> Self {
+                libm::Libm::<$t>::atan2(self, x)
+            }
+
+            fn next_up(self) -> Self {
+                <$t>::next_up(self)
+            }
+
+            fn next_down(self) -> Self {
+                <$t>::next_down(self)
+            }
+
+            fn is_finite(self) -> bool {
+                <$t>::is_finite(self)
+            }
+        }
+    )+};
+}
+
+impl_float!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcendentals() {
+        assert_eq!(Float::sqrt(2.0f64), std::f64::consts::SQRT_2);
+        assert!((Float::sin(0.5f64) - 0.479425538604203).abs() < 1e-15);
+        assert!((Float::cos(0.5f32) - 0.87758255).abs() < 1e-7);
+        assert_eq!(Float::atan2(1.0f64, -1.0), 3.0 * std::f64::consts::FRAC_PI_4);
+    }
+
+    #[cfg(feature = "deterministic")]
+    #[test]
+    fn deterministic_bits() {
+        // reference bits of the software implementations, the same on every platform
+        assert_eq!(Float::sin(1.0f64).to_bits(), 0x3FEA_ED54_8F09_0CEE);
+        assert_eq!(Float::cos(1e6f64).to_bits(), 0x3FED_F9DF_9906_D32C);
+        assert_eq!(Float::atan2(3.0f64, 7.0).to_bits(), 0x3FD9_E9BF_3D20_DC70);
+    }
+}
@@ -0,0 +1,525 @@
+//! Geometric algebra: bivectors and rotors
+//!
+//! Bivectors are oriented plane elements produced by the wedge product,
+//! rotors are normalized even multivectors acting on vectors by the
+//! sandwich product R v R̃.
+
+use std::ops::{ Add, Sub, Neg, Mul };
+
+use crate::{ Float, Vector2, Vector3 };
+use crate::conic::symmetric_eigen;
+
+/// 2d Bivector: xy
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Bivector2<T>(T);
+
+/// 3d Bivector: xy, xz, yz
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Bivector3<T>(T, T, T);
+
+/// 2d Rotor: scalar, xy
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Rotor2<T>(T, T);
+
+/// 3d Rotor: scalar, xy, xz, yz
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Rotor3<T>(T, T, T, T);
+
+impl<T> Bivector2<T> {
+    /// new: K -> Bivector
+    pub fn new(xy: T) -> Self {
+        Bivector2(xy)
+    }
+}
+
+impl<T> Bivector3<T> {
+    /// new: K^3 -> Bivector
+    pub fn new(xy: T, xz: T, yz: T) -> Self {
+        Bivector3(xy, xz, yz)
+    }
+}
+
+impl<T: Sub<Output=T> + Mul<Output=T> + Copy> Vector2<T> {
+    /// wedge: Vector × Vector -> Bivector
+    pub fn wedge(self, v: Vector2<T>) -> Bivector2<T> {
+        Bivector2(self.0 * v.1 - self.1 * v.0)
+    }
+}
+
+impl<T: Sub<Output=T> + Mul<Output=T> + Copy> Vector3<T> {
+    /// wedge: Vector × Vector -> Bivector
+    pub fn wedge(self, v: Vector3<T>) -> Bivector3<T> {
+        Bivector3(
+            self.0 * v.1 - self.1 * v.0,
+            self.0 * v.2 - self.2 * v.0,
+            self.1 * v.2 - self.2 * v.1,
+        )
+    }
+}
+
+/// (-): Bivector -> Bivector
+impl<T: Neg<Output=T>> Neg for Bivector2<T> {
+    type Output = Bivector2<T>;
+
+    fn neg(self) -> Self::Output {
+        Bivector2(-self.0)
+    }
+}
+
+/// (+): Bivector × Bivector -> Bivector
+impl<T: Add<Output=T>> Add for Bivector2<T> {
+    type Output = Bivector2<T>;
+
+    fn add(self, b: Bivector2<T>) -> Self::Output {
+        Bivector2(self.0 + b.0)
+    }
+}
+
+/// (-): Bivector × Bivector -> Bivector
+impl<T: Sub<Output=T>> Sub for Bivector2<T> {
+    type Output = Bivector2<T>;
+
+    fn sub(self, b: Bivector2<T>) -> Self::Output {
+        Bivector2(self.0 - b.0)
+    }
+}
+
+/// (*): Bivector × K -> Bivector
+impl<T: Mul<Output=T>> Mul<T> for Bivector2<T> {
+    type Output = Bivector2<T>;
+
+    fn mul(self, k: T) -> Self::Output {
+        Bivector2(self.0 * k)
+    }
+}
+
+/// (-): Bivector -> Bivector
+impl<T: Neg<Output=T>> Neg for Bivector3<T> {
+    type Output = Bivector3<T>;
+
+    fn neg(self) -> Self::Output {
+        Bivector3(-self.0, -self.1, -self.2)
+    }
+}
+
+/// (+): Bivector × Bivector -> Bivector
+impl<T: Add<Output=T>> Add for Bivector3<T> {
+    type Output = Bivector3<T>;
+
+    fn add(self, b: Bivector3<T>) -> Self::Output {
+        Bivector3(self.0 + b.0, self.1 + b.1, self.2 + b.2)
+    }
+}
+
+/// (-): Bivector × Bivector -> Bivector
+impl<T: Sub<Output=T>> Sub for Bivector3<T> {
+    type Output = Bivector3<T>;
+
+    fn sub(self, b: Bivector3<T>) -> Self::Output {
+        Bivector3(self.0 - b.0, self.1 - b.1, self.2 - b.2)
+    }
+}
+
+/// (*): Bivector × K -> Bivector
+impl<T: Mul<Output=T> + Copy> Mul<T> for Bivector3<T> {
+    type Output = Bivector3<T>;
+
+    fn mul(self, k: T) -> Self::Output {
+        Bivector3(self.0 * k, self.1 * k, self.2 * k)
+    }
+}
+
+impl<T: Float> Rotor2<T> {
+    /// identity: Rotor
+    pub fn identity() -> Self {
+        Rotor2(T::one(), T::zero())
+    }
+
+    /// from_to: Vector × Vector -> Rotor
+    /// rotates the direction of `from` onto the direction of `to`
+    pub fn from_to(from: Vector2<T>, to: Vector2<T>) -> Self {
+        let (from, to) = (from / from.length(), to / to.length());
+        let (dot, b) = (to.dot(from), to.wedge(from).0);
+        if dot < T::zero() && b == T::zero() {
+            // antiparallel: half turn
+            Rotor2(T::zero(), T::one())
+        } else {
+            Rotor2(T::one() + dot, b).normalize()
+        }
+    }
+
+    /// reverse: Rotor -> Rotor
+    pub fn reverse(self) -> Self {
+        Rotor2(self.0, -self.1)
+    }
+
+    /// rotate: Rotor × Vector -> Vector
+    /// R v R̃
+    pub fn rotate(self, v: Vector2<T>) -> Vector2<T> {
+        let Rotor2(a, b) = self;
+        let q = Vector2(a * v.0 + v.1 * b, a * v.1 - v.0 * b);
+        Vector2(a * q.0 + q.1 * b, a * q.1 - q.0 * b)
+    }
+
+    /// exp: K -> Rotor
+    /// counter-clockwise rotation by the angle
+    pub fn exp(angle: T) -> Self {
+        let half = angle * T::from_f64(0.5);
+        Rotor2(half.cos(), -half.sin())
+    }
+
+    /// log: Rotor -> K
+    /// rotation angle in [-π, π]
+    pub fn log(self) -> T {
+        let r = if self.0 < T::zero() { Rotor2(-self.0, -self.1) } else { self };
+        (-r.1).atan2(r.0) * T::from_f64(2.0)
+    }
+
+    /// interpolate: Rotor × Rotor × K -> Rotor
+    /// constant angular velocity along the shorter arc
+    pub fn interpolate(self, r: Rotor2<T>, t: T) -> Self {
+        Rotor2::exp((r * self.reverse()).log() * t) * self
+    }
+
+    fn norm(self) -> T {
+        (self.0 * self.0 + self.1 * self.1).sqrt()
+    }
+
+    fn normalize(self) -> Self {
+        let n = self.norm();
+        Rotor2(self.0 / n, self.1 / n)
+    }
+}
+
+/// (*): Rotor × Rotor -> Rotor
+/// applies the right rotor first
+impl<T: Float> Mul for Rotor2<T> {
+    type Output = Rotor2<T>;
+
+    fn mul(self, r: Rotor2<T>) -> Self::Output {
+        Rotor2(
+            self.0 * r.0 - self.1 * r.1,
+            self.1 * r.0 + self.0 * r.1,
+        )
+    }
+}
+
+impl<T: Float> Rotor3<T> {
+    /// identity: Rotor
+    pub fn identity() -> Self {
+        Rotor3(T::one(), T::zero(), T::zero(), T::zero())
+    }
+
+    /// from_to: Vector × Vector -> Rotor
+    /// shortest-arc rotation of the direction of `from` onto the direction of `to`
+    pub fn from_to(from: Vector3<T>, to: Vector3<T>) -> Self {
+        let (from, to) = (from / from.length(), to / to.length());
+        let (dot, b) = (to.dot(from), to.wedge(from));
+        let zero = T::zero();
+        if dot < zero && b == Bivector3(zero, zero, zero) {
+            // antiparallel: half turn in any plane containing `from`
+            let Vector3(x, y, z) = from;
+            let one = T::one();
+            let axis = if x.abs() <= y.abs() && x.abs() <= z.abs() {
+                Vector3(one, zero, zero)
+            } else if y.abs() <= z.abs() {
+                Vector3(zero, one, zero)
+            } else {
+                Vector3(zero, zero, one)
+            };
+            let Bivector3(xy, xz, yz) = from.wedge(axis);
+            let q = Rotor3::from_to(from, Vector3(yz, -xz, xy));
+            q * q
+        } else {
+            Rotor3(T::one() + dot, b.0, b.1, b.2).normalize()
+        }
+    }
+
+    /// look_rotation: Vector × Vector -> Rotor
+    /// turns +z onto `forward` and +y onto `up` made perpendicular to it;
+    /// an `up` parallel to `forward` is replaced by the least aligned axis
+    pub fn look_rotation(forward: Vector3<T>, up: Vector3<T>) -> Self {
+        let f = forward / forward.length();
+        let mut r = up.cross(f);
+        if r.length() <= T::epsilon() * up.length() {
+            let (zero, one) = (T::zero(), T::one());
+            let Vector3(x, y, z) = f;
+            let axis = if x.abs() <= y.abs() && x.abs() <= z.abs() {
+                Vector3(one, zero, zero)
+            } else if y.abs() <= z.abs() {
+                Vector3(zero, one, zero)
+            } else {
+                Vector3(zero, zero, one)
+            };
+            r = axis.cross(f);
+        }
+        let r = r / r.length();
+        let u = f.cross(r);
+        Rotor3::from_basis(r, u, f)
+    }
+
+    /// Rotor of the rotation matrix with columns x, y, z (Shepperd's method)
+    pub(crate) fn from_basis(x: Vector3<T>, y: Vector3<T>, z: Vector3<T>) -> Self {
+        let one = T::one();
+        let quarter = T::from_f64(0.25);
+        let trace = x.0 + y.1 + z.2;
+        // quaternion (w, i, j, k), picking the largest component to divide by
+        let (w, i, j, k) = if trace > T::zero() {
+            let s = (trace + one).sqrt() * T::from_f64(2.0);
+            (s * quarter, (y.2 - z.1) / s, (z.0 - x.2) / s, (x.1 - y.0) / s)
+        } else if x.0 > y.1 && x.0 > z.2 {
+            let s = (one + x.0 - y.1 - z.2).sqrt() * T::from_f64(2.0);
+            ((y.2 - z.1) / s, s * quarter, (y.0 + x.1) / s, (z.0 + x.2) / s)
+        } else if y.1 > z.2 {
+            let s = (one + y.1 - x.0 - z.2).sqrt() * T::from_f64(2.0);
+            ((z.0 - x.2) / s, (y.0 + x.1) / s, s * quarter, (z.1 + y.2) / s)
+        } else {
+            let s = (one + z.2 - x.0 - y.1).sqrt() * T::from_f64(2.0);
+            ((x.1 - y.0) / s, (z.0 + x.2) / s, (z.1 + y.2) / s, s * quarter)
+        };
+        Rotor3(w, -k, j, -i).normalize()
+    }
+
+    /// reverse: Rotor -> Rotor
+    pub fn reverse(self) -> Self {
+        Rotor3(self.0, -self.1, -self.2, -self.3)
+    }
+
+    /// rotate: Rotor × Vector -> Vector
+    /// R v R̃
+    pub fn rotate(self, v: Vector3<T>) -> Vector3<T> {
+        let Rotor3(a, xy, xz, yz) = self;
+        let q = Vector3(
+            a * v.0 + v.1 * xy + v.2 * xz,
+            a * v.1 - v.0 * xy + v.2 * yz,
+            a * v.2 - v.0 * xz - v.1 * yz,
+        );
+        let xyz = v.0 * yz - v.1 * xz + v.2 * xy;
+        Vector3(
+            a * q.0 + q.1 * xy + q.2 * xz + xyz * yz,
+            a * q.1 - q.0 * xy - xyz * xz + q.2 * yz,
+            a * q.2 + xyz * xy - q.0 * xz - q.1 * yz,
+        )
+    }
+
+    /// exp: Vector -> Rotor
+    /// rotation by |ω| about ω
+    pub fn exp(w: Vector3<T>) -> Self {
+        let theta = w.length();
+        let half = theta / (T::one() + T::one());
+        let s = if theta < T::epsilon().sqrt() {
+            T::one() / (T::one() + T::one())
+        } else {
+            half.sin() / theta
+        };
+        Rotor3(half.cos(), -s * w.2, s * w.1, -s * w.0)
+    }
+
+    /// log: Rotor -> Vector
+    /// rotation vector ω with |ω| ≤ π
+    pub fn log(self) -> Vector3<T> {
+        let r = if self.0 < T::zero() { Rotor3(-self.0, -self.1, -self.2, -self.3) } else { self };
+        let v = Vector3(-r.3, r.2, -r.1);
+        let sin = v.length();
+        if sin < T::epsilon() {
+            v * ((T::one() + T::one()) / r.0)
+        } else {
+            v * ((T::one() + T::one()) * sin.atan2(r.0) / sin)
+        }
+    }
+
+    /// interpolate: Rotor × Rotor × K -> Rotor
+    /// constant angular velocity along the shortest arc (slerp)
+    pub fn interpolate(self, r: Rotor3<T>, t: T) -> Self {
+        Rotor3::exp((r * self.reverse()).log() * t) * self
+    }
+
+    fn norm(self) -> T {
+        (self.0 * self.0 + self.1 * self.1 + self.2 * self.2 + self.3 * self.3).sqrt()
+    }
+
+    fn normalize(self) -> Self {
+        let n = self.norm();
+        Rotor3(self.0 / n, self.1 / n, self.2 / n, self.3 / n)
+    }
+}
+
+/// (*): Rotor × Rotor -> Rotor
+/// applies the right rotor first
+impl<T: Float> Mul for Rotor3<T> {
+    type Output = Rotor3<T>;
+
+    fn mul(self, r: Rotor3<T>) -> Self::Output {
+        let Rotor3(a, xy, xz, yz) = self;
+        Rotor3(
+            a * r.0 - xy * r.1 - xz * r.2 - yz * r.3,
+            xy * r.0 + a * r.1 + yz * r.2 - xz * r.3,
+            xz * r.0 + a * r.2 - yz * r.1 + xy * r.3,
+            yz * r.0 + a * r.3 + xz * r.1 - xy * r.2,
+        )
+    }
+}
+
+/// average_rotations: [(Rotor, K)] -> Option<Rotor>
+/// weighted chordal mean: the rotor q maximizing Σ wᵢ (q · rᵢ)², the
+/// dominant eigenvector of Σ wᵢ rᵢ rᵢᵀ (Markley). A rotor and its negation
+/// are the same rotation and count alike, so inputs need no hemisphere
+/// alignment; the result lies in the hemisphere of the heaviest input.
+/// None without positive total weight; weights should not be negative
+pub fn average_rotations<T: Float>(rotations: &[(Rotor3<T>, T)]) -> Option<Rotor3<T>> {
+    let total = rotations.iter().fold(T::zero(), |s, &(_, w)| s + w);
+    if total <= T::zero() || !total.is_finite() {
+        return None;
+    }
+    let mut m = [[T::zero(); 4]; 4];
+    for &(r, w) in rotations {
+        let r = r.normalize();
+        let q = [r.0, r.1, r.2, r.3];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, x) in row.iter_mut().enumerate() {
+                *x = *x + q[i] * q[j] * (w / total);
+            }
+        }
+    }
+    let (values, vectors) = symmetric_eigen(m);
+    let k = (1..4).fold(0, |k, i| if values[i] > values[k] { i } else { k });
+    let mean = Rotor3(vectors[0][k], vectors[1][k], vectors[2][k], vectors[3][k]).normalize();
+    let heaviest = rotations.iter().fold(rotations[0], |a, &b| if b.1 > a.1 { b } else { a }).0;
+    let dot = mean.0 * heaviest.0 + mean.1 * heaviest.1 + mean.2 * heaviest.2 + mean.3 * heaviest.3;
+    Some(if dot < T::zero() { Rotor3(-mean.0, -mean.1, -mean.2, -mean.3) } else { mean })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn near2(v: Vector2<f64>, w: Vector2<f64>) -> bool {
+        (v - w).length() < 1e-12
+    }
+
+    fn near3(v: Vector3<f64>, w: Vector3<f64>) -> bool {
+        (v - w).length() < 1e-12
+    }
+
+    #[test]
+    fn wedge() {
+        assert_eq!(Vector2(1, 0).wedge(Vector2(0, 1)), Bivector2(1));
+        assert_eq!(Vector2(2, 3).wedge(Vector2(2, 3)), Bivector2(0));
+        assert_eq!(Vector3(1, 0, 0).wedge(Vector3(0, 1, 0)), Bivector3(1, 0, 0));
+        assert_eq!(Vector3(0, 1, 0).wedge(Vector3(0, 0, 1)), Bivector3(0, 0, 1));
+        assert_eq!(Vector3(1, 2, 3).wedge(Vector3(4, 5, 6)), -Vector3(4, 5, 6).wedge(Vector3(1, 2, 3)));
+    }
+
+    #[test]
+    fn bivector_arith() {
+        assert_eq!(Bivector2(1) + Bivector2(2) - Bivector2(4), Bivector2(-1));
+        assert_eq!(Bivector3(1, 2, 3) * 2 + Bivector3(1, 1, 1), Bivector3(3, 5, 7));
+    }
+
+    #[test]
+    fn rotor2_from_to() {
+        let r = Rotor2::from_to(Vector2(2.0, 0.0), Vector2(0.0, 3.0));
+        assert!(near2(r.rotate(Vector2(1.0, 0.0)), Vector2(0.0, 1.0)));
+        assert!(near2(r.rotate(Vector2(0.0, 1.0)), Vector2(-1.0, 0.0)));
+        assert!(near2(r.reverse().rotate(r.rotate(Vector2(3.0, 4.0))), Vector2(3.0, 4.0)));
+        assert!(near2((r * r).rotate(Vector2(1.0, 0.0)), Vector2(-1.0, 0.0)));
+        let h = Rotor2::from_to(Vector2(1.0, 1.0), Vector2(-1.0, -1.0));
+        assert!(near2(h.rotate(Vector2(1.0, 2.0)), Vector2(-1.0, -2.0)));
+    }
+
+    #[test]
+    fn rotor3_from_to() {
+        let r = Rotor3::from_to(Vector3(1.0, 0.0, 0.0), Vector3(0.0, 1.0, 0.0));
+        assert!(near3(r.rotate(Vector3(1.0, 0.0, 0.0)), Vector3(0.0, 1.0, 0.0)));
+        assert!(near3(r.rotate(Vector3(0.0, 0.0, 1.0)), Vector3(0.0, 0.0, 1.0)));
+        let (a, b) = (Vector3(1.0, 2.0, 3.0), Vector3(-2.0, 0.5, 1.0));
+        let r = Rotor3::from_to(a, b);
+        assert!(near3(r.rotate(a) / a.length(), b / b.length()));
+        assert!(near3(Rotor3::identity().rotate(a), a));
+        assert!(near3(r.reverse().rotate(r.rotate(b)), b));
+    }
+
+    #[test]
+    fn rotor3_compose() {
+        let (x, y, z) = (Vector3(1.0, 0.0, 0.0), Vector3(0.0, 1.0, 0.0), Vector3(0.0, 0.0, 1.0));
+        let r = Rotor3::from_to(x, y);
+        let s = Rotor3::from_to(y, z);
+        assert!(near3((s * r).rotate(x), z));
+        assert!(near3((s * r).rotate(x), s.rotate(r.rotate(x))));
+    }
+
+    #[test]
+    fn rotor3_antiparallel() {
+        let a = Vector3(0.0, 2.0, 1.0);
+        let r = Rotor3::from_to(a, -a);
+        assert!(near3(r.rotate(a), -a));
+        assert!((r.norm() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rotor2_exp_log() {
+        let r = Rotor2::exp(std::f64::consts::FRAC_PI_2);
+        assert!(near2(r.rotate(Vector2(1.0, 0.0)), Vector2(0.0, 1.0)));
+        assert!((r.log() - std::f64::consts::FRAC_PI_2).abs() < 1e-15);
+        assert!((Rotor2::exp(-3.0).log() + 3.0).abs() < 1e-15);
+        // 350° is the same rotation as -10°
+        assert!((Rotor2::exp(350f64.to_radians()).log() + 10f64.to_radians()).abs() < 1e-12);
+        let (a, b) = (Rotor2::exp(170f64.to_radians()), Rotor2::exp(-170f64.to_radians()));
+        let mid = a.interpolate(b, 0.5);
+        assert!(near2(mid.rotate(Vector2(1.0, 0.0)), Vector2(-1.0, 0.0)));
+        assert!(near2(a.interpolate(b, 1.0).rotate(Vector2(1.0, 0.0)), b.rotate(Vector2(1.0, 0.0))));
+    }
+
+    #[test]
+    fn rotor3_interpolate() {
+        let (a, b) = (Rotor3::exp(Vector3(0.0, 0.0, 0.3)), Rotor3::exp(Vector3(0.0, 0.0, 1.5)));
+        let r = a.interpolate(b, 0.25);
+        assert!(near3(r.log(), Vector3(0.0, 0.0, 0.6)));
+        let c = Rotor3::exp(Vector3(1.0, -2.0, 0.5));
+        assert!(near3(a.interpolate(c, 1.0).rotate(Vector3(1.0, 2.0, 3.0)), c.rotate(Vector3(1.0, 2.0, 3.0))));
+        assert!(near3(a.interpolate(c, 0.0).log(), a.log()));
+    }
+
+    #[test]
+    fn rotor3_look_rotation() {
+        let (x, y, z) = (Vector3(1.0, 0.0, 0.0), Vector3(0.0, 1.0, 0.0), Vector3(0.0, 0.0, 1.0));
+        assert!(near3(Rotor3::look_rotation(z, y).rotate(x), x));
+        let cases = [
+            (Vector3(1.0, 2.0, 3.0), Vector3(0.0, 1.0, 0.0)),
+            (Vector3(0.0, 0.0, -1.0), Vector3(0.0, 1.0, 0.0)),
+            (Vector3(-1.0, 0.0, 0.0), Vector3(0.0, 0.0, 1.0)),
+            (Vector3(0.0, -3.0, 0.0), Vector3(1.0, 0.0, 0.0)),
+        ];
+        for (forward, up) in cases {
+            let r = Rotor3::look_rotation(forward, up);
+            let f = forward / forward.length();
+            assert!(near3(r.rotate(z), f));
+            let u = r.rotate(y);
+            assert!(u.dot(f).abs() < 1e-12 && u.dot(up) > 0.0);
+            assert!((r.norm() - 1.0).abs() < 1e-12);
+        }
+        // up along forward: any up perpendicular to it
+        let r = Rotor3::look_rotation(Vector3(0.0, 2.0, 0.0), y);
+        assert!(near3(r.rotate(z), y) && r.rotate(y).dot(y).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rotor3_average() {
+        let a = Rotor3::exp(Vector3(0.0, 0.0, 0.4));
+        let b = Rotor3::exp(Vector3(0.0, 0.0, 1.0));
+        let mid = average_rotations(&[(a, 1.0), (b, 1.0)]).unwrap();
+        assert!(near3(mid.log(), a.interpolate(b, 0.5).log()));
+        // a rotor and its negation are one rotation
+        let flipped = Rotor3(-b.0, -b.1, -b.2, -b.3);
+        let same = average_rotations(&[(a, 1.0), (flipped, 1.0)]).unwrap();
+        assert!(near3(same.log(), mid.log()) && same.0 > 0.0);
+        // perturbations in opposite pairs about a rotation average to it
+        let base = Rotor3::exp(Vector3(0.3, -2.0, 0.1));
+        let spread = [Vector3(0.5, 0.1, 0.0), Vector3(-0.2, 0.4, -0.3)];
+        let rs: Vec<(Rotor3<f64>, f64)> = spread.iter().flat_map(|&v| [(base * Rotor3::exp(v), 0.5), (base * Rotor3::exp(-v), 0.5)]).collect();
+        assert!(near3(average_rotations(&rs).unwrap().log(), base.log()));
+        assert!(near3(average_rotations(&[(a, 2.0), (b, 0.0)]).unwrap().log(), a.log()));
+        assert_eq!(average_rotations::<f64>(&[]), None);
+        assert_eq!(average_rotations(&[(a, 0.0)]), None);
+    }
+}
@@ -0,0 +1,177 @@
+//! Ray traversal of uniform grids (Amanatides–Woo)
+
+use crate::{ Float, Point2, Point3, Ray3, Vector2 };
+
+/// Cells of a square grid crossed by a ray, nearest first
+#[derive(Debug, Clone)]
+pub struct GridWalk2<T>(Walk<T, 2>);
+
+/// Cells of a cubic grid crossed by a ray, nearest first
+#[derive(Debug, Clone)]
+pub struct GridWalk3<T>(Walk<T, 3>);
+
+/// A grid cell and the stretch of the ray inside it
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GridCell2<T> {
+    /// integer coordinates: the cell covers [x, x + 1) · size × [y, y + 1) · size
+    pub cell: Point2<i64>,
+    /// ray parameter where the ray enters
+    pub enter: T,
+    /// ray parameter where the ray leaves, ∞ for the last cell of a ray
+    /// that stays put
+    pub exit: T,
+}
+
+/// A grid cell and the stretch of the ray inside it
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GridCell3<T> {
+    /// integer coordinates: the cell covers [x, x + 1) · size and so on
+    pub cell: Point3<i64>,
+    /// ray parameter where the ray enters
+    pub enter: T,
+    /// ray parameter where the ray leaves, ∞ for the last cell of a ray
+    /// that stays put
+    pub exit: T,
+}
+
+/// Walk state shared by both dimensions
+#[derive(Debug, Clone)]
+struct Walk<T, const N: usize> {
+    origin: [T; N],
+    direction: [T; N],
+    size: T,
+    cell: [i64; N],
+    t: T,
+    done: bool,
+}
+
+impl<T: Float> GridWalk2<T> {
+    /// new: Point × Vector × K -> GridWalk
+    /// cells of side `size` with a corner at the origin, from the cell
+    /// holding `origin` along origin + direction · t, t ≥ 0; endless unless
+    /// the direction is zero, so bound it with `take_while` on `enter`
+    pub fn new(origin: Point2<T>, direction: Vector2<T>, size: T) -> Self {
+        GridWalk2(Walk::new([origin.0, origin.1], [direction.0, direction.1], size))
+    }
+}
+
+impl<T: Float> GridWalk3<T> {
+    /// new: &Ray × K -> GridWalk
+    /// cells of side `size` with a corner at the origin, from the cell
+    /// holding the ray's origin; endless unless the direction is zero, so
+    /// bound it with `take_while` on `enter`
+    pub fn new(ray: &Ray3<T>, size: T) -> Self {
+        let (o, d) = (ray.origin(), ray.direction());
+        GridWalk3(Walk::new([o.0, o.1, o.2], [d.0, d.1, d.2], size))
+    }
+}
+
+impl<T: Float> Iterator for GridWalk2<T> {
+    type Item = GridCell2<T>;
+
+    fn next(&mut self) -> Option<GridCell2<T>> {
+        self.0.step().map(|([x, y], enter, exit)| GridCell2 { cell: Point2(x, y), enter, exit })
+    }
+}
+
+impl<T: Float> Iterator for GridWalk3<T> {
+    type Item = GridCell3<T>;
+
+    fn next(&mut self) -> Option<GridCell3<T>> {
+        self.0.step().map(|([x, y, z], enter, exit)| GridCell3 { cell: Point3(x, y, z), enter, exit })
+    }
+}
+
+impl<T: Float, const N: usize> Walk<T, N> {
+    fn new(origin: [T; N], direction: [T; N], size: T) -> Self {
+        let cell = origin.map(|o| (o / size).floor().to_f64() as i64);
+        Walk { origin, direction, size, cell, t: T::zero(), done: false }
+    }
+
+    /// Ray parameter of the next boundary along axis i, from the cell index
+    /// rather than running sums so that no error builds up
+    fn boundary(&self, i: usize) -> T {
+        let d = self.direction[i];
+        let side = if d > T::zero() {
+            self.cell[i] + 1
+        } else if d < T::zero() {
+            self.cell[i]
+        } else {
+            return T::infinity();
+        };
+        (T::from_f64(side as f64) * self.size - self.origin[i]) / d
+    }
+
+    /// The current cell with its entry and exit, then on to the next;
+    /// axes crossed at the same parameter are stepped together, so a ray
+    /// through a corner goes diagonally
+    fn step(&mut self) -> Option<([i64; N], T, T)> {
+        if self.done {
+            return None;
+        }
+        let ts: [T; N] = std::array::from_fn(|i| self.boundary(i));
+        let exit = ts.iter().fold(T::infinity(), |m, &t| if t < m { t } else { m });
+        // rounding may put a boundary a hair behind the start
+        let exit = if exit < self.t { self.t } else { exit };
+        let out = (self.cell, self.t, exit);
+        if !exit.is_finite() {
+            self.done = true;
+            return Some(out);
+        }
+        for (i, &t) in ts.iter().enumerate() {
+            if t <= exit {
+                self.cell[i] += if self.direction[i] > T::zero() { 1 } else { -1 };
+            }
+        }
+        self.t = exit;
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Vector3;
+
+    fn cells(walk: GridWalk2<f64>, n: usize) -> Vec<(i64, i64, f64, f64)> {
+        walk.take(n).map(|c| (c.cell.0, c.cell.1, c.enter, c.exit)).collect()
+    }
+
+    #[test]
+    fn squares() {
+        let walk = GridWalk2::new(Point2(0.5, 0.5), Vector2(1.0, 0.5), 1.0);
+        assert_eq!(cells(walk, 4), vec![(0, 0, 0.0, 0.5), (1, 0, 0.5, 1.0), (1, 1, 1.0, 1.5), (2, 1, 1.5, 2.5)]);
+        // through corners, diagonally
+        let walk = GridWalk2::new(Point2(1.0, 1.0), Vector2(-2.0, -2.0), 2.0);
+        assert_eq!(cells(walk, 3), vec![(0, 0, 0.0, 0.5), (-1, -1, 0.5, 1.5), (-2, -2, 1.5, 2.5)]);
+        let walk = GridWalk2::new(Point2(-0.75, 3.25), Vector2(-1.0, 0.0), 0.5);
+        assert_eq!(cells(walk, 2), vec![(-2, 6, 0.0, 0.25), (-3, 6, 0.25, 0.75)]);
+        let still = GridWalk2::new(Point2(2.5, -0.5), Vector2(0.0, 0.0), 1.0);
+        assert_eq!(cells(still, 3), vec![(2, -1, 0.0, f64::INFINITY)]);
+    }
+
+    #[test]
+    fn cubes_hold_their_stretch() {
+        let mut seed = 7u64;
+        let mut random = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64 * 10.0 - 5.0
+        };
+        for _ in 0..50 {
+            let ray = Ray3::new(Point3(random(), random(), random()), Vector3(random(), random(), random()));
+            let size = 0.25 + random().abs() / 5.0;
+            let mut last = 0.0;
+            for c in GridWalk3::new(&ray, size).take_while(|c| c.enter < 10.0) {
+                assert_eq!(c.enter, last);
+                assert!(c.exit >= c.enter);
+                last = c.exit;
+                let p = ray.point_at((c.enter + c.exit) / 2.0);
+                let inside = |x: f64, k: i64| {
+                    let slack = 1e-9;
+                    x >= k as f64 * size - slack && x <= (k + 1) as f64 * size + slack
+                };
+                assert!(inside(p.0, c.cell.0) && inside(p.1, c.cell.1) && inside(p.2, c.cell.2));
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! Half-precision scalars
+//!
+//! `f16` and `bf16` implement `Float` by rounding each operation through
+//! `f32`, so every generic algorithm accepts them. For compact vertex data
+//! the usual pattern is to store half vectors and widen before heavy math:
+//! `to_f32` / `to_f16` / `to_bf16` convert, and `dot_f32` / `length_f32`
+//! accumulate in single precision without the intermediate roundings.
+
+use ::half::{ bf16, f16 };
+
+use crate::{ Float, Point2, Point3, Vector2, Vector3 };
+
+/// Neighbouring representable value towards +∞, on the raw bits of a
+/// 16-bit format whose sign is the top bit; NaN and +∞ stay put
+fn bits_next_up(bits: u16, stays: bool) -> u16 {
+    if stays {
+        bits
+    } else if bits & 0x7FFF == 0 {
+        0x0001
+    } else if bits & 0x8000 == 0 {
+        bits + 1
+    } else {
+        bits - 1
+    }
+}
+
+macro_rules! impl_half {
+    ($($t:ident),+) => {$(
+        impl Float for $t {
+            fn zero() -> Self {
+                $t::ZERO
+            }
+
+            fn one() -> Self {
+                $t::ONE
+            }
+
+            fn infinity() -> Self {
+                $t::INFINITY
+            }
+
+            fn pi() -> Self {
+                $t::PI
+            }
+
+            fn from_f64(x: f64) -> Self {
+                $t::from_f64(x)
+            }
+
+            fn to_f64(self) -> f64 {
+                $t::to_f64(self)
+            }
+
+            fn epsilon() -> Self {
+                $t::EPSILON
+            }
+
+            fn abs(self) -> Self {
+                $t::from_bits(self.to_bits() & 0x7FFF)
+            }
+
+            fn floor(self) -> Self {
+                $t::from_f32(self.to_f32().floor())
+            }
+
+            fn sqrt(self) -> Self {
+                $t::from_f32(Float::sqrt(self.to_f32()))
+            }
+
+            fn sin(self) -> Self {
+                $t::from_f32(Float::sin(self.to_f32()))
+            }
+
+            fn cos(self) -> Self {
+                $t::from_f32(Float::cos(self.to_f32()))
+            }
+
+            fn atan2(self, x: Self) -> Self {
+                $t::from_f32(Float::atan2(self.to_f32(), x.to_f32()))
+            }
+
+            fn next_up(self) -> Self {
+                let stays = self.is_nan() || self == $t::INFINITY;
+                $t::from_bits(bits_next_up(self.to_bits(), stays))
+            }
+
+            fn next_down(self) -> Self {
+                -(-self).next_up()
+            }
+
+            fn is_finite(self) -> bool {
+                $t::is_finite(self)
+            }
+        }
+
+        impl Vector2<$t> {
+            /// to_f32: Vector -> Vector
+            pub fn to_f32(self) -> Vector2<f32> {
+                Vector2(self.0.to_f32(), self.1.to_f32())
+            }
+
+            /// dot_f32: Vector × Vector -> f32
+            /// accumulated in single precision
+            pub fn dot_f32(self, v: Vector2<$t>) -> f32 {
+                self.to_f32().dot(v.to_f32())
+            }
+
+            /// length_f32: Vector -> f32
+            /// accumulated in single precision
+            pub fn length_f32(self) -> f32 {
+                self.to_f32().length()
+            }
+        }
+
+        impl Vector3<$t> {
+            /// to_f32: Vector -> Vector
+            pub fn to_f32(self) -> Vector3<f32> {
+                Vector3(self.0.to_f32(), self.1.to_f32(), self.2.to_f32())
+            }
+
+            /// dot_f32: Vector × Vector -> f32
+            /// accumulated in single precision
+            pub fn dot_f32(self, v: Vector3<$t>) -> f32 {
+                self.to_f32().dot(v.to_f32())
+            }
+
+            /// length_f32: Vector -> f32
+            /// accumulated in single precision
+            pub fn length_f32(self) -> f32 {
+                self.to_f32().length()
+            }
+        }
+
+        impl Point2<$t> {
+            /// to_f32: Point -> Point
+            pub fn to_f32(self) -> Point2<f32> {
+                Point2(self.0.to_f32(), self.1.to_f32())
+            }
+        }
+
+        impl Point3<$t> {
+            /// to_f32: Point -> Point
+            pub fn to_f32(self) -> Point3<f32> {
+                Point3(self.0.to_f32(), self.1.to_f32(), self.2.to_f32())
+            }
+        }
+    )+};
+}
+
+impl_half!(f16, bf16);
+
+macro_rules! impl_narrow {
+    ($($ty:ident ($($i:tt),+)),+) => {$(
+        impl $ty<f32> {
+            /// to_f16: f32 -> f16, componentwise
+            /// nearest representable values
+            pub fn to_f16(self) -> $ty<f16> {
+                $ty($(f16::from_f32(self.$i)),+)
+            }
+
+            /// to_bf16: f32 -> bf16, componentwise
+            /// nearest representable values
+            pub fn to_bf16(self) -> $ty<bf16> {
+                $ty($(bf16::from_f32(self.$i)),+)
+            }
+        }
+    )+};
+}
+
+impl_narrow!(Vector2(0, 1), Vector3(0, 1, 2), Point2(0, 1), Point3(0, 1, 2));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn float_half() {
+        let x = f16::from_f32(2.0);
+        assert_eq!(Float::sqrt(x), f16::from_f32(std::f32::consts::SQRT_2));
+        assert_eq!(Float::next_up(f16::ONE), f16::ONE + f16::EPSILON);
+        assert_eq!(Float::next_down(f16::ZERO), -f16::from_bits(1));
+        assert_eq!(Float::next_up(-f16::INFINITY), f16::MIN);
+        assert_eq!(Float::next_up(f16::INFINITY), f16::INFINITY);
+        assert_eq!(Float::abs(bf16::from_f32(-3.5)), bf16::from_f32(3.5));
+        assert_eq!(Float::floor(bf16::from_f32(-0.5)), -bf16::ONE);
+    }
+
+    #[test]
+    fn compact_vectors() {
+        let v = Vector3(300.0f32, 400.0, 0.0).to_f16();
+        assert_eq!(v.to_f32(), Vector3(300.0, 400.0, 0.0));
+        // 300² + 400² overflows f16 but not the f32 accumulator
+        assert_eq!(v.length_f32(), 500.0);
+        assert_eq!(v.dot(v), f16::INFINITY);
+        assert_eq!(Vector2(1.0f32, 2.0).to_bf16().dot_f32(Vector2(3.0f32, 4.0).to_bf16()), 11.0);
+        assert_eq!(Point2(0.1f32, 0.0).to_f16().to_f32(), Point2(f16::from_f32(0.1).to_f32(), 0.0));
+        assert!(Point3(1.0f32, 2.0, 3.0).to_bf16().to_f32().distance(Point3(1.0, 2.0, 3.0)) == 0.0);
+    }
+}
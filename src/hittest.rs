@@ -0,0 +1,120 @@
+//! Hit testing with a tolerance in screen pixels
+
+use crate::curve::{ flatten, segment_closest };
+use crate::path::segments;
+use crate::{ Affine2, Float, Path2, Point2, Polyline2, Segment2 };
+
+/// What a hit test landed on
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Pick2<T> {
+    /// index of the segment along the shape, counted across contours for paths
+    pub segment: usize,
+    /// nearest point of the shape, in its own coordinates
+    pub point: Point2<T>,
+    /// distance on screen, in pixels
+    pub distance: T,
+}
+
+/// screen_distance: &Affine × Point × &Segment -> K
+/// pixels from the screen point p to a segment in world coordinates,
+/// `view` taking world to screen
+pub fn screen_distance<T: Float>(view: &Affine2<T>, p: Point2<T>, s: &Segment2<T>) -> T {
+    Segment2::new(view.transform_point(s.start()), view.transform_point(s.end())).distance(p)
+}
+
+/// hit_test_polyline: &Polyline × &Affine × Point × K -> Option<Pick>
+/// the segment nearest the screen point p, if within `tolerance` pixels
+pub fn hit_test_polyline<T: Float>(polyline: &Polyline2<T>, view: &Affine2<T>, p: Point2<T>, tolerance: T) -> Option<Pick2<T>> {
+    nearest(polyline.vertices(), view, p, tolerance).map(|(k, point, distance)| Pick2 { segment: k, point, distance })
+}
+
+/// hit_test_path: &Path × &Affine × Point × K -> Option<Pick>
+/// the drawn segment (lines, curves and arcs, contours left open unless
+/// closed) nearest the screen point p, if within `tolerance` pixels; curves
+/// are flattened finely enough to be off by a small part of the tolerance
+pub fn hit_test_path<T: Float>(path: &Path2<T>, view: &Affine2<T>, p: Point2<T>, tolerance: T) -> Option<Pick2<T>> {
+    // the linear part stretches world lengths by at most its largest singular value
+    let [c0, c1] = view.columns();
+    let frobenius = c0.dot(c0) + c1.dot(c1);
+    let det = c0.0 * c1.1 - c0.1 * c1.0;
+    let spread = frobenius * frobenius - T::from_f64(4.0) * det * det;
+    let root = if spread > T::zero() { spread.sqrt() } else { T::zero() };
+    let stretch = ((frobenius + root) / T::from_f64(2.0)).sqrt();
+    let fine = tolerance / T::from_f64(16.0);
+    let fine = if stretch > T::zero() { fine / stretch } else { fine };
+    let mut best: Option<Pick2<T>> = None;
+    let all = path.contours().flat_map(|c| segments(c, false));
+    for (k, s) in all.enumerate() {
+        let line = flatten(&s, fine);
+        let Some((_, point, distance)) = nearest(line.vertices(), view, p, tolerance) else { continue };
+        if best.is_none_or(|b| distance < b.distance) {
+            best = Some(Pick2 { segment: k, point, distance });
+        }
+    }
+    best
+}
+
+/// Nearest segment of a chain to the screen point, its nearest point in
+/// world coordinates and the pixel distance, if within tolerance
+fn nearest<T: Float>(chain: &[Point2<T>], view: &Affine2<T>, p: Point2<T>, tolerance: T) -> Option<(usize, Point2<T>, T)> {
+    let screen: Vec<Point2<T>> = chain.iter().map(|&q| view.transform_point(q)).collect();
+    let pairs = if screen.len() == 1 { vec![(0, 0)] } else { (0..screen.len().saturating_sub(1)).map(|k| (k, k + 1)).collect() };
+    let mut best: Option<(usize, Point2<T>, T)> = None;
+    for (k, (i, j)) in pairs.into_iter().enumerate() {
+        let (a, b) = (screen[i], screen[j]);
+        let q = segment_closest(p, a, b);
+        let d = q.distance(p);
+        if d > tolerance || best.is_some_and(|b| b.2 <= d) {
+            continue;
+        }
+        // affine maps keep ratios along a segment
+        let ab = b.vector_from(a);
+        let l = ab.dot(ab);
+        let t = if l > T::zero() { q.vector_from(a).dot(ab) / l } else { T::zero() };
+        best = Some((k, chain[i] + chain[j].vector_from(chain[i]) * t, d));
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ PathCommand, Vector2 };
+
+    fn view() -> Affine2<f64> {
+        Affine2::new([Vector2(2.0, 0.0), Vector2(0.0, 2.0)], Vector2(10.0, 0.0))
+    }
+
+    #[test]
+    fn polylines() {
+        let line = Polyline2::new(vec![Point2(0.0, 0.0), Point2(10.0, 0.0), Point2(10.0, 10.0)]);
+        // (34, 6) on screen is (12, 3) in the world, 4 pixels right of the second segment
+        let pick = hit_test_polyline(&line, &view(), Point2(34.0, 6.0), 5.0).unwrap();
+        assert_eq!(pick, Pick2 { segment: 1, point: Point2(10.0, 3.0), distance: 4.0 });
+        assert!(hit_test_polyline(&line, &view(), Point2(34.0, 6.0), 3.0).is_none());
+        assert_eq!(screen_distance(&view(), Point2(20.0, 3.0), &Segment2::new(Point2(0.0, 0.0), Point2(10.0, 0.0))), 3.0);
+        let dot = Polyline2::new(vec![Point2(1.0, 1.0)]);
+        assert_eq!(hit_test_polyline(&dot, &view(), Point2(12.0, 3.0), 2.0).map(|p| p.segment), Some(0));
+    }
+
+    #[test]
+    fn paths() {
+        let path = Path2::new(vec![
+            PathCommand::MoveTo(Point2(0.0, 0.0)),
+            PathCommand::LineTo(Point2(10.0, 0.0)),
+            PathCommand::ArcTo { radius: 5.0, large_arc: false, sweep: true, to: Point2(10.0, 10.0) },
+            PathCommand::MoveTo(Point2(0.0, 20.0)),
+            PathCommand::CubicTo(Point2(0.0, 30.0), Point2(10.0, 30.0), Point2(10.0, 20.0)),
+        ]);
+        // the arc bulges to x = 15, at screen x = 40
+        let arc = hit_test_path(&path, &view(), Point2(41.0, 10.0), 2.0).unwrap();
+        assert_eq!(arc.segment, 1);
+        assert!((arc.distance - 1.0).abs() < 0.2 && (arc.point.0 - 15.0).abs() < 0.1);
+        // the cubic peaks at y = 27.5
+        let cubic = hit_test_path(&path, &view(), Point2(20.0, 56.0), 2.0).unwrap();
+        assert_eq!(cubic.segment, 2);
+        assert!((cubic.distance - 1.0).abs() < 0.2);
+        // open contours are not closed back to their start
+        assert!(hit_test_path(&path, &view(), Point2(20.0, 40.0), 2.0).is_none());
+    }
+}
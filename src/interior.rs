@@ -0,0 +1,198 @@
+//! Points guaranteed inside polygons
+//!
+//! The centroid of a concave polygon may lie outside it. The pole of
+//! inaccessibility, the interior point farthest from the boundary, is found
+//! by subdividing square cells from the bounds down, best bound first: a
+//! cell whose center is d from the boundary holds no point farther than d
+//! plus its half-diagonal, so cells that cannot beat the best point by the
+//! precision are dropped (Agafonkin's polylabel).
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::curve::segment_distance;
+use crate::{ Containment, ContainmentGrid2, FillRule, Float, Point2, Polygon2, Rect2, Vector2 };
+
+/// interior_grid_points: &Polygon × K -> [Point]
+/// points of the grid of multiples of `spacing` strictly inside the region
+/// the rings enclose an odd number of times, row by row upward; none for
+/// a spacing that is not positive and finite
+pub fn interior_grid_points<T: Float>(polygon: &Polygon2<T>, spacing: T) -> Vec<Point2<T>> {
+    let Some(bounds) = Rect2::from_points(polygon.vertices().iter().copied()) else { return Vec::new() };
+    if !(spacing.is_finite() && spacing > T::zero()) {
+        return Vec::new();
+    }
+    let grid = ContainmentGrid2::new(polygon);
+    let first = |x: T| (x / spacing).floor().to_f64() as i64 + 1;
+    let last = |x: T| {
+        let f = (x / spacing).floor();
+        f.to_f64() as i64 - if f * spacing == x { 1 } else { 0 }
+    };
+    let (lo, hi) = (bounds.min(), bounds.max());
+    let mut points = Vec::new();
+    for j in first(lo.1)..=last(hi.1) {
+        for i in first(lo.0)..=last(hi.0) {
+            let p = Point2(T::from_f64(i as f64) * spacing, T::from_f64(j as f64) * spacing);
+            if grid.classify(p, FillRule::EvenOdd) == Containment::Inside {
+                points.push(p);
+            }
+        }
+    }
+    points
+}
+
+/// Square cell of the search, best bound first in a max-heap
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Cell<T> {
+    center: Point2<T>,
+    half: T,
+    /// signed distance of the center to the boundary, positive inside
+    distance: T,
+    /// most any point of the cell can be from the boundary
+    bound: T,
+}
+
+impl<T: Float> Eq for Cell<T> {}
+
+impl<T: Float> PartialOrd for Cell<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Float> Ord for Cell<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bound.partial_cmp(&other.bound).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// pole_of_inaccessibility: &Polygon × K -> Option<(Point, K)>
+/// a point inside the region the rings enclose an odd number of times, within
+/// `precision` of being as far from the boundary as any, with its distance to
+/// the boundary; None if the region has no area or the precision is not
+/// positive and finite
+pub fn pole_of_inaccessibility<T: Float>(polygon: &Polygon2<T>, precision: T) -> Option<(Point2<T>, T)> {
+    let bounds = Rect2::from_points(polygon.vertices().iter().copied())?;
+    if !(precision.is_finite() && precision > T::zero()) {
+        return None;
+    }
+    let grid = ContainmentGrid2::new(polygon);
+    let distance = |p: Point2<T>| {
+        let d = polygon.rings().flat_map(|r| (0..r.len()).map(move |k| (r[k], r[(k + 1) % r.len()])))
+            .fold(T::infinity(), |m, (a, b)| {
+                let d = segment_distance(p, a, b);
+                if d < m { d } else { m }
+            });
+        if grid.contains(p, FillRule::EvenOdd) { d } else { -d }
+    };
+    let root = T::from_f64(2.0).sqrt();
+    let cell = |center: Point2<T>, half: T| {
+        let d = distance(center);
+        Cell { center, half, distance: d, bound: d + half * root }
+    };
+    let (lo, hi) = (bounds.min(), bounds.max());
+    let size = hi.vector_from(lo);
+    let side = if size.0 < size.1 { size.0 } else { size.1 };
+    if !(side.is_finite() && side > T::zero()) {
+        return None;
+    }
+    let half = side / T::from_f64(2.0);
+    let mut heap = BinaryHeap::new();
+    let mut y = lo.1;
+    while y < hi.1 {
+        let mut x = lo.0;
+        while x < hi.0 {
+            heap.push(cell(Point2(x + half, y + half), half));
+            x = x + side;
+        }
+        y = y + side;
+    }
+    // the centroid and the center of the bounds as first guesses
+    let centroid = polygon.centroid();
+    let mut best = cell(lo + size / T::from_f64(2.0), T::zero());
+    if centroid.0.is_finite() && centroid.1.is_finite() {
+        let c = cell(centroid, T::zero());
+        if c.distance > best.distance {
+            best = c;
+        }
+    }
+    while let Some(c) = heap.pop() {
+        if c.distance > best.distance {
+            best = c;
+        }
+        if c.bound - best.distance <= precision {
+            continue;
+        }
+        let h = c.half / T::from_f64(2.0);
+        for (dx, dy) in [(-h, -h), (h, -h), (-h, h), (h, h)] {
+            heap.push(cell(c.center + Vector2(dx, dy), h));
+        }
+    }
+    (best.distance > T::zero()).then_some((best.center, best.distance))
+}
+
+impl<T: Float> Polygon2<T> {
+    /// representative_point: &Polygon -> Option<Point>
+    /// a point surely inside, well away from the boundary: the pole of
+    /// inaccessibility to a thousandth of the larger side of the bounds.
+    /// Unlike the centroid it never falls outside a concave region or
+    /// into a hole; None if the region has no area
+    pub fn representative_point(&self) -> Option<Point2<T>> {
+        let bounds = Rect2::from_points(self.vertices().iter().copied())?;
+        let size = bounds.max().vector_from(bounds.min());
+        let side = if size.0 > size.1 { size.0 } else { size.1 };
+        pole_of_inaccessibility(self, side / T::from_f64(1000.0)).map(|(p, _)| p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u_shape() -> Polygon2<f64> {
+        Polygon2::new(vec![
+            Point2(0.0, 0.0), Point2(10.0, 0.0), Point2(10.0, 10.0), Point2(8.0, 10.0),
+            Point2(8.0, 2.0), Point2(2.0, 2.0), Point2(2.0, 10.0), Point2(0.0, 10.0),
+        ])
+    }
+
+    #[test]
+    fn grid_points() {
+        let square = Polygon2::new(vec![Point2(0.0, 0.0), Point2(4.0, 0.0), Point2(4.0, 4.0), Point2(0.0, 4.0)]);
+        // the points on the boundary are left out
+        assert_eq!(interior_grid_points(&square, 1.0).len(), 9);
+        assert_eq!(interior_grid_points(&square, 1.0)[0], Point2(1.0, 1.0));
+        let holed = Polygon2::with_holes(square.vertices().to_vec(), vec![vec![Point2(1.5, 1.5), Point2(1.5, 2.5), Point2(2.5, 2.5), Point2(2.5, 1.5)]]);
+        let points = interior_grid_points(&holed, 1.0);
+        assert_eq!(points.len(), 8);
+        assert!(!points.contains(&Point2(2.0, 2.0)));
+        let u = u_shape();
+        assert!(interior_grid_points(&u, 0.5).iter().all(|&p| u.contains(p, FillRule::EvenOdd)));
+        assert!(interior_grid_points(&square, 0.0).is_empty());
+    }
+
+    #[test]
+    fn pole() {
+        let u = u_shape();
+        let c = u.centroid();
+        assert!(!u.contains(c, FillRule::EvenOdd));
+        let (p, d) = pole_of_inaccessibility(&u, 1e-3).unwrap();
+        assert!(u.contains(p, FillRule::EvenOdd));
+        // in a bottom corner, as far from the outer sides as from the inner
+        // corner, within the precision
+        let corner = 2.0 * 2f64.sqrt() / (1.0 + 2f64.sqrt());
+        assert!((d - corner).abs() <= 1e-3);
+        let r = u.representative_point().unwrap();
+        assert!(u.contains(r, FillRule::EvenOdd));
+        // a square with a hole at its center is farthest off in a corner
+        let holed = Polygon2::with_holes(
+            vec![Point2(0.0, 0.0), Point2(6.0, 0.0), Point2(6.0, 6.0), Point2(0.0, 6.0)],
+            vec![vec![Point2(2.0, 2.0), Point2(2.0, 4.0), Point2(4.0, 4.0), Point2(4.0, 2.0)]],
+        );
+        let (p, d) = pole_of_inaccessibility(&holed, 1e-4).unwrap();
+        assert!(!holed.contains(holed.centroid(), FillRule::EvenOdd) && holed.contains(p, FillRule::EvenOdd));
+        assert!((d - corner).abs() <= 1e-4);
+        let flat = Polygon2::new(vec![Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(2.0, 0.0)]);
+        assert_eq!(flat.representative_point(), None);
+    }
+}
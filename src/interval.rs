@@ -0,0 +1,447 @@
+//! Interval arithmetic
+//!
+//! Every operation rounds outward, so the exact result of a computation
+//! is always enclosed by the computed interval.
+
+use std::cmp::{ Ordering, PartialOrd };
+use std::ops::{ Add, Sub, Neg, Mul, Div };
+
+use crate::Float;
+
+/// Closed interval [lo, hi]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Interval<T>(T, T);
+
+/// Outcome of a predicate evaluated on intervals
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Certainty {
+    /// holds for every value in the intervals
+    True,
+    /// holds for some values only; fall back to exact arithmetic
+    Uncertain,
+    /// holds for no value in the intervals
+    False,
+}
+
+impl Certainty {
+    /// is_certain: Certainty -> bool
+    pub fn is_certain(self) -> bool {
+        self != Certainty::Uncertain
+    }
+
+    /// to_option: Certainty -> Option<bool>
+    pub fn to_option(self) -> Option<bool> {
+        match self {
+            Certainty::True => Some(true),
+            Certainty::Uncertain => None,
+            Certainty::False => Some(false),
+        }
+    }
+}
+
+impl<T: PartialOrd> Interval<T> {
+    /// new: K × K -> Interval
+    /// panics if lo > hi
+    pub fn new(lo: T, hi: T) -> Self {
+        assert!(lo <= hi, "Interval lower bound exceeds upper bound");
+        Interval(lo, hi)
+    }
+}
+
+impl<T: PartialOrd + Copy> Interval<T> {
+    /// spanning: K × K -> Interval
+    /// between two values in any order
+    pub fn spanning(a: T, b: T) -> Self {
+        if a <= b { Interval(a, b) } else { Interval(b, a) }
+    }
+
+    /// from_values: [K] -> Option<Interval>
+    /// smallest interval holding them, None for no values
+    pub fn from_values<I: IntoIterator<Item = T>>(values: I) -> Option<Self> {
+        let mut it = values.into_iter();
+        let x = it.next()?;
+        Some(it.fold(Interval(x, x), |i, x| i.union(Interval(x, x))))
+    }
+
+    /// union: Interval × Interval -> Interval
+    /// smallest interval holding both, the gap between them included
+    pub fn union(self, i: Interval<T>) -> Self {
+        let lo = if i.0 < self.0 { i.0 } else { self.0 };
+        let hi = if i.1 > self.1 { i.1 } else { self.1 };
+        Interval(lo, hi)
+    }
+
+    /// intersection: Interval × Interval -> Option<Interval>
+    /// None if they are disjoint; a single value if they only touch
+    pub fn intersection(self, i: Interval<T>) -> Option<Self> {
+        let lo = if i.0 > self.0 { i.0 } else { self.0 };
+        let hi = if i.1 < self.1 { i.1 } else { self.1 };
+        (lo <= hi).then_some(Interval(lo, hi))
+    }
+
+    /// overlaps: Interval × Interval -> bool
+    /// touching included
+    pub fn overlaps(self, i: Interval<T>) -> bool {
+        self.0 <= i.1 && i.0 <= self.1
+    }
+
+    /// contains: Interval × K -> bool
+    /// bounds included
+    pub fn contains(self, x: T) -> bool {
+        self.0 <= x && x <= self.1
+    }
+
+    /// clamp: Interval × K -> K
+    /// the value of the interval nearest x
+    pub fn clamp(self, x: T) -> T {
+        if x < self.0 { self.0 } else if x > self.1 { self.1 } else { x }
+    }
+}
+
+impl<T: Copy> Interval<T> {
+    /// point: K -> Interval
+    /// [x, x]
+    pub fn point(x: T) -> Self {
+        Interval(x, x)
+    }
+
+    /// lo: &Interval -> K
+    pub fn lo(&self) -> T {
+        self.0
+    }
+
+    /// hi: &Interval -> K
+    pub fn hi(&self) -> T {
+        self.1
+    }
+}
+
+impl<T: Float> Interval<T> {
+    /// width: Interval -> K
+    pub fn width(self) -> T {
+        (self.1 - self.0).next_up()
+    }
+
+    /// is_positive: Interval -> Certainty
+    /// x > 0
+    pub fn is_positive(self) -> Certainty {
+        self.gt(Interval::point(T::zero()))
+    }
+
+    /// is_negative: Interval -> Certainty
+    /// x < 0
+    pub fn is_negative(self) -> Certainty {
+        self.lt(Interval::point(T::zero()))
+    }
+
+    /// is_zero: Interval -> Certainty
+    /// x = 0
+    pub fn is_zero(self) -> Certainty {
+        let zero = T::zero();
+        if self.0 == zero && self.1 == zero {
+            Certainty::True
+        } else if self.0 <= zero && zero <= self.1 {
+            Certainty::Uncertain
+        } else {
+            Certainty::False
+        }
+    }
+
+    /// lt: Interval × Interval -> Certainty
+    /// x < y
+    pub fn lt(self, i: Interval<T>) -> Certainty {
+        if self.1 < i.0 {
+            Certainty::True
+        } else if self.0 >= i.1 {
+            Certainty::False
+        } else {
+            Certainty::Uncertain
+        }
+    }
+
+    /// gt: Interval × Interval -> Certainty
+    /// x > y
+    pub fn gt(self, i: Interval<T>) -> Certainty {
+        i.lt(self)
+    }
+
+    fn outward(lo: T, hi: T) -> Self {
+        Interval(lo.next_down(), hi.next_up())
+    }
+}
+
+/// (<): &Interval × &Interval -> bool
+/// ordered only when the intervals are disjoint or equal
+impl<T: PartialOrd> PartialOrd for Interval<T> {
+    fn partial_cmp(&self, i: &Interval<T>) -> Option<Ordering> {
+        if self == i {
+            Some(Ordering::Equal)
+        } else if self.1 < i.0 {
+            Some(Ordering::Less)
+        } else if self.0 > i.1 {
+            Some(Ordering::Greater)
+        } else {
+            None
+        }
+    }
+}
+
+/// (-): Interval -> Interval
+impl<T: Neg<Output=T>> Neg for Interval<T> {
+    type Output = Interval<T>;
+
+    fn neg(self) -> Self::Output {
+        Interval(-self.1, -self.0)
+    }
+}
+
+/// (+): Interval × Interval -> Interval
+impl<T: Float> Add for Interval<T> {
+    type Output = Interval<T>;
+
+    fn add(self, i: Interval<T>) -> Self::Output {
+        Interval::outward(self.0 + i.0, self.1 + i.1)
+    }
+}
+
+/// (-): Interval × Interval -> Interval
+impl<T: Float> Sub for Interval<T> {
+    type Output = Interval<T>;
+
+    fn sub(self, i: Interval<T>) -> Self::Output {
+        Interval::outward(self.0 - i.1, self.1 - i.0)
+    }
+}
+
+/// (*): Interval × Interval -> Interval
+impl<T: Float> Mul for Interval<T> {
+    type Output = Interval<T>;
+
+    fn mul(self, i: Interval<T>) -> Self::Output {
+        let ps = [self.0 * i.0, self.0 * i.1, self.1 * i.0, self.1 * i.1];
+        let lo = ps.iter().fold(ps[0], |m, &p| if p < m { p } else { m });
+        let hi = ps.iter().fold(ps[0], |m, &p| if p > m { p } else { m });
+        Interval::outward(lo, hi)
+    }
+}
+
+/// (/): Interval × Interval -> Interval
+/// the whole line when the divisor contains zero
+impl<T: Float> Div for Interval<T> {
+    type Output = Interval<T>;
+
+    fn div(self, i: Interval<T>) -> Self::Output {
+        let zero = T::zero();
+        if i.0 <= zero && zero <= i.1 {
+            Interval(-T::infinity(), T::infinity())
+        } else {
+            let ps = [self.0 / i.0, self.0 / i.1, self.1 / i.0, self.1 / i.1];
+            let lo = ps.iter().fold(ps[0], |m, &p| if p < m { p } else { m });
+            let hi = ps.iter().fold(ps[0], |m, &p| if p > m { p } else { m });
+            Interval::outward(lo, hi)
+        }
+    }
+}
+
+impl<T: Float> Float for Interval<T> {
+    fn zero() -> Self {
+        Interval::point(T::zero())
+    }
+
+    fn one() -> Self {
+        Interval::point(T::one())
+    }
+
+    fn infinity() -> Self {
+        Interval::point(T::infinity())
+    }
+
+    fn pi() -> Self {
+        let pi = T::pi();
+        Interval(pi.next_down(), pi.next_up())
+    }
+
+    fn from_f64(x: f64) -> Self {
+        Interval::point(T::from_f64(x))
+    }
+
+    /// of the midpoint
+    fn to_f64(self) -> f64 {
+        (self.0.to_f64() + self.1.to_f64()) / 2.0
+    }
+
+    fn epsilon() -> Self {
+        Interval::point(T::epsilon())
+    }
+
+    fn abs(self) -> Self {
+        let zero = T::zero();
+        if self.0 >= zero {
+            self
+        } else if self.1 <= zero {
+            -self
+        } else {
+            Interval(zero, if -self.0 > self.1 { -self.0 } else { self.1 })
+        }
+    }
+
+    fn floor(self) -> Self {
+        Interval(self.0.floor(), self.1.floor())
+    }
+
+    /// negative parts are clamped to zero
+    fn sqrt(self) -> Self {
+        let zero = T::zero();
+        let lo = if self.0 > zero { self.0.sqrt().next_down() } else { zero };
+        let hi = if self.1 > zero { self.1.sqrt().next_up() } else { zero };
+        Interval(if lo < zero { zero } else { lo }, hi)
+    }
+
+    fn sin(self) -> Self {
+        let half_pi = T::pi() / (T::one() + T::one());
+        (self - Interval::point(half_pi)).cos()
+    }
+
+    /// extrema are taken where the interval covers a multiple of π
+    fn cos(self) -> Self {
+        let (one, two) = (T::one(), T::one() + T::one());
+        let (pi, tau) = (T::pi(), T::pi() * two);
+        if !self.is_finite() || self.1 - self.0 >= tau {
+            return Interval(-one, one);
+        }
+        let k = (self.0 / tau).floor();
+        let (a, b) = (self.0 - tau * k, self.1 - tau * k);
+        let (ca, cb) = (self.0.cos(), self.1.cos());
+        let mut lo = if ca < cb { ca } else { cb };
+        let mut hi = if ca < cb { cb } else { ca };
+        if a <= tau && tau <= b {
+            hi = one;
+        }
+        if (a <= pi && pi <= b) || (a <= pi * (two + one) && pi * (two + one) <= b) {
+            lo = -one;
+        }
+        let (lo, hi) = (lo.next_down(), hi.next_up());
+        Interval(if lo < -one { -one } else { lo }, if hi > one { one } else { hi })
+    }
+
+    /// the whole range (-π, π] when the box meets the branch cut
+    fn atan2(self, x: Self) -> Self {
+        let (y, zero) = (self, T::zero());
+        if x.0 <= zero && y.0 <= zero && zero <= y.1 {
+            let pi = T::pi();
+            return Interval(-pi.next_up(), pi.next_up());
+        }
+        let ps = [y.0.atan2(x.0), y.0.atan2(x.1), y.1.atan2(x.0), y.1.atan2(x.1)];
+        let lo = ps.iter().fold(ps[0], |m, &p| if p < m { p } else { m });
+        let hi = ps.iter().fold(ps[0], |m, &p| if p > m { p } else { m });
+        Interval::outward(lo, hi)
+    }
+
+    fn next_up(self) -> Self {
+        Interval(self.0.next_up(), self.1.next_up())
+    }
+
+    fn next_down(self) -> Self {
+        Interval(self.0.next_down(), self.1.next_down())
+    }
+
+    fn is_finite(self) -> bool {
+        self.0.is_finite() && self.1.is_finite()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ Vector2, Point2 };
+
+    fn encloses(i: Interval<f64>, x: f64) -> bool {
+        i.lo() <= x && x <= i.hi()
+    }
+
+    #[test]
+    fn arith_interval() {
+        let a = Interval::new(1.0, 2.0);
+        let b = Interval::new(-3.0, 4.0);
+        assert!(encloses(a + b, -2.0) && encloses(a + b, 6.0));
+        assert!(encloses(a - b, -3.0) && encloses(a - b, 5.0));
+        assert!(encloses(a * b, -6.0) && encloses(a * b, 8.0));
+        assert!((a * b).lo() < -6.0 && (a * b).hi() > 8.0);
+        assert_eq!(-a, Interval(-2.0, -1.0));
+        assert_eq!((a / b).hi(), f64::INFINITY);
+        assert!(encloses(b / a, -3.0) && encloses(b / a, 4.0));
+        assert!(encloses(Interval::new(4.0, 9.0).sqrt(), 2.0));
+    }
+
+    #[test]
+    fn ranges() {
+        let (a, b) = (Interval::spanning(3.0, 1.0), Interval::new(2.0, 5.0));
+        assert_eq!(a, Interval(1.0, 3.0));
+        assert_eq!(a.union(b), Interval(1.0, 5.0));
+        assert_eq!(a.intersection(b), Some(Interval(2.0, 3.0)));
+        assert_eq!(a.intersection(Interval::new(3.0, 4.0)), Some(Interval::point(3.0)));
+        assert_eq!(a.intersection(Interval::new(4.0, 6.0)), None);
+        assert!(a.overlaps(Interval::new(3.0, 4.0)) && !a.overlaps(Interval::new(3.5, 4.0)));
+        assert!(a.contains(1.0) && !a.contains(0.5));
+        assert_eq!((a.clamp(0.0), a.clamp(2.5), a.clamp(9.0)), (1.0, 2.5, 3.0));
+        assert_eq!(Interval::from_values([4.0, -1.0, 2.0]), Some(Interval(-1.0, 4.0)));
+        assert_eq!(Interval::<f64>::from_values([]), None);
+    }
+
+    #[test]
+    fn enclose_rounding() {
+        let x = Interval::point(0.1) + Interval::point(0.2);
+        assert!(encloses(x, 0.1 + 0.2));
+        assert!(x.lo() < x.hi());
+    }
+
+    #[test]
+    fn trig_interval() {
+        let c = Interval::new(-0.5, 1.0).cos();
+        assert_eq!(c.hi(), 1.0);
+        assert!(encloses(c, 1.0f64.cos()) && encloses(c, 0.5f64.cos()));
+        let c = Interval::new(3.0, 7.0).cos();
+        assert_eq!((c.lo(), c.hi()), (-1.0, 1.0));
+        let s = Interval::new(0.1, 0.2).sin();
+        assert!(encloses(s, 0.1f64.sin()) && encloses(s, 0.2f64.sin()) && s.hi() < 0.21);
+        let s = Interval::new(1.0, 2.0).sin();
+        assert_eq!(s.hi(), 1.0);
+        let a = Interval::new(1.0, 2.0).atan2(Interval::new(1.0, 2.0));
+        assert!(encloses(a, 0.5f64.atan()) && encloses(a, 2.0f64.atan()));
+        let a = Interval::new(-1.0, 1.0).atan2(Interval::new(-2.0, -1.0));
+        assert!(encloses(a, std::f64::consts::PI) && encloses(a, -3.0));
+    }
+
+    #[test]
+    fn predicates() {
+        let a = Interval::new(1.0, 2.0);
+        assert_eq!(a.is_positive(), Certainty::True);
+        assert_eq!(a.is_negative(), Certainty::False);
+        assert_eq!(Interval::new(-1.0, 2.0).is_positive(), Certainty::Uncertain);
+        assert_eq!(Interval::point(0.0).is_zero(), Certainty::True);
+        assert_eq!(a.lt(Interval::new(3.0, 4.0)), Certainty::True);
+        assert_eq!(a.gt(Interval::new(1.5, 4.0)).to_option(), None);
+        assert!(a < Interval::new(3.0, 4.0));
+        assert_eq!(a.partial_cmp(&Interval::new(1.5, 4.0)), None);
+    }
+
+    #[test]
+    fn orientation_filter() {
+        let p = |x, y| Point2(Interval::point(x), Interval::point(y));
+        let orient = |a: Point2<Interval<f64>>, b: Point2<Interval<f64>>, c: Point2<Interval<f64>>| {
+            let u = Vector2(b.0 - a.0, b.1 - a.1);
+            let v = Vector2(c.0 - a.0, c.1 - a.1);
+            u.0 * v.1 - u.1 * v.0
+        };
+        let det = orient(p(0.0, 0.0), p(1.0, 0.0), p(0.0, 1.0));
+        assert_eq!(det.is_positive(), Certainty::True);
+        let det = orient(p(0.1, 0.1), p(0.2, 0.2), p(0.3, 0.3));
+        assert_eq!(det.is_positive(), Certainty::Uncertain);
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_reversed() {
+        Interval::new(2.0, 1.0);
+    }
+}
@@ -0,0 +1,213 @@
+//! Rigid motions and screw (twist) coordinates
+//!
+//! Every rigid motion is a screw: a rotation about some axis combined
+//! with a translation along it. The twist (ω, v) is the logarithm of
+//! the motion; scaling it and mapping back with exp moves along the
+//! screw, which gives the ScLERP blend between two poses.
+
+use std::ops::Mul;
+
+use crate::{ Float, Point3, Rotor3, Vector3 };
+
+/// 3d Isometry: rotation, then translation
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Isometry3<T>(Rotor3<T>, Vector3<T>);
+
+/// 3d Twist: angular velocity ω, linear velocity v
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Twist3<T>(Vector3<T>, Vector3<T>);
+
+impl<T: Copy> Twist3<T> {
+    /// new: Vector × Vector -> Twist
+    pub fn new(angular: Vector3<T>, linear: Vector3<T>) -> Self {
+        Twist3(angular, linear)
+    }
+
+    /// angular: &Twist -> Vector
+    pub fn angular(&self) -> Vector3<T> {
+        self.0
+    }
+
+    /// linear: &Twist -> Vector
+    pub fn linear(&self) -> Vector3<T> {
+        self.1
+    }
+}
+
+/// (*): Twist × K -> Twist
+impl<T: Mul<Output=T> + Copy> Mul<T> for Twist3<T> {
+    type Output = Twist3<T>;
+
+    fn mul(self, k: T) -> Self::Output {
+        Twist3(self.0 * k, self.1 * k)
+    }
+}
+
+impl<T: Float> Isometry3<T> {
+    /// new: Rotor × Vector -> Isometry
+    pub fn new(rotation: Rotor3<T>, translation: Vector3<T>) -> Self {
+        Isometry3(rotation, translation)
+    }
+
+    /// identity: Isometry
+    pub fn identity() -> Self {
+        let zero = T::zero();
+        Isometry3(Rotor3::identity(), Vector3(zero, zero, zero))
+    }
+
+    /// rotation: &Isometry -> Rotor
+    pub fn rotation(&self) -> Rotor3<T> {
+        self.0
+    }
+
+    /// translation: &Isometry -> Vector
+    pub fn translation(&self) -> Vector3<T> {
+        self.1
+    }
+
+    /// inverse: Isometry -> Isometry
+    pub fn inverse(self) -> Self {
+        let r = self.0.reverse();
+        Isometry3(r, -r.rotate(self.1))
+    }
+
+    /// transform_point: Isometry × Point -> Point
+    pub fn transform_point(self, p: Point3<T>) -> Point3<T> {
+        let Vector3(x, y, z) = self.0.rotate(Vector3(p.0, p.1, p.2));
+        Point3(x, y, z) + self.1
+    }
+
+    /// transform_vector: Isometry × Vector -> Vector
+    pub fn transform_vector(self, v: Vector3<T>) -> Vector3<T> {
+        self.0.rotate(v)
+    }
+
+    /// exp: Twist -> Isometry
+    /// the motion after following the twist for unit time
+    pub fn exp(twist: Twist3<T>) -> Self {
+        let Twist3(w, v) = twist;
+        let (one, two) = (T::one(), T::one() + T::one());
+        let theta = w.length();
+        let (a, b) = if theta < T::epsilon().sqrt() {
+            (one / two, one / (two + two + two))
+        } else {
+            let t2 = theta * theta;
+            ((one - theta.cos()) / t2, (theta - theta.sin()) / (t2 * theta))
+        };
+        let wv = w.cross(v);
+        Isometry3(Rotor3::exp(w), v + wv * a + w.cross(wv) * b)
+    }
+
+    /// log: Isometry -> Twist
+    /// rotation angle within [0, π]
+    pub fn log(self) -> Twist3<T> {
+        let (one, two) = (T::one(), T::one() + T::one());
+        let w = self.0.log();
+        let theta = w.length();
+        let c = if theta < T::epsilon().sqrt() {
+            one / (two * (two + two + two))
+        } else {
+            (one - theta * theta.sin() / (two * (one - theta.cos()))) / (theta * theta)
+        };
+        let t = self.1;
+        let wt = w.cross(t);
+        Twist3(w, t - wt / two + w.cross(wt) * c)
+    }
+
+    /// sclerp: Isometry × Isometry × K -> Isometry
+    /// screw linear interpolation, constant velocity along the shortest screw
+    pub fn sclerp(self, m: Isometry3<T>, t: T) -> Self {
+        self * Isometry3::exp((self.inverse() * m).log() * t)
+    }
+
+    /// interpolate: Isometry × Isometry × K -> Isometry
+    /// `sclerp`, named like the rotor interpolation
+    pub fn interpolate(self, m: Isometry3<T>, t: T) -> Self {
+        self.sclerp(m, t)
+    }
+}
+
+/// (*): Isometry × Isometry -> Isometry
+/// applies the right isometry first
+impl<T: Float> Mul for Isometry3<T> {
+    type Output = Isometry3<T>;
+
+    fn mul(self, m: Isometry3<T>) -> Self::Output {
+        Isometry3(self.0 * m.0, self.0.rotate(m.1) + self.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn near(p: Point3<f64>, q: Point3<f64>) -> bool {
+        p.distance(q) < 1e-12
+    }
+
+    fn near_vec(v: Vector3<f64>, w: Vector3<f64>) -> bool {
+        (v - w).length() < 1e-12
+    }
+
+    fn about_z(angle: f64) -> Rotor3<f64> {
+        Rotor3::exp(Vector3(0.0, 0.0, angle))
+    }
+
+    #[test]
+    fn rotor_exp_log() {
+        assert!(near_vec(about_z(PI / 2.0).rotate(Vector3(1.0, 0.0, 0.0)), Vector3(0.0, 1.0, 0.0)));
+        let rx = Rotor3::exp(Vector3(PI / 2.0, 0.0, 0.0));
+        assert!(near_vec(rx.rotate(Vector3(0.0, 1.0, 0.0)), Vector3(0.0, 0.0, 1.0)));
+        let ry = Rotor3::exp(Vector3(0.0, PI / 2.0, 0.0));
+        assert!(near_vec(ry.rotate(Vector3(0.0, 0.0, 1.0)), Vector3(1.0, 0.0, 0.0)));
+        let w = Vector3(0.3, -1.2, 0.5);
+        assert!(near_vec(Rotor3::exp(w).log(), w));
+        assert!(near_vec(Rotor3::exp(Vector3(1e-10, 0.0, 0.0)).log(), Vector3(1e-10, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn compose_inverse() {
+        let m = Isometry3::new(about_z(0.7), Vector3(1.0, 2.0, 3.0));
+        let p = Point3(0.5, -1.0, 2.0);
+        assert!(near((m.inverse() * m).transform_point(p), p));
+        assert!(near((m * m).transform_point(p), m.transform_point(m.transform_point(p))));
+        assert!(near(Isometry3::identity().transform_point(p), p));
+    }
+
+    #[test]
+    fn exp_log() {
+        let m = Isometry3::new(Rotor3::exp(Vector3(0.2, 0.4, -0.3)), Vector3(1.0, -2.0, 0.5));
+        let back = Isometry3::exp(m.log());
+        let p = Point3(1.0, 1.0, 1.0);
+        assert!(near(back.transform_point(p), m.transform_point(p)));
+        let t = Isometry3::new(Rotor3::identity(), Vector3(1.0, 2.0, 3.0));
+        assert!(near_vec(t.log().linear(), Vector3(1.0, 2.0, 3.0)));
+        assert!(near_vec(t.log().angular(), Vector3(0.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn sclerp_screw() {
+        // quarter turn about the z-parallel axis through (1, 0, 0), rising 2 along it
+        let c = Vector3(1.0, 0.0, 0.0);
+        let r = about_z(PI / 2.0);
+        let b = Isometry3::new(r, c - r.rotate(c) + Vector3(0.0, 0.0, 2.0));
+        let a = Isometry3::identity();
+        let p = Point3(0.0, 0.0, 0.0);
+        assert!(near(a.sclerp(b, 0.0).transform_point(p), p));
+        assert!(near(a.sclerp(b, 1.0).transform_point(p), b.transform_point(p)));
+        let mid = a.sclerp(b, 0.5);
+        assert!(near(mid.transform_point(Point3(1.0, 0.0, 0.0)), Point3(1.0, 0.0, 1.0)));
+        let h = (0.5f64).sqrt();
+        assert!(near(mid.transform_point(p), Point3(1.0 - h, -h, 1.0)));
+    }
+
+    #[test]
+    fn interpolate_screw() {
+        let (a, b) = (Isometry3::new(about_z(0.2), Vector3(1.0, 0.0, 0.0)), Isometry3::new(about_z(PI / 3.0), Vector3(0.0, 2.0, 1.0)));
+        let p = Point3(0.5, -1.0, 2.0);
+        for t in [0.0, 0.25, 0.5, 1.0] {
+            assert!(near(a.interpolate(b, t).transform_point(p), a.sclerp(b, t).transform_point(p)));
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! Spatial predicates between geometries and joins over collections
+//!
+//! Geometries are taken apart into points, segments and polygons; areas
+//! are filled by the non-zero rule. Each part keeps its pieces in an R-tree
+//! and its areas in containment grids, so two parts are compared piece
+//! against nearby piece only. Joins index the second collection's bounds in
+//! an R-tree too and test only the pairs whose bounds meet.
+
+use std::cmp::Ordering;
+
+use crate::rtree::RTree2;
+use crate::{ Bounded2, ContainmentGrid2, FillRule, Float, Geometry2, Point2, Polygon2, Rect2, Segment2 };
+
+/// Relation a spatial join keeps pairs for, first geometry to second
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SpatialPredicate<T> {
+    /// sharing at least a point
+    Intersects,
+    /// the second lying wholly in the first, boundary included
+    Contains,
+    /// no farther apart than the distance
+    WithinDistance(T),
+}
+
+/// A geometry taken apart
+struct Parts<T> {
+    points: Vec<Point2<T>>,
+    /// polyline pieces and polygon edges
+    segments: Vec<Segment2<T>>,
+    /// the points as zero-length segments, then the segments
+    pieces: Vec<Segment2<T>>,
+    /// bounds of the pieces
+    index: RTree2<T>,
+    /// the polygons, each as its own grid so overlaps count once
+    areas: Vec<ContainmentGrid2<T>>,
+    /// bounds of the areas
+    area_index: RTree2<T>,
+}
+
+impl<T: Float> Parts<T> {
+    fn of(g: &Geometry2<T>) -> Self {
+        let mut taken = Taken { points: Vec::new(), segments: Vec::new(), areas: Vec::new() };
+        taken.add(g);
+        let Taken { points, segments, areas } = taken;
+        let pieces: Vec<Segment2<T>> = points.iter().map(|&p| Segment2::new(p, p)).chain(segments.iter().copied()).collect();
+        let index = RTree2::new(pieces.iter().map(|s| Rect2::new(s.start(), s.end())).zip(0..).collect());
+        let area_index = RTree2::new(areas.iter().enumerate().filter_map(|(k, a)| Some((a.bounds()?, k))).collect());
+        let areas = areas.into_iter().map(ContainmentGrid2::new).collect();
+        Parts { points, segments, pieces, index, areas, area_index }
+    }
+
+    /// Vertices: a piece lying wholly inside something has them all inside
+    fn vertices(&self) -> impl Iterator<Item = Point2<T>> + '_ {
+        self.points.iter().copied().chain(self.segments.iter().flat_map(|s| [s.start(), s.end()]))
+    }
+
+    fn in_area(&self, p: Point2<T>) -> bool {
+        self.area_index.any(&Rect2::new(p, p), |k| self.areas[k].contains(p, FillRule::NonZero))
+    }
+
+    /// Whether p is on a segment or point
+    fn on_outline(&self, p: Point2<T>) -> bool {
+        self.index.any(&Rect2::new(p, p), |k| self.pieces[k].distance(p) == T::zero())
+    }
+
+    /// Whether p is in an area or on a segment or point
+    fn covers(&self, p: Point2<T>) -> bool {
+        self.on_outline(p) || self.in_area(p)
+    }
+
+    /// Least distance between the outlines, points included: the pieces of
+    /// the smaller part each against the index of the larger
+    fn outline_distance(&self, other: &Parts<T>) -> T {
+        let (few, many) = if self.pieces.len() <= other.pieces.len() { (self, other) } else { (other, self) };
+        let mut best = T::infinity();
+        for s in &few.pieces {
+            best = many.index.nearest(&Rect2::new(s.start(), s.end()), best, |k| segment_gap(s, &many.pieces[k]));
+            if best == T::zero() {
+                break;
+            }
+        }
+        best
+    }
+
+    /// Least distance between the parts, zero where they intersect
+    fn distance(&self, other: &Parts<T>) -> T {
+        let gap = self.outline_distance(other);
+        if gap == T::zero() || other.vertices().any(|p| self.in_area(p)) || self.vertices().any(|p| other.in_area(p)) {
+            T::zero()
+        } else {
+            gap
+        }
+    }
+
+    fn intersects(&self, other: &Parts<T>) -> bool {
+        self.distance(other) == T::zero()
+    }
+
+    fn contains(&self, other: &Parts<T>) -> bool {
+        if other.pieces.is_empty() {
+            return false;
+        }
+        if !other.points.iter().all(|&p| self.covers(p)) {
+            return false;
+        }
+        // each segment cut where it meets the outline: the pieces between
+        // are wholly in or out, so their middles decide
+        let half = T::from_f64(0.5);
+        let mut near = Vec::new();
+        let mut ts = Vec::new();
+        let inside = other.segments.iter().all(|s| {
+            let (a, d) = (s.start(), s.end().vector_from(s.start()));
+            let dd = d.dot(d);
+            ts.clear();
+            ts.extend([T::zero(), T::one()]);
+            if dd > T::zero() {
+                near.clear();
+                self.index.query(&Rect2::new(s.start(), s.end()), &mut near);
+                for e in near.iter().map(|&k| &self.pieces[k]) {
+                    if let Some(p) = s.intersection(e) {
+                        ts.push(p.vector_from(a).dot(d) / dd);
+                    }
+                    for q in [e.start(), e.end()] {
+                        if s.distance(q) == T::zero() {
+                            ts.push(q.vector_from(a).dot(d) / dd);
+                        }
+                    }
+                }
+            }
+            ts.sort_by(|x, y| x.partial_cmp(y).unwrap_or(Ordering::Equal));
+            ts.iter().all(|&t| self.covers(a + d * t)) && ts.windows(2).all(|w| self.covers(a + d * ((w[0] + w[1]) * half)))
+        });
+        // no part of this outline strictly inside the other's areas, as
+        // around a hole they fill
+        inside && !self.vertices().any(|p| other.in_area(p) && !other.on_outline(p))
+    }
+}
+
+/// A geometry's pieces as they are gathered
+struct Taken<'a, T> {
+    points: Vec<Point2<T>>,
+    segments: Vec<Segment2<T>>,
+    areas: Vec<&'a Polygon2<T>>,
+}
+
+impl<'a, T: Float> Taken<'a, T> {
+    fn add(&mut self, g: &'a Geometry2<T>) {
+        match g {
+            Geometry2::Point(p) => self.points.push(*p),
+            Geometry2::Polyline(l) => self.segments.extend(l.segments()),
+            Geometry2::Polygon(p) => self.add_polygon(p),
+            Geometry2::MultiPoint(m) => self.points.extend(m.iter().copied()),
+            Geometry2::MultiPolyline(m) => m.iter().for_each(|l| self.segments.extend(l.segments())),
+            Geometry2::MultiPolygon(m) => m.iter().for_each(|p| self.add_polygon(p)),
+            Geometry2::Collection(c) => c.iter().for_each(|g| self.add(g)),
+        }
+    }
+
+    fn add_polygon(&mut self, p: &'a Polygon2<T>) {
+        self.segments.extend(p.edges());
+        self.areas.push(p);
+    }
+}
+
+/// Least distance between two segments
+fn segment_gap<T: Float>(s: &Segment2<T>, t: &Segment2<T>) -> T {
+    if s.intersection(t).is_some() {
+        return T::zero();
+    }
+    [t.distance(s.start()), t.distance(s.end()), s.distance(t.start()), s.distance(t.end())]
+        .into_iter()
+        .fold(T::infinity(), |m, d| if d < m { d } else { m })
+}
+
+impl<T: Float> Geometry2<T> {
+    /// intersects: &Geometry × &Geometry -> bool
+    /// whether they share a point, areas filled by the non-zero rule
+    pub fn intersects(&self, other: &Geometry2<T>) -> bool {
+        Parts::of(self).intersects(&Parts::of(other))
+    }
+
+    /// contains: &Geometry × &Geometry -> bool
+    /// whether every point of the other lies in this one, boundary
+    /// included; false for an empty other
+    pub fn contains(&self, other: &Geometry2<T>) -> bool {
+        Parts::of(self).contains(&Parts::of(other))
+    }
+
+    /// distance_to: &Geometry × &Geometry -> K
+    /// least distance between their points, zero where they intersect;
+    /// infinite if either is empty
+    pub fn distance_to(&self, other: &Geometry2<T>) -> T {
+        Parts::of(self).distance(&Parts::of(other))
+    }
+}
+
+/// spatial_join: [Geometry] × [Geometry] × Predicate -> [(usize, usize)]
+/// index pairs (i, j) with a[i] related to b[j], sorted; candidates come
+/// from an R-tree over the bounds of b
+pub fn spatial_join<T: Float>(a: &[Geometry2<T>], b: &[Geometry2<T>], predicate: SpatialPredicate<T>) -> Vec<(usize, usize)> {
+    let reach = match predicate {
+        SpatialPredicate::WithinDistance(d) if d > T::zero() => d,
+        _ => T::zero(),
+    };
+    let tree = RTree2::new(b.iter().enumerate().filter_map(|(j, g)| Some((g.bounds()?, j))).collect());
+    let parts: Vec<Parts<T>> = b.iter().map(Parts::of).collect();
+    let mut pairs = Vec::new();
+    let mut found = Vec::new();
+    for (i, g) in a.iter().enumerate() {
+        let Some(bounds) = g.bounds() else {
+            continue;
+        };
+        let (lo, hi) = (bounds.min(), bounds.max());
+        let grown = Rect2::new(Point2(lo.0 - reach, lo.1 - reach), Point2(hi.0 + reach, hi.1 + reach));
+        let mine = Parts::of(g);
+        found.clear();
+        tree.query(&grown, &mut found);
+        found.sort_unstable();
+        for &j in &found {
+            let related = match predicate {
+                SpatialPredicate::Intersects => mine.intersects(&parts[j]),
+                SpatialPredicate::Contains => mine.contains(&parts[j]),
+                SpatialPredicate::WithinDistance(d) => mine.distance(&parts[j]) <= d,
+            };
+            if related {
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ MultiPoint2, Polyline2 };
+
+    fn square(x: f64, y: f64, s: f64) -> Geometry2<f64> {
+        Geometry2::Polygon(Polygon2::new(vec![Point2(x, y), Point2(x + s, y), Point2(x + s, y + s), Point2(x, y + s)]))
+    }
+
+    #[test]
+    fn predicates() {
+        let big = square(0.0, 0.0, 10.0);
+        let small = square(2.0, 2.0, 2.0);
+        let apart = square(13.0, 0.0, 1.0);
+        let line = Geometry2::Polyline(Polyline2::new(vec![Point2(5.0, 5.0), Point2(12.0, 5.0)]));
+        assert!(big.contains(&small) && !small.contains(&big) && big.contains(&big));
+        assert!(big.intersects(&small) && small.intersects(&big));
+        assert!(!big.contains(&line) && big.intersects(&line));
+        assert_eq!(big.distance_to(&apart), 3.0);
+        assert_eq!(line.distance_to(&apart), 17f64.sqrt());
+        // a hole: the ring around it is not inside, the square in it not contained
+        let ring = Geometry2::Polygon(Polygon2::with_holes(
+            vec![Point2(0.0, 0.0), Point2(10.0, 0.0), Point2(10.0, 10.0), Point2(0.0, 10.0)],
+            vec![vec![Point2(1.0, 1.0), Point2(1.0, 5.0), Point2(5.0, 5.0), Point2(5.0, 1.0)]],
+        ));
+        assert!(!ring.contains(&small) && !ring.intersects(&square(2.0, 2.0, 1.0)));
+        assert!(!small.contains(&ring) && ring.contains(&square(6.0, 6.0, 3.0)));
+        let points = Geometry2::MultiPoint(MultiPoint2::new(vec![Point2(0.0, 0.0), Point2(3.0, 3.0)]));
+        assert!(big.contains(&points) && !small.contains(&points));
+    }
+
+    #[test]
+    fn joins() {
+        let zones: Vec<Geometry2<f64>> = (0..4).map(|k| square(10.0 * k as f64, 0.0, 10.0)).collect();
+        let sites: Vec<Geometry2<f64>> = [(5.0, 5.0), (15.0, 5.0), (20.0, 5.0), (45.0, 5.0), (25.0, 12.0)]
+            .iter().map(|&(x, y)| Geometry2::Point(Point2(x, y))).collect();
+        assert_eq!(spatial_join(&zones, &sites, SpatialPredicate::Contains), vec![(0, 0), (1, 1), (1, 2), (2, 2)]);
+        assert_eq!(spatial_join(&sites, &zones, SpatialPredicate::Intersects), vec![(0, 0), (1, 1), (2, 1), (2, 2)]);
+        assert_eq!(
+            spatial_join(&sites, &zones, SpatialPredicate::WithinDistance(3.0)),
+            vec![(0, 0), (1, 1), (2, 1), (2, 2), (4, 2)],
+        );
+        // against every pair tested directly
+        for predicate in [SpatialPredicate::Intersects, SpatialPredicate::Contains, SpatialPredicate::WithinDistance(2.5)] {
+            let direct: Vec<(usize, usize)> = (0..sites.len()).flat_map(|i| (0..zones.len()).map(move |j| (i, j))).filter(|&(i, j)| match predicate {
+                SpatialPredicate::Intersects => sites[i].intersects(&zones[j]),
+                SpatialPredicate::Contains => sites[i].contains(&zones[j]),
+                SpatialPredicate::WithinDistance(d) => sites[i].distance_to(&zones[j]) <= d,
+            }).collect();
+            assert_eq!(spatial_join(&sites, &zones, predicate), direct);
+        }
+    }
+}
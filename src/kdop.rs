@@ -0,0 +1,174 @@
+//! Discrete oriented polytopes
+//!
+//! A k-DOP bounds a shape by k/2 slabs along fixed directions: AABB axes,
+//! cube corner diagonals and edge diagonals. More directions hug diagonal
+//! geometry more tightly than an AABB at the price of more comparisons.
+//! Directions are kept unnormalized (their coordinates are 0 or ±1), so
+//! slab extents are projections scaled by the direction's length.
+
+use crate::{ Float, Interval, Point3, TriMesh, Vector3 };
+
+/// k-DOP with N slabs: 4 (8-DOP), 7 (14-DOP), 9 (18-DOP) or 13 (26-DOP)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Kdop3<T, const N: usize> {
+    min: [T; N],
+    max: [T; N],
+}
+
+/// 8-DOP: the four corner diagonals
+pub type Dop8<T> = Kdop3<T, 4>;
+/// 14-DOP: the axes and the four corner diagonals
+pub type Dop14<T> = Kdop3<T, 7>;
+/// 18-DOP: the axes and the six edge diagonals
+pub type Dop18<T> = Kdop3<T, 9>;
+/// 26-DOP: the axes, corner and edge diagonals
+pub type Dop26<T> = Kdop3<T, 13>;
+
+const FACES: [[i8; 3]; 3] = [[1, 0, 0], [0, 1, 0], [0, 0, 1]];
+const CORNERS: [[i8; 3]; 4] = [[1, 1, 1], [1, 1, -1], [1, -1, 1], [-1, 1, 1]];
+const EDGES: [[i8; 3]; 6] = [[1, 1, 0], [1, 0, 1], [0, 1, 1], [1, -1, 0], [1, 0, -1], [0, 1, -1]];
+
+impl<T: Float, const N: usize> Kdop3<T, N> {
+    /// axes: () -> [Vector; N]
+    /// the slab directions, in the order of `min` and `max`
+    pub fn axes() -> [Vector3<T>; N] {
+        const { assert!(matches!(N, 4 | 7 | 9 | 13), "a k-DOP has 4, 7, 9 or 13 slabs") };
+        std::array::from_fn(|k| direction(N, k).map(|c| T::from_f64(c as f64))).map(|[x, y, z]| Vector3(x, y, z))
+    }
+
+    /// from_points: [Point] -> Option<Kdop>
+    /// None for no points
+    pub fn from_points<I: IntoIterator<Item = Point3<T>>>(points: I) -> Option<Self> {
+        let axes = Self::axes();
+        let mut it = points.into_iter();
+        let first = project(&axes, it.next()?);
+        Some(it.fold(Kdop3 { min: first, max: first }, |d, p| d.expand_projected(project(&axes, p))))
+    }
+
+    /// from_mesh: &Mesh -> Option<Kdop>
+    /// None for a mesh without vertices
+    pub fn from_mesh(mesh: &TriMesh<T>) -> Option<Self> {
+        Self::from_points(mesh.vertices().iter().copied())
+    }
+
+    /// min: &Kdop -> [K; N]
+    /// lowest projection on each axis
+    pub fn min(&self) -> [T; N] {
+        self.min
+    }
+
+    /// max: &Kdop -> [K; N]
+    /// highest projection on each axis
+    pub fn max(&self) -> [T; N] {
+        self.max
+    }
+
+    /// expand: Kdop × Point -> Kdop
+    pub fn expand(self, p: Point3<T>) -> Self {
+        self.expand_projected(project(&Self::axes(), p))
+    }
+
+    fn expand_projected(self, q: [T; N]) -> Self {
+        self.union(Kdop3 { min: q, max: q })
+    }
+
+    /// union: Kdop × Kdop -> Kdop
+    pub fn union(self, other: Self) -> Self {
+        Kdop3 {
+            min: std::array::from_fn(|k| if other.min[k] < self.min[k] { other.min[k] } else { self.min[k] }),
+            max: std::array::from_fn(|k| if other.max[k] > self.max[k] { other.max[k] } else { self.max[k] }),
+        }
+    }
+
+    /// inflated: Kdop × K -> Kdop
+    /// every slab widened by the distance on both sides
+    pub fn inflated(self, margin: T) -> Self {
+        let axes = Self::axes();
+        let grow = |k: usize| margin * axes[k].length();
+        Kdop3 {
+            min: std::array::from_fn(|k| self.min[k] - grow(k)),
+            max: std::array::from_fn(|k| self.max[k] + grow(k)),
+        }
+    }
+
+    /// contains: &Kdop × Point -> bool
+    /// boundary included
+    pub fn contains(&self, p: Point3<T>) -> bool {
+        let q = project(&Self::axes(), p);
+        (0..N).all(|k| self.interval(k).is_some_and(|i| i.contains(q[k])))
+    }
+
+    /// overlaps: &Kdop × &Kdop -> bool
+    /// whether the slabs overlap on every axis, touching included; like
+    /// the AABB test it may report polytopes meeting only across an edge
+    /// direction it does not check
+    pub fn overlaps(&self, other: &Self) -> bool {
+        (0..N).all(|k| matches!((self.interval(k), other.interval(k)), (Some(i), Some(j)) if i.overlaps(j)))
+    }
+
+    /// interval: &Kdop × usize -> Option<Interval>
+    /// the slab along the k-th of `axes`, in multiples of its length;
+    /// None if its bounds are inverted or NaN
+    pub fn interval(&self, k: usize) -> Option<Interval<T>> {
+        (self.min[k] <= self.max[k]).then(|| Interval::new(self.min[k], self.max[k]))
+    }
+}
+
+/// k-th slab direction of a k-DOP with n slabs: faces first, then corners, then edges
+const fn direction(n: usize, k: usize) -> [i8; 3] {
+    match (n, k) {
+        (4, _) => CORNERS[k],
+        (_, 0..=2) => FACES[k],
+        (9, _) => EDGES[k - 3],
+        (_, 3..=6) => CORNERS[k - 3],
+        _ => EDGES[k - 7],
+    }
+}
+
+fn project<T: Float, const N: usize>(axes: &[Vector3<T>; N], p: Point3<T>) -> [T; N] {
+    axes.map(|a| a.0 * p.0 + a.1 * p.1 + a.2 * p.2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Aabb3;
+
+    #[test]
+    fn diagonal_rods() {
+        // two parallel rods along (1, 1, 0): their boxes overlap, the
+        // edge diagonal slabs separate them
+        let rod = |o: f64| (0..=10).map(move |k| Point3(k as f64 + o, k as f64 - o, 0.0));
+        let (a, b) = (rod(0.0), rod(1.0));
+        let (ba, bb) = (Aabb3::from_points(a.clone()).unwrap(), Aabb3::from_points(b.clone()).unwrap());
+        assert!(ba.contains(Point3(5.0, 5.0, 0.0)) && bb.contains(Point3(5.0, 5.0, 0.0)));
+        let (da, db) = (Dop18::from_points(a.clone()).unwrap(), Dop18::from_points(b.clone()).unwrap());
+        assert!(!da.overlaps(&db));
+        assert!(da.overlaps(&db.inflated(2f64.sqrt() + 1e-9)));
+        assert!(Dop26::from_points(a.clone()).unwrap().overlaps(&Dop26::from_points(a).unwrap()));
+        assert!(!Dop26::from_points(b.clone()).unwrap().overlaps(&Dop26::from_points(rod(-1.0)).unwrap()));
+        assert!(!Dop14::from_points(b).unwrap().overlaps(&Dop14::from_points(rod(-1.0)).unwrap()));
+    }
+
+    #[test]
+    fn hull_of_a_mesh() {
+        let cube = TriMesh::new(
+            (0..8).map(|i| Point3((i & 1) as f64, (i >> 1 & 1) as f64, (i >> 2) as f64)).collect(),
+            Vec::new(),
+        );
+        let d = Dop8::from_mesh(&cube).unwrap();
+        assert_eq!((d.min()[0], d.max()[0]), (0.0, 3.0));
+        assert!(d.contains(Point3(0.5, 0.5, 0.5)) && d.contains(Point3(1.0, 1.0, 1.0)));
+        // the 8-DOP cuts the cube's corners off the octahedron around it
+        assert!(!d.contains(Point3(1.5, 1.5, 0.5)));
+        let d = Dop14::from_mesh(&cube).unwrap().expand(Point3(2.0, 0.0, 0.0));
+        assert_eq!((d.min()[0], d.max()[0]), (0.0, 2.0));
+        assert!(!d.contains(Point3(2.0, 1.0, 1.0)));
+        assert_eq!(d.interval(0), Some(Interval::new(0.0, 2.0)));
+        let nan = Dop14::from_points([Point3(f64::NAN, 0.0, 0.0)]).unwrap();
+        assert_eq!(nan.interval(0), None);
+        assert!(!nan.contains(Point3(0.0, 0.0, 0.0)) && !nan.overlaps(&d));
+        assert!(Dop26::from_mesh(&TriMesh::<f64>::new(Vec::new(), Vec::new())).is_none());
+        assert_eq!(Dop18::<f64>::axes().len(), 9);
+    }
+}
@@ -0,0 +1,150 @@
+//! Nearest neighbours among fixed points
+//!
+//! A balanced kd-tree kept implicitly in one array: each range is split at
+//! its middle by the median along the axis of its depth, so building is a
+//! selection per level and a query descends the near side first, crossing
+//! to the far one only while the splitting plane is closer than the worst
+//! neighbour found.
+
+use std::cmp::Ordering;
+
+use crate::{ Axis2, Axis3, Float, Point2, Point3 };
+
+/// Points split one coordinate at a time
+pub(crate) trait KdPoint: Copy {
+    type Scalar: Float;
+    type Axis: Copy;
+    /// axis of the splitting planes at a depth
+    fn axis(depth: usize) -> Self::Axis;
+    fn at(self, axis: Self::Axis) -> Self::Scalar;
+    fn squared_distance(self, p: Self) -> Self::Scalar;
+}
+
+impl<T: Float> KdPoint for Point2<T> {
+    type Scalar = T;
+    type Axis = Axis2;
+
+    fn axis(depth: usize) -> Axis2 {
+        Axis2::ALL[depth % 2]
+    }
+
+    fn at(self, axis: Axis2) -> T {
+        self[axis]
+    }
+
+    fn squared_distance(self, p: Self) -> T {
+        p.vector_from(self).dot(p.vector_from(self))
+    }
+}
+
+impl<T: Float> KdPoint for Point3<T> {
+    type Scalar = T;
+    type Axis = Axis3;
+
+    fn axis(depth: usize) -> Axis3 {
+        Axis3::ALL[depth % 3]
+    }
+
+    fn at(self, axis: Axis3) -> T {
+        self[axis]
+    }
+
+    fn squared_distance(self, p: Self) -> T {
+        p.vector_from(self).dot(p.vector_from(self))
+    }
+}
+
+/// Points in kd-tree order, with where each came from
+#[derive(Debug, Clone)]
+pub(crate) struct KdTree<P> {
+    points: Vec<P>,
+    indices: Vec<usize>,
+}
+
+impl<P: KdPoint> KdTree<P> {
+    pub(crate) fn new(points: &[P]) -> Self {
+        let mut indices: Vec<usize> = (0..points.len()).collect();
+        split(points, &mut indices, 0);
+        KdTree { points: indices.iter().map(|&i| points[i]).collect(), indices }
+    }
+
+    /// Index of a point closest to p, None if there are none
+    pub(crate) fn nearest(&self, p: P) -> Option<usize> {
+        let mut found = Vec::with_capacity(1);
+        self.k_nearest(p, 1, &mut found);
+        found.first().map(|&(_, i)| i)
+    }
+
+    /// The k points closest to p into `found`, nearest first, as squared
+    /// distance and index; p itself among them if it is one of the points
+    pub(crate) fn k_nearest(&self, p: P, k: usize, found: &mut Vec<(P::Scalar, usize)>) {
+        found.clear();
+        if k > 0 {
+            self.search(p, k, 0, self.points.len(), 0, found);
+        }
+    }
+
+    fn search(&self, p: P, k: usize, lo: usize, hi: usize, depth: usize, found: &mut Vec<(P::Scalar, usize)>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let q = self.points[mid];
+        let d = p.squared_distance(q);
+        if found.len() < k || d < found[found.len() - 1].0 {
+            let at = found.partition_point(|&(e, _)| e <= d);
+            found.insert(at, (d, self.indices[mid]));
+            found.truncate(k);
+        }
+        let axis = P::axis(depth);
+        let gap = p.at(axis) - q.at(axis);
+        let (near, far) = if gap < P::Scalar::zero() { ((lo, mid), (mid + 1, hi)) } else { ((mid + 1, hi), (lo, mid)) };
+        self.search(p, k, near.0, near.1, depth + 1, found);
+        if found.len() < k || gap * gap < found[found.len() - 1].0 {
+            self.search(p, k, far.0, far.1, depth + 1, found);
+        }
+    }
+}
+
+/// Orders `indices` so that each range has its median along the axis of
+/// its depth in the middle, smaller ones before and larger after
+fn split<P: KdPoint>(points: &[P], indices: &mut [usize], depth: usize) {
+    if indices.len() < 2 {
+        return;
+    }
+    let mid = indices.len() / 2;
+    let axis = P::axis(depth);
+    indices.select_nth_unstable_by(mid, |&i, &j| points[i].at(axis).partial_cmp(&points[j].at(axis)).unwrap_or(Ordering::Equal));
+    let (left, right) = indices.split_at_mut(mid);
+    split(points, left, depth + 1);
+    split(points, &mut right[1..], depth + 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_brute_force() {
+        let mut seed = 5u64;
+        let mut next = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((seed >> 40) % 50) as f64 / 5.0
+        };
+        let points: Vec<_> = (0..300).map(|_| Point3(next(), next(), next())).collect();
+        let tree = KdTree::new(&points);
+        let mut found = Vec::new();
+        for _ in 0..50 {
+            let p = Point3(next(), next(), next());
+            let mut all: Vec<f64> = points.iter().map(|&q| p.squared_distance(q)).collect();
+            all.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            tree.k_nearest(p, 7, &mut found);
+            assert_eq!(found.iter().map(|&(d, _)| d).collect::<Vec<_>>(), all[..7]);
+            assert!(found.iter().all(|&(d, i)| p.squared_distance(points[i]) == d));
+            assert_eq!(tree.nearest(p).map(|i| p.squared_distance(points[i])), Some(all[0]));
+        }
+        let flat = KdTree::new(&[Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(1.0, 0.0)]);
+        assert_eq!(flat.nearest(Point2(0.9, 0.2)).map(|i| i > 0), Some(true));
+        assert_eq!(KdTree::<Point2<f64>>::new(&[]).nearest(Point2(0.0, 0.0)), None);
+    }
+}
@@ -5,14 +5,231 @@
 use std::cmp::{ PartialEq };
 use std::ops::{ Add, Sub, Neg, Mul, Div, AddAssign, SubAssign, MulAssign, DivAssign };
 
+mod float;
+pub mod dual;
+pub mod interval;
+pub mod ga;
+pub mod plucker;
+pub mod isometry;
+pub mod polygon;
+pub mod multi;
+pub mod mesh;
+pub mod polyline;
+pub mod sweep;
+pub mod curve;
+pub mod stroke;
+pub mod bounds;
+pub mod closest;
+pub mod path;
+pub mod measure;
+pub mod cloud;
+pub mod line;
+pub mod plane;
+pub mod unit;
+pub mod affine;
+pub mod tetra;
+pub mod delaunay;
+pub mod scatter;
+pub mod arrangement;
+pub mod similarity;
+pub mod pack;
+pub mod simplify;
+pub mod debug;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "half")]
+pub mod half;
+pub mod circle;
+pub mod ray;
+pub mod sdf;
+pub mod solid;
+pub mod tessellate;
+pub mod contact;
+pub mod scene;
+pub mod hittest;
+pub mod grid;
+pub mod axis;
+pub mod broadphase;
+pub mod coherence;
+pub mod packed;
+pub mod anchor;
+pub mod morph;
+pub mod shadow;
+pub mod snap;
+pub mod coverage;
+pub mod conic;
+pub mod biarc;
+pub mod pose;
+pub mod uncertain;
+pub mod motion;
+pub mod conventions;
+pub mod euler;
+pub mod axisangle;
+pub mod cleanup;
+pub mod strip;
+pub mod lod;
+pub mod join;
+pub mod change;
+pub mod codec;
+pub mod stream;
+pub mod view;
+pub mod stats;
+pub mod budget;
+pub mod predicates;
+pub mod radial;
+pub mod kdop;
+pub mod containment;
+pub mod sketch;
+pub mod fingerprint;
+pub mod pathops;
+pub mod atlas;
+pub mod progressive;
+pub mod segmentation;
+pub mod interior;
+pub mod projection;
+mod earcut;
+mod kdtree;
+mod rtree;
+
+pub use float::Float;
+pub use dual::Dual;
+pub use interval::{ Interval, Certainty };
+pub use ga::{ Bivector2, Bivector3, Rotor2, Rotor3 };
+pub use plucker::PluckerLine;
+pub use isometry::{ Isometry3, Twist3 };
+pub use polygon::{ Polygon2, FillRule, Defect, Triangulation };
+pub use multi::{ MultiPoint2, MultiPolyline2, MultiPolygon2, Geometry2, GeometryCollection2 };
+pub use mesh::{ TriMesh, Triangle3, UvProjection };
+pub use polyline::{ Polyline2, Polyline3 };
+pub use curve::{ Curve2, QuadBezier2, CubicBezier2, Arc2, CatmullRom2, CubicBezier3 };
+pub use stroke::{ JoinStyle, CapStyle };
+pub use bounds::{ Rect2, Aabb3, Bounded2, Bounded3 };
+pub use closest::{ Closest2, Closest3 };
+pub use path::{ Path2, PathCommand, PathHit2 };
+pub use measure::{ Measure, PathMeasure };
+pub use cloud::PointCloud3;
+pub use line::{ Line2, Segment2 };
+pub use plane::Plane3;
+pub use unit::Unit;
+pub use affine::{ Affine2, Affine3, Decomposition2, Decomposition3 };
+pub use tetra::Tetrahedron;
+pub use scatter::{ Scattered2, Interpolation };
+pub use arrangement::{ Arrangement2, HalfEdge, Face };
+pub use pack::{ Packing, Placement };
+pub use circle::{ Circle2, Contact };
+pub use ray::{ Ray3, Hit, Raycast3 };
+pub use sdf::{ Sdf3, March };
+pub use solid::{ Sphere, Cone, Cylinder, Torus, Support3 };
+pub use tessellate::Tessellate3;
+pub use contact::{ Manifold2, ManifoldPoint2 };
+pub use scene::{ Scene3, Shape3 };
+pub use hittest::Pick2;
+pub use grid::{ GridWalk2, GridWalk3, GridCell2, GridCell3 };
+pub use axis::{ Axis2, Axis3 };
+pub use broadphase::{ AabbTree3, Proxy };
+pub use coherence::{ GjkCache3, SatCache2 };
+pub use anchor::Anchored2;
+pub use morph::Morph2;
+pub use snap::Grid2;
+pub use coverage::Coverage2;
+pub use conic::{ Conic2, Ellipse2, ConicFit2, EllipseFit2 };
+pub use biarc::ArcSpan2;
+pub use pose::Pose2;
+pub use uncertain::{ UncertainPoint2, UncertainPoint3 };
+pub use motion::{ Motion2, Swept2 };
+pub use conventions::{ Conventions, Handedness, UpAxis };
+pub use euler::{ EulerAngles, EulerOrder };
+pub use axisangle::{ AxisAngle3, RotationVector3 };
+pub use lod::MeshLods;
+pub use join::SpatialPredicate;
+pub use change::RegionDiff;
+pub use codec::{ Binary, Compression };
+pub use stream::{ MeshItem, ObjReader, PlyReader, WkbReader };
+pub use view::{ IndexFormat, MeshView, PointCloudView, ScalarFormat, VertexLayout };
+#[cfg(feature = "stats")]
+pub use stats::{ Counters, Stats };
+pub use budget::{ Budget, CancelToken };
+pub use kdop::{ Kdop3, Dop8, Dop14, Dop18, Dop26 };
+pub use containment::{ Classify2, Containment, ContainmentGrid2 };
+pub use sketch::{ Constraint2, Sketch2 };
+pub use fingerprint::{ Fingerprint, HashMode };
+pub use pathops::BooleanOp;
+pub use atlas::{ SdfAtlas2, SdfTile };
+pub use progressive::{ ProgressiveMesh, VertexSplit };
+pub use segmentation::PlaneSegment;
+pub use projection::{ Projection2, Projection3 };
+
+/// Reflexivity check: false only for NaN-like values
+#[cfg(feature = "nan-checks")]
+#[allow(clippy::eq_op)]
+fn is_reflexive<T: PartialEq>(x: &T) -> bool {
+    x == x
+}
+
 /// 2d Vector
 #[derive(Debug, Copy, Clone)]
 pub struct Vector2<T>(T, T);
 
+impl<T: PartialEq> Vector2<T> {
+    /// new: K^2 -> Vector
+    /// under `nan-checks`, traps NaN components in debug builds
+    pub fn new(x: T, y: T) -> Self {
+        #[cfg(feature = "nan-checks")]
+        debug_assert!(is_reflexive(&x) && is_reflexive(&y), "NaN component in Vector2");
+        Vector2(x, y)
+    }
+}
+
+impl<T: Float> Vector2<T> {
+    /// new_finite: K^2 -> Option<Vector>
+    /// None if any component is NaN or infinite
+    pub fn new_finite(x: T, y: T) -> Option<Self> {
+        if x.is_finite() && y.is_finite() {
+            Some(Vector2(x, y))
+        } else {
+            None
+        }
+    }
+
+    /// is_finite: &Vector -> bool
+    pub fn is_finite(&self) -> bool {
+        self.0.is_finite() && self.1.is_finite()
+    }
+}
+
 /// 2d Point
 #[derive(Debug, Copy, Clone)]
 pub struct Point2<T>(T, T);
 
+impl<T: PartialEq> Point2<T> {
+    /// new: K^2 -> Point
+    /// under `nan-checks`, traps NaN components in debug builds
+    pub fn new(x: T, y: T) -> Self {
+        #[cfg(feature = "nan-checks")]
+        debug_assert!(is_reflexive(&x) && is_reflexive(&y), "NaN component in Point2");
+        Point2(x, y)
+    }
+}
+
+impl<T: Float> Point2<T> {
+    /// new_finite: K^2 -> Option<Point>
+    /// None if any component is NaN or infinite
+    pub fn new_finite(x: T, y: T) -> Option<Self> {
+        if x.is_finite() && y.is_finite() {
+            Some(Point2(x, y))
+        } else {
+            None
+        }
+    }
+
+    /// is_finite: &Point -> bool
+    pub fn is_finite(&self) -> bool {
+        self.0.is_finite() && self.1.is_finite()
+    }
+}
+
 /// (==): &Vector × &Vector -> bool
 /// (!=): &Vector × &Vector -> bool
 impl<T: PartialEq> PartialEq for Vector2<T> {
@@ -108,6 +325,75 @@ impl<T: Add<Output=T> + Mul<Output=T>> Vector2<T> {
     }
 }
 
+impl<T: Float> Vector2<T> {
+    /// length: Vector -> K
+    pub fn length(self) -> T {
+        self.dot(self).sqrt()
+    }
+
+    /// angle: Vector -> K
+    /// direction from the x axis, in (-π, π]
+    pub fn angle(self) -> T {
+        self.1.atan2(self.0)
+    }
+
+    /// angle_between: Vector × Vector -> K
+    /// unsigned, in [0, π]
+    pub fn angle_between(self, v: Vector2<T>) -> T {
+        self.signed_angle_to(v).abs()
+    }
+
+    /// signed_angle_to: Vector × Vector -> K
+    /// counter-clockwise rotation taking self to the direction of v, in (-π, π]
+    pub fn signed_angle_to(self, v: Vector2<T>) -> T {
+        (self.0 * v.1 - self.1 * v.0).atan2(self.dot(v))
+    }
+
+    /// rotated_by: Vector × K -> Vector
+    /// counter-clockwise
+    pub fn rotated_by(self, angle: T) -> Vector2<T> {
+        let (s, c) = (angle.sin(), angle.cos());
+        Vector2(self.0 * c - self.1 * s, self.0 * s + self.1 * c)
+    }
+
+    /// reflect: Vector × Vector -> Vector
+    /// mirrored in the surface with the given normal, which need not be unit
+    pub fn reflect(self, normal: Vector2<T>) -> Vector2<T> {
+        self - normal * (T::from_f64(2.0) * self.dot(normal) / normal.dot(normal))
+    }
+
+    /// refract: Vector × Vector × K -> Option<Vector>
+    /// unit direction through a surface with unit normal facing against it, where
+    /// eta is the ratio of refractive indices (from / to); None on total internal reflection
+    pub fn refract(self, normal: Vector2<T>, eta: T) -> Option<Vector2<T>> {
+        let c = self.dot(normal);
+        let k = T::one() - eta * eta * (T::one() - c * c);
+        if k < T::zero() {
+            None
+        } else {
+            Some(self * eta - normal * (eta * c + k.sqrt()))
+        }
+    }
+
+    /// project_onto: Vector × Vector -> Vector
+    /// component along v; NaN for a zero v
+    pub fn project_onto(self, v: Vector2<T>) -> Vector2<T> {
+        v * (self.dot(v) / v.dot(v))
+    }
+
+    /// reject_from: Vector × Vector -> Vector
+    /// component perpendicular to v
+    pub fn reject_from(self, v: Vector2<T>) -> Vector2<T> {
+        self - self.project_onto(v)
+    }
+
+    /// project_onto_unit: Vector × Unit -> Vector
+    pub fn project_onto_unit(self, u: Unit<Vector2<T>>) -> Vector2<T> {
+        let u = u.get();
+        u * self.dot(u)
+    }
+}
+
 /// (==): &Point × &Point -> bool
 /// (!=): &Point × &Point -> bool
 impl<T: PartialEq> PartialEq for Point2<T> {
@@ -160,14 +446,82 @@ impl<T: Sub<Output=T>> Sub for Point2<T> {
     }
 }
 
+impl<T: Sub<Output=T>> Point2<T> {
+    /// Vector from p to self
+    pub(crate) fn vector_from(self, p: Point2<T>) -> Vector2<T> {
+        Vector2(self.0 - p.0, self.1 - p.1)
+    }
+}
+
+impl<T: Float> Point2<T> {
+    /// distance: Point × Point -> K
+    pub fn distance(self, p: Point2<T>) -> T {
+        self.vector_from(p).length()
+    }
+}
+
 /// 3d Vector
 #[derive(Debug, Copy, Clone)]
 pub struct Vector3<T>(T, T, T);
 
+impl<T: PartialEq> Vector3<T> {
+    /// new: K^3 -> Vector
+    /// under `nan-checks`, traps NaN components in debug builds
+    pub fn new(x: T, y: T, z: T) -> Self {
+        #[cfg(feature = "nan-checks")]
+        debug_assert!(is_reflexive(&x) && is_reflexive(&y) && is_reflexive(&z), "NaN component in Vector3");
+        Vector3(x, y, z)
+    }
+}
+
+impl<T: Float> Vector3<T> {
+    /// new_finite: K^3 -> Option<Vector>
+    /// None if any component is NaN or infinite
+    pub fn new_finite(x: T, y: T, z: T) -> Option<Self> {
+        if x.is_finite() && y.is_finite() && z.is_finite() {
+            Some(Vector3(x, y, z))
+        } else {
+            None
+        }
+    }
+
+    /// is_finite: &Vector -> bool
+    pub fn is_finite(&self) -> bool {
+        self.0.is_finite() && self.1.is_finite() && self.2.is_finite()
+    }
+}
+
 /// 3d Point
 #[derive(Debug, Copy, Clone)]
 pub struct Point3<T>(T, T, T);
 
+impl<T: PartialEq> Point3<T> {
+    /// new: K^3 -> Point
+    /// under `nan-checks`, traps NaN components in debug builds
+    pub fn new(x: T, y: T, z: T) -> Self {
+        #[cfg(feature = "nan-checks")]
+        debug_assert!(is_reflexive(&x) && is_reflexive(&y) && is_reflexive(&z), "NaN component in Point3");
+        Point3(x, y, z)
+    }
+}
+
+impl<T: Float> Point3<T> {
+    /// new_finite: K^3 -> Option<Point>
+    /// None if any component is NaN or infinite
+    pub fn new_finite(x: T, y: T, z: T) -> Option<Self> {
+        if x.is_finite() && y.is_finite() && z.is_finite() {
+            Some(Point3(x, y, z))
+        } else {
+            None
+        }
+    }
+
+    /// is_finite: &Point -> bool
+    pub fn is_finite(&self) -> bool {
+        self.0.is_finite() && self.1.is_finite() && self.2.is_finite()
+    }
+}
+
 /// (==): &Vector × &Vector -> bool
 /// (!=): &Vector × &Vector -> bool
 impl<T: PartialEq> PartialEq for Vector3<T> {
@@ -263,6 +617,67 @@ impl<T: Add<Output=T> + Mul<Output=T>> Vector3<T> {
     }
 }
 
+impl<T: Sub<Output=T> + Mul<Output=T> + Copy> Vector3<T> {
+    /// cross: Vector × Vector -> Vector
+    pub fn cross(self, v: Vector3<T>) -> Vector3<T> {
+        Vector3(
+            self.1 * v.2 - self.2 * v.1,
+            self.2 * v.0 - self.0 * v.2,
+            self.0 * v.1 - self.1 * v.0,
+        )
+    }
+}
+
+impl<T: Float> Vector3<T> {
+    /// length: Vector -> K
+    pub fn length(self) -> T {
+        self.dot(self).sqrt()
+    }
+
+    /// angle_between: Vector × Vector -> K
+    /// unsigned, in [0, π]; atan2 of |u × v| and u · v stays accurate near 0 and π
+    pub fn angle_between(self, v: Vector3<T>) -> T {
+        self.cross(v).length().atan2(self.dot(v))
+    }
+
+    /// reflect: Vector × Vector -> Vector
+    /// mirrored in the surface with the given normal, which need not be unit
+    pub fn reflect(self, normal: Vector3<T>) -> Vector3<T> {
+        self - normal * (T::from_f64(2.0) * self.dot(normal) / normal.dot(normal))
+    }
+
+    /// refract: Vector × Vector × K -> Option<Vector>
+    /// unit direction through a surface with unit normal facing against it, where
+    /// eta is the ratio of refractive indices (from / to); None on total internal reflection
+    pub fn refract(self, normal: Vector3<T>, eta: T) -> Option<Vector3<T>> {
+        let c = self.dot(normal);
+        let k = T::one() - eta * eta * (T::one() - c * c);
+        if k < T::zero() {
+            None
+        } else {
+            Some(self * eta - normal * (eta * c + k.sqrt()))
+        }
+    }
+
+    /// project_onto: Vector × Vector -> Vector
+    /// component along v; NaN for a zero v
+    pub fn project_onto(self, v: Vector3<T>) -> Vector3<T> {
+        v * (self.dot(v) / v.dot(v))
+    }
+
+    /// reject_from: Vector × Vector -> Vector
+    /// component perpendicular to v
+    pub fn reject_from(self, v: Vector3<T>) -> Vector3<T> {
+        self - self.project_onto(v)
+    }
+
+    /// project_onto_unit: Vector × Unit -> Vector
+    pub fn project_onto_unit(self, u: Unit<Vector3<T>>) -> Vector3<T> {
+        let u = u.get();
+        u * self.dot(u)
+    }
+}
+
 /// (==): &Point × &Point -> bool
 /// (!=): &Point × &Point -> bool
 impl<T: PartialEq> PartialEq for Point3<T> {
@@ -315,10 +730,200 @@ impl<T: Sub<Output=T>> Sub for Point3<T> {
     }
 }
 
+impl<T: Sub<Output=T>> Point3<T> {
+    /// Vector from p to self
+    pub(crate) fn vector_from(self, p: Point3<T>) -> Vector3<T> {
+        Vector3(self.0 - p.0, self.1 - p.1, self.2 - p.2)
+    }
+}
+
+impl<T: Float> Point3<T> {
+    /// distance: Point × Point -> K
+    pub fn distance(self, p: Point3<T>) -> T {
+        self.vector_from(p).length()
+    }
+}
+
+macro_rules! impl_components {
+    ($name:ident, $what:literal, $array:ty, $tuple:ty; $($f:ident => $i:tt),+) => {
+        impl<T: Copy> $name<T> {
+            $(
+                #[doc = concat!(stringify!($f), ": &", $what, " -> K")]
+                pub fn $f(&self) -> T {
+                    self.$i
+                }
+            )+
+        }
+
+        #[doc = concat!("from: ", $what, " -> [K]")]
+        impl<T> From<$name<T>> for $array {
+            fn from(v: $name<T>) -> Self {
+                [$(v.$i),+]
+            }
+        }
+
+        #[doc = concat!("from: ", $what, " -> (K, ..)")]
+        impl<T> From<$name<T>> for $tuple {
+            fn from(v: $name<T>) -> Self {
+                ($(v.$i),+)
+            }
+        }
+
+        #[doc = concat!("from: [K] -> ", $what, ", through `new`")]
+        impl<T: PartialEq> From<$array> for $name<T> {
+            fn from([$($f),+]: $array) -> Self {
+                $name::new($($f),+)
+            }
+        }
+
+        #[doc = concat!("from: (K, ..) -> ", $what, ", through `new`")]
+        impl<T: PartialEq> From<$tuple> for $name<T> {
+            fn from(($($f),+): $tuple) -> Self {
+                $name::new($($f),+)
+            }
+        }
+    };
+}
+
+// Component accessors and conversions to and from arrays and tuples
+impl_components!(Vector2, "Vector", [T; 2], (T, T); x => 0, y => 1);
+impl_components!(Point2, "Point", [T; 2], (T, T); x => 0, y => 1);
+impl_components!(Vector3, "Vector", [T; 3], (T, T, T); x => 0, y => 1, z => 2);
+impl_components!(Point3, "Point", [T; 3], (T, T, T); x => 0, y => 1, z => 2);
+
+macro_rules! impl_int_arith {
+    ($V:ident, $P:ident, $is:tt; $($t:ty),+) => {
+        $(impl_int_arith!(@impl $V, $P, $is, $t);)+
+    };
+    (@impl $V:ident, $P:ident, [$($i:tt),+], $t:ty) => {
+        impl $V<$t> {
+            /// checked_add: Vector × Vector -> Option<Vector>
+            pub fn checked_add(self, v: $V<$t>) -> Option<$V<$t>> {
+                Some($V($(self.$i.checked_add(v.$i)?),+))
+            }
+
+            /// checked_sub: Vector × Vector -> Option<Vector>
+            pub fn checked_sub(self, v: $V<$t>) -> Option<$V<$t>> {
+                Some($V($(self.$i.checked_sub(v.$i)?),+))
+            }
+
+            /// checked_mul: Vector × K -> Option<Vector>
+            pub fn checked_mul(self, k: $t) -> Option<$V<$t>> {
+                Some($V($(self.$i.checked_mul(k)?),+))
+            }
+
+            /// wrapping_add: Vector × Vector -> Vector
+            pub fn wrapping_add(self, v: $V<$t>) -> $V<$t> {
+                $V($(self.$i.wrapping_add(v.$i)),+)
+            }
+
+            /// wrapping_sub: Vector × Vector -> Vector
+            pub fn wrapping_sub(self, v: $V<$t>) -> $V<$t> {
+                $V($(self.$i.wrapping_sub(v.$i)),+)
+            }
+
+            /// wrapping_mul: Vector × K -> Vector
+            pub fn wrapping_mul(self, k: $t) -> $V<$t> {
+                $V($(self.$i.wrapping_mul(k)),+)
+            }
+
+            /// saturating_add: Vector × Vector -> Vector
+            pub fn saturating_add(self, v: $V<$t>) -> $V<$t> {
+                $V($(self.$i.saturating_add(v.$i)),+)
+            }
+
+            /// saturating_sub: Vector × Vector -> Vector
+            pub fn saturating_sub(self, v: $V<$t>) -> $V<$t> {
+                $V($(self.$i.saturating_sub(v.$i)),+)
+            }
+
+            /// saturating_mul: Vector × K -> Vector
+            pub fn saturating_mul(self, k: $t) -> $V<$t> {
+                $V($(self.$i.saturating_mul(k)),+)
+            }
+        }
+
+        impl $P<$t> {
+            /// checked_add: Point × Vector -> Option<Point>
+            pub fn checked_add(self, v: $V<$t>) -> Option<$P<$t>> {
+                Some($P($(self.$i.checked_add(v.$i)?),+))
+            }
+
+            /// checked_sub: Point × Vector -> Option<Point>
+            pub fn checked_sub(self, v: $V<$t>) -> Option<$P<$t>> {
+                Some($P($(self.$i.checked_sub(v.$i)?),+))
+            }
+
+            /// wrapping_add: Point × Vector -> Point
+            pub fn wrapping_add(self, v: $V<$t>) -> $P<$t> {
+                $P($(self.$i.wrapping_add(v.$i)),+)
+            }
+
+            /// wrapping_sub: Point × Vector -> Point
+            pub fn wrapping_sub(self, v: $V<$t>) -> $P<$t> {
+                $P($(self.$i.wrapping_sub(v.$i)),+)
+            }
+
+            /// saturating_add: Point × Vector -> Point
+            pub fn saturating_add(self, v: $V<$t>) -> $P<$t> {
+                $P($(self.$i.saturating_add(v.$i)),+)
+            }
+
+            /// saturating_sub: Point × Vector -> Point
+            pub fn saturating_sub(self, v: $V<$t>) -> $P<$t> {
+                $P($(self.$i.saturating_sub(v.$i)),+)
+            }
+        }
+    };
+}
+
+// Overflow-aware arithmetic for integer vectors and points
+impl_int_arith!(Vector2, Point2, [0, 1]; i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_int_arith!(Vector3, Point3, [0, 1, 2]; i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn new_finite() {
+        assert_eq!(Vector2::new(1.0, 2.0), Vector2(1.0, 2.0));
+        assert_eq!(Vector2::new_finite(1.0, 2.0), Some(Vector2(1.0, 2.0)));
+        assert_eq!(Vector2::new_finite(f64::NAN, 2.0), None);
+        assert_eq!(Point2::new_finite(1.0, f32::INFINITY), None);
+        assert_eq!(Vector3::new_finite(1.0, 2.0, f64::NEG_INFINITY), None);
+        assert_eq!(Point3::new_finite(1.0, 2.0, 3.0), Some(Point3(1.0, 2.0, 3.0)));
+    }
+
+    #[test]
+    fn components() {
+        let p = Point2::new(1.0, 2.0);
+        assert_eq!((p.x(), p.y()), (1.0, 2.0));
+        assert_eq!(<[f64; 2]>::from(p), [1.0, 2.0]);
+        assert_eq!(Point2::from((1.0, 2.0)), p);
+        let v = Vector3::from([1, 2, 3]);
+        assert_eq!((v.x(), v.y(), v.z()), (1, 2, 3));
+        assert_eq!(<(i32, i32, i32)>::from(v), (1, 2, 3));
+        assert_eq!(<[f32; 2]>::from(Vector2(0.5, 1.5)), [0.5, 1.5]);
+        assert_eq!(Point3::from([0, 1, 2]).z(), 2);
+    }
+
+    #[test]
+    fn is_finite() {
+        assert!(Vector2(1.0, 0.0).is_finite());
+        assert!(!(Vector2(1.0, 0.0) / 0.0).is_finite());
+        assert!(!Point2(f64::NAN, 0.0).is_finite());
+        assert!(Vector3(0.0f32, 0.0, 0.0).is_finite());
+        assert!(!Point3(0.0, f64::INFINITY, 0.0).is_finite());
+    }
+
+    #[test]
+    #[cfg(all(feature = "nan-checks", debug_assertions))]
+    #[should_panic(expected = "NaN component")]
+    fn nan_checks_trap() {
+        Point3::new(0.0, f64::NAN, 0.0);
+    }
+
     #[test]
     fn eq_vec2() {
         let v = Vector2(1, 0);
@@ -370,6 +975,44 @@ mod tests {
         assert_eq!(v, Vector2(0.5, 0.5));
     }
 
+    #[test]
+    fn length_vec2() {
+        assert_eq!(Vector2(3.0, 4.0).length(), 5.0);
+        assert_eq!(Point2(1.0, 1.0).distance(Point2(4.0, 5.0)), 5.0);
+    }
+
+    #[test]
+    fn angles_vec2() {
+        use std::f64::consts::{ FRAC_PI_2, FRAC_PI_4, PI };
+        assert_eq!(Vector2(0.0, 2.0).angle(), FRAC_PI_2);
+        assert_eq!(Vector2(-1.0, 0.0).angle(), PI);
+        assert_eq!(Vector2(1.0, 0.0).signed_angle_to(Vector2(1.0, -1.0)), -FRAC_PI_4);
+        assert_eq!(Vector2(1.0, 0.0).angle_between(Vector2(1.0, -1.0)), FRAC_PI_4);
+        assert_eq!(Vector2(0.0, 1.0).signed_angle_to(Vector2(-3.0, 0.0)), FRAC_PI_2);
+        let v = Vector2(2.0, 0.0).rotated_by(FRAC_PI_2);
+        assert!((v - Vector2(0.0, 2.0)).length() < 1e-15);
+    }
+
+    #[test]
+    fn reflect_refract_vec2() {
+        assert_eq!(Vector2(1.0, -1.0).reflect(Vector2(0.0, 3.0)), Vector2(1.0, 1.0));
+        let s = 0.5f64.sqrt();
+        let i = Vector2(s, -s);
+        // entering glass at 45°: sin θₜ = sin θᵢ / 1.5
+        let t = i.refract(Vector2(0.0, 1.0), 1.0 / 1.5).unwrap();
+        assert!((t.length() - 1.0).abs() < 1e-15 && (t.0 - s / 1.5).abs() < 1e-15 && t.1 < 0.0);
+        assert_eq!(i.refract(Vector2(0.0, 1.0), 1.0), Some(i));
+        assert_eq!(i.refract(Vector2(0.0, 1.0), 1.5), None);
+    }
+
+    #[test]
+    fn project_vec2() {
+        let (v, w) = (Vector2(3.0, 4.0), Vector2(2.0, 0.0));
+        assert_eq!(v.project_onto(w), Vector2(3.0, 0.0));
+        assert_eq!(v.reject_from(w), Vector2(0.0, 4.0));
+        assert_eq!(v.project_onto_unit(Unit::<Vector2<f64>>::new(w).unwrap()), Vector2(3.0, 0.0));
+    }
+
     #[test]
     fn eq_point2() {
         let a = Point2(1, 0);
@@ -406,6 +1049,31 @@ mod tests {
         assert_eq!(Point2(1, 1) - Point2(2, 2), Point2(-1, -1));
     }
 
+    #[test]
+    fn checked_vec2() {
+        assert_eq!(Vector2(1i8, 2).checked_add(Vector2(3, 4)), Some(Vector2(4, 6)));
+        assert_eq!(Vector2(127i8, 0).checked_add(Vector2(1, 0)), None);
+        assert_eq!(Vector2(0u8, 1).checked_sub(Vector2(1, 0)), None);
+        assert_eq!(Vector2(64i8, 1).checked_mul(2), None);
+    }
+
+    #[test]
+    fn wrapping_saturating_vec2() {
+        assert_eq!(Vector2(127i8, 0).wrapping_add(Vector2(1, 1)), Vector2(-128, 1));
+        assert_eq!(Vector2(0u8, 5).wrapping_sub(Vector2(1, 1)), Vector2(255, 4));
+        assert_eq!(Vector2(127i8, 0).saturating_add(Vector2(1, 1)), Vector2(127, 1));
+        assert_eq!(Vector2(0u8, 5).saturating_sub(Vector2(1, 1)), Vector2(0, 4));
+        assert_eq!(Vector2(100u8, 1).saturating_mul(3), Vector2(255, 3));
+    }
+
+    #[test]
+    fn checked_wrapping_saturating_point2() {
+        assert_eq!(Point2(0u32, 0).checked_sub(Vector2(1, 0)), None);
+        assert_eq!(Point2(1u32, 1).checked_add(Vector2(1, 0)), Some(Point2(2, 1)));
+        assert_eq!(Point2(u32::MAX, 0).wrapping_add(Vector2(1, 0)), Point2(0, 0));
+        assert_eq!(Point2(0u32, 0).saturating_sub(Vector2(1, 1)), Point2(0, 0));
+    }
+
     #[test]
     fn eq_vec3() {
         let v = Vector3(1, 0, -1);
@@ -457,6 +1125,45 @@ mod tests {
         assert_eq!(v, Vector3(0.5, 0.5, 0.5));
     }
 
+    #[test]
+    fn cross_vec3() {
+        assert_eq!(Vector3(1, 0, 0).cross(Vector3(0, 1, 0)), Vector3(0, 0, 1));
+        assert_eq!(Vector3(1, 2, 3).cross(Vector3(4, 5, 6)), Vector3(-3, 6, -3));
+        assert_eq!(Vector3(1, 2, 3).cross(Vector3(1, 2, 3)), Vector3(0, 0, 0));
+    }
+
+    #[test]
+    fn length_vec3() {
+        assert_eq!(Vector3(2.0, 3.0, 6.0).length(), 7.0);
+        assert_eq!(Point3(1.0, 1.0, 1.0).distance(Point3(3.0, 4.0, 7.0)), 7.0);
+    }
+
+    #[test]
+    fn angle_between_vec3() {
+        use std::f64::consts::{ FRAC_PI_2, PI };
+        assert_eq!(Vector3(0.0, 0.0, 3.0).angle_between(Vector3(1.0, 0.0, 0.0)), FRAC_PI_2);
+        assert_eq!(Vector3(1.0, 1.0, 1.0).angle_between(Vector3(-2.0, -2.0, -2.0)), PI);
+        assert_eq!(Vector3(1.0, 1e-9, 0.0).angle_between(Vector3(1.0, 0.0, 0.0)), 1e-9);
+    }
+
+    #[test]
+    fn reflect_refract_vec3() {
+        assert_eq!(Vector3(1.0, 2.0, -3.0).reflect(Vector3(0.0, 0.0, 1.0)), Vector3(1.0, 2.0, 3.0));
+        let n = Vector3(0.0, 0.0, 1.0);
+        assert_eq!(Vector3(0.0, 0.0, -1.0).refract(n, 1.0 / 1.33), Some(Vector3(0.0, 0.0, -1.0)));
+        assert_eq!(Vector3(0.8, 0.0, -0.6).refract(n, 1.5), None);
+    }
+
+    #[test]
+    fn project_vec3() {
+        let (v, w) = (Vector3(1.0, 2.0, 3.0), Vector3(0.0, 0.0, -5.0));
+        assert_eq!(v.project_onto(w), Vector3(0.0, 0.0, 3.0));
+        assert_eq!(v.reject_from(w), Vector3(1.0, 2.0, 0.0));
+        let u = Unit::<Vector3<f64>>::new(Vector3(1.0, 1.0, 0.0)).unwrap();
+        assert!((v.project_onto_unit(u) - Vector3(1.5, 1.5, 0.0)).length() < 1e-15);
+        assert_eq!(v.reject_from(u.get()).dot(u.get()).abs() < 1e-15, true);
+    }
+
     #[test]
     fn eq_point3() {
         let a = Point3(1, 0, -1);
@@ -492,4 +1199,19 @@ mod tests {
     fn sub_point3() {
         assert_eq!(Point3(1, 1, 1) - Point3(2, 2, 2), Point3(-1, -1, -1));
     }
+
+    #[test]
+    fn checked_wrapping_saturating_vec3() {
+        assert_eq!(Vector3(1i16, 2, 3).checked_add(Vector3(1, 1, 1)), Some(Vector3(2, 3, 4)));
+        assert_eq!(Vector3(i16::MIN, 0, 0).checked_sub(Vector3(1, 0, 0)), None);
+        assert_eq!(Vector3(i16::MAX, 0, 0).wrapping_mul(2), Vector3(-2, 0, 0));
+        assert_eq!(Vector3(i16::MAX, 0, -1).saturating_mul(2), Vector3(i16::MAX, 0, -2));
+    }
+
+    #[test]
+    fn checked_wrapping_saturating_point3() {
+        assert_eq!(Point3(usize::MAX, 0, 0).checked_add(Vector3(1, 0, 0)), None);
+        assert_eq!(Point3(0usize, 0, 0).wrapping_sub(Vector3(1, 0, 0)), Point3(usize::MAX, 0, 0));
+        assert_eq!(Point3(usize::MAX, 0, 0).saturating_add(Vector3(1, 2, 3)), Point3(usize::MAX, 2, 3));
+    }
 }
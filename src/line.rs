@@ -0,0 +1,170 @@
+//! Lines and segments
+
+use crate::{ Closest2, Float, Point2, Vector2 };
+use crate::anchor::impl_anchored;
+
+/// 2d Line: a point on it and its direction
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Line2<T>(Point2<T>, Vector2<T>);
+
+impl<T> Line2<T> {
+    /// new: Point × Vector -> Line
+    pub fn new(point: Point2<T>, direction: Vector2<T>) -> Self {
+        Line2(point, direction)
+    }
+}
+
+impl<T: Copy> Line2<T> {
+    /// point: &Line -> Point
+    pub fn point(&self) -> Point2<T> {
+        self.0
+    }
+
+    /// direction: &Line -> Vector
+    pub fn direction(&self) -> Vector2<T> {
+        self.1
+    }
+}
+
+impl<T: Float> Line2<T> {
+    /// through: Point × Point -> Line
+    /// directed from a to b
+    pub fn through(a: Point2<T>, b: Point2<T>) -> Self {
+        Line2(a, b.vector_from(a))
+    }
+
+    /// project: &Line × Point -> Point
+    /// closest point of the line
+    pub fn project(&self, p: Point2<T>) -> Point2<T> {
+        let d = self.1;
+        self.0 + d * (p.vector_from(self.0).dot(d) / d.dot(d))
+    }
+
+    /// signed_distance: &Line × Point -> K
+    /// positive to the left of the direction
+    pub fn signed_distance(&self, p: Point2<T>) -> T {
+        let (d, v) = (self.1, p.vector_from(self.0));
+        (d.0 * v.1 - d.1 * v.0) / d.length()
+    }
+
+    /// intersect: &Line × &Line -> Option<Point>
+    /// None for parallel lines
+    pub fn intersect(&self, l: &Line2<T>) -> Option<Point2<T>> {
+        let cross = |a: Vector2<T>, b: Vector2<T>| a.0 * b.1 - a.1 * b.0;
+        let den = cross(self.1, l.1);
+        if den == T::zero() {
+            return None;
+        }
+        Some(self.0 + self.1 * (cross(l.0.vector_from(self.0), l.1) / den))
+    }
+
+    /// offset: &Line × K -> Line
+    /// parallel line `distance` to the left
+    pub fn offset(&self, distance: T) -> Self {
+        let d = self.1;
+        Line2(self.0 + Vector2(-d.1, d.0) * (distance / d.length()), d)
+    }
+}
+
+/// 2d Segment: start, end
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Segment2<T>(Point2<T>, Point2<T>);
+
+impl<T> Segment2<T> {
+    /// new: Point × Point -> Segment
+    pub fn new(start: Point2<T>, end: Point2<T>) -> Self {
+        Segment2(start, end)
+    }
+}
+
+impl<T: Copy> Segment2<T> {
+    /// start: &Segment -> Point
+    pub fn start(&self) -> Point2<T> {
+        self.0
+    }
+
+    /// end: &Segment -> Point
+    pub fn end(&self) -> Point2<T> {
+        self.1
+    }
+}
+
+impl<T: Float> Segment2<T> {
+    /// line: &Segment -> Line
+    pub fn line(&self) -> Line2<T> {
+        Line2::through(self.0, self.1)
+    }
+
+    /// intersection: &Segment × &Segment -> Option<Point>
+    /// the single common point, end points included;
+    /// None if disjoint or parallel, even when overlapping
+    pub fn intersection(&self, s: &Segment2<T>) -> Option<Point2<T>> {
+        let cross = |a: Vector2<T>, b: Vector2<T>| a.0 * b.1 - a.1 * b.0;
+        let (d, e) = (self.1.vector_from(self.0), s.1.vector_from(s.0));
+        let den = cross(d, e);
+        if den == T::zero() {
+            return None;
+        }
+        let w = s.0.vector_from(self.0);
+        let (t, u) = (cross(w, e) / den, cross(w, d) / den);
+        let unit = |x: T| x >= T::zero() && x <= T::one();
+        if unit(t) && unit(u) { Some(self.0 + d * t) } else { None }
+    }
+
+    /// distance: &Segment × Point -> K
+    pub fn distance(&self, p: Point2<T>) -> T {
+        crate::curve::segment_distance(p, self.0, self.1)
+    }
+}
+
+impl_anchored!(Line2, |l, m| Line2(m.point(l.0), m.vector(l.1)));
+impl_anchored!(Segment2, |s, m| Segment2(m.point(s.0), m.point(s.1)));
+
+impl<T: Float> Closest2<T> for Line2<T> {
+    fn closest_point_on(&self, p: Point2<T>) -> Point2<T> {
+        self.project(p)
+    }
+}
+
+impl<T: Float> Closest2<T> for Segment2<T> {
+    fn closest_point_on(&self, p: Point2<T>) -> Point2<T> {
+        crate::curve::segment_closest(p, self.0, self.1)
+    }
+}
+
+impl<T: Float> Point2<T> {
+    /// reflect_across: Point × &Line -> Point
+    pub fn reflect_across(self, line: &Line2<T>) -> Point2<T> {
+        let d = line.1;
+        line.0 + self.vector_from(line.0).reflect(Vector2(-d.1, d.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line2() {
+        let l = Line2::through(Point2(0.0, 1.0), Point2(2.0, 1.0));
+        assert_eq!(l.project(Point2(5.0, -3.0)), Point2(5.0, 1.0));
+        assert_eq!((l.signed_distance(Point2(5.0, -3.0)), l.signed_distance(Point2(0.0, 3.0))), (-4.0, 2.0));
+        assert_eq!(l.offset(2.0).point(), Point2(0.0, 3.0));
+        let m = Line2::new(Point2(3.0, 0.0), Vector2(-1.0, 1.0));
+        assert_eq!(l.intersect(&m), Some(Point2(2.0, 1.0)));
+        assert_eq!(l.intersect(&l.offset(1.0)), None);
+        assert_eq!(Point2(5.0, -3.0).reflect_across(&l), Point2(5.0, 5.0));
+        assert_eq!(Point2(2.0, 0.0).reflect_across(&Line2::new(Point2(0.0, 0.0), Vector2(1.0, 1.0))), Point2(0.0, 2.0));
+    }
+
+    #[test]
+    fn segment2() {
+        let s = Segment2::new(Point2(0.0, 0.0), Point2(4.0, 4.0));
+        assert_eq!(s.intersection(&Segment2::new(Point2(0.0, 4.0), Point2(4.0, 0.0))), Some(Point2(2.0, 2.0)));
+        assert_eq!(s.intersection(&Segment2::new(Point2(4.0, 4.0), Point2(5.0, 0.0))), Some(Point2(4.0, 4.0)));
+        assert_eq!(s.intersection(&Segment2::new(Point2(0.0, 4.0), Point2(1.0, 3.0))), None);
+        assert_eq!(s.intersection(&Segment2::new(Point2(1.0, 1.0), Point2(5.0, 5.0))), None);
+        assert_eq!(s.distance(Point2(0.0, 2.0)), 2.0f64.sqrt());
+        assert_eq!(s.line().direction(), Vector2(4.0, 4.0));
+    }
+}
@@ -0,0 +1,365 @@
+//! Level-of-detail chains by quadric edge collapse
+//!
+//! Each step moves one vertex onto a neighbour (a half-edge collapse),
+//! choosing the move least costly under the summed squared distances to
+//! the planes of the triangles around both ends (Garland and Heckbert).
+//! Boundary edges add steep planes across them, so open borders hold.
+//! Since vertices only ever land on existing ones, every level indexes the
+//! original vertex buffer.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::stats;
+use crate::{ Budget, Float, Point3, TriMesh, Vector3 };
+
+/// Levels of detail over one shared vertex buffer
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshLods<T> {
+    pub vertices: Vec<Point3<T>>,
+    /// triangles of each level, indexing `vertices`
+    pub levels: Vec<Vec<[usize; 3]>>,
+}
+
+impl<T: Float> MeshLods<T> {
+    /// mesh: &Lods × usize -> Mesh
+    /// a level as a mesh of its own over a copy of the shared vertices
+    pub fn mesh(&self, level: usize) -> TriMesh<T> {
+        TriMesh::new(self.vertices.clone(), self.levels[level].clone())
+    }
+}
+
+/// Symmetric 4 × 4 quadric, upper triangle row by row
+#[derive(Debug, Copy, Clone)]
+struct Quadric<T>([T; 10]);
+
+impl<T: Float> Quadric<T> {
+    fn zero() -> Self {
+        Quadric([T::zero(); 10])
+    }
+
+    /// Squared distance to the plane n · x + d = 0, times the weight
+    fn plane(n: Vector3<T>, d: T, weight: T) -> Self {
+        let Vector3(a, b, c) = n;
+        Quadric([a * a, a * b, a * c, a * d, b * b, b * c, b * d, c * c, c * d, d * d].map(|x| x * weight))
+    }
+
+    fn add(self, q: Quadric<T>) -> Self {
+        Quadric(std::array::from_fn(|i| self.0[i] + q.0[i]))
+    }
+
+    fn at(&self, p: Point3<T>) -> T {
+        let [aa, ab, ac, ad, bb, bc, bd, cc, cd, dd] = self.0;
+        let Point3(x, y, z) = p;
+        let two = T::from_f64(2.0);
+        aa * x * x + bb * y * y + cc * z * z + dd
+            + two * (ab * x * y + ac * x * z + ad * x + bc * y * z + bd * y + cd * z)
+    }
+}
+
+/// Candidate move of `from` onto `to`, cheapest first in a max-heap
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Collapse<T> {
+    cost: T,
+    from: usize,
+    to: usize,
+    /// versions of both ends when it was scored
+    stamp: (u32, u32),
+}
+
+impl<T: Float> Eq for Collapse<T> {}
+
+impl<T: Float> PartialOrd for Collapse<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Float> Ord for Collapse<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.partial_cmp(&self.cost).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// One collapse, as a step reports it
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Collapsed {
+    pub(crate) from: usize,
+    pub(crate) to: usize,
+    /// triangles on the edge, left as they were and marked dead
+    pub(crate) removed: Vec<usize>,
+    /// corners (triangle, 0..3) that pointed at `from` and now at `to`
+    pub(crate) moved: Vec<(usize, usize)>,
+}
+
+/// Mesh being collapsed
+pub(crate) struct Decimator<'a, T> {
+    vertices: &'a [Point3<T>],
+    pub(crate) triangles: Vec<[usize; 3]>,
+    pub(crate) alive: Vec<bool>,
+    pub(crate) live: usize,
+    /// live triangles around each vertex
+    around: Vec<Vec<usize>>,
+    quadrics: Vec<Quadric<T>>,
+    version: Vec<u32>,
+    heap: BinaryHeap<Collapse<T>>,
+}
+
+impl<'a, T: Float> Decimator<'a, T> {
+    pub(crate) fn new(vertices: &'a [Point3<T>], triangles: &[[usize; 3]]) -> Self {
+        let n = vertices.len();
+        let mut around = vec![Vec::new(); n];
+        let mut quadrics = vec![Quadric::zero(); n];
+        let mut edges = std::collections::HashMap::new();
+        for (t, &tri) in triangles.iter().enumerate() {
+            let [a, b, c] = tri.map(|i| vertices[i]);
+            let normal = b.vector_from(a).cross(c.vector_from(a));
+            let l = normal.length();
+            for (k, &v) in tri.iter().enumerate() {
+                around[v].push(t);
+                let w = tri[(k + 1) % 3];
+                *edges.entry((v.min(w), v.max(w))).or_insert(0) += 1;
+            }
+            if l > T::zero() {
+                let unit = normal / l;
+                let q = Quadric::plane(unit, -unit.dot(Vector3(a.0, a.1, a.2)), l * T::from_f64(0.5));
+                for v in tri {
+                    quadrics[v] = quadrics[v].add(q);
+                }
+            }
+        }
+        // steep planes along the border, across each boundary edge
+        for &tri in triangles {
+            let [a, b, c] = tri.map(|i| vertices[i]);
+            let normal = b.vector_from(a).cross(c.vector_from(a));
+            for k in 0..3 {
+                let (v, w) = (tri[k], tri[(k + 1) % 3]);
+                if edges[&(v.min(w), v.max(w))] != 1 {
+                    continue;
+                }
+                let e = vertices[w].vector_from(vertices[v]);
+                let across = e.cross(normal);
+                let l = across.length();
+                if l > T::zero() {
+                    let unit = across / l;
+                    let p = vertices[v];
+                    let q = Quadric::plane(unit, -unit.dot(Vector3(p.0, p.1, p.2)), e.dot(e) * T::from_f64(1000.0));
+                    quadrics[v] = quadrics[v].add(q);
+                    quadrics[w] = quadrics[w].add(q);
+                }
+            }
+        }
+        let mut d = Decimator {
+            vertices,
+            triangles: triangles.to_vec(),
+            alive: vec![true; triangles.len()],
+            live: triangles.len(),
+            around,
+            quadrics,
+            version: vec![0; n],
+            heap: BinaryHeap::new(),
+        };
+        for tri in triangles {
+            for k in 0..3 {
+                d.score(tri[k], tri[(k + 1) % 3]);
+                d.score(tri[(k + 1) % 3], tri[k]);
+            }
+        }
+        d
+    }
+
+    fn score(&mut self, from: usize, to: usize) {
+        let cost = self.quadrics[from].add(self.quadrics[to]).at(self.vertices[to]);
+        self.heap.push(Collapse { cost, from, to, stamp: (self.version[from], self.version[to]) });
+    }
+
+    fn neighbours(&self, v: usize) -> Vec<usize> {
+        let mut n: Vec<usize> = self.around[v].iter().flat_map(|&t| self.triangles[t]).filter(|&w| w != v).collect();
+        n.sort_unstable();
+        n.dedup();
+        n
+    }
+
+    /// Whether moving `from` onto `to` keeps the surface manifold and turns
+    /// no triangle over
+    fn allowed(&self, from: usize, to: usize) -> bool {
+        let shared: Vec<usize> = self.around[from].iter().copied().filter(|&t| self.triangles[t].contains(&to)).collect();
+        if shared.is_empty() {
+            return false;
+        }
+        // link condition: the common neighbours are the shared triangles' corners
+        let mut opposite: Vec<usize> = shared.iter().flat_map(|&t| self.triangles[t]).filter(|&w| w != from && w != to).collect();
+        opposite.sort_unstable();
+        opposite.dedup();
+        let to_neighbours = self.neighbours(to);
+        let common = self.neighbours(from).into_iter().filter(|w| to_neighbours.binary_search(w).is_ok()).count();
+        if common != opposite.len() {
+            return false;
+        }
+        let normal = |tri: [usize; 3]| {
+            let [a, b, c] = tri.map(|i| self.vertices[i]);
+            b.vector_from(a).cross(c.vector_from(a))
+        };
+        self.around[from].iter().filter(|t| !shared.contains(t)).all(|&t| {
+            let tri = self.triangles[t];
+            let moved = tri.map(|v| if v == from { to } else { v });
+            normal(moved).dot(normal(tri)) > T::zero()
+        })
+    }
+
+    /// Collapses the cheapest allowed edge; None when none is left
+    pub(crate) fn step(&mut self) -> Option<Collapsed> {
+        while let Some(c) = self.heap.pop() {
+            let (from, to) = (c.from, c.to);
+            if c.stamp != (self.version[from], self.version[to]) || self.around[from].is_empty() || !self.allowed(from, to) {
+                continue;
+            }
+            let mut done = Collapsed { from, to, removed: Vec::new(), moved: Vec::new() };
+            for t in std::mem::take(&mut self.around[from]) {
+                if self.triangles[t].contains(&to) {
+                    done.removed.push(t);
+                    self.alive[t] = false;
+                    self.live -= 1;
+                    for v in self.triangles[t] {
+                        self.around[v].retain(|&s| s != t);
+                    }
+                } else {
+                    for (k, v) in self.triangles[t].iter_mut().enumerate() {
+                        if *v == from {
+                            *v = to;
+                            done.moved.push((t, k));
+                        }
+                    }
+                    self.around[to].push(t);
+                }
+            }
+            self.quadrics[to] = self.quadrics[to].add(self.quadrics[from]);
+            self.version[from] += 1;
+            let ring = self.neighbours(to);
+            self.version[to] += 1;
+            for &w in &ring {
+                self.version[w] += 1;
+            }
+            for &w in &ring {
+                self.score(to, w);
+                self.score(w, to);
+                for x in self.neighbours(w) {
+                    if x != to {
+                        self.score(w, x);
+                        self.score(x, w);
+                    }
+                }
+            }
+            return Some(done);
+        }
+        None
+    }
+
+    fn level(&self) -> Vec<[usize; 3]> {
+        self.triangles.iter().zip(&self.alive).filter(|e| *e.1).map(|e| *e.0).collect()
+    }
+}
+
+impl<T: Float> TriMesh<T> {
+    /// generate_lods: &Mesh × [f32] -> Lods
+    /// one level per ratio, in the given order, with at most that share of
+    /// the triangles (or as few as collapses could reach without folding
+    /// the surface); a ratio of 1 or more keeps the mesh whole. Texture
+    /// coordinates and normals are not carried over
+    pub fn generate_lods(&self, ratios: &[f32]) -> MeshLods<T> {
+        self.generate_lods_within(ratios, &mut Budget::unlimited()).expect("unlimited budget")
+    }
+
+    /// generate_lods_within: &Mesh × [f32] × &mut Budget -> Option<Lods>
+    /// `generate_lods`, a unit of the budget per collapse; None if it runs out
+    pub fn generate_lods_within(&self, ratios: &[f32], budget: &mut Budget) -> Option<MeshLods<T>> {
+        let _timer = stats::time("lod");
+        let total = self.triangles().len();
+        let target = |r: f32| ((r.clamp(0.0, 1.0) as f64) * total as f64).floor() as usize;
+        let mut order: Vec<usize> = (0..ratios.len()).collect();
+        order.sort_by(|&a, &b| ratios[b].partial_cmp(&ratios[a]).unwrap_or(Ordering::Equal));
+        let mut d = Decimator::new(self.vertices(), self.triangles());
+        let mut levels = vec![Vec::new(); ratios.len()];
+        let (mut exhausted, mut collapses) = (false, 0);
+        for i in order {
+            while !exhausted && d.live > target(ratios[i]) {
+                if !budget.tick() {
+                    return None;
+                }
+                exhausted = d.step().is_none();
+                collapses += usize::from(!exhausted);
+            }
+            levels[i] = d.level();
+        }
+        stats::count("lod", "collapses", collapses);
+        stats::count("lod", "levels", ratios.len());
+        Some(MeshLods { vertices: self.vertices().to_vec(), levels })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn sphere(rings: usize, segments: usize) -> TriMesh<f64> {
+        let mut vertices = vec![Point3(0.0, 0.0, 1.0)];
+        for i in 1..rings {
+            let theta = PI * i as f64 / rings as f64;
+            for j in 0..segments {
+                let phi = 2.0 * PI * j as f64 / segments as f64;
+                vertices.push(Point3(theta.sin() * phi.cos(), theta.sin() * phi.sin(), theta.cos()));
+            }
+        }
+        vertices.push(Point3(0.0, 0.0, -1.0));
+        let south = vertices.len() - 1;
+        let at = |i: usize, j: usize| 1 + (i - 1) * segments + j % segments;
+        let mut triangles = Vec::new();
+        for j in 0..segments {
+            triangles.push([0, at(1, j), at(1, j + 1)]);
+            triangles.push([south, at(rings - 1, j + 1), at(rings - 1, j)]);
+            for i in 1..rings - 1 {
+                triangles.push([at(i, j), at(i + 1, j), at(i + 1, j + 1)]);
+                triangles.push([at(i, j), at(i + 1, j + 1), at(i, j + 1)]);
+            }
+        }
+        TriMesh::new(vertices, triangles)
+    }
+
+    #[test]
+    fn sphere_chain() {
+        let mesh = sphere(16, 32);
+        let lods = mesh.generate_lods(&[1.0, 0.5, 0.1, 0.25]);
+        assert_eq!(lods.vertices, mesh.vertices());
+        assert_eq!(lods.levels[0], mesh.triangles());
+        let total = mesh.triangles().len();
+        for (level, ratio) in [(1, 0.5), (3, 0.25), (2, 0.1)] {
+            let t = &lods.levels[level];
+            assert!(t.len() <= (total as f64 * ratio) as usize && t.len() + 2 >= (total as f64 * ratio) as usize);
+            // still closed, and near the sphere's volume
+            let lod = lods.mesh(level);
+            let v = lod.volume();
+            assert!(v > 4.0 / 3.0 * PI * 0.75 && v <= 4.0 / 3.0 * PI, "{level}: {v}");
+        }
+        assert!(lods.levels[2].len() < lods.levels[3].len());
+    }
+
+    #[test]
+    fn flat_sheet_keeps_its_border() {
+        let n = 8;
+        let vertices = (0..=n).flat_map(|j| (0..=n).map(move |i| Point3(i as f64, j as f64, 0.0))).collect();
+        let at = |i: usize, j: usize| j * (n + 1) + i;
+        let triangles = (0..n).flat_map(|j| (0..n).flat_map(move |i| {
+            [[at(i, j), at(i + 1, j), at(i + 1, j + 1)], [at(i, j), at(i + 1, j + 1), at(i, j + 1)]]
+        })).collect();
+        let mesh = TriMesh::new(vertices, triangles);
+        let lods = mesh.generate_lods(&[0.1]);
+        let flat = &lods.levels[0];
+        assert!(flat.len() <= 12);
+        let area: f64 = flat.iter().map(|t| {
+            let [a, b, c] = t.map(|i| lods.vertices[i]);
+            b.vector_from(a).cross(c.vector_from(a)).2 / 2.0
+        }).sum();
+        assert!((area - (n * n) as f64).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,839 @@
+//! Arc-length parameterization of paths and polylines, bending of paths
+//! along curves by arc length, and the `Measure` trait giving length, area,
+//! volume and centroid of any shape alike
+
+use crate::curve::{ flatten_params, segment_distance, MAX_DEPTH };
+use crate::path::{ segments, Segment };
+use crate::{
+    Aabb3, Circle2, Cone, Curve2, Cylinder, Ellipse2, Float, Geometry2, GeometryCollection2, MultiPoint2, MultiPolygon2,
+    MultiPolyline2, Path2, PathCommand, Point2, Point3, PointCloud3, Polygon2, Polyline2, Polyline3, Rect2, Segment2, Sphere,
+    Tetrahedron, Torus, TriMesh, Triangle3, Vector2,
+};
+
+/// Cumulative arc-length tables over the segments of a path
+#[derive(Debug, Clone)]
+pub struct PathMeasure<T> {
+    pieces: Vec<Piece<T>>,
+}
+
+#[derive(Debug, Clone)]
+struct Piece<T> {
+    contour: usize,
+    segment: Segment<T>,
+    /// (parameter, arc length from the path start), both increasing
+    table: Vec<(T, T)>,
+}
+
+impl<T: Float> PathMeasure<T> {
+    /// new: &Path × K -> Measure
+    /// curves are sampled within `tolerance`; contours follow each other
+    /// without gaps in length, and only explicit Close commands add a closing segment
+    pub fn new(path: &Path2<T>, tolerance: T) -> Self {
+        let mut measure = PathMeasure { pieces: Vec::new() };
+        for (k, contour) in path.contours().enumerate() {
+            for segment in segments(contour, false) {
+                let samples = match segment {
+                    Segment::Line(a, b) => vec![(T::zero(), a), (T::one(), b)],
+                    _ => flatten_params(&segment, tolerance),
+                };
+                measure.push(k, segment, &samples);
+            }
+        }
+        measure
+    }
+
+    /// from_polyline: &Polyline -> Measure
+    pub fn from_polyline(line: &Polyline2<T>) -> Self {
+        let mut measure = PathMeasure { pieces: Vec::new() };
+        for w in line.vertices().windows(2) {
+            measure.push(0, Segment::Line(w[0], w[1]), &[(T::zero(), w[0]), (T::one(), w[1])]);
+        }
+        measure
+    }
+
+    fn push(&mut self, contour: usize, segment: Segment<T>, samples: &[(T, Point2<T>)]) {
+        let mut s = self.length();
+        let mut table = vec![(samples[0].0, s)];
+        for w in samples.windows(2) {
+            s = s + w[1].1.distance(w[0].1);
+            table.push((w[1].0, s));
+        }
+        self.pieces.push(Piece { contour, segment, table });
+    }
+
+    /// length: &Measure -> K
+    pub fn length(&self) -> T {
+        self.pieces.last().map_or(T::zero(), |p| p.table[p.table.len() - 1].1)
+    }
+
+    /// position_at: &Measure × K -> Option<Point>
+    /// the point `s` along the path, clamped to its ends; None if empty
+    pub fn position_at(&self, s: T) -> Option<Point2<T>> {
+        let (piece, t) = self.locate(s)?;
+        Some(piece.segment.point_at(t))
+    }
+
+    /// tangent_at: &Measure × K -> Option<Vector>
+    /// unit direction of travel at `s`; None if empty or degenerate
+    pub fn tangent_at(&self, s: T) -> Option<Vector2<T>> {
+        let (piece, t) = self.locate(s)?;
+        let mut d = piece.segment.derivative(t);
+        if d.length() == T::zero() {
+            // vanishing derivative at a cusp or doubled control point
+            let (a, b) = (piece.table[0].0, piece.table[piece.table.len() - 1].0);
+            d = piece.segment.point_at(b).vector_from(piece.segment.point_at(a));
+        }
+        let l = d.length();
+        if l == T::zero() { None } else { Some(d / l) }
+    }
+
+    /// segment_between: &Measure × K × K -> Path
+    /// the part of the path between the two arc lengths, curves kept exact
+    pub fn segment_between(&self, s0: T, s1: T) -> Path2<T> {
+        let (s0, s1) = if s1 < s0 { (s1, s0) } else { (s0, s1) };
+        let mut out = Path2::new(Vec::new());
+        let mut contour = None;
+        for piece in &self.pieces {
+            let (start, end) = (piece.table[0].1, piece.table[piece.table.len() - 1].1);
+            if end <= s0 || start >= s1 {
+                continue;
+            }
+            let t0 = if s0 > start { parameter(&piece.table, s0) } else { T::zero() };
+            let t1 = if s1 < end { parameter(&piece.table, s1) } else { T::one() };
+            let sub = piece.segment.sub(t0, t1);
+            if contour != Some(piece.contour) {
+                out.push(PathCommand::MoveTo(sub.point_at(T::zero())));
+                contour = Some(piece.contour);
+            }
+            out.push(sub.to_command());
+        }
+        out
+    }
+
+    fn locate(&self, s: T) -> Option<(&Piece<T>, T)> {
+        let last = self.pieces.last()?;
+        let s = if s < T::zero() { T::zero() } else if s > self.length() { self.length() } else { s };
+        let i = self.pieces.partition_point(|p| p.table[p.table.len() - 1].1 < s);
+        let piece = self.pieces.get(i).unwrap_or(last);
+        Some((piece, parameter(&piece.table, s)))
+    }
+}
+
+/// Parameter at arc length s in a (parameter, arc length) table, linear
+/// between samples
+fn parameter<T: Float>(table: &[(T, T)], s: T) -> T {
+    let j = table.partition_point(|e| e.1 < s).clamp(1, table.len() - 1);
+    let ((t0, s0), (t1, s1)) = (table[j - 1], table[j]);
+    if s1 == s0 {
+        t0
+    } else {
+        let f = (s - s0) / (s1 - s0);
+        let f = if f < T::zero() { T::zero() } else if f > T::one() { T::one() } else { f };
+        t0 + (t1 - t0) * f
+    }
+}
+
+/// deform_along: &Path × &Curve × K -> Path
+/// the path bent along the spine: x becomes arc length along it and y
+/// distance to its left, past its ends along the end tangents; every
+/// contour comes out as lines within `tolerance` of the exact bend
+pub fn deform_along<T: Float, C: Curve2<T> + ?Sized>(path: &Path2<T>, spine: &C, tolerance: T) -> Path2<T> {
+    let spine = Spine::new(spine, tolerance);
+    let mut out = Path2::new(Vec::new());
+    for contour in path.contours() {
+        let Some(&PathCommand::MoveTo(start)) = contour.first() else { continue };
+        let first = spine.place(start);
+        let mut points = vec![first];
+        for segment in segments(contour, false) {
+            let p1 = spine.place(segment.point_at(T::one()));
+            bend(&segment, &spine, (T::zero(), *points.last().unwrap_or(&first)), (T::one(), p1), tolerance, 0, &mut points);
+        }
+        let closed = contour.last() == Some(&PathCommand::Close);
+        if closed && points.len() > 1 {
+            points.pop();
+        }
+        out.push(PathCommand::MoveTo(first));
+        points.into_iter().skip(1).for_each(|p| out.push(PathCommand::LineTo(p)));
+        if closed {
+            out.push(PathCommand::Close);
+        }
+    }
+    out
+}
+
+/// Arc-length table of a curve, for placing points in its frame
+struct Spine<'a, T, C: ?Sized> {
+    curve: &'a C,
+    table: Vec<(T, T)>,
+}
+
+impl<'a, T: Float, C: Curve2<T> + ?Sized> Spine<'a, T, C> {
+    fn new(curve: &'a C, tolerance: T) -> Self {
+        let samples = flatten_params(curve, tolerance);
+        let mut s = T::zero();
+        let mut table = vec![(samples[0].0, s)];
+        for w in samples.windows(2) {
+            s = s + w[1].1.distance(w[0].1);
+            table.push((w[1].0, s));
+        }
+        Spine { curve, table }
+    }
+
+    /// Unit tangent at parameter t, from the sampled chord where the
+    /// derivative vanishes
+    fn tangent(&self, t: T) -> Vector2<T> {
+        let mut d = self.curve.derivative(t);
+        if d.length() == T::zero() && self.table.len() > 1 {
+            let j = self.table.partition_point(|e| e.0 < t).clamp(1, self.table.len() - 1);
+            let (a, b) = (self.table[j - 1].0, self.table[j].0);
+            d = self.curve.point_at(b).vector_from(self.curve.point_at(a));
+        }
+        let l = d.length();
+        if l == T::zero() { Vector2(T::one(), T::zero()) } else { d / l }
+    }
+
+    /// The point x along the spine and y to its left
+    fn place(&self, p: Point2<T>) -> Point2<T> {
+        let length = self.table[self.table.len() - 1].1;
+        let s = if p.0 < T::zero() { T::zero() } else if p.0 > length { length } else { p.0 };
+        let t = parameter(&self.table, s);
+        let d = self.tangent(t);
+        let n = Vector2(-d.1, d.0);
+        self.curve.point_at(t) + d * (p.0 - s) + n * p.1
+    }
+}
+
+/// Placed points of a segment over (t0, t1], subdivided until the bend
+/// stays within `tolerance` of every chord
+fn bend<T: Float, C: Curve2<T> + ?Sized>(
+    segment: &Segment<T>,
+    spine: &Spine<'_, T, C>,
+    (t0, p0): (T, Point2<T>),
+    (t1, p1): (T, Point2<T>),
+    tolerance: T,
+    depth: usize,
+    out: &mut Vec<Point2<T>>,
+) {
+    let at = |f: f64| spine.place(segment.point_at(t0 + (t1 - t0) * T::from_f64(f)));
+    let mid = at(0.5);
+    let flat = depth >= MAX_DEPTH || [at(0.25), mid, at(0.75)].iter().all(|&p| segment_distance(p, p0, p1) <= tolerance);
+    if flat {
+        out.push(p1);
+    } else {
+        let tm = (t0 + t1) / T::from_f64(2.0);
+        bend(segment, spine, (t0, p0), (tm, mid), tolerance, depth + 1, out);
+        bend(segment, spine, (tm, mid), (t1, p1), tolerance, depth + 1, out);
+    }
+}
+
+/// Extent and centre of mass of a shape
+///
+/// Each measure covers the shape or its boundary: a polygon's length is its
+/// perimeter and a solid's area its surface. Measures a shape lacks are
+/// zero, and collections sum those of their members.
+pub trait Measure<T: Float> {
+    /// Point2 or Point3
+    type Point;
+
+    /// length: &Shape -> K
+    fn length(&self) -> T {
+        T::zero()
+    }
+
+    /// area: &Shape -> K
+    fn area(&self) -> T {
+        T::zero()
+    }
+
+    /// volume: &Shape -> K
+    fn volume(&self) -> T {
+        T::zero()
+    }
+
+    /// centroid: &Shape -> Option<Point>
+    /// centre of mass of the highest-dimensional parts: solids over
+    /// surfaces over curves over points; None if empty or degenerate
+    fn centroid(&self) -> Option<Self::Point>;
+}
+
+/// Running centroid of the highest-dimensional parts seen: dimension,
+/// weight and weighted coordinate sums
+#[derive(Copy, Clone)]
+struct Mass2<T> {
+    dimension: u8,
+    weight: T,
+    sum: Vector2<T>,
+}
+
+impl<T: Float> Mass2<T> {
+    fn empty() -> Self {
+        Mass2 { dimension: 0, weight: T::zero(), sum: Vector2(T::zero(), T::zero()) }
+    }
+
+    /// A part of that dimension and weight; nothing if it has no weight
+    fn of(dimension: u8, weight: T, centroid: Option<Point2<T>>) -> Self {
+        match centroid {
+            Some(c) if weight > T::zero() => Mass2 { dimension, weight, sum: Vector2(c.0 * weight, c.1 * weight) },
+            _ => Mass2::empty(),
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        if other.weight == T::zero() || (self.weight > T::zero() && other.dimension < self.dimension) {
+            self
+        } else if self.weight == T::zero() || other.dimension > self.dimension {
+            other
+        } else {
+            Mass2 { dimension: self.dimension, weight: self.weight + other.weight, sum: self.sum + other.sum }
+        }
+    }
+
+    fn centroid(self) -> Option<Point2<T>> {
+        (self.weight > T::zero()).then(|| Point2(self.sum.0 / self.weight, self.sum.1 / self.weight))
+    }
+}
+
+fn ring_length<T: Float>(ring: &[Point2<T>]) -> T {
+    (0..ring.len()).fold(T::zero(), |s, i| s + ring[i].distance(ring[(i + 1) % ring.len()]))
+}
+
+/// Length-weighted centroid of the segments of a chain
+fn chain_mass<T: Float>(vertices: &[Point2<T>]) -> Mass2<T> {
+    vertices.windows(2).fold(Mass2::empty(), |m, w| {
+        m.add(Mass2::of(1, w[1].distance(w[0]), Some(w[0] + w[1].vector_from(w[0]) / T::from_f64(2.0))))
+    })
+}
+
+fn polygon_mass<T: Float>(polygon: &Polygon2<T>) -> Mass2<T> {
+    let area = polygon.area();
+    Mass2::of(2, area, (area > T::zero()).then(|| polygon.centroid()))
+}
+
+fn geometry_mass<T: Float>(geometry: &Geometry2<T>) -> Mass2<T> {
+    match geometry {
+        Geometry2::Point(p) => Mass2::of(0, T::one(), Some(*p)),
+        Geometry2::Polyline(l) => chain_mass(l.vertices()),
+        Geometry2::Polygon(p) => polygon_mass(p),
+        Geometry2::MultiPoint(m) => members_mass(m.iter()),
+        Geometry2::MultiPolyline(m) => members_mass(m.iter()),
+        Geometry2::MultiPolygon(m) => members_mass(m.iter()),
+        Geometry2::Collection(c) => members_mass(c.iter()),
+    }
+}
+
+impl<T: Float> Measure<T> for Point2<T> {
+    type Point = Point2<T>;
+
+    fn centroid(&self) -> Option<Point2<T>> {
+        Some(*self)
+    }
+}
+
+impl<T: Float> Measure<T> for Segment2<T> {
+    type Point = Point2<T>;
+
+    fn length(&self) -> T {
+        self.start().distance(self.end())
+    }
+
+    fn centroid(&self) -> Option<Point2<T>> {
+        Some(self.start() + self.end().vector_from(self.start()) / T::from_f64(2.0))
+    }
+}
+
+impl<T: Float> Measure<T> for Rect2<T> {
+    type Point = Point2<T>;
+
+    fn length(&self) -> T {
+        let d = self.max().vector_from(self.min());
+        (d.0 + d.1) * T::from_f64(2.0)
+    }
+
+    fn area(&self) -> T {
+        let d = self.max().vector_from(self.min());
+        d.0 * d.1
+    }
+
+    fn centroid(&self) -> Option<Point2<T>> {
+        Some(self.min() + self.max().vector_from(self.min()) / T::from_f64(2.0))
+    }
+}
+
+impl<T: Float> Measure<T> for Circle2<T> {
+    type Point = Point2<T>;
+
+    fn length(&self) -> T {
+        T::from_f64(2.0) * T::pi() * self.radius()
+    }
+
+    fn area(&self) -> T {
+        T::pi() * self.radius() * self.radius()
+    }
+
+    fn centroid(&self) -> Option<Point2<T>> {
+        Some(self.center())
+    }
+}
+
+impl<T: Float> Measure<T> for Ellipse2<T> {
+    type Point = Point2<T>;
+
+    /// Ramanujan's second approximation, exact for circles
+    fn length(&self) -> T {
+        let Vector2(a, b) = self.radii();
+        let (a, b) = (a.abs(), b.abs());
+        if a + b == T::zero() {
+            return T::zero();
+        }
+        let h = (a - b) * (a - b) / ((a + b) * (a + b));
+        let three = T::from_f64(3.0);
+        T::pi() * (a + b) * (T::one() + three * h / (T::from_f64(10.0) + (T::from_f64(4.0) - three * h).sqrt()))
+    }
+
+    fn area(&self) -> T {
+        Ellipse2::area(self).abs()
+    }
+
+    fn centroid(&self) -> Option<Point2<T>> {
+        Some(self.center())
+    }
+}
+
+impl<T: Float> Measure<T> for Polyline2<T> {
+    type Point = Point2<T>;
+
+    fn length(&self) -> T {
+        Polyline2::length(self)
+    }
+
+    fn centroid(&self) -> Option<Point2<T>> {
+        chain_mass(self.vertices()).centroid()
+    }
+}
+
+impl<T: Float> Measure<T> for Polygon2<T> {
+    type Point = Point2<T>;
+
+    fn length(&self) -> T {
+        self.rings().fold(T::zero(), |s, r| s + ring_length(r))
+    }
+
+    fn area(&self) -> T {
+        Polygon2::area(self)
+    }
+
+    fn centroid(&self) -> Option<Point2<T>> {
+        polygon_mass(self).centroid()
+    }
+}
+
+/// Members of collections, by the geometry they are
+trait Member2<T: Float> {
+    fn mass(&self) -> Mass2<T>;
+}
+
+impl<T: Float> Member2<T> for Point2<T> {
+    fn mass(&self) -> Mass2<T> {
+        Mass2::of(0, T::one(), Some(*self))
+    }
+}
+
+impl<T: Float> Member2<T> for Polyline2<T> {
+    fn mass(&self) -> Mass2<T> {
+        chain_mass(self.vertices())
+    }
+}
+
+impl<T: Float> Member2<T> for Polygon2<T> {
+    fn mass(&self) -> Mass2<T> {
+        polygon_mass(self)
+    }
+}
+
+impl<T: Float> Member2<T> for Geometry2<T> {
+    fn mass(&self) -> Mass2<T> {
+        geometry_mass(self)
+    }
+}
+
+fn members_mass<'a, T: Float + 'a, M: Member2<T> + 'a>(members: impl Iterator<Item = &'a M>) -> Mass2<T> {
+    members.fold(Mass2::empty(), |s, m| s.add(m.mass()))
+}
+
+macro_rules! impl_measure_collection {
+    ($name:ident) => {
+        impl<T: Float> Measure<T> for $name<T> {
+            type Point = Point2<T>;
+
+            fn length(&self) -> T {
+                self.iter().fold(T::zero(), |s, m| s + Measure::length(m))
+            }
+
+            fn area(&self) -> T {
+                self.iter().fold(T::zero(), |s, m| s + Measure::area(m))
+            }
+
+            fn centroid(&self) -> Option<Point2<T>> {
+                members_mass(self.iter()).centroid()
+            }
+        }
+    };
+}
+
+impl_measure_collection!(MultiPoint2);
+impl_measure_collection!(MultiPolyline2);
+impl_measure_collection!(MultiPolygon2);
+impl_measure_collection!(GeometryCollection2);
+
+impl<T: Float> Measure<T> for Geometry2<T> {
+    type Point = Point2<T>;
+
+    fn length(&self) -> T {
+        match self {
+            Geometry2::Point(_) => T::zero(),
+            Geometry2::Polyline(l) => Measure::length(l),
+            Geometry2::Polygon(p) => Measure::length(p),
+            Geometry2::MultiPoint(_) => T::zero(),
+            Geometry2::MultiPolyline(m) => Measure::length(m),
+            Geometry2::MultiPolygon(m) => Measure::length(m),
+            Geometry2::Collection(c) => Measure::length(c),
+        }
+    }
+
+    fn area(&self) -> T {
+        Geometry2::area(self)
+    }
+
+    fn centroid(&self) -> Option<Point2<T>> {
+        geometry_mass(self).centroid()
+    }
+}
+
+impl<T: Float> Measure<T> for Point3<T> {
+    type Point = Point3<T>;
+
+    fn centroid(&self) -> Option<Point3<T>> {
+        Some(*self)
+    }
+}
+
+impl<T: Float> Measure<T> for Polyline3<T> {
+    type Point = Point3<T>;
+
+    fn length(&self) -> T {
+        Polyline3::length(self)
+    }
+
+    /// length-weighted mean of the segment midpoints
+    fn centroid(&self) -> Option<Point3<T>> {
+        let v = self.vertices();
+        let first = *v.first()?;
+        let zero = first.vector_from(first);
+        let (w, sum) = v.windows(2).fold((T::zero(), zero), |(w, s), e| {
+            let l = e[1].distance(e[0]);
+            let mid = (e[0].vector_from(first) + e[1].vector_from(first)) / T::from_f64(2.0);
+            (w + l, s + mid * l)
+        });
+        (w > T::zero()).then(|| first + sum / w)
+    }
+}
+
+impl<T: Float> Measure<T> for Triangle3<T> {
+    type Point = Point3<T>;
+
+    fn length(&self) -> T {
+        let [a, b, c] = self.vertices();
+        a.distance(b) + b.distance(c) + c.distance(a)
+    }
+
+    fn area(&self) -> T {
+        Triangle3::area(self)
+    }
+
+    fn centroid(&self) -> Option<Point3<T>> {
+        let [a, b, c] = self.vertices();
+        Some(a + (b.vector_from(a) + c.vector_from(a)) / T::from_f64(3.0))
+    }
+}
+
+impl<T: Float> Measure<T> for Aabb3<T> {
+    type Point = Point3<T>;
+
+    fn area(&self) -> T {
+        let d = self.max().vector_from(self.min());
+        (d.0 * d.1 + d.1 * d.2 + d.2 * d.0) * T::from_f64(2.0)
+    }
+
+    fn volume(&self) -> T {
+        let d = self.max().vector_from(self.min());
+        d.0 * d.1 * d.2
+    }
+
+    fn centroid(&self) -> Option<Point3<T>> {
+        Some(self.min() + self.max().vector_from(self.min()) / T::from_f64(2.0))
+    }
+}
+
+impl<T: Float> Measure<T> for Sphere<T> {
+    type Point = Point3<T>;
+
+    fn area(&self) -> T {
+        T::from_f64(4.0) * T::pi() * self.radius() * self.radius()
+    }
+
+    fn volume(&self) -> T {
+        T::from_f64(4.0 / 3.0) * T::pi() * self.radius() * self.radius() * self.radius()
+    }
+
+    fn centroid(&self) -> Option<Point3<T>> {
+        Some(self.center())
+    }
+}
+
+impl<T: Float> Measure<T> for Cylinder<T> {
+    type Point = Point3<T>;
+
+    fn area(&self) -> T {
+        let r = self.radius();
+        T::from_f64(2.0) * T::pi() * r * (r + self.height())
+    }
+
+    fn volume(&self) -> T {
+        T::pi() * self.radius() * self.radius() * self.height()
+    }
+
+    fn centroid(&self) -> Option<Point3<T>> {
+        let (a, b) = self.ends();
+        Some(a + b.vector_from(a) / T::from_f64(2.0))
+    }
+}
+
+impl<T: Float> Measure<T> for Cone<T> {
+    type Point = Point3<T>;
+
+    fn area(&self) -> T {
+        let (r, h) = (self.radius(), self.height());
+        T::pi() * r * (r + (r * r + h * h).sqrt())
+    }
+
+    fn volume(&self) -> T {
+        T::pi() * self.radius() * self.radius() * self.height() / T::from_f64(3.0)
+    }
+
+    /// a quarter of the height up from the base
+    fn centroid(&self) -> Option<Point3<T>> {
+        Some(self.apex() + self.axis() * T::from_f64(0.75))
+    }
+}
+
+impl<T: Float> Measure<T> for Torus<T> {
+    type Point = Point3<T>;
+
+    fn area(&self) -> T {
+        T::from_f64(4.0) * T::pi() * T::pi() * self.major() * self.minor()
+    }
+
+    fn volume(&self) -> T {
+        T::from_f64(2.0) * T::pi() * T::pi() * self.major() * self.minor() * self.minor()
+    }
+
+    fn centroid(&self) -> Option<Point3<T>> {
+        Some(self.center())
+    }
+}
+
+impl<T: Float> Measure<T> for Tetrahedron<T> {
+    type Point = Point3<T>;
+
+    fn area(&self) -> T {
+        let [a, b, c, d] = self.vertices();
+        [[a, b, c], [a, b, d], [a, c, d], [b, c, d]].iter().fold(T::zero(), |s, &[p, q, r]| s + Triangle3::new(p, q, r).area())
+    }
+
+    fn volume(&self) -> T {
+        Tetrahedron::volume(self)
+    }
+
+    fn centroid(&self) -> Option<Point3<T>> {
+        let [a, b, c, d] = self.vertices();
+        Some(a + (b.vector_from(a) + c.vector_from(a) + d.vector_from(a)) / T::from_f64(4.0))
+    }
+}
+
+impl<T: Float> Measure<T> for TriMesh<T> {
+    type Point = Point3<T>;
+
+    fn area(&self) -> T {
+        let v = self.vertices();
+        self.triangles().iter().fold(T::zero(), |s, &[i, j, k]| s + Triangle3::new(v[i], v[j], v[k]).area())
+    }
+
+    /// of the enclosed solid, for closed outward-oriented meshes
+    fn volume(&self) -> T {
+        TriMesh::volume(self)
+    }
+
+    /// of the enclosed solid, None if the mesh encloses no volume
+    fn centroid(&self) -> Option<Point3<T>> {
+        (TriMesh::volume(self) != T::zero()).then(|| TriMesh::centroid(self))
+    }
+}
+
+impl<T: Float, A> Measure<T> for PointCloud3<T, A> {
+    type Point = Point3<T>;
+
+    fn centroid(&self) -> Option<Point3<T>> {
+        PointCloud3::centroid(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    use PathCommand::*;
+
+    fn near(a: f64, b: f64, eps: f64) -> bool {
+        (a - b).abs() < eps
+    }
+
+    #[test]
+    fn polyline() {
+        let line = Polyline2::new(vec![Point2(0.0, 0.0), Point2(3.0, 0.0), Point2(3.0, 4.0)]);
+        let m = PathMeasure::from_polyline(&line);
+        assert_eq!(m.length(), 7.0);
+        assert_eq!(m.position_at(1.5), Some(Point2(1.5, 0.0)));
+        assert_eq!(m.position_at(5.0), Some(Point2(3.0, 2.0)));
+        assert_eq!(m.position_at(10.0), Some(Point2(3.0, 4.0)));
+        assert_eq!(m.position_at(-1.0), Some(Point2(0.0, 0.0)));
+        assert_eq!(m.tangent_at(1.0), Some(Vector2(1.0, 0.0)));
+        assert_eq!(m.tangent_at(4.0), Some(Vector2(0.0, 1.0)));
+        let sub = m.segment_between(2.0, 5.0);
+        assert_eq!(sub.commands(), &[MoveTo(Point2(2.0, 0.0)), LineTo(Point2(3.0, 0.0)), LineTo(Point2(3.0, 2.0))]);
+        assert_eq!(PathMeasure::<f64>::from_polyline(&Polyline2::new(vec![])).position_at(0.0), None);
+    }
+
+    #[test]
+    fn circle() {
+        let path = Path2::new(vec![
+            MoveTo(Point2(1.0, 0.0)),
+            ArcTo { radius: 1.0, large_arc: false, sweep: true, to: Point2(-1.0, 0.0) },
+            ArcTo { radius: 1.0, large_arc: false, sweep: true, to: Point2(1.0, 0.0) },
+        ]);
+        let m = PathMeasure::new(&path, 1e-6);
+        assert!(near(m.length(), 2.0 * PI, 1e-5));
+        let p = m.position_at(PI / 2.0).unwrap();
+        assert!(p.distance(Point2(0.0, 1.0)) < 1e-4);
+        assert!((p.0 * p.0 + p.1 * p.1 - 1.0).abs() < 1e-12);
+        let t = m.tangent_at(PI / 2.0).unwrap();
+        assert!((t - Vector2(-1.0, 0.0)).length() < 1e-4);
+        let half = m.segment_between(PI / 2.0, 3.0 * PI / 2.0);
+        assert_eq!(half.commands().len(), 3);
+        assert!(near(PathMeasure::new(&half, 1e-6).length(), PI, 1e-4));
+    }
+
+    #[test]
+    fn cubic_split() {
+        let path = Path2::new(vec![
+            MoveTo(Point2(0.0, 0.0)),
+            CubicTo(Point2(0.0, 4.0), Point2(4.0, 4.0), Point2(4.0, 0.0)),
+            MoveTo(Point2(10.0, 0.0)),
+            QuadTo(Point2(11.0, 2.0), Point2(12.0, 0.0)),
+        ]);
+        let m = PathMeasure::new(&path, 1e-6);
+        let first = PathMeasure::new(&Path2::new(path.commands()[..2].to_vec()), 1e-6).length();
+        let sub = m.segment_between(0.25 * first, first + 0.1);
+        let cs = sub.commands();
+        assert_eq!(cs.len(), 4);
+        assert!(matches!(cs[1], CubicTo(..)) && matches!(cs[2], MoveTo(Point2(10.0, 0.0))));
+        assert!(near(PathMeasure::new(&sub, 1e-6).length(), 0.75 * first + 0.1, 1e-4));
+        if let (MoveTo(a), Some(b)) = (cs[0], m.position_at(0.25 * first)) {
+            assert!(a.distance(b) < 1e-9);
+        }
+    }
+
+    #[test]
+    fn deform() {
+        // along a straight spine bending is a move
+        let square = Path2::new(vec![MoveTo(Point2(0.0, 0.0)), LineTo(Point2(2.0, 0.0)), LineTo(Point2(2.0, 1.0)), LineTo(Point2(0.0, 1.0)), Close]);
+        let line = crate::QuadBezier2::new(Point2(5.0, 5.0), Point2(8.0, 5.0), Point2(11.0, 5.0));
+        let moved = deform_along(&square, &line, 1e-9);
+        assert_eq!(moved.commands(), &[
+            MoveTo(Point2(5.0, 5.0)), LineTo(Point2(7.0, 5.0)), LineTo(Point2(7.0, 6.0)), LineTo(Point2(5.0, 6.0)), Close,
+        ]);
+
+        // a ruler along a quarter circle: lengths on the spine are kept,
+        // offsets to the left land on a smaller circle
+        let arc = crate::Arc2::new(Point2(0.0, 0.0), 10.0, 0.0, PI / 2.0);
+        let ruler = Path2::new(vec![MoveTo(Point2(0.0, 0.0)), LineTo(Point2(10.0, 0.0)), MoveTo(Point2(0.0, 2.0)), LineTo(Point2(10.0, 2.0))]);
+        let bent = deform_along(&ruler, &arc, 1e-4);
+        let on_spine = PathMeasure::new(&Path2::new(bent.contours().next().unwrap().to_vec()), 1e-6).length();
+        assert!(near(on_spine, 10.0, 1e-3));
+        for c in bent.commands() {
+            if let LineTo(p) | MoveTo(p) = *c {
+                let r = p.distance(Point2(0.0, 0.0));
+                assert!(near(r, 10.0, 1e-4) || near(r, 8.0, 1e-4));
+            }
+        }
+        assert!(bent.commands().len() > 6);
+        // past the end, along the end tangent
+        let past = deform_along(&Path2::new(vec![MoveTo(Point2(10.0 * PI / 2.0 + 1.0, 0.0))]), &arc, 1e-6);
+        if let MoveTo(p) = past.commands()[0] {
+            assert!(p.distance(Point2(-1.0, 10.0)) < 1e-4);
+        }
+    }
+
+    fn report<M: Measure<f64>>(shape: &M) -> [f64; 3] {
+        [shape.length(), shape.area(), shape.volume()]
+    }
+
+    #[test]
+    fn measures_2d() {
+        let square = Polygon2::new(vec![Point2(0.0, 0.0), Point2(2.0, 0.0), Point2(2.0, 2.0), Point2(0.0, 2.0)]);
+        assert_eq!(report(&square), [8.0, 4.0, 0.0]);
+        assert_eq!(Measure::centroid(&square), Some(Point2(1.0, 1.0)));
+        assert_eq!(report(&Rect2::new(Point2(0.0, 0.0), Point2(2.0, 2.0))), report(&square));
+        let [l, a, _] = report(&crate::Ellipse2::new(Point2(1.0, 1.0), Vector2(2.0, 2.0), 0.3));
+        assert!(near(l, 4.0 * PI, 1e-12) && near(a, 4.0 * PI, 1e-12));
+        let [l, ..] = report(&crate::Ellipse2::new(Point2(0.0, 0.0), Vector2(5.0, 3.0), 0.0));
+        // the true perimeter, to the approximation's accuracy
+        assert!(near(l, 25.52699886235, 1e-9));
+        let line = Polyline2::new(vec![Point2(0.0, 0.0), Point2(3.0, 0.0), Point2(3.0, 1.0)]);
+        assert_eq!(Measure::centroid(&line), Some(Point2(1.875, 0.125)));
+        assert_eq!(Measure::centroid(&Polyline2::new(vec![Point2(1.0, 1.0)])), None);
+        // the polygon outweighs the chain and point, whatever their size
+        let mixed = GeometryCollection2::new(vec![
+            Geometry2::Point(Point2(100.0, 100.0)),
+            Geometry2::Polyline(line.clone()),
+            Geometry2::MultiPolygon(MultiPolygon2::new(vec![square.clone(), Polygon2::new(vec![Point2(9.0, 9.0), Point2(9.0, 9.0), Point2(9.0, 9.0)])])),
+        ]);
+        assert_eq!(report(&mixed), [12.0, 4.0, 0.0]);
+        assert_eq!(mixed.centroid(), Some(Point2(1.0, 1.0)));
+        let scattered = MultiPoint2::new(vec![Point2(0.0, 0.0), Point2(4.0, 2.0)]);
+        assert_eq!((report(&scattered), scattered.centroid()), ([0.0; 3], Some(Point2(2.0, 1.0))));
+        assert_eq!(MultiPolyline2::<f64>::new(Vec::new()).centroid(), None);
+    }
+
+    #[test]
+    fn measures_3d() {
+        let [a, b, c, d] = [Point3(0.0, 0.0, 0.0), Point3(1.0, 0.0, 0.0), Point3(0.0, 1.0, 0.0), Point3(0.0, 0.0, 1.0)];
+        let tetra = Tetrahedron::new(a, b, c, d);
+        let mesh = TriMesh::new(vec![a, b, c, d], vec![[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]]);
+        let ([_, ta, tv], [_, ma, mv]) = (report(&tetra), report(&mesh));
+        assert!(near(ta, ma, 1e-12) && near(tv, mv, 1e-12) && near(tv, 1.0 / 6.0, 1e-12));
+        assert!(Measure::centroid(&mesh).unwrap().distance(tetra.centroid().unwrap()) < 1e-12);
+        assert_eq!(Measure::centroid(&TriMesh::new(vec![a, b, c], vec![[0, 1, 2]])), None);
+        assert_eq!(report(&Aabb3::new(a, Point3(1.0, 2.0, 3.0))), [0.0, 22.0, 6.0]);
+        let [_, area, volume] = report(&Sphere::new(a, 3.0));
+        assert!(near(area, 36.0 * PI, 1e-12) && near(volume, 36.0 * PI, 1e-12));
+        let cone = Cone::new(Point3(0.0, 0.0, 4.0), crate::Vector3(0.0, 0.0, -4.0), (3.0f64 / 4.0).atan());
+        let [_, area, volume] = report(&cone);
+        assert!(near(area, 24.0 * PI, 1e-9) && near(volume, 12.0 * PI, 1e-9));
+        assert_eq!(cone.centroid(), Some(Point3(0.0, 0.0, 1.0)));
+        let [_, area, volume] = report(&Cylinder::new(a, Point3(0.0, 0.0, 2.0), 1.0));
+        assert!(near(area, 6.0 * PI, 1e-12) && near(volume, 2.0 * PI, 1e-12));
+        let wire = Polyline3::new(vec![a, b, Point3(1.0, 1.0, 0.0)]);
+        assert_eq!((report(&wire)[0], Measure::centroid(&wire)), (2.0, Some(Point3(0.75, 0.25, 0.0))));
+        assert_eq!(PointCloud3::new(vec![a, d]).centroid(), Some(Point3(0.0, 0.0, 0.5)));
+    }
+}
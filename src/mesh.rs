@@ -0,0 +1,580 @@
+//! Indexed triangle meshes
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::{ Aabb3, Bounded3, Closest3, Float, Hit, MultiPolygon2, Plane3, Point2, Point3, Polygon2, Ray3, Raycast3, Vector3 };
+use crate::bounds::{ minimal_sphere, ritter_sphere };
+
+/// Triangle mesh: shared vertices and counter-clockwise (outward) index triples
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriMesh<T> {
+    vertices: Vec<Point3<T>>,
+    triangles: Vec<[usize; 3]>,
+    /// texture coordinates of each triangle's corners
+    uvs: Option<Vec<[Point2<T>; 3]>>,
+    /// shading normals of each triangle's corners
+    normals: Option<Vec<[Vector3<T>; 3]>>,
+}
+
+/// 3d Triangle: three corners, counter-clockwise around its normal
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Triangle3<T>([Point3<T>; 3]);
+
+/// Way of laying texture coordinates onto a mesh
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum UvProjection<T> {
+    /// onto the plane, in its coordinates
+    Planar(Plane3<T>),
+    /// each face onto the coordinate plane it faces most, in model units,
+    /// seen from outside so outward faces keep their winding
+    Box,
+    /// around the axis through the point along the vector: u the turn about
+    /// it in [0, 1], v the height along it in model units
+    Cylindrical(Point3<T>, Vector3<T>),
+}
+
+impl<T: Float> Triangle3<T> {
+    /// new: Point × Point × Point -> Triangle
+    pub fn new(a: Point3<T>, b: Point3<T>, c: Point3<T>) -> Self {
+        Triangle3([a, b, c])
+    }
+
+    /// vertices: &Triangle -> [Point; 3]
+    pub fn vertices(&self) -> [Point3<T>; 3] {
+        self.0
+    }
+
+    /// normal: &Triangle -> Vector
+    /// (b - a) × (c - a), twice the area long
+    pub fn normal(&self) -> Vector3<T> {
+        let [a, b, c] = self.0;
+        b.vector_from(a).cross(c.vector_from(a))
+    }
+
+    /// area: &Triangle -> K
+    pub fn area(&self) -> T {
+        self.normal().length() / T::from_f64(2.0)
+    }
+}
+
+impl<T> TriMesh<T> {
+    /// new: [Point] × [[usize; 3]] -> Mesh
+    pub fn new(vertices: Vec<Point3<T>>, triangles: Vec<[usize; 3]>) -> Self {
+        TriMesh { vertices, triangles, uvs: None, normals: None }
+    }
+
+    /// vertices: &Mesh -> &[Point]
+    pub fn vertices(&self) -> &[Point3<T>] {
+        &self.vertices
+    }
+
+    /// triangles: &Mesh -> &[[usize; 3]]
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    /// uvs: &Mesh -> Option<&[[Point2; 3]]>
+    /// texture coordinates of each triangle's corners, if projected
+    pub fn uvs(&self) -> Option<&[[Point2<T>; 3]]> {
+        self.uvs.as_deref()
+    }
+
+    /// normals: &Mesh -> Option<&[[Vector; 3]]>
+    /// shading normals of each triangle's corners, if given
+    pub fn normals(&self) -> Option<&[[Vector3<T>; 3]]> {
+        self.normals.as_deref()
+    }
+
+    /// with_uvs: Mesh × [[Point; 3]] -> Mesh
+    /// panics unless there is one entry per triangle
+    pub fn with_uvs(mut self, uvs: Vec<[Point2<T>; 3]>) -> Self {
+        assert_eq!(uvs.len(), self.triangles.len(), "one uv triple per triangle");
+        self.uvs = Some(uvs);
+        self
+    }
+
+    /// with_normals: Mesh × [[Vector; 3]] -> Mesh
+    /// panics unless there is one entry per triangle
+    pub fn with_normals(mut self, normals: Vec<[Vector3<T>; 3]>) -> Self {
+        assert_eq!(normals.len(), self.triangles.len(), "one normal triple per triangle");
+        self.normals = Some(normals);
+        self
+    }
+}
+
+impl<T: Float> TriMesh<T> {
+    /// from_soup: &[Triangle] × K -> Mesh
+    /// indexed mesh of separate triangles, corners within `tolerance` of an
+    /// earlier vertex welded to it (exact matches only if it is zero).
+    /// Vertices keep the position and order of their first corner; triangles
+    /// keep their order, less those welded flat
+    pub fn from_soup(soup: &[Triangle3<T>], tolerance: T) -> Self {
+        let exact = tolerance <= T::zero();
+        let key = |p: Point3<T>| if exact {
+            [p.0, p.1, p.2]
+        } else {
+            [(p.0 / tolerance).floor(), (p.1 / tolerance).floor(), (p.2 / tolerance).floor()]
+        };
+        let mut cells: Vec<[T; 3]> = soup.iter().flat_map(|t| t.0).map(key).collect();
+        cells.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        cells.dedup();
+        let find = |k: [T; 3]| cells.binary_search_by(|c| c.partial_cmp(&k).unwrap_or(Ordering::Equal)).ok();
+        let (zero, one) = (T::zero(), T::one());
+        let steps: &[T] = if exact { &[zero] } else { &[-one, zero, one] };
+        let mut members: Vec<Vec<usize>> = vec![Vec::new(); cells.len()];
+        let mut vertices: Vec<Point3<T>> = Vec::new();
+        let mut weld = |p: Point3<T>| {
+            let k = key(p);
+            let mut found: Option<usize> = None;
+            for &dx in steps {
+                for &dy in steps {
+                    for &dz in steps {
+                        let Some(c) = find([k[0] + dx, k[1] + dy, k[2] + dz]) else { continue };
+                        for &v in &members[c] {
+                            if vertices[v].distance(p) <= tolerance && found.is_none_or(|f| v < f) {
+                                found = Some(v);
+                            }
+                        }
+                    }
+                }
+            }
+            found.unwrap_or_else(|| {
+                vertices.push(p);
+                members[find(k).unwrap_or(0)].push(vertices.len() - 1);
+                vertices.len() - 1
+            })
+        };
+        let triangles = soup
+            .iter()
+            .map(|t| t.0.map(&mut weld))
+            .filter(|[a, b, c]| a != b && b != c && c != a)
+            .collect();
+        TriMesh::new(vertices, triangles)
+    }
+
+    /// volume: &Mesh -> K
+    /// enclosed volume of a closed, outward-oriented mesh
+    pub fn volume(&self) -> T {
+        self.integrals()[0]
+    }
+
+    /// centroid: &Mesh -> Point
+    /// centre of mass of the enclosed solid
+    pub fn centroid(&self) -> Point3<T> {
+        let m = self.integrals();
+        Point3(m[1] / m[0], m[2] / m[0], m[3] / m[0])
+    }
+
+    /// minimal_bounding_sphere: &Mesh -> Option<(Point, K)>
+    /// center and radius of the smallest sphere holding every vertex (Welzl),
+    /// None without vertices
+    pub fn minimal_bounding_sphere(&self) -> Option<(Point3<T>, T)> {
+        minimal_sphere(&self.vertices)
+    }
+
+    /// inertia_tensor: &Mesh -> [Vector; 3]
+    /// columns of the inertia tensor of the solid about its centroid, unit density
+    pub fn inertia_tensor(&self) -> [Vector3<T>; 3] {
+        let m = self.integrals();
+        let v = m[0];
+        let (x, y, z) = (m[1] / v, m[2] / v, m[3] / v);
+        let xx = m[5] + m[6] - v * (y * y + z * z);
+        let yy = m[4] + m[6] - v * (z * z + x * x);
+        let zz = m[4] + m[5] - v * (x * x + y * y);
+        let xy = -(m[7] - v * x * y);
+        let yz = -(m[8] - v * y * z);
+        let zx = -(m[9] - v * z * x);
+        [Vector3(xx, xy, zx), Vector3(xy, yy, yz), Vector3(zx, yz, zz)]
+    }
+
+    /// project_uvs: &mut Mesh × &UvProjection -> ()
+    /// texture coordinates for every triangle corner; cylindrical faces
+    /// across the seam get u past 1 rather than wrapping
+    pub fn project_uvs(&mut self, projection: &UvProjection<T>) {
+        let (zero, one, half) = (T::zero(), T::one(), T::from_f64(0.5));
+        let uvs = self.triangles.iter().map(|t| {
+            let corners = t.map(|i| self.vertices[i]);
+            match *projection {
+                UvProjection::Planar(plane) => corners.map(|p| plane.coordinates(p)),
+                UvProjection::Box => {
+                    let [a, b, c] = corners;
+                    let n = b.vector_from(a).cross(c.vector_from(a));
+                    let (x, y, z) = (n.0.abs(), n.1.abs(), n.2.abs());
+                    let sign = |s: T| if s < zero { -one } else { one };
+                    corners.map(|p| if x >= y && x >= z {
+                        Point2(p.1 * sign(n.0), p.2)
+                    } else if y >= z {
+                        Point2(-p.0 * sign(n.1), p.2)
+                    } else {
+                        Point2(p.0 * sign(n.2), p.1)
+                    })
+                }
+                UvProjection::Cylindrical(origin, axis) => {
+                    let (u, v) = Plane3::new(origin, axis).basis();
+                    let turn = T::pi() * T::from_f64(2.0);
+                    let mut uv = corners.map(|p| {
+                        let d = p.vector_from(origin);
+                        let a = d.dot(v).atan2(d.dot(u)) / turn;
+                        Point2(if a < zero { a + one } else { a }, d.dot(axis) / axis.length())
+                    });
+                    let lo = uv.iter().fold(one, |m, q| if q.0 < m { q.0 } else { m });
+                    let hi = uv.iter().fold(zero, |m, q| if q.0 > m { q.0 } else { m });
+                    if hi - lo > half {
+                        for q in uv.iter_mut().filter(|q| q.0 < half) {
+                            q.0 = q.0 + one;
+                        }
+                    }
+                    uv
+                }
+            }
+        });
+        self.uvs = Some(uvs.collect());
+    }
+
+    /// slice: &Mesh × &Plane -> [Polygon]
+    /// cross-section of a closed, outward-oriented mesh in the plane's
+    /// coordinates (see `Plane3::coordinates`): counter-clockwise contours
+    /// seen from the side the normal faces, each with the holes inside it.
+    /// Vertices on the plane count as above it, so faces lying in it add nothing
+    pub fn slice(&self, plane: &Plane3<T>) -> Vec<Polygon2<T>> {
+        let below: Vec<bool> = self.vertices.iter().map(|&p| plane.signed_distance(p) < T::zero()).collect();
+        // where the edge between two vertices crosses, found the same way from either face
+        let cut = |i: usize, j: usize| {
+            let (i, j) = if i < j { (i, j) } else { (j, i) };
+            let (a, b) = (self.vertices[i], self.vertices[j]);
+            let (da, db) = (plane.signed_distance(a), plane.signed_distance(b));
+            plane.coordinates(a + b.vector_from(a) * (da / (da - db)))
+        };
+        // each face crossing the plane adds a piece from the edge going down
+        // to the edge coming up, keyed by those edges
+        let key = |i: usize, j: usize| if i < j { (i, j) } else { (j, i) };
+        let mut pieces = HashMap::new();
+        for t in &self.triangles {
+            let (mut down, mut up) = (None, None);
+            for k in 0..3 {
+                let (i, j) = (t[k], t[(k + 1) % 3]);
+                match (below[i], below[j]) {
+                    (false, true) => down = Some(key(i, j)),
+                    (true, false) => up = Some(key(i, j)),
+                    _ => {}
+                }
+            }
+            if let (Some(d), Some(u)) = (down, up) {
+                pieces.insert(d, u);
+            }
+        }
+        let mut rings = Vec::new();
+        while let Some(&start) = pieces.keys().next() {
+            let mut ring = Vec::new();
+            let mut e = start;
+            while let Some(next) = pieces.remove(&e) {
+                ring.push(cut(e.0, e.1));
+                e = next;
+            }
+            if ring.len() > 2 {
+                rings.push(ring);
+            }
+        }
+        MultiPolygon2::from_rings(rings).into_iter().collect()
+    }
+
+    /// Divergence theorem integrals of 1, x, y, z, x², y², z², xy, yz, zx over the solid
+    /// (Eberly, "Polyhedral Mass Properties")
+    fn integrals(&self) -> [T; 10] {
+        fn sub<T: Float>(w0: T, w1: T, w2: T) -> (T, T, T, T, T, T) {
+            let t0 = w0 + w1;
+            let f1 = t0 + w2;
+            let t1 = w0 * w0;
+            let t2 = t1 + w1 * t0;
+            let f2 = t2 + w2 * f1;
+            let f3 = w0 * t1 + w1 * t2 + w2 * f2;
+            (f1, f2, f3, f2 + w0 * (f1 + w0), f2 + w1 * (f1 + w1), f2 + w2 * (f1 + w2))
+        }
+        let mut m = [T::zero(); 10];
+        for &[i, j, k] in &self.triangles {
+            let (p0, p1, p2) = (self.vertices[i], self.vertices[j], self.vertices[k]);
+            let e1 = p1.vector_from(p0);
+            let e2 = p2.vector_from(p0);
+            let d = e1.cross(e2);
+            let (f1x, f2x, f3x, g0x, g1x, g2x) = sub(p0.0, p1.0, p2.0);
+            let (_, f2y, f3y, g0y, g1y, g2y) = sub(p0.1, p1.1, p2.1);
+            let (_, f2z, f3z, g0z, g1z, g2z) = sub(p0.2, p1.2, p2.2);
+            m[0] = m[0] + d.0 * f1x;
+            m[1] = m[1] + d.0 * f2x;
+            m[2] = m[2] + d.1 * f2y;
+            m[3] = m[3] + d.2 * f2z;
+            m[4] = m[4] + d.0 * f3x;
+            m[5] = m[5] + d.1 * f3y;
+            m[6] = m[6] + d.2 * f3z;
+            m[7] = m[7] + d.0 * (p0.1 * g0x + p1.1 * g1x + p2.1 * g2x);
+            m[8] = m[8] + d.1 * (p0.2 * g0y + p1.2 * g1y + p2.2 * g2y);
+            m[9] = m[9] + d.2 * (p0.0 * g0z + p1.0 * g1z + p2.0 * g2z);
+        }
+        let k = [6.0, 24.0, 24.0, 24.0, 60.0, 60.0, 60.0, 120.0, 120.0, 120.0];
+        std::array::from_fn(|i| m[i] / T::from_f64(k[i]))
+    }
+}
+
+impl<T: Float> Bounded3<T> for TriMesh<T> {
+    fn bounds(&self) -> Option<Aabb3<T>> {
+        Aabb3::from_points(self.vertices.iter().copied())
+    }
+
+    /// Ritter's sphere: a pass over the vertices, a few percent above minimal
+    fn bounding_sphere(&self) -> Option<(Point3<T>, T)> {
+        ritter_sphere(&self.vertices)
+    }
+}
+
+impl<T: Float> Closest3<T> for TriMesh<T> {
+    fn closest_point_on(&self, p: Point3<T>) -> Point3<T> {
+        let on = self.triangles.iter().map(|t| triangle_closest(p, t.map(|i| self.vertices[i])));
+        crate::polyline::nearest(on, |q| q.distance(p)).unwrap_or(p)
+    }
+}
+
+impl<T: Float> Raycast3<T> for TriMesh<T> {
+    /// Möller–Trumbore against every triangle, from either side;
+    /// the normal is that of the face as wound
+    fn raycast(&self, ray: &Ray3<T>) -> Option<Hit<T>> {
+        let mut best: Option<(T, Vector3<T>)> = None;
+        for t in &self.triangles {
+            if let Some(hit) = ray_triangle(ray, t.map(|i| self.vertices[i])) {
+                if best.is_none_or(|b| hit.0 < b.0) {
+                    best = Some(hit);
+                }
+            }
+        }
+        let (t, n) = best?;
+        Some(Hit { t, point: ray.point_at(t), normal: n / n.length() })
+    }
+}
+
+/// Möller–Trumbore from either side: ray parameter t ≥ 0 of the hit and
+/// the unnormalized face normal as wound
+pub(crate) fn ray_triangle<T: Float>(ray: &Ray3<T>, [a, b, c]: [Point3<T>; 3]) -> Option<(T, Vector3<T>)> {
+    let (o, d) = (ray.origin(), ray.direction());
+    let (zero, one) = (T::zero(), T::one());
+    let (e1, e2) = (b.vector_from(a), c.vector_from(a));
+    let p = d.cross(e2);
+    let det = e1.dot(p);
+    if det == zero {
+        return None;
+    }
+    let s = o.vector_from(a);
+    let u = s.dot(p) / det;
+    if u < zero || u > one {
+        return None;
+    }
+    let q = s.cross(e1);
+    let v = d.dot(q) / det;
+    if v < zero || u + v > one {
+        return None;
+    }
+    let t = e2.dot(q) / det;
+    (t >= zero).then(|| (t, e1.cross(e2)))
+}
+
+/// Nearest point of a triangle, by the Voronoi region of p
+/// (Ericson, Real-Time Collision Detection, 5.1.5)
+pub(crate) fn triangle_closest<T: Float>(p: Point3<T>, [a, b, c]: [Point3<T>; 3]) -> Point3<T> {
+    let zero = T::zero();
+    let (ab, ac, ap) = (b.vector_from(a), c.vector_from(a), p.vector_from(a));
+    let (d1, d2) = (ab.dot(ap), ac.dot(ap));
+    if d1 <= zero && d2 <= zero {
+        return a;
+    }
+    let bp = p.vector_from(b);
+    let (d3, d4) = (ab.dot(bp), ac.dot(bp));
+    if d3 >= zero && d4 <= d3 {
+        return b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= zero && d1 >= zero && d3 <= zero {
+        return a + ab * (d1 / (d1 - d3));
+    }
+    let cp = p.vector_from(c);
+    let (d5, d6) = (ab.dot(cp), ac.dot(cp));
+    if d6 >= zero && d5 <= d6 {
+        return c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= zero && d2 >= zero && d6 <= zero {
+        return a + ac * (d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= zero && d4 - d3 >= zero && d5 - d6 >= zero {
+        return b + c.vector_from(b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let denom = T::one() / (va + vb + vc);
+    a + ab * (vb * denom) + ac * (vc * denom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cuboid(x: f64, y: f64, z: f64, w: f64, h: f64, d: f64) -> TriMesh<f64> {
+        let v = (0..8)
+            .map(|i| Point3(
+                x + if i & 1 != 0 { w } else { 0.0 },
+                y + if i & 2 != 0 { h } else { 0.0 },
+                z + if i & 4 != 0 { d } else { 0.0 },
+            ))
+            .collect();
+        let t = vec![
+            [0, 2, 1], [1, 2, 3], [4, 5, 6], [5, 7, 6],
+            [0, 1, 4], [1, 5, 4], [2, 6, 3], [3, 6, 7],
+            [0, 4, 2], [2, 4, 6], [1, 3, 5], [3, 7, 5],
+        ];
+        TriMesh::new(v, t)
+    }
+
+    fn near(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn raycast() {
+        let c = cuboid(1.0, 2.0, 3.0, 2.0, 3.0, 4.0);
+        let hit = c.raycast(&Ray3::new(Point3(2.0, 3.0, 10.0), Vector3(0.0, 0.0, -2.0))).unwrap();
+        assert!(near(hit.t, 1.5) && hit.normal == Vector3(0.0, 0.0, 1.0));
+        let out = c.raycast(&Ray3::new(Point3(2.0, 3.0, 4.0), Vector3(-1.0, 0.0, 0.0))).unwrap();
+        assert!(near(out.t, 1.0) && out.normal == Vector3(-1.0, 0.0, 0.0));
+        assert!(c.raycast(&Ray3::new(Point3(2.0, 3.0, 10.0), Vector3(0.0, 0.0, 1.0))).is_none());
+    }
+
+    #[test]
+    fn volume_centroid() {
+        let c = cuboid(1.0, 2.0, 3.0, 2.0, 3.0, 4.0);
+        assert!(near(c.volume(), 24.0));
+        let p = c.centroid();
+        assert!(near(p.0, 2.0) && near(p.1, 3.5) && near(p.2, 5.0));
+    }
+
+    #[test]
+    fn bounding_spheres() {
+        let c = cuboid(1.0, 2.0, 3.0, 2.0, 3.0, 4.0);
+        let (m, r) = c.minimal_bounding_sphere().unwrap();
+        assert!(m.distance(Point3(2.0, 3.5, 5.0)) < 1e-9 && near(r, 29f64.sqrt() / 2.0));
+        let (q, s) = c.bounding_sphere().unwrap();
+        assert!(s >= r && s < r * 1.2);
+        assert!(c.vertices().iter().all(|p| p.distance(q) <= s + 1e-9));
+        assert_eq!(TriMesh::<f64>::new(Vec::new(), Vec::new()).minimal_bounding_sphere(), None);
+    }
+
+    #[test]
+    fn inertia_tensor() {
+        let (w, h, d) = (2.0, 3.0, 4.0);
+        let [cx, cy, cz] = cuboid(-7.0, 1.0, 5.0, w, h, d).inertia_tensor();
+        let m = w * h * d;
+        assert!(near(cx.0, m * (h * h + d * d) / 12.0));
+        assert!(near(cy.1, m * (w * w + d * d) / 12.0));
+        assert!(near(cz.2, m * (w * w + h * h) / 12.0));
+        assert!(near(cx.1, 0.0) && near(cx.2, 0.0) && near(cy.2, 0.0));
+    }
+
+    #[test]
+    fn slice() {
+        // a cube with a cubic void, whose faces point into it
+        let (outer, void) = (cuboid(0.0, 0.0, 0.0, 4.0, 4.0, 4.0), cuboid(1.0, 1.0, 1.0, 2.0, 2.0, 2.0));
+        let mut v = outer.vertices().to_vec();
+        v.extend_from_slice(void.vertices());
+        let mut t = outer.triangles().to_vec();
+        t.extend(void.triangles().iter().map(|&[a, b, c]| [a + 8, c + 8, b + 8]));
+        let plane = Plane3::new(Point3(0.0, 0.0, 2.0), Vector3(0.0, 0.0, 1.0));
+        let section = TriMesh::new(v, t).slice(&plane);
+        assert_eq!(section.len(), 1);
+        assert_eq!(section[0].holes().len(), 1);
+        assert!(near(section[0].area(), 12.0) && section[0].is_valid());
+        let solid = outer;
+        let section = solid.slice(&plane);
+        assert_eq!(section.len(), 1);
+        assert!(near(section[0].area(), 16.0) && section[0].signed_area() > 0.0);
+        // through the bottom face, whose vertices count as above
+        assert!(solid.slice(&Plane3::new(Point3(0.0, 0.0, 0.0), Vector3(0.0, 0.0, 1.0))).is_empty());
+        assert!(solid.slice(&Plane3::new(Point3(0.0, 0.0, 9.0), Vector3(0.0, 0.0, 1.0))).is_empty());
+    }
+
+    #[test]
+    fn uvs() {
+        let uv_area = |[a, b, c]: [Point2<f64>; 3]| (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
+        let mut c = cuboid(0.0, 0.0, 0.0, 1.0, 2.0, 3.0);
+        assert!(c.uvs().is_none());
+        c.project_uvs(&UvProjection::Box);
+        let uvs = c.uvs().unwrap();
+        assert_eq!(uvs.len(), 12);
+        assert!(uvs.iter().all(|&t| uv_area(t) > 0.0));
+        assert_eq!(uvs.iter().map(|&t| uv_area(t)).sum::<f64>(), 2.0 * 2.0 * (2.0 + 3.0 + 6.0));
+        let top = Plane3::new(Point3(0.0, 0.0, 5.0), Vector3(0.0, 0.0, 1.0));
+        c.project_uvs(&UvProjection::Planar(top));
+        let (u, v) = top.basis();
+        let uvs = c.uvs().unwrap();
+        assert_eq!(uvs[0][0], Point2(0.0, 0.0));
+        assert!((uvs[1][2].0 - Vector3(1.0, 2.0, 0.0).dot(u)).abs() < 1e-12 && (uvs[1][2].1 - Vector3(1.0, 2.0, 0.0).dot(v)).abs() < 1e-12);
+        // an open prism around the z axis
+        let n = 8;
+        let v = (0..2 * n).map(|i| {
+            let a = (i % n) as f64 / n as f64 * std::f64::consts::TAU;
+            Point3(a.cos(), a.sin(), (i / n) as f64)
+        }).collect();
+        let t = (0..n).flat_map(|k| {
+            let j = (k + 1) % n;
+            [[k, j, j + n], [k, j + n, k + n]]
+        }).collect();
+        let mut prism = TriMesh::new(v, t);
+        prism.project_uvs(&UvProjection::Cylindrical(Point3(0.0, 0.0, 0.0), Vector3(0.0, 0.0, 2.0)));
+        let uvs = prism.uvs().unwrap();
+        assert!(uvs.iter().all(|&t| uv_area(t) > 0.0 && t.iter().all(|q| (0.0..=1.0).contains(&q.0))));
+        assert!((uvs.iter().map(|&t| uv_area(t)).sum::<f64>() - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn from_soup() {
+        let c = cuboid(0.0, 0.0, 0.0, 1.0, 2.0, 3.0);
+        let jitter = |p: Point3<f64>, k: usize| Point3(p.0 + 1e-7 * (k % 3) as f64, p.1, p.2 - 1e-7 * (k % 2) as f64);
+        let soup: Vec<Triangle3<f64>> = c
+            .triangles()
+            .iter()
+            .enumerate()
+            .map(|(k, t)| {
+                let [a, b, c] = t.map(|i| jitter(c.vertices()[i], k));
+                Triangle3::new(a, b, c)
+            })
+            .collect();
+        let welded = TriMesh::from_soup(&soup, 1e-5);
+        assert_eq!((welded.vertices().len(), welded.triangles().len()), (8, 12));
+        assert!(near(welded.volume(), 6.0));
+        // first seen first, at its first position
+        assert_eq!(welded.vertices()[0], soup[0].vertices()[0]);
+        assert_eq!(welded.triangles()[0], [0, 1, 2]);
+        assert!(TriMesh::from_soup(&soup, 0.0).vertices().len() > 8);
+        let exact: Vec<_> = c.triangles().iter().map(|t| {
+            let [a, b, c] = t.map(|i| c.vertices()[i]);
+            Triangle3::new(a, b, c)
+        }).collect();
+        let exact = TriMesh::from_soup(&exact, 0.0);
+        assert_eq!(exact.vertices().len(), 8);
+        assert_eq!(exact.vertices()[1], c.vertices()[2]);
+        // a sliver welded flat is dropped
+        let sliver = Triangle3::new(Point3(0.0, 0.0, 0.0), Point3(1.0, 0.0, 0.0), Point3(1.0, 1e-9, 0.0));
+        assert!(TriMesh::from_soup(&[sliver], 1e-6).triangles().is_empty());
+        assert_eq!(soup[0].area(), 1.0);
+    }
+
+    #[test]
+    fn tetrahedron() {
+        let t = TriMesh::new(
+            vec![Point3(0.0, 0.0, 0.0), Point3(1.0, 0.0, 0.0), Point3(0.0, 1.0, 0.0), Point3(0.0, 0.0, 1.0)],
+            vec![[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]],
+        );
+        assert!(near(t.volume(), 1.0 / 6.0));
+        let c = t.centroid();
+        assert!(near(c.0, 0.25) && near(c.1, 0.25) && near(c.2, 0.25));
+        // products of inertia of the unit corner tetrahedron about its centroid
+        let [cx, _, _] = t.inertia_tensor();
+        assert!(near(cx.0, 1.0 / 80.0));
+        assert!(near(cx.1, 1.0 / 480.0));
+    }
+}
@@ -0,0 +1,158 @@
+//! Shape morphing between polygons
+
+use crate::{ Float, Point2, Polygon2 };
+
+/// Vertex pairing between two exterior rings, for interpolating one into
+/// the other: each ring keeps its own vertices and gains the other's,
+/// matched by fraction of perimeter from the best aligned starts
+#[derive(Debug, Clone, PartialEq)]
+pub struct Morph2<T> {
+    pairs: Vec<(Point2<T>, Point2<T>)>,
+}
+
+impl<T: Float> Morph2<T> {
+    /// new: &Polygon × &Polygon -> Morph
+    /// holes are dropped; both rings are taken counter-clockwise
+    pub fn new(a: &Polygon2<T>, b: &Polygon2<T>) -> Self {
+        let (a, b) = (counter_clockwise(a.vertices()), counter_clockwise(b.vertices()));
+        if a.is_empty() || b.is_empty() {
+            return Morph2 { pairs: Vec::new() };
+        }
+        let (ua, ub) = (stations(&a), stations(&b));
+        let offset = alignment(&a, &ua, &b, &ub);
+        let mut us = ua.clone();
+        us.extend(ub.iter().map(|&u| wrap(u - offset)));
+        us.sort_by(|x, y| x.partial_cmp(y).unwrap_or(std::cmp::Ordering::Equal));
+        let tolerance = T::epsilon() * T::from_f64(64.0);
+        us.dedup_by(|x, y| (*x - *y).abs() <= tolerance);
+        let pairs = us.into_iter().map(|u| (at(&a, &ua, u), at(&b, &ub, wrap(u + offset)))).collect();
+        Morph2 { pairs }
+    }
+
+    /// len: &Morph -> usize
+    /// vertices of every in-between polygon
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// is_empty: &Morph -> bool
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// at: &Morph × K -> Polygon
+    /// the first polygon at 0, the second at 1, each point moving straight
+    pub fn at(&self, t: T) -> Polygon2<T> {
+        Polygon2::new(self.pairs.iter().map(|&(p, q)| p + q.vector_from(p) * t).collect())
+    }
+}
+
+/// morph: &Polygon × &Polygon × K -> Polygon
+/// `Morph2::new(a, b).at(t)`; build the `Morph2` once to animate
+pub fn morph<T: Float>(a: &Polygon2<T>, b: &Polygon2<T>, t: T) -> Polygon2<T> {
+    Morph2::new(a, b).at(t)
+}
+
+fn counter_clockwise<T: Float>(ring: &[Point2<T>]) -> Vec<Point2<T>> {
+    let mut ring = ring.to_vec();
+    if Polygon2::new(ring.clone()).signed_area() < T::zero() {
+        ring.reverse();
+    }
+    ring
+}
+
+/// Fraction of the perimeter at each vertex, from 0; evenly spread for
+/// rings of no length
+fn stations<T: Float>(ring: &[Point2<T>]) -> Vec<T> {
+    let n = ring.len();
+    let mut us = Vec::with_capacity(n);
+    let mut total = T::zero();
+    for i in 0..n {
+        us.push(total);
+        total = total + ring[i].distance(ring[(i + 1) % n]);
+    }
+    if total > T::zero() {
+        us.iter_mut().for_each(|u| *u = *u / total);
+    } else {
+        us.iter_mut().enumerate().for_each(|(i, u)| *u = T::from_f64(i as f64 / n as f64));
+    }
+    us
+}
+
+/// Point at a fraction of the perimeter in [0, 1)
+fn at<T: Float>(ring: &[Point2<T>], us: &[T], u: T) -> Point2<T> {
+    let n = ring.len();
+    let i = us.partition_point(|&s| s <= u).max(1) - 1;
+    let end = if i + 1 < n { us[i + 1] } else { T::one() };
+    let (p, q) = (ring[i], ring[(i + 1) % n]);
+    if end > us[i] {
+        p + q.vector_from(p) * ((u - us[i]) / (end - us[i]))
+    } else {
+        p
+    }
+}
+
+/// Into [0, 1)
+fn wrap<T: Float>(u: T) -> T {
+    let w = u - u.floor();
+    if w >= T::one() { T::zero() } else { w }
+}
+
+/// Shift of the second ring's start, as a fraction of its perimeter, least
+/// squares closest to the first once both are centred
+fn alignment<T: Float>(a: &[Point2<T>], ua: &[T], b: &[Point2<T>], ub: &[T]) -> T {
+    let n = 4 * a.len().max(b.len());
+    let sample = |ring: &[Point2<T>], us: &[T]| {
+        let ps: Vec<Point2<T>> = (0..n).map(|k| at(ring, us, T::from_f64(k as f64 / n as f64))).collect();
+        let scale = T::one() / T::from_f64(n as f64);
+        let mean = ps.iter().fold(Point2(T::zero(), T::zero()), |m, &p| Point2(m.0 + p.0 * scale, m.1 + p.1 * scale));
+        ps.into_iter().map(|p| p.vector_from(mean)).collect::<Vec<_>>()
+    };
+    let (sa, sb) = (sample(a, ua), sample(b, ub));
+    let cost = |k: usize| (0..n).fold(T::zero(), |c, i| {
+        let d = sa[i] - sb[(i + k) % n];
+        c + d.dot(d)
+    });
+    let best = (0..n).map(|k| (k, cost(k))).fold((0, T::infinity()), |best, c| if c.1 < best.1 { c } else { best });
+    T::from_f64(best.0 as f64 / n as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(start: usize) -> Polygon2<f64> {
+        let corners = [Point2(0.0, 0.0), Point2(2.0, 0.0), Point2(2.0, 2.0), Point2(0.0, 2.0)];
+        Polygon2::new((0..4).map(|i| corners[(i + start) % 4]).collect())
+    }
+
+    #[test]
+    fn aligned_starts() {
+        // the same square listed from another corner and the other way round
+        let b = Polygon2::new(square(2).vertices().iter().rev().copied().collect());
+        let m = Morph2::new(&square(0), &b);
+        assert_eq!(m.len(), 4);
+        for t in [0.0, 0.3, 1.0] {
+            assert!((m.at(t).area() - 4.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn triangle_to_square() {
+        let tri = Polygon2::new(vec![Point2(1.0, 0.0), Point2(1.0, 3.0), Point2(-2.0, 0.0)]);
+        let m = Morph2::new(&tri, &square(1));
+        // vertices of each, less those meeting at the same fraction
+        assert!((4..=7).contains(&m.len()));
+        assert!((m.at(0.0).area() - 4.5).abs() < 1e-12);
+        assert!((m.at(1.0).area() - 4.0).abs() < 1e-12);
+        for (t, p) in [(0.0, &tri), (1.0, &square(0))] {
+            for v in p.vertices() {
+                assert!(m.at(t).vertices().iter().any(|q| q.distance(*v) < 1e-12));
+            }
+        }
+        let half = morph(&tri, &square(1), 0.5);
+        assert_eq!(half.vertices().len(), m.len());
+        assert!(half.signed_area() > 0.0);
+        assert!(Morph2::new(&tri, &Polygon2::new(Vec::new())).is_empty());
+    }
+}
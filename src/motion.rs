@@ -0,0 +1,190 @@
+//! Regions swept by shapes translating along a path
+//!
+//! A convex shape moved without turning along a path covers the shape
+//! summed with the path. Along a segment that is the hull of the shape at
+//! both ends. An arc is cut into pieces, each held in the triangle of its
+//! ends and their tangents' meeting point, and the hulls of the shape at
+//! those three corners are merged: the region found contains the true one
+//! and strays at most `tolerance` past it, as clearance checks want.
+
+use std::cmp::Ordering;
+
+use crate::{ Arc2, Circle2, Curve2, Float, MultiPolygon2, Point2, Polygon2, Rect2, Segment2, Vector2 };
+use crate::polygon::union_all;
+
+/// Path followed by a moving shape's reference point; the shape is given
+/// where the path starts
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Motion2<T> {
+    /// straight, from the segment's start to its end
+    Linear(Segment2<T>),
+    /// around the arc's center, without turning the shape
+    Circular(Arc2<T>),
+}
+
+/// Shapes whose sweep along a motion can be bounded
+pub trait Swept2<T> {
+    /// swept: &Shape × &Motion × K -> MultiPolygon
+    /// everything the shape covers on the way, within `tolerance`
+    fn swept(&self, motion: &Motion2<T>, tolerance: T) -> MultiPolygon2<T>;
+}
+
+/// Convex polygons sweep exactly along segments; others sweep as their
+/// convex hull, so the region still contains the true one
+impl<T: Float> Swept2<T> for Polygon2<T> {
+    fn swept(&self, motion: &Motion2<T>, tolerance: T) -> MultiPolygon2<T> {
+        sweep(self.vertices(), motion, tolerance)
+    }
+}
+
+/// Exact along segments
+impl<T: Float> Swept2<T> for Rect2<T> {
+    fn swept(&self, motion: &Motion2<T>, tolerance: T) -> MultiPolygon2<T> {
+        let (a, b) = (self.min(), self.max());
+        sweep(&[a, Point2(b.0, a.1), b, Point2(a.0, b.1)], motion, tolerance)
+    }
+}
+
+/// The circle is replaced by a polygon around it, half the tolerance each
+impl<T: Float> Swept2<T> for Circle2<T> {
+    fn swept(&self, motion: &Motion2<T>, tolerance: T) -> MultiPolygon2<T> {
+        let half = tolerance * T::from_f64(0.5);
+        let (c, r) = (self.center(), self.radius());
+        let n = steps(r, T::pi() * T::from_f64(2.0), half).max(3);
+        // vertices at r / cos(π / n) put the edges on the circle
+        let step = T::pi() * T::from_f64(2.0) / T::from_f64(n as f64);
+        let outer = r / (step * T::from_f64(0.5)).cos();
+        let ring: Vec<Point2<T>> = (0..n).map(|k| {
+            let a = step * T::from_f64(k as f64);
+            c + Vector2(a.cos(), a.sin()) * outer
+        }).collect();
+        sweep(&ring, motion, half)
+    }
+}
+
+/// Pieces a turn of the given radius and angle is cut into, so that the
+/// tangents' meeting points are within `tolerance` of it; capped at 4096
+fn steps<T: Float>(radius: T, angle: T, tolerance: T) -> usize {
+    let angle = angle.abs();
+    if radius <= T::zero() || angle == T::zero() {
+        return 1;
+    }
+    if tolerance <= T::zero() || !tolerance.is_finite() {
+        return 4096;
+    }
+    // R (1 / cos(Δ/2) − 1) ≤ tolerance
+    let c = radius / (radius + tolerance);
+    let max_step = T::from_f64(2.0) * (T::one() - c * c).sqrt().atan2(c);
+    if max_step <= T::zero() {
+        return 4096;
+    }
+    let n = (angle / max_step).to_f64().ceil();
+    if n.is_finite() { (n as usize).clamp(1, 4096) } else { 4096 }
+}
+
+/// Sweep of the hull of the points
+fn sweep<T: Float>(shape: &[Point2<T>], motion: &Motion2<T>, tolerance: T) -> MultiPolygon2<T> {
+    if shape.is_empty() {
+        return MultiPolygon2::new(Vec::new());
+    }
+    let placed = |offsets: &[Vector2<T>]| {
+        let points: Vec<Point2<T>> = offsets.iter().flat_map(|&d| shape.iter().map(move |&p| p + d)).collect();
+        Polygon2::new(hull(points))
+    };
+    let zero = Vector2(T::zero(), T::zero());
+    match motion {
+        Motion2::Linear(s) => MultiPolygon2::new(vec![placed(&[zero, s.end().vector_from(s.start())])]),
+        Motion2::Circular(a) => {
+            let n = steps(a.radius(), a.sweep(), tolerance);
+            let start = a.point_at(T::zero());
+            let half = a.sweep() / T::from_f64(2.0 * n as f64);
+            let reach = T::one() / half.cos();
+            let pieces: Vec<Polygon2<T>> = (0..n).map(|k| {
+                let t = |k: f64| T::from_f64(k / n as f64);
+                let (p, q) = (a.point_at(t(k as f64)), a.point_at(t(k as f64 + 1.0)));
+                // tangents from both ends meet beyond the piece's middle
+                let m = a.point_at(t(k as f64 + 0.5));
+                let apex = a.center() + m.vector_from(a.center()) * reach;
+                placed(&[p.vector_from(start), apex.vector_from(start), q.vector_from(start)])
+            }).collect();
+            union_all(&pieces)
+        }
+    }
+}
+
+/// Convex hull, counter-clockwise without collinear vertices (monotone chain)
+pub(crate) fn hull<T: Float>(mut points: Vec<Point2<T>>) -> Vec<Point2<T>> {
+    points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal).then(a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    let turn = |o: Point2<T>, a: Point2<T>, b: Point2<T>| {
+        let (u, v) = (a.vector_from(o), b.vector_from(o));
+        u.0 * v.1 - u.1 * v.0
+    };
+    let mut chain: Vec<Point2<T>> = Vec::with_capacity(2 * points.len());
+    for pass in [points.clone(), points.into_iter().rev().collect()] {
+        let floor = chain.len();
+        for p in pass {
+            while chain.len() >= floor + 2 && turn(chain[chain.len() - 2], chain[chain.len() - 1], p) <= T::zero() {
+                chain.pop();
+            }
+            chain.push(p);
+        }
+        chain.pop();
+    }
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FillRule;
+    use std::f64::consts::PI;
+
+    fn covers(region: &MultiPolygon2<f64>, p: Point2<f64>) -> bool {
+        region.polygons().iter().any(|q| q.contains(p, FillRule::NonZero))
+    }
+
+    #[test]
+    fn along_segments() {
+        let path = Motion2::Linear(Segment2::new(Point2(0.0, 0.0), Point2(3.0, 4.0)));
+        let square = Rect2::new(Point2(0.0, 0.0), Point2(1.0, 1.0));
+        // the square's width across the motion is 1.4
+        let s = square.swept(&path, 1e-3);
+        assert_eq!(s.len(), 1);
+        assert!((s.area() - (1.0 + 5.0 * 1.4)).abs() < 1e-12);
+        let triangle = Polygon2::new(vec![Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(0.0, 1.0)]);
+        let t = triangle.swept(&path, 1e-3);
+        assert!(covers(&t, Point2(3.2, 4.2)) && !covers(&t, Point2(3.9, 4.9)));
+        // a capsule: exact area πr² + 2r|d|, from the outside
+        let c = Circle2::new(Point2(0.0, 0.0), 0.5).swept(&path, 1e-3);
+        let exact = PI * 0.25 + 5.0;
+        assert!(c.area() >= exact && c.area() < exact + 1e-3 * 10.0);
+    }
+
+    #[test]
+    fn along_arcs() {
+        // a unit circle a quarter turn around the origin at radius 5
+        let arc = Arc2::new(Point2(0.0, 0.0), 5.0, 0.0, PI / 2.0);
+        let circle = Circle2::new(Point2(5.0, 0.0), 1.0);
+        let region = circle.swept(&Motion2::Circular(arc), 1e-2);
+        assert_eq!(region.len(), 1);
+        // annular sector and two half discs
+        let exact = 2.0 * 5.0 * 1.0 * PI / 2.0 + PI;
+        assert!(region.area() >= exact && region.area() < exact + 1e-2 * 20.0);
+        for k in 0..=40 {
+            let a = k as f64 / 40.0 * PI / 2.0;
+            for r in [4.01, 5.0, 5.99] {
+                assert!(covers(&region, Point2(r * a.cos(), r * a.sin())));
+            }
+            assert!(!covers(&region, Point2(3.9 * a.cos(), 3.9 * a.sin())));
+            assert!(!covers(&region, Point2(6.1 * a.cos(), 6.1 * a.sin())));
+        }
+        let square = Rect2::new(Point2(4.5, -0.5), Point2(5.5, 0.5));
+        let backwards = Arc2::new(Point2(0.0, 0.0), 5.0, 0.0, -PI);
+        let s = square.swept(&Motion2::Circular(backwards), 1e-3);
+        assert!(covers(&s, Point2(-5.0, 0.4)) && covers(&s, Point2(0.0, -5.0)) && !covers(&s, Point2(0.0, 5.0)));
+    }
+}
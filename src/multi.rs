@@ -0,0 +1,251 @@
+//! Collections of geometries
+
+use crate::{ Bounded2, Float, Point2, Polygon2, Polyline2, Rect2 };
+use crate::anchor::impl_anchored;
+use crate::polygon::nesting;
+
+/// 2d MultiPoint: unconnected points forming one shape
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiPoint2<T>(Vec<Point2<T>>);
+
+/// 2d MultiPolyline: unconnected polylines forming one shape
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiPolyline2<T>(Vec<Polyline2<T>>);
+
+/// 2d MultiPolygon: disjoint polygons forming one shape
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiPolygon2<T>(Vec<Polygon2<T>>);
+
+/// Any 2d geometry a collection may hold
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry2<T> {
+    /// single point
+    Point(Point2<T>),
+    /// single open chain
+    Polyline(Polyline2<T>),
+    /// single polygon
+    Polygon(Polygon2<T>),
+    /// several points
+    MultiPoint(MultiPoint2<T>),
+    /// several open chains
+    MultiPolyline(MultiPolyline2<T>),
+    /// several polygons
+    MultiPolygon(MultiPolygon2<T>),
+    /// nested collection
+    Collection(GeometryCollection2<T>),
+}
+
+/// 2d GeometryCollection: geometries of mixed kinds
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeometryCollection2<T>(Vec<Geometry2<T>>);
+
+macro_rules! impl_multi {
+    ($name:ident, $member:ty, $members:ident) => {
+        impl<T> $name<T> {
+            /// new: [Member] -> Multi
+            pub fn new(members: Vec<$member>) -> Self {
+                $name(members)
+            }
+
+            #[doc = concat!(stringify!($members), ": &Multi -> &[Member]")]
+            pub fn $members(&self) -> &[$member] {
+                &self.0
+            }
+
+            /// iter: &Multi -> Iterator<&Member>
+            pub fn iter(&self) -> std::slice::Iter<'_, $member> {
+                self.0.iter()
+            }
+
+            /// len: &Multi -> usize
+            pub fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            /// is_empty: &Multi -> bool
+            pub fn is_empty(&self) -> bool {
+                self.0.is_empty()
+            }
+
+            /// push: &mut Multi × Member -> ()
+            pub fn push(&mut self, member: $member) {
+                self.0.push(member)
+            }
+        }
+
+        impl<'a, T> IntoIterator for &'a $name<T> {
+            type Item = &'a $member;
+            type IntoIter = std::slice::Iter<'a, $member>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.iter()
+            }
+        }
+
+        impl<T> IntoIterator for $name<T> {
+            type Item = $member;
+            type IntoIter = std::vec::IntoIter<$member>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.into_iter()
+            }
+        }
+
+        impl<T> FromIterator<$member> for $name<T> {
+            fn from_iter<I: IntoIterator<Item = $member>>(iter: I) -> Self {
+                $name(iter.into_iter().collect())
+            }
+        }
+
+        impl<T: Float> Bounded2<T> for $name<T> {
+            fn bounds(&self) -> Option<Rect2<T>> {
+                self.0.iter().filter_map(|m| m.bounds()).reduce(Rect2::union)
+            }
+        }
+
+        impl_anchored!($name, |s, m| $name(s.0.iter().map(|x| x.mapped(m)).collect()));
+    };
+}
+
+impl_multi!(MultiPoint2, Point2<T>, points);
+impl_multi!(MultiPolyline2, Polyline2<T>, polylines);
+impl_multi!(MultiPolygon2, Polygon2<T>, polygons);
+impl_multi!(GeometryCollection2, Geometry2<T>, geometries);
+
+impl<T: Float> MultiPolyline2<T> {
+    /// length: &MultiPolyline -> K
+    pub fn length(&self) -> T {
+        self.0.iter().fold(T::zero(), |a, l| a + l.length())
+    }
+}
+
+impl<T: Float> MultiPolygon2<T> {
+    /// from_rings: [[Point]] -> MultiPolygon
+    /// rings at even depth of their nesting become counter-clockwise
+    /// exteriors, their children clockwise holes
+    pub fn from_rings(rings: Vec<Vec<Point2<T>>>) -> Self {
+        let parents = nesting(&rings);
+        let depth = |mut i: usize| {
+            let mut d = 0;
+            while let Some(p) = parents[i] {
+                d += 1;
+                i = p;
+            }
+            d
+        };
+        let oriented = |ring: &Vec<Point2<T>>, ccw: bool| {
+            let mut ring = ring.clone();
+            if (Polygon2::new(ring.clone()).signed_area() > T::zero()) != ccw {
+                ring.reverse();
+            }
+            ring
+        };
+        let mut children = vec![Vec::new(); rings.len()];
+        for (i, p) in parents.iter().enumerate() {
+            if let Some(p) = *p {
+                children[p].push(i);
+            }
+        }
+        let exteriors: Vec<usize> = (0..rings.len()).filter(|&i| depth(i) % 2 == 0).collect();
+        MultiPolygon2(exteriors.into_iter().map(|e| {
+            let holes = children[e].iter().map(|&i| oriented(&rings[i], false)).collect();
+            Polygon2::with_holes(oriented(&rings[e], true), holes)
+        }).collect())
+    }
+
+    /// area: &MultiPolygon -> K
+    pub fn area(&self) -> T {
+        self.0.iter().fold(T::zero(), |a, p| a + p.area())
+    }
+
+    /// centroid: &MultiPolygon -> Point
+    /// area-weighted centroid of the members;
+    /// not finite when the total area is zero
+    pub fn centroid(&self) -> Point2<T> {
+        let (a, x, y) = self.0.iter().fold((T::zero(), T::zero(), T::zero()), |(a, x, y), p| {
+            let (w, c) = (p.area(), p.centroid());
+            (a + w, x + c.0 * w, y + c.1 * w)
+        });
+        Point2(x / a, y / a)
+    }
+}
+
+impl<T: Float> Geometry2<T> {
+    /// area: &Geometry -> K
+    /// zero for points and polylines
+    pub fn area(&self) -> T {
+        match self {
+            Geometry2::Polygon(p) => p.area(),
+            Geometry2::MultiPolygon(m) => m.area(),
+            Geometry2::Collection(c) => c.area(),
+            _ => T::zero(),
+        }
+    }
+}
+
+impl<T: Float> GeometryCollection2<T> {
+    /// area: &GeometryCollection -> K
+    pub fn area(&self) -> T {
+        self.0.iter().fold(T::zero(), |a, g| a + g.area())
+    }
+}
+
+impl<T: Float> Bounded2<T> for Geometry2<T> {
+    fn bounds(&self) -> Option<Rect2<T>> {
+        match self {
+            Geometry2::Point(p) => p.bounds(),
+            Geometry2::Polyline(l) => l.bounds(),
+            Geometry2::Polygon(p) => p.bounds(),
+            Geometry2::MultiPoint(m) => m.bounds(),
+            Geometry2::MultiPolyline(m) => m.bounds(),
+            Geometry2::MultiPolygon(m) => m.bounds(),
+            Geometry2::Collection(c) => c.bounds(),
+        }
+    }
+}
+
+impl_anchored!(Geometry2, |g, m| match g {
+    Geometry2::Point(p) => Geometry2::Point(p.mapped(m)),
+    Geometry2::Polyline(l) => Geometry2::Polyline(l.mapped(m)),
+    Geometry2::Polygon(p) => Geometry2::Polygon(p.mapped(m)),
+    Geometry2::MultiPoint(mp) => Geometry2::MultiPoint(mp.mapped(m)),
+    Geometry2::MultiPolyline(ml) => Geometry2::MultiPolyline(ml.mapped(m)),
+    Geometry2::MultiPolygon(mp) => Geometry2::MultiPolygon(mp.mapped(m)),
+    Geometry2::Collection(c) => Geometry2::Collection(c.mapped(m)),
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f64, size: f64) -> Polygon2<f64> {
+        Polygon2::new(vec![Point2(x, 0.0), Point2(x + size, 0.0), Point2(x + size, size), Point2(x, size)])
+    }
+
+    #[test]
+    fn aggregates() {
+        let polygons: MultiPolygon2<f64> = [square(0.0, 1.0), square(3.0, 2.0)].into_iter().collect();
+        assert_eq!(polygons.area(), 5.0);
+        assert_eq!(polygons.bounds(), Some(Rect2::new(Point2(0.0, 0.0), Point2(5.0, 2.0))));
+        let lines = MultiPolyline2::new(vec![
+            Polyline2::new(vec![Point2(0.0, 0.0), Point2(3.0, 4.0)]),
+            Polyline2::new(vec![Point2(-1.0, 0.0), Point2(-1.0, 1.0), Point2(-2.0, 1.0)]),
+        ]);
+        assert_eq!(lines.length(), 7.0);
+        assert_eq!(lines.iter().count(), 2);
+        assert!(MultiPoint2::<f64>::new(Vec::new()).bounds().is_none());
+    }
+
+    #[test]
+    fn collection() {
+        let mut c = GeometryCollection2::new(vec![
+            Geometry2::Point(Point2(-1.0, 7.0)),
+            Geometry2::Polygon(square(0.0, 2.0)),
+            Geometry2::MultiPolygon(MultiPolygon2::new(vec![square(4.0, 1.0)])),
+        ]);
+        c.push(Geometry2::Collection(GeometryCollection2::new(vec![Geometry2::Polygon(square(6.0, 1.0))])));
+        assert_eq!(c.area(), 6.0);
+        assert_eq!(c.bounds(), Some(Rect2::new(Point2(-1.0, 0.0), Point2(7.0, 7.0))));
+        assert_eq!((&c).into_iter().filter(|g| g.area() > 0.0).count(), 3);
+    }
+}
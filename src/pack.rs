@@ -0,0 +1,268 @@
+//! Packing of rectangles into bins
+
+use std::cmp::Ordering;
+
+use crate::{ Affine2, Float, Point2, Rect2, Vector2 };
+
+/// Rule choosing where a rectangle goes in a bin
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Packing {
+    /// on the skyline of what is packed, as low as possible, then leftmost
+    Skyline,
+    /// in the free maximal rectangle it leaves the least space along its shorter side
+    MaxRects,
+}
+
+/// Where an item went
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Placement<T> {
+    /// index into the items
+    pub item: usize,
+    /// index of the bin, in order of opening
+    pub bin: usize,
+    /// takes the item rectangle to its place in the bin
+    pub transform: Affine2<T>,
+    /// turned a quarter counter-clockwise
+    pub rotated: bool,
+}
+
+/// pack: [Rect] × Vector × Packing × bool -> [Placement]
+/// items, largest first, go into the first bin of size `bin` (spanning from
+/// the origin) with room, a new bin being opened when none has; quarter turns
+/// are tried if `rotate`. Placements are sorted by item; items too large for
+/// an empty bin are left out
+pub fn pack<T: Float>(items: &[Rect2<T>], bin: Vector2<T>, packing: Packing, rotate: bool) -> Vec<Placement<T>> {
+    let size = |r: &Rect2<T>| r.max().vector_from(r.min());
+    let side = |r: &Rect2<T>| {
+        let s = size(r);
+        if s.0 > s.1 { s.0 } else { s.1 }
+    };
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|&i, &j| side(&items[j]).partial_cmp(&side(&items[i])).unwrap_or(Ordering::Equal));
+    let mut bins: Vec<Bin<T>> = Vec::new();
+    let mut placements = Vec::new();
+    for i in order {
+        let s = size(&items[i]);
+        let found = bins
+            .iter_mut()
+            .enumerate()
+            .find_map(|(k, b)| b.insert(s, rotate).map(|spot| (k, spot)))
+            .or_else(|| {
+                let mut fresh = Bin::new(packing, bin);
+                let spot = fresh.insert(s, rotate)?;
+                bins.push(fresh);
+                Some((bins.len() - 1, spot))
+            });
+        let Some((k, (p, rotated))) = found else { continue };
+        let m = items[i].min();
+        let (zero, one) = (T::zero(), T::one());
+        let transform = if rotated {
+            Affine2::new([Vector2(zero, one), Vector2(-one, zero)], Vector2(p.0 + m.1 + s.1, p.1 - m.0))
+        } else {
+            Affine2::new([Vector2(one, zero), Vector2(zero, one)], Vector2(p.0 - m.0, p.1 - m.1))
+        };
+        placements.push(Placement { item: i, bin: k, transform, rotated });
+    }
+    placements.sort_by_key(|p| p.item);
+    placements
+}
+
+/// Space left in one bin
+#[derive(Debug, Clone)]
+enum Bin<T> {
+    /// width of the bin, top edge as (x, y, width) pieces left to right
+    Skyline(T, T, Vec<(T, T, T)>),
+    /// free maximal rectangles, possibly overlapping
+    MaxRects(Vec<Rect2<T>>),
+}
+
+impl<T: Float> Bin<T> {
+    fn new(packing: Packing, size: Vector2<T>) -> Self {
+        let zero = T::zero();
+        match packing {
+            Packing::Skyline => Bin::Skyline(size.0, size.1, vec![(zero, zero, size.0)]),
+            Packing::MaxRects => Bin::MaxRects(vec![Rect2::new(Point2(zero, zero), Point2(size.0, size.1))]),
+        }
+    }
+
+    /// Lower left corner given to a rectangle of size s, and whether it is turned
+    fn insert(&mut self, s: Vector2<T>, rotate: bool) -> Option<(Point2<T>, bool)> {
+        let turns: &[bool] = if rotate { &[false, true] } else { &[false] };
+        match self {
+            Bin::Skyline(width, height, line) => {
+                let mut best: Option<(T, T, bool)> = None;
+                for &turned in turns {
+                    let (w, h) = if turned { (s.1, s.0) } else { (s.0, s.1) };
+                    for k in 0..line.len() {
+                        let Some(y) = skyline_fit(line, k, w, *width) else { continue };
+                        let top = y + h;
+                        if top > *height {
+                            continue;
+                        }
+                        let better = best.is_none_or(|(t, x, _)| top < t || (top == t && line[k].0 < x));
+                        if better {
+                            best = Some((top, line[k].0, turned));
+                        }
+                    }
+                }
+                let (top, x, turned) = best?;
+                let (w, h) = if turned { (s.1, s.0) } else { (s.0, s.1) };
+                let y = top - h;
+                skyline_raise(line, x, w, top);
+                Some((Point2(x, y), turned))
+            }
+            Bin::MaxRects(free) => {
+                let mut best: Option<(T, T, Point2<T>, bool)> = None;
+                for &turned in turns {
+                    let (w, h) = if turned { (s.1, s.0) } else { (s.0, s.1) };
+                    for r in free.iter() {
+                        let room = r.max().vector_from(r.min());
+                        if room.0 < w || room.1 < h {
+                            continue;
+                        }
+                        let (a, b) = (room.0 - w, room.1 - h);
+                        let (short, long) = if a < b { (a, b) } else { (b, a) };
+                        let better = best.is_none_or(|(s, l, _, _)| short < s || (short == s && long < l));
+                        if better {
+                            best = Some((short, long, r.min(), turned));
+                        }
+                    }
+                }
+                let (_, _, p, turned) = best?;
+                let (w, h) = if turned { (s.1, s.0) } else { (s.0, s.1) };
+                split_free(free, Rect2::new(p, Point2(p.0 + w, p.1 + h)));
+                Some((p, turned))
+            }
+        }
+    }
+}
+
+/// Height a piece of width w starting at skyline piece k rests at
+fn skyline_fit<T: Float>(line: &[(T, T, T)], k: usize, w: T, width: T) -> Option<T> {
+    let x = line[k].0;
+    if x + w > width {
+        return None;
+    }
+    let mut y = T::zero();
+    for &(px, py, _) in &line[k..] {
+        if px >= x + w {
+            break;
+        }
+        if py > y {
+            y = py;
+        }
+    }
+    Some(y)
+}
+
+/// Lifts the skyline to `top` over [x, x + w]
+fn skyline_raise<T: Float>(line: &mut Vec<(T, T, T)>, x: T, w: T, top: T) {
+    let end = x + w;
+    let mut raised = Vec::with_capacity(line.len() + 2);
+    for &(px, py, pw) in line.iter() {
+        let pe = px + pw;
+        if pe <= x || px >= end {
+            raised.push((px, py, pw));
+            continue;
+        }
+        if px < x {
+            raised.push((px, py, x - px));
+        }
+        if px <= x {
+            raised.push((x, top, w));
+        }
+        if pe > end {
+            raised.push((end, py, pe - end));
+        }
+    }
+    raised.dedup_by(|b, a| {
+        let joins = a.1 == b.1 && a.0 + a.2 == b.0;
+        if joins {
+            a.2 = a.2 + b.2;
+        }
+        joins
+    });
+    *line = raised;
+}
+
+/// Carves a used rectangle out of the free ones, keeping them maximal
+fn split_free<T: Float>(free: &mut Vec<Rect2<T>>, used: Rect2<T>) {
+    let (u0, u1) = (used.min(), used.max());
+    let mut pieces = Vec::new();
+    for r in free.drain(..) {
+        let (r0, r1) = (r.min(), r.max());
+        if u0.0 >= r1.0 || u1.0 <= r0.0 || u0.1 >= r1.1 || u1.1 <= r0.1 {
+            pieces.push(r);
+            continue;
+        }
+        if u0.0 > r0.0 {
+            pieces.push(Rect2::new(r0, Point2(u0.0, r1.1)));
+        }
+        if u1.0 < r1.0 {
+            pieces.push(Rect2::new(Point2(u1.0, r0.1), r1));
+        }
+        if u0.1 > r0.1 {
+            pieces.push(Rect2::new(r0, Point2(r1.0, u0.1)));
+        }
+        if u1.1 < r1.1 {
+            pieces.push(Rect2::new(Point2(r0.0, u1.1), r1));
+        }
+    }
+    let inside = |a: &Rect2<T>, b: &Rect2<T>| b.contains(a.min()) && b.contains(a.max());
+    for (i, r) in pieces.iter().enumerate() {
+        let covered = pieces.iter().enumerate().any(|(j, s)| j != i && inside(r, s) && (!inside(s, r) || j < i));
+        if !covered {
+            free.push(*r);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placed(items: &[Rect2<f64>], p: &Placement<f64>) -> Rect2<f64> {
+        let r = items[p.item];
+        Rect2::new(p.transform.transform_point(r.min()), p.transform.transform_point(r.max()))
+    }
+
+    fn check(items: &[Rect2<f64>], bin: Vector2<f64>, placements: &[Placement<f64>]) {
+        for (k, p) in placements.iter().enumerate() {
+            let r = placed(items, p);
+            assert!(r.min().0 >= 0.0 && r.min().1 >= 0.0 && r.max().0 <= bin.0 && r.max().1 <= bin.1);
+            for q in &placements[k + 1..] {
+                let s = placed(items, q);
+                let apart = r.max().0 <= s.min().0 || s.max().0 <= r.min().0 || r.max().1 <= s.min().1 || s.max().1 <= r.min().1;
+                assert!(p.bin != q.bin || apart);
+            }
+        }
+    }
+
+    #[test]
+    fn packs_without_overlap() {
+        let sizes = [(4.0, 3.0), (2.0, 5.0), (6.0, 1.0), (3.0, 3.0), (1.0, 1.0), (5.0, 2.0), (2.0, 2.0), (7.0, 1.0)];
+        let items: Vec<_> = sizes.iter().enumerate().map(|(k, &(w, h))| {
+            let o = Point2(k as f64, -(k as f64));
+            Rect2::new(o, Point2(o.0 + w, o.1 + h))
+        }).collect();
+        let bin = Vector2(8.0, 8.0);
+        for packing in [Packing::Skyline, Packing::MaxRects] {
+            for rotate in [false, true] {
+                let placements = pack(&items, bin, packing, rotate);
+                assert_eq!(placements.len(), items.len());
+                assert!(placements.iter().enumerate().all(|(k, p)| p.item == k));
+                check(&items, bin, &placements);
+            }
+        }
+    }
+
+    #[test]
+    fn bins_and_rotation() {
+        let items = vec![Rect2::new(Point2(0.0, 0.0), Point2(1.0, 4.0)); 3];
+        let placements = pack(&items, Vector2(4.0, 2.0), Packing::MaxRects, true);
+        assert!(placements.iter().all(|p| p.rotated));
+        assert_eq!(placements.iter().map(|p| p.bin).max(), Some(1));
+        check(&items, Vector2(4.0, 2.0), &placements);
+        assert!(pack(&items, Vector2(4.0, 2.0), Packing::Skyline, false).is_empty());
+    }
+}
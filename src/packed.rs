@@ -0,0 +1,129 @@
+//! Packed vertex formats
+//!
+//! Positions quantize to three 16-bit fractions of a bounding box and unit
+//! normals to two 16-bit octahedral coordinates, 6 and 4 bytes in place of
+//! 12. Decoding is exact: a code always decodes to the same value, and the
+//! box corners to themselves.
+
+use crate::{ Aabb3, Point3, Vector3 };
+
+/// Largest code
+const MAX: f32 = u16::MAX as f32;
+
+impl Aabb3<f32> {
+    /// quantize: &Aabb × Point -> [u16; 3]
+    /// the nearest of 65536 steps across the box on each axis, points
+    /// outside clamped onto it; off by at most half a step
+    pub fn quantize(&self, p: Point3<f32>) -> [u16; 3] {
+        let (lo, hi) = (self.min(), self.max());
+        let code = |x: f32, lo: f32, hi: f32| {
+            if hi <= lo {
+                return 0;
+            }
+            ((x - lo) / (hi - lo) * MAX).round().clamp(0.0, MAX) as u16
+        };
+        [code(p.0, lo.0, hi.0), code(p.1, lo.1, hi.1), code(p.2, lo.2, hi.2)]
+    }
+
+    /// dequantize: &Aabb × [u16; 3] -> Point
+    pub fn dequantize(&self, q: [u16; 3]) -> Point3<f32> {
+        let (lo, hi) = (self.min(), self.max());
+        // blended rather than offset so that the end codes land on the corners
+        let value = |q: u16, lo: f32, hi: f32| {
+            let t = q as f32 / MAX;
+            lo * (1.0 - t) + hi * t
+        };
+        Point3(value(q[0], lo.0, hi.0), value(q[1], lo.1, hi.1), value(q[2], lo.2, hi.2))
+    }
+}
+
+impl Vector3<f32> {
+    /// to_octahedral: Vector -> [u16; 2]
+    /// direction folded onto the octahedron and unfolded into the square;
+    /// of the four codes around it the one decoding closest is kept, within
+    /// 5e-5 radians. Zero vectors encode as +z
+    pub fn to_octahedral(self) -> [u16; 2] {
+        let l1 = self.0.abs() + self.1.abs() + self.2.abs();
+        if l1 == 0.0 {
+            return Vector3(0.0, 0.0, 1.0).to_octahedral();
+        }
+        let (x, y) = (self.0 / l1, self.1 / l1);
+        let (u, v) = if self.2 < 0.0 { ((1.0 - y.abs()) * sign(x), (1.0 - x.abs()) * sign(y)) } else { (x, y) };
+        let unit = self / self.length();
+        let (fu, fv) = ((u * 0.5 + 0.5) * MAX, (v * 0.5 + 0.5) * MAX);
+        // compared by the sine of the error, as cosines this close to 1
+        // are beyond single precision
+        let mut best = ([0, 0], f32::INFINITY);
+        for du in [0.0, 1.0] {
+            for dv in [0.0, 1.0] {
+                let q = [(fu.floor() + du).clamp(0.0, MAX) as u16, (fv.floor() + dv).clamp(0.0, MAX) as u16];
+                let d = Vector3::from_octahedral(q);
+                let off = if d.dot(unit) > 0.0 { d.cross(unit).length() } else { f32::INFINITY };
+                if off < best.1 {
+                    best = (q, off);
+                }
+            }
+        }
+        best.0
+    }
+
+    /// from_octahedral: [u16; 2] -> Vector
+    /// unit vector
+    pub fn from_octahedral(q: [u16; 2]) -> Self {
+        let (u, v) = (q[0] as f32 / MAX * 2.0 - 1.0, q[1] as f32 / MAX * 2.0 - 1.0);
+        let z = 1.0 - u.abs() - v.abs();
+        let (x, y) = if z < 0.0 { ((1.0 - v.abs()) * sign(u), (1.0 - u.abs()) * sign(v)) } else { (u, v) };
+        let n = Vector3(x, y, z);
+        n / n.length()
+    }
+}
+
+/// ±1, positive for zero
+fn sign(x: f32) -> f32 {
+    if x < 0.0 { -1.0 } else { 1.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions() {
+        let b = Aabb3::new(Point3(-1.0, 0.0, 10.0), Point3(3.0, 0.5, 10.0));
+        assert_eq!(b.quantize(b.min()), [0, 0, 0]);
+        assert_eq!(b.quantize(b.max()), [65535, 65535, 0]);
+        assert_eq!(b.dequantize([65535, 65535, 65535]), b.max());
+        assert_eq!(b.dequantize([0, 0, 0]), b.min());
+        assert_eq!(b.quantize(Point3(-5.0, 9.0, 0.0)), [0, 65535, 0]);
+        for k in 0..1000 {
+            let t = k as f32 / 999.0;
+            let p = Point3(-1.0 + 4.0 * t, 0.5 * t * t, 10.0);
+            let q = b.dequantize(b.quantize(p));
+            assert!((q.0 - p.0).abs() <= 4.0 / 65535.0 && (q.1 - p.1).abs() <= 0.5 / 65535.0 && q.2 == 10.0);
+            assert_eq!(b.quantize(q), b.quantize(p));
+        }
+    }
+
+    #[test]
+    fn normals() {
+        let axes = [Vector3(1.0, 0.0, 0.0), Vector3(0.0, -1.0, 0.0), Vector3(0.0, 0.0, 1.0), Vector3(0.0, 0.0, -1.0)];
+        for n in axes {
+            assert!(Vector3::from_octahedral(n.to_octahedral()).dot(n) > 1.0 - 1e-6);
+        }
+        let mut seed = 5u64;
+        let mut random = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+        };
+        let mut worst = 0.0f32;
+        for _ in 0..2000 {
+            let v = Vector3(random() as f32, random() as f32, random() as f32);
+            let n = v / v.length();
+            let d = Vector3::from_octahedral(n.to_octahedral());
+            assert!((d.length() - 1.0).abs() < 1e-6);
+            worst = worst.max(d.cross(n).length());
+        }
+        assert!(worst < 5e-5);
+        assert_eq!(Vector3(0.0f32, 0.0, 0.0).to_octahedral(), Vector3(0.0f32, 0.0, 1.0).to_octahedral());
+    }
+}
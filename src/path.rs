@@ -0,0 +1,631 @@
+//! SVG-style paths
+
+use crate::anchor::impl_anchored;
+use crate::curve::{ closest_on_curve, flatten, segment_distance };
+use crate::{ Arc2, Bounded2, CubicBezier2, Curve2, FillRule, Float, Point2, Polygon2, QuadBezier2, Rect2, Vector2 };
+
+/// Drawing command of a path
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PathCommand<T> {
+    /// starts a new contour at the point
+    MoveTo(Point2<T>),
+    /// straight line to the point
+    LineTo(Point2<T>),
+    /// quadratic Bézier: control, end
+    QuadTo(Point2<T>, Point2<T>),
+    /// cubic Bézier: control, control, end
+    CubicTo(Point2<T>, Point2<T>, Point2<T>),
+    /// circular arc to `to`, picked by the SVG large-arc and sweep flags
+    /// (sweep: counter-clockwise); too small a radius is scaled up to fit
+    ArcTo { radius: T, large_arc: bool, sweep: bool, to: Point2<T> },
+    /// straight line back to the contour start
+    Close,
+}
+
+/// Where a ray crosses a drawn path
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PathHit2<T> {
+    /// index of the segment, counted across contours as in `hit_test_path`
+    pub segment: usize,
+    /// ray parameter of the point
+    pub t: T,
+    /// point on the path
+    pub point: Point2<T>,
+    /// unit normal right of the drawing direction, outward for
+    /// counter-clockwise contours
+    pub normal: Vector2<T>,
+}
+
+/// 2d Path: contours of lines, Béziers and arcs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path2<T>(Vec<PathCommand<T>>);
+
+impl<T> Path2<T> {
+    /// new: [Command] -> Path
+    pub fn new(commands: Vec<PathCommand<T>>) -> Self {
+        Path2(commands)
+    }
+
+    /// commands: &Path -> &[Command]
+    pub fn commands(&self) -> &[PathCommand<T>] {
+        &self.0
+    }
+
+    /// push: &mut Path × Command
+    pub fn push(&mut self, command: PathCommand<T>) {
+        self.0.push(command)
+    }
+
+    /// contours: &Path -> Iterator<&[Command]>
+    /// runs of commands, each starting at a MoveTo
+    pub fn contours(&self) -> impl Iterator<Item = &[PathCommand<T>]> + '_ {
+        let mut rest = &self.0[..];
+        std::iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+            let end = rest.iter().skip(1)
+                .position(|c| matches!(c, PathCommand::MoveTo(_)))
+                .map_or(rest.len(), |i| i + 1);
+            let (contour, tail) = rest.split_at(end);
+            rest = tail;
+            Some(contour)
+        })
+    }
+}
+
+impl<T: Float> Path2<T> {
+    /// bounds: &Path -> Option<Rect>
+    /// tight bounds of the curves, None for an empty path
+    pub fn bounds(&self) -> Option<Rect2<T>> {
+        let segments: Vec<_> = self.contours().flat_map(|c| segments(c, true)).collect();
+        segment_bounds(&segments)
+    }
+
+    /// winding_number: &Path × Point -> i32
+    /// every contour implicitly closed
+    pub fn winding_number(&self, p: Point2<T>) -> i32 {
+        self.contours().flat_map(|c| segments(c, true)).map(|s| s.winding(p)).sum()
+    }
+
+    /// contains: &Path × Point × FillRule -> bool
+    pub fn contains(&self, p: Point2<T>, rule: FillRule) -> bool {
+        rule.is_filled(self.winding_number(p))
+    }
+
+    /// hit_test_fill: &Path × Point × FillRule -> bool
+    /// whether p lies in the fill, the outline itself included
+    pub fn hit_test_fill(&self, p: Point2<T>, rule: FillRule) -> bool {
+        let scale = T::one() + p.0.abs() + p.1.abs();
+        self.contains(p, rule) || self.outline_distance(p, true).is_some_and(|d| d <= T::epsilon() * T::from_f64(64.0) * scale)
+    }
+
+    /// hit_test_stroke: &Path × Point × K -> bool
+    /// whether p lies within half the stroke width of the drawn segments,
+    /// contours left open unless closed; caps and joins taken as round
+    pub fn hit_test_stroke(&self, p: Point2<T>, width: T) -> bool {
+        self.outline_distance(p, false).is_some_and(|d| d <= width.abs() / T::from_f64(2.0))
+    }
+
+    fn outline_distance(&self, p: Point2<T>, close: bool) -> Option<T> {
+        self.contours().flat_map(|c| segments(c, close)).map(|s| s.distance(p))
+            .fold(None, |best: Option<T>, d| Some(best.map_or(d, |b| if d < b { d } else { b })))
+    }
+
+    /// intersect_ray: &Path × Point × Vector -> [PathHit]
+    /// crossings of origin + direction · t, t ≥ 0, with the drawn segments,
+    /// nearest first; a crossing through a joint is reported once, and a
+    /// curve only grazing the ray may be missed
+    pub fn intersect_ray(&self, origin: Point2<T>, direction: Vector2<T>) -> Vec<PathHit2<T>> {
+        let dd = direction.dot(direction);
+        if dd == T::zero() {
+            return Vec::new();
+        }
+        let side = |q: Point2<T>| {
+            let v = q.vector_from(origin);
+            direction.0 * v.1 - direction.1 * v.0
+        };
+        let mut hits = Vec::new();
+        let all = self.contours().flat_map(|c| segments(c, false));
+        for (k, s) in all.enumerate() {
+            for u in s.roots(side) {
+                let point = s.point_at(u);
+                let t = point.vector_from(origin).dot(direction) / dd;
+                if t < T::zero() {
+                    continue;
+                }
+                let d = s.derivative(u);
+                let l = d.length();
+                let normal = if l > T::zero() { Vector2(d.1, -d.0) / l } else { Vector2(T::zero(), T::zero()) };
+                hits.push(PathHit2 { segment: k, t, point, normal });
+            }
+        }
+        hits.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap_or(std::cmp::Ordering::Equal));
+        let tol = T::epsilon() * T::from_f64(64.0);
+        hits.dedup_by(|b, a| (b.t - a.t).abs() <= tol * (T::one() + a.t.abs()) && b.point.distance(a.point) <= tol * (T::one() + a.point.0.abs() + a.point.1.abs()));
+        hits
+    }
+
+    /// to_polygons: &Path × K -> [Polygon]
+    /// one ring per contour, curves flattened within `tolerance`
+    pub fn to_polygons(&self, tolerance: T) -> Vec<Polygon2<T>> {
+        self.contours().filter_map(|contour| {
+            let mut ring: Vec<Point2<T>> = Vec::new();
+            for s in segments(contour, true) {
+                let line = flatten(&s, tolerance);
+                let skip = if ring.is_empty() { 0 } else { 1 };
+                ring.extend(line.vertices().iter().skip(skip));
+            }
+            if ring.len() > 1 && ring.first() == ring.last() {
+                ring.pop();
+            }
+            if ring.len() < 3 { None } else { Some(Polygon2::new(ring)) }
+        }).collect()
+    }
+}
+
+/// Drawable piece of a contour
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum Segment<T> {
+    Line(Point2<T>, Point2<T>),
+    Quad([Point2<T>; 3]),
+    Cubic([Point2<T>; 4]),
+    /// center, radius, start angle, sweep angle
+    Arc(Point2<T>, T, T, T),
+}
+
+impl_anchored!(Path2, |path, m| Path2(path.0.iter().map(|&c| match c {
+    PathCommand::MoveTo(p) => PathCommand::MoveTo(m.point(p)),
+    PathCommand::LineTo(p) => PathCommand::LineTo(m.point(p)),
+    PathCommand::QuadTo(c, p) => PathCommand::QuadTo(m.point(c), m.point(p)),
+    PathCommand::CubicTo(c, d, p) => PathCommand::CubicTo(m.point(c), m.point(d), m.point(p)),
+    PathCommand::ArcTo { radius, large_arc, sweep, to } => PathCommand::ArcTo { radius: m.length(radius), large_arc, sweep, to: m.point(to) },
+    PathCommand::Close => PathCommand::Close,
+}).collect()));
+
+impl<T: Float> Bounded2<T> for Path2<T> {
+    fn bounds(&self) -> Option<Rect2<T>> {
+        Path2::bounds(self)
+    }
+}
+
+/// Tight bounds of segments: end points and axis extrema
+pub(crate) fn segment_bounds<T: Float>(segments: &[Segment<T>]) -> Option<Rect2<T>> {
+    Rect2::from_points(segments.iter().flat_map(|s| {
+        let ts = [T::zero(), T::one()].into_iter().chain(s.extrema(0)).chain(s.extrema(1));
+        ts.map(move |t| s.point_at(t)).collect::<Vec<_>>()
+    }))
+}
+
+/// Segments of a contour, with `close` also joined back to its start
+pub(crate) fn segments<T: Float>(contour: &[PathCommand<T>], close: bool) -> Vec<Segment<T>> {
+    let zero = T::zero();
+    let (mut start, mut cur) = (Point2(zero, zero), Point2(zero, zero));
+    let mut out = Vec::new();
+    for &command in contour {
+        match command {
+            PathCommand::MoveTo(p) => {
+                start = p;
+                cur = p;
+            }
+            PathCommand::LineTo(p) => {
+                out.push(Segment::Line(cur, p));
+                cur = p;
+            }
+            PathCommand::QuadTo(c, p) => {
+                out.push(Segment::Quad([cur, c, p]));
+                cur = p;
+            }
+            PathCommand::CubicTo(c1, c2, p) => {
+                out.push(Segment::Cubic([cur, c1, c2, p]));
+                cur = p;
+            }
+            PathCommand::ArcTo { radius, large_arc, sweep, to } => {
+                if cur != to {
+                    out.push(arc_segment(cur, radius, large_arc, sweep, to));
+                }
+                cur = to;
+            }
+            PathCommand::Close => {
+                if cur != start {
+                    out.push(Segment::Line(cur, start));
+                }
+                cur = start;
+            }
+        }
+    }
+    if close && cur != start {
+        out.push(Segment::Line(cur, start));
+    }
+    out
+}
+
+/// SVG endpoint to center parameterization of a circular arc
+fn arc_segment<T: Float>(from: Point2<T>, radius: T, large_arc: bool, sweep: bool, to: Point2<T>) -> Segment<T> {
+    let two = T::from_f64(2.0);
+    let chord = to.vector_from(from);
+    let d = chord.length();
+    let r = radius.abs();
+    if r == T::zero() {
+        return Segment::Line(from, to);
+    }
+    let r = if r < d / two { d / two } else { r };
+    let h = (r * r - d * d / (two * two)).abs().sqrt();
+    let left = Vector2(-chord.1, chord.0) / d;
+    let mid = from + chord / two;
+    let c = if large_arc != sweep { mid + left * h } else { mid - left * h };
+    let (u, v) = (from.vector_from(c), to.vector_from(c));
+    let a0 = u.1.atan2(u.0);
+    let mut delta = v.1.atan2(v.0) - a0;
+    let tau = T::pi() * two;
+    if sweep && delta <= T::zero() {
+        delta = delta + tau;
+    } else if !sweep && delta >= T::zero() {
+        delta = delta - tau;
+    }
+    Segment::Arc(c, r, a0, delta)
+}
+
+fn coord<T: Copy>(p: Point2<T>, axis: usize) -> T {
+    if axis == 0 { p.0 } else { p.1 }
+}
+
+impl<T: Float> Curve2<T> for Segment<T> {
+    fn point_at(&self, t: T) -> Point2<T> {
+        match *self {
+            Segment::Line(a, b) => a + b.vector_from(a) * t,
+            Segment::Quad([a, b, c]) => QuadBezier2::new(a, b, c).point_at(t),
+            Segment::Cubic([a, b, c, d]) => CubicBezier2::new(a, b, c, d).point_at(t),
+            Segment::Arc(c, r, a0, sweep) => Arc2::new(c, r, a0, sweep).point_at(t),
+        }
+    }
+
+    fn derivative(&self, t: T) -> Vector2<T> {
+        let one = T::one();
+        let s = one - t;
+        match *self {
+            Segment::Line(a, b) => b.vector_from(a),
+            Segment::Quad([a, b, c]) => {
+                (b.vector_from(a) * s + c.vector_from(b) * t) * T::from_f64(2.0)
+            }
+            Segment::Cubic([a, b, c, d]) => {
+                (b.vector_from(a) * (s * s) + c.vector_from(b) * (T::from_f64(2.0) * s * t) + d.vector_from(c) * (t * t))
+                    * T::from_f64(3.0)
+            }
+            Segment::Arc(_, r, a0, sweep) => {
+                let a = a0 + sweep * t;
+                Vector2(-a.sin(), a.cos()) * (r * sweep)
+            }
+        }
+    }
+
+    fn second_derivative(&self, t: T) -> Vector2<T> {
+        match *self {
+            Segment::Line(..) => Vector2(T::zero(), T::zero()),
+            Segment::Quad([a, b, c]) => QuadBezier2::new(a, b, c).second_derivative(t),
+            Segment::Cubic([a, b, c, d]) => CubicBezier2::new(a, b, c, d).second_derivative(t),
+            Segment::Arc(c, r, a0, sweep) => Arc2::new(c, r, a0, sweep).second_derivative(t),
+        }
+    }
+}
+
+impl<T: Float> Segment<T> {
+    /// Parameters in (0, 1) where the coordinate along `axis` has a local extremum
+    pub(crate) fn extrema(&self, axis: usize) -> Vec<T> {
+        let (zero, one) = (T::zero(), T::one());
+        let inside = |t: &T| zero < *t && *t < one;
+        match *self {
+            Segment::Line(..) => Vec::new(),
+            Segment::Quad(p) => {
+                let [a, b, c] = p.map(|p| coord(p, axis));
+                let den = a - b - b + c;
+                if den == zero { Vec::new() } else { [(a - b) / den].into_iter().filter(inside).collect() }
+            }
+            Segment::Cubic(p) => {
+                let [a, b, c, d] = p.map(|p| coord(p, axis));
+                let three = T::from_f64(3.0);
+                let qa = -a + b * three - c * three + d;
+                let qb = (a - b - b + c) * T::from_f64(2.0);
+                let qc = b - a;
+                quadratic_roots(qa, qb, qc).into_iter().filter(inside).collect()
+            }
+            Segment::Arc(_, _, a0, sweep) => {
+                let pi = T::pi();
+                let phase = if axis == 0 { zero } else { pi / T::from_f64(2.0) };
+                let (lo, hi) = if sweep < zero { (a0 + sweep, a0) } else { (a0, a0 + sweep) };
+                let mut theta = phase + ((lo - phase) / pi).floor() * pi;
+                let mut out = Vec::new();
+                while theta < hi {
+                    if theta > lo {
+                        out.push((theta - a0) / sweep);
+                    }
+                    theta = theta + pi;
+                }
+                out.sort_by(|s, t| s.partial_cmp(t).unwrap_or(std::cmp::Ordering::Equal));
+                out
+            }
+        }
+    }
+
+    /// The piece of the segment over [t0, t1]
+    pub(crate) fn sub(&self, t0: T, t1: T) -> Segment<T> {
+        let lerp = |p: Point2<T>, q: Point2<T>, t: T| p + q.vector_from(p) * t;
+        match *self {
+            Segment::Line(..) => Segment::Line(self.point_at(t0), self.point_at(t1)),
+            Segment::Quad(p) => {
+                // left part up to t1, then its right part from t0 / t1
+                let [a, b, c] = p;
+                let (ab, bc) = (lerp(a, b, t1), lerp(b, c, t1));
+                let [a, b, c] = [a, ab, lerp(ab, bc, t1)];
+                let u = if t1 == T::zero() { T::zero() } else { t0 / t1 };
+                let (ab, bc) = (lerp(a, b, u), lerp(b, c, u));
+                Segment::Quad([lerp(ab, bc, u), bc, c])
+            }
+            Segment::Cubic(p) => {
+                let split = |[a, b, c, d]: [Point2<T>; 4], t: T| {
+                    let (ab, bc, cd) = (lerp(a, b, t), lerp(b, c, t), lerp(c, d, t));
+                    let (abc, bcd) = (lerp(ab, bc, t), lerp(bc, cd, t));
+                    let m = lerp(abc, bcd, t);
+                    ([a, ab, abc, m], [m, bcd, cd, d])
+                };
+                let (left, _) = split(p, t1);
+                let u = if t1 == T::zero() { T::zero() } else { t0 / t1 };
+                Segment::Cubic(split(left, u).1)
+            }
+            Segment::Arc(c, r, a0, sweep) => Segment::Arc(c, r, a0 + sweep * t0, sweep * (t1 - t0)),
+        }
+    }
+
+    /// The segment run backwards
+    pub(crate) fn reversed(self) -> Segment<T> {
+        match self {
+            Segment::Line(a, b) => Segment::Line(b, a),
+            Segment::Quad([a, b, c]) => Segment::Quad([c, b, a]),
+            Segment::Cubic([a, b, c, d]) => Segment::Cubic([d, c, b, a]),
+            Segment::Arc(c, r, a0, sweep) => Segment::Arc(c, r, a0 + sweep, -sweep),
+        }
+    }
+
+    /// Drawing command from the segment start to its end
+    pub(crate) fn to_command(self) -> PathCommand<T> {
+        match self {
+            Segment::Line(_, b) => PathCommand::LineTo(b),
+            Segment::Quad([_, b, c]) => PathCommand::QuadTo(b, c),
+            Segment::Cubic([_, b, c, d]) => PathCommand::CubicTo(b, c, d),
+            Segment::Arc(_, r, _, sweep) => PathCommand::ArcTo {
+                radius: r,
+                large_arc: sweep.abs() > T::pi(),
+                sweep: sweep > T::zero(),
+                to: self.point_at(T::one()),
+            },
+        }
+    }
+
+    /// Distance from p to the segment
+    pub(crate) fn distance(&self, p: Point2<T>) -> T {
+        match *self {
+            Segment::Line(a, b) => segment_distance(p, a, b),
+            _ => closest_on_curve(self, p).distance(p),
+        }
+    }
+
+    /// Parameters where `f` of the segment point is zero: sign changes
+    /// between samples, refined by bisection; exact for lines
+    pub(crate) fn roots(&self, f: impl Fn(Point2<T>) -> T) -> Vec<T> {
+        let (zero, one) = (T::zero(), T::one());
+        let n = if let Segment::Line(..) = self { 1 } else { self.spans() * 16 };
+        let at = |k: usize| T::from_f64(k as f64 / n as f64);
+        let mut out = Vec::new();
+        for k in 0..n {
+            let (u0, u1) = (at(k), at(k + 1));
+            let (f0, f1) = (f(self.point_at(u0)), f(self.point_at(u1)));
+            if f0 == zero {
+                out.push(u0);
+            } else if k == n - 1 && f1 == zero {
+                out.push(u1);
+            }
+            if (f0 < zero) == (f1 < zero) || f1 == zero || f0 == zero {
+                continue;
+            }
+            if let Segment::Line(..) = self {
+                out.push(f0 / (f0 - f1));
+                continue;
+            }
+            let (mut lo, mut hi) = (u0, u1);
+            for _ in 0..64 {
+                let mid = (lo + hi) / T::from_f64(2.0);
+                if mid <= lo || mid >= hi {
+                    break;
+                }
+                if (f(self.point_at(mid)) < zero) == (f0 < zero) { lo = mid } else { hi = mid }
+            }
+            out.push((lo + hi) / T::from_f64(2.0));
+        }
+        out.retain(|u| zero <= *u && *u <= one);
+        out
+    }
+
+    /// Signed crossings of the rightward ray from p, half-open in y
+    pub(crate) fn winding(&self, p: Point2<T>) -> i32 {
+        if let Segment::Line(a, b) = *self {
+            return line_winding(a, b, p);
+        }
+        let mut ts = vec![T::zero()];
+        ts.extend(self.extrema(1));
+        ts.push(T::one());
+        ts.windows(2).map(|w| {
+            let (t0, t1) = (w[0], w[1]);
+            let (y0, y1) = (self.point_at(t0).1, self.point_at(t1).1);
+            let up = y1 > y0;
+            let crosses = if up { y0 <= p.1 && p.1 < y1 } else { y1 <= p.1 && p.1 < y0 };
+            if !crosses {
+                return 0;
+            }
+            let (mut lo, mut hi) = (t0, t1);
+            for _ in 0..64 {
+                let mid = (lo + hi) / T::from_f64(2.0);
+                if mid <= lo || mid >= hi {
+                    break;
+                }
+                if (self.point_at(mid).1 < p.1) == up { lo = mid } else { hi = mid }
+            }
+            if self.point_at((lo + hi) / T::from_f64(2.0)).0 > p.0 {
+                if up { 1 } else { -1 }
+            } else {
+                0
+            }
+        }).sum()
+    }
+}
+
+fn line_winding<T: Float>(a: Point2<T>, b: Point2<T>, p: Point2<T>) -> i32 {
+    let (u, v) = (b.vector_from(a), p.vector_from(a));
+    let side = u.0 * v.1 - u.1 * v.0;
+    if a.1 <= p.1 && p.1 < b.1 && side > T::zero() {
+        1
+    } else if b.1 <= p.1 && p.1 < a.1 && side < T::zero() {
+        -1
+    } else {
+        0
+    }
+}
+
+/// Real roots of a t² + b t + c
+pub(crate) fn quadratic_roots<T: Float>(a: T, b: T, c: T) -> Vec<T> {
+    let zero = T::zero();
+    if a == zero {
+        return if b == zero { Vec::new() } else { vec![-c / b] };
+    }
+    let disc = b * b - T::from_f64(4.0) * a * c;
+    if disc < zero {
+        return Vec::new();
+    }
+    let s = disc.sqrt();
+    // numerically stable form
+    let q = if b < zero { (-b + s) / T::from_f64(2.0) } else { (-b - s) / T::from_f64(2.0) };
+    if q == zero {
+        vec![zero]
+    } else {
+        vec![q / a, c / q]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PathCommand::*;
+
+    fn near(a: f64, b: f64, eps: f64) -> bool {
+        (a - b).abs() < eps
+    }
+
+    fn circle(c: Point2<f64>, r: f64, ccw: bool) -> Vec<PathCommand<f64>> {
+        vec![
+            MoveTo(c + Vector2(r, 0.0)),
+            ArcTo { radius: r, large_arc: false, sweep: ccw, to: c + Vector2(-r, 0.0) },
+            ArcTo { radius: r, large_arc: false, sweep: ccw, to: c + Vector2(r, 0.0) },
+            Close,
+        ]
+    }
+
+    #[test]
+    fn contours() {
+        let mut commands = circle(Point2(0.0, 0.0), 1.0, true);
+        commands.extend(circle(Point2(5.0, 0.0), 1.0, true));
+        let path = Path2::new(commands);
+        let cs: Vec<_> = path.contours().collect();
+        assert_eq!(cs.len(), 2);
+        assert_eq!(cs[1][0], MoveTo(Point2(6.0, 0.0)));
+    }
+
+    #[test]
+    fn bounds() {
+        let path = Path2::new(circle(Point2(1.0, 2.0), 3.0, true));
+        let b = path.bounds().unwrap();
+        assert!(b.min().distance(Point2(-2.0, -1.0)) < 1e-12);
+        assert!(b.max().distance(Point2(4.0, 5.0)) < 1e-12);
+        let bump = Path2::new(vec![MoveTo(Point2(0.0, 0.0)), CubicTo(Point2(0.0, 4.0), Point2(4.0, 4.0), Point2(4.0, 0.0))]);
+        let b = bump.bounds().unwrap();
+        assert_eq!((b.min(), b.max()), (Point2(0.0, 0.0), Point2(4.0, 3.0)));
+        let q = Path2::new(vec![MoveTo(Point2(0.0, 0.0)), QuadTo(Point2(1.0, 2.0), Point2(2.0, 0.0))]);
+        assert_eq!(q.bounds().unwrap().max(), Point2(2.0, 1.0));
+        assert_eq!(Path2::<f64>::new(vec![]).bounds(), None);
+    }
+
+    #[test]
+    fn contains_fill_rules() {
+        let mut commands = circle(Point2(0.0, 0.0), 2.0, true);
+        commands.extend(circle(Point2(0.0, 0.0), 1.0, true));
+        let same = Path2::new(commands);
+        assert_eq!(same.winding_number(Point2(0.0, 0.5)), 2);
+        assert!(same.contains(Point2(0.0, 0.5), FillRule::NonZero));
+        assert!(!same.contains(Point2(0.0, 0.5), FillRule::EvenOdd));
+        assert!(same.contains(Point2(1.5, 0.0), FillRule::EvenOdd));
+        assert!(!same.contains(Point2(2.5, 0.0), FillRule::NonZero));
+        let mut commands = circle(Point2(0.0, 0.0), 2.0, true);
+        commands.extend(circle(Point2(0.0, 0.0), 1.0, false));
+        let hole = Path2::new(commands);
+        assert!(!hole.contains(Point2(0.0, 0.5), FillRule::NonZero));
+        // just inside the arc, outside its chords
+        assert!(hole.contains(Point2(0.0, 1.999), FillRule::NonZero));
+        let bump = Path2::new(vec![MoveTo(Point2(0.0, 0.0)), CubicTo(Point2(0.0, 4.0), Point2(4.0, 4.0), Point2(4.0, 0.0))]);
+        assert_eq!(bump.winding_number(Point2(2.0, 2.9)), -1);
+        assert_eq!(bump.winding_number(Point2(2.0, 3.1)), 0);
+    }
+
+    #[test]
+    fn to_polygons() {
+        let path = Path2::new(circle(Point2(0.0, 0.0), 10.0, true));
+        let polys = path.to_polygons(0.01);
+        assert_eq!(polys.len(), 1);
+        assert!(near(polys[0].area(), std::f64::consts::PI * 100.0, 0.2));
+        let v = polys[0].vertices();
+        assert!(v.first() != v.last());
+        let square = Path2::new(vec![
+            MoveTo(Point2(0.0, 0.0)), LineTo(Point2(1.0, 0.0)), LineTo(Point2(1.0, 1.0)), LineTo(Point2(0.0, 1.0)),
+        ]);
+        assert_eq!(square.to_polygons(0.1)[0].vertices().len(), 4);
+    }
+
+    #[test]
+    fn hit_tests() {
+        let mut commands = vec![MoveTo(Point2(-4.0, -4.0)), LineTo(Point2(4.0, -4.0)), LineTo(Point2(4.0, 4.0)), LineTo(Point2(-4.0, 4.0))];
+        commands.extend(circle(Point2(0.0, 0.0), 2.0, false));
+        let frame = Path2::new(commands);
+        assert!(frame.hit_test_fill(Point2(3.0, 0.0), FillRule::NonZero));
+        assert!(!frame.hit_test_fill(Point2(1.0, 0.0), FillRule::NonZero));
+        // both outlines count, the top edge included though the winding there is 0
+        assert!(frame.hit_test_fill(Point2(2.0, 0.0), FillRule::EvenOdd));
+        assert!(frame.hit_test_fill(Point2(0.0, 4.0), FillRule::NonZero));
+        assert!(!frame.contains(Point2(0.0, 4.0), FillRule::NonZero));
+        assert!(frame.hit_test_stroke(Point2(0.0, 2.4), 1.0));
+        assert!(!frame.hit_test_stroke(Point2(0.0, 2.6), 1.0));
+        // the square is left open: no stroke along its left side
+        assert!(!frame.hit_test_stroke(Point2(-4.0, 0.0), 1.0));
+        assert!(frame.hit_test_stroke(Point2(4.2, 0.0), 0.5));
+    }
+
+    #[test]
+    fn ray_crossings() {
+        let mut commands = vec![MoveTo(Point2(-4.0, -4.0)), LineTo(Point2(4.0, -4.0)), LineTo(Point2(4.0, 4.0)), LineTo(Point2(-4.0, 4.0)), Close];
+        commands.extend(circle(Point2(0.0, 0.0), 2.0, true));
+        let frame = Path2::new(commands);
+        let hits = frame.intersect_ray(Point2(-10.0, 0.0), Vector2(2.0, 0.0));
+        let ts: Vec<f64> = hits.iter().map(|h| h.t).collect();
+        assert_eq!(ts.len(), 4);
+        for (t, want) in ts.iter().zip([3.0, 4.0, 6.0, 7.0]) {
+            assert!(near(*t, want, 1e-9));
+        }
+        assert_eq!((hits[0].segment, hits[3].segment), (3, 1));
+        assert!((hits[0].normal - Vector2(-1.0, 0.0)).length() < 1e-12);
+        assert!((hits[1].normal - Vector2(-1.0, 0.0)).length() < 1e-6);
+        assert!(hits[2].point.distance(Point2(2.0, 0.0)) < 1e-9);
+        // through two corners and the circle: each corner once, not once per side
+        let corner = frame.intersect_ray(Point2(-5.0, -5.0), Vector2(-1.0, -1.0));
+        assert!(corner.is_empty());
+        let corner = frame.intersect_ray(Point2(5.0, -5.0), Vector2(-1.0, 1.0));
+        assert_eq!(corner.len(), 4);
+        assert!(corner[0].point.distance(Point2(4.0, -4.0)) < 1e-12 && corner[3].point.distance(Point2(-4.0, 4.0)) < 1e-12);
+        assert!(frame.intersect_ray(Point2(0.0, 0.0), Vector2(0.0, 0.0)).is_empty());
+    }
+}
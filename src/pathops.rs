@@ -0,0 +1,316 @@
+//! Boolean operations on paths, curves kept
+//!
+//! The operands are flattened with every sample remembering the curve and
+//! parameter it came from, combined as polygons by winding number, and the
+//! result traced back: each stretch of output running along one input
+//! curve becomes the matching piece of that curve, cut with `sub`, so
+//! Béziers and arcs come out as Béziers and arcs.
+
+use crate::curve::{ flatten_params, segment_distance };
+use crate::path::{ segments, Segment };
+use crate::{ Aabb3, AabbTree3, Budget, Curve2, FillRule, Float, MultiPolygon2, Path2, PathCommand, Point2, Point3, Polygon2, Rect2 };
+
+/// Way two regions are combined
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BooleanOp {
+    /// in either
+    Union,
+    /// in both
+    Intersection,
+    /// in the first but not the second
+    Difference,
+    /// in exactly one
+    Xor,
+}
+
+/// Straight piece of a flattened curve
+#[derive(Debug, Copy, Clone)]
+struct Chord<T> {
+    segment: usize,
+    /// curve parameters at the two ends
+    t: (T, T),
+    a: Point2<T>,
+    b: Point2<T>,
+}
+
+/// Input curve an output edge runs along, and its parameters at the ends
+type Source<T> = Option<(usize, T, T)>;
+
+/// Flattened rings of the paths' contours, each implicitly closed, with
+/// their curves and chords
+#[derive(Debug, Default)]
+struct Flattened<T> {
+    segments: Vec<Segment<T>>,
+    chords: Vec<Chord<T>>,
+}
+
+impl<T: Float> Flattened<T> {
+    fn rings(&mut self, path: &Path2<T>, tolerance: T) -> Vec<Vec<Point2<T>>> {
+        let mut rings = Vec::new();
+        for contour in path.contours() {
+            let mut ring: Vec<Point2<T>> = Vec::new();
+            for segment in segments(contour, true) {
+                let samples = match segment {
+                    Segment::Line(a, b) => vec![(T::zero(), a), (T::one(), b)],
+                    _ => flatten_params(&segment, tolerance),
+                };
+                let id = self.segments.len();
+                self.segments.push(segment);
+                for w in samples.windows(2) {
+                    self.chords.push(Chord { segment: id, t: (w[0].0, w[1].0), a: w[0].1, b: w[1].1 });
+                }
+                let skip = if ring.is_empty() { 0 } else { 1 };
+                ring.extend(samples.iter().skip(skip).map(|s| s.1));
+            }
+            if ring.len() > 1 && ring.first() == ring.last() {
+                ring.pop();
+            }
+            if ring.len() >= 3 {
+                rings.push(ring);
+            }
+        }
+        rings
+    }
+}
+
+/// One polygon of all the rings, for `fill_where`
+fn gathered<T: Float>(mut rings: Vec<Vec<Point2<T>>>) -> Option<Polygon2<T>> {
+    if rings.is_empty() {
+        return None;
+    }
+    let first = rings.remove(0);
+    Some(Polygon2::with_holes(first, rings))
+}
+
+fn rings_of<T: Float>(region: &MultiPolygon2<T>, reverse: bool) -> Vec<Vec<Point2<T>>> {
+    region.iter().flat_map(|p| p.rings()).map(|r| {
+        let mut r = r.to_vec();
+        if reverse {
+            r.reverse();
+        }
+        r
+    }).collect()
+}
+
+impl<T: Float> Path2<T> {
+    /// boolean: &Path × &Path × BooleanOp × FillRule × K -> Path
+    /// the regions the two paths fill under `rule`, every contour closed,
+    /// combined by `op`; one closed contour per output ring, exteriors
+    /// counter-clockwise and holes clockwise, with curves cut from the
+    /// input curves and lines merged, within `tolerance` of the exact result
+    pub fn boolean(&self, other: &Path2<T>, op: BooleanOp, rule: FillRule, tolerance: T) -> Path2<T> {
+        self.boolean_within(other, op, rule, tolerance, &mut Budget::unlimited()).expect("unlimited budget")
+    }
+
+    /// boolean_within: &Path × &Path × BooleanOp × FillRule × K × &mut Budget -> Option<Path>
+    /// `boolean`, a unit of the budget per stop of each of its three sweeps
+    /// and per face; None if it runs out
+    pub fn boolean_within(&self, other: &Path2<T>, op: BooleanOp, rule: FillRule, tolerance: T, budget: &mut Budget) -> Option<Path2<T>> {
+        let mut flat = Flattened { segments: Vec::new(), chords: Vec::new() };
+        let mut fill = |rings: Vec<Vec<Point2<T>>>| match gathered(rings) {
+            Some(p) => p.fill_within(rule, budget),
+            None => Some(MultiPolygon2::new(Vec::new())),
+        };
+        let a = fill(flat.rings(self, tolerance))?;
+        let b = fill(flat.rings(other, tolerance))?;
+        // each filled once: the second wound against the first to tell
+        // them apart, or with it to find where both are
+        let (reverse, keep): (bool, fn(i32) -> bool) = match op {
+            BooleanOp::Union => (false, |w| w > 0),
+            BooleanOp::Intersection => (false, |w| w > 1),
+            BooleanOp::Difference => (true, |w| w > 0),
+            BooleanOp::Xor => (true, |w| w != 0),
+        };
+        let mut rings = rings_of(&a, false);
+        rings.extend(rings_of(&b, reverse));
+        let Some(combined) = gathered(rings) else { return Some(Path2::new(Vec::new())) };
+        let result = combined.fill_where_within(keep, budget)?;
+        Some(flat.trace(&result, tolerance))
+    }
+}
+
+impl<T: Float> Flattened<T> {
+    /// Contours of the region, stretches along input curves made curves again
+    fn trace(&self, region: &MultiPolygon2<T>, tolerance: T) -> Path2<T> {
+        let mut out = Path2::new(Vec::new());
+        let Some(bounds) = Rect2::from_points(region.iter().flat_map(|p| p.rings()).flatten().copied()) else { return out };
+        let e = bounds.max().vector_from(bounds.min());
+        let near = (e.0 + e.1) * T::epsilon() * T::from_f64(1024.0);
+        let mut tree = AabbTree3::new(T::zero());
+        for (k, c) in self.chords.iter().enumerate() {
+            let (lo, hi) = (Rect2::new(c.a, c.b).min(), Rect2::new(c.a, c.b).max());
+            tree.insert(Aabb3::new(Point3(lo.0 - near, lo.1 - near, T::zero()), Point3(hi.0 + near, hi.1 + near, T::zero())), k);
+        }
+        // the curve and parameters an edge runs along, if any
+        let source = |p: Point2<T>, q: Point2<T>| -> Source<T> {
+            let r = Rect2::new(p, q);
+            let query = Aabb3::new(Point3(r.min().0, r.min().1, T::zero()), Point3(r.max().0, r.max().1, T::zero()));
+            let mut found: Vec<usize> = tree.query(&query).into_iter().filter_map(|h| tree.get(h).copied()).collect();
+            found.sort_unstable();
+            let c = found.into_iter().map(|k| self.chords[k]).find(|c| segment_distance(p, c.a, c.b) <= near && segment_distance(q, c.a, c.b) <= near)?;
+            let along = |x: Point2<T>| {
+                let d = c.b.vector_from(c.a);
+                let l = d.dot(d);
+                let f = if l > T::zero() { x.vector_from(c.a).dot(d) / l } else { T::zero() };
+                let f = if f < T::zero() { T::zero() } else if f > T::one() { T::one() } else { f };
+                c.t.0 + (c.t.1 - c.t.0) * f
+            };
+            Some((c.segment, along(p), along(q)))
+        };
+        for ring in region.iter().flat_map(|p| p.rings()) {
+            let n = ring.len();
+            let edges: Vec<Source<T>> = (0..n).map(|i| source(ring[i], ring[(i + 1) % n])).collect();
+            let joined = |i: usize, j: usize| match (edges[i], edges[j]) {
+                (Some((s, t0, t1)), Some((u, t2, t3))) => {
+                    s == u && (t1 - t2).abs() <= T::epsilon() * T::from_f64(64.0) && (t1 > t0) == (t3 > t2)
+                }
+                _ => false,
+            };
+            let start = (0..n).find(|&i| !joined((i + n - 1) % n, i)).unwrap_or(0);
+            // stretches: the curve, its parameters at both ends, and where it ends
+            let mut runs: Vec<(Source<T>, Point2<T>)> = Vec::new();
+            let mut i = start;
+            loop {
+                let mut k = i;
+                while (k + 1) % n != start && joined(k, (k + 1) % n) {
+                    k = (k + 1) % n;
+                }
+                let run = match (edges[i], edges[k]) {
+                    (Some((s, t0, _)), Some((_, _, t1))) => Some((s, t0, t1)),
+                    _ => None,
+                };
+                runs.push((run, ring[(k + 1) % n]));
+                i = (k + 1) % n;
+                if i == start {
+                    break;
+                }
+            }
+            // where two curves meet the flattened crossing is only near the
+            // true one; move it there
+            let m = runs.len();
+            for r in 0..m {
+                let next = (r + 1) % m;
+                if let (Some((s, t0, t1)), Some((u, t2, t3))) = (runs[r].0, runs[next].0) {
+                    if let Some((a, b, x)) = self.crossing(s, t1, u, t2, tolerance) {
+                        runs[r] = (Some((s, t0, a)), x);
+                        runs[next].0 = Some((u, b, t3));
+                    }
+                }
+            }
+            out.push(PathCommand::MoveTo(runs[m - 1].1));
+            for &(run, end) in &runs {
+                out.push(self.stretch(run, end));
+            }
+            out.push(PathCommand::Close);
+        }
+        out
+    }
+
+    /// Point where curves `s` and `u` cross near parameters `a` and `b`, by
+    /// Newton steps, if within `tolerance` of where the search starts
+    fn crossing(&self, s: usize, a: T, u: usize, b: T, tolerance: T) -> Option<(T, T, Point2<T>)> {
+        let (f, g) = (&self.segments[s], &self.segments[u]);
+        if s == u || matches!((f, g), (Segment::Line(..), Segment::Line(..))) {
+            return None;
+        }
+        let from = f.point_at(a);
+        let (mut a, mut b) = (a, b);
+        for _ in 0..16 {
+            let r = f.point_at(a).vector_from(g.point_at(b));
+            let (da, db) = (f.derivative(a), g.derivative(b));
+            // r + da·Δa - db·Δb = 0
+            let det = db.0 * da.1 - da.0 * db.1;
+            if det == T::zero() {
+                return None;
+            }
+            a = a + (db.1 * r.0 - db.0 * r.1) / det;
+            b = b + (da.1 * r.0 - da.0 * r.1) / det;
+            let unit = |t: T| t >= T::zero() && t <= T::one();
+            if !unit(a) || !unit(b) {
+                return None;
+            }
+        }
+        let x = f.point_at(a);
+        let close = x.distance(g.point_at(b)) <= T::epsilon() * T::from_f64(1024.0) * (T::one() + x.0.abs() + x.1.abs());
+        (close && x.distance(from) <= tolerance * T::from_f64(4.0)).then_some((a, b, x))
+    }
+
+    /// Command along the curve of a stretch, ending exactly at `end`
+    fn stretch(&self, run: Source<T>, end: Point2<T>) -> PathCommand<T> {
+        let Some((s, t0, t1)) = run else { return PathCommand::LineTo(end) };
+        let segment = self.segments[s];
+        if matches!(segment, Segment::Line(..)) || t0 == t1 {
+            return PathCommand::LineTo(end);
+        }
+        let piece = if t0 < t1 { segment.sub(t0, t1) } else { segment.sub(t1, t0).reversed() };
+        match piece.to_command() {
+            PathCommand::QuadTo(c, _) => PathCommand::QuadTo(c, end),
+            PathCommand::CubicTo(c, d, _) => PathCommand::CubicTo(c, d, end),
+            PathCommand::ArcTo { radius, large_arc, sweep, .. } => PathCommand::ArcTo { radius, large_arc, sweep, to: end },
+            _ => PathCommand::LineTo(end),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Vector2;
+    use std::f64::consts::PI;
+    use PathCommand::*;
+
+    fn circle(c: Point2<f64>, r: f64) -> Path2<f64> {
+        Path2::new(vec![
+            MoveTo(c + Vector2(r, 0.0)),
+            ArcTo { radius: r, large_arc: false, sweep: true, to: c + Vector2(-r, 0.0) },
+            ArcTo { radius: r, large_arc: false, sweep: true, to: c + Vector2(r, 0.0) },
+            Close,
+        ])
+    }
+
+    fn square(x: f64, y: f64, s: f64) -> Path2<f64> {
+        Path2::new(vec![MoveTo(Point2(x, y)), LineTo(Point2(x + s, y)), LineTo(Point2(x + s, y + s)), LineTo(Point2(x, y + s)), Close])
+    }
+
+    fn area(path: &Path2<f64>) -> f64 {
+        path.to_polygons(1e-5).iter().map(|p| p.signed_area()).sum()
+    }
+
+    #[test]
+    fn circles_stay_arcs() {
+        let (a, b) = (circle(Point2(0.0, 0.0), 1.0), circle(Point2(1.0, 0.0), 1.0));
+        let lens = 2.0 * (0.5f64).acos() - 0.5 * 3f64.sqrt();
+        let union = a.boolean(&b, BooleanOp::Union, FillRule::NonZero, 1e-3);
+        assert!(union.commands().iter().all(|c| matches!(c, MoveTo(_) | ArcTo { .. } | Close)));
+        assert_eq!(union.contours().count(), 1);
+        assert!((area(&union) - (2.0 * PI - lens)).abs() < 1e-4);
+        let both = a.boolean(&b, BooleanOp::Intersection, FillRule::NonZero, 1e-3);
+        assert!(both.commands().iter().all(|c| matches!(c, MoveTo(_) | ArcTo { .. } | Close)));
+        assert!((area(&both) - lens).abs() < 1e-4);
+        let xor = a.boolean(&b, BooleanOp::Xor, FillRule::NonZero, 1e-3);
+        assert!((area(&xor) - (2.0 * PI - 2.0 * lens)).abs() < 1e-4);
+        // the lens corners are where the circles cross
+        let corner = Point2(0.5, 0.75f64.sqrt());
+        assert!(both.commands().iter().any(|c| matches!(c, MoveTo(p) | ArcTo { to: p, .. } if p.distance(corner) < 1e-3)));
+    }
+
+    #[test]
+    fn mixed_edges() {
+        // a square with a round bite out of its corner, and a cubic hole
+        let bite = square(0.0, 0.0, 4.0).boolean(&circle(Point2(4.0, 4.0), 2.0), BooleanOp::Difference, FillRule::NonZero, 1e-3);
+        let kinds: Vec<_> = bite.commands().iter().map(std::mem::discriminant).collect();
+        assert_eq!(bite.commands().len(), 7);
+        assert_eq!(kinds.iter().filter(|&&k| k == std::mem::discriminant(&LineTo(Point2(0.0, 0.0)))).count(), 4);
+        assert!(bite.commands().iter().any(|c| matches!(c, ArcTo { sweep: false, .. })));
+        assert!((area(&bite) - (16.0 - PI)).abs() < 1e-4);
+        assert!(bite.contains(Point2(1.0, 1.0), FillRule::NonZero) && !bite.contains(Point2(3.5, 3.5), FillRule::NonZero));
+
+        let blob = Path2::new(vec![MoveTo(Point2(1.0, 2.0)), CubicTo(Point2(1.0, 0.5), Point2(3.0, 0.5), Point2(3.0, 2.0)), Close]);
+        let holed = square(0.0, 0.0, 4.0).boolean(&blob, BooleanOp::Difference, FillRule::NonZero, 1e-3);
+        assert_eq!(holed.contours().count(), 2);
+        assert!(holed.commands().iter().any(|c| matches!(c, CubicTo(..))));
+        assert!((area(&holed) - (16.0 - area(&blob).abs())).abs() < 1e-4);
+        assert!(square(0.0, 0.0, 1.0).boolean(&square(5.0, 5.0, 1.0), BooleanOp::Intersection, FillRule::NonZero, 1e-3).commands().is_empty());
+        assert!(Path2::new(Vec::new()).boolean(&Path2::new(Vec::new()), BooleanOp::Union, FillRule::EvenOdd, 1e-3).commands().is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+//! Planes
+
+use crate::{ Closest3, Float, Point2, Point3, Vector3 };
+
+/// 3d Plane: a point on it and its normal
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Plane3<T>(Point3<T>, Vector3<T>);
+
+impl<T> Plane3<T> {
+    /// new: Point × Vector -> Plane
+    pub fn new(point: Point3<T>, normal: Vector3<T>) -> Self {
+        Plane3(point, normal)
+    }
+}
+
+impl<T: Copy> Plane3<T> {
+    /// point: &Plane -> Point
+    pub fn point(&self) -> Point3<T> {
+        self.0
+    }
+
+    /// normal: &Plane -> Vector
+    pub fn normal(&self) -> Vector3<T> {
+        self.1
+    }
+}
+
+impl<T: Float> Plane3<T> {
+    /// through: Point × Point × Point -> Plane
+    /// normal facing the side from which a, b, c run counter-clockwise
+    pub fn through(a: Point3<T>, b: Point3<T>, c: Point3<T>) -> Self {
+        Plane3(a, b.vector_from(a).cross(c.vector_from(a)))
+    }
+
+    /// signed_distance: &Plane × Point -> K
+    /// positive on the side the normal faces
+    pub fn signed_distance(&self, p: Point3<T>) -> T {
+        p.vector_from(self.0).dot(self.1) / self.1.length()
+    }
+
+    /// project: &Plane × Point -> Point
+    /// closest point of the plane
+    pub fn project(&self, p: Point3<T>) -> Point3<T> {
+        let n = self.1;
+        p - n * (p.vector_from(self.0).dot(n) / n.dot(n))
+    }
+
+    /// basis: &Plane -> (Vector, Vector)
+    /// unit vectors u, v along the plane with u × v along the normal
+    pub fn basis(&self) -> (Vector3<T>, Vector3<T>) {
+        let n = self.1 / self.1.length();
+        let (x, y, z) = (n.0.abs(), n.1.abs(), n.2.abs());
+        let (zero, one) = (T::zero(), T::one());
+        // the axis least along the normal
+        let a = if x <= y && x <= z {
+            Vector3(one, zero, zero)
+        } else if y <= z {
+            Vector3(zero, one, zero)
+        } else {
+            Vector3(zero, zero, one)
+        };
+        let u = a - n * a.dot(n);
+        let u = u / u.length();
+        (u, n.cross(u))
+    }
+
+    /// coordinates: &Plane × Point -> Point2
+    /// of the projection, along the basis from the plane's point
+    pub fn coordinates(&self, p: Point3<T>) -> Point2<T> {
+        let (u, v) = self.basis();
+        let d = p.vector_from(self.0);
+        Point2(d.dot(u), d.dot(v))
+    }
+
+    /// point_at: &Plane × Point2 -> Point
+    /// inverse of `coordinates`
+    pub fn point_at(&self, q: Point2<T>) -> Point3<T> {
+        let (u, v) = self.basis();
+        self.0 + u * q.0 + v * q.1
+    }
+}
+
+impl<T: Float> Closest3<T> for Plane3<T> {
+    fn closest_point_on(&self, p: Point3<T>) -> Point3<T> {
+        self.project(p)
+    }
+}
+
+impl<T: Float> Point3<T> {
+    /// reflect_across: Point × &Plane -> Point
+    pub fn reflect_across(self, plane: &Plane3<T>) -> Point3<T> {
+        plane.0 + self.vector_from(plane.0).reflect(plane.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plane3() {
+        let p = Plane3::through(Point3(0.0, 0.0, 1.0), Point3(1.0, 0.0, 1.0), Point3(0.0, 1.0, 1.0));
+        assert_eq!(p.normal(), Vector3(0.0, 0.0, 1.0));
+        assert_eq!(p.signed_distance(Point3(5.0, 5.0, -2.0)), -3.0);
+        assert_eq!(p.project(Point3(5.0, 5.0, -2.0)), Point3(5.0, 5.0, 1.0));
+        assert_eq!(Point3(5.0, 5.0, -2.0).reflect_across(&p), Point3(5.0, 5.0, 4.0));
+        let tilted = Plane3::new(Point3(0.0, 0.0, 0.0), Vector3(1.0, 1.0, 0.0));
+        assert_eq!(Point3(2.0, 0.0, 7.0).reflect_across(&tilted), Point3(0.0, -2.0, 7.0));
+    }
+
+    #[test]
+    fn coordinates() {
+        let p = Plane3::new(Point3(1.0, 2.0, 3.0), Vector3(1.0, 2.0, 2.0));
+        let (u, v) = p.basis();
+        let n = u.cross(v);
+        assert!((u.length() - 1.0).abs() < 1e-12 && u.dot(v).abs() < 1e-12);
+        assert!((n - Vector3(1.0, 2.0, 2.0) / 3.0).length() < 1e-12);
+        let q = Point3(4.0, -1.0, 0.5);
+        assert!(p.point_at(p.coordinates(q)).distance(p.project(q)) < 1e-12);
+        assert_eq!(p.coordinates(p.point()), Point2(0.0, 0.0));
+    }
+}
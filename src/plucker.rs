@@ -0,0 +1,109 @@
+//! Plücker coordinates of 3d lines
+//!
+//! A line through p with direction d is stored as (d, p × d).
+//! The permuted inner product of two lines tells on which side one
+//! passes the other, which gives exact-sign orientation tests for
+//! ray–edge and ray–triangle queries.
+
+use std::ops::{ Add, Sub, Mul };
+
+use crate::{ Point3, Vector3 };
+
+/// Oriented 3d line: direction, moment
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PluckerLine<T>(Vector3<T>, Vector3<T>);
+
+impl<T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Copy> PluckerLine<T> {
+    /// from_points: Point × Point -> Line
+    /// oriented from p to q
+    pub fn from_points(p: Point3<T>, q: Point3<T>) -> Self {
+        PluckerLine::from_point_direction(p, q.vector_from(p))
+    }
+
+    /// from_point_direction: Point × Vector -> Line
+    pub fn from_point_direction(p: Point3<T>, d: Vector3<T>) -> Self {
+        PluckerLine(d, Vector3(p.0, p.1, p.2).cross(d))
+    }
+
+    /// direction: &Line -> Vector
+    pub fn direction(&self) -> Vector3<T> {
+        self.0
+    }
+
+    /// moment: &Line -> Vector
+    pub fn moment(&self) -> Vector3<T> {
+        self.1
+    }
+
+    /// side: Line × Line -> K
+    /// permuted inner product d₁·m₂ + d₂·m₁;
+    /// zero iff the lines are coplanar, the sign tells which way one passes the other
+    pub fn side(self, l: PluckerLine<T>) -> T {
+        self.0.dot(l.1) + l.0.dot(self.1)
+    }
+
+    /// contains: Line × Point -> bool
+    /// p × d = m
+    pub fn contains(self, p: Point3<T>) -> bool
+    where
+        T: PartialEq,
+    {
+        Vector3(p.0, p.1, p.2).cross(self.0) == self.1
+    }
+}
+
+impl<T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + PartialOrd + Default + Copy> PluckerLine<T> {
+    /// crosses_triangle: Line × Point × Point × Point -> bool
+    /// true if the line pierces the triangle abc (boundary included);
+    /// edges shared by adjacent triangles give the same side value,
+    /// so a line never slips between them
+    pub fn crosses_triangle(self, a: Point3<T>, b: Point3<T>, c: Point3<T>) -> bool {
+        let zero = T::default();
+        let s = [
+            self.side(PluckerLine::from_points(a, b)),
+            self.side(PluckerLine::from_points(b, c)),
+            self.side(PluckerLine::from_points(c, a)),
+        ];
+        let all_zero = s.iter().all(|&x| x == zero);
+        !all_zero && (s.iter().all(|&x| x >= zero) || s.iter().all(|&x| x <= zero))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_points() {
+        let l = PluckerLine::from_points(Point3(1, 0, 0), Point3(1, 1, 0));
+        assert_eq!(l.direction(), Vector3(0, 1, 0));
+        assert_eq!(l.moment(), Vector3(0, 0, 1));
+        assert!(l.contains(Point3(1, 5, 0)));
+        assert!(!l.contains(Point3(0, 5, 0)));
+    }
+
+    #[test]
+    fn side() {
+        let x = PluckerLine::from_points(Point3(0, 0, 0), Point3(1, 0, 0));
+        let above = PluckerLine::from_points(Point3(0, 0, 1), Point3(0, 1, 1));
+        let below = PluckerLine::from_points(Point3(0, 0, -1), Point3(0, 1, -1));
+        let meets = PluckerLine::from_points(Point3(5, -1, 0), Point3(5, 1, 0));
+        assert!(x.side(above) * x.side(below) < 0);
+        assert_eq!(x.side(meets), 0);
+        assert_eq!(x.side(x), 0);
+        assert_eq!(x.side(above), above.side(x));
+    }
+
+    #[test]
+    fn crosses_triangle() {
+        let (a, b, c) = (Point3(0, 0, 0), Point3(4, 0, 0), Point3(0, 4, 0));
+        let hit = PluckerLine::from_point_direction(Point3(1, 1, 5), Vector3(0, 0, -1));
+        let miss = PluckerLine::from_point_direction(Point3(3, 3, 5), Vector3(0, 0, -1));
+        let edge = PluckerLine::from_point_direction(Point3(2, 2, 5), Vector3(0, 0, -1));
+        assert!(hit.crosses_triangle(a, b, c));
+        assert!(hit.crosses_triangle(a, c, b));
+        assert!(!miss.crosses_triangle(a, b, c));
+        assert!(edge.crosses_triangle(a, b, c));
+        assert!(edge.crosses_triangle(b, Point3(4, 4, 0), c));
+    }
+}
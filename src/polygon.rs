@@ -0,0 +1,979 @@
+//! Simple polygons
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+use crate::anchor::impl_anchored;
+use crate::arrangement::{ self, lexicographic };
+use crate::containment::ContainmentGrid2;
+use crate::rtree::RTree2;
+use crate::stats;
+use crate::{ Arrangement2, Bounded2, Budget, Closest2, Float, Line2, MultiPolygon2, Point2, Rect2, Segment2 };
+
+/// Rule deciding which points a (possibly self-overlapping) outline fills
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FillRule {
+    /// filled where the winding number is not zero
+    NonZero,
+    /// filled where the winding number is odd
+    EvenOdd,
+}
+
+impl FillRule {
+    /// is_filled: FillRule × i32 -> bool
+    pub fn is_filled(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+/// Method of `Polygon2::triangulate_with`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Triangulation {
+    /// ear clipping of the exterior as `triangulate`, every vertex checked
+    /// against every ear; holes ignored
+    Robust,
+    /// earcut: holes bridged into the exterior and ears clipped off one
+    /// linked list, falling back to splitting where none is left; fast on
+    /// small polygons, degenerate input gives some triangulation rather
+    /// than a correct one
+    Earcut,
+}
+
+/// Reason a ring is not a valid polygon outline
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Defect {
+    /// fewer than three vertices
+    TooFewVertices,
+    /// vertex equal to the one after it
+    RepeatedVertex(usize),
+    /// vertex where the outline turns straight back
+    Spike(usize),
+    /// two non-adjacent edges (indexed by their first vertex) meet
+    SelfIntersection(usize, usize),
+    /// vertices run clockwise
+    Clockwise,
+    /// hole that is not a valid clockwise ring strictly inside the exterior
+    Hole(usize),
+}
+
+/// 2d Polygon: an exterior ring of vertices, the last joined to the first,
+/// and interior rings cut out of it as holes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon2<T>(Vec<Point2<T>>, Vec<Vec<Point2<T>>>);
+
+impl<T> Polygon2<T> {
+    /// new: [Point] -> Polygon
+    pub fn new(vertices: Vec<Point2<T>>) -> Self {
+        Polygon2(vertices, Vec::new())
+    }
+
+    /// with_holes: [Point] × [[Point]] -> Polygon
+    pub fn with_holes(exterior: Vec<Point2<T>>, holes: Vec<Vec<Point2<T>>>) -> Self {
+        Polygon2(exterior, holes)
+    }
+
+    /// vertices: &Polygon -> &[Point]
+    /// the exterior ring
+    pub fn vertices(&self) -> &[Point2<T>] {
+        &self.0
+    }
+
+    /// holes: &Polygon -> &[[Point]]
+    pub fn holes(&self) -> &[Vec<Point2<T>>] {
+        &self.1
+    }
+
+    /// rings: &Polygon -> Iterator<&[Point]>
+    /// the exterior ring, then the holes
+    pub fn rings(&self) -> impl Iterator<Item = &[Point2<T>]> {
+        std::iter::once(&self.0[..]).chain(self.1.iter().map(|h| &h[..]))
+    }
+}
+
+impl<T: Copy> Polygon2<T> {
+    /// edges: &Polygon -> Iterator<Segment>
+    /// closing edges included, holes after the exterior
+    pub fn edges(&self) -> impl Iterator<Item = Segment2<T>> + '_ {
+        self.rings().flat_map(|ring| (0..ring.len()).map(move |i| Segment2::new(ring[i], ring[(i + 1) % ring.len()])))
+    }
+}
+
+impl<T: Float> Polygon2<T> {
+    /// signed_area: &Polygon -> K
+    /// positive for counter-clockwise exterior rings;
+    /// holes count against it whatever their orientation
+    pub fn signed_area(&self) -> T {
+        let (_, [a, _, _, _, _, _]) = self.moments();
+        a
+    }
+
+    /// area: &Polygon -> K
+    pub fn area(&self) -> T {
+        self.signed_area().abs()
+    }
+
+    /// centroid: &Polygon -> Point
+    /// centre of mass of the enclosed region;
+    /// not finite for degenerate (zero area) rings
+    pub fn centroid(&self) -> Point2<T> {
+        let (o, [a, sx, sy, _, _, _]) = self.moments();
+        Point2(o.0 + sx / a, o.1 + sy / a)
+    }
+
+    /// second_moments: &Polygon -> (K, K, K)
+    /// (Ixx, Iyy, Ixy) of the region about its centroid, with
+    /// Ixx = ∫y² dA, Iyy = ∫x² dA, Ixy = ∫xy dA
+    pub fn second_moments(&self) -> (T, T, T) {
+        let (_, [a, sx, sy, xx, yy, xy]) = self.moments();
+        let (cx, cy) = (sx / a, sy / a);
+        let s = if a < T::zero() { -T::one() } else { T::one() };
+        (s * (yy - a * cy * cy), s * (xx - a * cx * cx), s * (xy - a * cx * cy))
+    }
+
+    /// winding_number: &Polygon × Point -> i32
+    /// signed count of turns the rings make around p
+    pub fn winding_number(&self, p: Point2<T>) -> i32 {
+        self.rings().map(|ring| {
+            let n = ring.len();
+            (0..n).map(|i| {
+                let (a, b) = (ring[i], ring[(i + 1) % n]);
+                let side = cross(a, b, p);
+                if a.1 <= p.1 && p.1 < b.1 && side > T::zero() {
+                    1
+                } else if b.1 <= p.1 && p.1 < a.1 && side < T::zero() {
+                    -1
+                } else {
+                    0
+                }
+            }).sum::<i32>()
+        }).sum()
+    }
+
+    /// contains: &Polygon × Point × FillRule -> bool
+    pub fn contains(&self, p: Point2<T>, rule: FillRule) -> bool {
+        rule.is_filled(self.winding_number(p))
+    }
+
+    /// defects: &Polygon -> [Defect]
+    pub fn defects(&self) -> Vec<Defect> {
+        let n = self.0.len();
+        if n < 3 {
+            return vec![Defect::TooFewVertices];
+        }
+        let mut found = Vec::new();
+        for i in 0..n {
+            let (a, b, c) = (self.0[(i + n - 1) % n], self.0[i], self.0[(i + 1) % n]);
+            if b == c {
+                found.push(Defect::RepeatedVertex(i));
+            } else if a != b && cross(a, b, c) == T::zero() && b.vector_from(a).dot(c.vector_from(b)) < T::zero() {
+                found.push(Defect::Spike(i));
+            }
+        }
+        let rings: Vec<&[Point2<T>]> = self.rings().collect();
+        let touching = touching(&rings);
+        found.extend(touching.iter().filter(|&&((r, _), (s, _))| r == 0 && s == 0).map(|&((_, i), (_, j))| Defect::SelfIntersection(i, j)));
+        let (_, [a, ..]) = Polygon2::new(self.0.clone()).moments();
+        if a < T::zero() {
+            found.push(Defect::Clockwise);
+        }
+        if self.1.is_empty() {
+            return found;
+        }
+        let mut crossing = vec![false; self.1.len()];
+        for &((r, _), (s, _)) in &touching {
+            if r == 0 && s > 0 {
+                crossing[s - 1] = true;
+            }
+        }
+        // a hole meeting no exterior edge is inside if its first vertex is
+        let exterior = ContainmentGrid2::new(&Polygon2::new(self.0.clone()));
+        for (k, hole) in self.1.iter().enumerate() {
+            let ring = Polygon2::new(hole.iter().rev().copied().collect());
+            let inside = hole.first().is_some_and(|&p| exterior.winding_number(p) != 0);
+            if crossing[k] || !inside || !ring.is_valid() {
+                found.push(Defect::Hole(k));
+            }
+        }
+        found
+    }
+
+    /// is_valid: &Polygon -> bool
+    /// simple counter-clockwise exterior without repeated vertices or
+    /// spikes, holes likewise but clockwise and strictly inside it
+    pub fn is_valid(&self) -> bool {
+        self.defects().is_empty()
+    }
+
+    /// fill: &Polygon × FillRule -> MultiPolygon
+    /// the region the rings fill under `rule`, cut at their crossings into
+    /// valid polygons with counter-clockwise exteriors and clockwise holes
+    pub fn fill(&self, rule: FillRule) -> MultiPolygon2<T> {
+        self.fill_within(rule, &mut Budget::unlimited()).expect("unlimited budget")
+    }
+
+    /// fill_within: &Polygon × FillRule × &mut Budget -> Option<MultiPolygon>
+    /// `fill`, a unit of the budget per stop of its sweep and per face;
+    /// None if it runs out
+    pub fn fill_within(&self, rule: FillRule, budget: &mut Budget) -> Option<MultiPolygon2<T>> {
+        self.fill_where_within(|w| rule.is_filled(w), budget)
+    }
+
+    /// Region where the winding number of the rings passes `keep`, as
+    /// `fill_within`
+    pub(crate) fn fill_where_within(&self, keep: impl Fn(i32) -> bool, budget: &mut Budget) -> Option<MultiPolygon2<T>> {
+        let [filled, _] = self.regions(&keep, None, budget)?;
+        Some(filled)
+    }
+
+    /// split: &Polygon × &Line -> (MultiPolygon, MultiPolygon)
+    /// the parts left and right of the line, valid if the polygon was
+    pub fn split(&self, line: &Line2<T>) -> (MultiPolygon2<T>, MultiPolygon2<T>) {
+        self.split_within(line, &mut Budget::unlimited()).expect("unlimited budget")
+    }
+
+    /// split_within: &Polygon × &Line × &mut Budget -> Option<(MultiPolygon, MultiPolygon)>
+    /// `split`, as `fill_within`
+    pub fn split_within(&self, line: &Line2<T>, budget: &mut Budget) -> Option<(MultiPolygon2<T>, MultiPolygon2<T>)> {
+        let [left, right] = self.regions(&|w| FillRule::NonZero.is_filled(w), Some(line), budget)?;
+        Some((left, right))
+    }
+
+    /// Filled region on either side of the cut, everything on the first
+    /// without one; a unit of the budget per sweep stop and per face
+    fn regions(&self, keep: &dyn Fn(i32) -> bool, cut: Option<&Line2<T>>, budget: &mut Budget) -> Option<[MultiPolygon2<T>; 2]> {
+        // all rings, as those of a union need not nest
+        let Some(bounds) = Rect2::from_points(self.rings().flatten().copied()) else {
+            return Some([MultiPolygon2::new(Vec::new()), MultiPolygon2::new(Vec::new())]);
+        };
+        let _timer = stats::time("fill");
+        let e = bounds.max().vector_from(bounds.min());
+        let tolerance = (e.0 + e.1) * T::epsilon() * T::from_f64(64.0);
+        let mut segments: Vec<Segment2<T>> = self.edges().collect();
+        let ring_edges = segments.len();
+        if let Some(line) = cut {
+            // the line across the bounds, with room to spare at both ends
+            let d = line.direction();
+            let corners = [bounds.min(), bounds.max(), Point2(bounds.min().0, bounds.max().1), Point2(bounds.max().0, bounds.min().1)];
+            let t: Vec<T> = corners.iter().map(|c| c.vector_from(line.point()).dot(d) / d.dot(d)).collect();
+            let lo = t.iter().fold(T::infinity(), |m, &t| if t < m { t } else { m });
+            let hi = t.iter().fold(-T::infinity(), |m, &t| if t > m { t } else { m });
+            let margin = hi - lo + T::one();
+            segments.push(Segment2::new(line.point() + d * (lo - margin), line.point() + d * (hi + margin)));
+        }
+        let arrangement = Arrangement2::new_within(&segments, tolerance, budget)?;
+        stats::count("fill", "segments", segments.len());
+        stats::count("fill", "faces", arrangement.faces().len());
+        let (vertices, half_edges) = (arrangement.vertices(), arrangement.half_edges());
+        let winding = windings(&arrangement, |s| (s < ring_edges).then_some(0), budget)?;
+        let filled: Vec<bool> = winding.iter().map(|w| w.is_some_and(|w| keep(w[0]))).collect();
+        // side of the cut each face lies on, by its edge farthest from the line
+        let mut side = vec![(T::zero(), 0); arrangement.faces().len()];
+        if let Some(line) = cut {
+            for (h, e) in half_edges.iter().enumerate() {
+                let (a, b) = (vertices[e.origin], vertices[half_edges[h ^ 1].origin]);
+                let d = line.signed_distance(a + b.vector_from(a) * T::from_f64(0.5));
+                if d.abs() > side[e.face].0 {
+                    side[e.face] = (d.abs(), if d < T::zero() { 1 } else { 0 });
+                }
+            }
+        }
+        Some([0, 1].map(|s| {
+            let inside: Vec<bool> = (0..filled.len()).map(|f| filled[f] && side[f].1 == s).collect();
+            trace(&arrangement, &inside)
+        }))
+    }
+
+    /// filled_area: &Polygon × FillRule -> K
+    pub fn filled_area(&self, rule: FillRule) -> T {
+        self.fill(rule).area()
+    }
+
+    /// filled_centroid: &Polygon × FillRule -> Point
+    /// not finite when nothing is filled
+    pub fn filled_centroid(&self, rule: FillRule) -> Point2<T> {
+        self.fill(rule).centroid()
+    }
+
+    /// make_valid: &Polygon -> MultiPolygon
+    /// the region the rings enclose an odd number of times as valid
+    /// polygons; self-intersections are cut, spikes and repeated vertices
+    /// dropped and orientations fixed
+    pub fn make_valid(&self) -> MultiPolygon2<T> {
+        self.fill(FillRule::EvenOdd)
+    }
+
+    /// make_valid_within: &Polygon × &mut Budget -> Option<MultiPolygon>
+    /// `make_valid`, as `fill_within`
+    pub fn make_valid_within(&self, budget: &mut Budget) -> Option<MultiPolygon2<T>> {
+        self.fill_within(FillRule::EvenOdd, budget)
+    }
+
+    /// Centroid of the largest triangle of the exterior's triangulation
+    fn interior_point(&self) -> Option<Point2<T>> {
+        let third = T::one() / T::from_f64(3.0);
+        self.triangulate()
+            .into_iter()
+            .map(|t| t.map(|i| self.0[i]))
+            .map(|[a, b, c]| (cross(a, b, c), Point2((a.0 + b.0 + c.0) * third, (a.1 + b.1 + c.1) * third)))
+            .fold(None, |best: Option<(T, Point2<T>)>, t| match best {
+                Some(b) if b.0 >= t.0 => Some(b),
+                _ if t.0 > T::zero() => Some(t),
+                _ => best,
+            })
+            .map(|(_, p)| p)
+    }
+
+    /// triangulate: &Polygon -> [[usize; 3]]
+    /// ear clipping of the exterior ring, holes ignored; triangles index into `vertices`
+    /// and are counter-clockwise whatever the ring orientation
+    pub fn triangulate(&self) -> Vec<[usize; 3]> {
+        let n = self.0.len();
+        if n < 3 {
+            return Vec::new();
+        }
+        let ring: Vec<usize> = if self.signed_area() < T::zero() {
+            (0..n).rev().collect()
+        } else {
+            (0..n).collect()
+        };
+        let _timer = stats::time("ear clipping");
+        let mut tests = 0;
+        let mut triangles = Vec::with_capacity(n - 2);
+        // what is left of the ring as a doubly linked list over its positions,
+        // searched on from just before the last ear clipped
+        let mut next: Vec<usize> = (1..=n).map(|k| k % n).collect();
+        let mut prev: Vec<usize> = (0..n).map(|k| (k + n - 1) % n).collect();
+        let (mut k, mut m) = (0, n);
+        while m > 3 {
+            let mut tried = 0;
+            while tried < m {
+                tests += 1;
+                if self.is_ear(&ring, &next, [prev[k], k, next[k]]) {
+                    break;
+                }
+                tried += 1;
+                k = next[k];
+            }
+            triangles.push([ring[prev[k]], ring[k], ring[next[k]]]);
+            let (p, q) = (prev[k], next[k]);
+            next[p] = q;
+            prev[q] = p;
+            m -= 1;
+            k = p;
+        }
+        triangles.push([ring[prev[k]], ring[k], ring[next[k]]]);
+        stats::count("ear clipping", "ear tests", tests);
+        triangles
+    }
+
+    /// triangulate_with: &Polygon × Triangulation -> [[usize; 3]]
+    /// triangles index into the rings concatenated in order, exterior first,
+    /// and are counter-clockwise
+    pub fn triangulate_with(&self, method: Triangulation) -> Vec<[usize; 3]> {
+        match method {
+            Triangulation::Robust => self.triangulate(),
+            Triangulation::Earcut => crate::earcut::earcut(&self.rings().collect::<Vec<_>>()),
+        }
+    }
+
+    fn is_ear(&self, ring: &[usize], next: &[usize], [i, j, k]: [usize; 3]) -> bool {
+        let (a, b, c) = (self.0[ring[i]], self.0[ring[j]], self.0[ring[k]]);
+        let zero = T::zero();
+        if cross(a, b, c) <= zero {
+            return false;
+        }
+        let mut l = next[k];
+        while l != i {
+            let p = self.0[ring[l]];
+            if !(p == a || p == b || p == c || cross(a, b, p) < zero || cross(b, c, p) < zero || cross(c, a, p) < zero) {
+                return false;
+            }
+            l = next[l];
+        }
+        true
+    }
+
+    /// Green's theorem integrals of 1, x, y, x², y², xy over the region,
+    /// relative to the first vertex to limit cancellation
+    fn moments(&self) -> (Point2<T>, [T; 6]) {
+        let zero = T::zero();
+        let Some(&o) = self.0.first() else {
+            return (Point2(zero, zero), [zero; 6]);
+        };
+        let mut m = ring_moments(&self.0, o);
+        let outward = m[0] >= zero;
+        for hole in &self.1 {
+            let h = ring_moments(hole, o);
+            let s = if (h[0] >= zero) == outward { -T::one() } else { T::one() };
+            m = std::array::from_fn(|i| m[i] + h[i] * s);
+        }
+        (o, m)
+    }
+}
+
+/// Winding number of each face about the segments of either layer, spread
+/// from the unbounded face across one edge at a time; segments in no layer
+/// only cut. A unit of the budget per face
+fn windings<T: Float>(arrangement: &Arrangement2<T>, layer: impl Fn(usize) -> Option<usize>, budget: &mut Budget) -> Option<Vec<Option<[i32; 2]>>> {
+    let half_edges = arrangement.half_edges();
+    // net count of segments of each layer running along a half-edge, by
+    // which the winding number on its left exceeds the one on its right
+    let along = |h: usize| {
+        let mut net = [0; 2];
+        for (s, same) in arrangement.sources(h) {
+            if let Some(l) = layer(s) {
+                net[l] += if same { 1 } else { -1 };
+            }
+        }
+        net
+    };
+    let mut bounding = vec![Vec::new(); arrangement.faces().len()];
+    for (h, e) in half_edges.iter().enumerate() {
+        bounding[e.face].push(h);
+    }
+    let mut winding = vec![None; arrangement.faces().len()];
+    winding[0] = Some([0, 0]);
+    let mut queue = VecDeque::from([0]);
+    while let Some(f) = queue.pop_front() {
+        if !budget.tick() {
+            return None;
+        }
+        let w = winding[f].unwrap_or([0, 0]);
+        for &h in &bounding[f] {
+            let g = half_edges[h ^ 1].face;
+            if winding[g].is_none() {
+                let a = along(h);
+                winding[g] = Some([w[0] - a[0], w[1] - a[1]]);
+                queue.push_back(g);
+            }
+        }
+    }
+    Some(winding)
+}
+
+/// Two layers of rings in one arrangement, traced once per class into the
+/// faces whose winding numbers about the first and the second it holds
+/// for; a unit of the budget per sweep stop and per face
+pub(crate) fn overlay_within<T: Float, const N: usize>(first: &[Segment2<T>], second: &[Segment2<T>], classes: [&dyn Fn(i32, i32) -> bool; N], budget: &mut Budget) -> Option<[MultiPolygon2<T>; N]> {
+    let Some(bounds) = Rect2::from_points(first.iter().chain(second).flat_map(|s| [s.start(), s.end()])) else {
+        return Some(classes.map(|_| MultiPolygon2::new(Vec::new())));
+    };
+    let _timer = stats::time("fill");
+    let e = bounds.max().vector_from(bounds.min());
+    let tolerance = (e.0 + e.1) * T::epsilon() * T::from_f64(64.0);
+    let segments: Vec<Segment2<T>> = first.iter().chain(second).copied().collect();
+    let arrangement = Arrangement2::new_within(&segments, tolerance, budget)?;
+    stats::count("fill", "segments", segments.len());
+    stats::count("fill", "faces", arrangement.faces().len());
+    let winding = windings(&arrangement, |s| Some(usize::from(s >= first.len())), budget)?;
+    Some(classes.map(|class| {
+        let inside: Vec<bool> = winding.iter().map(|w| w.is_some_and(|w| class(w[0], w[1]))).collect();
+        trace(&arrangement, &inside)
+    }))
+}
+
+/// Rings around the faces marked inside, counter-clockwise around the region,
+/// as polygons with their holes: a hole goes to the shell around the faces
+/// it borders, unless they have several and it must be found by area
+fn trace<T: Float>(arrangement: &Arrangement2<T>, filled: &[bool]) -> MultiPolygon2<T> {
+    let (vertices, half_edges) = (arrangement.vertices(), arrangement.half_edges());
+    let border = |h: usize| filled[half_edges[h].face] && !filled[half_edges[h ^ 1].face];
+    // filled faces joined across the edges between them
+    let mut region: Vec<usize> = (0..filled.len()).collect();
+    fn root(r: &mut [usize], mut f: usize) -> usize {
+        while r[f] != f {
+            r[f] = r[r[f]];
+            f = r[f];
+        }
+        f
+    }
+    for (h, e) in half_edges.iter().enumerate() {
+        let twin = half_edges[h ^ 1].face;
+        if filled[e.face] && filled[twin] {
+            let (a, b) = (root(&mut region, e.face), root(&mut region, twin));
+            region[a] = b;
+        }
+    }
+    let mut seen = vec![false; half_edges.len()];
+    let (mut shells, mut holes) = (Vec::new(), Vec::new());
+    let mut around = vec![Vec::new(); filled.len()];
+    for h in 0..half_edges.len() {
+        if seen[h] || !border(h) {
+            continue;
+        }
+        let mut ring = Vec::new();
+        let mut g = h;
+        while !seen[g] {
+            seen[g] = true;
+            ring.push(vertices[half_edges[g].origin]);
+            // turn clockwise around the end vertex to the next border edge
+            g = half_edges[g].next;
+            while !border(g) {
+                g = half_edges[g ^ 1].next;
+            }
+        }
+        let ring = Polygon2::new(ring);
+        let a = ring.signed_area();
+        let r = root(&mut region, half_edges[h].face);
+        if a > T::zero() {
+            around[r].push(shells.len());
+            shells.push((a, ring));
+        } else if a < T::zero() {
+            holes.push((r, ring));
+        }
+    }
+    for (r, hole) in holes {
+        let host = match around[r][..] {
+            [k] => Some(k),
+            _ => {
+                let Some(p) = hole.interior_point() else { continue };
+                let candidates = if around[r].is_empty() { (0..shells.len()).collect() } else { around[r].clone() };
+                candidates
+                    .into_iter()
+                    .filter(|&k| shells[k].1.winding_number(p) != 0)
+                    .min_by(|&a, &b| shells[a].0.partial_cmp(&shells[b].0).unwrap_or(Ordering::Equal))
+            }
+        };
+        if let Some(k) = host {
+            shells[k].1.1.push(hole.0);
+        }
+    }
+    MultiPolygon2::new(shells.into_iter().map(|(_, shell)| shell).collect())
+}
+
+/// union_all: [Polygon] -> MultiPolygon
+/// the region covered by any of the valid polygons, as valid polygons.
+/// Cascaded: the inputs are halved across the longest extent of their
+/// bounds and each half merged first, so neighbours meet early, their shared
+/// edges dissolve and every edge takes part in O(log n) merges; halves with
+/// disjoint bounds are merged without cutting
+pub fn union_all<T: Float>(polygons: &[Polygon2<T>]) -> MultiPolygon2<T> {
+    union_all_within(polygons, &mut Budget::unlimited()).expect("unlimited budget")
+}
+
+/// union_all_within: [Polygon] × &mut Budget -> Option<MultiPolygon>
+/// `union_all`, a unit of the budget per merge and, within a merge, per
+/// stop of its sweep and per face; None if it runs out, even mid-merge
+pub fn union_all_within<T: Float>(polygons: &[Polygon2<T>], budget: &mut Budget) -> Option<MultiPolygon2<T>> {
+    let _timer = stats::time("union");
+    let mut boxed: Vec<(Rect2<T>, &Polygon2<T>)> = polygons.iter().filter_map(|p| p.bounds().map(|b| (b, p))).collect();
+    cascade(&mut boxed, budget).map(|c| c.1)
+}
+
+/// Union of a run of polygons with their bounds, and its bounds
+fn cascade<T: Float>(run: &mut [(Rect2<T>, &Polygon2<T>)], budget: &mut Budget) -> Option<(Option<Rect2<T>>, MultiPolygon2<T>)> {
+    let bounds = run.iter().map(|e| e.0).reduce(Rect2::union);
+    if let [(_, p)] = run {
+        return Some((bounds, MultiPolygon2::new(vec![p.oriented()])));
+    }
+    let Some(b) = bounds else {
+        return Some((None, MultiPolygon2::new(Vec::new())));
+    };
+    let axis = b.longest_axis();
+    let centre = |r: &Rect2<T>| r.min()[axis] + r.max()[axis];
+    run.sort_by(|x, y| centre(&x.0).partial_cmp(&centre(&y.0)).unwrap_or(Ordering::Equal));
+    let (left, right) = run.split_at_mut(run.len() / 2);
+    let ((lb, l), (rb, r)) = (cascade(left, budget)?, cascade(right, budget)?);
+    if !budget.tick() {
+        return None;
+    }
+    let apart = match (lb, rb) {
+        (Some(l), Some(r)) => l.max().0 < r.min().0 || r.max().0 < l.min().0 || l.max().1 < r.min().1 || r.max().1 < l.min().1,
+        _ => true,
+    };
+    // the halves' polygons are moved on, not copied, at every level
+    let count = l.len() + r.len();
+    let mut members = l.into_iter().chain(r);
+    if apart || count < 2 {
+        stats::count("union", "disjoint merges", 1);
+        return Some((bounds, members.collect()));
+    }
+    stats::count("union", "merges", 1);
+    // every ring in one outline: exteriors wind once around what they cover,
+    // holes undo it, so the non-zero fill is the union
+    let first = members.next().expect("two members at least");
+    let mut rings = first.1;
+    rings.extend(members.flat_map(|p| std::iter::once(p.0).chain(p.1)));
+    let [merged, _] = Polygon2::with_holes(first.0, rings).regions(&|w| FillRule::NonZero.is_filled(w), None, budget)?;
+    Some((bounds, merged))
+}
+
+/// nesting: [[Point]] -> [Option<usize>]
+/// parent of each ring: the smallest other ring around it, None at the top
+/// level or for an empty ring. Rings may touch but not cross; of identical
+/// rings the later one counts as inside
+pub fn nesting<T: Float>(rings: &[Vec<Point2<T>>]) -> Vec<Option<usize>> {
+    // a ring inside another lies within its bounds: those are the only
+    // candidates, each tested against the other's grid and edge index once
+    // both are built
+    let polygons: Vec<Polygon2<T>> = rings.iter().map(|r| Polygon2::new(r.clone())).collect();
+    let bounds: Vec<Option<Rect2<T>>> = polygons.iter().map(|p| p.bounds()).collect();
+    let areas: Vec<T> = polygons.iter().map(|p| p.area()).collect();
+    let parents: Vec<usize> = (0..rings.len()).filter(|&j| rings[j].len() > 2).collect();
+    let tree = RTree2::new(parents.iter().filter_map(|&j| Some((bounds[j]?, j))).collect());
+    let mut grids = vec![None; rings.len()];
+    let mut edges = vec![None; rings.len()];
+    for &j in &parents {
+        grids[j] = Some(ContainmentGrid2::new(&polygons[j]));
+        edges[j] = Some(RTree2::new(polygons[j].edges().map(|e| Rect2::new(e.start(), e.end())).zip(0..).collect()));
+    }
+    let on_ring = |j: usize, p: Point2<T>| {
+        let (r, m) = (&rings[j], rings[j].len());
+        edges[j].as_ref().is_some_and(|e: &RTree2<T>| e.any(&Rect2::new(p, p), |k| Segment2::new(r[k], r[(k + 1) % m]).distance(p) == T::zero()))
+    };
+    let inside = |i: usize, j: usize| {
+        let off = rings[i].iter().find(|&&p| !on_ring(j, p));
+        match (off, &grids[j]) {
+            (Some(&p), Some(grid)) => grid.winding_number(p) != 0,
+            _ => areas[i] < areas[j] || (areas[i] == areas[j] && i > j),
+        }
+    };
+    let mut found = Vec::new();
+    (0..rings.len())
+        .map(|i| {
+            let b = bounds[i]?;
+            found.clear();
+            tree.query(&b, &mut found);
+            found.sort_unstable();
+            let around = |j: usize| bounds[j].is_some_and(|c| c.contains(b.min()) && c.contains(b.max()));
+            found.iter().copied()
+                .filter(|&j| j != i && around(j) && inside(i, j))
+                .min_by(|&a, &b| areas[a].partial_cmp(&areas[b]).unwrap_or(Ordering::Equal))
+        })
+        .collect()
+}
+
+fn ring_moments<T: Float>(ring: &[Point2<T>], o: Point2<T>) -> [T; 6] {
+    let n = ring.len();
+    let mut m = [T::zero(); 6];
+    for i in 0..n {
+        let (p, q) = (ring[i], ring[(i + 1) % n]);
+        let (x0, y0, x1, y1) = (p.0 - o.0, p.1 - o.1, q.0 - o.0, q.1 - o.1);
+        let c = x0 * y1 - x1 * y0;
+        m[0] = m[0] + c;
+        m[1] = m[1] + (x0 + x1) * c;
+        m[2] = m[2] + (y0 + y1) * c;
+        m[3] = m[3] + (x0 * x0 + x0 * x1 + x1 * x1) * c;
+        m[4] = m[4] + (y0 * y0 + y0 * y1 + y1 * y1) * c;
+        m[5] = m[5] + (x0 * y1 + (x0 * y0 + x1 * y1) * T::from_f64(2.0) + x1 * y0) * c;
+    }
+    let k = [2.0, 6.0, 6.0, 12.0, 12.0, 24.0];
+    std::array::from_fn(|i| m[i] / T::from_f64(k[i]))
+}
+
+/// Edges of the rings that cross, overlap or meet at a point, as pairs of
+/// (ring, edge) in order, from one sweep over all of them; edges next to
+/// each other in a ring are left out
+fn touching<T: Float>(rings: &[&[Point2<T>]]) -> Vec<((usize, usize), (usize, usize))> {
+    let mut edges = Vec::new();
+    let mut owner = Vec::new();
+    for (r, ring) in rings.iter().enumerate() {
+        let m = ring.len();
+        edges.extend((0..m).map(|i| Segment2::new(ring[i], ring[(i + 1) % m])));
+        owner.extend((0..m).map(|i| (r, i)));
+    }
+    // the edge before the one starting at each vertex, also indexed by edge
+    let previous = |g: usize| {
+        let (r, i) = owner[g];
+        g - i + (i + rings[r].len() - 1) % rings[r].len()
+    };
+    let swept = arrangement::sweep(&edges, Some(T::zero()), &mut Budget::unlimited()).expect("unlimited budget");
+    let mut pairs: Vec<(usize, usize)> = swept.crossings.iter().map(|&(i, j, _)| (i, j)).collect();
+    // vertices sorted by position, edges i and i - 1 meeting at vertex i
+    let mut order: Vec<usize> = (0..edges.len()).collect();
+    order.sort_by(|&i, &j| lexicographic(edges[i].start(), edges[j].start()));
+    for (i, p) in swept.touches {
+        let from = order.partition_point(|&g| lexicographic(edges[g].start(), p) == Ordering::Less);
+        if edges[i].distance(p) != T::zero() {
+            continue;
+        }
+        for &g in order[from..].iter().take_while(|&&g| edges[g].start() == p) {
+            pairs.extend([(i, g), (i, previous(g))]);
+        }
+    }
+    // edges around a vertex visited more than once
+    for group in order.chunk_by(|&g, &h| edges[g].start() == edges[h].start()).filter(|group| group.len() > 1) {
+        let around: Vec<usize> = group.iter().flat_map(|&g| [g, previous(g)]).collect();
+        pairs.extend(around.iter().enumerate().flat_map(|(k, &e)| around[k + 1..].iter().map(move |&f| (e, f))));
+    }
+    let mut found: Vec<_> = pairs
+        .into_iter()
+        .map(|(e, f)| if e < f { (owner[e], owner[f]) } else { (owner[f], owner[e]) })
+        .filter(|&((r, i), (s, j))| r != s || (i != j && i + 1 != j && !(i == 0 && j + 1 == rings[r].len())))
+        .collect();
+    found.sort_unstable();
+    found.dedup();
+    found
+}
+
+/// (b - a) × (c - a)
+fn cross<T: Float>(a: Point2<T>, b: Point2<T>, c: Point2<T>) -> T {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+impl_anchored!(Polygon2, |p, m| Polygon2(m.points(&p.0), p.1.iter().map(|h| m.points(h)).collect()));
+
+impl<T: Float> Closest2<T> for Polygon2<T> {
+    fn closest_point_on(&self, p: Point2<T>) -> Point2<T> {
+        crate::polyline::nearest(self.edges().map(|e| e.closest_point_on(p)), |q| q.distance(p)).unwrap_or(p)
+    }
+}
+
+impl<T: Float> Bounded2<T> for Polygon2<T> {
+    fn bounds(&self) -> Option<Rect2<T>> {
+        Rect2::from_points(self.0.iter().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Vector2;
+
+    fn near(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    fn rect(x: f64, y: f64, w: f64, h: f64) -> Polygon2<f64> {
+        Polygon2::new(vec![Point2(x, y), Point2(x + w, y), Point2(x + w, y + h), Point2(x, y + h)])
+    }
+
+    #[test]
+    fn area_centroid() {
+        let r = rect(1.0, 2.0, 4.0, 2.0);
+        assert_eq!(r.signed_area(), 8.0);
+        assert_eq!(r.centroid(), Point2(3.0, 3.0));
+        let cw = Polygon2::new(r.vertices().iter().rev().copied().collect());
+        assert_eq!(cw.signed_area(), -8.0);
+        assert_eq!(cw.area(), 8.0);
+        assert_eq!(cw.centroid(), Point2(3.0, 3.0));
+        let t = Polygon2::new(vec![Point2(0.0, 0.0), Point2(3.0, 0.0), Point2(0.0, 3.0)]);
+        assert_eq!(t.centroid(), Point2(1.0, 1.0));
+    }
+
+    fn triangulated_area(p: &Polygon2<f64>) -> f64 {
+        p.triangulate().iter().map(|&[a, b, c]| {
+            let v = p.vertices();
+            let t = cross(v[a], v[b], v[c]);
+            assert!(t >= 0.0);
+            t / 2.0
+        }).sum()
+    }
+
+    #[test]
+    fn triangulate() {
+        let r = rect(0.0, 0.0, 2.0, 1.0);
+        assert_eq!(r.triangulate().len(), 2);
+        assert_eq!(triangulated_area(&r), 2.0);
+        // concave "L"
+        let l = Polygon2::new(vec![
+            Point2(0.0, 0.0), Point2(2.0, 0.0), Point2(2.0, 1.0),
+            Point2(1.0, 1.0), Point2(1.0, 2.0), Point2(0.0, 2.0),
+        ]);
+        assert_eq!(l.triangulate().len(), 4);
+        assert_eq!(triangulated_area(&l), 3.0);
+        let cw = Polygon2::new(l.vertices().iter().rev().copied().collect());
+        assert_eq!(triangulated_area(&cw), 3.0);
+        assert!(Polygon2::new(vec![Point2(0.0, 0.0), Point2(1.0, 0.0)]).triangulate().is_empty());
+    }
+
+    #[test]
+    fn triangulate_earcut() {
+        let area = |p: &Polygon2<f64>| {
+            let v: Vec<Point2<f64>> = p.rings().flatten().copied().collect();
+            p.triangulate_with(Triangulation::Earcut).iter().map(|&[a, b, c]| {
+                let t = cross(v[a], v[b], v[c]);
+                assert!(t >= 0.0);
+                t / 2.0
+            }).sum::<f64>()
+        };
+        let r = rect(0.0, 0.0, 4.0, 4.0);
+        assert_eq!(r.triangulate_with(Triangulation::Earcut).len(), 2);
+        assert_eq!(r.triangulate_with(Triangulation::Robust), r.triangulate());
+        // holes either way round, one touching another
+        let holed = Polygon2::with_holes(r.vertices().iter().rev().copied().collect(), vec![
+            rect(1.0, 1.0, 1.0, 1.0).vertices().to_vec(),
+            rect(2.0, 2.0, 1.0, 1.0).vertices().iter().rev().copied().collect(),
+        ]);
+        assert!(near(area(&holed), 14.0));
+        // star polygons with a hole in the middle
+        let mut seed = 3u64;
+        let mut random = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+        for n in 6..40 {
+            let star: Vec<Point2<f64>> = (0..n).map(|k| {
+                let (s, c) = (k as f64 / n as f64 * std::f64::consts::TAU).sin_cos();
+                let r = 2.0 + random() * 3.0;
+                Point2(r * c, r * s)
+            }).collect();
+            let outline = Polygon2::new(star.clone());
+            assert!(near(area(&outline), outline.area()));
+            let hole = vec![Point2(-1.0, -1.0), Point2(-1.0, 1.0), Point2(1.0, 1.0), Point2(1.0, -1.0)];
+            let holed = Polygon2::with_holes(star, vec![hole]);
+            assert!(near(area(&holed), outline.area() - 4.0));
+            assert_eq!(holed.triangulate_with(Triangulation::Earcut).len(), n + 4);
+        }
+        assert!(Polygon2::new(vec![Point2(0.0, 0.0), Point2(1.0, 0.0)]).triangulate_with(Triangulation::Earcut).is_empty());
+    }
+
+    #[test]
+    fn second_moments() {
+        let (ixx, iyy, ixy) = rect(5.0, -3.0, 4.0, 2.0).second_moments();
+        assert!(near(ixx, 4.0 * 8.0 / 12.0));
+        assert!(near(iyy, 2.0 * 64.0 / 12.0));
+        assert!(near(ixy, 0.0));
+        let cw = Polygon2::new(rect(0.0, 0.0, 4.0, 2.0).vertices().iter().rev().copied().collect());
+        assert!(near(cw.second_moments().0, 4.0 * 8.0 / 12.0));
+        // right triangle with legs a, b: Ixy about centroid = -a²b²/72
+        let t = Polygon2::new(vec![Point2(0.0, 0.0), Point2(3.0, 0.0), Point2(0.0, 6.0)]);
+        let (ixx, iyy, ixy) = t.second_moments();
+        assert!(near(ixx, 3.0 * 216.0 / 36.0));
+        assert!(near(iyy, 6.0 * 27.0 / 36.0));
+        assert!(near(ixy, -9.0 * 36.0 / 72.0));
+    }
+
+    #[test]
+    fn winding_and_defects() {
+        let r = rect(0.0, 0.0, 2.0, 2.0);
+        assert_eq!(r.winding_number(Point2(1.0, 1.0)), 1);
+        assert_eq!(r.winding_number(Point2(3.0, 1.0)), 0);
+        assert!(r.is_valid());
+        let cw = Polygon2::new(r.vertices().iter().rev().copied().collect());
+        assert_eq!(cw.winding_number(Point2(1.0, 1.0)), -1);
+        assert_eq!(cw.defects(), vec![Defect::Clockwise]);
+        let bowtie = Polygon2::new(vec![Point2(0.0, 0.0), Point2(2.0, 2.0), Point2(2.0, 0.0), Point2(0.0, 2.0)]);
+        assert_eq!(bowtie.defects(), vec![Defect::SelfIntersection(0, 2)]);
+        let spiky = Polygon2::new(vec![Point2(0.0, 0.0), Point2(2.0, 0.0), Point2(3.0, 0.0), Point2(2.0, 0.0), Point2(2.0, 2.0), Point2(2.0, 2.0)]);
+        let defects = spiky.defects();
+        assert!(defects.contains(&Defect::Spike(2)));
+        assert!(defects.contains(&Defect::RepeatedVertex(4)));
+        assert_eq!(Polygon2::new(vec![Point2(0.0, 0.0), Point2(1.0, 0.0)]).defects(), vec![Defect::TooFewVertices]);
+    }
+
+    #[test]
+    fn make_valid() {
+        let bowtie = Polygon2::new(vec![Point2(0.0, 0.0), Point2(2.0, 2.0), Point2(2.0, 0.0), Point2(0.0, 2.0)]);
+        let fixed = bowtie.make_valid();
+        assert_eq!(fixed.polygons().len(), 2);
+        assert!(fixed.polygons().iter().all(|p| p.is_valid() && near(p.area(), 1.0)));
+        let cw = Polygon2::new(rect(0.0, 0.0, 2.0, 1.0).vertices().iter().rev().copied().collect());
+        let fixed = cw.make_valid();
+        assert_eq!(fixed.polygons().len(), 1);
+        assert!(fixed.polygons()[0].is_valid());
+        assert!(near(fixed.area(), 2.0));
+        let spiky = Polygon2::new(vec![Point2(0.0, 0.0), Point2(2.0, 0.0), Point2(3.0, 0.0), Point2(2.0, 0.0), Point2(2.0, 2.0), Point2(2.0, 2.0), Point2(0.0, 2.0)]);
+        let fixed = spiky.make_valid();
+        assert_eq!(fixed.polygons().len(), 1);
+        assert_eq!(fixed.polygons()[0].vertices().len(), 4);
+        assert!(near(fixed.area(), 4.0));
+        // keyhole: exterior and hole joined by a doubled bridge
+        let keyhole = Polygon2::new(vec![
+            Point2(0.0, 0.0), Point2(4.0, 0.0), Point2(4.0, 4.0), Point2(0.0, 4.0), Point2(0.0, 2.0),
+            Point2(1.0, 2.0), Point2(1.0, 3.0), Point2(3.0, 3.0), Point2(3.0, 1.0), Point2(1.0, 1.0),
+            Point2(1.0, 2.0), Point2(0.0, 2.0),
+        ]);
+        let fixed = keyhole.make_valid();
+        assert_eq!(fixed.polygons().len(), 1);
+        assert_eq!(fixed.polygons()[0].holes().len(), 1);
+        assert!(fixed.polygons()[0].is_valid());
+        assert!(near(fixed.area(), 12.0));
+    }
+
+    #[test]
+    fn holes() {
+        let outer = rect(0.0, 0.0, 4.0, 4.0).vertices().to_vec();
+        let cw: Vec<_> = rect(1.0, 1.0, 2.0, 1.0).vertices().iter().rev().copied().collect();
+        let p = Polygon2::with_holes(outer.clone(), vec![cw.clone()]);
+        assert!(p.is_valid());
+        assert_eq!(p.area(), 14.0);
+        assert_eq!(p.rings().count(), 2);
+        assert!(near(p.centroid().1, (16.0 * 2.0 - 2.0 * 1.5) / 14.0));
+        assert!(!p.contains(Point2(2.0, 1.5), FillRule::NonZero));
+        assert!(p.contains(Point2(0.5, 1.5), FillRule::EvenOdd));
+        // a hole wound like the exterior still counts against the area but
+        // is filled under the nonzero rule
+        let ccw = rect(1.0, 1.0, 2.0, 1.0).vertices().to_vec();
+        let q = Polygon2::with_holes(outer.clone(), vec![ccw]);
+        assert_eq!(q.area(), 14.0);
+        assert_eq!(q.defects(), vec![Defect::Hole(0)]);
+        assert!(q.contains(Point2(2.0, 1.5), FillRule::NonZero));
+        assert!(!q.contains(Point2(2.0, 1.5), FillRule::EvenOdd));
+        assert!(near(q.filled_area(FillRule::NonZero), 16.0));
+        assert!(near(q.filled_area(FillRule::EvenOdd), 14.0));
+        assert!(near(q.filled_centroid(FillRule::NonZero).0, 2.0));
+        let filled = q.fill(FillRule::EvenOdd);
+        assert_eq!(filled.polygons()[0].holes().len(), 1);
+        assert!(filled.polygons()[0].is_valid());
+        // hole poking out of the exterior
+        let out = Polygon2::with_holes(outer, vec![rect(3.0, 1.0, 2.0, 1.0).vertices().iter().rev().copied().collect()]);
+        assert_eq!(out.defects(), vec![Defect::Hole(0)]);
+        assert!(near(out.filled_area(FillRule::EvenOdd), 16.0));
+    }
+
+    #[test]
+    fn nesting_tree() {
+        let rings = vec![
+            rect(1.0, 1.0, 6.0, 6.0).vertices().to_vec(),
+            rect(2.0, 2.0, 1.0, 1.0).vertices().to_vec(),
+            rect(0.0, 0.0, 10.0, 10.0).vertices().to_vec(),
+            rect(20.0, 0.0, 1.0, 1.0).vertices().to_vec(),
+            // touches its parent along an edge
+            rect(1.0, 4.0, 2.0, 2.0).vertices().to_vec(),
+            rect(2.2, 2.2, 0.5, 0.5).vertices().to_vec(),
+        ];
+        assert_eq!(nesting(&rings), vec![Some(2), Some(0), None, None, Some(0), Some(1)]);
+        let mut with_empty = rings.clone();
+        with_empty.push(Vec::new());
+        assert_eq!(nesting(&with_empty)[6], None);
+        let shapes = MultiPolygon2::from_rings(rings);
+        assert_eq!(shapes.polygons().len(), 4);
+        assert!(shapes.polygons().iter().all(|p| p.signed_area() > 0.0 && p.holes().len() <= 2));
+        assert!(near(shapes.area(), 100.0 - 36.0 + 1.0 - 0.25 + 4.0 + 1.0));
+    }
+
+    #[test]
+    fn union_cascade() {
+        // overlapping tiles in a 10 × 10 grid, some wound clockwise
+        let tiles: Vec<_> = (0..100).map(|k| {
+            let t = rect((k % 10) as f64, (k / 10) as f64, 1.1, 1.1);
+            if k % 3 == 0 { Polygon2::new(t.vertices().iter().rev().copied().collect()) } else { t }
+        }).collect();
+        let merged = union_all(&tiles);
+        assert_eq!(merged.polygons().len(), 1);
+        assert!(merged.polygons()[0].holes().is_empty() && merged.polygons()[0].is_valid());
+        assert!(near(merged.area(), 10.1 * 10.1));
+        // a ring of tiles leaves a hole, a far tile stays apart
+        let mut ring: Vec<_> = (0..9).filter(|&k| k != 4).map(|k| rect((k % 3) as f64, (k / 3) as f64, 1.0, 1.0)).collect();
+        ring.push(rect(10.0, 10.0, 1.0, 1.0));
+        let merged = union_all(&ring);
+        assert_eq!(merged.polygons().len(), 2);
+        assert_eq!(merged.polygons().iter().map(|p| p.holes().len()).sum::<usize>(), 1);
+        assert!(near(merged.area(), 9.0));
+        assert!(union_all::<f64>(&[]).is_empty());
+    }
+
+    #[test]
+    fn split() {
+        // a U shape cut across both arms, and a square with a hole cut through it
+        let u = Polygon2::new(vec![
+            Point2(0.0, 0.0), Point2(3.0, 0.0), Point2(3.0, 3.0), Point2(2.0, 3.0),
+            Point2(2.0, 1.0), Point2(1.0, 1.0), Point2(1.0, 3.0), Point2(0.0, 3.0),
+        ]);
+        let (above, below) = u.split(&Line2::new(Point2(0.0, 2.0), Vector2(-1.0, 0.0)));
+        assert_eq!((above.polygons().len(), below.polygons().len()), (1, 2));
+        assert!(near(above.area(), 5.0) && near(below.area(), 2.0));
+        assert!(above.polygons().iter().chain(below.polygons()).all(|p| p.is_valid()));
+        let ring = Polygon2::with_holes(rect(0.0, 0.0, 4.0, 4.0).vertices().to_vec(), vec![rect(1.0, 1.0, 2.0, 2.0).vertices().iter().rev().copied().collect()]);
+        let (left, right) = ring.split(&Line2::new(Point2(1.0, 0.0), Vector2(1.0, 1.0)));
+        assert!(near(left.area() + right.area(), 12.0));
+        assert!(near(left.area(), 8.0));
+        let (all, none) = ring.split(&Line2::new(Point2(9.0, 0.0), Vector2(0.0, 1.0)));
+        assert_eq!(all.polygons()[0].holes().len(), 1);
+        assert!(none.is_empty());
+    }
+}
@@ -0,0 +1,270 @@
+//! Open polygonal chains
+
+use crate::anchor::impl_anchored;
+use crate::{ Aabb3, Bounded2, Bounded3, Closest2, Closest3, Float, Point2, Point3, Rect2, Segment2 };
+
+/// 2d Polyline: consecutive vertices joined by segments
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polyline2<T>(Vec<Point2<T>>);
+
+/// 3d Polyline: consecutive vertices joined by segments
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polyline3<T>(Vec<Point3<T>>);
+
+impl<T> Polyline2<T> {
+    /// new: [Point] -> Polyline
+    pub fn new(vertices: Vec<Point2<T>>) -> Self {
+        Polyline2(vertices)
+    }
+
+    /// vertices: &Polyline -> &[Point]
+    pub fn vertices(&self) -> &[Point2<T>] {
+        &self.0
+    }
+}
+
+impl<T: Copy> Polyline2<T> {
+    /// segments: &Polyline -> Iterator<Segment>
+    pub fn segments(&self) -> impl Iterator<Item = Segment2<T>> + '_ {
+        self.0.windows(2).map(|w| Segment2::new(w[0], w[1]))
+    }
+}
+
+impl<T> Polyline3<T> {
+    /// new: [Point] -> Polyline
+    pub fn new(vertices: Vec<Point3<T>>) -> Self {
+        Polyline3(vertices)
+    }
+
+    /// vertices: &Polyline -> &[Point]
+    pub fn vertices(&self) -> &[Point3<T>] {
+        &self.0
+    }
+}
+
+impl<T: Float> Bounded2<T> for Polyline2<T> {
+    fn bounds(&self) -> Option<Rect2<T>> {
+        Rect2::from_points(self.0.iter().copied())
+    }
+}
+
+impl<T: Float> Bounded3<T> for Polyline3<T> {
+    fn bounds(&self) -> Option<Aabb3<T>> {
+        Aabb3::from_points(self.0.iter().copied())
+    }
+}
+
+impl_anchored!(Polyline2, |l, m| Polyline2(m.points(&l.0)));
+
+impl<T: Float> Closest2<T> for Polyline2<T> {
+    fn closest_point_on(&self, p: Point2<T>) -> Point2<T> {
+        match self.0[..] {
+            [] => p,
+            [q] => q,
+            _ => nearest(self.segments().map(|s| s.closest_point_on(p)), |q| q.distance(p)).unwrap_or(p),
+        }
+    }
+}
+
+impl<T: Float> Closest3<T> for Polyline3<T> {
+    fn closest_point_on(&self, p: Point3<T>) -> Point3<T> {
+        match self.0[..] {
+            [] => p,
+            [q] => q,
+            _ => {
+                let on = self.0.windows(2).map(|w| {
+                    let (ab, ap) = (w[1].vector_from(w[0]), p.vector_from(w[0]));
+                    let l = ab.dot(ab);
+                    let t = if l == T::zero() { T::zero() } else { ap.dot(ab) / l };
+                    let t = if t < T::zero() { T::zero() } else if t > T::one() { T::one() } else { t };
+                    w[0] + ab * t
+                });
+                nearest(on, |q| q.distance(p)).unwrap_or(p)
+            }
+        }
+    }
+}
+
+macro_rules! impl_resample {
+    ($name:ident) => {
+        impl<T: Float> $name<T> {
+            /// length: &Polyline -> K
+            pub fn length(&self) -> T {
+                self.0.windows(2).fold(T::zero(), |s, w| s + w[1].distance(w[0]))
+            }
+
+            /// resample_uniform: &Polyline × K -> Polyline
+            /// points every `spacing` of arc length from the start, then the end;
+            /// corners falling between samples are cut
+            pub fn resample_uniform(&self, spacing: T) -> Self {
+                if self.0.len() < 2 || spacing <= T::zero() {
+                    return self.clone();
+                }
+                let length = self.length();
+                let last = length - length * T::epsilon() * T::from_f64(64.0);
+                let mut stations = Vec::new();
+                let mut k = T::zero();
+                while k * spacing < last {
+                    stations.push(k * spacing);
+                    k = k + T::one();
+                }
+                let mut points = self.at_stations(&stations);
+                points.0.push(self.0[self.0.len() - 1]);
+                points
+            }
+
+            /// curvature: &Polyline -> [K]
+            /// at each vertex, the turn there over half its adjacent lengths;
+            /// zero at the ends
+            pub fn curvature(&self) -> Vec<T> {
+                let n = self.0.len();
+                let mut kappa = vec![T::zero(); n];
+                for i in 1..n.saturating_sub(1) {
+                    let (d0, d1) = (self.0[i].vector_from(self.0[i - 1]), self.0[i + 1].vector_from(self.0[i]));
+                    let l = d0.length() + d1.length();
+                    if l > T::zero() {
+                        kappa[i] = T::from_f64(2.0) * d0.angle_between(d1) / l;
+                    }
+                }
+                kappa
+            }
+
+            /// max_curvature: &Polyline -> K
+            pub fn max_curvature(&self) -> T {
+                self.curvature().into_iter().fold(T::zero(), |m, k| if k > m { k } else { m })
+            }
+
+            /// resample_adaptive: &Polyline × K × K -> Polyline
+            /// samples at most `spacing` apart, closer where the chain bends so
+            /// that it turns by about `angle` between neighbours; the vertex
+            /// curvature is interpolated along the arc
+            pub fn resample_adaptive(&self, spacing: T, angle: T) -> Self {
+                if self.0.len() < 2 || spacing <= T::zero() || angle <= T::zero() {
+                    return self.clone();
+                }
+                let n = self.0.len();
+                let lengths: Vec<T> = self.0.windows(2).map(|w| w[1].distance(w[0])).collect();
+                let mut at = vec![T::zero()];
+                for &l in &lengths {
+                    at.push(at[at.len() - 1] + l);
+                }
+                let kappa = self.curvature();
+                let curvature = |s: T| {
+                    let j = at.partition_point(|&c| c <= s).clamp(1, n - 1) - 1;
+                    let l = lengths[j];
+                    let t = if l > T::zero() { (s - at[j]) / l } else { T::zero() };
+                    kappa[j] + (kappa[j + 1] - kappa[j]) * t
+                };
+                // greatest curvature over [a, b], at an end or a vertex between
+                let bend = |a: T, b: T| {
+                    let inner = at.partition_point(|&c| c <= a)..at.partition_point(|&c| c < b);
+                    inner.map(|i| kappa[i]).fold(if curvature(a) > curvature(b) { curvature(a) } else { curvature(b) }, |m, k| if k > m { k } else { m })
+                };
+                let last = at[n - 1] - at[n - 1] * T::epsilon() * T::from_f64(64.0);
+                let mut stations = vec![T::zero()];
+                let mut s = T::zero();
+                loop {
+                    // shrinking the step only lowers the curvature it spans, so this settles at once
+                    let mut h = spacing;
+                    for _ in 0..8 {
+                        let k = bend(s, s + h);
+                        let fit = if k * spacing > angle { angle / k } else { spacing };
+                        if fit >= h {
+                            break;
+                        }
+                        h = fit;
+                    }
+                    s = s + h;
+                    if s >= last {
+                        break;
+                    }
+                    stations.push(s);
+                }
+                let mut points = self.at_stations(&stations);
+                points.0.push(self.0[self.0.len() - 1]);
+                points
+            }
+
+            /// Points at increasing arc lengths
+            fn at_stations(&self, stations: &[T]) -> Self {
+                let (mut i, mut start) = (0, T::zero());
+                let mut points = Vec::with_capacity(stations.len());
+                for &s in stations {
+                    while i + 2 < self.0.len() && start + self.0[i + 1].distance(self.0[i]) < s {
+                        start = start + self.0[i + 1].distance(self.0[i]);
+                        i += 1;
+                    }
+                    let d = self.0[i + 1].vector_from(self.0[i]);
+                    let l = d.length();
+                    let t = if l > T::zero() { (s - start) / l } else { T::zero() };
+                    let t = if t < T::zero() { T::zero() } else if t > T::one() { T::one() } else { t };
+                    points.push(self.0[i] + d * t);
+                }
+                $name(points)
+            }
+        }
+    };
+}
+
+impl_resample!(Polyline2);
+impl_resample!(Polyline3);
+
+/// Candidate with the least distance
+pub(crate) fn nearest<P, T: Float>(candidates: impl Iterator<Item = P>, distance: impl Fn(&P) -> T) -> Option<P> {
+    candidates.map(|q| (distance(&q), q)).reduce(|a, b| if b.0 < a.0 { b } else { a }).map(|(_, q)| q)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resample_uniform() {
+        let l = Polyline2::new(vec![Point2(0.0, 0.0), Point2(4.0, 0.0), Point2(4.0, 3.0)]);
+        assert_eq!(l.length(), 7.0);
+        let r = l.resample_uniform(1.0);
+        assert_eq!(r.vertices().len(), 8);
+        assert_eq!(r.vertices()[5], Point2(4.0, 1.0));
+        let r = l.resample_uniform(3.0);
+        assert_eq!(r.vertices(), &[Point2(0.0, 0.0), Point2(3.0, 0.0), Point2(4.0, 2.0), Point2(4.0, 3.0)]);
+        let m = Polyline3::new(vec![Point3(0.0, 0.0, 0.0), Point3(0.0, 0.0, 1.0)]).resample_uniform(0.1);
+        assert_eq!(m.vertices().len(), 11);
+        assert!(m.vertices().windows(2).all(|w| (w[1].distance(w[0]) - 0.1).abs() < 1e-12));
+    }
+
+    #[test]
+    fn resample_adaptive() {
+        // a long straight run into a tight quarter turn of radius 1 at x = 20
+        let mut points: Vec<_> = (0..=20).map(|x| Point2(x as f64, 0.0)).collect();
+        points.extend((1..=16).map(|k| {
+            let a = std::f64::consts::FRAC_PI_2 * k as f64 / 16.0;
+            Point2(20.0 + a.sin(), 1.0 - a.cos())
+        }));
+        let l = Polyline2::new(points);
+        let r = l.resample_adaptive(2.0, 0.1);
+        let v = r.vertices();
+        assert_eq!(v[0], Point2(0.0, 0.0));
+        assert_eq!(v[v.len() - 1], l.vertices()[36]);
+        assert!(v.windows(2).all(|w| w[1].distance(w[0]) <= 2.0 + 1e-12));
+        let straight = v.iter().filter(|p| p.0 < 19.0).count();
+        let turn = v.iter().filter(|p| p.0 > 20.0).count();
+        // the turn spans π/2 / 0.1 angle steps, the run 19 / 2 length steps
+        assert!(straight <= 11 && turn >= 14);
+        assert_eq!(l.resample_adaptive(2.0, 0.0), l);
+    }
+
+    #[test]
+    fn curvature() {
+        // vertices on circles of radius 2 turn at about 1 / 2
+        let arc = |r: f64| Polyline2::new((0..=32).map(|k| {
+            let a = k as f64 / 32.0;
+            Point2(r * a.cos(), r * a.sin())
+        }).collect());
+        let k = arc(2.0).curvature();
+        assert_eq!((k[0], k[32]), (0.0, 0.0));
+        assert!(k[1..32].iter().all(|k| (k - 0.5).abs() < 1e-4));
+        assert!(arc(0.5).max_curvature() > 1.99);
+        let bent = Polyline3::new(vec![Point3(0.0, 0.0, 0.0), Point3(1.0, 0.0, 0.0), Point3(1.0, 0.0, 1.0)]);
+        assert!((bent.curvature()[1] - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+    }
+}
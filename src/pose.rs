@@ -0,0 +1,141 @@
+//! 2d poses: position and heading, as in planar odometry
+//!
+//! A pose maps points from its own frame into the world: turn by the
+//! heading, then move to the position. Composition chains frames, so the
+//! pose of b seen from a is a⁻¹ b, and a (a⁻¹ b) = b.
+
+use std::ops::Mul;
+
+use crate::{ Float, Point2, Vector2 };
+
+/// 2d Pose: position, heading in (-π, π] counter-clockwise from +x
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Pose2<T>(Point2<T>, T);
+
+/// Angle wrapped into (-π, π]
+fn wrapped<T: Float>(angle: T) -> T {
+    let tau = T::pi() * T::from_f64(2.0);
+    let a = angle - tau * ((angle + T::pi()) / tau).floor();
+    if a <= -T::pi() { a + tau } else { a }
+}
+
+/// Vector turned counter-clockwise by the angle
+fn rotate<T: Float>(v: Vector2<T>, angle: T) -> Vector2<T> {
+    let (s, c) = (angle.sin(), angle.cos());
+    Vector2(c * v.0 - s * v.1, s * v.0 + c * v.1)
+}
+
+impl<T: Float> Pose2<T> {
+    /// new: Point × K -> Pose
+    /// the heading is wrapped into (-π, π]
+    pub fn new(position: Point2<T>, heading: T) -> Self {
+        Pose2(position, wrapped(heading))
+    }
+
+    /// identity: Pose
+    pub fn identity() -> Self {
+        Pose2(Point2(T::zero(), T::zero()), T::zero())
+    }
+
+    /// position: &Pose -> Point
+    pub fn position(&self) -> Point2<T> {
+        self.0
+    }
+
+    /// heading: &Pose -> K
+    pub fn heading(&self) -> T {
+        self.1
+    }
+
+    /// direction: &Pose -> Vector
+    /// unit vector along the heading
+    pub fn direction(&self) -> Vector2<T> {
+        Vector2(self.1.cos(), self.1.sin())
+    }
+
+    /// inverse: Pose -> Pose
+    /// the world frame seen from the pose
+    pub fn inverse(self) -> Self {
+        let Vector2(x, y) = rotate(Vector2(-self.0.0, -self.0.1), -self.1);
+        Pose2::new(Point2(x, y), -self.1)
+    }
+
+    /// transform_point: Pose × Point -> Point
+    /// from the pose's frame into the world
+    pub fn transform_point(self, p: Point2<T>) -> Point2<T> {
+        self.0 + self.transform_vector(Vector2(p.0, p.1))
+    }
+
+    /// transform_vector: Pose × Vector -> Vector
+    pub fn transform_vector(self, v: Vector2<T>) -> Vector2<T> {
+        rotate(v, self.1)
+    }
+
+    /// between: Pose × Pose -> Pose
+    /// the other pose seen from this one, a⁻¹ b: the odometry delta
+    /// taking this pose to the other
+    pub fn between(self, other: Pose2<T>) -> Self {
+        self.inverse() * other
+    }
+
+    fn compose(self, p: Pose2<T>) -> Self {
+        Pose2::new(self.transform_point(p.0), self.1 + p.1)
+    }
+}
+
+/// (*): Pose × Pose -> Pose
+/// the right pose taken in the left one's frame, as dead reckoning
+/// appends a delta
+impl<T: Float> Mul for Pose2<T> {
+    type Output = Pose2<T>;
+
+    fn mul(self, p: Pose2<T>) -> Self::Output {
+        self.compose(p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn near(a: Pose2<f64>, b: Pose2<f64>) -> bool {
+        let turn = wrapped(a.heading() - b.heading());
+        a.position().distance(b.position()) < 1e-12 && turn.abs() < 1e-12
+    }
+
+    #[test]
+    fn compose_inverse() {
+        let a = Pose2::new(Point2(1.0, 2.0), PI / 2.0);
+        assert_eq!(a.transform_point(Point2(1.0, 0.0)), Point2(1.0, 3.0));
+        let step = Pose2::new(Point2(2.0, 0.0), PI / 2.0);
+        let b = a * step;
+        assert!(near(b, Pose2::new(Point2(1.0, 4.0), PI)));
+        assert!(near(a * a.inverse(), Pose2::identity()));
+        assert!(near(a.inverse() * a, Pose2::identity()));
+        let p = Point2(-0.5, 3.0);
+        assert!(b.transform_point(p).distance(a.transform_point(step.transform_point(p))) < 1e-12);
+        assert_eq!(Pose2::new(Point2(0.0, 0.0), 3.0 * PI).heading(), PI);
+        assert_eq!(Pose2::new(Point2(0.0, 0.0), -PI).heading(), PI);
+    }
+
+    #[test]
+    fn odometry_deltas() {
+        let path = [
+            Pose2::new(Point2(0.0, 0.0), 0.0),
+            Pose2::new(Point2(1.0, 0.5), 0.4),
+            Pose2::new(Point2(1.5, 2.0), 2.9),
+            Pose2::new(Point2(-1.0, 2.5), -2.8),
+        ];
+        let deltas: Vec<Pose2<f64>> = path.windows(2).map(|w| w[0].between(w[1])).collect();
+        // chaining the deltas from the start replays the path
+        let mut at = path[0];
+        for (delta, expected) in deltas.iter().zip(&path[1..]) {
+            at = at * *delta;
+            assert!(near(at, *expected));
+        }
+        // a move straight ahead is ahead in the pose's own frame
+        let d = path[1].between(path[1] * Pose2::new(Point2(3.0, 0.0), 0.0));
+        assert!(near(d, Pose2::new(Point2(3.0, 0.0), 0.0)));
+    }
+}
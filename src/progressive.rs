@@ -0,0 +1,266 @@
+//! Progressive meshes: a coarse base and the vertex splits refining it
+//!
+//! The quadric edge collapses of `lod`, read backwards, become vertex
+//! splits: the collapse of `from` onto `to` is undone by splitting `to`,
+//! bringing `from` back with the triangles the collapse removed and moving
+//! to it the corners that pointed at it before (Hoppe). Vertices and
+//! triangles are numbered in the order the splits bring them, so the base
+//! followed by any prefix of the splits is a mesh, and a receiver can draw
+//! the mesh at each step of a stream, or step back down.
+
+use std::collections::HashMap;
+
+use crate::lod::Decimator;
+use crate::{ Budget, Float, Point3, TriMesh };
+
+/// Record turning one vertex into two
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexSplit<T> {
+    /// vertex split, keeping its number
+    pub parent: usize,
+    /// position of the vertex brought back, numbered after all before it
+    pub position: Point3<T>,
+    /// corners (triangle, 0..3) moved from the parent to the new vertex
+    pub corners: Vec<(usize, usize)>,
+    /// triangles brought back, numbered after all before them
+    pub faces: Vec<[usize; 3]>,
+}
+
+/// Base mesh, the splits held for it, and how many of them are applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressiveMesh<T> {
+    vertices: Vec<Point3<T>>,
+    triangles: Vec<[usize; 3]>,
+    splits: Vec<VertexSplit<T>>,
+    level: usize,
+}
+
+impl<T: Float> ProgressiveMesh<T> {
+    /// new: Mesh -> ProgressiveMesh
+    /// a base with no splits yet, to `push` them onto as they arrive
+    pub fn new(base: TriMesh<T>) -> Self {
+        ProgressiveMesh { vertices: base.vertices().to_vec(), triangles: base.triangles().to_vec(), splits: Vec::new(), level: 0 }
+    }
+
+    /// push: &mut ProgressiveMesh × VertexSplit -> bool
+    /// holds a split after the others, leaving the level alone; false,
+    /// holding nothing, if it names vertices or triangles that the mesh
+    /// refined through all the held splits would not have
+    pub fn push(&mut self, split: VertexSplit<T>) -> bool {
+        let pending = &self.splits[self.level..];
+        let vertices = self.vertices.len() + pending.len();
+        let triangles = self.triangles.len() + pending.iter().map(|s| s.faces.len()).sum::<usize>();
+        let fits = split.parent < vertices
+            && split.corners.iter().all(|&(t, k)| t < triangles && k < 3)
+            && split.faces.iter().flatten().all(|&v| v <= vertices);
+        if fits {
+            self.splits.push(split);
+        }
+        fits
+    }
+
+    /// splits: &ProgressiveMesh -> [VertexSplit]
+    /// every split held, applied or not, in order
+    pub fn splits(&self) -> &[VertexSplit<T>] {
+        &self.splits
+    }
+
+    /// level: &ProgressiveMesh -> usize
+    /// number of splits applied
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// refine: &mut ProgressiveMesh × usize -> usize
+    /// applies up to n more splits, returning how many were
+    pub fn refine(&mut self, n: usize) -> usize {
+        let steps = n.min(self.splits.len() - self.level);
+        for split in &self.splits[self.level..self.level + steps] {
+            let v = self.vertices.len();
+            self.vertices.push(split.position);
+            for &(t, k) in &split.corners {
+                self.triangles[t][k] = v;
+            }
+            self.triangles.extend(&split.faces);
+        }
+        self.level += steps;
+        steps
+    }
+
+    /// coarsen: &mut ProgressiveMesh × usize -> usize
+    /// undoes up to n applied splits, latest first, returning how many were
+    pub fn coarsen(&mut self, n: usize) -> usize {
+        let steps = n.min(self.level);
+        for split in self.splits[self.level - steps..self.level].iter().rev() {
+            self.triangles.truncate(self.triangles.len() - split.faces.len());
+            for &(t, k) in &split.corners {
+                self.triangles[t][k] = split.parent;
+            }
+            self.vertices.pop();
+        }
+        self.level -= steps;
+        steps
+    }
+
+    /// vertices: &ProgressiveMesh -> [Point]
+    /// of the mesh at the current level
+    pub fn vertices(&self) -> &[Point3<T>] {
+        &self.vertices
+    }
+
+    /// triangles: &ProgressiveMesh -> [[usize; 3]]
+    /// of the mesh at the current level
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    /// mesh: &ProgressiveMesh -> Mesh
+    /// a copy of the mesh at the current level
+    pub fn mesh(&self) -> TriMesh<T> {
+        TriMesh::new(self.vertices.clone(), self.triangles.clone())
+    }
+
+    /// base: &ProgressiveMesh -> Mesh
+    /// a copy of the mesh with no splits applied
+    pub fn base(&self) -> TriMesh<T> {
+        let mut at = self.clone();
+        at.coarsen(self.level);
+        at.mesh()
+    }
+}
+
+impl<T: Float> TriMesh<T> {
+    /// progressive: &Mesh × f32 -> ProgressiveMesh
+    /// collapses as in `generate_lods` down to at most that share of the
+    /// triangles (or as few as collapses reach) for the base, holding the
+    /// splits back to the whole mesh, renumbered, at level 0. Texture
+    /// coordinates and normals are not carried over
+    pub fn progressive(&self, ratio: f32) -> ProgressiveMesh<T> {
+        self.progressive_within(ratio, &mut Budget::unlimited()).expect("unlimited budget")
+    }
+
+    /// progressive_within: &Mesh × f32 × &mut Budget -> Option<ProgressiveMesh>
+    /// `progressive`, a unit of the budget per collapse; None if it runs out
+    pub fn progressive_within(&self, ratio: f32, budget: &mut Budget) -> Option<ProgressiveMesh<T>> {
+        let target = ((ratio.clamp(0.0, 1.0) as f64) * self.triangles().len() as f64).floor() as usize;
+        let mut d = Decimator::new(self.vertices(), self.triangles());
+        let mut collapses = Vec::new();
+        while d.live > target {
+            if !budget.tick() {
+                return None;
+            }
+            match d.step() {
+                Some(c) => collapses.push(c),
+                None => break,
+            }
+        }
+        // the base keeps the vertices never collapsed, in their order
+        let mut gone = vec![false; self.vertices().len()];
+        collapses.iter().for_each(|c| gone[c.from] = true);
+        let mut number: HashMap<usize, usize> = (0..gone.len()).filter(|&v| !gone[v]).enumerate().map(|(k, v)| (v, k)).collect();
+        let mut face: HashMap<usize, usize> = (0..d.triangles.len()).filter(|&t| d.alive[t]).enumerate().map(|(k, t)| (t, k)).collect();
+        let vertices: Vec<Point3<T>> = (0..gone.len()).filter(|&v| !gone[v]).map(|v| self.vertices()[v]).collect();
+        let triangles: Vec<[usize; 3]> = (0..d.triangles.len()).filter(|&t| d.alive[t]).map(|t| d.triangles[t].map(|v| number[&v])).collect();
+        let (mut nv, mut nt) = (vertices.len(), triangles.len());
+        let mut splits = Vec::with_capacity(collapses.len());
+        for c in collapses.iter().rev() {
+            number.insert(c.from, nv);
+            nv += 1;
+            let corners = c.moved.iter().map(|&(t, k)| (face[&t], k)).collect();
+            for &t in &c.removed {
+                face.insert(t, nt);
+                nt += 1;
+            }
+            let faces = c.removed.iter().map(|&t| d.triangles[t].map(|v| number[&v])).collect();
+            splits.push(VertexSplit { parent: number[&c.to], position: self.vertices()[c.from], corners, faces });
+        }
+        Some(ProgressiveMesh { vertices, triangles, splits, level: 0 })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ Binary, Compression };
+    use std::f64::consts::PI;
+
+    fn sphere(rings: usize, segments: usize) -> TriMesh<f64> {
+        let mut vertices = vec![Point3(0.0, 0.0, 1.0)];
+        for i in 1..rings {
+            let theta = PI * i as f64 / rings as f64;
+            for j in 0..segments {
+                let phi = 2.0 * PI * j as f64 / segments as f64;
+                vertices.push(Point3(theta.sin() * phi.cos(), theta.sin() * phi.sin(), theta.cos()));
+            }
+        }
+        vertices.push(Point3(0.0, 0.0, -1.0));
+        let south = vertices.len() - 1;
+        let at = |i: usize, j: usize| 1 + (i - 1) * segments + j % segments;
+        let mut triangles = Vec::new();
+        for j in 0..segments {
+            triangles.push([0, at(1, j), at(1, j + 1)]);
+            triangles.push([south, at(rings - 1, j + 1), at(rings - 1, j)]);
+            for i in 1..rings - 1 {
+                triangles.push([at(i, j), at(i + 1, j), at(i + 1, j + 1)]);
+                triangles.push([at(i, j), at(i + 1, j + 1), at(i, j + 1)]);
+            }
+        }
+        TriMesh::new(vertices, triangles)
+    }
+
+    fn sorted(mesh: &TriMesh<f64>) -> Vec<[u64; 9]> {
+        let mut t: Vec<[u64; 9]> = mesh.triangles().iter().map(|tri| {
+            let p = tri.map(|i| mesh.vertices()[i]);
+            // rotated to start at its least corner, keeping the winding
+            let k = (0..3).min_by(|&a, &b| p[a].0.total_cmp(&p[b].0).then(p[a].1.total_cmp(&p[b].1)).then(p[a].2.total_cmp(&p[b].2))).unwrap();
+            let q = [p[k], p[(k + 1) % 3], p[(k + 2) % 3]];
+            q.map(|v| [v.0.to_bits(), v.1.to_bits(), v.2.to_bits()]).concat().try_into().unwrap()
+        }).collect();
+        t.sort_unstable();
+        t
+    }
+
+    #[test]
+    fn refine_and_coarsen() {
+        let mesh = sphere(12, 24);
+        let mut pm = mesh.progressive(0.05);
+        let base = pm.mesh();
+        assert!(base.triangles().len() <= mesh.triangles().len() / 20);
+        assert_eq!(pm.splits().len() + base.vertices().len(), mesh.vertices().len());
+        // every step a closed surface, growing to the whole mesh
+        let mut last = base.triangles().len();
+        while pm.refine(10) > 0 {
+            let m = pm.mesh();
+            assert!(m.triangles().len() > last && m.volume() > 0.0);
+            last = m.triangles().len();
+        }
+        assert_eq!(pm.level(), pm.splits().len());
+        assert_eq!(sorted(&pm.mesh()), sorted(&mesh));
+        assert_eq!(pm.coarsen(usize::MAX), pm.splits().len());
+        assert_eq!(pm.mesh(), base);
+        pm.refine(7);
+        assert_eq!(pm.base(), base);
+        assert_eq!((pm.vertices().len(), pm.level()), (base.vertices().len() + 7, 7));
+    }
+
+    #[test]
+    fn streamed() {
+        let mesh = sphere(8, 16);
+        let pm = mesh.progressive(0.1);
+        let compression = Compression::None;
+        let mut received = ProgressiveMesh::new(TriMesh::from_bytes(&pm.base().to_bytes(compression)).unwrap());
+        for split in pm.splits() {
+            assert!(received.push(VertexSplit::from_bytes(&split.to_bytes(compression)).unwrap()));
+            received.refine(1);
+        }
+        assert_eq!(received.mesh(), {
+            let mut all = pm.clone();
+            all.refine(usize::MAX);
+            all.mesh()
+        });
+        assert_eq!(ProgressiveMesh::from_bytes(&pm.to_bytes(compression)), Some(pm.clone()));
+        let bad = VertexSplit { parent: 1000, position: Point3(0.0, 0.0, 0.0), corners: Vec::new(), faces: Vec::new() };
+        assert!(!received.push(bad));
+        assert_eq!(received.splits().len(), pm.splits().len());
+    }
+}
@@ -0,0 +1,174 @@
+//! Projections of shapes onto axes
+//!
+//! A shape projected onto an axis is the interval of p · axis over its
+//! points. Two convex shapes are disjoint exactly when some axis separates
+//! their projections, which is the test of separating-axis collision code;
+//! the bounds of a shape along an axis are its projection onto it.
+
+use crate::{ Aabb3, Circle2, Cone, Cylinder, Float, Interval, Point2, Point3, PointCloud3, Polygon2, Polyline2, Polyline3, Rect2, Segment2, Sphere, Support3, TriMesh, Triangle3, Vector2, Vector3 };
+
+/// 2d shapes with a projection onto any axis
+pub trait Projection2<T: Float> {
+    /// projection: &Shape × Vector -> Option<Interval>
+    /// the values p · axis over the shape, in multiples of the axis length;
+    /// None for empty shapes
+    fn projection(&self, axis: Vector2<T>) -> Option<Interval<T>>;
+
+    /// separated_on: &Shape × &Shape × Vector -> bool
+    /// whether the projections are disjoint, touching not counting; an
+    /// empty shape is separated from any
+    fn separated_on<S: Projection2<T> + ?Sized>(&self, other: &S, axis: Vector2<T>) -> bool {
+        match (self.projection(axis), other.projection(axis)) {
+            (Some(a), Some(b)) => !a.overlaps(b),
+            _ => true,
+        }
+    }
+}
+
+/// 3d shapes with a projection onto any axis
+pub trait Projection3<T: Float> {
+    /// projection: &Shape × Vector -> Option<Interval>
+    /// the values p · axis over the shape, in multiples of the axis length;
+    /// None for empty shapes
+    fn projection(&self, axis: Vector3<T>) -> Option<Interval<T>>;
+
+    /// separated_on: &Shape × &Shape × Vector -> bool
+    /// whether the projections are disjoint, touching not counting; an
+    /// empty shape is separated from any
+    fn separated_on<S: Projection3<T> + ?Sized>(&self, other: &S, axis: Vector3<T>) -> bool {
+        match (self.projection(axis), other.projection(axis)) {
+            (Some(a), Some(b)) => !a.overlaps(b),
+            _ => true,
+        }
+    }
+}
+
+fn dot2<T: Float>(p: Point2<T>, axis: Vector2<T>) -> T {
+    p.0 * axis.0 + p.1 * axis.1
+}
+
+fn dot3<T: Float>(p: Point3<T>, axis: Vector3<T>) -> T {
+    p.0 * axis.0 + p.1 * axis.1 + p.2 * axis.2
+}
+
+impl<T: Float> Projection2<T> for Point2<T> {
+    fn projection(&self, axis: Vector2<T>) -> Option<Interval<T>> {
+        Some(Interval::point(dot2(*self, axis)))
+    }
+}
+
+impl<T: Float> Projection2<T> for Segment2<T> {
+    fn projection(&self, axis: Vector2<T>) -> Option<Interval<T>> {
+        Some(Interval::spanning(dot2(self.start(), axis), dot2(self.end(), axis)))
+    }
+}
+
+impl<T: Float> Projection2<T> for Rect2<T> {
+    fn projection(&self, axis: Vector2<T>) -> Option<Interval<T>> {
+        let (lo, hi) = (self.min(), self.max());
+        Interval::from_values([lo, Point2(hi.0, lo.1), hi, Point2(lo.0, hi.1)].map(|p| dot2(p, axis)))
+    }
+}
+
+impl<T: Float> Projection2<T> for Circle2<T> {
+    fn projection(&self, axis: Vector2<T>) -> Option<Interval<T>> {
+        let (c, r) = (dot2(self.center(), axis), self.radius() * axis.length());
+        Some(Interval::spanning(c - r, c + r))
+    }
+}
+
+impl<T: Float> Projection2<T> for Polyline2<T> {
+    fn projection(&self, axis: Vector2<T>) -> Option<Interval<T>> {
+        Interval::from_values(self.vertices().iter().map(|&p| dot2(p, axis)))
+    }
+}
+
+/// by the exterior ring, the holes lying within it
+impl<T: Float> Projection2<T> for Polygon2<T> {
+    fn projection(&self, axis: Vector2<T>) -> Option<Interval<T>> {
+        Interval::from_values(self.vertices().iter().map(|&p| dot2(p, axis)))
+    }
+}
+
+/// Convex shapes, between their support points in the two directions
+macro_rules! impl_by_support {
+    ($($shape:ident),*) => {$(
+        impl<T: Float> Projection3<T> for $shape<T> {
+            fn projection(&self, axis: Vector3<T>) -> Option<Interval<T>> {
+                Some(Interval::spanning(dot3(self.support(-axis), axis), dot3(self.support(axis), axis)))
+            }
+        }
+    )*};
+}
+
+impl_by_support!(Sphere, Aabb3, Cone, Cylinder);
+
+impl<T: Float> Projection3<T> for Point3<T> {
+    fn projection(&self, axis: Vector3<T>) -> Option<Interval<T>> {
+        Some(Interval::point(dot3(*self, axis)))
+    }
+}
+
+impl<T: Float> Projection3<T> for Triangle3<T> {
+    fn projection(&self, axis: Vector3<T>) -> Option<Interval<T>> {
+        Interval::from_values(self.vertices().map(|p| dot3(p, axis)))
+    }
+}
+
+impl<T: Float> Projection3<T> for Polyline3<T> {
+    fn projection(&self, axis: Vector3<T>) -> Option<Interval<T>> {
+        Interval::from_values(self.vertices().iter().map(|&p| dot3(p, axis)))
+    }
+}
+
+impl<T: Float> Projection3<T> for TriMesh<T> {
+    fn projection(&self, axis: Vector3<T>) -> Option<Interval<T>> {
+        Interval::from_values(self.vertices().iter().map(|&p| dot3(p, axis)))
+    }
+}
+
+impl<T: Float, A> Projection3<T> for PointCloud3<T, A> {
+    fn projection(&self, axis: Vector3<T>) -> Option<Interval<T>> {
+        Interval::from_values(self.points().iter().map(|&p| dot3(p, axis)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ Axis2, Axis3 };
+
+    #[test]
+    fn planar() {
+        let r = Rect2::new(Point2(0.0, 0.0), Point2(2.0, 1.0));
+        assert_eq!(r.projection(Vector2(1.0, 0.0)), Some(r.interval(Axis2::X)));
+        assert_eq!(r.projection(Vector2(1.0, -1.0)), Some(Interval::new(-1.0, 2.0)));
+        assert_eq!(Rect2::from_intervals(r.interval(Axis2::X), r.interval(Axis2::Y)), r);
+        let c = Circle2::new(Point2(4.0, 0.0), 1.0);
+        assert_eq!(c.projection(Vector2(0.0, 2.0)), Some(Interval::new(-2.0, 2.0)));
+        // the boxes overlap along both axes, the diagonal tells them apart
+        let s = Segment2::new(Point2(1.5, 2.0), Point2(3.0, 0.5));
+        assert!(!r.separated_on(&s, Vector2(1.0, 0.0)) && !r.separated_on(&s, Vector2(0.0, 1.0)));
+        assert!(r.separated_on(&s, Vector2(1.0, 1.0)));
+        assert!(r.separated_on(&c, Vector2(1.0, 0.0)));
+        assert!(Polyline2::new(Vec::new()).separated_on(&r, Vector2(1.0, 0.0)));
+    }
+
+    #[test]
+    fn spatial() {
+        let b = Aabb3::new(Point3(0.0, 0.0, 0.0), Point3(1.0, 2.0, 3.0));
+        for a in Axis3::ALL {
+            let mut axis = Vector3(0.0, 0.0, 0.0);
+            axis[a] = 1.0;
+            assert_eq!(b.projection(axis), Some(b.interval(a)));
+        }
+        assert_eq!(Aabb3::from_intervals(b.interval(Axis3::X), b.interval(Axis3::Y), b.interval(Axis3::Z)), b);
+        let s = Sphere::new(Point3(3.0, 3.0, 0.0), 1.0);
+        assert_eq!(s.projection(Vector3(0.0, 0.0, 2.0)), Some(Interval::new(-2.0, 2.0)));
+        let t = Triangle3::new(Point3(2.0, 0.0, 0.0), Point3(0.0, 3.0, 0.0), Point3(0.0, 0.0, 4.0));
+        assert_eq!(t.projection(Vector3(1.0, 1.0, 1.0)), Some(Interval::new(2.0, 4.0)));
+        assert!(b.overlaps(&Aabb3::new(Point3(1.0, 2.0, 3.0), Point3(4.0, 4.0, 4.0))));
+        assert!(!b.separated_on(&t, Vector3(1.0, 0.0, 0.0)));
+        assert!(s.separated_on(&b, Vector3(1.0, 1.0, 0.0)));
+    }
+}
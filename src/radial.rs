@@ -0,0 +1,118 @@
+//! Radial ordering around a center
+//!
+//! Points are ordered counter-clockwise by direction from the center,
+//! starting along +x, nearer first along the same direction, and the
+//! center itself before everything. Directions are compared with
+//! `orient2d`, so collinear points tie exactly and the order is a strict
+//! weak one whatever the rounding; equal points keep their input order.
+
+use std::cmp::Ordering;
+
+use crate::predicates::orient2d;
+use crate::{ Float, Point2 };
+
+/// compare_radially: Point × Point × Point -> Ordering
+/// order of p and q around the center
+pub fn compare_radially<T: Float>(center: Point2<T>, p: Point2<T>, q: Point2<T>) -> Ordering {
+    half(center, p).cmp(&half(center, q))
+        .then_with(|| orient2d(center, q, p))
+        .then_with(|| farther(center, p, q))
+}
+
+/// compare_directions: Point × Point × Point -> Ordering
+/// as `compare_radially`, Equal for points along the same direction
+pub fn compare_directions<T: Float>(center: Point2<T>, p: Point2<T>, q: Point2<T>) -> Ordering {
+    half(center, p).cmp(&half(center, q)).then_with(|| orient2d(center, q, p))
+}
+
+/// sort_radially_around: Point × &mut [Point] -> ()
+/// stable
+pub fn sort_radially_around<T: Float>(center: Point2<T>, points: &mut [Point2<T>]) {
+    points.sort_by(|&p, &q| compare_radially(center, p, q));
+}
+
+/// radial_order: Point × [Point] -> [usize]
+/// indices of the points in radial order, stable
+pub fn radial_order<T: Float>(center: Point2<T>, points: &[Point2<T>]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&i, &j| compare_radially(center, points[i], points[j]));
+    order
+}
+
+/// radial_groups: Point × [Point] -> [[usize]]
+/// indices of the points bucketed by direction from the center, buckets in
+/// radial order and each nearest first; points at the center share the
+/// first bucket
+pub fn radial_groups<T: Float>(center: Point2<T>, points: &[Point2<T>]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for i in radial_order(center, points) {
+        match groups.last_mut() {
+            Some(g) if compare_directions(center, points[g[0]], points[i]) == Ordering::Equal => g.push(i),
+            _ => groups.push(vec![i]),
+        }
+    }
+    groups
+}
+
+/// 0 at the center, 1 for directions in [0, π), 2 in [π, 2π); exact, as
+/// the sign of a rounded difference is that of the difference
+fn half<T: Float>(center: Point2<T>, p: Point2<T>) -> u8 {
+    let (dx, dy) = (p.0 - center.0, p.1 - center.1);
+    if dx == T::zero() && dy == T::zero() {
+        0
+    } else if dy > T::zero() || (dy == T::zero() && dx > T::zero()) {
+        1
+    } else {
+        2
+    }
+}
+
+/// Nearer first, for points along one direction; rounding is monotonic so
+/// the offsets keep their order
+fn farther<T: Float>(center: Point2<T>, p: Point2<T>, q: Point2<T>) -> Ordering {
+    let (p, q) = ((p.0 - center.0).abs() + (p.1 - center.1).abs(), (q.0 - center.0).abs() + (q.1 - center.1).abs());
+    p.partial_cmp(&q).unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sweep_order() {
+        let c = Point2(1.0, 1.0);
+        let mut points = vec![
+            Point2(1.0, 0.0), Point2(3.0, 1.0), Point2(0.0, 1.0), Point2(2.0, 1.0), Point2(1.0, 1.0),
+            Point2(2.0, 2.0), Point2(0.0, 0.0), Point2(1.0, 5.0), Point2(3.0, 3.0), Point2(2.0, 0.0),
+        ];
+        sort_radially_around(c, &mut points);
+        assert_eq!(points, vec![
+            Point2(1.0, 1.0), Point2(2.0, 1.0), Point2(3.0, 1.0), Point2(2.0, 2.0), Point2(3.0, 3.0),
+            Point2(1.0, 5.0), Point2(0.0, 1.0), Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(2.0, 0.0),
+        ]);
+        let groups = radial_groups(c, &points);
+        assert_eq!(groups.iter().map(Vec::len).collect::<Vec<_>>(), vec![1, 2, 2, 1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn collinear_ties_are_exact() {
+        // one ray of exactly representable points whose offsets round
+        // differently once divided: a single group, nearest first
+        let c = Point2(0.1, 0.3);
+        let ray: Vec<Point2<f64>> = (1..40).map(|k| Point2(0.1 + 0.375 * k as f64, 0.3 + 0.875 * k as f64)).collect();
+        let ray: Vec<Point2<f64>> = ray.into_iter().filter(|&p| orient2d(c, Point2(0.475, 1.175), p) == Ordering::Equal).collect();
+        assert!(ray.len() > 1);
+        let mut shuffled: Vec<Point2<f64>> = ray.iter().rev().copied().collect();
+        shuffled.push(Point2(5.0, 0.3));
+        sort_radially_around(c, &mut shuffled);
+        assert_eq!(shuffled[0], Point2(5.0, 0.3));
+        assert_eq!(&shuffled[1..], ray.as_slice());
+        let groups = radial_groups(c, &shuffled);
+        assert_eq!(groups.len(), 2);
+        // the order does not depend on the starting one
+        let order = radial_order(c, &ray);
+        let mut back: Vec<Point2<f64>> = ray.iter().rev().copied().collect();
+        sort_radially_around(c, &mut back);
+        assert_eq!(order.iter().map(|&i| ray[i]).collect::<Vec<_>>(), back);
+    }
+}
@@ -0,0 +1,114 @@
+//! Rays and ray hits
+
+use crate::{ Aabb3, Float, Point3, Vector3 };
+
+/// 3d Ray: origin and direction, covering origin + direction · t for t ≥ 0
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Ray3<T>(Point3<T>, Vector3<T>);
+
+/// Shapes a ray can be cast against
+pub trait Raycast3<T: Float> {
+    /// raycast: &Shape × &Ray -> Option<Hit>
+    /// nearest hit at t ≥ 0; a ray starting inside a solid hits where it leaves
+    fn raycast(&self, ray: &Ray3<T>) -> Option<Hit<T>>;
+}
+
+/// Where a ray meets a surface
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Hit<T> {
+    /// ray parameter of the point
+    pub t: T,
+    /// point on the surface
+    pub point: Point3<T>,
+    /// unit outward normal there
+    pub normal: Vector3<T>,
+}
+
+impl<T> Ray3<T> {
+    /// new: Point × Vector -> Ray
+    pub fn new(origin: Point3<T>, direction: Vector3<T>) -> Self {
+        Ray3(origin, direction)
+    }
+}
+
+impl<T: Copy> Ray3<T> {
+    /// origin: &Ray -> Point
+    pub fn origin(&self) -> Point3<T> {
+        self.0
+    }
+
+    /// direction: &Ray -> Vector
+    pub fn direction(&self) -> Vector3<T> {
+        self.1
+    }
+}
+
+impl<T: Float> Ray3<T> {
+    /// point_at: &Ray × K -> Point
+    pub fn point_at(&self, t: T) -> Point3<T> {
+        self.0 + self.1 * t
+    }
+}
+
+impl<T: Float> Raycast3<T> for Aabb3<T> {
+    fn raycast(&self, ray: &Ray3<T>) -> Option<Hit<T>> {
+        let (enter, exit) = slabs(ray, self)?;
+        let (t, normal) = if enter.0 >= T::zero() { enter } else { exit };
+        Some(Hit { t, point: ray.point_at(t), normal })
+    }
+}
+
+/// Ray parameter and outward normal where a ray crosses a face
+type Crossing<T> = (T, Vector3<T>);
+
+/// Where the ray's line enters and leaves the box, each with the outward
+/// normal of the face crossed; None if it misses, the box lies behind the
+/// ray or the direction is zero
+pub(crate) fn slabs<T: Float>(ray: &Ray3<T>, b: &Aabb3<T>) -> Option<(Crossing<T>, Crossing<T>)> {
+    let (o, d) = (ray.origin(), ray.direction());
+    let (lo, hi) = (b.min(), b.max());
+    let (zero, one) = (T::zero(), T::one());
+    let axes = [
+        (o.0, d.0, lo.0, hi.0, Vector3(one, zero, zero)),
+        (o.1, d.1, lo.1, hi.1, Vector3(zero, one, zero)),
+        (o.2, d.2, lo.2, hi.2, Vector3(zero, zero, one)),
+    ];
+    let mut enter = (-T::infinity(), Vector3(zero, zero, zero));
+    let mut exit = (T::infinity(), Vector3(zero, zero, zero));
+    for (o, d, lo, hi, e) in axes {
+        if d == zero {
+            if o < lo || o > hi {
+                return None;
+            }
+            continue;
+        }
+        let arrive = if d > zero { ((lo - o) / d, -e) } else { ((hi - o) / d, e) };
+        let leave = if d > zero { ((hi - o) / d, e) } else { ((lo - o) / d, -e) };
+        if arrive.0 > enter.0 {
+            enter = arrive;
+        }
+        if leave.0 < exit.0 {
+            exit = leave;
+        }
+    }
+    (exit.0.is_finite() && enter.0 <= exit.0 && exit.0 >= zero).then_some((enter, exit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boxes() {
+        let b = Aabb3::new(Point3(0.0, 0.0, 0.0), Point3(2.0, 2.0, 2.0));
+        let hit = b.raycast(&Ray3::new(Point3(-3.0, 1.0, 1.0), Vector3(2.0, 0.0, 0.0))).unwrap();
+        assert_eq!((hit.t, hit.point, hit.normal), (1.5, Point3(0.0, 1.0, 1.0), Vector3(-1.0, 0.0, 0.0)));
+        let out = b.raycast(&Ray3::new(Point3(1.0, 1.0, 1.0), Vector3(0.0, 0.0, -1.0))).unwrap();
+        assert_eq!((out.t, out.normal), (1.0, Vector3(0.0, 0.0, -1.0)));
+        let corner = b.raycast(&Ray3::new(Point3(3.0, 3.0, 1.0), Vector3(-1.0, -1.0, 0.0))).unwrap();
+        assert_eq!(corner.t, 1.0);
+        assert!(b.raycast(&Ray3::new(Point3(3.0, 1.0, 1.0), Vector3(1.0, 0.0, 0.0))).is_none());
+        assert!(b.raycast(&Ray3::new(Point3(3.0, 1.0, 1.0), Vector3(0.0, 1.0, 0.0))).is_none());
+        assert_eq!(Ray3::new(Point3(1.0, 2.0, 3.0), Vector3(0.0, 2.0, 0.0)).point_at(1.5), Point3(1.0, 5.0, 3.0));
+    }
+}
@@ -0,0 +1,188 @@
+//! Rectangles indexed for overlap and nearest queries
+//!
+//! A static R-tree packed by sort-tile-recursive: the entries of a level are
+//! sorted into vertical slabs by the x of their centers, each slab by y, and
+//! every run of `FANOUT` becomes a node of the level above, until one node
+//! is left. Queries descend only into nodes whose bounds can still matter.
+
+use std::cmp::Ordering;
+
+use crate::{ Float, Point2, Rect2 };
+
+const FANOUT: usize = 8;
+
+/// A node: its bounds and the range of its children, items for a leaf
+#[derive(Debug, Clone)]
+struct Node<T> {
+    bounds: Rect2<T>,
+    start: usize,
+    end: usize,
+    leaf: bool,
+}
+
+/// Rectangles with a payload each, packed into a tree
+#[derive(Debug, Clone)]
+pub(crate) struct RTree2<T> {
+    items: Vec<(Rect2<T>, usize)>,
+    nodes: Vec<Node<T>>,
+}
+
+impl<T: Float> RTree2<T> {
+    pub(crate) fn new(mut items: Vec<(Rect2<T>, usize)>) -> Self {
+        let mut nodes = Vec::new();
+        tile(&mut items, |e| e.0);
+        for (k, run) in items.chunks(FANOUT).enumerate() {
+            let bounds = run.iter().skip(1).fold(run[0].0, |b, e| b.union(e.0));
+            nodes.push(Node { bounds, start: k * FANOUT, end: k * FANOUT + run.len(), leaf: true });
+        }
+        let mut level = 0;
+        while nodes.len() - level > 1 {
+            let top = nodes.len();
+            tile(&mut nodes[level..], |n| n.bounds);
+            for start in (level..top).step_by(FANOUT) {
+                let end = (start + FANOUT).min(top);
+                let bounds = nodes[start + 1..end].iter().fold(nodes[start].bounds, |b, n| b.union(n.bounds));
+                nodes.push(Node { bounds, start, end, leaf: false });
+            }
+            level = top;
+        }
+        RTree2 { items, nodes }
+    }
+
+    /// Payloads of the rectangles overlapping r, touching included, into
+    /// `found` after what it holds
+    pub(crate) fn query(&self, r: &Rect2<T>, found: &mut Vec<usize>) {
+        if let Some(root) = self.nodes.len().checked_sub(1) {
+            self.search(root, r, found);
+        }
+    }
+
+    fn search(&self, node: usize, r: &Rect2<T>, found: &mut Vec<usize>) {
+        let n = &self.nodes[node];
+        if !n.bounds.overlaps(r) {
+            return;
+        }
+        if n.leaf {
+            found.extend(self.items[n.start..n.end].iter().filter(|e| e.0.overlaps(r)).map(|e| e.1));
+        } else {
+            (n.start..n.end).for_each(|c| self.search(c, r, found));
+        }
+    }
+
+    /// Whether `keep` holds for the payload of a rectangle overlapping r,
+    /// stopping at the first
+    pub(crate) fn any(&self, r: &Rect2<T>, mut keep: impl FnMut(usize) -> bool) -> bool {
+        self.nodes.len().checked_sub(1).is_some_and(|root| self.find(root, r, &mut keep))
+    }
+
+    fn find(&self, node: usize, r: &Rect2<T>, keep: &mut impl FnMut(usize) -> bool) -> bool {
+        let n = &self.nodes[node];
+        if !n.bounds.overlaps(r) {
+            false
+        } else if n.leaf {
+            self.items[n.start..n.end].iter().any(|e| e.0.overlaps(r) && keep(e.1))
+        } else {
+            (n.start..n.end).any(|c| self.find(c, r, keep))
+        }
+    }
+
+    /// Least of `best` and `gap` over the payloads whose rectangles lie
+    /// nearer r than that least, given `gap` is never below the distance
+    /// between the rectangles
+    pub(crate) fn nearest(&self, r: &Rect2<T>, best: T, mut gap: impl FnMut(usize) -> T) -> T {
+        let mut best = best;
+        if let Some(root) = self.nodes.len().checked_sub(1) {
+            if rect_gap(&self.nodes[root].bounds, r) < best {
+                self.descend(root, r, &mut best, &mut gap);
+            }
+        }
+        best
+    }
+
+    fn descend(&self, node: usize, r: &Rect2<T>, best: &mut T, gap: &mut impl FnMut(usize) -> T) {
+        let n = &self.nodes[node];
+        if n.leaf {
+            for e in &self.items[n.start..n.end] {
+                if rect_gap(&e.0, r) < *best {
+                    let g = gap(e.1);
+                    if g < *best {
+                        *best = g;
+                    }
+                }
+            }
+            return;
+        }
+        // nearest child first, so the others are more often cut
+        let mut children = [(T::zero(), 0); FANOUT];
+        for (slot, c) in children.iter_mut().zip(n.start..n.end) {
+            *slot = (rect_gap(&self.nodes[c].bounds, r), c);
+        }
+        let children = &mut children[..n.end - n.start];
+        children.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        for &(g, c) in children.iter() {
+            if g < *best {
+                self.descend(c, r, best, gap);
+            }
+        }
+    }
+}
+
+/// Sorts entries so that each run of `FANOUT` is a tile: slabs by x, then
+/// runs by y within each slab
+fn tile<T: Float, E>(entries: &mut [E], rect: impl Fn(&E) -> Rect2<T>) {
+    let center = |e: &E| {
+        let r = rect(e);
+        Point2(r.min().0 + r.max().0, r.min().1 + r.max().1)
+    };
+    let by = |x: T, y: T| x.partial_cmp(&y).unwrap_or(Ordering::Equal);
+    entries.sort_by(|a, b| by(center(a).0, center(b).0));
+    let runs = entries.len().div_ceil(FANOUT);
+    let slab = FANOUT * (runs as f64).sqrt().ceil().max(1.0) as usize;
+    for s in entries.chunks_mut(slab) {
+        s.sort_by(|a, b| by(center(a).1, center(b).1));
+    }
+}
+
+/// Distance between two rectangles, zero if they meet
+fn rect_gap<T: Float>(a: &Rect2<T>, b: &Rect2<T>) -> T {
+    let apart = |lo: T, hi: T| if lo > hi { lo - hi } else { T::zero() };
+    let dx = apart(a.min().0, b.max().0) + apart(b.min().0, a.max().0);
+    let dy = apart(a.min().1, b.max().1) + apart(b.min().1, a.max().1);
+    (dx * dx + dy * dy).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_brute_force() {
+        let mut seed = 11u64;
+        let mut next = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((seed >> 40) % 100) as f64 / 4.0
+        };
+        let rects: Vec<Rect2<f64>> = (0..500).map(|_| {
+            let p = Point2(next(), next());
+            Rect2::new(p, Point2(p.0 + next() / 10.0, p.1 + next() / 10.0))
+        }).collect();
+        let tree = RTree2::new(rects.iter().copied().zip(0..).collect());
+        let mut found = Vec::new();
+        for _ in 0..100 {
+            let p = Point2(next(), next());
+            let r = Rect2::new(p, Point2(p.0 + next() / 5.0, p.1 + next() / 5.0));
+            found.clear();
+            tree.query(&r, &mut found);
+            found.sort_unstable();
+            let direct: Vec<usize> = (0..rects.len()).filter(|&i| rects[i].overlaps(&r)).collect();
+            assert_eq!(found, direct);
+            assert_eq!(tree.any(&r, |i| i % 2 == 1), direct.iter().any(|i| i % 2 == 1));
+            let least = rects.iter().map(|q| rect_gap(q, &r)).fold(f64::INFINITY, f64::min);
+            assert_eq!(tree.nearest(&r, f64::INFINITY, |i| rect_gap(&rects[i], &r)), least);
+        }
+        let empty = RTree2::<f64>::new(Vec::new());
+        empty.query(&rects[0], &mut found);
+        assert!(!empty.any(&rects[0], |_| true));
+        assert_eq!(empty.nearest(&rects[0], 2.0, |_| 0.0), 2.0);
+    }
+}
@@ -0,0 +1,184 @@
+//! Interpolation of values scattered over the plane
+
+use std::collections::HashSet;
+
+use crate::delaunay::{ self, circumcenter, cross, Mesh };
+use crate::kdtree::KdTree;
+use crate::{ Budget, Float, Point2 };
+
+/// How values between the samples are reconstructed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Interpolation {
+    /// value of the closest sample, defined everywhere
+    Nearest,
+    /// barycentric within the enclosing Delaunay triangle
+    Linear,
+    /// Sibson's weights: the shares of Voronoi area the point would take
+    /// from its neighbours; smooth away from the samples
+    NaturalNeighbor,
+}
+
+/// Scalar samples at scattered points, with their Delaunay triangulation
+/// to walk to a query point and a kd-tree for the nearest one
+#[derive(Debug, Clone)]
+pub struct Scattered2<T> {
+    points: Vec<Point2<T>>,
+    values: Vec<T>,
+    /// None when the points are all on a line
+    mesh: Option<Mesh<T>>,
+    /// a triangle at each point, where walks start
+    incident: Vec<Option<usize>>,
+    tree: KdTree<Point2<T>>,
+}
+
+impl<T: Float> Scattered2<T> {
+    /// new: [Point] × [K] -> Scattered
+    /// panics unless there is one value per point
+    pub fn new(points: Vec<Point2<T>>, values: Vec<T>) -> Self {
+        assert_eq!(points.len(), values.len(), "one value per point");
+        let mesh = delaunay::first_triangle(&points).map(|first| Mesh::new(points.clone(), first, &mut Budget::unlimited()).expect("unlimited budget"));
+        let incident = mesh.as_ref().map_or_else(Vec::new, |mesh| mesh.incident());
+        let tree = KdTree::new(&points);
+        Scattered2 { points, values, mesh, incident, tree }
+    }
+
+    /// points: &Scattered -> &[Point]
+    pub fn points(&self) -> &[Point2<T>] {
+        &self.points
+    }
+
+    /// triangles: &Scattered -> [[usize; 3]]
+    /// the Delaunay triangles, counter-clockwise
+    pub fn triangles(&self) -> Vec<[usize; 3]> {
+        self.mesh.iter().flat_map(|mesh| mesh.finite()).collect()
+    }
+
+    /// interpolate: &Scattered × Point × Interpolation -> Option<K>
+    /// None with no samples, or outside their convex hull unless nearest
+    pub fn interpolate(&self, p: Point2<T>, mode: Interpolation) -> Option<T> {
+        match mode {
+            Interpolation::Nearest => self.nearest(p),
+            Interpolation::Linear => self.linear(p),
+            Interpolation::NaturalNeighbor => self.natural(p),
+        }
+    }
+
+    fn nearest(&self, p: Point2<T>) -> Option<T> {
+        self.tree.nearest(p).map(|i| self.values[i])
+    }
+
+    /// The triangle holding p, or a ghost if it is outside the hull,
+    /// walking from one at the sample nearest to it
+    fn locate(&self, mesh: &Mesh<T>, p: Point2<T>) -> usize {
+        match self.tree.nearest(p).and_then(|i| self.incident[i]) {
+            Some(start) => mesh.locate_from(p, start),
+            None => mesh.locate(p),
+        }
+    }
+
+    fn linear(&self, p: Point2<T>) -> Option<T> {
+        let mesh = self.mesh.as_ref()?;
+        let t = self.locate(mesh, p);
+        if mesh.is_ghost(t) {
+            return None;
+        }
+        let [i, j, k] = mesh.corners[t];
+        let [a, b, c] = [i, j, k].map(|i| self.points[i]);
+        let total = cross(a, b, c);
+        let w = [cross(p, b, c) / total, cross(a, p, c) / total, cross(a, b, p) / total];
+        Some(self.values[i] * w[0] + self.values[j] * w[1] + self.values[k] * w[2])
+    }
+
+    fn natural(&self, p: Point2<T>) -> Option<T> {
+        self.linear(p)?;
+        let mesh = self.mesh.as_ref()?;
+        if let Some(i) = self.tree.nearest(p).filter(|&i| self.points[i] == p) {
+            return Some(self.values[i]);
+        }
+        // triangles p would destroy on insertion, and the ring of neighbours around them
+        let mut seen = HashSet::new();
+        let mut bad = Vec::new();
+        mesh.cavity(self.locate(mesh, p), p, false, |t| seen.insert(t), &mut bad, &mut Budget::unlimited())?;
+        bad.sort_unstable();
+        let inside = |t: usize| bad.binary_search(&t).is_ok();
+        let (mut total, mut sum) = (T::zero(), T::zero());
+        for &t in &bad {
+            for k in 0..3 {
+                if inside(mesh.neighbours[t][k]) {
+                    continue;
+                }
+                let c = mesh.corners[t];
+                let (u, v) = (c[(k + 1) % 3], c[(k + 2) % 3]);
+                // stolen region of v: from the new Voronoi vertex on uv, through the
+                // circumcenters of the destroyed triangles around v, to the one on vw
+                let mut ring = vec![circumcenter(self.points[u], self.points[v], p)?];
+                let mut at = t;
+                let w = loop {
+                    ring.push(mesh.center(at)?);
+                    let c = mesh.corners[at];
+                    let k = c.iter().position(|&x| x == v)?;
+                    let across = mesh.neighbours[at][(k + 2) % 3];
+                    if !inside(across) {
+                        break c[(k + 1) % 3];
+                    }
+                    if ring.len() > bad.len() {
+                        return None;
+                    }
+                    at = across;
+                };
+                ring.push(circumcenter(self.points[v], self.points[w], p)?);
+                let area = ring.iter().zip(ring.iter().cycle().skip(1))
+                    .fold(T::zero(), |s, (a, b)| s + a.0 * b.1 - a.1 * b.0)
+                    .abs();
+                total = total + area;
+                sum = sum + self.values[v] * area;
+            }
+        }
+        if total == T::zero() { None } else { Some(sum / total) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(f: impl Fn(f64, f64) -> f64) -> Scattered2<f64> {
+        let mut seed = 42u64;
+        let mut next = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            0.05 + 0.9 * ((seed >> 11) as f64 / (1u64 << 53) as f64)
+        };
+        let mut points = vec![Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(1.0, 1.0), Point2(0.0, 1.0)];
+        for _ in 0..30 {
+            points.push(Point2(next(), next()));
+        }
+        let values = points.iter().map(|p| f(p.0, p.1)).collect();
+        Scattered2::new(points, values)
+    }
+
+    #[test]
+    fn reproduces_linear_fields() {
+        let f = |x: f64, y: f64| 2.0 * x - 3.0 * y + 1.0;
+        let s = samples(f);
+        for (x, y) in [(0.5, 0.5), (0.13, 0.87), (0.9, 0.02), (0.31, 0.66)] {
+            for mode in [Interpolation::Linear, Interpolation::NaturalNeighbor] {
+                let v = s.interpolate(Point2(x, y), mode).unwrap();
+                assert!((v - f(x, y)).abs() < 1e-9, "{mode:?} at ({x}, {y}): {v}");
+            }
+        }
+        let p = s.points()[7];
+        assert_eq!(s.interpolate(p, Interpolation::NaturalNeighbor), Some(f(p.0, p.1)));
+    }
+
+    #[test]
+    fn modes_and_hull() {
+        let s = samples(|x, _| if x < 0.5 { 0.0 } else { 1.0 });
+        assert_eq!(s.interpolate(Point2(0.01, 0.01), Interpolation::Nearest), Some(0.0));
+        assert_eq!(s.interpolate(Point2(5.0, 0.5), Interpolation::Nearest), Some(1.0));
+        assert_eq!(s.interpolate(Point2(1.5, 0.5), Interpolation::Linear), None);
+        assert_eq!(s.interpolate(Point2(-0.1, 0.5), Interpolation::NaturalNeighbor), None);
+        let v = s.interpolate(Point2(0.5, 0.5), Interpolation::NaturalNeighbor).unwrap();
+        assert!(v > 0.0 && v < 1.0);
+        assert_eq!(Scattered2::<f64>::new(vec![], vec![]).interpolate(Point2(0.0, 0.0), Interpolation::Nearest), None);
+    }
+}
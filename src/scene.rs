@@ -0,0 +1,207 @@
+//! Ray picking over scenes of mixed shapes
+
+use std::cmp::Ordering;
+
+use crate::ray::slabs;
+use crate::stats;
+use crate::{ Aabb3, Bounded3, Cone, Cylinder, Float, Hit, Ray3, Raycast3, Sphere, Torus, TriMesh };
+
+/// Any 3d shape a scene may hold
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape3<T> {
+    /// solid ball
+    Sphere(Sphere<T>),
+    /// axis-aligned box
+    Box(Aabb3<T>),
+    /// capped cylinder
+    Cylinder(Cylinder<T>),
+    /// capped cone
+    Cone(Cone<T>),
+    /// ring
+    Torus(Torus<T>),
+    /// triangle mesh
+    Mesh(TriMesh<T>),
+}
+
+/// Shapes tagged with ids, with a bounding volume hierarchy for ray picking
+#[derive(Debug, Clone)]
+pub struct Scene3<T, I> {
+    entries: Vec<(Shape3<T>, I)>,
+    /// root first; leaves cover a run of `order`
+    nodes: Vec<Node<T>>,
+    /// entries with bounds, grouped by leaf
+    order: Vec<usize>,
+}
+
+/// Bounding volume hierarchy node
+#[derive(Debug, Clone)]
+struct Node<T> {
+    bounds: Aabb3<T>,
+    /// children, None for leaves
+    children: Option<(usize, usize)>,
+    /// run of `order` below
+    start: usize,
+    end: usize,
+}
+
+/// Most entries in a leaf
+const LEAF: usize = 2;
+
+impl<T: Float> Bounded3<T> for Shape3<T> {
+    fn bounds(&self) -> Option<Aabb3<T>> {
+        match self {
+            Shape3::Sphere(s) => s.bounds(),
+            Shape3::Box(b) => Some(*b),
+            Shape3::Cylinder(c) => c.bounds(),
+            Shape3::Cone(c) => c.bounds(),
+            Shape3::Torus(t) => t.bounds(),
+            Shape3::Mesh(m) => m.bounds(),
+        }
+    }
+}
+
+impl<T: Float> Raycast3<T> for Shape3<T> {
+    fn raycast(&self, ray: &Ray3<T>) -> Option<Hit<T>> {
+        match self {
+            Shape3::Sphere(s) => s.raycast(ray),
+            Shape3::Box(b) => b.raycast(ray),
+            Shape3::Cylinder(c) => c.raycast(ray),
+            Shape3::Cone(c) => c.raycast(ray),
+            Shape3::Torus(t) => t.raycast(ray),
+            Shape3::Mesh(m) => m.raycast(ray),
+        }
+    }
+}
+
+impl<T: Float, I> Scene3<T, I> {
+    /// new: [(Shape, Id)] -> Scene
+    /// splitting at the median along the widest extent; shapes without
+    /// bounds are kept but never picked
+    pub fn new(entries: Vec<(Shape3<T>, I)>) -> Self {
+        let _timer = stats::time("bvh build");
+        let mut bounded: Vec<(usize, Aabb3<T>)> = entries.iter().enumerate().filter_map(|(i, (s, _))| s.bounds().map(|b| (i, b))).collect();
+        let mut nodes = Vec::new();
+        if !bounded.is_empty() {
+            build(&mut nodes, &mut bounded, 0);
+        }
+        stats::count("bvh build", "nodes", nodes.len());
+        stats::count("bvh build", "leaves", nodes.iter().filter(|n| n.children.is_none()).count());
+        let order = bounded.into_iter().map(|(i, _)| i).collect();
+        Scene3 { entries, nodes, order }
+    }
+
+    /// entries: &Scene -> &[(Shape, Id)]
+    pub fn entries(&self) -> &[(Shape3<T>, I)] {
+        &self.entries
+    }
+
+    /// pick: &Scene × &Ray -> Option<(Id, Hit)>
+    /// the entry the ray meets first
+    pub fn pick(&self, ray: &Ray3<T>) -> Option<(I, Hit<T>)> where I: Clone {
+        let _timer = stats::time("bvh pick");
+        let (mut visited, mut tests) = (0, 0);
+        let entry = |n: &Node<T>| slabs(ray, &n.bounds).map(|(enter, _)| if enter.0 > T::zero() { enter.0 } else { T::zero() });
+        let mut best: Option<(usize, Hit<T>)> = None;
+        let mut stack: Vec<(usize, T)> = self.nodes.first().and_then(entry).map(|t| (0, t)).into_iter().collect();
+        while let Some((k, t)) = stack.pop() {
+            if best.as_ref().is_some_and(|b| b.1.t < t) {
+                continue;
+            }
+            let node = &self.nodes[k];
+            visited += 1;
+            match node.children {
+                None => {
+                    for &i in &self.order[node.start..node.end] {
+                        tests += 1;
+                        let Some(hit) = self.entries[i].0.raycast(ray) else { continue };
+                        if best.as_ref().is_none_or(|b| hit.t < b.1.t) {
+                            best = Some((i, hit));
+                        }
+                    }
+                }
+                Some((l, r)) => {
+                    let mut near: Vec<(usize, T)> = [l, r].into_iter().filter_map(|c| entry(&self.nodes[c]).map(|t| (c, t))).collect();
+                    // nearer child on top
+                    near.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+                    stack.extend(near);
+                }
+            }
+        }
+        stats::count("bvh pick", "nodes visited", visited);
+        stats::count("bvh pick", "shape tests", tests);
+        best.map(|(i, hit)| (self.entries[i].1.clone(), hit))
+    }
+}
+
+/// Adds the node over a run of entries and their bounds and those below it,
+/// returning its index
+fn build<T: Float>(nodes: &mut Vec<Node<T>>, order: &mut [(usize, Aabb3<T>)], start: usize) -> usize {
+    let bounds = order.iter().map(|e| e.1).reduce(Aabb3::union).expect("non-empty run");
+    let index = nodes.len();
+    nodes.push(Node { bounds, children: None, start, end: start + order.len() });
+    if order.len() <= LEAF {
+        return index;
+    }
+    let axis = bounds.longest_axis();
+    let centre = |b: &Aabb3<T>| b.min()[axis] + b.max()[axis];
+    order.sort_by(|a, b| centre(&a.1).partial_cmp(&centre(&b.1)).unwrap_or(Ordering::Equal));
+    let mid = order.len() / 2;
+    let (left, right) = order.split_at_mut(mid);
+    let l = build(nodes, left, start);
+    let r = build(nodes, right, start + mid);
+    nodes[index].children = Some((l, r));
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ Point3, Vector3 };
+
+    #[test]
+    fn picks_nearest() {
+        let scene = Scene3::new(vec![
+            (Shape3::Sphere(Sphere::new(Point3(0.0, 0.0, 10.0), 1.0)), "far"),
+            (Shape3::Box(Aabb3::new(Point3(-1.0, -1.0, 4.0), Point3(1.0, 1.0, 5.0))), "near"),
+            (Shape3::Cylinder(Cylinder::new(Point3(5.0, 0.0, 0.0), Point3(5.0, 0.0, 20.0), 1.0)), "side"),
+            (Shape3::Mesh(TriMesh::new(Vec::new(), Vec::new())), "empty"),
+        ]);
+        let down = Ray3::new(Point3(0.0, 0.0, 0.0), Vector3(0.0, 0.0, 1.0));
+        let (id, hit) = scene.pick(&down).unwrap();
+        assert_eq!((id, hit.t, hit.normal), ("near", 4.0, Vector3(0.0, 0.0, -1.0)));
+        let (id, hit) = scene.pick(&Ray3::new(Point3(0.0, 0.0, 7.0), Vector3(0.0, 0.0, 1.0))).unwrap();
+        assert_eq!((id, hit.t), ("far", 2.0));
+        let (id, _) = scene.pick(&Ray3::new(Point3(0.0, 0.0, 3.0), Vector3(1.0, 0.0, 0.0))).unwrap();
+        assert_eq!(id, "side");
+        assert!(scene.pick(&Ray3::new(Point3(0.0, 0.0, 0.0), Vector3(0.0, 1.0, 0.0))).is_none());
+        assert!(Scene3::<f64, u8>::new(Vec::new()).pick(&down).is_none());
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut seed = 99u64;
+        let mut random = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64 * 20.0 - 10.0
+        };
+        let entries: Vec<_> = (0..200).map(|k| {
+            let c = Point3(random(), random(), random());
+            let r = 0.2 + random().abs() / 20.0;
+            let shape = if k % 2 == 0 {
+                Shape3::Sphere(Sphere::new(c, r))
+            } else {
+                Shape3::Box(Aabb3::new(c, c + Vector3(r, r, r)))
+            };
+            (shape, k)
+        }).collect();
+        let scene = Scene3::new(entries.clone());
+        for _ in 0..200 {
+            let ray = Ray3::new(Point3(random(), random(), random()), Vector3(random(), random(), random()));
+            let brute = entries
+                .iter()
+                .filter_map(|(s, k)| s.raycast(&ray).map(|h| (*k, h.t)))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+            assert_eq!(scene.pick(&ray).map(|(k, h)| (k, h.t)), brute);
+        }
+    }
+}
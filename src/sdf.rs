@@ -0,0 +1,230 @@
+//! Signed distance fields and sphere tracing
+
+use crate::{ Aabb3, Float, Hit, Plane3, Point3, Ray3, Vector3 };
+
+/// Signed distance field: negative inside, never more than the distance
+/// to the surface, so a ball of that radius is free of it
+pub trait Sdf3<T: Float> {
+    /// distance: &Sdf × Point -> K
+    fn distance(&self, p: Point3<T>) -> T;
+
+    /// gradient: &Sdf × Point × K -> Vector
+    /// by central differences of step h; the outward normal on the surface
+    fn gradient(&self, p: Point3<T>, h: T) -> Vector3<T> {
+        let two_h = h * T::from_f64(2.0);
+        let d = |v: Vector3<T>| (self.distance(p + v * h) - self.distance(p - v * h)) / two_h;
+        let (zero, one) = (T::zero(), T::one());
+        Vector3(d(Vector3(one, zero, zero)), d(Vector3(zero, one, zero)), d(Vector3(zero, zero, one)))
+    }
+
+    /// march: &Sdf × &Ray × K × K × usize -> March
+    /// sphere tracing along the ray until the field drops below `tolerance`,
+    /// the ray passes `max_t` or `max_steps` evaluations are spent; a ray
+    /// starting inside hits at once
+    fn march(&self, ray: &Ray3<T>, max_t: T, tolerance: T, max_steps: usize) -> March<T> {
+        let speed = ray.direction().length();
+        let mut t = T::zero();
+        for steps in 1..=max_steps {
+            let point = ray.point_at(t);
+            let d = self.distance(point);
+            if d < tolerance {
+                let g = self.gradient(point, tolerance);
+                let l = g.length();
+                let normal = if l > T::zero() { g / l } else { g };
+                return March { hit: Some(Hit { t, point, normal }), steps };
+            }
+            t = t + d / speed;
+            if t > max_t {
+                return March { hit: None, steps };
+            }
+        }
+        March { hit: None, steps: max_steps }
+    }
+}
+
+/// Outcome of sphere tracing
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct March<T> {
+    /// surface reached, if any
+    pub hit: Option<Hit<T>>,
+    /// field evaluations spent
+    pub steps: usize,
+}
+
+impl<T: Float, F: Fn(Point3<T>) -> T> Sdf3<T> for F {
+    fn distance(&self, p: Point3<T>) -> T {
+        self(p)
+    }
+}
+
+impl<T: Float> Sdf3<T> for Aabb3<T> {
+    fn distance(&self, p: Point3<T>) -> T {
+        let zero = T::zero();
+        let max = |a: T, b: T| if a > b { a } else { b };
+        let half = T::from_f64(0.5);
+        let (lo, hi) = (self.min(), self.max());
+        // per axis, how far outside the slab p lies (negative inside)
+        let q = |p: T, lo: T, hi: T| (p - (lo + hi) * half).abs() - (hi - lo) * half;
+        let (x, y, z) = (q(p.0, lo.0, hi.0), q(p.1, lo.1, hi.1), q(p.2, lo.2, hi.2));
+        let outside = Vector3(max(x, zero), max(y, zero), max(z, zero)).length();
+        let inside = max(x, max(y, z));
+        outside + if inside < zero { inside } else { zero }
+    }
+}
+
+impl<T: Float> Sdf3<T> for Plane3<T> {
+    fn distance(&self, p: Point3<T>) -> T {
+        self.signed_distance(p)
+    }
+}
+
+/// Points in either field
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Union<A, B>(A, B);
+
+/// Points in both fields
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Intersection<A, B>(A, B);
+
+/// Points in the first field and not the second
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Difference<A, B>(A, B);
+
+/// Union blended over a width
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SmoothUnion<A, B, T>(A, B, T);
+
+/// Field grown outward by a distance, rounding its edges
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Offset<S, T>(S, T);
+
+/// Field moved by a vector
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Translated<S, T>(S, Vector3<T>);
+
+/// union: Sdf × Sdf -> Sdf
+pub fn union<A, B>(a: A, b: B) -> Union<A, B> {
+    Union(a, b)
+}
+
+/// intersection: Sdf × Sdf -> Sdf
+/// exact outside only where one field is; a bound elsewhere
+pub fn intersection<A, B>(a: A, b: B) -> Intersection<A, B> {
+    Intersection(a, b)
+}
+
+/// difference: Sdf × Sdf -> Sdf
+/// a with b carved out; a bound, like intersection
+pub fn difference<A, B>(a: A, b: B) -> Difference<A, B> {
+    Difference(a, b)
+}
+
+/// smooth_union: Sdf × Sdf × K -> Sdf
+/// polynomial smooth minimum over width k, at most k / 4 below the union
+pub fn smooth_union<A, B, T>(a: A, b: B, k: T) -> SmoothUnion<A, B, T> {
+    SmoothUnion(a, b, k)
+}
+
+/// offset: Sdf × K -> Sdf
+pub fn offset<S, T>(s: S, r: T) -> Offset<S, T> {
+    Offset(s, r)
+}
+
+/// translate: Sdf × Vector -> Sdf
+pub fn translate<S, T>(s: S, v: Vector3<T>) -> Translated<S, T> {
+    Translated(s, v)
+}
+
+impl<T: Float, A: Sdf3<T>, B: Sdf3<T>> Sdf3<T> for Union<A, B> {
+    fn distance(&self, p: Point3<T>) -> T {
+        let (a, b) = (self.0.distance(p), self.1.distance(p));
+        if a < b { a } else { b }
+    }
+}
+
+impl<T: Float, A: Sdf3<T>, B: Sdf3<T>> Sdf3<T> for Intersection<A, B> {
+    fn distance(&self, p: Point3<T>) -> T {
+        let (a, b) = (self.0.distance(p), self.1.distance(p));
+        if a > b { a } else { b }
+    }
+}
+
+impl<T: Float, A: Sdf3<T>, B: Sdf3<T>> Sdf3<T> for Difference<A, B> {
+    fn distance(&self, p: Point3<T>) -> T {
+        let (a, b) = (self.0.distance(p), -self.1.distance(p));
+        if a > b { a } else { b }
+    }
+}
+
+impl<T: Float, A: Sdf3<T>, B: Sdf3<T>> Sdf3<T> for SmoothUnion<A, B, T> {
+    fn distance(&self, p: Point3<T>) -> T {
+        let (a, b, k) = (self.0.distance(p), self.1.distance(p), self.2);
+        let m = if a < b { a } else { b };
+        if k <= T::zero() {
+            return m;
+        }
+        let h = k - (a - b).abs();
+        if h <= T::zero() { m } else { m - h * h / (k * T::from_f64(4.0)) }
+    }
+}
+
+impl<T: Float, S: Sdf3<T>> Sdf3<T> for Offset<S, T> {
+    fn distance(&self, p: Point3<T>) -> T {
+        self.0.distance(p) - self.1
+    }
+}
+
+impl<T: Float, S: Sdf3<T>> Sdf3<T> for Translated<S, T> {
+    fn distance(&self, p: Point3<T>) -> T {
+        self.0.distance(p - self.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sphere(c: Point3<f64>, r: f64) -> impl Sdf3<f64> + Copy {
+        move |p: Point3<f64>| p.distance(c) - r
+    }
+
+    #[test]
+    fn primitives() {
+        let b = Aabb3::new(Point3(0.0, 0.0, 0.0), Point3(2.0, 4.0, 6.0));
+        assert_eq!(b.distance(Point3(1.0, 1.0, 3.0)), -1.0);
+        assert_eq!(b.distance(Point3(5.0, 8.0, 3.0)), 5.0);
+        let g = b.gradient(Point3(3.0, 2.0, 3.0), 1e-6);
+        assert!((g - Vector3(1.0, 0.0, 0.0)).length() < 1e-6);
+        let floor = Plane3::new(Point3(0.0, 0.0, 0.0), Vector3(0.0, 0.0, 2.0));
+        assert_eq!(floor.distance(Point3(1.0, 1.0, -3.0)), -3.0);
+    }
+
+    #[test]
+    fn combinators() {
+        let (a, b) = (sphere(Point3(0.0, 0.0, 0.0), 1.0), sphere(Point3(1.5, 0.0, 0.0), 1.0));
+        let p = Point3(0.75, 1.0, 0.0);
+        let u = union(a, b).distance(p);
+        assert_eq!(u, a.distance(p).min(b.distance(p)));
+        assert!(smooth_union(a, b, 0.5).distance(p) < u && smooth_union(a, b, 0.5).distance(p) >= u - 0.125);
+        assert_eq!(intersection(a, b).distance(Point3(0.75, 0.0, 0.0)), -0.25);
+        assert_eq!(difference(a, b).distance(Point3(0.75, 0.0, 0.0)), 0.25);
+        let moved = translate(offset(Aabb3::new(Point3(-1.0, -1.0, -1.0), Point3(1.0, 1.0, 1.0)), 0.5), Vector3(10.0, 0.0, 0.0));
+        assert_eq!(moved.distance(Point3(10.0, 0.0, 3.0)), 1.5);
+    }
+
+    #[test]
+    fn march() {
+        let scene = union(sphere(Point3(0.0, 0.0, 5.0), 1.0), Plane3::new(Point3(0.0, 0.0, -1.0), Vector3(0.0, 0.0, 1.0)));
+        let ray = Ray3::new(Point3(0.0, 0.0, 0.0), Vector3(0.0, 0.0, 2.0));
+        let m = scene.march(&ray, 100.0, 1e-9, 200);
+        let hit = m.hit.unwrap();
+        assert!((hit.t - 2.0).abs() < 1e-8 && (hit.point.2 - 4.0).abs() < 1e-8);
+        assert!((hit.normal - Vector3(0.0, 0.0, -1.0)).length() < 1e-5);
+        assert!(m.steps > 1);
+        let down = scene.march(&Ray3::new(Point3(3.0, 0.0, 0.0), Vector3(0.0, 1.0, -1.0)), 100.0, 1e-9, 200).hit.unwrap();
+        assert!((down.point.2 + 1.0).abs() < 1e-8 && (down.normal - Vector3(0.0, 0.0, 1.0)).length() < 1e-5);
+        let away = scene.march(&Ray3::new(Point3(3.0, 0.0, 0.0), Vector3(1.0, 0.0, 1.0)), 50.0, 1e-9, 200);
+        assert!(away.hit.is_none() && away.steps < 200);
+        assert_eq!(scene.march(&ray, 100.0, 1e-9, 3), March { hit: None, steps: 3 });
+    }
+}
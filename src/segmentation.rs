@@ -0,0 +1,227 @@
+//! Plane extraction and region growing over point clouds
+//!
+//! RANSAC takes planes through random triples of the points not yet used,
+//! keeps the one with the most points within a threshold, refits it by least
+//! squares and sets its points aside, until no plane gathers enough. Region
+//! growing estimates a normal at each point from its nearest neighbours and
+//! floods outward from the flattest points, across neighbours whose normals
+//! turn by less than an angle. Neighbours are found with a kd-tree.
+
+use std::cmp::Ordering;
+
+use crate::conic::symmetric_eigen;
+use crate::kdtree::KdTree;
+use crate::{ Float, Plane3, Point3, PointCloud3, Vector3 };
+
+/// Points of a cloud and the plane fitted to them
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaneSegment<T> {
+    /// least-squares plane through the centroid, with a unit normal
+    pub plane: Plane3<T>,
+    /// indices into the cloud, ascending
+    pub indices: Vec<usize>,
+}
+
+/// Least-squares plane of the points and their surface variation, the
+/// smallest eigenvalue of the covariance over the sum; None for fewer than
+/// three points or points on a line
+fn fit<T: Float>(points: impl Iterator<Item = Point3<T>> + Clone) -> Option<(Plane3<T>, T)> {
+    let (sum, n) = points.clone().fold((Vector3(T::zero(), T::zero(), T::zero()), 0usize), |(s, n), p| (s + Vector3(p.0, p.1, p.2), n + 1));
+    if n < 3 {
+        return None;
+    }
+    let c = sum / T::from_f64(n as f64);
+    let mut m = [[T::zero(); 3]; 3];
+    for p in points {
+        let d = [p.0 - c.0, p.1 - c.1, p.2 - c.2];
+        for (row, &di) in m.iter_mut().zip(&d) {
+            for (x, &dj) in row.iter_mut().zip(&d) {
+                *x = *x + di * dj;
+            }
+        }
+    }
+    let (values, vectors) = symmetric_eigen(m);
+    let mut order = [0, 1, 2];
+    order.sort_by(|&i, &j| values[i].partial_cmp(&values[j]).unwrap_or(Ordering::Equal));
+    let total = values[0] + values[1] + values[2];
+    if values[order[1]] <= total * T::epsilon() * T::from_f64(64.0) {
+        return None;
+    }
+    let k = order[0];
+    let normal = Vector3(vectors[0][k], vectors[1][k], vectors[2][k]);
+    let normal = normal / normal.length();
+    let variation = if values[k] > T::zero() { values[k] / total } else { T::zero() };
+    Some((Plane3::new(Point3(c.0, c.1, c.2), normal), variation))
+}
+
+impl<T: Float, A> PointCloud3<T, A> {
+    /// ransac_planes: &Cloud × K × usize × usize × u64 -> [PlaneSegment]
+    /// planes found one after another, each trying `iterations` triples of
+    /// the points left, drawn from `seed`, and taking the points within
+    /// `threshold` of its refitted plane; stops when the best plane has
+    /// fewer than `min_points`. Points on no plane are in no segment
+    pub fn ransac_planes(&self, threshold: T, min_points: usize, iterations: usize, seed: u64) -> Vec<PlaneSegment<T>> {
+        let points = self.points();
+        let mut state = seed;
+        let mut next = |n: usize| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((state >> 33) % n as u64) as usize
+        };
+        let near = |plane: &Plane3<T>, i: usize| plane.signed_distance(points[i]).abs() <= threshold;
+        let mut left: Vec<usize> = (0..points.len()).collect();
+        let mut segments = Vec::new();
+        while left.len() >= min_points.max(3) {
+            let mut best: Option<(Plane3<T>, usize)> = None;
+            for _ in 0..iterations {
+                let (a, b, c) = (next(left.len()), next(left.len()), next(left.len()));
+                if a == b || b == c || a == c {
+                    continue;
+                }
+                let plane = Plane3::through(points[left[a]], points[left[b]], points[left[c]]);
+                if plane.normal().length() == T::zero() {
+                    continue;
+                }
+                let count = left.iter().filter(|&&i| near(&plane, i)).count();
+                if best.as_ref().is_none_or(|b| count > b.1) {
+                    best = Some((plane, count));
+                }
+            }
+            let Some((plane, count)) = best else { break };
+            if count < min_points {
+                break;
+            }
+            let inliers: Vec<usize> = left.iter().copied().filter(|&i| near(&plane, i)).collect();
+            let plane = fit(inliers.iter().map(|&i| points[i])).map_or(plane, |f| f.0);
+            let (indices, rest): (Vec<usize>, Vec<usize>) = left.iter().partition(|&&i| near(&plane, i));
+            if indices.len() < min_points {
+                break;
+            }
+            left = rest;
+            segments.push(PlaneSegment { plane, indices });
+        }
+        segments
+    }
+
+    /// region_growing: &Cloud × usize × K × usize -> [PlaneSegment]
+    /// regions of points whose normals, fitted to their `k` nearest
+    /// neighbours, turn by at most `angle` radians from a neighbour's in the
+    /// region, grown from the flattest points first; regions of fewer than
+    /// `min_points` are dropped, leaving their points in no segment. A
+    /// smooth curved patch is one region, with its best plane
+    pub fn region_growing(&self, k: usize, angle: T, min_points: usize) -> Vec<PlaneSegment<T>> {
+        let points = self.points();
+        let n = points.len();
+        let k = k.min(n.saturating_sub(1));
+        let tree = KdTree::new(points);
+        let mut found = Vec::with_capacity(k + 1);
+        let neighbours: Vec<Vec<usize>> = (0..n).map(|i| {
+            tree.k_nearest(points[i], k + 1, &mut found);
+            // the point itself is among them, at distance 0
+            let mut others: Vec<usize> = found.iter().map(|&(_, j)| j).filter(|&j| j != i).collect();
+            others.truncate(k);
+            others
+        }).collect();
+        let normals: Vec<Option<(Vector3<T>, T)>> = (0..n).map(|i| {
+            fit(std::iter::once(i).chain(neighbours[i].iter().copied()).map(|j| points[j])).map(|(plane, variation)| (plane.normal(), variation))
+        }).collect();
+        let mut order: Vec<usize> = (0..n).filter(|&i| normals[i].is_some()).collect();
+        order.sort_by(|&i, &j| normals[i].map(|x| x.1).partial_cmp(&normals[j].map(|x| x.1)).unwrap_or(Ordering::Equal));
+        let cos = angle.cos();
+        let mut taken = vec![false; n];
+        let mut segments = Vec::new();
+        for seed in order {
+            if taken[seed] {
+                continue;
+            }
+            taken[seed] = true;
+            let mut region = vec![seed];
+            let mut at = 0;
+            while at < region.len() {
+                let i = region[at];
+                at += 1;
+                let Some((ni, _)) = normals[i] else { continue };
+                for &j in &neighbours[i] {
+                    if taken[j] {
+                        continue;
+                    }
+                    if normals[j].is_some_and(|(nj, _)| ni.dot(nj).abs() >= cos) {
+                        taken[j] = true;
+                        region.push(j);
+                    }
+                }
+            }
+            if region.len() < min_points.max(3) {
+                continue;
+            }
+            region.sort_unstable();
+            if let Some((plane, _)) = fit(region.iter().map(|&i| points[i])) {
+                segments.push(PlaneSegment { plane, indices: region });
+            }
+        }
+        segments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a floor at z = 0 and a wall at x = -1, apart, jittered by ±0.01
+    fn room() -> (PointCloud3<f64>, usize) {
+        let mut seed = 7u64;
+        let mut jitter = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((seed >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0) * 0.01
+        };
+        let mut points = Vec::new();
+        for i in 0..12 {
+            for j in 0..12 {
+                points.push(Point3(0.5 + i as f64 * 0.5, j as f64 * 0.5, jitter()));
+            }
+        }
+        let floor = points.len();
+        for i in 0..8 {
+            for j in 0..12 {
+                points.push(Point3(-1.0 + jitter(), j as f64 * 0.5, 0.5 + i as f64 * 0.5));
+            }
+        }
+        points.push(Point3(3.0, 3.0, 4.0));
+        (PointCloud3::new(points), floor)
+    }
+
+    fn axis(segment: &PlaneSegment<f64>) -> usize {
+        let n = segment.plane.normal();
+        assert!((n.length() - 1.0).abs() < 1e-9);
+        [n.0, n.1, n.2].iter().position(|c| c.abs() > 0.999).unwrap()
+    }
+
+    #[test]
+    fn ransac() {
+        let (cloud, floor) = room();
+        let planes = cloud.ransac_planes(0.05, 20, 200, 1);
+        assert_eq!(planes.len(), 2);
+        assert_eq!((axis(&planes[0]), axis(&planes[1])), (2, 0));
+        assert_eq!(planes[0].indices, (0..floor).collect::<Vec<_>>());
+        assert_eq!(planes[1].indices, (floor..floor + 96).collect::<Vec<_>>());
+        assert!(planes[1].plane.signed_distance(Point3(-1.0, 0.0, 0.0)).abs() < 0.01);
+        assert!(cloud.ransac_planes(0.05, 500, 200, 1).is_empty());
+    }
+
+    #[test]
+    fn regions() {
+        let (cloud, floor) = room();
+        let mut regions = cloud.region_growing(8, 0.2, 10);
+        regions.sort_by_key(|r| r.indices[0]);
+        assert_eq!(regions.len(), 2);
+        assert_eq!((axis(&regions[0]), axis(&regions[1])), (2, 0));
+        assert_eq!(regions[0].indices, (0..floor).collect::<Vec<_>>());
+        assert_eq!(regions[1].indices, (floor..floor + 96).collect::<Vec<_>>());
+        // a cylinder bends slowly enough to grow into one region
+        let tube = PointCloud3::new((0..36).flat_map(|i| {
+            let a = i as f64 * std::f64::consts::PI / 18.0;
+            (0..6).map(move |j| Point3(4.0 * a.cos(), 4.0 * a.sin(), j as f64 * 0.7))
+        }).collect());
+        assert_eq!(tube.region_growing(8, 0.3, 10).len(), 1);
+        assert!(PointCloud3::<f64>::new(Vec::new()).region_growing(8, 0.2, 1).is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+//! Shadows and line of sight among polygon obstacles
+
+use std::cmp::Ordering;
+
+use crate::{ FillRule, Float, MultiPolygon2, Point2, Polygon2, Segment2 };
+
+/// cast_shadow: &Polygon × Point × K -> MultiPolygon
+/// region a point light cannot reach past the polygon, the polygon included:
+/// every edge facing the light swept away from it until `extent` from the
+/// light, or no further than the edge where that is farther. The far side
+/// of each sweep is straight, so wide edges fall a little short of `extent`
+/// between their ends
+pub fn cast_shadow<T: Float>(polygon: &Polygon2<T>, light: Point2<T>, extent: T) -> MultiPolygon2<T> {
+    let far = |p: Point2<T>| {
+        let d = p.vector_from(light);
+        let r = d.length();
+        if r >= extent || r == T::zero() { p } else { light + d * (extent / r) }
+    };
+    let mut quads = Vec::new();
+    for (k, ring) in polygon.rings().enumerate() {
+        // walked with the region on the left: exterior counter-clockwise,
+        // holes clockwise
+        let ccw = Polygon2::new(ring.to_vec()).signed_area() > T::zero();
+        let forward = ccw == (k == 0);
+        let n = ring.len();
+        for i in 0..n {
+            let (mut a, mut b) = (ring[i], ring[(i + 1) % n]);
+            if !forward {
+                std::mem::swap(&mut a, &mut b);
+            }
+            // facing the light: the light on the right, outside
+            let side = b.vector_from(a);
+            let to_light = light.vector_from(a);
+            if side.0 * to_light.1 - side.1 * to_light.0 < T::zero() {
+                quads.push(vec![a, b, far(b), far(a)]);
+            }
+        }
+    }
+    if quads.is_empty() {
+        return MultiPolygon2::new(Vec::new());
+    }
+    // every sweep counter-clockwise, so the non-zero fill is their union
+    let first = quads.remove(0);
+    Polygon2::with_holes(first, quads).fill(FillRule::NonZero)
+}
+
+/// line_of_sight: Point × Point × [Polygon] -> bool
+/// whether the segment from a to b stays out of the obstacles' interiors;
+/// grazing a corner or running along an edge does not block it
+pub fn line_of_sight<T: Float>(a: Point2<T>, b: Point2<T>, obstacles: &[Polygon2<T>]) -> bool {
+    let sight = Segment2::new(a, b);
+    let d = b.vector_from(a);
+    let dd = d.dot(d);
+    obstacles.iter().all(|o| {
+        // the pieces between crossings of the outline lie wholly in or out
+        let mut ts = vec![T::zero(), T::one()];
+        if dd > T::zero() {
+            ts.extend(o.edges().filter_map(|e| sight.intersection(&e)).map(|p| p.vector_from(a).dot(d) / dd));
+        }
+        ts.sort_by(|x, y| x.partial_cmp(y).unwrap_or(Ordering::Equal));
+        let half = T::from_f64(0.5);
+        ts.windows(2).all(|w| !o.contains(a + d * ((w[0] + w[1]) * half), FillRule::NonZero))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f64, y: f64, s: f64) -> Polygon2<f64> {
+        Polygon2::new(vec![Point2(x, y), Point2(x + s, y), Point2(x + s, y + s), Point2(x, y + s)])
+    }
+
+    #[test]
+    fn shadow() {
+        let box_ = square(2.0, -1.0, 2.0);
+        let light = Point2(0.0, 0.0);
+        let shadow = cast_shadow(&box_, light, 10.0);
+        assert_eq!(shadow.len(), 1);
+        let inside = |p: Point2<f64>| shadow.polygons()[0].contains(p, FillRule::NonZero);
+        assert!(inside(Point2(3.0, 0.0)) && inside(Point2(8.0, 0.5)) && inside(Point2(8.0, -2.0)));
+        assert!(!inside(Point2(1.0, 0.0)) && !inside(Point2(6.0, 4.0)) && !inside(Point2(11.0, 0.0)));
+        // the sweep of the near edge, from it to the chord 10 from the light
+        let wedge = 0.5 * 10.0 * 10.0 * (2.0 * 0.5f64.atan()).sin() - 0.5 * 2.0 * 2.0;
+        assert!((shadow.area() - wedge).abs() < 1e-9);
+        // clockwise input casts the same shadow
+        let cw = Polygon2::new(box_.vertices().iter().rev().copied().collect());
+        assert!((cast_shadow(&cw, light, 10.0).area() - shadow.area()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sight() {
+        let walls = [square(2.0, -1.0, 2.0), square(2.0, 3.0, 2.0)];
+        assert!(!line_of_sight(Point2(0.0, 0.0), Point2(6.0, 0.0), &walls));
+        assert!(line_of_sight(Point2(0.0, 2.0), Point2(6.0, 2.0), &walls));
+        // along an edge and through corners
+        assert!(line_of_sight(Point2(0.0, 1.0), Point2(6.0, 1.0), &walls));
+        assert!(line_of_sight(Point2(1.0, 0.0), Point2(3.0, 2.0), &walls));
+        assert!(!line_of_sight(Point2(0.0, 4.0), Point2(3.0, 4.0), &walls));
+        assert!(line_of_sight(Point2(1.0, 1.0), Point2(1.0, 1.0), &walls));
+    }
+}
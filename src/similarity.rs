@@ -0,0 +1,117 @@
+//! Distances between curves
+
+use crate::{ Float, Point2, Polygon2, Polyline2, Segment2 };
+
+/// directed_hausdorff: [Segment] × [Segment] × K -> K
+/// greatest distance from a point of `from` to the nearest point of `to`,
+/// within `tolerance`; infinite if `to` is empty
+pub fn directed_hausdorff<T: Float>(from: &[Segment2<T>], to: &[Segment2<T>], tolerance: T) -> T {
+    let min = |a: T, b: T| if a < b { a } else { b };
+    let max = |a: T, b: T| if a > b { a } else { b };
+    if to.is_empty() {
+        return T::infinity();
+    }
+    let distance = |p: Point2<T>| to.iter().map(|s| s.distance(p)).fold(T::infinity(), min);
+    let mut best = T::zero();
+    let mut pending: Vec<_> = from.iter().map(|s| (s.start(), s.end())).collect();
+    while let Some((p, q)) = pending.pop() {
+        best = max(best, max(distance(p), distance(q)));
+        // the distance to each target segment is convex along ours, so its
+        // end values bound it in between
+        let bound = to.iter().map(|s| max(s.distance(p), s.distance(q))).fold(T::infinity(), min);
+        if bound <= best + tolerance {
+            continue;
+        }
+        let m = p + q.vector_from(p) * T::from_f64(0.5);
+        if m == p || m == q {
+            continue;
+        }
+        pending.push((p, m));
+        pending.push((m, q));
+    }
+    best
+}
+
+/// hausdorff: [Segment] × [Segment] × K -> K
+/// larger of both directed distances
+pub fn hausdorff<T: Float>(a: &[Segment2<T>], b: &[Segment2<T>], tolerance: T) -> T {
+    let (ab, ba) = (directed_hausdorff(a, b, tolerance), directed_hausdorff(b, a, tolerance));
+    if ab > ba { ab } else { ba }
+}
+
+/// discrete_frechet: [Point] × [Point] -> K
+/// shortest leash letting two walkers cross the vertex sequences in order,
+/// each step moving one or both to their next vertex; infinite if either is empty
+pub fn discrete_frechet<T: Float>(a: &[Point2<T>], b: &[Point2<T>]) -> T {
+    let max = |x: T, y: T| if x > y { x } else { y };
+    let min = |x: T, y: T| if x < y { x } else { y };
+    let mut previous = vec![T::infinity(); b.len()];
+    for (i, &p) in a.iter().enumerate() {
+        let mut current = Vec::with_capacity(b.len());
+        for (j, &q) in b.iter().enumerate() {
+            let d = q.vector_from(p).length();
+            let reach = match (i, j) {
+                (0, 0) => T::zero(),
+                (_, 0) => previous[0],
+                (0, _) => current[j - 1],
+                _ => min(min(previous[j], previous[j - 1]), current[j - 1]),
+            };
+            current.push(max(reach, d));
+        }
+        previous = current;
+    }
+    previous.last().copied().unwrap_or(T::infinity())
+}
+
+impl<T: Float> Polyline2<T> {
+    /// hausdorff_distance: &Polyline × &Polyline × K -> K
+    pub fn hausdorff_distance(&self, other: &Polyline2<T>, tolerance: T) -> T {
+        hausdorff(&self.segments().collect::<Vec<_>>(), &other.segments().collect::<Vec<_>>(), tolerance)
+    }
+
+    /// frechet_distance: &Polyline × &Polyline -> K
+    /// discrete, over the vertices
+    pub fn frechet_distance(&self, other: &Polyline2<T>) -> T {
+        discrete_frechet(self.vertices(), other.vertices())
+    }
+}
+
+impl<T: Float> Polygon2<T> {
+    /// hausdorff_distance: &Polygon × &Polygon × K -> K
+    /// between the outlines, holes included
+    pub fn hausdorff_distance(&self, other: &Polygon2<T>, tolerance: T) -> T {
+        hausdorff(&self.edges().collect::<Vec<_>>(), &other.edges().collect::<Vec<_>>(), tolerance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hausdorff_distances() {
+        let a = Polyline2::new(vec![Point2(0.0, 0.0), Point2(4.0, 0.0)]);
+        let b = Polyline2::new(vec![Point2(0.0, 1.0), Point2(2.0, 3.0), Point2(4.0, 1.0)]);
+        assert!((a.hausdorff_distance(&b, 1e-9) - 3.0).abs() < 1e-9);
+        // the far point of `from` lies inside a segment, not at a vertex
+        let v = [Segment2::new(Point2(0.0, 2.0), Point2(4.0, 2.0))];
+        let w = [Segment2::new(Point2(0.0, 0.0), Point2(0.0, 1.0)), Segment2::new(Point2(4.0, 0.0), Point2(4.0, 1.0))];
+        assert!((directed_hausdorff(&v, &w, 1e-9) - 5.0f64.sqrt()).abs() < 1e-9);
+        assert!((directed_hausdorff(&w, &v, 1e-9) - 2.0).abs() < 1e-9);
+        let square = |s: f64| Polygon2::new(vec![Point2(-s, -s), Point2(s, -s), Point2(s, s), Point2(-s, s)]);
+        assert!((square(1.0).hausdorff_distance(&square(2.0), 1e-9) - 2.0f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn frechet() {
+        let a = Polyline2::new(vec![Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(2.0, 0.0)]);
+        let b = Polyline2::new(vec![Point2(0.0, 1.0), Point2(1.0, 1.0), Point2(2.0, 1.0)]);
+        assert_eq!(a.frechet_distance(&b), 1.0);
+        // same trace, walked backwards
+        let r = Polyline2::new(b.vertices().iter().rev().copied().collect());
+        assert_eq!(a.frechet_distance(&r), 5.0f64.sqrt());
+        assert!(a.hausdorff_distance(&r, 1e-9) < 1.0 + 1e-9);
+        let c = Polyline2::new(vec![Point2(0.0, 0.0), Point2(2.0, 0.0)]);
+        assert_eq!(a.frechet_distance(&c), 1.0);
+    }
+}
@@ -0,0 +1,338 @@
+//! Line and ring simplification
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::curve::segment_distance;
+use crate::{ FillRule, Float, MultiPolygon2, Point2, Polygon2, Polyline2, Polyline3 };
+
+/// Indices Douglas–Peucker keeps out of 0..n, ends included, where
+/// `deviation(a, b, i)` is how far vertex i lies from the shortcut a–b
+fn douglas_peucker<T: Float>(n: usize, tolerance: T, deviation: impl Fn(usize, usize, usize) -> T) -> Vec<usize> {
+    if n < 3 {
+        return (0..n).collect();
+    }
+    let mut keep = vec![0, n - 1];
+    let mut pending = vec![(0, n - 1)];
+    while let Some((a, b)) = pending.pop() {
+        match farthest(a, b, |i| deviation(a, b, i)) {
+            Some((i, d)) if d > tolerance => {
+                keep.push(i);
+                pending.push((a, i));
+                pending.push((i, b));
+            }
+            _ => {}
+        }
+    }
+    keep.sort_unstable();
+    keep
+}
+
+/// Vertex strictly between a and b with the greatest deviation
+fn farthest<T: Float>(a: usize, b: usize, deviation: impl Fn(usize) -> T) -> Option<(usize, T)> {
+    (a + 1..b).map(|i| (i, deviation(i))).reduce(|m, c| if c.1 > m.1 { c } else { m })
+}
+
+impl<T: Float> Polyline2<T> {
+    /// simplify: &Polyline × K -> Polyline
+    /// Douglas–Peucker: the fewest vertices it finds leaving no dropped one
+    /// farther than `tolerance` from the result; the ends are kept
+    pub fn simplify(&self, tolerance: T) -> Self {
+        let v = self.vertices();
+        let keep = douglas_peucker(v.len(), tolerance, |a, b, i| segment_distance(v[i], v[a], v[b]));
+        Polyline2::new(keep.into_iter().map(|i| v[i]).collect())
+    }
+}
+
+impl<T: Float> Polyline3<T> {
+    /// simplify: &Polyline × K -> Polyline
+    /// Douglas–Peucker, as for 2d polylines
+    pub fn simplify(&self, tolerance: T) -> Self {
+        let v = self.vertices();
+        let keep = douglas_peucker(v.len(), tolerance, |a, b, i| {
+            let (ab, ap) = (v[b].vector_from(v[a]), v[i].vector_from(v[a]));
+            let l = ab.dot(ab);
+            let t = if l == T::zero() { T::zero() } else { ap.dot(ab) / l };
+            let t = if t < T::zero() { T::zero() } else if t > T::one() { T::one() } else { t };
+            (ap - ab * t).length()
+        });
+        Polyline3::new(keep.into_iter().map(|i| v[i]).collect())
+    }
+}
+
+impl<T: Float> Polygon2<T> {
+    /// simplify: &Polygon × K -> Polygon
+    /// rings simplified within `tolerance` by `simplify_rings`, so the
+    /// result stays valid if the polygon was
+    pub fn simplify(&self, tolerance: T) -> Self {
+        let mut rings = simplify_rings(&self.rings().map(|r| r.to_vec()).collect::<Vec<_>>(), tolerance).into_iter();
+        let exterior = rings.next().unwrap_or_default();
+        Polygon2::with_holes(exterior, rings.collect())
+    }
+}
+
+impl<T: Float> MultiPolygon2<T> {
+    /// simplify: &MultiPolygon × K -> MultiPolygon
+    /// all rings simplified together by `simplify_rings`, so borders shared
+    /// between members stay shared
+    pub fn simplify(&self, tolerance: T) -> Self {
+        let rings: Vec<Vec<Point2<T>>> = self.iter().flat_map(|p| p.rings().map(|r| r.to_vec())).collect();
+        let mut rings = simplify_rings(&rings, tolerance).into_iter();
+        self.iter()
+            .map(|p| {
+                let exterior = rings.next().unwrap_or_default();
+                Polygon2::with_holes(exterior, rings.by_ref().take(p.holes().len()).collect())
+            })
+            .collect()
+    }
+}
+
+/// Run of ring vertices between junctions, simplified once wherever it is used
+struct Chain {
+    /// vertex ids, first equal to last when the chain is a whole ring
+    ids: Vec<usize>,
+    /// positions in `ids` kept, increasing, ends included
+    keep: Vec<usize>,
+}
+
+/// simplify_rings: [[Point]] × K -> [[Point]]
+/// Douglas–Peucker on closed rings, topology preserved: shortcuts are
+/// refined until none crosses another or sweeps over a kept vertex, and
+/// every ring keeps at least three vertices. Runs of vertices shared by
+/// several rings, matched by exact coordinates, are simplified once so
+/// shared borders stay shared; vertices where rings meet or part are kept
+pub fn simplify_rings<T: Float>(rings: &[Vec<Point2<T>>], tolerance: T) -> Vec<Vec<Point2<T>>> {
+    let lexicographic = |a: &Point2<T>, b: &Point2<T>| {
+        a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal).then(a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+    };
+    // one id per distinct point
+    let mut all: Vec<(Point2<T>, usize, usize)> = rings
+        .iter()
+        .enumerate()
+        .flat_map(|(r, ring)| ring.iter().enumerate().map(move |(i, &p)| (p, r, i)))
+        .collect();
+    all.sort_by(|a, b| lexicographic(&a.0, &b.0));
+    let mut points: Vec<Point2<T>> = Vec::new();
+    let mut id: Vec<Vec<usize>> = rings.iter().map(|r| vec![0; r.len()]).collect();
+    for &(p, r, i) in &all {
+        if points.last() != Some(&p) {
+            points.push(p);
+        }
+        id[r][i] = points.len() - 1;
+    }
+    for ids in id.iter_mut() {
+        ids.dedup();
+        while ids.len() > 1 && ids.first() == ids.last() {
+            ids.pop();
+        }
+    }
+    // junctions: vertices not joining exactly two distinct neighbours
+    let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); points.len()];
+    for ids in &id {
+        let n = ids.len();
+        for k in 0..n {
+            neighbours[ids[k]].push(ids[(k + n - 1) % n]);
+            neighbours[ids[k]].push(ids[(k + 1) % n]);
+        }
+    }
+    let junction: Vec<bool> = neighbours
+        .iter_mut()
+        .map(|v| {
+            v.sort_unstable();
+            v.dedup();
+            v.len() != 2
+        })
+        .collect();
+    // each ring as chains, stored once in a canonical direction
+    let mut chains: Vec<Chain> = Vec::new();
+    let mut lookup: HashMap<Vec<usize>, usize> = HashMap::new();
+    let mut uses: Vec<Vec<(usize, bool)>> = Vec::new();
+    for ids in &id {
+        let n = ids.len();
+        let mut ring_uses = Vec::new();
+        if n >= 3 {
+            let start = (0..n).find(|&k| junction[ids[k]]).unwrap_or(0);
+            let mut run = vec![ids[start]];
+            for k in 1..=n {
+                let v = ids[(start + k) % n];
+                run.push(v);
+                if k == n || junction[v] {
+                    let reversed: Vec<usize> = run.iter().rev().copied().collect();
+                    let forward = run <= reversed;
+                    let key = if forward { run.clone() } else { reversed };
+                    let c = *lookup.entry(key.clone()).or_insert_with(|| {
+                        chains.push(Chain { keep: vec![0, key.len() - 1], ids: key });
+                        chains.len() - 1
+                    });
+                    ring_uses.push((c, forward));
+                    run = vec![v];
+                }
+            }
+        }
+        uses.push(ring_uses);
+    }
+    let deviation = |c: &Chain, a: usize, b: usize, i: usize| {
+        segment_distance(points[c.ids[i]], points[c.ids[a]], points[c.ids[b]])
+    };
+    let refine = |c: &mut Chain, k: usize| {
+        let (a, b) = (c.keep[k], c.keep[k + 1]);
+        if let Some((i, _)) = farthest(a, b, |i| deviation(c, a, b, i)) {
+            c.keep.insert(k + 1, i);
+        }
+    };
+    for c in chains.iter_mut() {
+        let n = c.ids.len();
+        c.keep = douglas_peucker(n, tolerance, |a, b, i| deviation(c, a, b, i));
+        // a closed chain keeps the vertex farthest from its start as well
+        if c.ids[0] == c.ids[n - 1] && c.keep.len() == 2 {
+            let far = farthest(0, n - 1, |i| points[c.ids[i]].distance(points[c.ids[0]]));
+            if let Some((i, _)) = far {
+                c.keep.insert(1, i);
+            }
+        }
+    }
+    loop {
+        let segments: Vec<(usize, usize)> =
+            chains.iter().enumerate().flat_map(|(c, chain)| (0..chain.keep.len() - 1).map(move |k| (c, k))).collect();
+        let ends = |&(c, k): &(usize, usize)| (chains[c].ids[chains[c].keep[k]], chains[c].ids[chains[c].keep[k + 1]]);
+        let kept: Vec<usize> = {
+            let mut v: Vec<usize> = segments.iter().map(|s| ends(s).0).collect();
+            v.sort_unstable();
+            v.dedup();
+            v
+        };
+        let mut conflicts: Vec<(usize, usize)> = segments
+            .iter()
+            .filter(|&&(c, k)| chains[c].keep[k + 1] > chains[c].keep[k] + 1)
+            .filter(|s| {
+                let (a, b) = ends(s);
+                let (p, q) = (points[a], points[b]);
+                let crosses = segments.iter().any(|t| {
+                    let (e, f) = ends(t);
+                    t != *s && a != e && a != f && b != e && b != f && proper_cross(p, q, points[e], points[f])
+                });
+                // the region between the shortcut and what it replaces holds no kept vertex
+                let (c, k) = **s;
+                let replaced = Polygon2::new(chains[c].ids[chains[c].keep[k]..=chains[c].keep[k + 1]].iter().map(|&v| points[v]).collect());
+                let sweeps = kept.iter().any(|&v| v != a && v != b && replaced.contains(points[v], FillRule::EvenOdd));
+                crosses || sweeps
+            })
+            .copied()
+            .collect();
+        // rings left with fewer than three vertices
+        for ring in &uses {
+            let count: usize = ring.iter().map(|&(c, _)| chains[c].keep.len() - 1).sum();
+            if !ring.is_empty() && count < 3 {
+                let longest = ring
+                    .iter()
+                    .flat_map(|&(c, _)| (0..chains[c].keep.len() - 1).map(move |k| (c, k)))
+                    .filter(|&(c, k)| chains[c].keep[k + 1] > chains[c].keep[k] + 1)
+                    .max_by_key(|&(c, k)| chains[c].keep[k + 1] - chains[c].keep[k]);
+                conflicts.extend(longest);
+            }
+        }
+        if conflicts.is_empty() {
+            break;
+        }
+        // later positions first so earlier ones stay put
+        conflicts.sort_unstable_by(|s, t| t.cmp(s));
+        conflicts.dedup();
+        for (c, k) in conflicts {
+            refine(&mut chains[c], k);
+        }
+    }
+    uses.iter()
+        .zip(&id)
+        .map(|(ring, ids)| {
+            if ring.is_empty() {
+                return ids.iter().map(|&v| points[v]).collect();
+            }
+            let mut out = Vec::new();
+            for &(c, forward) in ring {
+                let chain = &chains[c];
+                let mut run: Vec<usize> = chain.keep.iter().map(|&k| chain.ids[k]).collect();
+                if !forward {
+                    run.reverse();
+                }
+                run.pop();
+                out.extend(run.into_iter().map(|v| points[v]));
+            }
+            out
+        })
+        .collect()
+}
+
+/// The open segments p–q and r–s meet
+fn proper_cross<T: Float>(p: Point2<T>, q: Point2<T>, r: Point2<T>, s: Point2<T>) -> bool {
+    let side = |a: Point2<T>, b: Point2<T>, c: Point2<T>| {
+        let (u, v) = (b.vector_from(a), c.vector_from(a));
+        let z = u.0 * v.1 - u.1 * v.0;
+        if z > T::zero() { 1 } else if z < T::zero() { -1 } else { 0 }
+    };
+    let (d1, d2, d3, d4) = (side(p, q, r), side(p, q, s), side(r, s, p), side(r, s, q));
+    if d1 * d2 < 0 && d3 * d4 < 0 {
+        return true;
+    }
+    // touching or overlapping in a collinear configuration
+    let within = |a: Point2<T>, b: Point2<T>, c: Point2<T>| {
+        let (lo0, hi0) = if a.0 < b.0 { (a.0, b.0) } else { (b.0, a.0) };
+        let (lo1, hi1) = if a.1 < b.1 { (a.1, b.1) } else { (b.1, a.1) };
+        lo0 <= c.0 && c.0 <= hi0 && lo1 <= c.1 && c.1 <= hi1
+    };
+    (d1 == 0 && within(p, q, r)) || (d2 == 0 && within(p, q, s)) || (d3 == 0 && within(r, s, p)) || (d4 == 0 && within(r, s, q))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn douglas_peucker_lines() {
+        let l = Polyline2::new(vec![Point2(0.0, 0.0), Point2(1.0, 0.1), Point2(2.0, -0.1), Point2(3.0, 5.0), Point2(4.0, 6.0), Point2(5.0, 7.0)]);
+        assert_eq!(l.simplify(0.5).vertices(), &[Point2(0.0, 0.0), Point2(2.0, -0.1), Point2(3.0, 5.0), Point2(5.0, 7.0)]);
+        // collinear vertices go at any tolerance
+        assert_eq!(l.simplify(0.01).vertices().len(), 5);
+        let h = Polyline3::new((0..=20).map(|k| {
+            let a = k as f64 * 0.3;
+            crate::Point3(a.cos(), a.sin(), 0.1 * a)
+        }).collect());
+        let s = h.simplify(0.05);
+        assert!(s.vertices().len() < 21 && s.vertices().len() > 2);
+        assert_eq!((s.vertices()[0], s.vertices()[s.vertices().len() - 1]), (h.vertices()[0], h.vertices()[20]));
+    }
+
+    #[test]
+    fn rings_stay_simple() {
+        // a bump whose shortcut would leave the hole inside it out
+        let exterior = vec![
+            Point2(0.0, 0.0), Point2(10.0, 0.0), Point2(10.0, 10.0), Point2(6.0, 10.0),
+            Point2(5.0, 12.0), Point2(4.0, 10.0), Point2(0.0, 10.0),
+        ];
+        let hole = vec![Point2(4.8, 10.4), Point2(5.0, 11.0), Point2(5.2, 10.4)];
+        let p = Polygon2::with_holes(exterior, vec![hole]);
+        assert!(p.is_valid());
+        let s = p.simplify(3.0);
+        assert!(s.is_valid());
+        assert!(s.vertices().len() >= 3 && s.holes()[0].len() >= 3);
+        assert!(s.vertices().contains(&Point2(5.0, 12.0)));
+        // each ring alone collapses the notch
+        let alone = Polygon2::new(p.vertices().to_vec()).simplify(3.0);
+        assert!(!alone.vertices().contains(&Point2(5.0, 12.0)));
+    }
+
+    #[test]
+    fn shared_borders() {
+        // two squares sharing a wiggly border
+        let border: Vec<Point2<f64>> = (0..=10).map(|k| Point2(5.0 + if k % 2 == 0 { 0.0 } else { 0.1 }, k as f64)).collect();
+        let mut left = vec![Point2(0.0, 10.0), Point2(0.0, 0.0)];
+        left.extend(border.iter().copied());
+        let mut right = vec![Point2(10.0, 0.0), Point2(10.0, 10.0)];
+        right.extend(border.iter().rev().copied());
+        let m = MultiPolygon2::new(vec![Polygon2::new(left), Polygon2::new(right)]);
+        let s = m.simplify(0.5);
+        let (a, b) = (s.polygons()[0].vertices(), s.polygons()[1].vertices());
+        assert_eq!((a.len(), b.len()), (4, 4));
+        assert!(a.iter().filter(|p| b.contains(p)).count() == 2);
+        assert!((s.area() - 100.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,350 @@
+//! Geometric constraints for sketch editing
+//!
+//! A `Sketch2` holds points, segments between them and circles around
+//! them, with constraints among these. `solve` projects the free points
+//! onto one constraint after another, round after round, until every
+//! constraint holds within a tolerance: cheap enough to rerun on each
+//! mouse move while a point is dragged.
+
+use crate::{ Circle2, Float, Point2, Segment2, Vector2 };
+
+/// Relation a sketch keeps; indices are those returned by the `add_` methods
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Constraint2<T> {
+    /// two points at the same place
+    Coincident(usize, usize),
+    /// two points this far apart
+    Distance(usize, usize, T),
+    /// two segments along the same direction, either way round
+    Parallel(usize, usize),
+    /// two segments at a right angle
+    Perpendicular(usize, usize),
+    /// the line of a segment touching a circle
+    Tangent(usize, usize),
+}
+
+/// Points, segments and circles under constraints
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sketch2<T> {
+    points: Vec<Point2<T>>,
+    fixed: Vec<bool>,
+    segments: Vec<[usize; 2]>,
+    circles: Vec<(usize, T)>,
+    constraints: Vec<Constraint2<T>>,
+}
+
+impl<T> Default for Sketch2<T> {
+    fn default() -> Self {
+        Sketch2 { points: Vec::new(), fixed: Vec::new(), segments: Vec::new(), circles: Vec::new(), constraints: Vec::new() }
+    }
+}
+
+impl<T: Float> Sketch2<T> {
+    /// new: () -> Sketch
+    pub fn new() -> Self {
+        Sketch2::default()
+    }
+
+    /// add_point: &mut Sketch × Point -> usize
+    pub fn add_point(&mut self, p: Point2<T>) -> usize {
+        self.points.push(p);
+        self.fixed.push(false);
+        self.points.len() - 1
+    }
+
+    /// add_segment: &mut Sketch × usize × usize -> usize
+    /// the segment between two points
+    pub fn add_segment(&mut self, a: usize, b: usize) -> usize {
+        assert!(a < self.points.len() && b < self.points.len(), "segment between missing points");
+        self.segments.push([a, b]);
+        self.segments.len() - 1
+    }
+
+    /// add_circle: &mut Sketch × usize × K -> usize
+    /// a circle of fixed radius around a point
+    pub fn add_circle(&mut self, center: usize, radius: T) -> usize {
+        assert!(center < self.points.len(), "circle around a missing point");
+        self.circles.push((center, radius.abs()));
+        self.circles.len() - 1
+    }
+
+    /// constrain: &mut Sketch × Constraint
+    pub fn constrain(&mut self, constraint: Constraint2<T>) {
+        let (points, segments, circles) = (self.points.len(), self.segments.len(), self.circles.len());
+        let valid = match constraint {
+            Constraint2::Coincident(a, b) | Constraint2::Distance(a, b, _) => a < points && b < points,
+            Constraint2::Parallel(s, t) | Constraint2::Perpendicular(s, t) => s < segments && t < segments,
+            Constraint2::Tangent(s, c) => s < segments && c < circles,
+        };
+        assert!(valid, "constraint on missing elements");
+        self.constraints.push(constraint);
+    }
+
+    /// fix: &mut Sketch × usize × bool
+    /// a fixed point is never moved by `solve`
+    pub fn fix(&mut self, point: usize, fixed: bool) {
+        self.fixed[point] = fixed;
+    }
+
+    /// set_point: &mut Sketch × usize × Point
+    pub fn set_point(&mut self, point: usize, p: Point2<T>) {
+        self.points[point] = p;
+    }
+
+    /// point: &Sketch × usize -> Point
+    pub fn point(&self, point: usize) -> Point2<T> {
+        self.points[point]
+    }
+
+    /// segment: &Sketch × usize -> Segment
+    pub fn segment(&self, segment: usize) -> Segment2<T> {
+        let [a, b] = self.segments[segment];
+        Segment2::new(self.points[a], self.points[b])
+    }
+
+    /// circle: &Sketch × usize -> Circle
+    pub fn circle(&self, circle: usize) -> Circle2<T> {
+        let (c, r) = self.circles[circle];
+        Circle2::new(self.points[c], r)
+    }
+
+    /// constraints: &Sketch -> &[Constraint]
+    pub fn constraints(&self) -> &[Constraint2<T>] {
+        &self.constraints
+    }
+
+    /// residual: &Sketch -> K
+    /// largest violation of a constraint, in lengths: the angle
+    /// constraints measured as the offset of the shorter segment's end
+    pub fn residual(&self) -> T {
+        self.constraints.iter().map(|&c| self.violation(c)).fold(T::zero(), |m, v| if v > m { v } else { m })
+    }
+
+    /// solve: &mut Sketch × usize × K -> Option<usize>
+    /// rounds of projection taken until the residual is within `tolerance`,
+    /// None if `rounds` were not enough; the points are left where the
+    /// last round put them either way
+    pub fn solve(&mut self, rounds: usize, tolerance: T) -> Option<usize> {
+        if self.residual() <= tolerance {
+            return Some(0);
+        }
+        for round in 1..=rounds {
+            for k in 0..self.constraints.len() {
+                self.project(self.constraints[k]);
+            }
+            if self.residual() <= tolerance {
+                return Some(round);
+            }
+        }
+        None
+    }
+
+    /// drag: &mut Sketch × usize × Point × usize × K -> Option<usize>
+    /// moves a point, then solves with it held there
+    pub fn drag(&mut self, point: usize, to: Point2<T>, rounds: usize, tolerance: T) -> Option<usize> {
+        let fixed = self.fixed[point];
+        self.points[point] = to;
+        self.fixed[point] = true;
+        let solved = self.solve(rounds, tolerance);
+        self.fixed[point] = fixed;
+        solved
+    }
+
+    /// Share of a correction a point takes: none if fixed
+    fn weight(&self, point: usize) -> T {
+        if self.fixed[point] { T::zero() } else { T::one() }
+    }
+
+    fn direction(&self, segment: usize) -> Vector2<T> {
+        let [a, b] = self.segments[segment];
+        self.points[b].vector_from(self.points[a])
+    }
+
+    fn violation(&self, constraint: Constraint2<T>) -> T {
+        let angle = |s: usize, t: usize, perpendicular: bool| {
+            let (u, v) = (self.direction(s), self.direction(t));
+            let (lu, lv) = (u.length(), v.length());
+            if lu == T::zero() || lv == T::zero() {
+                return T::zero();
+            }
+            let off = if perpendicular { u.dot(v) } else { u.0 * v.1 - u.1 * v.0 };
+            let shorter = if lu < lv { lu } else { lv };
+            (off / (lu * lv)).abs() * shorter
+        };
+        match constraint {
+            Constraint2::Coincident(a, b) => self.points[a].distance(self.points[b]),
+            Constraint2::Distance(a, b, d) => (self.points[a].distance(self.points[b]) - d).abs(),
+            Constraint2::Parallel(s, t) => angle(s, t, false),
+            Constraint2::Perpendicular(s, t) => angle(s, t, true),
+            Constraint2::Tangent(s, c) => {
+                let Some((_, h)) = self.line_offset(s, self.points[self.circles[c].0]) else { return T::zero() };
+                (h.abs() - self.circles[c].1).abs()
+            }
+        }
+    }
+
+    /// Unit normal of a segment's line and the signed distance of p from it
+    fn line_offset(&self, segment: usize, p: Point2<T>) -> Option<(Vector2<T>, T)> {
+        let [a, _] = self.segments[segment];
+        let u = self.direction(segment);
+        let l = u.length();
+        if l == T::zero() {
+            return None;
+        }
+        let n = Vector2(-u.1, u.0) / l;
+        Some((n, n.dot(p.vector_from(self.points[a]))))
+    }
+
+    /// Moves two points by shares of `delta`: a forward, b back
+    fn share(&mut self, a: usize, b: usize, delta: Vector2<T>) {
+        let (wa, wb) = (self.weight(a), self.weight(b));
+        let w = wa + wb;
+        if w == T::zero() {
+            return;
+        }
+        self.points[a] += delta * (wa / w);
+        self.points[b] -= delta * (wb / w);
+    }
+
+    /// Whether a segment can turn: one of its ends is free
+    fn turns(&self, segment: usize) -> bool {
+        let [a, b] = self.segments[segment];
+        !(self.fixed[a] && self.fixed[b])
+    }
+
+    /// Turns a segment onto the unit direction d, or its opposite, keeping
+    /// its length and a fixed end or else its midpoint
+    fn orient(&mut self, segment: usize, d: Vector2<T>) {
+        let [a, b] = self.segments[segment];
+        let u = self.direction(segment);
+        let l = u.length();
+        let d = if u.dot(d) < T::zero() { -d } else { d };
+        if self.fixed[a] {
+            self.points[b] = self.points[a] + d * l;
+        } else if self.fixed[b] {
+            self.points[a] = self.points[b] - d * l;
+        } else {
+            let mid = self.points[a] + u / T::from_f64(2.0);
+            self.points[a] = mid - d * (l / T::from_f64(2.0));
+            self.points[b] = mid + d * (l / T::from_f64(2.0));
+        }
+    }
+
+    /// Turns two segments onto a shared direction, t's taken a quarter
+    /// turn back first when they should be perpendicular
+    fn align(&mut self, s: usize, t: usize, perpendicular: bool) {
+        let (u, v) = (self.direction(s), self.direction(t));
+        let (lu, lv) = (u.length(), v.length());
+        if lu == T::zero() || lv == T::zero() {
+            return;
+        }
+        let (u, v) = (u / lu, v / lv);
+        let v = if perpendicular { Vector2(v.1, -v.0) } else { v };
+        let v = if u.dot(v) < T::zero() { -v } else { v };
+        let d = match (self.turns(s), self.turns(t)) {
+            (false, false) => return,
+            (false, true) => u,
+            (true, false) => v,
+            (true, true) => {
+                let m = u + v;
+                let l = m.length();
+                if l == T::zero() { u } else { m / l }
+            }
+        };
+        self.orient(s, d);
+        self.orient(t, if perpendicular { Vector2(-d.1, d.0) } else { d });
+    }
+
+    fn project(&mut self, constraint: Constraint2<T>) {
+        match constraint {
+            Constraint2::Coincident(a, b) => {
+                let delta = self.points[b].vector_from(self.points[a]);
+                self.share(a, b, delta);
+            }
+            Constraint2::Distance(a, b, d) => {
+                let u = self.points[b].vector_from(self.points[a]);
+                let l = u.length();
+                let n = if l == T::zero() { Vector2(T::one(), T::zero()) } else { u / l };
+                self.share(a, b, n * (l - d));
+            }
+            Constraint2::Parallel(s, t) => self.align(s, t, false),
+            Constraint2::Perpendicular(s, t) => self.align(s, t, true),
+            Constraint2::Tangent(s, c) => {
+                let (center, r) = self.circles[c];
+                let Some((n, h)) = self.line_offset(s, self.points[center]) else { return };
+                // the center moves toward the line and the line toward it
+                let side = if h < T::zero() { -n } else { n };
+                let e = h.abs() - r;
+                let [a, b] = self.segments[s];
+                let wl = if self.turns(s) { T::one() } else { T::zero() };
+                let w = self.weight(center) + wl;
+                if w == T::zero() {
+                    return;
+                }
+                let pull = side * (e / w);
+                let wc = self.weight(center);
+                self.points[center] -= pull * wc;
+                for p in [a, b] {
+                    let wp = wl * self.weight(p);
+                    self.points[p] += pull * wp;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rectangle() {
+        // a sloppy quadrilateral pulled into a 4 × 2 rectangle
+        let mut sketch = Sketch2::new();
+        let p: Vec<usize> = [Point2(0.0, 0.0), Point2(4.3, 0.4), Point2(3.8, 2.5), Point2(-0.2, 1.7)]
+            .into_iter().map(|q| sketch.add_point(q)).collect();
+        sketch.fix(p[0], true);
+        let s: Vec<usize> = (0..4).map(|k| sketch.add_segment(p[k], p[(k + 1) % 4])).collect();
+        sketch.constrain(Constraint2::Parallel(s[0], s[2]));
+        sketch.constrain(Constraint2::Parallel(s[1], s[3]));
+        sketch.constrain(Constraint2::Perpendicular(s[0], s[1]));
+        sketch.constrain(Constraint2::Distance(p[0], p[1], 4.0));
+        sketch.constrain(Constraint2::Distance(p[1], p[2], 2.0));
+        assert!(sketch.residual() > 0.1);
+        let rounds = sketch.solve(500, 1e-9).unwrap();
+        assert!(rounds > 1);
+        assert_eq!(sketch.point(p[0]), Point2(0.0, 0.0));
+        let (d0, d1) = (sketch.segment(s[0]), sketch.segment(s[1]));
+        let (u, v) = (d0.end().vector_from(d0.start()), d1.end().vector_from(d1.start()));
+        assert!(u.dot(v).abs() < 1e-8 && (u.length() - 4.0).abs() < 1e-8 && (v.length() - 2.0).abs() < 1e-8);
+        let top = sketch.segment(s[2]);
+        assert!((top.start().distance(top.end()) - 4.0).abs() < 1e-8);
+        assert_eq!(sketch.solve(10, 1e-9), Some(0));
+    }
+
+    #[test]
+    fn tangent_drag() {
+        let mut sketch = Sketch2::new();
+        let (a, b) = (sketch.add_point(Point2(0.0, 0.0)), sketch.add_point(Point2(10.0, 0.0)));
+        sketch.fix(a, true);
+        sketch.fix(b, true);
+        let base = sketch.add_segment(a, b);
+        let center = sketch.add_point(Point2(3.0, 2.5));
+        let wheel = sketch.add_circle(center, 1.0);
+        let pin = sketch.add_point(Point2(3.5, 1.0));
+        sketch.constrain(Constraint2::Tangent(base, wheel));
+        sketch.constrain(Constraint2::Coincident(pin, center));
+        assert!(sketch.solve(100, 1e-9).is_some());
+        assert!((sketch.circle(wheel).center().1 - 1.0).abs() < 1e-9);
+        // dragging the pin along the fixed base rolls the wheel with it
+        assert!(sketch.drag(pin, Point2(7.0, 1.0), 100, 1e-9).is_some());
+        assert!(sketch.point(center).distance(Point2(7.0, 1.0)) < 1e-9);
+        // a drag off the base wins over the tangency it cannot keep
+        assert!(sketch.drag(pin, Point2(7.0, 3.0), 100, 1e-9).is_none());
+        assert_eq!(sketch.point(pin), Point2(7.0, 3.0));
+        assert!(sketch.residual() > 1.0);
+        sketch.fix(pin, false);
+        assert!(sketch.solve(100, 1e-9).is_some());
+        assert!((sketch.point(pin).1 - 1.0).abs() < 1e-9 && sketch.point(b) == Point2(10.0, 0.0));
+    }
+}
@@ -0,0 +1,161 @@
+//! Grid snapping and integer grid coordinates
+
+use crate::{ Float, Point2, Point3, Vector2, Vector3 };
+
+/// Axis-aligned 2d grid: nodes at origin + (i · spacing.x, j · spacing.y)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Grid2<T> {
+    origin: Point2<T>,
+    spacing: Vector2<T>,
+}
+
+/// Nearest integer, halves away from zero as `f64::round`
+fn round<T: Float>(x: T) -> T {
+    if x < T::zero() {
+        return -round(-x);
+    }
+    let f = x.floor();
+    if x - f >= T::from_f64(0.5) { f + T::one() } else { f }
+}
+
+fn ceil<T: Float>(x: T) -> T {
+    -(-x).floor()
+}
+
+/// Multiple of `spacing` nearest x; x itself for no spacing
+fn snap<T: Float>(x: T, spacing: T) -> T {
+    if spacing == T::zero() { x } else { round(x / spacing) * spacing }
+}
+
+/// Integer, saturating at the ends of i64 and 0 for NaN
+fn to_i64<T: Float>(x: T) -> i64 {
+    x.to_f64() as i64
+}
+
+macro_rules! impl_snap {
+    ($name:ident, $($field:tt),+) => {
+        impl<T: Float> $name<T> {
+            /// floor: Self -> Self
+            /// each coordinate rounded down
+            pub fn floor(self) -> Self {
+                $name($(self.$field.floor()),+)
+            }
+
+            /// ceil: Self -> Self
+            /// each coordinate rounded up
+            pub fn ceil(self) -> Self {
+                $name($(ceil(self.$field)),+)
+            }
+
+            /// round: Self -> Self
+            /// each coordinate to the nearest integer, halves away from zero
+            pub fn round(self) -> Self {
+                $name($(round(self.$field)),+)
+            }
+
+            /// snap_to_grid: Self × K -> Self
+            /// each coordinate to the nearest multiple of `spacing`
+            pub fn snap_to_grid(self, spacing: T) -> Self {
+                $name($(snap(self.$field, spacing)),+)
+            }
+        }
+    };
+}
+
+impl_snap!(Vector2, 0, 1);
+impl_snap!(Point2, 0, 1);
+impl_snap!(Vector3, 0, 1, 2);
+impl_snap!(Point3, 0, 1, 2);
+
+impl<T: Float> Grid2<T> {
+    /// new: Point × Vector -> Grid
+    /// a node at `origin`, the next ones `spacing` apart along each axis;
+    /// a zero spacing leaves that axis unsnapped
+    pub fn new(origin: Point2<T>, spacing: Vector2<T>) -> Self {
+        Grid2 { origin, spacing }
+    }
+
+    /// square: Point × K -> Grid
+    pub fn square(origin: Point2<T>, spacing: T) -> Self {
+        Grid2::new(origin, Vector2(spacing, spacing))
+    }
+
+    /// origin: &Grid -> Point
+    pub fn origin(&self) -> Point2<T> {
+        self.origin
+    }
+
+    /// spacing: &Grid -> Vector
+    pub fn spacing(&self) -> Vector2<T> {
+        self.spacing
+    }
+
+    /// snap: &Grid × Point -> Point
+    /// nearest node
+    pub fn snap(&self, p: Point2<T>) -> Point2<T> {
+        let d = p.vector_from(self.origin);
+        self.origin + Vector2(snap(d.0, self.spacing.0), snap(d.1, self.spacing.1))
+    }
+
+    /// nearest: &Grid × Point -> Point<i64>
+    /// integer coordinates of the nearest node
+    pub fn nearest(&self, p: Point2<T>) -> Point2<i64> {
+        let Point2(x, y) = self.scaled(p);
+        Point2(to_i64(round(x)), to_i64(round(y)))
+    }
+
+    /// cell: &Grid × Point -> Point<i64>
+    /// integer coordinates of the node at the lower corner of the cell
+    /// holding p, cells closed below and open above
+    pub fn cell(&self, p: Point2<T>) -> Point2<i64> {
+        let Point2(x, y) = self.scaled(p);
+        Point2(to_i64(x.floor()), to_i64(y.floor()))
+    }
+
+    /// node: &Grid × Point<i64> -> Point
+    /// position of the node with integer coordinates i
+    pub fn node(&self, i: Point2<i64>) -> Point2<T> {
+        self.origin + Vector2(self.spacing.0 * T::from_f64(i.0 as f64), self.spacing.1 * T::from_f64(i.1 as f64))
+    }
+
+    /// Offset from the origin in spacings, axes without one left at zero
+    fn scaled(&self, p: Point2<T>) -> Point2<T> {
+        let d = p.vector_from(self.origin);
+        let per = |x: T, s: T| if s == T::zero() { T::zero() } else { x / s };
+        Point2(per(d.0, self.spacing.0), per(d.1, self.spacing.1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn per_component() {
+        let v = Vector3(1.5, -1.5, -0.2);
+        assert_eq!(v.floor(), Vector3(1.0, -2.0, -1.0));
+        assert_eq!(v.ceil(), Vector3(2.0, -1.0, 0.0));
+        assert_eq!(v.round(), Vector3(2.0, -2.0, 0.0));
+        assert_eq!(Point2(0.49999999999999994, 2.5).round(), Point2(0.0, 3.0));
+        assert_eq!(Point2(7.0, -7.0).snap_to_grid(5.0), Point2(5.0, -5.0));
+        assert_eq!(Point3(7.0, -8.0, 1.0).snap_to_grid(0.0), Point3(7.0, -8.0, 1.0));
+    }
+
+    #[test]
+    fn offset_grid() {
+        let g = Grid2::new(Point2(1.0, 2.0), Vector2(0.5, 4.0));
+        assert_eq!(g.snap(Point2(1.3, 7.0)), Point2(1.5, 6.0));
+        assert_eq!(g.nearest(Point2(1.3, 7.0)), Point2(1, 1));
+        assert_eq!(g.cell(Point2(0.9, 1.9)), Point2(-1, -1));
+        assert_eq!(g.cell(Point2(1.5, 2.0)), Point2(1, 0));
+        assert_eq!(g.node(Point2(-2, 3)), Point2(0.0, 14.0));
+        for i in -5..5 {
+            let p = Point2(i, 2 * i);
+            assert_eq!(g.nearest(g.node(p)), p);
+            assert_eq!(g.cell(g.node(p)), p);
+        }
+        let free = Grid2::new(Point2(0.0, 0.0), Vector2(1.0, 0.0));
+        assert_eq!(free.snap(Point2(0.7, 0.7)), Point2(1.0, 0.7));
+        assert_eq!(Grid2::square(Point2(0.0, 0.0), 2.0).nearest(Point2(f64::NAN, 1e300)), Point2(0, i64::MAX));
+    }
+}
@@ -0,0 +1,754 @@
+//! Solid primitives
+
+use crate::{ Aabb3, Bounded3, Float, Hit, Plane3, Point3, Ray3, Raycast3, Sdf3, Vector3 };
+use crate::path::quadratic_roots;
+
+/// Convex shapes given by their farthest points, for GJK
+pub trait Support3<T: Float> {
+    /// support: &Shape × Vector -> Point
+    /// a point of the shape farthest in direction d
+    fn support(&self, d: Vector3<T>) -> Point3<T>;
+}
+
+/// Sphere: center, radius
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Sphere<T>(Point3<T>, T);
+
+/// Cone: apex, axis from the apex to the center of the base (its length the
+/// height), half-angle at the apex in (0, π/2)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Cone<T>(Point3<T>, Vector3<T>, T);
+
+/// Cylinder: centers of the two caps, radius
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Cylinder<T>(Point3<T>, Point3<T>, T);
+
+/// Torus: center, axis normal to the ring, major radius from the center to
+/// the middle of the tube, minor radius of the tube, smaller than the major
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Torus<T>(Point3<T>, Vector3<T>, T, T);
+
+impl<T: Float> Sphere<T> {
+    /// new: Point × K -> Sphere
+    pub fn new(center: Point3<T>, radius: T) -> Self {
+        Sphere(center, radius)
+    }
+
+    /// center: &Sphere -> Point
+    pub fn center(&self) -> Point3<T> {
+        self.0
+    }
+
+    /// radius: &Sphere -> K
+    pub fn radius(&self) -> T {
+        self.1
+    }
+
+    /// contains: &Sphere × Point -> bool
+    /// boundary included
+    pub fn contains(&self, p: Point3<T>) -> bool {
+        p.distance(self.0) <= self.1
+    }
+}
+
+impl<T: Float> Sdf3<T> for Sphere<T> {
+    fn distance(&self, p: Point3<T>) -> T {
+        p.distance(self.0) - self.1
+    }
+}
+
+impl<T: Float> Bounded3<T> for Sphere<T> {
+    fn bounds(&self) -> Option<Aabb3<T>> {
+        let r = Vector3(self.1, self.1, self.1);
+        Some(Aabb3::new(self.0 - r, self.0 + r))
+    }
+}
+
+impl<T: Float> Support3<T> for Sphere<T> {
+    fn support(&self, d: Vector3<T>) -> Point3<T> {
+        let l = d.length();
+        if l > T::zero() { self.0 + d * (self.1 / l) } else { self.0 }
+    }
+}
+
+impl<T: Float> Support3<T> for Aabb3<T> {
+    fn support(&self, d: Vector3<T>) -> Point3<T> {
+        let (lo, hi) = (self.min(), self.max());
+        let pick = |d: T, lo: T, hi: T| if d >= T::zero() { hi } else { lo };
+        Point3(pick(d.0, lo.0, hi.0), pick(d.1, lo.1, hi.1), pick(d.2, lo.2, hi.2))
+    }
+}
+
+impl<T: Float> Raycast3<T> for Sphere<T> {
+    fn raycast(&self, ray: &Ray3<T>) -> Option<Hit<T>> {
+        let (o, d) = (ray.origin().vector_from(self.0), ray.direction());
+        let roots = quadratic_roots(d.dot(d), o.dot(d) * T::from_f64(2.0), o.dot(o) - self.1 * self.1);
+        nearest(ray, roots.into_iter().map(|t| (t, o + d * t)))
+    }
+}
+
+impl<T: Float> Cone<T> {
+    /// new: Point × Vector × K -> Cone
+    pub fn new(apex: Point3<T>, axis: Vector3<T>, angle: T) -> Self {
+        Cone(apex, axis, angle)
+    }
+
+    /// apex: &Cone -> Point
+    pub fn apex(&self) -> Point3<T> {
+        self.0
+    }
+
+    /// axis: &Cone -> Vector
+    pub fn axis(&self) -> Vector3<T> {
+        self.1
+    }
+
+    /// angle: &Cone -> K
+    pub fn angle(&self) -> T {
+        self.2
+    }
+
+    /// height: &Cone -> K
+    pub fn height(&self) -> T {
+        self.1.length()
+    }
+
+    /// radius: &Cone -> K
+    /// of the base
+    pub fn radius(&self) -> T {
+        self.height() * self.2.sin() / self.2.cos()
+    }
+
+    /// contains: &Cone × Point -> bool
+    /// boundary included
+    pub fn contains(&self, p: Point3<T>) -> bool {
+        let (s, r) = self.local(p);
+        s >= T::zero() && s <= self.height() && r * self.2.cos() <= s * self.2.sin()
+    }
+
+    /// contains_sphere: &Cone × &Sphere -> bool
+    /// the whole ball inside
+    pub fn contains_sphere(&self, s: &Sphere<T>) -> bool {
+        self.distance(s.0) <= -s.1
+    }
+
+    /// intersects_sphere: &Cone × &Sphere -> bool
+    /// touching counts
+    pub fn intersects_sphere(&self, s: &Sphere<T>) -> bool {
+        self.distance(s.0) <= s.1
+    }
+
+    /// intersects_aabb: &Cone × &Aabb -> bool
+    /// touching counts
+    pub fn intersects_aabb(&self, b: &Aabb3<T>) -> bool {
+        overlap(|d| self.support(d), |d| b.support(d))
+    }
+
+    /// Distance along the axis from the apex and distance from the axis
+    fn local(&self, p: Point3<T>) -> (T, T) {
+        let v = p.vector_from(self.0);
+        let h = self.height();
+        if h == T::zero() {
+            return (T::zero(), v.length());
+        }
+        let s = v.dot(self.1) / h;
+        let r2 = v.dot(v) - s * s;
+        (s, if r2 > T::zero() { r2.sqrt() } else { T::zero() })
+    }
+}
+
+impl<T: Float> Support3<T> for Cone<T> {
+    fn support(&self, d: Vector3<T>) -> Point3<T> {
+        let base = self.0 + self.1;
+        let rim = base + radial(self.1, d, self.radius());
+        if rim.vector_from(self.0).dot(d) > T::zero() { rim } else { self.0 }
+    }
+}
+
+impl<T: Float> Sdf3<T> for Cone<T> {
+    /// exact, from the distances to the slant side and the base in the
+    /// half-plane through the axis
+    fn distance(&self, p: Point3<T>) -> T {
+        let (s, r) = self.local(p);
+        let (h, rim) = (self.height(), self.radius());
+        let (sin, cos) = (self.2.sin(), self.2.cos());
+        if s >= T::zero() && s <= h && r * cos <= s * sin {
+            let slant = s * sin - r * cos;
+            let base = h - s;
+            return -if slant < base { slant } else { base };
+        }
+        let slant = segment_distance((s, r), (T::zero(), T::zero()), (h, rim));
+        let base = segment_distance((s, r), (h, T::zero()), (h, rim));
+        if slant < base { slant } else { base }
+    }
+}
+
+impl<T: Float> Raycast3<T> for Cone<T> {
+    fn raycast(&self, ray: &Ray3<T>) -> Option<Hit<T>> {
+        let h = self.height();
+        if h == T::zero() {
+            return None;
+        }
+        let n = self.1 / h;
+        let (o, d) = (ray.origin().vector_from(self.0), ray.direction());
+        let c2 = self.2.cos() * self.2.cos();
+        let (on, dn) = (o.dot(n), d.dot(n));
+        // (v · n)² = cos²α |v|² holds on both nappes; keep the one toward the base
+        let two = T::from_f64(2.0);
+        let roots = quadratic_roots(dn * dn - c2 * d.dot(d), (on * dn - c2 * o.dot(d)) * two, on * on - c2 * o.dot(o));
+        let side = roots.into_iter().filter_map(|t| {
+            let v = o + d * t;
+            let s = v.dot(n);
+            (s >= T::zero() && s <= h).then_some((t, v * c2 - n * s))
+        });
+        let r = self.radius();
+        let base = (dn != T::zero()).then(|| {
+            let t = (h - on) / dn;
+            let q = o + d * t - n * h;
+            (q.dot(q) <= r * r).then_some((t, n))
+        });
+        nearest(ray, side.chain(base.flatten()))
+    }
+}
+
+impl<T: Float> Bounded3<T> for Cone<T> {
+    fn bounds(&self) -> Option<Aabb3<T>> {
+        let base = self.0 + self.1;
+        let e = disk_reach(self.1, self.radius());
+        Some(Aabb3::new(base - e, base + e).expand(self.0))
+    }
+}
+
+impl<T: Float> Cylinder<T> {
+    /// new: Point × Point × K -> Cylinder
+    pub fn new(a: Point3<T>, b: Point3<T>, radius: T) -> Self {
+        Cylinder(a, b, radius)
+    }
+
+    /// ends: &Cylinder -> (Point, Point)
+    pub fn ends(&self) -> (Point3<T>, Point3<T>) {
+        (self.0, self.1)
+    }
+
+    /// radius: &Cylinder -> K
+    pub fn radius(&self) -> T {
+        self.2
+    }
+
+    /// height: &Cylinder -> K
+    pub fn height(&self) -> T {
+        self.1.distance(self.0)
+    }
+
+    /// contains: &Cylinder × Point -> bool
+    /// boundary included
+    pub fn contains(&self, p: Point3<T>) -> bool {
+        let (s, r) = self.local(p);
+        s >= T::zero() && s <= self.height() && r <= self.2
+    }
+
+    /// sample: &Cylinder × K × K -> Point
+    /// point on the surface, caps included, for (u, v) in the unit square;
+    /// uniform (u, v) give points uniform over the area
+    pub fn sample(&self, u: T, v: T) -> Point3<T> {
+        let (h, r) = (self.height(), self.2);
+        let (e1, e2) = Plane3::new(self.0, self.1.vector_from(self.0)).basis();
+        let a = u * T::pi() * T::from_f64(2.0);
+        let around = e1 * a.cos() + e2 * a.sin();
+        let axis = self.1.vector_from(self.0);
+        let side = h / (h + r);
+        if v < side {
+            return self.0 + axis * (v / side) + around * r;
+        }
+        // each cap takes half of the rest, its radius square-rooted for uniform area
+        let w = (v - side) / (T::one() - side) * T::from_f64(2.0);
+        if w < T::one() {
+            self.0 + around * (r * w.sqrt())
+        } else {
+            self.1 + around * (r * (w - T::one()).sqrt())
+        }
+    }
+
+    /// Distance along the axis from the first cap and distance from the axis
+    fn local(&self, p: Point3<T>) -> (T, T) {
+        let v = p.vector_from(self.0);
+        let axis = self.1.vector_from(self.0);
+        let h = axis.length();
+        if h == T::zero() {
+            return (T::zero(), v.length());
+        }
+        let s = v.dot(axis) / h;
+        let r2 = v.dot(v) - s * s;
+        (s, if r2 > T::zero() { r2.sqrt() } else { T::zero() })
+    }
+}
+
+impl<T: Float> Sdf3<T> for Cylinder<T> {
+    fn distance(&self, p: Point3<T>) -> T {
+        let (s, r) = self.local(p);
+        let half = self.height() / T::from_f64(2.0);
+        let (x, y) = (r - self.2, (s - half).abs() - half);
+        let zero = T::zero();
+        let max = |a: T, b: T| if a > b { a } else { b };
+        let (ox, oy) = (max(x, zero), max(y, zero));
+        let inside = max(x, y);
+        (ox * ox + oy * oy).sqrt() + if inside < zero { inside } else { zero }
+    }
+}
+
+impl<T: Float> Bounded3<T> for Cylinder<T> {
+    fn bounds(&self) -> Option<Aabb3<T>> {
+        let e = disk_reach(self.1.vector_from(self.0), self.2);
+        Some(Aabb3::new(self.0 - e, self.0 + e).union(Aabb3::new(self.1 - e, self.1 + e)))
+    }
+}
+
+impl<T: Float> Support3<T> for Cylinder<T> {
+    fn support(&self, d: Vector3<T>) -> Point3<T> {
+        let axis = self.1.vector_from(self.0);
+        let end = if axis.dot(d) > T::zero() { self.1 } else { self.0 };
+        end + radial(axis, d, self.2)
+    }
+}
+
+impl<T: Float> Raycast3<T> for Cylinder<T> {
+    fn raycast(&self, ray: &Ray3<T>) -> Option<Hit<T>> {
+        let h = self.height();
+        if h == T::zero() {
+            return None;
+        }
+        let n = self.1.vector_from(self.0) / h;
+        let (o, d) = (ray.origin().vector_from(self.0), ray.direction());
+        let (op, dp) = (o - n * o.dot(n), d - n * d.dot(n));
+        let r2 = self.2 * self.2;
+        let two = T::from_f64(2.0);
+        let side = quadratic_roots(dp.dot(dp), op.dot(dp) * two, op.dot(op) - r2).into_iter().filter_map(|t| {
+            let s = (o + d * t).dot(n);
+            (s >= T::zero() && s <= h).then_some((t, op + dp * t))
+        });
+        let dn = d.dot(n);
+        let caps = [(T::zero(), -n), (h, n)].into_iter().filter_map(|(s, normal)| {
+            if dn == T::zero() {
+                return None;
+            }
+            let t = (s - o.dot(n)) / dn;
+            let q = op + dp * t;
+            (q.dot(q) <= r2).then_some((t, normal))
+        });
+        nearest(ray, side.chain(caps))
+    }
+}
+
+impl<T: Float> Torus<T> {
+    /// new: Point × Vector × K × K -> Torus
+    pub fn new(center: Point3<T>, axis: Vector3<T>, major: T, minor: T) -> Self {
+        Torus(center, axis, major, minor)
+    }
+
+    /// center: &Torus -> Point
+    pub fn center(&self) -> Point3<T> {
+        self.0
+    }
+
+    /// axis: &Torus -> Vector
+    pub fn axis(&self) -> Vector3<T> {
+        self.1
+    }
+
+    /// major: &Torus -> K
+    pub fn major(&self) -> T {
+        self.2
+    }
+
+    /// minor: &Torus -> K
+    pub fn minor(&self) -> T {
+        self.3
+    }
+
+    /// contains: &Torus × Point -> bool
+    /// boundary included
+    pub fn contains(&self, p: Point3<T>) -> bool {
+        self.distance(p) <= T::zero()
+    }
+
+    /// sample: &Torus × K × K -> Point
+    /// point on the surface at turn u about the axis and turn v about the
+    /// tube, v warped so uniform (u, v) give points uniform over the area
+    pub fn sample(&self, u: T, v: T) -> Point3<T> {
+        let (big, small) = (self.2, self.3);
+        let tau = T::pi() * T::from_f64(2.0);
+        // the outer side of the tube has more area: invert
+        // (R φ + r sin φ) / (2π R) = v by Newton's method
+        let mut phi = v * tau;
+        for _ in 0..16 {
+            let f = big * phi + small * phi.sin() - v * tau * big;
+            phi = phi - f / (big + small * phi.cos());
+        }
+        self.point_at(u * tau, phi)
+    }
+
+    /// Point at angle theta about the axis and phi about the tube, phi = 0 outermost
+    fn point_at(&self, theta: T, phi: T) -> Point3<T> {
+        let (e1, e2) = Plane3::new(self.0, self.1).basis();
+        let n = self.1 / self.1.length();
+        let radial = e1 * theta.cos() + e2 * theta.sin();
+        self.0 + radial * (self.2 + self.3 * phi.cos()) + n * (self.3 * phi.sin())
+    }
+
+    /// Offset of p from the nearest point of the tube's center line
+    fn off_core(&self, p: Point3<T>) -> Vector3<T> {
+        let n = self.1 / self.1.length();
+        let q = p.vector_from(self.0);
+        let flat = q - n * q.dot(n);
+        let l = flat.length();
+        if l == T::zero() {
+            // on the axis every point of the center line is nearest
+            return q - Plane3::new(self.0, self.1).basis().0 * self.2;
+        }
+        q - flat * (self.2 / l)
+    }
+}
+
+impl<T: Float> Sdf3<T> for Torus<T> {
+    fn distance(&self, p: Point3<T>) -> T {
+        self.off_core(p).length() - self.3
+    }
+}
+
+impl<T: Float> Bounded3<T> for Torus<T> {
+    fn bounds(&self) -> Option<Aabb3<T>> {
+        let e = disk_reach(self.1, self.2);
+        let r = Vector3(self.3, self.3, self.3);
+        Some(Aabb3::new(self.0 - e - r, self.0 + e + r))
+    }
+}
+
+impl<T: Float> Raycast3<T> for Torus<T> {
+    fn raycast(&self, ray: &Ray3<T>) -> Option<Hit<T>> {
+        // with a unit direction, |p|² = t² + 2 b t + o·o and
+        // (|p|² + R² - r²)² = 4 R² (|p|² - (p·n)²) is a monic quartic in t
+        let speed = ray.direction().length();
+        if speed == T::zero() {
+            return None;
+        }
+        let d = ray.direction() / speed;
+        let n = self.1 / self.1.length();
+        let o = ray.origin().vector_from(self.0);
+        let (big, small) = (self.2, self.3);
+        let (b, oo, on, dn) = (o.dot(d), o.dot(o), o.dot(n), d.dot(n));
+        let outer = big + small;
+        let disc = b * b - oo + outer * outer;
+        if disc < T::zero() {
+            return None;
+        }
+        let (near, far) = (-b - disc.sqrt(), -b + disc.sqrt());
+        if far < T::zero() {
+            return None;
+        }
+        let g = oo + big * big - small * small;
+        let (two, four) = (T::from_f64(2.0), T::from_f64(4.0));
+        let k = four * big * big;
+        let quartic = [
+            g * g - k * (oo - on * on),
+            four * b * g - k * two * (b - on * dn),
+            four * b * b + two * g - k * (T::one() - dn * dn),
+            four * b,
+            T::one(),
+        ];
+        let lo = if near > T::zero() { near } else { T::zero() };
+        let t = *poly_roots(&quartic, lo, far).first()?;
+        let p = o + d * t;
+        nearest(ray, [(t / speed, self.off_core(self.0 + p))])
+    }
+}
+
+/// Nearest of the candidate (t, outward normal) pairs at t ≥ 0, the normal made unit
+fn nearest<T: Float>(ray: &Ray3<T>, candidates: impl IntoIterator<Item = (T, Vector3<T>)>) -> Option<Hit<T>> {
+    let (t, normal) = candidates
+        .into_iter()
+        .filter(|&(t, _)| t >= T::zero())
+        .fold(None, |best: Option<(T, Vector3<T>)>, c| match best {
+            Some(b) if b.0 <= c.0 => Some(b),
+            _ => Some(c),
+        })?;
+    let l = normal.length();
+    Some(Hit { t, point: ray.point_at(t), normal: if l > T::zero() { normal / l } else { normal } })
+}
+
+/// Half extent per coordinate of a disk of radius r normal to axis:
+/// r · sqrt(1 - n_i²) for the unit axis n
+fn disk_reach<T: Float>(axis: Vector3<T>, r: T) -> Vector3<T> {
+    let l = axis.length();
+    let reach = |a: T| {
+        let n = if l > T::zero() { a / l } else { T::zero() };
+        let k = T::one() - n * n;
+        r * if k > T::zero() { k.sqrt() } else { T::zero() }
+    };
+    Vector3(reach(axis.0), reach(axis.1), reach(axis.2))
+}
+
+/// Real roots in [lo, hi] of the polynomial with the given coefficients,
+/// lowest degree first, ascending; each found by bisection between the
+/// roots of the derivative, so double roots are missed
+fn poly_roots<T: Float>(c: &[T], lo: T, hi: T) -> Vec<T> {
+    let eval = |x: T| c.iter().rev().fold(T::zero(), |a, &k| a * x + k);
+    match c.len() {
+        0 | 1 => return Vec::new(),
+        2 => {
+            if c[1] == T::zero() {
+                return Vec::new();
+            }
+            let x = -c[0] / c[1];
+            return if x >= lo && x <= hi { vec![x] } else { Vec::new() };
+        }
+        _ => {}
+    }
+    let derivative: Vec<T> = c.iter().enumerate().skip(1).map(|(k, &a)| a * T::from_f64(k as f64)).collect();
+    let mut stops = vec![lo];
+    stops.extend(poly_roots(&derivative, lo, hi));
+    stops.push(hi);
+    let mut roots = Vec::new();
+    for w in stops.windows(2) {
+        let (mut a, mut b) = (w[0], w[1]);
+        let (fa, fb) = (eval(a), eval(b));
+        if fa == T::zero() {
+            roots.push(a);
+            continue;
+        }
+        if (fa < T::zero()) == (fb < T::zero()) || fb == T::zero() {
+            continue;
+        }
+        for _ in 0..200 {
+            let m = (a + b) / T::from_f64(2.0);
+            if m <= a || m >= b {
+                break;
+            }
+            if (eval(m) < T::zero()) == (fa < T::zero()) { a = m } else { b = m }
+        }
+        roots.push((a + b) / T::from_f64(2.0));
+    }
+    if eval(hi) == T::zero() {
+        roots.push(hi);
+    }
+    roots.dedup();
+    roots
+}
+
+/// Distance from p to the segment ab in the plane
+fn segment_distance<T: Float>(p: (T, T), a: (T, T), b: (T, T)) -> T {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let l2 = dx * dx + dy * dy;
+    let t = if l2 > T::zero() { ((p.0 - a.0) * dx + (p.1 - a.1) * dy) / l2 } else { T::zero() };
+    let t = if t < T::zero() { T::zero() } else if t > T::one() { T::one() } else { t };
+    let (x, y) = (p.0 - a.0 - dx * t, p.1 - a.1 - dy * t);
+    (x * x + y * y).sqrt()
+}
+
+/// Offset of radius r from an axis toward d, square to the axis
+fn radial<T: Float>(axis: Vector3<T>, d: Vector3<T>, r: T) -> Vector3<T> {
+    let a2 = axis.dot(axis);
+    let w = if a2 > T::zero() { d - axis * (d.dot(axis) / a2) } else { d };
+    let l = w.length();
+    if l > T::zero() { w * (r / l) } else { w }
+}
+
+/// overlap: Support × Support -> bool
+/// GJK on the difference of two convex shapes given by their support
+/// points; touching counts
+pub(crate) fn overlap<T: Float>(a: impl Fn(Vector3<T>) -> Point3<T>, b: impl Fn(Vector3<T>) -> Point3<T>) -> bool {
+    overlap_from(a, b, Vector3(T::one(), T::zero(), T::zero())).0
+}
+
+/// overlap_from: Support × Support × Vector -> (bool, Vector)
+/// GJK searching first along `start`; also the last search direction,
+/// which separates the shapes when they are apart and so makes a good start
+/// for the next query on nearby shapes
+pub(crate) fn overlap_from<T: Float>(a: impl Fn(Vector3<T>) -> Point3<T>, b: impl Fn(Vector3<T>) -> Point3<T>, start: Vector3<T>) -> (bool, Vector3<T>) {
+    let zero = T::zero();
+    let support = |d: Vector3<T>| a(d).vector_from(b(-d));
+    let mut simplex = Vec::with_capacity(4);
+    let mut d = if start.dot(start) > zero { start } else { Vector3(T::one(), zero, zero) };
+    for _ in 0..64 {
+        if d.dot(d) == zero {
+            return (true, d);
+        }
+        let p = support(d);
+        if p.dot(d) < zero {
+            return (false, d);
+        }
+        simplex.push(p);
+        if simplex.len() == 1 {
+            d = -p;
+        } else if enclose(&mut simplex, &mut d) {
+            return (true, d);
+        }
+    }
+    (true, d)
+}
+
+/// Reduces the simplex to the feature nearest the origin, newest point last,
+/// and points d at the origin from it; true if the origin is enclosed
+fn enclose<T: Float>(simplex: &mut Vec<Vector3<T>>, d: &mut Vector3<T>) -> bool {
+    let zero = T::zero();
+    let a = simplex[simplex.len() - 1];
+    let ao = -a;
+    match simplex.len() {
+        2 => {
+            let ab = simplex[0] - a;
+            if ab.dot(ao) > zero {
+                *d = ab.cross(ao).cross(ab);
+            } else {
+                *simplex = vec![a];
+                *d = ao;
+            }
+            false
+        }
+        3 => {
+            let (b, c) = (simplex[1], simplex[0]);
+            let (ab, ac) = (b - a, c - a);
+            let abc = ab.cross(ac);
+            if abc.cross(ac).dot(ao) > zero {
+                if ac.dot(ao) > zero {
+                    *simplex = vec![c, a];
+                    *d = ac.cross(ao).cross(ac);
+                    return false;
+                }
+                *simplex = vec![b, a];
+                return enclose(simplex, d);
+            }
+            if ab.cross(abc).dot(ao) > zero {
+                *simplex = vec![b, a];
+                return enclose(simplex, d);
+            }
+            let side = abc.dot(ao);
+            if side == zero {
+                return abc.dot(abc) > zero;
+            }
+            *d = if side > zero { abc } else { -abc };
+            false
+        }
+        _ => {
+            let (b, c, e) = (simplex[2], simplex[1], simplex[0]);
+            for (p, q, opposite) in [(b, c, e), (c, e, b), (e, b, c)] {
+                let n = (p - a).cross(q - a);
+                let n = if n.dot(opposite - a) > zero { -n } else { n };
+                if n.dot(ao) > zero {
+                    *simplex = vec![q, p, a];
+                    return enclose(simplex, d);
+                }
+            }
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cone() -> Cone<f64> {
+        Cone::new(Point3(0.0, 0.0, 0.0), Vector3(0.0, 0.0, 4.0), std::f64::consts::FRAC_PI_4)
+    }
+
+    #[test]
+    fn containment() {
+        let c = cone();
+        assert!((c.radius() - 4.0).abs() < 1e-12);
+        assert!(c.contains(Point3(1.0, 1.0, 3.0)) && c.contains(Point3(0.0, 0.0, 0.0)));
+        assert!(!c.contains(Point3(2.0, 0.0, 1.0)) && !c.contains(Point3(0.0, 0.0, 4.5)));
+        assert!(c.contains_sphere(&Sphere::new(Point3(0.0, 0.0, 2.5), 1.0)));
+        assert!(!c.contains_sphere(&Sphere::new(Point3(0.0, 0.0, 2.5), 2.0)));
+        assert!(c.intersects_sphere(&Sphere::new(Point3(0.0, 0.0, -1.0), 1.0)));
+        assert!(!c.intersects_sphere(&Sphere::new(Point3(3.0, 0.0, 0.0), 2.0)));
+        assert!((c.distance(Point3(3.0, 0.0, 0.0)) - 1.5 * 2f64.sqrt()).abs() < 1e-12);
+        assert!((c.distance(Point3(0.0, 0.0, 3.5)) + 0.5).abs() < 1e-12);
+        let b = c.bounds().unwrap();
+        assert!(b.min().distance(Point3(-4.0, -4.0, 0.0)) < 1e-12 && b.max().distance(Point3(4.0, 4.0, 4.0)) < 1e-12);
+    }
+
+    #[test]
+    fn overlaps_aabb() {
+        let c = cone();
+        let unit = |x: f64, y: f64, z: f64| Aabb3::new(Point3(x, y, z), Point3(x + 1.0, y + 1.0, z + 1.0));
+        assert!(c.intersects_aabb(&unit(-0.5, -0.5, -0.5)));
+        assert!(c.intersects_aabb(&unit(3.5, -0.5, 3.5)));
+        assert!(c.intersects_aabb(&Aabb3::new(Point3(-10.0, -10.0, 1.0), Point3(10.0, 10.0, 2.0))));
+        // inside the bounds but off the slant side
+        assert!(!c.intersects_aabb(&unit(3.0, 0.0, 0.5)));
+        assert!(!c.intersects_aabb(&unit(3.0, 3.0, 3.5)));
+        assert!(!c.intersects_aabb(&unit(0.0, 0.0, 4.5)));
+        let tilted = Cone::new(Point3(1.0, 1.0, 1.0), Vector3(3.0, -2.0, 1.0), 0.3);
+        for i in 0..6 {
+            for j in 0..6 {
+                let b = unit(i as f64 - 1.0, j as f64 - 4.0, 1.0);
+                let hit = tilted.intersects_aabb(&b);
+                let near = tilted.distance(Point3(i as f64 - 0.5, j as f64 - 3.5, 1.5)) <= 0.5;
+                let far = tilted.distance(Point3(i as f64 - 0.5, j as f64 - 3.5, 1.5)) > 3f64.sqrt() / 2.0;
+                assert!(!near || hit);
+                assert!(!far || !hit);
+            }
+        }
+    }
+
+    #[test]
+    fn cylinder() {
+        let c = Cylinder::new(Point3(1.0, 0.0, 0.0), Point3(1.0, 0.0, 4.0), 1.0);
+        assert!(c.contains(Point3(1.5, 0.5, 4.0)) && !c.contains(Point3(2.0, 0.5, 2.0)));
+        assert_eq!(c.distance(Point3(4.0, 0.0, 2.0)), 2.0);
+        assert_eq!(c.distance(Point3(1.0, 0.0, 3.5)), -0.5);
+        let side = c.raycast(&Ray3::new(Point3(5.0, 0.0, 1.0), Vector3(-2.0, 0.0, 0.0))).unwrap();
+        assert!((side.t - 1.5).abs() < 1e-12 && (side.normal - Vector3(1.0, 0.0, 0.0)).length() < 1e-12);
+        let cap = c.raycast(&Ray3::new(Point3(1.5, 0.0, 9.0), Vector3(0.0, 0.0, -1.0))).unwrap();
+        assert!((cap.t - 5.0).abs() < 1e-12 && (cap.normal - Vector3(0.0, 0.0, 1.0)).length() < 1e-12);
+        let out = c.raycast(&Ray3::new(Point3(1.0, 0.0, 2.0), Vector3(0.0, 0.0, -1.0))).unwrap();
+        assert!((out.t - 2.0).abs() < 1e-12 && (out.normal - Vector3(0.0, 0.0, -1.0)).length() < 1e-12);
+        assert!(c.raycast(&Ray3::new(Point3(5.0, 0.0, 5.0), Vector3(-1.0, 0.0, 0.0))).is_none());
+        let mut caps = 0;
+        for i in 0..20 {
+            for j in 0..20 {
+                let p = c.sample((i as f64 + 0.5) / 20.0, (j as f64 + 0.5) / 20.0);
+                assert!(c.distance(p).abs() < 1e-12);
+                if p.2 == 0.0 || p.2 == 4.0 {
+                    caps += 1;
+                }
+            }
+        }
+        assert_eq!(caps, 80);
+    }
+
+    #[test]
+    fn torus() {
+        let t = Torus::new(Point3(0.0, 0.0, 0.0), Vector3(0.0, 0.0, 2.0), 3.0, 1.0);
+        assert!(t.contains(Point3(0.0, 3.5, 0.5)) && !t.contains(Point3(0.0, 0.0, 0.0)));
+        assert_eq!(t.distance(Point3(6.0, 0.0, 0.0)), 2.0);
+        let hit = t.raycast(&Ray3::new(Point3(10.0, 0.0, 0.0), Vector3(-2.0, 0.0, 0.0))).unwrap();
+        assert!((hit.t - 3.0).abs() < 1e-9 && (hit.normal - Vector3(1.0, 0.0, 0.0)).length() < 1e-9);
+        let top = t.raycast(&Ray3::new(Point3(3.0, 0.0, 5.0), Vector3(0.0, 0.0, -1.0))).unwrap();
+        assert!((top.point.2 - 1.0).abs() < 1e-9 && (top.normal - Vector3(0.0, 0.0, 1.0)).length() < 1e-9);
+        let inside = t.raycast(&Ray3::new(Point3(3.0, 0.0, 0.0), Vector3(1.0, 0.0, 0.0))).unwrap();
+        assert!((inside.t - 1.0).abs() < 1e-9);
+        assert!(t.raycast(&Ray3::new(Point3(0.0, 0.0, 5.0), Vector3(0.0, 0.0, -1.0))).is_none());
+        assert!(t.raycast(&Ray3::new(Point3(10.0, 0.0, 0.0), Vector3(1.0, 0.0, 0.0))).is_none());
+        let mut outer = 0;
+        for i in 0..20 {
+            for j in 0..20 {
+                let p = t.sample((i as f64 + 0.5) / 20.0, (j as f64 + 0.5) / 20.0);
+                assert!(t.distance(p).abs() < 1e-12);
+                if p.0.hypot(p.1) > 3.0 {
+                    outer += 1;
+                }
+            }
+        }
+        // the outer half holds (π + 2 r / R) / 2π of the area
+        assert!((outer as f64 / 400.0 - (0.5 + 1.0 / (3.0 * std::f64::consts::PI))).abs() < 0.03);
+        let s = Sphere::new(Point3(0.0, 0.0, 0.0), 2.0);
+        let c = cone();
+        let side = c.raycast(&Ray3::new(Point3(5.0, 0.0, 2.0), Vector3(-1.0, 0.0, 0.0))).unwrap();
+        assert!((side.t - 3.0).abs() < 1e-12 && (side.normal - Vector3(1.0, 0.0, -1.0) / 2f64.sqrt()).length() < 1e-12);
+        let base = c.raycast(&Ray3::new(Point3(1.0, 0.0, 9.0), Vector3(0.0, 0.0, -1.0))).unwrap();
+        assert!((base.t - 5.0).abs() < 1e-12 && base.normal == Vector3(0.0, 0.0, 1.0));
+        assert!(c.raycast(&Ray3::new(Point3(0.0, 0.0, -1.0), Vector3(1.0, 0.0, -1.0))).is_none());
+        let hit = s.raycast(&Ray3::new(Point3(0.0, -5.0, 0.0), Vector3(0.0, 1.0, 0.0))).unwrap();
+        assert!(hit.t == 3.0 && hit.normal == Vector3(0.0, -1.0, 0.0));
+    }
+}
@@ -0,0 +1,218 @@
+//! Operation statistics
+//!
+//! With the `stats` feature, heavy algorithms report counters and their
+//! running time to the innermost sink that `collect` installed on the
+//! current thread; without it the reports compile to nothing. Operations
+//! and the counters they report:
+//!
+//! - `bvh build`: `nodes`, `leaves`
+//! - `bvh pick`: `nodes visited`, `shape tests`
+//! - `arrangement`: `segment tests`, `crossings`
+//! - `fill`: `segments`, `faces`
+//! - `union`: `merges`, `disjoint merges`
+//! - `ear clipping`: `ear tests`
+//! - `delaunay`: `points`, `circle tests`
+//! - `earcut`: `holes`, `ear tests`
+//! - `tetrahedralization`: `points`, `sphere tests`
+//! - `lod`: `collapses`, `levels`
+
+#[cfg(feature = "stats")]
+use std::any::Any;
+#[cfg(feature = "stats")]
+use std::cell::RefCell;
+#[cfg(feature = "stats")]
+use std::collections::BTreeMap;
+#[cfg(feature = "stats")]
+use std::time::{ Duration, Instant };
+
+/// Receiver of the statistics of operations run under `collect`
+#[cfg(feature = "stats")]
+pub trait Stats: Any {
+    /// counter: &mut Stats × operation × counter × u64 -> ()
+    /// one run's count
+    fn counter(&mut self, operation: &'static str, counter: &'static str, amount: u64);
+
+    /// elapsed: &mut Stats × operation × Duration -> ()
+    /// one run's wall-clock time
+    fn elapsed(&mut self, operation: &'static str, time: Duration);
+}
+
+/// Totals of every counter and time reported
+#[cfg(feature = "stats")]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Counters {
+    /// summed by operation and counter
+    pub counts: BTreeMap<(&'static str, &'static str), u64>,
+    /// runs and time summed by operation
+    pub times: BTreeMap<&'static str, (u64, Duration)>,
+}
+
+#[cfg(feature = "stats")]
+impl Counters {
+    /// new: () -> Counters
+    pub fn new() -> Self {
+        Counters::default()
+    }
+
+    /// get: &Counters × operation × counter -> u64
+    /// zero if never reported
+    pub fn get(&self, operation: &str, counter: &str) -> u64 {
+        self.counts.get(&(operation, counter)).copied().unwrap_or(0)
+    }
+
+    /// runs: &Counters × operation -> u64
+    pub fn runs(&self, operation: &str) -> u64 {
+        self.times.get(operation).map_or(0, |t| t.0)
+    }
+}
+
+#[cfg(feature = "stats")]
+impl Stats for Counters {
+    fn counter(&mut self, operation: &'static str, counter: &'static str, amount: u64) {
+        *self.counts.entry((operation, counter)).or_insert(0) += amount;
+    }
+
+    fn elapsed(&mut self, operation: &'static str, time: Duration) {
+        let t = self.times.entry(operation).or_insert((0, Duration::ZERO));
+        *t = (t.0 + 1, t.1 + time);
+    }
+}
+
+#[cfg(feature = "stats")]
+thread_local! {
+    static SINKS: RefCell<Vec<Box<dyn Stats>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// collect: Stats × (() -> R) -> (R, Stats)
+/// runs `f` with `sink` receiving the reports made on this thread, nested
+/// calls taking them over until they return
+#[cfg(feature = "stats")]
+pub fn collect<S: Stats, R>(sink: S, f: impl FnOnce() -> R) -> (R, S) {
+    /// Takes the sink off again if `f` unwinds
+    struct Installed;
+
+    impl Drop for Installed {
+        fn drop(&mut self) {
+            if std::thread::panicking() {
+                SINKS.with(|s| s.borrow_mut().pop());
+            }
+        }
+    }
+
+    SINKS.with(|s| s.borrow_mut().push(Box::new(sink)));
+    let installed = Installed;
+    let result = f();
+    std::mem::forget(installed);
+    let sink: Box<dyn Any> = SINKS.with(|s| s.borrow_mut().pop()).expect("sink installed by collect");
+    (result, *sink.downcast::<S>().expect("sink installed by collect"))
+}
+
+#[cfg(feature = "stats")]
+fn report(event: impl FnOnce(&mut dyn Stats)) {
+    SINKS.with(|s| {
+        if let Some(sink) = s.borrow_mut().last_mut() {
+            event(sink.as_mut());
+        }
+    });
+}
+
+/// Reports a counter of one run
+#[inline]
+pub(crate) fn count(operation: &'static str, counter: &'static str, amount: usize) {
+    #[cfg(feature = "stats")]
+    report(|s| s.counter(operation, counter, amount as u64));
+    #[cfg(not(feature = "stats"))]
+    let _ = (operation, counter, amount);
+}
+
+/// Running time of one run, reported when dropped
+pub(crate) struct Timer {
+    #[cfg(feature = "stats")]
+    started: (&'static str, Instant),
+}
+
+/// Starts timing a run
+#[inline]
+pub(crate) fn time(operation: &'static str) -> Timer {
+    #[cfg(feature = "stats")]
+    return Timer { started: (operation, Instant::now()) };
+    #[cfg(not(feature = "stats"))]
+    {
+        let _ = operation;
+        Timer {}
+    }
+}
+
+#[cfg(feature = "stats")]
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let (operation, start) = self.started;
+        report(|s| s.elapsed(operation, start.elapsed()));
+    }
+}
+
+#[cfg(all(test, feature = "stats"))]
+mod tests {
+    use super::*;
+    use crate::{ Aabb3, Point2, Point3, Polygon2, Ray3, Scene3, Shape3, TriMesh, Triangulation, Vector3 };
+
+    #[test]
+    fn counts_operations() {
+        let entries: Vec<_> = (0..16).map(|k| {
+            let c = Point3(k as f64 * 3.0, 0.0, 0.0);
+            (Shape3::Box(Aabb3::new(c, c + Vector3(1.0, 1.0, 1.0))), k)
+        }).collect();
+        let (scene, stats) = collect(Counters::new(), || Scene3::new(entries));
+        // 16 entries, two to a leaf: 8 leaves under 7 splits
+        assert_eq!((stats.get("bvh build", "nodes"), stats.get("bvh build", "leaves")), (15, 8));
+        assert_eq!(stats.runs("bvh build"), 1);
+        let ray = Ray3::new(Point3(-5.0, 0.5, 0.5), Vector3(1.0, 0.0, 0.0));
+        let (_, stats) = collect(Counters::new(), || (0..3).for_each(|_| { scene.pick(&ray); }));
+        assert_eq!(stats.runs("bvh pick"), 3);
+        assert!(stats.get("bvh pick", "shape tests") < 3 * 16);
+
+        let square = |x: f64| Polygon2::new(vec![Point2(x, 0.0), Point2(x + 2.0, 0.0), Point2(x + 2.0, 2.0), Point2(x, 2.0)]);
+        let (_, stats) = collect(Counters::new(), || crate::polygon::union_all(&[square(0.0), square(1.0), square(10.0)]));
+        assert_eq!(stats.get("union", "merges"), 1);
+        assert_eq!(stats.get("union", "disjoint merges"), 1);
+        assert_eq!(stats.runs("fill"), 1);
+        assert!(stats.get("arrangement", "crossings") >= 2);
+
+        let framed = Polygon2::with_holes(
+            vec![Point2(0.0, 0.0), Point2(4.0, 0.0), Point2(4.0, 4.0), Point2(0.0, 4.0)],
+            vec![vec![Point2(1.0, 1.0), Point2(1.0, 3.0), Point2(3.0, 3.0), Point2(3.0, 1.0)]],
+        );
+        let (triangles, stats) = collect(Counters::new(), || framed.triangulate_with(Triangulation::Earcut));
+        assert_eq!((stats.runs("earcut"), stats.get("earcut", "holes")), (1, 1));
+        assert!(stats.get("earcut", "ear tests") >= triangles.len() as u64);
+
+        let corners: Vec<_> = (0..8).map(|i| Point3((i & 1) as f64, (i >> 1 & 1) as f64, (i >> 2) as f64 + 0.1 * i as f64)).collect();
+        let (_, stats) = collect(Counters::new(), || crate::tetra::delaunay(&corners));
+        assert_eq!(stats.get("tetrahedralization", "points"), 8);
+        assert!(stats.get("tetrahedralization", "sphere tests") >= 8);
+
+        let octahedron = TriMesh::new(
+            vec![Point3(1.0, 0.0, 0.0), Point3(-1.0, 0.0, 0.0), Point3(0.0, 1.0, 0.0), Point3(0.0, -1.0, 0.0), Point3(0.0, 0.0, 1.0), Point3(0.0, 0.0, -1.0)],
+            vec![[0, 2, 4], [2, 1, 4], [1, 3, 4], [3, 0, 4], [2, 0, 5], [1, 2, 5], [3, 1, 5], [0, 3, 5]],
+        );
+        let (lods, stats) = collect(Counters::new(), || octahedron.generate_lods(&[1.0, 0.5]));
+        assert_eq!((stats.runs("lod"), stats.get("lod", "levels")), (1, 2));
+        assert!(stats.get("lod", "collapses") > 0 && lods.mesh(1).triangles().len() < 8);
+    }
+
+    #[test]
+    fn nesting_and_unwinding() {
+        let points: Vec<Point2<f64>> = (0..20).map(|k| Point2((k * 7 % 20) as f64, (k * k % 13) as f64)).collect();
+        let ((inner, outer_seen), outer) = collect(Counters::new(), || {
+            let (_, inner) = collect(Counters::new(), || crate::delaunay::triangulate(&points));
+            (inner, crate::delaunay::triangulate(&points[..10]).len())
+        });
+        assert!(outer_seen > 0);
+        assert_eq!((inner.get("delaunay", "points"), outer.get("delaunay", "points")), (20, 10));
+        let unwound = std::panic::catch_unwind(|| collect(Counters::new(), || panic!("inside")));
+        assert!(unwound.is_err());
+        // the unwound sink is gone: reports reach the one installed here
+        let (_, after) = collect(Counters::new(), || crate::delaunay::triangulate(&points));
+        assert_eq!(after.runs("delaunay"), 1);
+    }
+}
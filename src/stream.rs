@@ -0,0 +1,544 @@
+//! Streaming readers for large inputs
+//!
+//! Each reader pulls from an `io` reader and yields one item at a time, so
+//! memory stays bounded by the longest line or record rather than the
+//! file: OBJ and PLY meshes as vertices and triangles in file order (faces
+//! fanned from their first corner), WKB as one geometry per record.
+//! Malformed input ends the stream with an `InvalidData` error.
+
+use std::collections::VecDeque;
+use std::io::{ self, BufRead, Read };
+use std::marker::PhantomData;
+
+use crate::{ Float, Geometry2, GeometryCollection2, MultiPoint2, MultiPolygon2, MultiPolyline2, Point2, Point3, Polygon2, Polyline2 };
+
+/// Piece of a mesh as it is read
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MeshItem<T> {
+    /// next vertex, numbered from zero in the order read
+    Vertex(Point3<T>),
+    /// corners by vertex number
+    Triangle([usize; 3]),
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Triangles fanned from the first corner of a face
+fn fan(face: &[usize], out: &mut VecDeque<[usize; 3]>) {
+    out.extend((2..face.len()).map(|k| [face[0], face[k - 1], face[k]]));
+}
+
+/// Wavefront OBJ reader: `v` and `f` lines, the rest skipped
+pub struct ObjReader<R, T> {
+    input: R,
+    line: String,
+    vertices: usize,
+    pending: VecDeque<[usize; 3]>,
+    done: bool,
+    scalar: PhantomData<T>,
+}
+
+impl<R: BufRead, T: Float> ObjReader<R, T> {
+    /// new: BufRead -> ObjReader
+    pub fn new(input: R) -> Self {
+        ObjReader { input, line: String::new(), vertices: 0, pending: VecDeque::new(), done: false, scalar: PhantomData }
+    }
+
+    /// Next item of the next line that has one
+    fn advance(&mut self) -> io::Result<Option<MeshItem<T>>> {
+        loop {
+            if let Some(t) = self.pending.pop_front() {
+                return Ok(Some(MeshItem::Triangle(t)));
+            }
+            self.line.clear();
+            if self.input.read_line(&mut self.line)? == 0 {
+                return Ok(None);
+            }
+            let mut tokens = self.line.split_whitespace();
+            match tokens.next() {
+                Some("v") => {
+                    let mut c = [T::zero(); 3];
+                    for x in &mut c {
+                        *x = number(tokens.next())?;
+                    }
+                    self.vertices += 1;
+                    return Ok(Some(MeshItem::Vertex(Point3(c[0], c[1], c[2]))));
+                }
+                Some("f") => {
+                    // `v`, `v/t`, `v/t/n` or `v//n`, negative counting back
+                    let face = tokens
+                        .map(|t| {
+                            let i: i64 = t.split('/').next().and_then(|v| v.parse().ok()).ok_or_else(|| invalid("bad OBJ face index"))?;
+                            let k = if i < 0 { self.vertices as i64 + i } else { i - 1 };
+                            usize::try_from(k).map_err(|_| invalid("OBJ face index out of range"))
+                        })
+                        .collect::<io::Result<Vec<usize>>>()?;
+                    fan(&face, &mut self.pending);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<R: BufRead, T: Float> Iterator for ObjReader<R, T> {
+    type Item = io::Result<MeshItem<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.advance().transpose();
+        self.done = !matches!(item, Some(Ok(_)));
+        item
+    }
+}
+
+fn number<T: Float>(token: Option<&str>) -> io::Result<T> {
+    token.and_then(|t| t.parse::<f64>().ok()).map(T::from_f64).ok_or_else(|| invalid("bad number"))
+}
+
+/// PLY scalar type
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> io::Result<Self> {
+        Ok(match name {
+            "char" | "int8" => Scalar::I8,
+            "uchar" | "uint8" => Scalar::U8,
+            "short" | "int16" => Scalar::I16,
+            "ushort" | "uint16" => Scalar::U16,
+            "int" | "int32" => Scalar::I32,
+            "uint" | "uint32" => Scalar::U32,
+            "float" | "float32" => Scalar::F32,
+            "double" | "float64" => Scalar::F64,
+            _ => return Err(invalid("unknown PLY property type")),
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+
+    fn decode(self, b: &[u8], big: bool) -> f64 {
+        macro_rules! get {
+            ($t:ty) => {{
+                let a = b.try_into().unwrap();
+                (if big { <$t>::from_be_bytes(a) } else { <$t>::from_le_bytes(a) }) as f64
+            }};
+        }
+        match self {
+            Scalar::I8 => b[0] as i8 as f64,
+            Scalar::U8 => b[0] as f64,
+            Scalar::I16 => get!(i16),
+            Scalar::U16 => get!(u16),
+            Scalar::I32 => get!(i32),
+            Scalar::U32 => get!(u32),
+            Scalar::F32 => get!(f32),
+            Scalar::F64 => get!(f64),
+        }
+    }
+}
+
+/// PLY element declared in the header
+#[derive(Debug)]
+struct Element {
+    name: String,
+    count: u64,
+    /// type, list count type for list properties, name
+    properties: Vec<(Scalar, Option<Scalar>, String)>,
+}
+
+/// Stanford PLY reader, ASCII or binary: `vertex` x, y, z and the first
+/// list of each `face`, other elements and properties skipped
+pub struct PlyReader<R, T> {
+    input: R,
+    binary: Option<bool>,
+    elements: Vec<Element>,
+    /// element being read and instances of it left
+    at: usize,
+    left: u64,
+    line: String,
+    pending: VecDeque<[usize; 3]>,
+    done: bool,
+    scalar: PhantomData<T>,
+}
+
+impl<R: BufRead, T: Float> PlyReader<R, T> {
+    /// new: BufRead -> io::Result<PlyReader>
+    /// reads the header
+    pub fn new(mut input: R) -> io::Result<Self> {
+        let mut line = String::new();
+        let mut header = |line: &mut String| -> io::Result<()> {
+            line.clear();
+            match input.read_line(line)? {
+                0 => Err(invalid("PLY header ends early")),
+                _ => Ok(()),
+            }
+        };
+        header(&mut line)?;
+        if line.trim() != "ply" {
+            return Err(invalid("not a PLY file"));
+        }
+        let (mut binary, mut elements) = (None, Vec::<Element>::new());
+        let mut format = false;
+        loop {
+            header(&mut line)?;
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            match tokens.as_slice() {
+                ["format", f, _] => {
+                    binary = match *f {
+                        "ascii" => None,
+                        "binary_little_endian" => Some(false),
+                        "binary_big_endian" => Some(true),
+                        _ => return Err(invalid("unknown PLY format")),
+                    };
+                    format = true;
+                }
+                ["element", name, count] => elements.push(Element {
+                    name: name.to_string(),
+                    count: count.parse().map_err(|_| invalid("bad PLY element count"))?,
+                    properties: Vec::new(),
+                }),
+                ["property", "list", n, t, name] => {
+                    let e = elements.last_mut().ok_or_else(|| invalid("PLY property outside an element"))?;
+                    e.properties.push((Scalar::parse(t)?, Some(Scalar::parse(n)?), name.to_string()));
+                }
+                ["property", t, name] => {
+                    let e = elements.last_mut().ok_or_else(|| invalid("PLY property outside an element"))?;
+                    e.properties.push((Scalar::parse(t)?, None, name.to_string()));
+                }
+                ["end_header"] => break,
+                _ => {}
+            }
+        }
+        if !format {
+            return Err(invalid("PLY header without a format"));
+        }
+        let left = elements.first().map_or(0, |e| e.count);
+        Ok(PlyReader { input, binary, elements, at: 0, left, line, pending: VecDeque::new(), done: false, scalar: PhantomData })
+    }
+
+    /// Values of the next instance of the current element, lists flattened
+    /// behind their lengths
+    fn instance(&mut self) -> io::Result<Vec<f64>> {
+        let element = &self.elements[self.at];
+        let mut values = Vec::with_capacity(element.properties.len());
+        match self.binary {
+            None => {
+                self.line.clear();
+                if self.input.read_line(&mut self.line)? == 0 {
+                    return Err(invalid("PLY data ends early"));
+                }
+                for t in self.line.split_whitespace() {
+                    values.push(t.parse::<f64>().map_err(|_| invalid("bad number"))?);
+                }
+            }
+            Some(big) => {
+                let mut buffer = [0u8; 8];
+                let mut read = |input: &mut R, t: Scalar| -> io::Result<f64> {
+                    input.read_exact(&mut buffer[..t.size()])?;
+                    Ok(t.decode(&buffer[..t.size()], big))
+                };
+                for &(t, list, _) in &element.properties {
+                    let n = match list {
+                        Some(c) => {
+                            let n = read(&mut self.input, c)?;
+                            values.push(n);
+                            n as usize
+                        }
+                        None => 1,
+                    };
+                    for _ in 0..n {
+                        values.push(read(&mut self.input, t)?);
+                    }
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    fn advance(&mut self) -> io::Result<Option<MeshItem<T>>> {
+        loop {
+            if let Some(t) = self.pending.pop_front() {
+                return Ok(Some(MeshItem::Triangle(t)));
+            }
+            while self.left == 0 {
+                self.at += 1;
+                match self.elements.get(self.at) {
+                    Some(e) => self.left = e.count,
+                    None => return Ok(None),
+                }
+            }
+            self.left -= 1;
+            let values = self.instance()?;
+            let element = &self.elements[self.at];
+            // offset of each property's values, walking past list lengths
+            let mut offset = 0;
+            let mut found = [None; 3];
+            let mut face = None;
+            for (_, list, name) in &element.properties {
+                match list {
+                    Some(_) => {
+                        let n = *values.get(offset).ok_or_else(|| invalid("PLY instance too short"))? as usize;
+                        let items = values.get(offset + 1..offset + 1 + n).ok_or_else(|| invalid("PLY instance too short"))?;
+                        if face.is_none() {
+                            face = Some(items.iter().map(|&i| i as usize).collect::<Vec<_>>());
+                        }
+                        offset += 1 + n;
+                    }
+                    None => {
+                        if let Some(k) = ["x", "y", "z"].iter().position(|a| a == name) {
+                            found[k] = values.get(offset).copied();
+                        }
+                        offset += 1;
+                    }
+                }
+            }
+            match element.name.as_str() {
+                "vertex" => {
+                    let [Some(x), Some(y), Some(z)] = found else {
+                        return Err(invalid("PLY vertex without x, y and z"));
+                    };
+                    return Ok(Some(MeshItem::Vertex(Point3(T::from_f64(x), T::from_f64(y), T::from_f64(z)))));
+                }
+                "face" => fan(&face.ok_or_else(|| invalid("PLY face without indices"))?, &mut self.pending),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<R: BufRead, T: Float> Iterator for PlyReader<R, T> {
+    type Item = io::Result<MeshItem<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.advance().transpose();
+        self.done = !matches!(item, Some(Ok(_)));
+        item
+    }
+}
+
+/// Well-known binary reader: consecutive records, ISO or EWKB, each
+/// yielded as a geometry; z and m are dropped, SRIDs skipped
+pub struct WkbReader<R, T> {
+    input: R,
+    done: bool,
+    scalar: PhantomData<T>,
+}
+
+impl<R: Read, T: Float> WkbReader<R, T> {
+    /// new: Read -> WkbReader
+    pub fn new(input: R) -> Self {
+        WkbReader { input, done: false, scalar: PhantomData }
+    }
+
+    /// The next record, None at a clean end of input
+    fn record(&mut self) -> io::Result<Option<Geometry2<T>>> {
+        let mut order = [0u8; 1];
+        if self.input.read(&mut order)? == 0 {
+            return Ok(None);
+        }
+        self.geometry(order[0]).map(Some)
+    }
+
+    fn geometry(&mut self, order: u8) -> io::Result<Geometry2<T>> {
+        let big = match order {
+            0 => true,
+            1 => false,
+            _ => return Err(invalid("bad WKB byte order")),
+        };
+        let code = self.u32(big)?;
+        if code & 0x2000_0000 != 0 {
+            self.u32(big)?;
+        }
+        let kind = (code & 0xffff) % 1000;
+        let extra = usize::from(code & 0x8000_0000 != 0) + usize::from(code & 0x4000_0000 != 0)
+            + match (code & 0xffff) / 1000 {
+                1 | 2 => 1,
+                3 => 2,
+                _ => 0,
+            };
+        let points = |r: &mut Self, n: u32| (0..n).map(|_| r.point(big, extra)).collect::<io::Result<Vec<_>>>();
+        Ok(match kind {
+            1 => Geometry2::Point(self.point(big, extra)?),
+            2 => {
+                let n = self.u32(big)?;
+                Geometry2::Polyline(Polyline2::new(points(self, n)?))
+            }
+            3 => Geometry2::Polygon(self.polygon(big, extra)?),
+            4..=7 => {
+                let n = self.u32(big)?;
+                let members = (0..n)
+                    .map(|_| {
+                        let mut order = [0u8; 1];
+                        self.input.read_exact(&mut order)?;
+                        self.geometry(order[0])
+                    })
+                    .collect::<io::Result<Vec<_>>>()?;
+                let mismatch = || invalid("WKB member of the wrong kind");
+                match kind {
+                    4 => Geometry2::MultiPoint(MultiPoint2::new(
+                        members.into_iter().map(|g| if let Geometry2::Point(p) = g { Ok(p) } else { Err(mismatch()) }).collect::<io::Result<_>>()?,
+                    )),
+                    5 => Geometry2::MultiPolyline(MultiPolyline2::new(
+                        members.into_iter().map(|g| if let Geometry2::Polyline(l) = g { Ok(l) } else { Err(mismatch()) }).collect::<io::Result<_>>()?,
+                    )),
+                    6 => Geometry2::MultiPolygon(MultiPolygon2::new(
+                        members.into_iter().map(|g| if let Geometry2::Polygon(p) = g { Ok(p) } else { Err(mismatch()) }).collect::<io::Result<_>>()?,
+                    )),
+                    _ => Geometry2::Collection(GeometryCollection2::new(members)),
+                }
+            }
+            _ => return Err(invalid("unknown WKB geometry type")),
+        })
+    }
+
+    /// Rings without their closing repeat of the first vertex
+    fn polygon(&mut self, big: bool, extra: usize) -> io::Result<Polygon2<T>> {
+        let n = self.u32(big)?;
+        let mut rings = (0..n)
+            .map(|_| {
+                let k = self.u32(big)?;
+                let mut ring = (0..k).map(|_| self.point(big, extra)).collect::<io::Result<Vec<_>>>()?;
+                if ring.len() > 1 && ring.first() == ring.last() {
+                    ring.pop();
+                }
+                Ok(ring)
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        if rings.is_empty() {
+            return Ok(Polygon2::new(Vec::new()));
+        }
+        let exterior = rings.remove(0);
+        Ok(Polygon2::with_holes(exterior, rings))
+    }
+
+    fn u32(&mut self, big: bool) -> io::Result<u32> {
+        let mut b = [0u8; 4];
+        self.input.read_exact(&mut b)?;
+        Ok(if big { u32::from_be_bytes(b) } else { u32::from_le_bytes(b) })
+    }
+
+    fn f64(&mut self, big: bool) -> io::Result<f64> {
+        let mut b = [0u8; 8];
+        self.input.read_exact(&mut b)?;
+        Ok(if big { f64::from_be_bytes(b) } else { f64::from_le_bytes(b) })
+    }
+
+    fn point(&mut self, big: bool, extra: usize) -> io::Result<Point2<T>> {
+        let (x, y) = (self.f64(big)?, self.f64(big)?);
+        for _ in 0..extra {
+            self.f64(big)?;
+        }
+        Ok(Point2(T::from_f64(x), T::from_f64(y)))
+    }
+}
+
+impl<R: Read, T: Float> Iterator for WkbReader<R, T> {
+    type Item = io::Result<Geometry2<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.record().transpose();
+        self.done = !matches!(item, Some(Ok(_)));
+        item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::debug::Scene;
+
+    #[test]
+    fn meshes() {
+        let obj = "# quad and triangle\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvn 0 0 1\nf 1//1 2//1 3//1 4//1\nv 0 0 1\nf -1 1/1 2\n";
+        let items: Vec<MeshItem<f64>> = ObjReader::new(obj.as_bytes()).collect::<io::Result<_>>().unwrap();
+        assert_eq!(items.len(), 8);
+        assert_eq!(items[4], MeshItem::Triangle([0, 1, 2]));
+        assert_eq!(items[5], MeshItem::Triangle([0, 2, 3]));
+        assert_eq!(items[7], MeshItem::Triangle([4, 0, 1]));
+        assert!(ObjReader::<_, f64>::new("f 0 1 2\n".as_bytes()).next().unwrap().is_err());
+
+        // what the debug dump writes reads back, edges skipped
+        let mut scene = Scene::new();
+        scene.mesh("m", &crate::TriMesh::new(vec![Point3(0.0, 0.0, 0.0), Point3(1.0, 0.0, 0.0), Point3(0.0, 1.0, 0.0)], vec![[0, 1, 2]]));
+        scene.segment3("s", Point3(0.0, 0.0, 0.0), Point3(0.0, 0.0, 1.0));
+        let ply = scene.to_ply();
+        let items: Vec<MeshItem<f64>> = PlyReader::new(ply.as_bytes()).unwrap().collect::<io::Result<_>>().unwrap();
+        assert_eq!(items.len(), 6);
+        assert_eq!(items[5], MeshItem::Triangle([0, 1, 2]));
+
+        let mut binary = b"ply\nformat binary_little_endian 1.0\nelement vertex 3\nproperty double x\nproperty double y\nproperty double z\nproperty uchar red\n\
+element face 1\nproperty list uchar int vertex_indices\nend_header\n".to_vec();
+        for k in 0..3 {
+            for c in [k as f64, 1.0, 2.0] {
+                binary.extend(c.to_le_bytes());
+            }
+            binary.push(255);
+        }
+        binary.push(3);
+        for i in [2i32, 1, 0] {
+            binary.extend(i.to_le_bytes());
+        }
+        let items: Vec<MeshItem<f32>> = PlyReader::new(binary.as_slice()).unwrap().collect::<io::Result<_>>().unwrap();
+        assert_eq!(items[2], MeshItem::Vertex(Point3(2.0, 1.0, 2.0)));
+        assert_eq!(items[3], MeshItem::Triangle([2, 1, 0]));
+        assert!(PlyReader::<_, f32>::new(&binary[..binary.len() - 1]).unwrap().last().unwrap().is_err());
+    }
+
+    #[test]
+    fn wkb_records() {
+        let mut wkb = Vec::new();
+        // little-endian point, then a big-endian polygon with z
+        wkb.push(1);
+        wkb.extend(1u32.to_le_bytes());
+        wkb.extend(3.0f64.to_le_bytes());
+        wkb.extend(4.0f64.to_le_bytes());
+        wkb.push(0);
+        wkb.extend(1003u32.to_be_bytes());
+        wkb.extend(1u32.to_be_bytes());
+        wkb.extend(4u32.to_be_bytes());
+        for (x, y) in [(0.0, 0.0), (2.0, 0.0), (0.0, 2.0), (0.0, 0.0)] {
+            for c in [x, y, 9.0f64] {
+                wkb.extend(c.to_be_bytes());
+            }
+        }
+        // multipoint of one point
+        wkb.push(1);
+        wkb.extend(4u32.to_le_bytes());
+        wkb.extend(1u32.to_le_bytes());
+        wkb.push(1);
+        wkb.extend(1u32.to_le_bytes());
+        wkb.extend(5.0f64.to_le_bytes());
+        wkb.extend(6.0f64.to_le_bytes());
+        let records: Vec<Geometry2<f64>> = WkbReader::new(wkb.as_slice()).collect::<io::Result<_>>().unwrap();
+        assert_eq!(records[0], Geometry2::Point(Point2(3.0, 4.0)));
+        assert_eq!(records[1], Geometry2::Polygon(Polygon2::new(vec![Point2(0.0, 0.0), Point2(2.0, 0.0), Point2(0.0, 2.0)])));
+        assert_eq!(records[2], Geometry2::MultiPoint(MultiPoint2::new(vec![Point2(5.0, 6.0)])));
+        assert!(WkbReader::<_, f64>::new(&wkb[..wkb.len() - 3]).last().unwrap().is_err());
+    }
+}
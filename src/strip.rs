@@ -0,0 +1,300 @@
+//! Triangle strips, fans and vertex cache friendly triangle order
+//!
+//! Strips keep winding: triangle k of a strip s is (s[k], s[k+1], s[k+2])
+//! for even k and (s[k+1], s[k], s[k+2]) for odd k, as GPUs draw them.
+//! Fans share their first index: triangle k is (f[0], f[k+1], f[k+2]).
+
+use std::collections::HashMap;
+
+use crate::TriMesh;
+
+/// Grows a run from a first triangle's corners, returning the run and the
+/// triangles it covers
+type Grow = fn(&Edges, [usize; 3]) -> (Vec<usize>, Vec<usize>);
+
+/// Unused triangles by directed edge
+struct Edges<'a> {
+    triangles: &'a [[usize; 3]],
+    by_edge: HashMap<(usize, usize), Vec<usize>>,
+    used: Vec<bool>,
+}
+
+impl<'a> Edges<'a> {
+    fn new(triangles: &'a [[usize; 3]]) -> Self {
+        let mut by_edge: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (t, &[a, b, c]) in triangles.iter().enumerate() {
+            for e in [(a, b), (b, c), (c, a)] {
+                by_edge.entry(e).or_default().push(t);
+            }
+        }
+        Edges { triangles, by_edge, used: vec![false; triangles.len()] }
+    }
+
+    /// Unused triangle running a -> b, and its third corner
+    fn next(&self, a: usize, b: usize, taken: &[usize]) -> Option<(usize, usize)> {
+        let ts = self.by_edge.get(&(a, b))?;
+        let t = *ts.iter().find(|&&t| !self.used[t] && !taken.contains(&t))?;
+        let [x, y, z] = self.triangles[t];
+        let third = if (x, y) == (a, b) { z } else if (y, z) == (a, b) { x } else { y };
+        Some((t, third))
+    }
+
+    /// Longest run from each turn of triangle t, grown by `grow`
+    fn best(&self, t: usize, grow: Grow) -> (Vec<usize>, Vec<usize>) {
+        let [a, b, c] = self.triangles[t];
+        [[a, b, c], [b, c, a], [c, a, b]]
+            .into_iter()
+            .map(|start| grow(self, start))
+            .reduce(|m, r| if r.1.len() > m.1.len() { r } else { m })
+            .unwrap()
+    }
+}
+
+/// Strip from a first triangle and the triangles it covers
+fn grow_strip(edges: &Edges, start: [usize; 3]) -> (Vec<usize>, Vec<usize>) {
+    let first = edges.by_edge[&(start[0], start[1])].iter().copied().find(|&t| !edges.used[t]).unwrap();
+    let (mut strip, mut taken) = (start.to_vec(), vec![first]);
+    loop {
+        let (a, b) = (strip[strip.len() - 2], strip[strip.len() - 1]);
+        // the next triangle is drawn (a, b, c) at an even place, (b, a, c) at an odd one
+        let edge = if (strip.len() - 2) % 2 == 0 { (a, b) } else { (b, a) };
+        match edges.next(edge.0, edge.1, &taken) {
+            Some((t, c)) => {
+                strip.push(c);
+                taken.push(t);
+            }
+            None => return (strip, taken),
+        }
+    }
+}
+
+/// Fan from a first triangle and the triangles it covers
+fn grow_fan(edges: &Edges, start: [usize; 3]) -> (Vec<usize>, Vec<usize>) {
+    let first = edges.by_edge[&(start[0], start[1])].iter().copied().find(|&t| !edges.used[t]).unwrap();
+    let (mut fan, mut taken) = (start.to_vec(), vec![first]);
+    while let Some((t, c)) = edges.next(fan[0], fan[fan.len() - 1], &taken) {
+        fan.push(c);
+        taken.push(t);
+    }
+    (fan, taken)
+}
+
+/// Greedy cover of the triangles by runs, each started from the first
+/// triangle left and grown from whichever corner goes furthest
+fn cover(triangles: &[[usize; 3]], grow: Grow) -> Vec<Vec<usize>> {
+    let mut edges = Edges::new(triangles);
+    let mut runs = Vec::new();
+    for t in 0..triangles.len() {
+        if edges.used[t] {
+            continue;
+        }
+        let (run, taken) = edges.best(t, grow);
+        for t in taken {
+            edges.used[t] = true;
+        }
+        runs.push(run);
+    }
+    runs
+}
+
+/// triangle_strips: [[usize; 3]] -> [[usize]]
+/// strips covering every triangle once, winding kept; greedy, each grown
+/// forward from the first triangle not yet covered
+pub fn triangle_strips(triangles: &[[usize; 3]]) -> Vec<Vec<usize>> {
+    cover(triangles, grow_strip)
+}
+
+/// triangle_fans: [[usize; 3]] -> [[usize]]
+/// fans covering every triangle once, winding kept; greedy as strips
+pub fn triangle_fans(triangles: &[[usize; 3]]) -> Vec<Vec<usize>> {
+    cover(triangles, grow_fan)
+}
+
+/// strip_triangles: [usize] -> [[usize; 3]]
+/// the triangles a strip draws, degenerate ones skipped
+pub fn strip_triangles(strip: &[usize]) -> Vec<[usize; 3]> {
+    strip.windows(3).enumerate()
+        .map(|(k, w)| if k % 2 == 0 { [w[0], w[1], w[2]] } else { [w[1], w[0], w[2]] })
+        .filter(|&[a, b, c]| a != b && b != c && c != a)
+        .collect()
+}
+
+/// Size of the vertex cache Forsyth's scores are tuned for
+const CACHE: usize = 32;
+
+/// Forsyth's vertex score: recently used vertices rank high, the last
+/// triangle's three a little lower so strips do not win outright, and
+/// vertices with few triangles left get a boost to finish them off
+fn vertex_score(position: Option<usize>, remaining: usize) -> f64 {
+    if remaining == 0 {
+        return -1.0;
+    }
+    let cached = match position {
+        Some(p) if p < 3 => 0.75,
+        Some(p) => (1.0 - (p - 3) as f64 / (CACHE - 3) as f64).powf(1.5),
+        None => 0.0,
+    };
+    cached + 2.0 / (remaining as f64).sqrt()
+}
+
+/// vertex_cache_order: [[usize; 3]] × usize -> [usize]
+/// triangle order for a post-transform vertex cache (Forsyth's linear-speed
+/// optimizer), as indices into `triangles`; indices must be below
+/// `vertex_count`
+pub fn vertex_cache_order(triangles: &[[usize; 3]], vertex_count: usize) -> Vec<usize> {
+    let mut around: Vec<Vec<usize>> = vec![Vec::new(); vertex_count];
+    for (t, tri) in triangles.iter().enumerate() {
+        for &v in tri {
+            around[v].push(t);
+        }
+    }
+    let mut remaining: Vec<usize> = around.iter().map(Vec::len).collect();
+    let mut position: Vec<Option<usize>> = vec![None; vertex_count];
+    let mut score: Vec<f64> = (0..vertex_count).map(|v| vertex_score(None, remaining[v])).collect();
+    let triangle_score = |score: &[f64], t: usize| triangles[t].iter().map(|&v| score[v]).sum::<f64>();
+    let mut added = vec![false; triangles.len()];
+    let mut cache: Vec<usize> = Vec::with_capacity(CACHE + 3);
+    let mut order = Vec::with_capacity(triangles.len());
+    let mut scan = 0;
+    while order.len() < triangles.len() {
+        // best triangle around the cache, else the first one left
+        let near = cache.iter().flat_map(|&v| around[v].iter().copied()).filter(|&t| !added[t]);
+        let best = near.map(|t| (t, triangle_score(&score, t))).reduce(|m, c| if c.1 > m.1 { c } else { m });
+        let t = match best {
+            Some((t, _)) => t,
+            None => {
+                while added[scan] {
+                    scan += 1;
+                }
+                scan
+            }
+        };
+        added[t] = true;
+        order.push(t);
+        for &v in &triangles[t] {
+            remaining[v] -= 1;
+            cache.retain(|&c| c != v);
+        }
+        let evicted: Vec<usize> = triangles[t].iter().rev().copied().chain(cache.drain(..)).collect();
+        let mut seen = Vec::with_capacity(evicted.len());
+        for v in evicted {
+            if !seen.contains(&v) {
+                seen.push(v);
+            }
+        }
+        for (p, &v) in seen.iter().enumerate() {
+            position[v] = if p < CACHE { Some(p) } else { None };
+            score[v] = vertex_score(position[v], remaining[v]);
+        }
+        seen.truncate(CACHE);
+        cache = seen;
+    }
+    order
+}
+
+/// average_cache_miss_ratio: [[usize; 3]] × usize -> f64
+/// vertices transformed per triangle through a FIFO cache of the given
+/// size: 3 with no reuse, near 0.5 at best on large regular meshes
+pub fn average_cache_miss_ratio(triangles: &[[usize; 3]], cache_size: usize) -> f64 {
+    if triangles.is_empty() {
+        return 0.0;
+    }
+    let mut fifo = std::collections::VecDeque::with_capacity(cache_size + 1);
+    let mut misses = 0;
+    for &v in triangles.iter().flatten() {
+        if !fifo.contains(&v) {
+            misses += 1;
+            fifo.push_back(v);
+            if fifo.len() > cache_size {
+                fifo.pop_front();
+            }
+        }
+    }
+    misses as f64 / triangles.len() as f64
+}
+
+impl<T: Clone> TriMesh<T> {
+    /// strips: &Mesh -> [[usize]]
+    /// as `triangle_strips`
+    pub fn strips(&self) -> Vec<Vec<usize>> {
+        triangle_strips(self.triangles())
+    }
+
+    /// fans: &Mesh -> [[usize]]
+    /// as `triangle_fans`
+    pub fn fans(&self) -> Vec<Vec<usize>> {
+        triangle_fans(self.triangles())
+    }
+
+    /// optimize_vertex_cache: &Mesh -> Mesh
+    /// the triangles, with their uvs and normals, in `vertex_cache_order`
+    pub fn optimize_vertex_cache(&self) -> Self {
+        let order = vertex_cache_order(self.triangles(), self.vertices().len());
+        let triangles = order.iter().map(|&t| self.triangles()[t]).collect();
+        let mut mesh = TriMesh::new(self.vertices().to_vec(), triangles);
+        if let Some(uvs) = self.uvs() {
+            mesh = mesh.with_uvs(order.iter().map(|&t| uvs[t].clone()).collect());
+        }
+        if let Some(normals) = self.normals() {
+            mesh = mesh.with_normals(order.iter().map(|&t| normals[t].clone()).collect());
+        }
+        mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point3;
+
+    /// n × n quads of two triangles each, counter-clockwise seen from +z
+    fn grid(n: usize) -> TriMesh<f64> {
+        let vertices = (0..=n).flat_map(|j| (0..=n).map(move |i| Point3(i as f64, j as f64, 0.0))).collect();
+        let at = |i: usize, j: usize| j * (n + 1) + i;
+        let triangles = (0..n).flat_map(|j| (0..n).flat_map(move |i| {
+            [[at(i, j), at(i + 1, j), at(i + 1, j + 1)], [at(i, j), at(i + 1, j + 1), at(i, j + 1)]]
+        })).collect();
+        TriMesh::new(vertices, triangles)
+    }
+
+    /// Triangles as corner cycles starting from the least index
+    fn canonical(ts: &[[usize; 3]]) -> Vec<[usize; 3]> {
+        let mut ts: Vec<[usize; 3]> = ts.iter().map(|&[a, b, c]| {
+            if a < b && a < c { [a, b, c] } else if b < c { [b, c, a] } else { [c, a, b] }
+        }).collect();
+        ts.sort_unstable();
+        ts
+    }
+
+    #[test]
+    fn strips_and_fans_cover_once() {
+        let mesh = grid(6);
+        let expected = canonical(mesh.triangles());
+        let strips = mesh.strips();
+        let drawn: Vec<[usize; 3]> = strips.iter().flat_map(|s| strip_triangles(s)).collect();
+        assert_eq!(canonical(&drawn), expected);
+        assert!(strips.len() < mesh.triangles().len() / 4);
+        let fans = mesh.fans();
+        let drawn: Vec<[usize; 3]> = fans.iter().flat_map(|f| (1..f.len() - 1).map(|k| [f[0], f[k], f[k + 1]])).collect();
+        assert_eq!(canonical(&drawn), expected);
+        assert!(fans.len() < mesh.triangles().len() / 2);
+        assert!(triangle_strips(&[]).is_empty());
+    }
+
+    #[test]
+    fn forsyth_order() {
+        let mesh = grid(24);
+        // scrambled triangle order
+        let mut triangles = mesh.triangles().to_vec();
+        let mut seed = 5u64;
+        for i in (1..triangles.len()).rev() {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            triangles.swap(i, (seed >> 33) as usize % (i + 1));
+        }
+        let scrambled = TriMesh::new(mesh.vertices().to_vec(), triangles);
+        let optimized = scrambled.optimize_vertex_cache();
+        assert_eq!(canonical(optimized.triangles()), canonical(mesh.triangles()));
+        let (before, after) = (average_cache_miss_ratio(scrambled.triangles(), 16), average_cache_miss_ratio(optimized.triangles(), 16));
+        assert!(before > 1.5 && after < 0.8, "{before} -> {after}");
+    }
+}
@@ -0,0 +1,236 @@
+//! Stroke outlines of polylines
+
+use crate::{ Float, Point2, Polygon2, Polyline2, Vector2 };
+
+/// Shape of the outer corner where two segments meet
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum JoinStyle<T> {
+    /// sharp corner, beveled once the miter length exceeds `limit` × half width
+    Miter(T),
+    /// circular corner
+    Round,
+    /// corner cut straight across
+    Bevel,
+}
+
+/// Shape of the open ends
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CapStyle {
+    /// flush with the end point
+    Butt,
+    /// half circle around the end point
+    Round,
+    /// half width square past the end point
+    Square,
+}
+
+/// Angular step of round joins and caps
+const ROUND_STEPS_PER_HALF_TURN: f64 = 16.0;
+
+/// stroke: &Polyline × K × Join × Cap -> Polygon
+/// counter-clockwise outline of the polyline drawn with the given width;
+/// tight turns against short segments make the outline self-overlap,
+/// so fill it with the nonzero rule
+pub fn stroke<T: Float>(line: &Polyline2<T>, width: T, join: JoinStyle<T>, cap: CapStyle) -> Polygon2<T> {
+    let mut points = line.vertices().to_vec();
+    points.dedup();
+    if points.len() < 2 || width <= T::zero() {
+        return Polygon2::new(Vec::new());
+    }
+    let h = width / T::from_f64(2.0);
+    let mut outline = Vec::new();
+    side(&points, h, join, cap, &mut outline);
+    points.reverse();
+    side(&points, h, join, cap, &mut outline);
+    Polygon2::new(outline)
+}
+
+/// dash: &Polyline × [K] × K -> [Polyline]
+/// pieces of the polyline under the on/off `pattern` of arc lengths,
+/// starting `offset` into the pattern; odd patterns are repeated twice
+/// as in SVG, and an empty or zero-length pattern leaves the line whole
+pub fn dash<T: Float>(line: &Polyline2<T>, pattern: &[T], offset: T) -> Vec<Polyline2<T>> {
+    let points = line.vertices();
+    let zero = T::zero();
+    let total = pattern.iter().fold(zero, |s, &l| s + l);
+    if points.len() < 2 || pattern.iter().any(|&l| l < zero) || total <= zero {
+        return vec![line.clone()];
+    }
+    let pattern: Vec<T> = pattern.iter().chain(if pattern.len() % 2 == 1 { pattern } else { &[] }).copied().collect();
+    let total = pattern.iter().fold(zero, |s, &l| s + l);
+    let mut phase = offset - (offset / total).floor() * total;
+    let mut i = 0;
+    while phase >= pattern[i] {
+        phase = phase - pattern[i];
+        i = (i + 1) % pattern.len();
+    }
+    let mut left = pattern[i] - phase;
+    let mut dashes = Vec::new();
+    let mut current = if i % 2 == 0 { vec![points[0]] } else { Vec::new() };
+    let emit = |current: &mut Vec<Point2<T>>, dashes: &mut Vec<Polyline2<T>>| {
+        if current.windows(2).any(|w| w[0] != w[1]) {
+            dashes.push(Polyline2::new(std::mem::take(current)));
+        }
+        current.clear();
+    };
+    for w in points.windows(2) {
+        let (a, d) = (w[0], w[1].vector_from(w[0]));
+        let s = d.length();
+        let mut t = zero;
+        while s - t > left {
+            t = t + left;
+            let p = a + d * (t / s);
+            current.push(p);
+            if i % 2 == 0 {
+                emit(&mut current, &mut dashes);
+            }
+            i = (i + 1) % pattern.len();
+            left = pattern[i];
+        }
+        left = left - (s - t);
+        if i % 2 == 0 {
+            current.push(w[1]);
+        }
+    }
+    if i % 2 == 0 {
+        emit(&mut current, &mut dashes);
+    }
+    dashes
+}
+
+/// Right-hand offset of the path with its joins, then the cap around the last point
+fn side<T: Float>(points: &[Point2<T>], h: T, join: JoinStyle<T>, cap: CapStyle, out: &mut Vec<Point2<T>>) {
+    let n = points.len();
+    let one = T::one();
+    let dirs: Vec<_> = points.windows(2).map(|w| {
+        let d = w[1].vector_from(w[0]);
+        d / d.length()
+    }).collect();
+    let right = |d: Vector2<T>| Vector2(d.1, -d.0);
+    out.push(points[0] + right(dirs[0]) * h);
+    for k in 1..n - 1 {
+        let (p, d0, d1) = (points[k], dirs[k - 1], dirs[k]);
+        let (n0, n1) = (right(d0), right(d1));
+        let turn = d0.0 * d1.1 - d0.1 * d1.0;
+        let c = n0.dot(n1);
+        let degenerate = one + c <= T::epsilon();
+        let miter = |out: &mut Vec<_>| out.push(p + (n0 + n1) * (h / (one + c)));
+        let bevel = |out: &mut Vec<_>| {
+            out.push(p + n0 * h);
+            out.push(p + n1 * h);
+        };
+        if turn <= T::zero() {
+            if degenerate { bevel(out) } else { miter(out) }
+        } else {
+            match join {
+                JoinStyle::Miter(limit) if !degenerate && T::from_f64(2.0) / (one + c) <= limit * limit => miter(out),
+                JoinStyle::Round => arc(p, h, n0, turn.atan2(c), out),
+                _ => bevel(out),
+            }
+        }
+    }
+    let (p, d) = (points[n - 1], dirs[n - 2]);
+    let nr = right(d);
+    match cap {
+        CapStyle::Butt => out.push(p + nr * h),
+        CapStyle::Square => {
+            out.push(p + nr * h);
+            out.push(p + (nr + d) * h);
+            out.push(p + (d - nr) * h);
+        }
+        CapStyle::Round => {
+            // the far end of the arc starts the other side
+            arc(p, h, nr, T::pi(), out);
+            out.pop();
+        }
+    }
+}
+
+/// Points of the arc around c from direction n through the signed angle `sweep`, both ends included
+fn arc<T: Float>(c: Point2<T>, r: T, n: Vector2<T>, sweep: T, out: &mut Vec<Point2<T>>) {
+    let a0 = n.1.atan2(n.0);
+    let steps = (sweep.abs() / T::pi() * T::from_f64(ROUND_STEPS_PER_HALF_TURN)).floor() + T::one();
+    let mut i = T::zero();
+    while i <= steps {
+        let a = a0 + sweep * i / steps;
+        out.push(c + Vector2(a.cos(), a.sin()) * r);
+        i = i + T::one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn near(a: f64, b: f64, eps: f64) -> bool {
+        (a - b).abs() < eps
+    }
+
+    fn segment() -> Polyline2<f64> {
+        Polyline2::new(vec![Point2(0.0, 0.0), Point2(4.0, 0.0)])
+    }
+
+    #[test]
+    fn caps() {
+        let butt = stroke(&segment(), 2.0, JoinStyle::Bevel, CapStyle::Butt);
+        assert_eq!(butt.vertices().len(), 4);
+        assert!(near(butt.signed_area(), 8.0, 1e-12));
+        let square = stroke(&segment(), 2.0, JoinStyle::Bevel, CapStyle::Square);
+        assert!(near(square.signed_area(), 12.0, 1e-12));
+        let round = stroke(&segment(), 2.0, JoinStyle::Bevel, CapStyle::Round);
+        assert!(near(round.signed_area(), 8.0 + PI, 0.05));
+        assert!(round.signed_area() < 8.0 + PI);
+        let v = round.vertices();
+        assert!(v.windows(2).all(|w| w[0] != w[1]) && v[0] != v[v.len() - 1]);
+    }
+
+    #[test]
+    fn joins() {
+        let l = Polyline2::new(vec![Point2(0.0, 0.0), Point2(4.0, 0.0), Point2(4.0, 4.0)]);
+        let miter = stroke(&l, 2.0, JoinStyle::Miter(4.0), CapStyle::Butt);
+        // two 4 × 2 arms overlapping in a 1 × 1 square, plus the 1 × 1 outer corner
+        assert!(near(miter.signed_area(), 16.0, 1e-12));
+        let bevel = stroke(&l, 2.0, JoinStyle::Bevel, CapStyle::Butt);
+        assert!(near(bevel.signed_area(), 15.5, 1e-12));
+        let round = stroke(&l, 2.0, JoinStyle::Round, CapStyle::Butt);
+        assert!(near(round.signed_area(), 15.0 + PI / 4.0, 0.01));
+        let limited = stroke(&l, 2.0, JoinStyle::Miter(1.2), CapStyle::Butt);
+        assert!(near(limited.signed_area(), 15.5, 1e-12));
+    }
+
+    fn lengths(dashes: &[Polyline2<f64>]) -> Vec<f64> {
+        dashes.iter().map(|d| d.vertices().windows(2).map(|w| w[0].distance(w[1])).sum()).collect()
+    }
+
+    #[test]
+    fn dash_pattern() {
+        let d = dash(&segment(), &[1.0, 0.5], 0.0);
+        assert_eq!(lengths(&d), vec![1.0, 1.0, 1.0]);
+        assert_eq!(d[1].vertices(), &[Point2(1.5, 0.0), Point2(2.5, 0.0)]);
+        let d = dash(&segment(), &[1.0, 0.5], 1.25);
+        assert_eq!(d[0].vertices()[0], Point2(0.25, 0.0));
+        assert_eq!(lengths(&d), vec![1.0, 1.0, 0.75]);
+        let d = dash(&segment(), &[1.0, 0.5], -0.5);
+        assert_eq!(lengths(&d), vec![1.0, 1.0, 0.5]);
+        assert_eq!(d[0].vertices()[0], Point2(0.5, 0.0));
+    }
+
+    #[test]
+    fn dash_corners() {
+        let l = Polyline2::new(vec![Point2(0.0, 0.0), Point2(2.0, 0.0), Point2(2.0, 2.0)]);
+        let d = dash(&l, &[3.0], 0.0);
+        // odd pattern: 3 on, 3 off
+        assert_eq!(d.len(), 1);
+        assert_eq!(d[0].vertices(), &[Point2(0.0, 0.0), Point2(2.0, 0.0), Point2(2.0, 1.0)]);
+        assert_eq!(dash(&l, &[], 0.0), vec![l.clone()]);
+        assert_eq!(dash(&l, &[0.0, 0.0], 0.0), vec![l.clone()]);
+    }
+
+    #[test]
+    fn degenerate() {
+        let dot = Polyline2::new(vec![Point2(1.0, 1.0), Point2(1.0, 1.0)]);
+        assert!(stroke(&dot, 2.0, JoinStyle::Round, CapStyle::Round).vertices().is_empty());
+        assert!(stroke(&segment(), 0.0, JoinStyle::Round, CapStyle::Round).vertices().is_empty());
+    }
+}
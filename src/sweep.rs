@@ -0,0 +1,206 @@
+//! Solids swept from 2d profiles
+
+use crate::{ Float, Point2, Point3, Polygon2, Polyline2, Polyline3, Rotor3, TriMesh, Vector2, Vector3 };
+
+/// extrude: &Polygon × K -> Mesh
+/// closed prism between z = 0 and z = height, with both ends capped
+pub fn extrude<T: Float>(polygon: &Polygon2<T>, height: T) -> TriMesh<T> {
+    let (zero, one) = (T::zero(), T::one());
+    let (x, y) = (Vector3(one, zero, zero), Vector3(zero, one, zero));
+    let (bottom, top) = (Point3(zero, zero, zero), Point3(zero, zero, height));
+    if height < zero {
+        sweep(polygon, &[(top, x, y), (bottom, x, y)])
+    } else {
+        sweep(polygon, &[(bottom, x, y), (top, x, y)])
+    }
+}
+
+/// extrude_along: &Polygon × &Polyline -> Mesh
+/// profile swept along the path with rotation-minimizing frames,
+/// capped at both ends; at joints the profile is kept perpendicular
+/// to the averaged tangent
+pub fn extrude_along<T: Float>(polygon: &Polygon2<T>, path: &Polyline3<T>) -> TriMesh<T> {
+    let points = path.vertices();
+    let n = points.len();
+    if n < 2 {
+        return TriMesh::new(Vec::new(), Vec::new());
+    }
+    let unit = |v: Vector3<T>| v / v.length();
+    let dirs: Vec<_> = points.windows(2).map(|w| unit(w[1].vector_from(w[0]))).collect();
+    let tangents: Vec<_> = (0..n).map(|i| {
+        if i == 0 {
+            dirs[0]
+        } else if i == n - 1 {
+            dirs[n - 2]
+        } else {
+            let t = dirs[i - 1] + dirs[i];
+            if t.length() == T::zero() { dirs[i] } else { unit(t) }
+        }
+    }).collect();
+    let mut r = unit(tangents[0].cross(least_aligned_axis(tangents[0])));
+    let mut frames = Vec::with_capacity(n);
+    for i in 0..n {
+        if i > 0 {
+            // double reflection (Wang et al. 2008)
+            let two = T::one() + T::one();
+            let v1 = points[i].vector_from(points[i - 1]);
+            let c1 = v1.dot(v1);
+            let rl = r - v1 * (two / c1 * v1.dot(r));
+            let tl = tangents[i - 1] - v1 * (two / c1 * v1.dot(tangents[i - 1]));
+            let v2 = tangents[i] - tl;
+            let c2 = v2.dot(v2);
+            r = if c2 == T::zero() { rl } else { rl - v2 * (two / c2 * v2.dot(rl)) };
+        }
+        frames.push((points[i], r, tangents[i].cross(r)));
+    }
+    sweep(polygon, &frames)
+}
+
+/// revolve: &Polyline × Vector × K × usize -> Mesh
+/// surface of revolution of the profile (drawn in the z = 0 plane) about
+/// the axis through the origin in direction `axis`, turned by `angle` in
+/// `segments` steps; a full turn closes the surface, and profile points on
+/// the axis are shared between steps. Faces point outward for profiles
+/// running counter-clockwise about the region they bound.
+pub fn revolve<T: Float>(profile: &Polyline2<T>, axis: Vector2<T>, angle: T, segments: usize) -> TriMesh<T> {
+    let points = profile.vertices();
+    let segments = segments.max(1);
+    let a = axis / axis.length();
+    let (a3, zero) = (Vector3(a.0, a.1, T::zero()), T::zero());
+    let closed = angle.abs() >= T::pi() * T::from_f64(2.0);
+    let rings = if closed { segments } else { segments + 1 };
+    let mut vertices = Vec::new();
+    let mut index = vec![Vec::with_capacity(points.len()); rings];
+    for &Point2(x, y) in points {
+        let on_axis = a.1 * x - a.0 * y == zero;
+        for (k, ring) in index.iter_mut().enumerate() {
+            if on_axis && k > 0 {
+                ring.push(vertices.len() - 1);
+                continue;
+            }
+            let theta = angle * T::from_f64(k as f64) / T::from_f64(segments as f64);
+            let Vector3(u, v, w) = Rotor3::exp(a3 * theta).rotate(Vector3(x, y, zero));
+            ring.push(vertices.len());
+            vertices.push(Point3(u, v, w));
+        }
+    }
+    let mut triangles = Vec::new();
+    for k in 0..segments {
+        let (ra, rb) = (&index[k], &index[(k + 1) % rings]);
+        for i in 1..points.len() {
+            for t in [[ra[i - 1], rb[i - 1], rb[i]], [ra[i - 1], rb[i], ra[i]]] {
+                if t[0] != t[1] && t[1] != t[2] && t[2] != t[0] {
+                    triangles.push(if angle < zero { [t[0], t[2], t[1]] } else { t });
+                }
+            }
+        }
+    }
+    TriMesh::new(vertices, triangles)
+}
+
+/// Profile copies placed in each (origin, x, y) frame, joined by side quads;
+/// the ends are capped with the profile triangulation
+fn sweep<T: Float>(polygon: &Polygon2<T>, frames: &[(Point3<T>, Vector3<T>, Vector3<T>)]) -> TriMesh<T> {
+    let mut profile = polygon.vertices().to_vec();
+    if polygon.signed_area() < T::zero() {
+        profile.reverse();
+    }
+    let m = profile.len();
+    let cap = Polygon2::new(profile.clone()).triangulate();
+    let mut vertices = Vec::with_capacity(m * frames.len());
+    for &(o, x, y) in frames {
+        vertices.extend(profile.iter().map(|&Point2(u, v)| o + x * u + y * v));
+    }
+    let mut triangles = Vec::new();
+    for k in 1..frames.len() {
+        let (a, b) = ((k - 1) * m, k * m);
+        for i in 0..m {
+            let j = (i + 1) % m;
+            triangles.push([a + i, a + j, b + j]);
+            triangles.push([a + i, b + j, b + i]);
+        }
+    }
+    let last = (frames.len() - 1) * m;
+    triangles.extend(cap.iter().map(|&[i, j, k]| [k, j, i]));
+    triangles.extend(cap.iter().map(|&[i, j, k]| [last + i, last + j, last + k]));
+    TriMesh::new(vertices, triangles)
+}
+
+fn least_aligned_axis<T: Float>(v: Vector3<T>) -> Vector3<T> {
+    let (zero, one) = (T::zero(), T::one());
+    let Vector3(x, y, z) = v;
+    if x.abs() <= y.abs() && x.abs() <= z.abs() {
+        Vector3(one, zero, zero)
+    } else if y.abs() <= z.abs() {
+        Vector3(zero, one, zero)
+    } else {
+        Vector3(zero, zero, one)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn near(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    fn square() -> Polygon2<f64> {
+        Polygon2::new(vec![Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(1.0, 1.0), Point2(0.0, 1.0)])
+    }
+
+    #[test]
+    fn extrude_prism() {
+        let m = extrude(&square(), 3.0);
+        assert_eq!(m.vertices().len(), 8);
+        assert_eq!(m.triangles().len(), 12);
+        assert!(near(m.volume(), 3.0));
+        let c = m.centroid();
+        assert!(near(c.0, 0.5) && near(c.1, 0.5) && near(c.2, 1.5));
+        let cw = Polygon2::new(square().vertices().iter().rev().copied().collect());
+        assert!(near(extrude(&cw, 3.0).volume(), 3.0));
+        assert!(near(extrude(&square(), -2.0).volume(), 2.0));
+    }
+
+    #[test]
+    fn extrude_along_path() {
+        let path = Polyline3::new(vec![Point3(0.0, 0.0, 0.0), Point3(0.0, 0.0, 2.0), Point3(0.0, 0.0, 5.0)]);
+        let m = extrude_along(&square(), &path);
+        assert_eq!(m.vertices().len(), 12);
+        assert!(near(m.volume(), 5.0));
+        let bent = Polyline3::new(vec![
+            Point3(0.0, 0.0, 0.0), Point3(4.0, 0.0, 0.0), Point3(4.0, 4.0, 0.0), Point3(4.0, 4.0, 4.0),
+        ]);
+        assert!(extrude_along(&square(), &bent).volume() > 0.0);
+    }
+
+    #[test]
+    fn revolve_cylinder() {
+        use std::f64::consts::PI;
+        let profile = Polyline2::new(vec![
+            Point2(0.0, 0.0), Point2(1.0, 0.0), Point2(1.0, 2.0), Point2(0.0, 2.0),
+        ]);
+        let m = revolve(&profile, Vector2(0.0, 1.0), 2.0 * PI, 64);
+        // 2 welded axis points and 2 rings of 64
+        assert_eq!(m.vertices().len(), 2 + 2 * 64);
+        assert_eq!(m.triangles().len(), 64 * 4);
+        let prism = 64.0 * (2.0 * PI / 64.0).sin() / 2.0 * 2.0;
+        assert!(near(m.volume(), prism));
+        let c = m.centroid();
+        assert!(near(c.0, 0.0) && near(c.1, 1.0) && near(c.2, 0.0));
+        assert!(near(revolve(&profile, Vector2(0.0, 1.0), -2.0 * PI, 64).volume(), prism));
+    }
+
+    #[test]
+    fn revolve_partial() {
+        use std::f64::consts::PI;
+        let profile = Polyline2::new(vec![Point2(1.0, 0.0), Point2(1.0, 1.0)]);
+        let m = revolve(&profile, Vector2(0.0, 1.0), PI / 2.0, 4);
+        assert_eq!(m.vertices().len(), 10);
+        assert_eq!(m.triangles().len(), 8);
+        for p in m.vertices() {
+            assert!(near(p.0 * p.0 + p.2 * p.2, 1.0));
+        }
+    }
+}
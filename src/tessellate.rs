@@ -0,0 +1,268 @@
+//! Tessellation of solid primitives
+
+use crate::{ Aabb3, Cone, Cylinder, Float, Plane3, Point2, Point3, Sphere, Torus, TriMesh, Vector3 };
+
+/// Solids that can be approximated by a closed triangle mesh
+pub trait Tessellate3<T: Float> {
+    /// tessellate: &Solid × usize -> Mesh
+    /// outward-wound mesh with corner normals and uvs, `resolution` segments
+    /// (at least 3) making a full turn around curved surfaces; uvs run along
+    /// the turn and across it, flat faces get their own unit square
+    fn tessellate(&self, resolution: usize) -> TriMesh<T>;
+}
+
+/// Mesh under construction, attributes per triangle corner
+struct Builder<T> {
+    vertices: Vec<Point3<T>>,
+    triangles: Vec<[usize; 3]>,
+    normals: Vec<[Vector3<T>; 3]>,
+    uvs: Vec<[Point2<T>; 3]>,
+}
+
+impl<T: Float> Builder<T> {
+    fn new() -> Self {
+        Builder { vertices: Vec::new(), triangles: Vec::new(), normals: Vec::new(), uvs: Vec::new() }
+    }
+
+    fn face(&mut self, t: [usize; 3], normals: [Vector3<T>; 3], uvs: [Point2<T>; 3]) {
+        self.triangles.push(t);
+        self.normals.push(normals);
+        self.uvs.push(uvs);
+    }
+
+    fn build(self) -> TriMesh<T> {
+        TriMesh::new(self.vertices, self.triangles).with_normals(self.normals).with_uvs(self.uvs)
+    }
+}
+
+/// i / n
+fn frac<T: Float>(i: usize, n: usize) -> T {
+    T::from_f64(i as f64 / n as f64)
+}
+
+/// Unit vector i / n of a turn around, in the plane of the basis
+fn around<T: Float>((e1, e2): (Vector3<T>, Vector3<T>), i: usize, n: usize) -> Vector3<T> {
+    let a = T::from_f64(2.0 * std::f64::consts::PI * i as f64 / n as f64);
+    e1 * a.cos() + e2 * a.sin()
+}
+
+/// Texture coordinates of a point i / n of a turn around the rim of a disk
+/// filling the unit square
+fn disk<T: Float>(i: usize, n: usize) -> Point2<T> {
+    let half = T::from_f64(0.5);
+    let a = T::from_f64(2.0 * std::f64::consts::PI * i as f64 / n as f64);
+    Point2(half + half * a.cos(), half + half * a.sin())
+}
+
+impl<T: Float> Tessellate3<T> for Sphere<T> {
+    /// latitude rings from pole to pole along z, half as many as segments
+    fn tessellate(&self, resolution: usize) -> TriMesh<T> {
+        let n = resolution.max(3);
+        let m = (n / 2).max(2);
+        let (zero, one) = (T::zero(), T::one());
+        let e = (Vector3(one, zero, zero), Vector3(zero, one, zero));
+        let up = Vector3(zero, zero, one);
+        let normal = |i: usize, j: usize| match j {
+            0 => -up,
+            _ if j == m => up,
+            _ => {
+                let phi = T::pi() * (frac::<T>(j, m) - T::from_f64(0.5));
+                around(e, i, n) * phi.cos() + up * phi.sin()
+            }
+        };
+        let index = |i: usize, j: usize| match j {
+            0 => 0,
+            _ if j == m => 1 + (m - 1) * n,
+            _ => 1 + (j - 1) * n + i % n,
+        };
+        let mut b = Builder::new();
+        b.vertices.push(self.center() + normal(0, 0) * self.radius());
+        for j in 1..m {
+            for i in 0..n {
+                b.vertices.push(self.center() + normal(i, j) * self.radius());
+            }
+        }
+        b.vertices.push(self.center() + normal(0, m) * self.radius());
+        for j in 0..m {
+            for i in 0..n {
+                // poles take the middle of the segment they close
+                let uv = |k: usize, l: usize| {
+                    let u = if l == 0 || l == m { T::from_f64((i as f64 + 0.5) / n as f64) } else { frac(k, n) };
+                    Point2(u, frac(l, m))
+                };
+                let corner = |k: usize, l: usize| (index(k, l), normal(k % n, l), uv(k, l));
+                let quad = [corner(i, j), corner(i + 1, j), corner(i + 1, j + 1), corner(i, j + 1)];
+                let mut tri = |[p, q, r]: [(usize, Vector3<T>, Point2<T>); 3]| b.face([p.0, q.0, r.0], [p.1, q.1, r.1], [p.2, q.2, r.2]);
+                if j != 0 {
+                    tri([quad[0], quad[1], quad[2]]);
+                }
+                if j != m - 1 {
+                    tri([quad[0], quad[2], quad[3]]);
+                }
+            }
+        }
+        b.build()
+    }
+}
+
+impl<T: Float> Tessellate3<T> for Cylinder<T> {
+    fn tessellate(&self, resolution: usize) -> TriMesh<T> {
+        let n = resolution.max(3);
+        let (a, top) = self.ends();
+        let axis = top.vector_from(a);
+        let up = axis / axis.length();
+        let e = Plane3::new(a, axis).basis();
+        let mut b = Builder::new();
+        for end in [a, top] {
+            for i in 0..n {
+                b.vertices.push(end + around(e, i, n) * self.radius());
+            }
+        }
+        b.vertices.extend([a, top]);
+        let (c0, c1) = (2 * n, 2 * n + 1);
+        let (zero, one, half) = (T::zero(), T::one(), T::from_f64(0.5));
+        for i in 0..n {
+            let j = (i + 1) % n;
+            let (ri, rj) = (around(e, i, n), around(e, j, n));
+            let (ui, uj) = (frac(i, n), frac(i + 1, n));
+            b.face([i, j, n + j], [ri, rj, rj], [Point2(ui, zero), Point2(uj, zero), Point2(uj, one)]);
+            b.face([i, n + j, n + i], [ri, rj, ri], [Point2(ui, zero), Point2(uj, one), Point2(ui, one)]);
+            b.face([c0, j, i], [-up; 3], [Point2(half, half), disk(j, n), disk(i, n)]);
+            b.face([c1, n + i, n + j], [up; 3], [Point2(half, half), disk(i, n), disk(j, n)]);
+        }
+        b.build()
+    }
+}
+
+impl<T: Float> Tessellate3<T> for Cone<T> {
+    /// side uvs run from the apex at v = 0 to the base rim at v = 1
+    fn tessellate(&self, resolution: usize) -> TriMesh<T> {
+        let n = resolution.max(3);
+        let (apex, axis) = (self.apex(), self.axis());
+        let up = axis / axis.length();
+        let e = Plane3::new(apex, axis).basis();
+        let (sin, cos) = (self.angle().sin(), self.angle().cos());
+        // normals of the side tilt from the radial direction toward the apex
+        let slant = |r: Vector3<T>| r * cos - up * sin;
+        let base = apex + axis;
+        let mut b = Builder::new();
+        b.vertices.push(apex);
+        for i in 0..n {
+            b.vertices.push(base + around(e, i, n) * self.radius());
+        }
+        b.vertices.push(base);
+        let (zero, one, half) = (T::zero(), T::one(), T::from_f64(0.5));
+        for i in 0..n {
+            let j = (i + 1) % n;
+            let (ri, rj, mid) = (around(e, i, n), around(e, j, n), around(e, 2 * i + 1, 2 * n));
+            let tip = Point2(T::from_f64((i as f64 + 0.5) / n as f64), zero);
+            b.face([0, 1 + j, 1 + i], [slant(mid), slant(rj), slant(ri)], [tip, Point2(frac(i + 1, n), one), Point2(frac(i, n), one)]);
+            b.face([n + 1, 1 + i, 1 + j], [up; 3], [Point2(half, half), disk(i, n), disk(j, n)]);
+        }
+        b.build()
+    }
+}
+
+impl<T: Float> Tessellate3<T> for Torus<T> {
+    /// as many segments around the tube as around the axis
+    fn tessellate(&self, resolution: usize) -> TriMesh<T> {
+        let n = resolution.max(3);
+        let up = self.axis() / self.axis().length();
+        let e = Plane3::new(self.center(), self.axis()).basis();
+        let tube = (Vector3(T::one(), T::zero(), T::zero()), Vector3(T::zero(), T::one(), T::zero()));
+        let normal = |i: usize, j: usize| {
+            let a = around(tube, j, n);
+            around(e, i, n) * a.0 + up * a.1
+        };
+        let mut b = Builder::new();
+        for i in 0..n {
+            for j in 0..n {
+                b.vertices.push(self.center() + around(e, i, n) * self.major() + normal(i, j) * self.minor());
+            }
+        }
+        for i in 0..n {
+            for j in 0..n {
+                let corner = |k: usize, l: usize| ((k % n) * n + l % n, normal(k % n, l % n), Point2(frac(k, n), frac(l, n)));
+                let quad = [corner(i, j), corner(i + 1, j), corner(i + 1, j + 1), corner(i, j + 1)];
+                for [p, q, r] in [[quad[0], quad[1], quad[2]], [quad[0], quad[2], quad[3]]] {
+                    b.face([p.0, q.0, r.0], [p.1, q.1, r.1], [p.2, q.2, r.2]);
+                }
+            }
+        }
+        b.build()
+    }
+}
+
+impl<T: Float> Tessellate3<T> for Aabb3<T> {
+    /// twelve triangles whatever the resolution
+    fn tessellate(&self, _resolution: usize) -> TriMesh<T> {
+        let (lo, hi) = (self.min(), self.max());
+        let mut b = Builder::new();
+        // corner k has bit 0 for x, bit 1 for y, bit 2 for z at the max side
+        for k in 0..8 {
+            let pick = |bit: usize, l: T, h: T| if k & bit != 0 { h } else { l };
+            b.vertices.push(Point3(pick(1, lo.0, hi.0), pick(2, lo.1, hi.1), pick(4, lo.2, hi.2)));
+        }
+        let (zero, one) = (T::zero(), T::one());
+        let faces = [
+            ([1, 3, 7, 5], Vector3(one, zero, zero)),
+            ([0, 4, 6, 2], Vector3(-one, zero, zero)),
+            ([2, 6, 7, 3], Vector3(zero, one, zero)),
+            ([0, 1, 5, 4], Vector3(zero, -one, zero)),
+            ([4, 5, 7, 6], Vector3(zero, zero, one)),
+            ([0, 2, 3, 1], Vector3(zero, zero, -one)),
+        ];
+        let square = [Point2(zero, zero), Point2(one, zero), Point2(one, one), Point2(zero, one)];
+        for ([p, q, r, s], normal) in faces {
+            b.face([p, q, r], [normal; 3], [square[0], square[1], square[2]]);
+            b.face([p, r, s], [normal; 3], [square[0], square[2], square[3]]);
+        }
+        b.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    /// Corner normals unit and on the outer side of their face, uvs in the unit square
+    fn check(mesh: &TriMesh<f64>) {
+        let (normals, uvs) = (mesh.normals().unwrap(), mesh.uvs().unwrap());
+        assert!(normals.len() == mesh.triangles().len() && uvs.len() == mesh.triangles().len());
+        for (k, t) in mesh.triangles().iter().enumerate() {
+            let [a, b, c] = t.map(|i| mesh.vertices()[i]);
+            let face = b.vector_from(a).cross(c.vector_from(a));
+            assert!(face.length() > 0.0);
+            for n in normals[k] {
+                assert!((n.length() - 1.0).abs() < 1e-12 && n.dot(face) > 0.0);
+            }
+            assert!(uvs[k].iter().all(|p| (0.0..=1.0).contains(&p.0) && (0.0..=1.0).contains(&p.1)));
+        }
+    }
+
+    #[test]
+    fn round_solids() {
+        let s = Sphere::new(Point3(1.0, 2.0, 3.0), 2.0).tessellate(64);
+        check(&s);
+        assert!((s.volume() - 32.0 * PI / 3.0).abs() < 32.0 * PI / 3.0 * 0.01);
+        assert_eq!(s.vertices().len(), 2 + 31 * 64);
+        let c = Cylinder::new(Point3(1.0, 0.0, 0.0), Point3(1.0, 0.0, 4.0), 1.0).tessellate(24);
+        check(&c);
+        assert!((c.volume() - 12.0 * (PI / 12.0).sin() * 4.0).abs() < 1e-9);
+        let cone = Cone::new(Point3(0.0, 0.0, 0.0), Vector3(0.0, 3.0, 0.0), PI / 4.0).tessellate(16);
+        check(&cone);
+        assert!((cone.volume() - 8.0 * (PI / 8.0).sin() * 9.0 / 3.0 * 3.0).abs() < 1e-9);
+        let t = Torus::new(Point3(0.0, 0.0, 0.0), Vector3(0.0, 0.0, 2.0), 3.0, 1.0).tessellate(64);
+        check(&t);
+        assert!((t.volume() - 6.0 * PI * PI).abs() < 6.0 * PI * PI * 0.01);
+    }
+
+    #[test]
+    fn boxes() {
+        let b = Aabb3::new(Point3(1.0, 2.0, 3.0), Point3(2.0, 4.0, 6.0)).tessellate(0);
+        check(&b);
+        assert_eq!((b.vertices().len(), b.triangles().len()), (8, 12));
+        assert_eq!(b.volume(), 6.0);
+    }
+}
@@ -0,0 +1,389 @@
+//! Tetrahedra and Delaunay tetrahedralization
+
+use crate::stats;
+use crate::{ Aabb3, Bounded3, Budget, Float, Point3, Vector3 };
+
+/// Tetrahedron: four corners, positively oriented when
+/// (b - a) · ((c - a) × (d - a)) > 0
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Tetrahedron<T>([Point3<T>; 4]);
+
+impl<T: Float> Tetrahedron<T> {
+    /// new: Point × Point × Point × Point -> Tetrahedron
+    pub fn new(a: Point3<T>, b: Point3<T>, c: Point3<T>, d: Point3<T>) -> Self {
+        Tetrahedron([a, b, c, d])
+    }
+
+    /// vertices: &Tetrahedron -> [Point; 4]
+    pub fn vertices(&self) -> [Point3<T>; 4] {
+        self.0
+    }
+
+    /// signed_volume: &Tetrahedron -> K
+    /// positive for positive orientation
+    pub fn signed_volume(&self) -> T {
+        let [a, b, c, d] = self.0;
+        orient(a, b, c, d) / T::from_f64(6.0)
+    }
+
+    /// volume: &Tetrahedron -> K
+    pub fn volume(&self) -> T {
+        self.signed_volume().abs()
+    }
+
+    /// circumsphere: &Tetrahedron -> Option<(Point, K)>
+    /// center and radius of the sphere through the corners, None if flat
+    pub fn circumsphere(&self) -> Option<(Point3<T>, T)> {
+        let [a, b, c, d] = self.0;
+        let (u, v, w) = (b.vector_from(a), c.vector_from(a), d.vector_from(a));
+        let det = u.dot(v.cross(w)) * T::from_f64(2.0);
+        if det == T::zero() {
+            return None;
+        }
+        let o = (v.cross(w) * u.dot(u) + w.cross(u) * v.dot(v) + u.cross(v) * w.dot(w)) / det;
+        Some((a + o, o.length()))
+    }
+
+    /// barycentric: &Tetrahedron × Point -> Option<[K; 4]>
+    /// weights of the corners summing to one, None if flat
+    pub fn barycentric(&self, p: Point3<T>) -> Option<[T; 4]> {
+        let [a, b, c, d] = self.0;
+        let total = orient(a, b, c, d);
+        if total == T::zero() {
+            return None;
+        }
+        Some([
+            orient(p, b, c, d) / total,
+            orient(a, p, c, d) / total,
+            orient(a, b, p, d) / total,
+            orient(a, b, c, p) / total,
+        ])
+    }
+
+    /// contains: &Tetrahedron × Point -> bool
+    /// boundary included; false for a flat tetrahedron
+    pub fn contains(&self, p: Point3<T>) -> bool {
+        self.barycentric(p).is_some_and(|w| w.iter().all(|&x| x >= T::zero()))
+    }
+}
+
+impl<T: Float> Bounded3<T> for Tetrahedron<T> {
+    fn bounds(&self) -> Option<Aabb3<T>> {
+        Aabb3::from_points(self.0)
+    }
+}
+
+/// Six times the signed volume of abcd
+fn orient<T: Float>(a: Point3<T>, b: Point3<T>, c: Point3<T>, d: Point3<T>) -> T {
+    b.vector_from(a).dot(c.vector_from(a).cross(d.vector_from(a)))
+}
+
+/// delaunay: [Point] -> [[usize; 4]]
+/// positively oriented tetrahedra, as indices into `points`, whose circumspheres
+/// hold no other point (Bowyer–Watson insertion inside an enclosing tetrahedron,
+/// each point located by a walk from the last new tetrahedron and its cavity
+/// grown from there). Hull tetrahedra whose circumsphere would reach the
+/// enclosing corners may be missing when points are nearly coplanar on the
+/// hull; repeated points are left out
+pub fn delaunay<T: Float>(points: &[Point3<T>]) -> Vec<[usize; 4]> {
+    delaunay_within(points, &mut Budget::unlimited()).expect("unlimited budget")
+}
+
+/// delaunay_within: [Point] × &mut Budget -> Option<[[usize; 4]]>
+/// `delaunay`, a unit of the budget per point inserted and per circumsphere
+/// test; None if it runs out
+pub fn delaunay_within<T: Float>(points: &[Point3<T>], budget: &mut Budget) -> Option<Vec<[usize; 4]>> {
+    let _timer = stats::time("tetrahedralization");
+    let n = points.len();
+    let Some(bounds) = Aabb3::from_points(points.iter().copied()) else {
+        return Some(Vec::new());
+    };
+    let (lo, hi) = (bounds.min(), bounds.max());
+    let half = T::from_f64(0.5);
+    let c = lo + hi.vector_from(lo) * half;
+    let e = hi.vector_from(lo);
+    let size = [e.0, e.1, e.2].into_iter().fold(T::one(), |m, x| if x > m { x } else { m });
+    let k = size * T::from_f64(100.0);
+    let mut vertices = points.to_vec();
+    let one = T::one();
+    vertices.extend([
+        c + Vector3(-one, -one, -one) * k,
+        c + Vector3(one, one, -one) * k,
+        c + Vector3(one, -one, one) * k,
+        c + Vector3(-one, one, one) * k,
+    ]);
+    let first = oriented([n, n + 1, n + 2, n + 3], &vertices);
+    let mut mesh = Tetrahedra::new(vertices, first);
+    // along a Morton curve, so each walk starts next to its point
+    let mut order: Vec<usize> = (0..n).collect();
+    let cells = T::from_f64(2097151.0);
+    let cell = |x: T, w: T| if w > T::zero() { ((x / w) * cells).to_f64() as u64 } else { 0 };
+    order.sort_by_cached_key(|&i| {
+        let v = points[i].vector_from(lo);
+        morton([cell(v.0, e.0), cell(v.1, e.1), cell(v.2, e.2)])
+    });
+    let mut tests = 0;
+    for i in order {
+        if !budget.tick() {
+            return None;
+        }
+        tests += mesh.insert(i, budget)?;
+    }
+    stats::count("tetrahedralization", "points", n);
+    stats::count("tetrahedralization", "sphere tests", tests);
+    Some(mesh.finished(n))
+}
+/// No tetrahedron, across the faces of the enclosing one
+const NONE: usize = usize::MAX;
+
+/// Face opposite each corner, wound so that the corner is on its negative
+/// side: orient(face, p) > 0 puts p beyond it
+const FACES: [[usize; 3]; 4] = [[1, 2, 3], [0, 3, 2], [0, 1, 3], [0, 2, 1]];
+
+/// Tetrahedralization with the four neighbours of each tetrahedron
+struct Tetrahedra<T> {
+    vertices: Vec<Point3<T>>,
+    /// positively oriented corners, slots of removed ones reused
+    corners: Vec<[usize; 4]>,
+    /// neighbours[t][k] lies across the face opposite corner k of t
+    neighbours: Vec<[usize; 4]>,
+    spheres: Vec<Option<(Point3<T>, T)>>,
+    alive: Vec<bool>,
+    free: Vec<usize>,
+    /// a tetrahedron made last, where walks start
+    last: usize,
+    /// round in which each tetrahedron was last seen, plus one if taken
+    mark: Vec<usize>,
+    round: usize,
+    cavity: Vec<usize>,
+    /// boundary face of the cavity, the tetrahedron outside and its corner
+    /// facing in
+    boundary: Vec<([usize; 3], usize, usize)>,
+    /// edge of a new face through the inserted point, with the
+    /// tetrahedron and its corner opposite that face
+    edges: Vec<([usize; 2], usize, usize)>,
+}
+
+impl<T: Float> Tetrahedra<T> {
+    fn new(vertices: Vec<Point3<T>>, first: [usize; 4]) -> Self {
+        let mut mesh = Tetrahedra {
+            vertices,
+            corners: Vec::new(),
+            neighbours: Vec::new(),
+            spheres: Vec::new(),
+            alive: Vec::new(),
+            free: Vec::new(),
+            last: 0,
+            mark: Vec::new(),
+            round: 0,
+            cavity: Vec::new(),
+            boundary: Vec::new(),
+            edges: Vec::new(),
+        };
+        mesh.add(first);
+        mesh
+    }
+
+    fn face(&self, t: usize, k: usize) -> [usize; 3] {
+        FACES[k].map(|j| self.corners[t][j])
+    }
+
+    /// p beyond the face opposite corner k of t
+    fn beyond(&self, t: usize, k: usize, p: Point3<T>) -> bool {
+        let [a, b, c] = self.face(t, k).map(|v| self.vertices[v]);
+        orient(a, b, c, p) > T::zero()
+    }
+
+    /// The tetrahedron holding p, walking across the faces p is beyond,
+    /// trying them in turn from a different one each step
+    fn locate(&self, p: Point3<T>) -> usize {
+        let mut t = self.last;
+        for step in 0..self.corners.len() {
+            match (0..4).map(|s| (s + step) % 4).find(|&k| self.beyond(t, k, p) && self.neighbours[t][k] != NONE) {
+                Some(k) => t = self.neighbours[t][k],
+                None => return t,
+            }
+        }
+        // rounding sent the walk round in circles
+        (0..self.corners.len())
+            .find(|&t| self.alive[t] && (0..4).all(|k| !self.beyond(t, k, p)))
+            .unwrap_or(t)
+    }
+
+    /// Bowyer–Watson step: the tetrahedra whose circumspheres hold point i,
+    /// grown breadth first from the one holding it, replaced by a fan from
+    /// i to their boundary; how many spheres were tested, a unit of the
+    /// budget each, None if it runs out
+    fn insert(&mut self, i: usize, budget: &mut Budget) -> Option<usize> {
+        let p = self.vertices[i];
+        let start = self.locate(p);
+        if self.corners[start].iter().any(|&v| self.vertices[v] == p) {
+            return Some(0);
+        }
+        // seen this round: marked `round`, in the cavity: one more
+        self.round += 2;
+        let round = self.round;
+        self.mark.resize(self.corners.len(), 0);
+        self.mark[start] = round + 1;
+        self.cavity.clear();
+        self.cavity.push(start);
+        let mut tests = 0;
+        let mut at = 0;
+        while at < self.cavity.len() {
+            let t = self.cavity[at];
+            at += 1;
+            for k in 0..4 {
+                let u = self.neighbours[t][k];
+                if u != NONE && self.mark[u] < round {
+                    if !budget.tick() {
+                        return None;
+                    }
+                    tests += 1;
+                    // also taken if p is not strictly inside the face they
+                    // share, which would leave a flat tetrahedron
+                    let holds = self.spheres[u].is_some_and(|(o, r)| p.distance(o) < r) || {
+                        let [a, b, c] = self.face(t, k).map(|v| self.vertices[v]);
+                        orient(a, b, c, p) >= T::zero()
+                    };
+                    self.mark[u] = if holds { round + 1 } else { round };
+                    if holds {
+                        self.cavity.push(u);
+                    }
+                }
+            }
+        }
+        self.boundary.clear();
+        for &t in &self.cavity {
+            for (face, u) in FACES.iter().zip(self.neighbours[t]) {
+                if u == NONE || self.mark[u] != round + 1 {
+                    let back = if u == NONE { NONE } else { (0..4).find(|&j| self.neighbours[u][j] == t).unwrap_or(0) };
+                    self.boundary.push((face.map(|j| self.corners[t][j]), u, back));
+                }
+            }
+        }
+        for &t in &self.cavity {
+            self.alive[t] = false;
+        }
+        self.free.extend(self.cavity.iter().copied());
+        self.edges.clear();
+        for f in 0..self.boundary.len() {
+            let ([a, b, c], out, back) = self.boundary[f];
+            let t = self.add([a, c, b, i]);
+            self.neighbours[t][3] = out;
+            if out != NONE {
+                self.neighbours[out][back] = t;
+            }
+            for (k, [u, v]) in [(0, [c, b]), (1, [a, b]), (2, [a, c])] {
+                self.edges.push((if u < v { [u, v] } else { [v, u] }, t, k));
+            }
+        }
+        // each boundary edge is on two boundary faces, whose fan
+        // tetrahedra meet across the face through it and i
+        self.edges.sort_unstable_by_key(|e| e.0);
+        for pair in self.edges.chunks(2) {
+            if let [(_, t, k), (_, u, j)] = *pair {
+                self.neighbours[t][k] = u;
+                self.neighbours[u][j] = t;
+            }
+        }
+        if let Some(&(_, t, _)) = self.edges.last() {
+            self.last = t;
+        }
+        Some(tests)
+    }
+
+    fn add(&mut self, corners: [usize; 4]) -> usize {
+        let [a, b, c, d] = corners.map(|v| self.vertices[v]);
+        let sphere = Tetrahedron::new(a, b, c, d).circumsphere();
+        match self.free.pop() {
+            Some(t) => {
+                self.corners[t] = corners;
+                self.spheres[t] = sphere;
+                self.alive[t] = true;
+                t
+            }
+            None => {
+                self.corners.push(corners);
+                self.neighbours.push([NONE; 4]);
+                self.spheres.push(sphere);
+                self.alive.push(true);
+                self.corners.len() - 1
+            }
+        }
+    }
+
+    /// Live tetrahedra with no corner of the enclosing one
+    fn finished(self, n: usize) -> Vec<[usize; 4]> {
+        (0..self.corners.len())
+            .filter(|&t| self.alive[t] && self.corners[t].iter().all(|&v| v < n))
+            .map(|t| self.corners[t])
+            .collect()
+    }
+}
+
+/// Position of cell (x, y, z) of a 2²¹-cell cube along a Morton curve
+fn morton(cell: [u64; 3]) -> u64 {
+    let spread = |mut x: u64| {
+        x &= 0x1f_ffff;
+        x = (x | x << 32) & 0x1f_0000_0000_ffff;
+        x = (x | x << 16) & 0x1f_0000_ff00_00ff;
+        x = (x | x << 8) & 0x100f_00f0_0f00_f00f;
+        x = (x | x << 4) & 0x10c3_0c30_c30c_30c3;
+        (x | x << 2) & 0x1249_2492_4924_9249
+    };
+    spread(cell[0]) | spread(cell[1]) << 1 | spread(cell[2]) << 2
+}
+
+/// The tetrahedron with its last two corners swapped if negatively oriented
+fn oriented<T: Float>(t: [usize; 4], vertices: &[Point3<T>]) -> [usize; 4] {
+    let [a, b, c, d] = t.map(|i| vertices[i]);
+    if orient(a, b, c, d) < T::zero() { [t[0], t[1], t[3], t[2]] } else { t }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tetrahedron() {
+        let o = Point3(0.0, 0.0, 0.0);
+        let t = Tetrahedron::new(o, Point3(1.0, 0.0, 0.0), Point3(0.0, 1.0, 0.0), Point3(0.0, 0.0, 1.0));
+        assert_eq!(t.signed_volume(), 1.0 / 6.0);
+        let (c, r) = t.circumsphere().unwrap();
+        assert_eq!(c, Point3(0.5, 0.5, 0.5));
+        assert!((r - 0.75f64.sqrt()).abs() < 1e-15);
+        assert_eq!(t.barycentric(Point3(0.25, 0.25, 0.25)), Some([0.25; 4]));
+        assert!(t.contains(Point3(0.1, 0.1, 0.1)) && t.contains(o) && !t.contains(Point3(0.5, 0.5, 0.5)));
+        let flat = Tetrahedron::new(o, Point3(1.0, 0.0, 0.0), Point3(0.0, 1.0, 0.0), Point3(1.0, 1.0, 0.0));
+        assert_eq!((flat.circumsphere(), flat.barycentric(o), flat.contains(o)), (None, None, false));
+        assert_eq!(t.bounds(), Some(Aabb3::new(o, Point3(1.0, 1.0, 1.0))));
+    }
+
+    #[test]
+    fn delaunay_cloud() {
+        // cube corners plus scattered interior points
+        let mut seed = 12345u64;
+        let mut next = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            0.05 + 0.9 * ((seed >> 11) as f64 / (1u64 << 53) as f64)
+        };
+        let mut points: Vec<Point3<f64>> = (0..8)
+            .map(|i| Point3((i & 1) as f64, ((i >> 1) & 1) as f64, ((i >> 2) & 1) as f64))
+            .collect();
+        for _ in 0..40 {
+            points.push(Point3(next(), next(), next()));
+        }
+        let tets = delaunay(&points);
+        let tetra = |t: &[usize; 4]| Tetrahedron::new(points[t[0]], points[t[1]], points[t[2]], points[t[3]]);
+        let volume: f64 = tets.iter().map(|t| tetra(t).signed_volume()).sum();
+        assert!((volume - 1.0).abs() < 1e-9);
+        assert!(tets.iter().all(|t| tetra(t).signed_volume() > 0.0));
+        for t in &tets {
+            let (c, r) = tetra(t).circumsphere().unwrap();
+            assert!(points.iter().all(|p| p.distance(c) >= r - 1e-9));
+        }
+        assert!(delaunay::<f64>(&[]).is_empty());
+        assert_eq!(delaunay(&points[..4]).len(), 0);
+        assert_eq!(delaunay(&[points[0], points[1], points[2], points[4]]).len(), 1);
+    }
+}
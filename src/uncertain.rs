@@ -0,0 +1,205 @@
+//! Points with covariance, for sensor fusion and tracking
+//!
+//! A rigid or affine map x ↦ J x + t carries a covariance Σ to J Σ Jᵀ.
+//! Since Σ is symmetric, J Σ Jᵀ = J (J Σ)ᵀ: the linear part applied to the
+//! columns of Σ, then to the rows of the result.
+
+use crate::conic::inverse3;
+use crate::{ Affine2, Affine3, Float, Isometry3, Point2, Point3, Pose2, Vector2, Vector3 };
+
+/// 2d Point with a covariance, by columns
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UncertainPoint2<T>(Point2<T>, [Vector2<T>; 2]);
+
+/// 3d Point with a covariance, by columns
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UncertainPoint3<T>(Point3<T>, [Vector3<T>; 3]);
+
+fn transpose2<T: Copy>(m: [Vector2<T>; 2]) -> [Vector2<T>; 2] {
+    [Vector2(m[0].0, m[1].0), Vector2(m[0].1, m[1].1)]
+}
+
+fn transpose3<T: Copy>(m: [Vector3<T>; 3]) -> [Vector3<T>; 3] {
+    [Vector3(m[0].0, m[1].0, m[2].0), Vector3(m[0].1, m[1].1, m[2].1), Vector3(m[0].2, m[1].2, m[2].2)]
+}
+
+impl<T: Float> UncertainPoint2<T> {
+    /// new: Point × [Vector; 2] -> UncertainPoint
+    /// the covariance should be symmetric and positive semi-definite
+    pub fn new(point: Point2<T>, covariance: [Vector2<T>; 2]) -> Self {
+        UncertainPoint2(point, covariance)
+    }
+
+    /// isotropic: Point × K -> UncertainPoint
+    /// the same variance along every direction
+    pub fn isotropic(point: Point2<T>, variance: T) -> Self {
+        let zero = T::zero();
+        UncertainPoint2(point, [Vector2(variance, zero), Vector2(zero, variance)])
+    }
+
+    /// point: &UncertainPoint -> Point
+    pub fn point(&self) -> Point2<T> {
+        self.0
+    }
+
+    /// covariance: &UncertainPoint -> [Vector; 2]
+    pub fn covariance(&self) -> [Vector2<T>; 2] {
+        self.1
+    }
+
+    /// transformed: &UncertainPoint × &Affine -> UncertainPoint
+    pub fn transformed(&self, m: &Affine2<T>) -> Self {
+        self.mapped(m.transform_point(self.0), |v| m.transform_vector(v))
+    }
+
+    /// posed: &UncertainPoint × &Pose -> UncertainPoint
+    /// from the pose's frame into the world, as `Pose2::transform_point`
+    pub fn posed(&self, pose: &Pose2<T>) -> Self {
+        self.mapped(pose.transform_point(self.0), |v| pose.transform_vector(v))
+    }
+
+    /// mahalanobis_distance: &UncertainPoint × Point -> Option<K>
+    /// √(dᵀ Σ⁻¹ d) for d from the point to p; None for a singular covariance
+    pub fn mahalanobis_distance(&self, p: Point2<T>) -> Option<T> {
+        mahalanobis2(p.vector_from(self.0), self.1)
+    }
+
+    /// mahalanobis_to: &UncertainPoint × &UncertainPoint -> Option<K>
+    /// distance between two independent estimates under their summed
+    /// covariances, as used to gate associations
+    pub fn mahalanobis_to(&self, other: &UncertainPoint2<T>) -> Option<T> {
+        let s = [self.1[0] + other.1[0], self.1[1] + other.1[1]];
+        mahalanobis2(other.0.vector_from(self.0), s)
+    }
+
+    fn mapped(&self, point: Point2<T>, linear: impl Fn(Vector2<T>) -> Vector2<T>) -> Self {
+        let js = self.1.map(&linear);
+        UncertainPoint2(point, transpose2(js).map(&linear))
+    }
+}
+
+fn mahalanobis2<T: Float>(d: Vector2<T>, s: [Vector2<T>; 2]) -> Option<T> {
+    let (a, b, c) = (s[0].0, s[1].0, s[1].1);
+    let det = a * c - b * b;
+    if det == T::zero() || !det.is_finite() {
+        return None;
+    }
+    let q = (c * d.0 * d.0 - (b + b) * d.0 * d.1 + a * d.1 * d.1) / det;
+    Some(if q > T::zero() { q.sqrt() } else { T::zero() })
+}
+
+impl<T: Float> UncertainPoint3<T> {
+    /// new: Point × [Vector; 3] -> UncertainPoint
+    /// the covariance should be symmetric and positive semi-definite
+    pub fn new(point: Point3<T>, covariance: [Vector3<T>; 3]) -> Self {
+        UncertainPoint3(point, covariance)
+    }
+
+    /// isotropic: Point × K -> UncertainPoint
+    /// the same variance along every direction
+    pub fn isotropic(point: Point3<T>, variance: T) -> Self {
+        let zero = T::zero();
+        UncertainPoint3(point, [Vector3(variance, zero, zero), Vector3(zero, variance, zero), Vector3(zero, zero, variance)])
+    }
+
+    /// point: &UncertainPoint -> Point
+    pub fn point(&self) -> Point3<T> {
+        self.0
+    }
+
+    /// covariance: &UncertainPoint -> [Vector; 3]
+    pub fn covariance(&self) -> [Vector3<T>; 3] {
+        self.1
+    }
+
+    /// transformed: &UncertainPoint × &Affine -> UncertainPoint
+    pub fn transformed(&self, m: &Affine3<T>) -> Self {
+        self.mapped(m.transform_point(self.0), |v| m.transform_vector(v))
+    }
+
+    /// moved: &UncertainPoint × &Isometry -> UncertainPoint
+    pub fn moved(&self, m: &Isometry3<T>) -> Self {
+        self.mapped(m.transform_point(self.0), |v| m.transform_vector(v))
+    }
+
+    /// mahalanobis_distance: &UncertainPoint × Point -> Option<K>
+    /// √(dᵀ Σ⁻¹ d) for d from the point to p; None for a singular covariance
+    pub fn mahalanobis_distance(&self, p: Point3<T>) -> Option<T> {
+        mahalanobis3(p.vector_from(self.0), self.1)
+    }
+
+    /// mahalanobis_to: &UncertainPoint × &UncertainPoint -> Option<K>
+    /// distance between two independent estimates under their summed
+    /// covariances, as used to gate associations
+    pub fn mahalanobis_to(&self, other: &UncertainPoint3<T>) -> Option<T> {
+        let s = [self.1[0] + other.1[0], self.1[1] + other.1[1], self.1[2] + other.1[2]];
+        mahalanobis3(other.0.vector_from(self.0), s)
+    }
+
+    fn mapped(&self, point: Point3<T>, linear: impl Fn(Vector3<T>) -> Vector3<T>) -> Self {
+        let js = self.1.map(&linear);
+        UncertainPoint3(point, transpose3(js).map(&linear))
+    }
+}
+
+fn mahalanobis3<T: Float>(d: Vector3<T>, s: [Vector3<T>; 3]) -> Option<T> {
+    // symmetric, so the columns serve as rows
+    let inv = inverse3(s.map(|c| [c.0, c.1, c.2]))?;
+    let d = [d.0, d.1, d.2];
+    let q = (0..3).fold(T::zero(), |q, i| (0..3).fold(q, |q, j| q + d[i] * inv[i][j] * d[j]));
+    if !q.is_finite() {
+        return None;
+    }
+    Some(if q > T::zero() { q.sqrt() } else { T::zero() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rotor3;
+    use std::f64::consts::PI;
+
+    fn near(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn propagate_2d() {
+        // variance 4 along x, 1 along y, turned a quarter
+        let u = UncertainPoint2::new(Point2(1.0, 0.0), [Vector2(4.0, 0.0), Vector2(0.0, 1.0)]);
+        let turned = u.posed(&Pose2::new(Point2(0.0, 0.0), PI / 2.0));
+        assert!(turned.point().distance(Point2(0.0, 1.0)) < 1e-12);
+        let [c0, c1] = turned.covariance();
+        assert!(near(c0.0, 1.0) && near(c1.1, 4.0) && near(c0.1, 0.0) && near(c1.0, 0.0));
+        // a shear couples the axes: J Σ Jᵀ for J = [[1, 1], [0, 1]]
+        let shear = Affine2::new([Vector2(1.0, 0.0), Vector2(1.0, 1.0)], Vector2(0.0, 0.0));
+        let [c0, c1] = u.transformed(&shear).covariance();
+        assert!(near(c0.0, 5.0) && near(c0.1, 1.0) && near(c1.0, 1.0) && near(c1.1, 1.0));
+        assert!(near(u.mahalanobis_distance(Point2(3.0, 0.0)).unwrap(), 1.0));
+        assert!(near(u.mahalanobis_distance(Point2(1.0, 2.0)).unwrap(), 2.0));
+        let other = UncertainPoint2::isotropic(Point2(1.0, 3.0), 4.0);
+        assert!(near(u.mahalanobis_to(&other).unwrap(), 3.0 / 5f64.sqrt()));
+        assert_eq!(UncertainPoint2::isotropic(Point2(0.0, 0.0), 0.0).mahalanobis_distance(Point2(1.0, 0.0)), None);
+    }
+
+    #[test]
+    fn propagate_3d() {
+        let cov = [Vector3(2.0, 0.5, 0.0), Vector3(0.5, 1.0, 0.2), Vector3(0.0, 0.2, 3.0)];
+        let u = UncertainPoint3::new(Point3(1.0, 2.0, 3.0), cov);
+        let m = Isometry3::new(Rotor3::exp(Vector3(0.3, -0.7, 1.1)), Vector3(1.0, 0.0, -2.0));
+        let moved = u.moved(&m);
+        // a rigid motion keeps the distance of moved points
+        let p = Point3(2.0, 1.5, 2.0);
+        let (before, after) = (u.mahalanobis_distance(p).unwrap(), moved.mahalanobis_distance(m.transform_point(p)).unwrap());
+        assert!(near(before, after));
+        // the same as the affine with the rotation's columns
+        let r = m.rotation();
+        let columns = [r.rotate(Vector3(1.0, 0.0, 0.0)), r.rotate(Vector3(0.0, 1.0, 0.0)), r.rotate(Vector3(0.0, 0.0, 1.0))];
+        let a = u.transformed(&Affine3::new(columns, m.translation()));
+        for (x, y) in a.covariance().iter().zip(moved.covariance()) {
+            assert!((*x - y).length() < 1e-12);
+        }
+        let iso = UncertainPoint3::isotropic(Point3(0.0, 0.0, 0.0), 4.0);
+        assert!(near(iso.mahalanobis_distance(Point3(2.0, 2.0, 1.0)).unwrap(), 1.5));
+    }
+}
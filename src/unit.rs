@@ -0,0 +1,71 @@
+//! Vectors known to have unit length
+
+use std::ops::Neg;
+
+use crate::{ Float, Vector2, Vector3 };
+
+/// Unit-length wrapper of a vector
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Unit<V>(V);
+
+impl<V> Unit<V> {
+    /// new_unchecked: Vector -> Unit
+    /// the caller guarantees unit length
+    pub fn new_unchecked(v: V) -> Self {
+        Unit(v)
+    }
+
+    /// into_inner: Unit -> Vector
+    pub fn into_inner(self) -> V {
+        self.0
+    }
+}
+
+impl<V: Copy> Unit<V> {
+    /// get: &Unit -> Vector
+    pub fn get(&self) -> V {
+        self.0
+    }
+}
+
+impl<T: Float> Unit<Vector2<T>> {
+    /// new: Vector -> Option<Unit>
+    /// normalized, None for a zero or non-finite vector
+    pub fn new(v: Vector2<T>) -> Option<Self> {
+        let l = v.length();
+        if l > T::zero() && l.is_finite() { Some(Unit(v / l)) } else { None }
+    }
+}
+
+impl<T: Float> Unit<Vector3<T>> {
+    /// new: Vector -> Option<Unit>
+    /// normalized, None for a zero or non-finite vector
+    pub fn new(v: Vector3<T>) -> Option<Self> {
+        let l = v.length();
+        if l > T::zero() && l.is_finite() { Some(Unit(v / l)) } else { None }
+    }
+}
+
+/// (-): Unit -> Unit
+impl<V: Neg<Output=V>> Neg for Unit<V> {
+    type Output = Unit<V>;
+
+    fn neg(self) -> Self::Output {
+        Unit(-self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit() {
+        let u = Unit::<Vector2<f64>>::new(Vector2(3.0, 4.0)).unwrap();
+        assert_eq!(u.get(), Vector2(0.6, 0.8));
+        assert_eq!((-u).into_inner(), Vector2(-0.6, -0.8));
+        assert_eq!(Unit::<Vector3<f64>>::new(Vector3(0.0, 0.0, 2.0)).map(|u| u.get()), Some(Vector3(0.0, 0.0, 1.0)));
+        assert_eq!(Unit::<Vector3<f64>>::new(Vector3(0.0, 0.0, 0.0)), None);
+        assert_eq!(Unit::<Vector2<f64>>::new(Vector2(f64::INFINITY, 0.0)), None);
+    }
+}
@@ -0,0 +1,243 @@
+//! Read-only views over raw vertex and index bytes
+//!
+//! Borrow a byte slice, such as a memory-mapped asset, check its layout once
+//! and read coordinates in place: nothing is copied into owned containers,
+//! so bounds and raycasts work on files larger than memory. Scalars are
+//! little-endian and need no alignment.
+
+use std::marker::PhantomData;
+
+use crate::{ Aabb3, Bounded3, Float, Hit, Point3, Ray3, Raycast3, Vector3 };
+use crate::mesh::ray_triangle;
+
+/// Stored type of coordinates
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScalarFormat {
+    /// 4-byte float
+    F32,
+    /// 8-byte float
+    F64,
+}
+
+impl ScalarFormat {
+    /// size: ScalarFormat -> usize
+    /// bytes per scalar
+    pub fn size(self) -> usize {
+        match self {
+            ScalarFormat::F32 => 4,
+            ScalarFormat::F64 => 8,
+        }
+    }
+}
+
+/// Stored type of triangle indices
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IndexFormat {
+    /// 2-byte unsigned
+    U16,
+    /// 4-byte unsigned
+    U32,
+}
+
+impl IndexFormat {
+    /// size: IndexFormat -> usize
+    /// bytes per index
+    pub fn size(self) -> usize {
+        match self {
+            IndexFormat::U16 => 2,
+            IndexFormat::U32 => 4,
+        }
+    }
+}
+
+/// Where the position of each vertex lies in an interleaved buffer
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct VertexLayout {
+    /// bytes from one vertex to the next
+    pub stride: usize,
+    /// bytes from the start of a vertex to its x
+    pub offset: usize,
+    /// type of x, y and z, stored one after the other
+    pub format: ScalarFormat,
+}
+
+impl VertexLayout {
+    /// packed: ScalarFormat -> VertexLayout
+    /// bare positions, nothing between them
+    pub fn packed(format: ScalarFormat) -> Self {
+        VertexLayout { stride: 3 * format.size(), offset: 0, format }
+    }
+}
+
+/// Points read in place from a vertex buffer
+#[derive(Debug, Copy, Clone)]
+pub struct PointCloudView<'a, T> {
+    bytes: &'a [u8],
+    layout: VertexLayout,
+    len: usize,
+    scalar: PhantomData<T>,
+}
+
+impl<'a, T: Float> PointCloudView<'a, T> {
+    /// new: &[u8] × VertexLayout -> Option<PointCloudView>
+    /// None unless the position fits in the stride and the bytes hold a
+    /// whole number of vertices
+    pub fn new(bytes: &'a [u8], layout: VertexLayout) -> Option<Self> {
+        let fits = layout.stride > 0 && layout.offset.checked_add(3 * layout.format.size()).is_some_and(|end| end <= layout.stride);
+        (fits && bytes.len().is_multiple_of(layout.stride)).then(|| PointCloudView { bytes, layout, len: bytes.len() / layout.stride, scalar: PhantomData })
+    }
+
+    /// len: &View -> usize
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// is_empty: &View -> bool
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// point: &View × usize -> Point
+    /// panics past the end
+    pub fn point(&self, i: usize) -> Point3<T> {
+        assert!(i < self.len, "point {i} of {}", self.len);
+        let size = self.layout.format.size();
+        let at = i * self.layout.stride + self.layout.offset;
+        let read = |k: usize| {
+            let b = &self.bytes[at + k * size..at + (k + 1) * size];
+            T::from_f64(match self.layout.format {
+                ScalarFormat::F32 => f32::from_le_bytes(b.try_into().unwrap()) as f64,
+                ScalarFormat::F64 => f64::from_le_bytes(b.try_into().unwrap()),
+            })
+        };
+        Point3(read(0), read(1), read(2))
+    }
+
+    /// points: &View -> Iterator<Point>
+    pub fn points(&self) -> impl Iterator<Item = Point3<T>> + '_ {
+        (0..self.len).map(|i| self.point(i))
+    }
+}
+
+impl<T: Float> Bounded3<T> for PointCloudView<'_, T> {
+    fn bounds(&self) -> Option<Aabb3<T>> {
+        Aabb3::from_points(self.points())
+    }
+}
+
+/// Triangle mesh read in place from a vertex and an index buffer
+#[derive(Debug, Copy, Clone)]
+pub struct MeshView<'a, T> {
+    vertices: PointCloudView<'a, T>,
+    indices: &'a [u8],
+    format: IndexFormat,
+}
+
+impl<'a, T: Float> MeshView<'a, T> {
+    /// new: &[u8] × VertexLayout × &[u8] × IndexFormat -> Option<MeshView>
+    /// None unless the vertices are valid for the layout and the indices
+    /// form whole triangles of vertices that exist; one pass over the indices
+    pub fn new(vertices: &'a [u8], layout: VertexLayout, indices: &'a [u8], format: IndexFormat) -> Option<Self> {
+        let vertices = PointCloudView::new(vertices, layout)?;
+        let view = MeshView { vertices, indices, format };
+        (indices.len().is_multiple_of(3 * format.size()) && (0..indices.len() / format.size()).all(|k| view.index(k) < vertices.len()))
+            .then_some(view)
+    }
+
+    fn index(&self, k: usize) -> usize {
+        let size = self.format.size();
+        let b = &self.indices[k * size..(k + 1) * size];
+        match self.format {
+            IndexFormat::U16 => u16::from_le_bytes(b.try_into().unwrap()) as usize,
+            IndexFormat::U32 => u32::from_le_bytes(b.try_into().unwrap()) as usize,
+        }
+    }
+
+    /// vertices: &MeshView -> &PointCloudView
+    pub fn vertices(&self) -> &PointCloudView<'a, T> {
+        &self.vertices
+    }
+
+    /// triangle_count: &MeshView -> usize
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / (3 * self.format.size())
+    }
+
+    /// triangle: &MeshView × usize -> [usize; 3]
+    /// panics past the end
+    pub fn triangle(&self, i: usize) -> [usize; 3] {
+        assert!(i < self.triangle_count(), "triangle {i} of {}", self.triangle_count());
+        [0, 1, 2].map(|k| self.index(3 * i + k))
+    }
+
+    /// triangles: &MeshView -> Iterator<[usize; 3]>
+    pub fn triangles(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        (0..self.triangle_count()).map(|i| self.triangle(i))
+    }
+}
+
+impl<T: Float> Bounded3<T> for MeshView<'_, T> {
+    fn bounds(&self) -> Option<Aabb3<T>> {
+        self.vertices.bounds()
+    }
+}
+
+impl<T: Float> Raycast3<T> for MeshView<'_, T> {
+    /// as for `TriMesh`, every triangle read where it lies
+    fn raycast(&self, ray: &Ray3<T>) -> Option<Hit<T>> {
+        let mut best: Option<(T, Vector3<T>)> = None;
+        for t in self.triangles() {
+            if let Some(hit) = ray_triangle(ray, t.map(|i| self.vertices.point(i))) {
+                if best.is_none_or(|b| hit.0 < b.0) {
+                    best = Some(hit);
+                }
+            }
+        }
+        let (t, n) = best?;
+        Some(Hit { t, point: ray.point_at(t), normal: n / n.length() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TriMesh;
+
+    #[test]
+    fn interleaved_points() {
+        // position after a 4-byte tag, then 4 bytes of colour
+        let mut bytes = Vec::new();
+        for (k, p) in [[1.0f32, 2.0, 3.0], [-1.0, 5.0, 0.5]].iter().enumerate() {
+            bytes.extend((k as u32).to_le_bytes());
+            p.iter().for_each(|c| bytes.extend(c.to_le_bytes()));
+            bytes.extend([255, 0, 0, 255]);
+        }
+        let layout = VertexLayout { stride: 20, offset: 4, format: ScalarFormat::F32 };
+        let view = PointCloudView::<f64>::new(&bytes, layout).unwrap();
+        assert_eq!(view.len(), 2);
+        assert_eq!(view.point(1), Point3(-1.0, 5.0, 0.5));
+        assert_eq!(view.bounds(), Some(Aabb3::new(Point3(-1.0, 2.0, 0.5), Point3(1.0, 5.0, 3.0))));
+        assert!(PointCloudView::<f64>::new(&bytes[1..], layout).is_none());
+        assert!(PointCloudView::<f64>::new(&bytes, VertexLayout { stride: 20, offset: 12, format: ScalarFormat::F32 }).is_none());
+    }
+
+    #[test]
+    fn mesh_matches_owned() {
+        let vertices = [Point3(0.0, 0.0, 0.0), Point3(2.0, 0.0, 0.0), Point3(0.0, 2.0, 0.0), Point3(0.0, 0.0, 2.0)];
+        let triangles = [[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]];
+        let bytes: Vec<u8> = vertices.iter().flat_map(|p| [p.0, p.1, p.2]).flat_map(f64::to_le_bytes).collect();
+        let indices: Vec<u8> = triangles.iter().flatten().flat_map(|&i| (i as u16).to_le_bytes()).collect();
+        let view = MeshView::<f64>::new(&bytes, VertexLayout::packed(ScalarFormat::F64), &indices, IndexFormat::U16).unwrap();
+        let owned = TriMesh::new(vertices.to_vec(), triangles.to_vec());
+        assert_eq!(view.triangles().collect::<Vec<_>>(), triangles);
+        assert_eq!(view.bounds(), owned.bounds());
+        for d in [Vector3(-1.0, -1.0, -1.0), Vector3(-1.0, 0.2, -0.3), Vector3(1.0, 0.0, 0.0)] {
+            let ray = Ray3::new(Point3(1.0, 1.0, 1.0), d);
+            assert_eq!(view.raycast(&ray), owned.raycast(&ray));
+        }
+        let mut bad = indices.clone();
+        bad[0] = 4;
+        assert!(MeshView::<f64>::new(&bytes, VertexLayout::packed(ScalarFormat::F64), &bad, IndexFormat::U16).is_none());
+        assert!(MeshView::<f64>::new(&bytes, VertexLayout::packed(ScalarFormat::F64), &indices[..4], IndexFormat::U16).is_none());
+    }
+}
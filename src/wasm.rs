@@ -0,0 +1,209 @@
+//! JavaScript-facing bindings
+//!
+//! Flat `f64` arrays in and out, so wasm-bindgen maps them to `Float64Array`:
+//! 2d points are packed as [x0, y0, x1, y1, ...], 3d points as [x0, y0, z0, ...],
+//! and index lists as `Uint32Array`. Sets of rings travel as their packed
+//! points next to the number of points in each ring.
+
+use wasm_bindgen::prelude::*;
+
+use crate::motion::hull;
+use crate::polygon::union_all;
+use crate::stroke::stroke;
+use crate::{ BooleanOp, CapStyle, FillRule, Isometry3, JoinStyle, Path2, PathCommand, Point2, Point3, Polygon2, Polyline2, Rotor3, Vector3 };
+
+fn points2(coords: &[f64]) -> Vec<Point2<f64>> {
+    coords.chunks_exact(2).map(|c| Point2(c[0], c[1])).collect()
+}
+
+fn flat2(points: &[Point2<f64>]) -> Vec<f64> {
+    points.iter().flat_map(|p| [p.0, p.1]).collect()
+}
+
+fn rings2(coords: &[f64], lengths: &[u32]) -> Vec<Vec<Point2<f64>>> {
+    let points = points2(coords);
+    let mut start = 0;
+    lengths.iter().map(|&n| {
+        let end = (start + n as usize).min(points.len());
+        let ring = points[start..end].to_vec();
+        start = end;
+        ring
+    }).collect()
+}
+
+/// Rings packed as their points and the number of points in each;
+/// exteriors run counter-clockwise, holes clockwise after their exterior
+#[wasm_bindgen]
+pub struct Rings {
+    coords: Vec<f64>,
+    lengths: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl Rings {
+    /// Points of every ring, packed in order
+    #[wasm_bindgen(getter)]
+    pub fn coords(&self) -> Vec<f64> {
+        self.coords.clone()
+    }
+
+    /// Number of points in each ring
+    #[wasm_bindgen(getter)]
+    pub fn lengths(&self) -> Vec<u32> {
+        self.lengths.clone()
+    }
+}
+
+impl<'a> FromIterator<&'a [Point2<f64>]> for Rings {
+    fn from_iter<I: IntoIterator<Item = &'a [Point2<f64>]>>(rings: I) -> Self {
+        let mut packed = Rings { coords: Vec::new(), lengths: Vec::new() };
+        for ring in rings {
+            packed.coords.extend(flat2(ring));
+            packed.lengths.push(ring.len() as u32);
+        }
+        packed
+    }
+}
+
+/// Moves 3d points by the rigid motion rotating `axis` (x, y, z) by `angle` radians,
+/// then translating by (tx, ty, tz); an error unless both have three components
+#[wasm_bindgen(js_name = transformPoints3)]
+pub fn transform_points3(coords: &[f64], axis: &[f64], angle: f64, translation: &[f64]) -> Result<Vec<f64>, JsError> {
+    let (&[ax, ay, az], &[tx, ty, tz]) = (axis, translation) else {
+        return Err(JsError::new("axis and translation take three components each"));
+    };
+    let w = Vector3(ax, ay, az);
+    let l = w.length();
+    let rotation = if l == 0.0 { Rotor3::identity() } else { Rotor3::exp(w * (angle / l)) };
+    let m = Isometry3::new(rotation, Vector3(tx, ty, tz));
+    Ok(coords.chunks_exact(3).flat_map(|c| {
+        let p = m.transform_point(Point3(c[0], c[1], c[2]));
+        [p.0, p.1, p.2]
+    }).collect())
+}
+
+/// Signed area of the polygon ring, positive when counter-clockwise
+#[wasm_bindgen(js_name = polygonArea)]
+pub fn polygon_area(coords: &[f64]) -> f64 {
+    Polygon2::new(points2(coords)).signed_area()
+}
+
+/// Triangles of a simple polygon ring, as index triples into its vertices
+#[wasm_bindgen(js_name = triangulate)]
+pub fn triangulate(coords: &[f64]) -> Vec<u32> {
+    Polygon2::new(points2(coords)).triangulate().into_iter().flatten().map(|i| i as u32).collect()
+}
+
+/// Outline ring of the polyline stroked at `width` with round joins and caps
+#[wasm_bindgen(js_name = strokePolyline)]
+pub fn stroke_polyline(coords: &[f64], width: f64) -> Vec<f64> {
+    let outline = stroke(&Polyline2::new(points2(coords)), width, JoinStyle::Round, CapStyle::Round);
+    flat2(outline.vertices())
+}
+
+/// Convex hull of 2d points, counter-clockwise without collinear vertices
+#[wasm_bindgen(js_name = convexHull)]
+pub fn convex_hull(coords: &[f64]) -> Vec<f64> {
+    flat2(&hull(points2(coords)))
+}
+
+/// Union of simple polygons, each given as one ring
+#[wasm_bindgen(js_name = unionAll)]
+pub fn union_all_rings(coords: &[f64], lengths: &[u32]) -> Rings {
+    let polygons: Vec<Polygon2<f64>> = rings2(coords, lengths).into_iter().map(Polygon2::new).collect();
+    union_all(&polygons).iter().flat_map(Polygon2::rings).collect()
+}
+
+fn boolean(a: (&[f64], &[u32]), b: (&[f64], &[u32]), op: BooleanOp, tolerance: f64) -> Rings {
+    let path = |(coords, lengths): (&[f64], &[u32])| Path2::new(rings2(coords, lengths).into_iter().filter(|r| !r.is_empty()).flat_map(|ring| {
+        let rest = ring[1..].iter().map(|&p| PathCommand::LineTo(p));
+        std::iter::once(PathCommand::MoveTo(ring[0])).chain(rest).chain([PathCommand::Close]).collect::<Vec<_>>()
+    }).collect());
+    let polygons = path(a).boolean(&path(b), op, FillRule::NonZero, tolerance).to_polygons(tolerance);
+    polygons.iter().map(|p| p.vertices()).collect()
+}
+
+/// Region filled by either set of rings (non-zero rule), within `tolerance`
+#[wasm_bindgen(js_name = booleanUnion)]
+pub fn boolean_union(a: &[f64], a_lengths: &[u32], b: &[f64], b_lengths: &[u32], tolerance: f64) -> Rings {
+    boolean((a, a_lengths), (b, b_lengths), BooleanOp::Union, tolerance)
+}
+
+/// Region filled by both sets of rings (non-zero rule), within `tolerance`
+#[wasm_bindgen(js_name = booleanIntersection)]
+pub fn boolean_intersection(a: &[f64], a_lengths: &[u32], b: &[f64], b_lengths: &[u32], tolerance: f64) -> Rings {
+    boolean((a, a_lengths), (b, b_lengths), BooleanOp::Intersection, tolerance)
+}
+
+/// Region filled by the first set of rings and not the second (non-zero
+/// rule), within `tolerance`
+#[wasm_bindgen(js_name = booleanDifference)]
+pub fn boolean_difference(a: &[f64], a_lengths: &[u32], b: &[f64], b_lengths: &[u32], tolerance: f64) -> Rings {
+    boolean((a, a_lengths), (b, b_lengths), BooleanOp::Difference, tolerance)
+}
+
+/// Region filled by exactly one of the sets of rings (non-zero rule),
+/// within `tolerance`
+#[wasm_bindgen(js_name = booleanXor)]
+pub fn boolean_xor(a: &[f64], a_lengths: &[u32], b: &[f64], b_lengths: &[u32], tolerance: f64) -> Rings {
+    boolean((a, a_lengths), (b, b_lengths), BooleanOp::Xor, tolerance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flat_arrays() {
+        let moved = transform_points3(&[1.0, 0.0, 0.0, 0.0, 0.0, 2.0], &[0.0, 0.0, 2.0], std::f64::consts::FRAC_PI_2, &[0.0, 0.0, 1.0]).unwrap();
+        let expected = [0.0, 1.0, 1.0, 0.0, 0.0, 3.0];
+        assert!(moved.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-12));
+        let square = [0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0];
+        assert_eq!(polygon_area(&square), 1.0);
+        assert_eq!(triangulate(&square).len(), 6);
+        let outline = stroke_polyline(&[0.0, 0.0, 4.0, 0.0], 1.0);
+        assert!(outline.len() > 8 && outline.len().is_multiple_of(2));
+    }
+
+    fn area(rings: &Rings) -> f64 {
+        let mut start = 0;
+        rings.lengths().iter().map(|&n| {
+            let ring = &rings.coords()[2 * start..2 * (start + n as usize)];
+            start += n as usize;
+            polygon_area(ring)
+        }).sum()
+    }
+
+    #[test]
+    fn hull() {
+        let points = [0.0, 0.0, 2.0, 0.0, 1.0, 1.0, 2.0, 2.0, 0.0, 2.0, 1.0, 0.0];
+        let ring = convex_hull(&points);
+        assert_eq!(ring.len(), 8);
+        assert_eq!(polygon_area(&ring), 4.0);
+    }
+
+    #[test]
+    fn union_of_rings() {
+        let squares = [0.0, 0.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0, 1.0, 1.0, 3.0, 1.0, 3.0, 3.0, 1.0, 3.0, 5.0, 0.0, 6.0, 0.0, 6.0, 1.0, 5.0, 1.0];
+        let merged = union_all_rings(&squares, &[4, 4, 4]);
+        assert_eq!(merged.lengths().len(), 2);
+        assert!((area(&merged) - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn booleans() {
+        let a = [0.0, 0.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0];
+        let b = [1.0, 1.0, 3.0, 1.0, 3.0, 3.0, 1.0, 3.0];
+        let areas = [
+            area(&boolean_union(&a, &[4], &b, &[4], 1e-6)),
+            area(&boolean_intersection(&a, &[4], &b, &[4], 1e-6)),
+            area(&boolean_difference(&a, &[4], &b, &[4], 1e-6)),
+            area(&boolean_xor(&a, &[4], &b, &[4], 1e-6)),
+        ];
+        assert!(areas.iter().zip([7.0, 1.0, 3.0, 6.0]).all(|(a, b)| (a - b).abs() < 1e-9), "{areas:?}");
+        // a hole cut through the middle
+        let frame = boolean_difference(&[0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0], &[4], &[1.0, 1.0, 3.0, 1.0, 3.0, 3.0, 1.0, 3.0], &[4], 1e-6);
+        assert_eq!(frame.lengths(), vec![4, 4]);
+        assert!((area(&frame) - 12.0).abs() < 1e-9);
+    }
+}